@@ -24,6 +24,8 @@ pub mod common_cli_args;
 pub mod identity_management;
 #[cfg(feature = "miner_input")]
 pub mod parse_miner_input;
+#[cfg(feature = "stratum")]
+pub mod stratum;
 pub mod utilities;
 
 pub mod consts {
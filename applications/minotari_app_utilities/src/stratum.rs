@@ -0,0 +1,345 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The parts shared by the stratum servers of the applications: the newline delimited JSON-RPC connection of a worker,
+//! the common JSON-RPC messages and the set of jobs that shares are accepted for.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+        RwLock,
+    },
+};
+
+use log::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+    sync::{mpsc, watch},
+    task,
+};
+
+const LOG_TARGET: &str = "minotari_app_utilities::stratum";
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// The maximum length of a request line, a connection that sends a longer line is closed
+pub const MAX_REQUEST_LENGTH: usize = 16 * 1024;
+/// The maximum length of the login (worker name) of a worker
+pub const MAX_WORKER_NAME_LENGTH: usize = 128;
+/// The number of requests read ahead of the request being handled
+const REQUEST_BUFFER_SIZE: usize = 10;
+/// The maximum number of jobs on the current tip that shares are accepted for. Older jobs are dropped when a new job
+/// is published.
+pub const MAX_ACTIVE_JOBS: usize = 10;
+
+pub const PARSE_ERROR: i32 = -32700;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+
+#[derive(Debug, Deserialize)]
+pub struct RpcRequest {
+    /// Miners use both numeric and string ids, the id is returned as it was received
+    pub id: Option<Value>,
+    pub method: String,
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RpcResponse {
+    pub id: Value,
+    pub jsonrpc: &'static str,
+    pub result: Option<Value>,
+    pub error: Option<RpcError>,
+}
+
+/// A request sent by the server that does not expect a response, e.g. a new job
+#[derive(Debug, Serialize)]
+pub struct RpcNotification<T> {
+    pub jsonrpc: &'static str,
+    pub method: &'static str,
+    pub params: T,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginParams {
+    pub login: String,
+    #[serde(default)]
+    pub agent: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkerIdentifier {
+    pub id: String,
+}
+
+/// The newline delimited JSON-RPC connection of a worker. Requests are read ahead on a separate task, so that the
+/// connection can be polled for the next request alongside other events.
+pub struct JsonRpcConnection {
+    peer: SocketAddr,
+    writer: OwnedWriteHalf,
+    requests: mpsc::Receiver<String>,
+}
+
+impl JsonRpcConnection {
+    pub fn new(stream: TcpStream, peer: SocketAddr) -> Self {
+        let (reader, writer) = stream.into_split();
+        let (request_tx, requests) = mpsc::channel(REQUEST_BUFFER_SIZE);
+        task::spawn(read_requests(reader, peer, request_tx));
+        Self { peer, writer, requests }
+    }
+
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+
+    /// Returns the next request, or an error for a request that could not be parsed. Returns `None` once the
+    /// connection is closed. This is cancel safe.
+    pub async fn next_request(&mut self) -> Option<Result<RpcRequest, RpcError>> {
+        loop {
+            let line = self.requests.recv().await?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return Some(
+                serde_json::from_str::<RpcRequest>(line)
+                    .map_err(|err| rpc_error(PARSE_ERROR, format!("Parse error: {}", err))),
+            );
+        }
+    }
+
+    pub async fn send_response(&mut self, id: Value, result: Result<Value, RpcError>) -> io::Result<()> {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        let response = RpcResponse {
+            id,
+            jsonrpc: JSONRPC_VERSION,
+            result,
+            error,
+        };
+        self.write_message(&response).await
+    }
+
+    pub async fn send_notification<T: Serialize>(&mut self, method: &'static str, params: T) -> io::Result<()> {
+        let notification = RpcNotification {
+            jsonrpc: JSONRPC_VERSION,
+            method,
+            params,
+        };
+        self.write_message(&notification).await
+    }
+
+    async fn write_message<T: Serialize>(&mut self, message: &T) -> io::Result<()> {
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+        self.writer.write_all(&line).await
+    }
+}
+
+/// Reads newline delimited requests from the connection until it is closed
+async fn read_requests(reader: OwnedReadHalf, peer: SocketAddr, requests: mpsc::Sender<String>) {
+    let mut reader = BufReader::new(reader);
+    loop {
+        let mut line = String::new();
+        match (&mut reader).take(MAX_REQUEST_LENGTH as u64).read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(n) => {
+                let is_complete = line.ends_with('\n');
+                if !is_complete && n >= MAX_REQUEST_LENGTH {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Stratum request from {} exceeds {} bytes", peer, MAX_REQUEST_LENGTH
+                    );
+                    break;
+                }
+                if requests.send(line).await.is_err() || !is_complete {
+                    break;
+                }
+            },
+            Err(err) => {
+                debug!(target: LOG_TARGET, "Failed to read stratum request from {}: {}", peer, err);
+                break;
+            },
+        }
+    }
+}
+
+/// Returns the trimmed worker name of a login, if it is not empty and at most `MAX_WORKER_NAME_LENGTH` long
+pub fn worker_name(login: &str) -> Result<&str, RpcError> {
+    let name = login.trim();
+    if name.is_empty() || name.len() > MAX_WORKER_NAME_LENGTH {
+        return Err(rpc_error(
+            INVALID_PARAMS,
+            format!("Login must be between 1 and {} characters", MAX_WORKER_NAME_LENGTH),
+        ));
+    }
+    Ok(name)
+}
+
+pub fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, RpcError> {
+    let params = params.ok_or_else(|| rpc_error(INVALID_PARAMS, "Missing params".to_string()))?;
+    serde_json::from_value(params).map_err(|err| rpc_error(INVALID_PARAMS, format!("Invalid params: {}", err)))
+}
+
+pub fn to_result<T: Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|err| rpc_error(INTERNAL_ERROR, err.to_string()))
+}
+
+pub fn rpc_error(code: i32, message: String) -> RpcError {
+    RpcError { code, message }
+}
+
+/// The jobs that shares are accepted for, and the current job that is sent to the workers
+pub struct JobQueue<J> {
+    jobs: RwLock<VecDeque<Arc<J>>>,
+    next_job_id: AtomicU64,
+    job_sender: watch::Sender<Option<Arc<J>>>,
+}
+
+impl<J> JobQueue<J> {
+    pub fn new() -> Self {
+        let (job_sender, _) = watch::channel(None);
+        Self {
+            jobs: RwLock::new(VecDeque::new()),
+            next_job_id: AtomicU64::new(1),
+            job_sender,
+        }
+    }
+
+    pub fn next_job_id(&self) -> u64 {
+        self.next_job_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Subscribes to new jobs. The receiver holds `None` until the first job has been published.
+    pub fn subscribe(&self) -> watch::Receiver<Option<Arc<J>>> {
+        self.job_sender.subscribe()
+    }
+
+    pub fn current(&self) -> Option<Arc<J>> {
+        self.job_sender.borrow().clone()
+    }
+
+    /// Returns the first job that shares are still accepted for that matches the predicate
+    pub fn find<P: Fn(&J) -> bool>(&self, predicate: P) -> Option<Arc<J>> {
+        self.jobs
+            .read()
+            .expect("jobs lock poisoned")
+            .iter()
+            .find(|job| predicate(job))
+            .cloned()
+    }
+
+    /// Makes `job` the current job and sends it to all subscribers. Shares remain accepted for the last
+    /// `MAX_ACTIVE_JOBS` jobs that `is_active` holds for, which should be the jobs on the same tip as the new job.
+    pub fn publish<P: Fn(&J) -> bool>(&self, job: Arc<J>, is_active: P) {
+        {
+            let mut jobs = self.jobs.write().expect("jobs lock poisoned");
+            jobs.retain(|j| is_active(j));
+            jobs.push_back(job.clone());
+            while jobs.len() > MAX_ACTIVE_JOBS {
+                jobs.pop_front();
+            }
+        }
+        self.job_sender.send_replace(Some(job));
+    }
+}
+
+impl<J> Default for JobQueue<J> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The nonces of the valid shares submitted for a job, used to reject duplicate shares
+#[derive(Debug, Default)]
+pub struct SubmittedNonces<N>(Mutex<HashSet<N>>);
+
+impl<N: Eq + Hash> SubmittedNonces<N> {
+    /// Records the nonce of a share, returning false if it was submitted before
+    pub fn insert(&self, nonce: N) -> bool {
+        self.0.lock().expect("submitted nonces lock poisoned").insert(nonce)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_keeps_the_active_jobs() {
+        let queue = JobQueue::<(u64, u64)>::new();
+        assert!(queue.current().is_none());
+        let mut receiver = queue.subscribe();
+
+        // Jobs are (tip, id)
+        for _ in 0..MAX_ACTIVE_JOBS + 1 {
+            queue.publish(Arc::new((1, queue.next_job_id())), |job| job.0 == 1);
+        }
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(*queue.current().unwrap(), (1, MAX_ACTIVE_JOBS as u64 + 1));
+        assert!(queue.find(|job| job.1 == 1).is_none());
+        assert!(queue.find(|job| job.1 == 2).is_some());
+
+        // A job on a new tip drops the jobs on the old tip
+        let id = queue.next_job_id();
+        queue.publish(Arc::new((2, id)), |job| job.0 == 2);
+        assert!(queue.find(|job| job.1 == 2).is_none());
+        assert_eq!(*queue.find(|job| job.1 == id).unwrap(), (2, id));
+        assert_eq!(*receiver.borrow_and_update().as_deref().unwrap(), (2, id));
+    }
+
+    #[test]
+    fn it_rejects_duplicate_nonces() {
+        let nonces = SubmittedNonces::default();
+        assert!(nonces.insert(1u64));
+        assert!(nonces.insert(2u64));
+        assert!(!nonces.insert(1u64));
+    }
+
+    #[test]
+    fn it_validates_worker_names() {
+        assert_eq!(worker_name(" worker ").unwrap(), "worker");
+        assert!(worker_name("  ").is_err());
+        assert!(worker_name(&"w".repeat(MAX_WORKER_NAME_LENGTH + 1)).is_err());
+    }
+}
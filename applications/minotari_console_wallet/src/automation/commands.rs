@@ -46,7 +46,7 @@ use minotari_wallet::{
         UtxoSelectionCriteria,
     },
     transaction_service::{
-        handle::{TransactionEvent, TransactionServiceHandle},
+        handle::{BatchRecipient, TransactionEvent, TransactionServiceHandle},
         storage::models::WalletTransaction,
     },
     utxo_scanner_service::handle::UtxoScannerEvent,
@@ -93,6 +93,7 @@ use tari_core::{
             UnblindedOutput,
             WalletOutput,
         },
+        transaction_protocol::unsigned_transaction::{SignedTransactionBundle, UnsignedTransactionBundle},
         CryptoFactories,
     },
 };
@@ -104,6 +105,7 @@ use tari_crypto::{
 use tari_key_manager::{
     cipher_seed::CipherSeed,
     key_manager_service::{KeyId, KeyManagerInterface},
+    mnemonic::MnemonicLanguage,
     SeedWords,
 };
 use tari_p2p::{auto_update::AutoUpdateConfig, peer_seeds::SeedPeer, PeerSeedsConfig};
@@ -167,6 +169,7 @@ pub async fn send_tari(
     mut wallet_transaction_service: TransactionServiceHandle,
     fee_per_gram: u64,
     amount: MicroMinotari,
+    selection_criteria: UtxoSelectionCriteria,
     destination: TariAddress,
     payment_id: PaymentId,
 ) -> Result<TxId, CommandError> {
@@ -174,7 +177,7 @@ pub async fn send_tari(
         .send_transaction(
             destination,
             amount,
-            UtxoSelectionCriteria::default(),
+            selection_criteria,
             OutputFeatures::default(),
             fee_per_gram * uT,
             payment_id,
@@ -555,7 +558,15 @@ pub async fn make_it_rain(
                     // Send transaction
                     let tx_id = match transaction_type {
                         MakeItRainTransactionType::Interactive => {
-                            send_tari(tx_service, fee, amount, address.clone(), payment_id_clone).await
+                            send_tari(
+                                tx_service,
+                                fee,
+                                amount,
+                                UtxoSelectionCriteria::default(),
+                                address.clone(),
+                                payment_id_clone,
+                            )
+                            .await
                         },
                         MakeItRainTransactionType::StealthOneSided => {
                             send_one_sided_to_stealth_address(
@@ -1987,6 +1998,10 @@ pub async fn command_runner(
                     transaction_service.clone(),
                     config.fee_per_gram,
                     args.amount,
+                    UtxoSelectionCriteria {
+                        ordering: args.selection,
+                        ..Default::default()
+                    },
                     args.destination,
                     PaymentId::open_from_str(&args.payment_id),
                 )
@@ -2004,7 +2019,10 @@ pub async fn command_runner(
                     transaction_service.clone(),
                     config.fee_per_gram,
                     args.amount,
-                    UtxoSelectionCriteria::default(),
+                    UtxoSelectionCriteria {
+                        ordering: args.selection,
+                        ..Default::default()
+                    },
                     args.destination,
                     PaymentId::open_from_str(&args.payment_id),
                 )
@@ -2020,6 +2038,104 @@ pub async fn command_runner(
                     Err(e) => eprintln!("SendOneSidedToStealthAddress error! {}", e),
                 }
             },
+            SendBatch(args) => match load_batch_recipients_from_csv_file(args.input_file) {
+                Ok(recipients) => {
+                    let count = recipients.len();
+                    let total = recipients.iter().map(|r| r.amount).sum::<MicroMinotari>();
+                    match transaction_service
+                        .send_batch_one_sided_transaction(
+                            recipients,
+                            UtxoSelectionCriteria {
+                                ordering: args.selection,
+                                ..Default::default()
+                            },
+                            config.fee_per_gram * uT,
+                        )
+                        .await
+                    {
+                        Ok(tx_id) => {
+                            println!("Sent {} to {} recipients in transaction {}", total, count, tx_id);
+                            tx_ids.push(tx_id);
+                        },
+                        Err(e) => eprintln!("SendBatch error! {}", e),
+                    }
+                },
+                Err(e) => eprintln!("SendBatch error! {}", e),
+            },
+            PrepareOfflineTransaction(args) => match load_batch_recipients_from_csv_file(args.input_file) {
+                Ok(recipients) => {
+                    match transaction_service
+                        .prepare_offline_transaction(
+                            recipients,
+                            UtxoSelectionCriteria {
+                                ordering: args.selection,
+                                ..Default::default()
+                            },
+                            config.fee_per_gram * uT,
+                            PaymentId::Empty,
+                        )
+                        .await
+                    {
+                        Ok(bundle) => match write_json_file(&args.output_file, &bundle) {
+                            Ok(()) => println!(
+                                "Prepared transaction {} spending {} input(s) with fee {}, written to '{}'",
+                                bundle.tx_id,
+                                bundle.inputs.len(),
+                                bundle.fee,
+                                args.output_file.display()
+                            ),
+                            Err(e) => eprintln!("PrepareOfflineTransaction error! {}", e),
+                        },
+                        Err(e) => eprintln!("PrepareOfflineTransaction error! {}", e),
+                    }
+                },
+                Err(e) => eprintln!("PrepareOfflineTransaction error! {}", e),
+            },
+            SignOfflineTransaction(args) => match read_json_file::<_, UnsignedTransactionBundle>(&args.input_file) {
+                Ok(bundle) => {
+                    let tx_id = bundle.tx_id;
+                    match confirm_offline_signing(&bundle, args.yes) {
+                        Ok(true) => {},
+                        Ok(false) => {
+                            println!("Transaction {} was not signed", tx_id);
+                            continue;
+                        },
+                        Err(e) => {
+                            eprintln!("SignOfflineTransaction error! {}", e);
+                            continue;
+                        },
+                    }
+                    match transaction_service.sign_offline_transaction(bundle).await {
+                        Ok(signed) => match write_json_file(&args.output_file, &signed) {
+                            Ok(()) => println!(
+                                "Signed transaction {}, written to '{}'",
+                                tx_id,
+                                args.output_file.display()
+                            ),
+                            Err(e) => eprintln!("SignOfflineTransaction error! {}", e),
+                        },
+                        Err(e) => eprintln!("SignOfflineTransaction error! {}", e),
+                    }
+                },
+                Err(e) => eprintln!("SignOfflineTransaction error! {}", e),
+            },
+            BroadcastSignedTransaction(args) => match read_json_file::<_, SignedTransactionBundle>(&args.input_file) {
+                Ok(bundle) => match transaction_service.broadcast_signed_transaction(bundle).await {
+                    Ok(tx_id) => {
+                        println!("Broadcast signed transaction {}", tx_id);
+                        tx_ids.push(tx_id);
+                    },
+                    Err(e) => eprintln!("BroadcastSignedTransaction error! {}", e),
+                },
+                Err(e) => eprintln!("BroadcastSignedTransaction error! {}", e),
+            },
+            BumpFee(args) => match transaction_service.bump_fee(args.tx_id.into(), args.fee_per_gram).await {
+                Ok(tx_id) => {
+                    println!("Transaction {} replaced by {}", args.tx_id, tx_id);
+                    tx_ids.push(tx_id);
+                },
+                Err(e) => eprintln!("BumpFee error! {}", e),
+            },
             MakeItRain(args) => {
                 let transaction_type = args.transaction_type();
                 if let Err(e) = make_it_rain(
@@ -2242,7 +2358,10 @@ pub async fn command_runner(
                     transaction_service.clone(),
                     config.fee_per_gram,
                     args.amount,
-                    UtxoSelectionCriteria::default(),
+                    UtxoSelectionCriteria {
+                        ordering: args.selection,
+                        ..Default::default()
+                    },
                     args.destination,
                     PaymentId::open_from_str(&args.payment_id),
                 )
@@ -2489,6 +2608,37 @@ pub async fn command_runner(
                     println!("Spend key: {}", spend_key_hex);
                 }
             },
+            ExportSeedShares(args) => {
+                match wallet.get_seed_shares(args.threshold, args.shares, &MnemonicLanguage::English) {
+                    Ok(shares) => {
+                        for (i, share) in shares.iter().enumerate() {
+                            let words = share.join(" ");
+                            if let Some(ref dir) = args.output_dir {
+                                let file = dir.join(format!("seed_share_{}.txt", i + 1));
+                                fs::create_dir_all(dir)
+                                    .and_then(|_| fs::write(&file, words.reveal()))
+                                    .map_err(|e| CommandError::General(e.to_string()))?;
+                                println!(
+                                    "Seed share {} of {} written to '{}'",
+                                    i + 1,
+                                    shares.len(),
+                                    file.display()
+                                );
+                            } else {
+                                println!("Seed share {} of {}:", i + 1, shares.len());
+                                println!("{}", words.reveal());
+                                println!();
+                            }
+                        }
+                        println!(
+                            "Any {} of these {} shares recover the wallet. Store them in separate places.",
+                            args.threshold,
+                            shares.len()
+                        );
+                    },
+                    Err(e) => eprintln!("ExportSeedShares error! {}", e),
+                }
+            },
             ImportPaperWallet(args) => {
                 let temp_path = config
                     .db_file
@@ -2507,7 +2657,7 @@ pub async fn command_runner(
                             let seed_words = SeedWords::from_str(args.seed_words.as_str())
                                 .map_err(|e| CommandError::General(e.to_string()))?;
 
-                            get_seed_from_seed_words(&seed_words, passphrase)
+                            get_seed_from_seed_words(&seed_words, passphrase, args.bip39)
                                 .map_err(|e| CommandError::General(e.to_string()))?
                         },
                         (false, true) => {
@@ -2967,7 +3117,45 @@ fn load_tx_from_csv_file(file_path: PathBuf) -> Result<Vec<WalletTransaction>, C
     Ok(results)
 }
 
-#[allow(dead_code)]
+/// Reads batch payment recipients from a CSV file with one `address,amount,memo` payment per line. Empty lines, lines
+/// starting with `#` and an optional `address,amount,memo` header are skipped.
+fn load_batch_recipients_from_csv_file(file_path: PathBuf) -> Result<Vec<BatchRecipient>, CommandError> {
+    let file_contents = fs::read_to_string(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    let mut recipients = Vec::new();
+    for (idx, line) in file_contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || (idx == 0 && line.to_lowercase().starts_with("address,")) {
+            continue;
+        }
+        let mut fields = line.splitn(3, ',');
+        let destination = fields
+            .next()
+            .map(str::trim)
+            .unwrap_or_default()
+            .parse::<TariAddress>()
+            .map_err(|e| CommandError::CSVFile(format!("Invalid address on line {}: {}", idx + 1, e)))?;
+        let amount = fields
+            .next()
+            .ok_or_else(|| CommandError::CSVFile(format!("Missing amount on line {}", idx + 1)))?
+            .trim()
+            .parse::<MicroMinotari>()
+            .map_err(|e| CommandError::CSVFile(format!("Invalid amount on line {}: {}", idx + 1, e)))?;
+        let payment_id = match fields.next().map(str::trim) {
+            Some(memo) if !memo.is_empty() => PaymentId::open_from_str(memo),
+            _ => PaymentId::Empty,
+        };
+        recipients.push(BatchRecipient {
+            destination,
+            amount,
+            payment_id,
+        });
+    }
+    if recipients.is_empty() {
+        return Err(CommandError::CSVFile("No payments found".to_string()));
+    }
+    Ok(recipients)
+}
+
 fn write_json_file<P: AsRef<Path>, T: Serialize>(path: P, data: &T) -> Result<(), CommandError> {
     fs::create_dir_all(path.as_ref().parent().unwrap()).map_err(|e| CommandError::JsonFile(e.to_string()))?;
     let file = File::create(path).map_err(|e| CommandError::JsonFile(e.to_string()))?;
@@ -2975,12 +3163,41 @@ fn write_json_file<P: AsRef<Path>, T: Serialize>(path: P, data: &T) -> Result<()
     Ok(())
 }
 
-#[allow(dead_code)]
 fn read_json_file<P: AsRef<Path>, T: DeserializeOwned>(path: P) -> Result<T, CommandError> {
     let file = File::open(path).map_err(|e| CommandError::JsonFile(e.to_string()))?;
     serde_json::from_reader(file).map_err(|e| CommandError::JsonFile(e.to_string()))
 }
 
+/// Prints what an offline transaction pays and asks the user to confirm it before it is signed, unless `yes` is set
+fn confirm_offline_signing(bundle: &UnsignedTransactionBundle, yes: bool) -> Result<bool, CommandError> {
+    let total_amount = bundle
+        .total_amount()
+        .map_err(|e| CommandError::General(e.to_string()))?;
+    let total_input_value = bundle
+        .total_input_value()
+        .map_err(|e| CommandError::General(e.to_string()))?;
+    println!("Transaction {}", bundle.tx_id);
+    for recipient in &bundle.recipients {
+        println!(
+            "  pays {} to {} ({})",
+            recipient.amount,
+            recipient.destination.to_base58(),
+            recipient.payment_id.user_data_as_string()
+        );
+    }
+    println!("  total {}, fee {}, change {}", total_amount, bundle.fee, bundle.change);
+    println!("  spends {} input(s) worth {}", bundle.inputs.len(), total_input_value);
+    if yes {
+        return Ok(true);
+    }
+
+    print!("Sign this transaction? (y/N) ");
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(matches!(line.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
 #[allow(dead_code)]
 async fn get_tip_height(wallet: &WalletSqlite) -> Option<u64> {
     let client = wallet
@@ -30,6 +30,7 @@ use std::{
 use chrono::{DateTime, Utc};
 use clap::{Args, Parser, Subcommand};
 use minotari_app_utilities::{common_cli_args::CommonCliArgs, utilities::UniPublicKey};
+use minotari_wallet::output_manager_service::UtxoSelectionOrdering;
 use tari_common::configuration::{ConfigOverrideProvider, Network};
 use tari_common_types::tari_address::TariAddress;
 use tari_comms::multiaddr::Multiaddr;
@@ -63,6 +64,9 @@ pub struct Cli {
     /// separated. e.g. --seed-words "seed1 seed2 ..."
     #[clap(long, alias = "seed-words")]
     pub seed_words: Option<SeedWords>,
+    /// Recover from a standard 12 or 24 word BIP-39 phrase instead of wallet seed words
+    #[clap(long)]
+    pub bip39: bool,
     /// Supply the optional file name to save the wallet seed words into
     #[clap(long, aliases = &["seed_words_file_name", "seed-words-file"], parse(from_os_str))]
     pub seed_words_file_name: Option<PathBuf>,
@@ -94,6 +98,9 @@ pub struct Cli {
     pub view_private_key: Option<String>,
     #[clap(long)]
     pub spend_key: Option<String>,
+    /// Use the connected Ledger device with this account number for a new or recovered wallet, without prompting
+    #[clap(long)]
+    pub ledger_account: Option<u64>,
 }
 
 impl ConfigOverrideProvider for Cli {
@@ -143,6 +150,13 @@ pub enum CliCommands {
     PreMineSpendAggregateTransaction(PreMineSpendAggregateTransactionArgs),
     PreMineSpendBackupUtxo(PreMineSpendBackupUtxoArgs),
     SendOneSidedToStealthAddress(SendMinotariArgs),
+    SendBatch(SendBatchArgs),
+    PrepareOfflineTransaction(PrepareOfflineTransactionArgs),
+    SignOfflineTransaction(SignOfflineTransactionArgs),
+    BroadcastSignedTransaction(BroadcastSignedTransactionArgs),
+    /// Replace an unconfirmed one-sided transaction with one paying a higher fee per gram. Base nodes only evict the
+    /// original when they run with `unconfirmed_pool.replace_by_fee` enabled.
+    BumpFee(BumpFeeArgs),
     MakeItRain(MakeItRainArgs),
     CoinSplit(CoinSplitArgs),
     DiscoverPeer(DiscoverPeerArgs),
@@ -163,6 +177,7 @@ pub enum CliCommands {
     CreateTlsCerts,
     Sync(SyncArgs),
     ExportViewKeyAndSpendKey(ExportViewKeyAndSpendKeyArgs),
+    ExportSeedShares(ExportSeedSharesArgs),
     ImportPaperWallet(ImportPaperWalletArgs),
 }
 
@@ -177,6 +192,59 @@ pub struct SendMinotariArgs {
     pub destination: TariAddress,
     #[clap(short, long, default_value = "<No message>")]
     pub payment_id: String,
+    /// How to select the UTXOs to spend: default, smallest, largest, branch-and-bound or random
+    #[clap(long, default_value = "default")]
+    pub selection: UtxoSelectionOrdering,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SendBatchArgs {
+    /// A CSV file with one `address,amount,memo` payment per line. All payments are made one-sided, to the
+    /// recipients' stealth addresses, in a single transaction. The memo is optional and may contain commas.
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+    /// How to select the UTXOs to spend: default, smallest, largest, branch-and-bound or random
+    #[clap(long, default_value = "default")]
+    pub selection: UtxoSelectionOrdering,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct PrepareOfflineTransactionArgs {
+    /// A CSV file with one `address,amount,memo` payment per line, as for `send-batch`
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+    /// Where to write the unsigned transaction, to be carried to the wallet holding the spend key
+    #[clap(short, long)]
+    pub output_file: PathBuf,
+    /// How to select the UTXOs to spend: default, smallest, largest, branch-and-bound or random
+    #[clap(long, default_value = "default")]
+    pub selection: UtxoSelectionOrdering,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SignOfflineTransactionArgs {
+    /// The unsigned transaction written by `prepare-offline-transaction`
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+    /// Where to write the signed transaction, to be carried back to the preparing wallet
+    #[clap(short, long)]
+    pub output_file: PathBuf,
+    /// Sign without asking for confirmation of the recipients, amounts and fee
+    #[clap(long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct BroadcastSignedTransactionArgs {
+    /// The signed transaction written by `sign-offline-transaction`
+    #[clap(short, long)]
+    pub input_file: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct BumpFeeArgs {
+    pub tx_id: u64,
+    pub fee_per_gram: MicroMinotari,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -393,6 +461,19 @@ pub struct ExportViewKeyAndSpendKeyArgs {
     pub output_file: Option<PathBuf>,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct ExportSeedSharesArgs {
+    /// The number of shares needed to recover the wallet
+    #[clap(short, long)]
+    pub threshold: u8,
+    /// The number of shares to produce
+    #[clap(short, long)]
+    pub shares: u8,
+    /// Write each share to its own file in this directory instead of printing them
+    #[clap(short, long)]
+    pub output_dir: Option<PathBuf>,
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct ImportPaperWalletArgs {
     #[clap(short, long, default_value = "")]
@@ -401,6 +482,9 @@ pub struct ImportPaperWalletArgs {
     pub cipher_seed: String,
     #[clap(short, long, default_value = "")]
     pub passphrase: String,
+    /// The seed words are a standard 12 or 24 word BIP-39 phrase
+    #[clap(long)]
+    pub bip39: bool,
 }
 
 #[derive(Debug, Args, Clone)]
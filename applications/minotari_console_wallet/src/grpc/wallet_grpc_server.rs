@@ -35,6 +35,8 @@ use minotari_app_grpc::tari_rpc::{
     self,
     payment_recipient::PaymentType,
     wallet_server,
+    BroadcastSignedTransactionRequest,
+    BroadcastSignedTransactionResponse,
     CheckConnectivityResponse,
     ClaimHtlcRefundRequest,
     ClaimHtlcRefundResponse,
@@ -62,6 +64,8 @@ use minotari_app_grpc::tari_rpc::{
     GetVersionResponse,
     ImportUtxosRequest,
     ImportUtxosResponse,
+    PrepareOfflineTransactionRequest,
+    PrepareOfflineTransactionResponse,
     RegisterValidatorNodeRequest,
     RegisterValidatorNodeResponse,
     RevalidateRequest,
@@ -70,12 +74,16 @@ use minotari_app_grpc::tari_rpc::{
     SendShaAtomicSwapResponse,
     SetBaseNodeRequest,
     SetBaseNodeResponse,
+    SignOfflineTransactionRequest,
+    SignOfflineTransactionResponse,
     TransactionDirection,
     TransactionEvent,
     TransactionEventRequest,
     TransactionEventResponse,
     TransactionInfo,
     TransactionStatus,
+    TransferBatchRequest,
+    TransferBatchResponse,
     TransferRequest,
     TransferResponse,
     TransferResult,
@@ -87,7 +95,7 @@ use minotari_wallet::{
     error::WalletStorageError,
     output_manager_service::{handle::OutputManagerHandle, UtxoSelectionCriteria},
     transaction_service::{
-        handle::TransactionServiceHandle,
+        handle::{BatchRecipient, TransactionServiceHandle},
         storage::models::{self, WalletTransaction},
     },
     WalletSqlite,
@@ -110,6 +118,7 @@ use tari_core::{
             SideChainFeature,
             UnblindedOutput,
         },
+        transaction_protocol::unsigned_transaction::{SignedTransactionBundle, UnsignedTransactionBundle},
     },
 };
 use tari_script::script;
@@ -598,6 +607,108 @@ impl wallet_server::Wallet for WalletGrpcServer {
         Ok(Response::new(TransferResponse { results }))
     }
 
+    async fn transfer_batch(
+        &self,
+        request: Request<TransferBatchRequest>,
+    ) -> Result<Response<TransferBatchResponse>, Status> {
+        let message = request.into_inner();
+        if message.recipients.is_empty() {
+            return Err(Status::invalid_argument("No recipients provided"));
+        }
+        let recipients = convert_batch_recipients(message.recipients)?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let response = match transaction_service
+            .send_batch_one_sided_transaction(
+                recipients,
+                UtxoSelectionCriteria::default(),
+                message.fee_per_gram.into(),
+            )
+            .await
+        {
+            Ok(tx_id) => TransferBatchResponse {
+                transaction_id: tx_id.as_u64(),
+                is_success: true,
+                failure_message: Default::default(),
+            },
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to send batch transaction: {}", e);
+                TransferBatchResponse {
+                    transaction_id: Default::default(),
+                    is_success: false,
+                    failure_message: e.to_string(),
+                }
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn prepare_offline_transaction(
+        &self,
+        request: Request<PrepareOfflineTransactionRequest>,
+    ) -> Result<Response<PrepareOfflineTransactionResponse>, Status> {
+        let message = request.into_inner();
+        if message.recipients.is_empty() {
+            return Err(Status::invalid_argument("No recipients provided"));
+        }
+        let recipients = convert_batch_recipients(message.recipients)?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let bundle = transaction_service
+            .prepare_offline_transaction(
+                recipients,
+                UtxoSelectionCriteria::default(),
+                message.fee_per_gram.into(),
+                PaymentId::Empty,
+            )
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let unsigned_transaction = serde_json::to_vec(&bundle).map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(PrepareOfflineTransactionResponse {
+            transaction_id: bundle.tx_id.as_u64(),
+            unsigned_transaction,
+        }))
+    }
+
+    async fn sign_offline_transaction(
+        &self,
+        request: Request<SignOfflineTransactionRequest>,
+    ) -> Result<Response<SignOfflineTransactionResponse>, Status> {
+        let message = request.into_inner();
+        let bundle = serde_json::from_slice::<UnsignedTransactionBundle>(&message.unsigned_transaction)
+            .map_err(|e| Status::invalid_argument(format!("Unsigned transaction is malformed: {}", e)))?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let signed = transaction_service
+            .sign_offline_transaction(bundle)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let signed_transaction = serde_json::to_vec(&signed).map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(SignOfflineTransactionResponse { signed_transaction }))
+    }
+
+    async fn broadcast_signed_transaction(
+        &self,
+        request: Request<BroadcastSignedTransactionRequest>,
+    ) -> Result<Response<BroadcastSignedTransactionResponse>, Status> {
+        let message = request.into_inner();
+        let bundle = serde_json::from_slice::<SignedTransactionBundle>(&message.signed_transaction)
+            .map_err(|e| Status::invalid_argument(format!("Signed transaction is malformed: {}", e)))?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let tx_id = transaction_service
+            .broadcast_signed_transaction(bundle)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(BroadcastSignedTransactionResponse {
+            transaction_id: tx_id.as_u64(),
+        }))
+    }
+
     async fn create_burn_transaction(
         &self,
         request: Request<CreateBurnTransactionRequest>,
@@ -956,6 +1067,34 @@ impl wallet_server::Wallet for WalletGrpcServer {
         }
     }
 
+    async fn bump_fee(
+        &self,
+        request: Request<tari_rpc::BumpFeeRequest>,
+    ) -> Result<Response<tari_rpc::BumpFeeResponse>, Status> {
+        let message = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming gRPC request to Bump Fee (TxId: {}, fee per gram: {})", message.tx_id, message.new_fee_per_gram,
+        );
+        let mut transaction_service = self.get_transaction_service();
+
+        match transaction_service
+            .bump_fee(message.tx_id.into(), message.new_fee_per_gram.into())
+            .await
+        {
+            Ok(tx_id) => Ok(Response::new(tari_rpc::BumpFeeResponse {
+                is_success: true,
+                new_tx_id: tx_id.into(),
+                failure_message: "".to_string(),
+            })),
+            Err(e) => Ok(Response::new(tari_rpc::BumpFeeResponse {
+                is_success: false,
+                new_tx_id: 0,
+                failure_message: e.to_string(),
+            })),
+        }
+    }
+
     async fn create_template_registration(
         &self,
         request: Request<CreateTemplateRegistrationRequest>,
@@ -1066,6 +1205,23 @@ impl wallet_server::Wallet for WalletGrpcServer {
     }
 }
 
+fn convert_batch_recipients(recipients: Vec<tari_rpc::BatchPaymentRecipient>) -> Result<Vec<BatchRecipient>, Status> {
+    recipients
+        .into_iter()
+        .enumerate()
+        .map(|(idx, dest)| -> Result<_, String> {
+            let destination = TariAddress::from_str(&dest.address)
+                .map_err(|_| format!("Destination address at index {} is malformed", idx))?;
+            Ok(BatchRecipient {
+                destination,
+                amount: dest.amount.into(),
+                payment_id: PaymentId::from_bytes(&dest.payment_id),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Status::invalid_argument)
+}
+
 async fn handle_completed_tx(
     tx_id: TxId,
     event: &str,
@@ -845,8 +845,17 @@ pub fn prompt_wallet_type(
     non_interactive: bool,
     view_private_key: Option<String>,
     spend_key: Option<String>,
+    ledger_account: Option<u64>,
 ) -> Option<WalletType> {
     if non_interactive && !matches!(boot_mode, WalletBoot::ViewAndSpendKey) {
+        #[cfg(feature = "ledger")]
+        if let (Some(account), WalletBoot::New | WalletBoot::Recovery) = (ledger_account, boot_mode) {
+            return Some(ledger_wallet_type(account, wallet_config));
+        }
+        #[cfg(not(feature = "ledger"))]
+        if ledger_account.is_some() {
+            panic!("{}", LEDGER_NOT_SUPPORTED);
+        }
         return Some(WalletType::default());
     }
 
@@ -894,24 +903,12 @@ pub fn prompt_wallet_type(
                     },
                     _ => "\r\nWould you like to use a connected hardware wallet? (Supported types: Ledger) (Y/n)",
                 };
-                if prompt(connected_hardware_msg) {
+                if let Some(account) = ledger_account {
+                    Some(ledger_wallet_type(account, wallet_config))
+                } else if prompt(connected_hardware_msg) {
                     print!("Scanning for connected Ledger hardware device... ");
                     let account = prompt_ledger_account(boot_mode).expect("An account value");
-                    match ledger_get_public_spend_key(account) {
-                        Ok(public_alpha) => match ledger_get_view_key(account) {
-                            Ok(view_key) => {
-                                let ledger = LedgerWallet::new(
-                                    account,
-                                    wallet_config.network,
-                                    Some(public_alpha),
-                                    Some(view_key),
-                                );
-                                Some(WalletType::Ledger(ledger))
-                            },
-                            Err(e) => panic!("{}", e),
-                        },
-                        Err(e) => panic!("{}", e),
-                    }
+                    Some(ledger_wallet_type(account, wallet_config))
                 } else {
                     Some(WalletType::default())
                 }
@@ -921,6 +918,20 @@ pub fn prompt_wallet_type(
     }
 }
 
+#[cfg(feature = "ledger")]
+fn ledger_wallet_type(account: u64, wallet_config: &WalletConfig) -> WalletType {
+    match ledger_get_public_spend_key(account) {
+        Ok(public_alpha) => match ledger_get_view_key(account) {
+            Ok(view_key) => {
+                let ledger = LedgerWallet::new(account, wallet_config.network, Some(public_alpha), Some(view_key));
+                WalletType::Ledger(ledger)
+            },
+            Err(e) => panic!("{}", e),
+        },
+        Err(e) => panic!("{}", e),
+    }
+}
+
 pub fn prompt_ledger_account(boot_mode: WalletBoot) -> Option<u64> {
     let question = match boot_mode {
         WalletBoot::Recovery => "\r\nPlease enter the account number you previously used for your device.",
@@ -69,7 +69,7 @@ use tari_libtor::tor::Tor;
 use tari_shutdown::Shutdown;
 use tari_utilities::SafePassword;
 use tokio::runtime::Runtime;
-use wallet_modes::{command_mode, grpc_mode, recovery_mode, script_mode, tui_mode, WalletMode};
+use wallet_modes::{command_mode, grpc_mode, recovery_mode, script_mode, start_webhooks, tui_mode, WalletMode};
 
 pub use crate::config::ApplicationConfig;
 use crate::init::{boot_with_password, confirm_direct_only_send, confirm_seed_words, prompt_wallet_type, wallet_mode};
@@ -96,6 +96,7 @@ pub fn run_wallet(shutdown: &mut Shutdown, runtime: Runtime, config: &mut Applic
         change_password: false,
         recovery: false,
         seed_words: None,
+        bip39: false,
         seed_words_file_name: None,
         non_interactive_mode: true,
         input_file: None,
@@ -108,6 +109,7 @@ pub fn run_wallet(shutdown: &mut Shutdown, runtime: Runtime, config: &mut Applic
         profile_with_tokio_console: false,
         view_private_key: None,
         spend_key: None,
+        ledger_account: None,
     };
 
     run_wallet_with_cli(shutdown, runtime, config, cli)
@@ -142,6 +144,7 @@ pub fn run_wallet_with_cli(
         cli.non_interactive_mode,
         cli.view_private_key.clone(),
         cli.spend_key.clone(),
+        cli.ledger_account,
     );
 
     let recovery_seed = get_recovery_seed(boot_mode, &cli, &wallet_type)?;
@@ -239,6 +242,7 @@ pub fn run_wallet_with_cli(
     debug!(target: LOG_TARGET, "Starting app");
 
     let handle = runtime.handle().clone();
+    start_webhooks(&handle, &config.wallet, &wallet)?;
 
     let result = match wallet_mode {
         WalletMode::Tui => tui_mode(handle, &config.wallet, &base_node_config, wallet.clone()),
@@ -284,9 +288,9 @@ fn get_recovery_seed(
 ) -> Result<Option<CipherSeed>, ExitError> {
     if matches!(boot_mode, WalletBoot::Recovery) && !matches!(wallet_type, Some(WalletType::Ledger(_))) {
         let seed = if let Some(ref seed_words) = cli.seed_words {
-            get_seed_from_seed_words(seed_words, None)?
+            get_seed_from_seed_words(seed_words, None, cli.bip39)?
         } else {
-            prompt_private_key_from_seed_words()?
+            prompt_private_key_from_seed_words(cli.bip39)?
         };
         Ok(Some(seed))
     } else {
@@ -28,17 +28,23 @@ use std::{
 
 use log::*;
 use minotari_wallet::{
-    transaction_service::storage::models::{
-        CompletedTransaction,
-        InboundTransaction,
-        OutboundTransaction,
-        WalletTransaction,
+    transaction_service::{
+        handle::{TransactionEvent, TransactionEventReceiver},
+        storage::models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
     },
     WalletSqlite,
 };
 use tari_common_types::transaction::TxId;
+use tari_shutdown::ShutdownSignal;
 use tari_utilities::hex::Hex;
-use tokio::{runtime::Handle, sync::broadcast::Sender};
+use tokio::{
+    runtime::Handle,
+    sync::broadcast::{error::RecvError, Sender},
+};
+use webhook::TransactionDetails;
+pub use webhook::WebhookNotifier;
+
+mod webhook;
 
 pub const LOG_TARGET: &str = "wallet::notifier";
 pub const RECEIVED: &str = "received";
@@ -69,6 +75,7 @@ pub enum WalletEventMessage {
 #[derive(Clone)]
 pub struct Notifier {
     path: Option<PathBuf>,
+    webhooks: Option<WebhookNotifier>,
     handle: Handle,
     wallet: WalletSqlite,
     event_broadcaster: Sender<WalletEventMessage>,
@@ -77,41 +84,85 @@ pub struct Notifier {
 impl Notifier {
     pub fn new(
         path: Option<PathBuf>,
+        webhooks: Option<WebhookNotifier>,
         handle: Handle,
         wallet: WalletSqlite,
         event_broadcaster: Sender<WalletEventMessage>,
     ) -> Self {
         Self {
             path,
+            webhooks,
             handle,
             wallet,
             event_broadcaster,
         }
     }
 
+    fn is_enabled(&self) -> bool {
+        self.path.is_some() || self.webhooks.is_some()
+    }
+
+    /// Triggers notifications for the wallet's transaction events until shutdown. In the TUI the wallet event monitor
+    /// triggers them instead.
+    pub async fn run(self, mut events: TransactionEventReceiver, mut shutdown_signal: ShutdownSignal) {
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => match *event {
+                        TransactionEvent::ReceivedFinalizedTransaction(tx_id) => self.transaction_received(tx_id),
+                        TransactionEvent::TransactionMinedUnconfirmed { tx_id, num_confirmations, .. } |
+                        TransactionEvent::DetectedTransactionUnconfirmed { tx_id, num_confirmations, .. } => {
+                            self.transaction_mined_unconfirmed(tx_id, num_confirmations);
+                        },
+                        TransactionEvent::TransactionMined { tx_id, .. } |
+                        TransactionEvent::DetectedTransactionConfirmed { tx_id, .. } => self.transaction_mined(tx_id),
+                        TransactionEvent::TransactionCancelled(tx_id, _) => self.transaction_cancelled(tx_id),
+                        TransactionEvent::TransactionCompletedImmediately(tx_id) => {
+                            self.transaction_sent_or_queued(tx_id, true);
+                        },
+                        TransactionEvent::TransactionSendResult(tx_id, ref status) => self.transaction_sent_or_queued(
+                            tx_id,
+                            status.direct_send_result || status.store_and_forward_send_result,
+                        ),
+                        _ => {},
+                    },
+                    Err(RecvError::Lagged(n)) => warn!(target: LOG_TARGET, "Missed {} transaction event(s)", n),
+                    Err(RecvError::Closed) => break,
+                },
+                _ = shutdown_signal.wait() => break,
+            }
+        }
+    }
+
     /// Trigger a notification that a negotiated transaction was received.
     pub fn transaction_received(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_received tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if self.is_enabled() {
+            let program = self.path.clone();
+            let webhooks = self.webhooks.clone();
             let mut transaction_service = self.wallet.transaction_service.clone();
             let sender = self.event_broadcaster.clone();
             self.handle.spawn(async move {
                 match transaction_service.get_completed_transaction(tx_id).await {
                     Ok(tx) => {
-                        let args = args_from_complete(&tx, RECEIVED, None);
-                        let result = Command::new(program).args(&args).output();
+                        if let Some(webhooks) = webhooks {
+                            enqueue(&webhooks, RECEIVED, TransactionDetails::from_completed(&tx, None));
+                        }
+                        if let Some(program) = program {
+                            let args = args_from_complete(&tx, RECEIVED, None);
+                            log(Command::new(program).args(&args).output());
+                        }
                         let _ignored = sender.send(WalletEventMessage::Completed {
                             event: RECEIVED.to_string(),
                             transaction: tx,
                         });
-                        log(result);
                     },
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
         } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
+            trace!(target: LOG_TARGET, "No script or webhooks defined, not running.");
         }
     }
 
@@ -119,26 +170,33 @@ impl Notifier {
     pub fn transaction_mined_unconfirmed(&self, tx_id: TxId, confirmations: u64) {
         debug!(target: LOG_TARGET, "transaction_mined_unconfirmed tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if self.is_enabled() {
+            let program = self.path.clone();
+            let webhooks = self.webhooks.clone();
             let mut transaction_service = self.wallet.transaction_service.clone();
             let sender = self.event_broadcaster.clone();
             self.handle.spawn(async move {
                 match transaction_service.get_completed_transaction(tx_id).await {
                     Ok(tx) => {
-                        let args = args_from_complete(&tx, CONFIRMATION, Some(confirmations));
-                        let result = Command::new(program).args(&args).output();
+                        if let Some(webhooks) = webhooks {
+                            let details = TransactionDetails::from_completed(&tx, Some(confirmations));
+                            enqueue(&webhooks, CONFIRMATION, details);
+                        }
+                        if let Some(program) = program {
+                            let args = args_from_complete(&tx, CONFIRMATION, Some(confirmations));
+                            log(Command::new(program).args(&args).output());
+                        }
                         let message = WalletEventMessage::Completed {
                             event: CONFIRMATION.to_string(),
                             transaction: tx,
                         };
                         let _ignored = sender.send(message);
-                        log(result);
                     },
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
         } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
+            trace!(target: LOG_TARGET, "No script or webhooks defined, not running.");
         }
     }
 
@@ -146,7 +204,9 @@ impl Notifier {
     pub fn transaction_mined(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_mined tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if self.is_enabled() {
+            let program = self.path.clone();
+            let webhooks = self.webhooks.clone();
             let mut transaction_service = self.wallet.transaction_service.clone();
             let sender = self.event_broadcaster.clone();
             self.handle.spawn(async move {
@@ -160,20 +220,24 @@ impl Notifier {
                             },
                         };
 
-                        let args = args_from_complete(&tx, MINED, confirmations);
-                        let result = Command::new(program).args(&args).output();
+                        if let Some(webhooks) = webhooks {
+                            enqueue(&webhooks, MINED, TransactionDetails::from_completed(&tx, confirmations));
+                        }
+                        if let Some(program) = program {
+                            let args = args_from_complete(&tx, MINED, confirmations);
+                            log(Command::new(program).args(&args).output());
+                        }
                         let message = WalletEventMessage::Completed {
                             event: MINED.to_string(),
                             transaction: tx,
                         };
                         let _ignored = sender.send(message);
-                        log(result);
                     },
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
         } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
+            trace!(target: LOG_TARGET, "No script or webhooks defined, not running.");
         }
     }
 
@@ -190,22 +254,27 @@ impl Notifier {
             QUEUED
         };
 
-        if let Some(program) = self.path.clone() {
+        if self.is_enabled() {
+            let program = self.path.clone();
+            let webhooks = self.webhooks.clone();
             let mut transaction_service = self.wallet.transaction_service.clone();
             let sender = self.event_broadcaster.clone();
             self.handle.spawn(async move {
                 match transaction_service.get_pending_outbound_transactions().await {
                     Ok(txs) => {
                         if let Some(tx) = txs.get(&tx_id) {
-                            let args = args_from_outbound(tx, event);
-                            let result = Command::new(program).args(&args).output();
+                            if let Some(webhooks) = webhooks {
+                                enqueue(&webhooks, event, TransactionDetails::from_outbound(tx));
+                            }
+                            if let Some(program) = program {
+                                let args = args_from_outbound(tx, event);
+                                log(Command::new(program).args(&args).output());
+                            }
                             let message = WalletEventMessage::Outbound {
                                 event: event.to_string(),
                                 transaction: tx.clone(),
                             };
                             let _ignored = sender.send(message);
-
-                            log(result);
                         } else {
                             error!(target: LOG_TARGET, "Not found in pending outbound set tx_id: {}", tx_id);
                         }
@@ -214,7 +283,7 @@ impl Notifier {
                 }
             });
         } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
+            trace!(target: LOG_TARGET, "No script or webhooks defined, not running.");
         }
     }
 
@@ -222,20 +291,25 @@ impl Notifier {
     pub fn transaction_cancelled(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_cancelled tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if self.is_enabled() {
+            let program = self.path.clone();
+            let webhooks = self.webhooks.clone();
             let mut transaction_service = self.wallet.transaction_service.clone();
             let sender = self.event_broadcaster.clone();
             self.handle.spawn(async move {
                 match transaction_service.get_any_transaction(tx_id).await {
                     Ok(Some(wallet_tx)) => {
-                        let args = match wallet_tx {
+                        let (args, details) = match wallet_tx {
                             WalletTransaction::Completed(tx) => {
                                 let message = WalletEventMessage::Completed {
                                     event: CANCELLED.to_string(),
                                     transaction: tx.clone(),
                                 };
                                 let _ignored = sender.send(message);
-                                args_from_complete(&tx, CANCELLED, None)
+                                (
+                                    args_from_complete(&tx, CANCELLED, None),
+                                    TransactionDetails::from_completed(&tx, None),
+                                )
                             },
                             WalletTransaction::PendingInbound(tx) => {
                                 let message = WalletEventMessage::Inbound {
@@ -243,7 +317,7 @@ impl Notifier {
                                     transaction: tx.clone(),
                                 };
                                 let _ignored = sender.send(message);
-                                args_from_inbound(&tx, CANCELLED)
+                                (args_from_inbound(&tx, CANCELLED), TransactionDetails::from_inbound(&tx))
                             },
                             WalletTransaction::PendingOutbound(tx) => {
                                 let message = WalletEventMessage::Outbound {
@@ -251,23 +325,35 @@ impl Notifier {
                                     transaction: tx.clone(),
                                 };
                                 let _ignored = sender.send(message);
-                                args_from_outbound(&tx, CANCELLED)
+                                (
+                                    args_from_outbound(&tx, CANCELLED),
+                                    TransactionDetails::from_outbound(&tx),
+                                )
                             },
                         };
-                        // c
-                        let result = Command::new(program).args(&args).output();
-                        log(result);
+                        if let Some(webhooks) = webhooks {
+                            enqueue(&webhooks, CANCELLED, details);
+                        }
+                        if let Some(program) = program {
+                            log(Command::new(program).args(&args).output());
+                        }
                     },
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                     _ => error!(target: LOG_TARGET, "Transaction not found tx_id: {}", tx_id),
                 }
             });
         } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
+            trace!(target: LOG_TARGET, "No script or webhooks defined, not running.");
         }
     }
 }
 
+fn enqueue(webhooks: &WebhookNotifier, event: &str, details: TransactionDetails) {
+    if let Err(e) = webhooks.enqueue(event, details) {
+        error!(target: LOG_TARGET, "Failed to queue webhook event: {}", e);
+    }
+}
+
 fn log(result: Result<Output, Error>) {
     match result {
         Ok(output) => {
@@ -0,0 +1,651 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! HTTP webhook delivery for wallet transaction events.
+//!
+//! Every event is written to an outbox file before any delivery is attempted, and is only removed from the outbox once
+//! an endpoint has accepted it with a 2xx response. Failed deliveries are retried with exponential backoff, so events
+//! are not lost if an endpoint is unavailable or the wallet is restarted. Deliveries to each endpoint are made in the
+//! order in which the events occurred.
+
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use log::*;
+use minotari_wallet::{
+    transaction_service::storage::models::{CompletedTransaction, InboundTransaction, OutboundTransaction},
+    WebhookConfig,
+};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tari_utilities::hex::Hex;
+use thiserror::Error;
+use tokio::{runtime::Handle, sync::Notify, time};
+use url::Url;
+
+const LOG_TARGET: &str = "wallet::notifier::webhook";
+
+/// The header containing the id of the event being delivered. The same event is delivered with the same id on retries.
+pub const EVENT_ID_HEADER: &str = "X-Tari-Event-Id";
+/// The header containing the unix time (in seconds) at which the delivery was signed
+pub const TIMESTAMP_HEADER: &str = "X-Tari-Timestamp";
+/// The header containing the `sha256=<hex>` HMAC-SHA256 signature of `<timestamp>.<body>`
+pub const SIGNATURE_HEADER: &str = "X-Tari-Signature";
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook URL `{url}`: {source}")]
+    InvalidUrl { url: String, source: url::ParseError },
+    #[error("Webhook outbox IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Webhook serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Webhook HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Webhook endpoint responded with status {0}")]
+    UnexpectedStatus(u16),
+}
+
+/// The transaction fields included in a webhook notification. These are the same values that are passed to the
+/// notify script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionDetails {
+    pub tx_id: u64,
+    /// The amount in micro Minotari
+    pub amount: u64,
+    pub status: String,
+    pub direction: String,
+    pub payment_id: String,
+    pub source_address: Option<String>,
+    pub destination_address: Option<String>,
+    pub excess: Option<String>,
+    pub public_nonce: Option<String>,
+    pub signature: Option<String>,
+    pub confirmations: Option<u64>,
+}
+
+impl TransactionDetails {
+    pub fn from_completed(tx: &CompletedTransaction, confirmations: Option<u64>) -> Self {
+        let kernel = tx.transaction.body.kernels().first();
+        Self {
+            tx_id: tx.tx_id.as_u64(),
+            amount: tx.amount.as_u64(),
+            status: tx.status.to_string(),
+            direction: tx.direction.to_string(),
+            payment_id: tx.payment_id.user_data_as_string(),
+            source_address: Some(tx.source_address.to_base58()),
+            destination_address: Some(tx.destination_address.to_base58()),
+            excess: kernel.map(|k| k.excess.to_hex()),
+            public_nonce: kernel.map(|k| k.excess_sig.get_public_nonce().to_hex()),
+            signature: kernel.map(|k| k.excess_sig.get_signature().to_hex()),
+            confirmations,
+        }
+    }
+
+    pub fn from_outbound(tx: &OutboundTransaction) -> Self {
+        Self {
+            tx_id: tx.tx_id.as_u64(),
+            amount: tx.amount.as_u64(),
+            status: tx.status.to_string(),
+            direction: "outbound".to_string(),
+            payment_id: tx.payment_id.user_data_as_string(),
+            source_address: None,
+            destination_address: Some(tx.destination_address.to_base58()),
+            excess: None,
+            public_nonce: None,
+            signature: None,
+            confirmations: None,
+        }
+    }
+
+    pub fn from_inbound(tx: &InboundTransaction) -> Self {
+        Self {
+            tx_id: tx.tx_id.as_u64(),
+            amount: tx.amount.as_u64(),
+            status: tx.status.to_string(),
+            direction: "inbound".to_string(),
+            payment_id: tx.payment_id.user_data_as_string(),
+            source_address: Some(tx.source_address.to_base58()),
+            destination_address: None,
+            excess: None,
+            public_nonce: None,
+            signature: None,
+            confirmations: None,
+        }
+    }
+}
+
+/// The JSON body of a webhook notification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// A unique, increasing id for the event. Endpoints can use this to ignore duplicate deliveries.
+    pub id: u64,
+    /// The event name, e.g. `received` or `mined`
+    pub event: String,
+    /// The unix time (in seconds) at which the event occurred
+    pub timestamp: u64,
+    pub transaction: TransactionDetails,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Delivery {
+    url: String,
+    event: WebhookEvent,
+    attempts: u32,
+    /// The unix time (in milliseconds) after which the next attempt may be made
+    next_attempt_at: u64,
+}
+
+/// The persisted delivery state
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Outbox {
+    next_event_id: u64,
+    deliveries: Vec<Delivery>,
+}
+
+impl Outbox {
+    fn load(path: &Path) -> Result<Self, WebhookError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read(path)?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    /// Writes the outbox to a temporary file and then moves it into place, so that a partially written outbox is
+    /// never loaded.
+    fn save(&self, path: &Path) -> Result<(), WebhookError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Returns the oldest delivery for each URL if it is due, and the time at which the next delivery that is not yet
+    /// due will be.
+    fn due_deliveries(&self, now: u64) -> (Vec<Delivery>, Option<u64>) {
+        let mut seen_urls = Vec::<&str>::new();
+        let mut due = Vec::new();
+        let mut next_at = None;
+        for delivery in &self.deliveries {
+            if seen_urls.contains(&delivery.url.as_str()) {
+                continue;
+            }
+            seen_urls.push(&delivery.url);
+            if delivery.next_attempt_at <= now {
+                due.push(delivery.clone());
+            } else {
+                next_at = Some(next_at.map_or(delivery.next_attempt_at, |t: u64| t.min(delivery.next_attempt_at)));
+            }
+        }
+        (due, next_at)
+    }
+
+    fn position(&self, url: &str, event_id: u64) -> Option<usize> {
+        self.deliveries
+            .iter()
+            .position(|d| d.url == url && d.event.id == event_id)
+    }
+}
+
+/// Delivers transaction events to the configured webhook endpoints. Cloning this returns a handle to the same outbox.
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    config: WebhookConfig,
+    outbox: Mutex<Outbox>,
+    notify: Notify,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    /// Loads any undelivered events from the outbox file. Deliveries for URLs that are no longer configured are
+    /// discarded.
+    pub fn new(config: WebhookConfig) -> Result<Self, WebhookError> {
+        for url in config.urls.iter() {
+            Url::parse(url).map_err(|source| WebhookError::InvalidUrl {
+                url: url.clone(),
+                source,
+            })?;
+        }
+        let mut outbox = Outbox::load(&config.outbox_file)?;
+        let (deliveries, discarded) = std::mem::take(&mut outbox.deliveries)
+            .into_iter()
+            .partition::<Vec<_>, _>(|d| config.urls.iter().any(|url| *url == d.url));
+        outbox.deliveries = deliveries;
+        for delivery in &discarded {
+            warn!(
+                target: LOG_TARGET,
+                "Discarding undelivered webhook event {} ({}) for {}, which is no longer configured",
+                delivery.event.id,
+                delivery.event.event,
+                delivery.url
+            );
+        }
+        if !discarded.is_empty() {
+            outbox.save(&config.outbox_file)?;
+        }
+        if !outbox.deliveries.is_empty() {
+            info!(
+                target: LOG_TARGET,
+                "Resuming delivery of {} webhook event(s)",
+                outbox.deliveries.len()
+            );
+        }
+        let client = reqwest::Client::builder().timeout(config.request_timeout).build()?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                config,
+                outbox: Mutex::new(outbox),
+                notify: Notify::new(),
+                client,
+            }),
+        })
+    }
+
+    /// Starts delivering events from the outbox
+    pub fn start(&self, handle: &Handle) {
+        handle.spawn(self.clone().run());
+    }
+
+    /// Adds an event for each configured URL to the outbox. The event is persisted before this returns.
+    pub fn enqueue(&self, event: &str, transaction: TransactionDetails) -> Result<(), WebhookError> {
+        {
+            let mut outbox = self.inner.outbox.lock().expect("webhook outbox lock poisoned");
+            let event = WebhookEvent {
+                id: outbox.next_event_id,
+                event: event.to_string(),
+                timestamp: now_millis() / 1000,
+                transaction,
+            };
+            outbox.next_event_id += 1;
+            for url in self.inner.config.urls.iter() {
+                outbox.deliveries.push(Delivery {
+                    url: url.clone(),
+                    event: event.clone(),
+                    attempts: 0,
+                    next_attempt_at: 0,
+                });
+            }
+            outbox.save(&self.inner.config.outbox_file)?;
+        }
+        self.inner.notify.notify_one();
+        Ok(())
+    }
+
+    async fn run(self) {
+        loop {
+            let (due, next_at) = {
+                let outbox = self.inner.outbox.lock().expect("webhook outbox lock poisoned");
+                outbox.due_deliveries(now_millis())
+            };
+
+            if due.is_empty() {
+                match next_at {
+                    Some(next_at) => {
+                        let wait = Duration::from_millis(next_at.saturating_sub(now_millis()));
+                        tokio::select! {
+                            _ = time::sleep(wait) => {},
+                            _ = self.inner.notify.notified() => {},
+                        }
+                    },
+                    None => self.inner.notify.notified().await,
+                }
+                continue;
+            }
+
+            let results = futures::future::join_all(due.iter().map(|d| self.deliver(d))).await;
+            let mut outbox = self.inner.outbox.lock().expect("webhook outbox lock poisoned");
+            for (delivery, result) in due.into_iter().zip(results) {
+                let pos = match outbox.position(&delivery.url, delivery.event.id) {
+                    Some(pos) => pos,
+                    None => continue,
+                };
+                match result {
+                    Ok(()) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Delivered webhook event {} ({}) to {}", delivery.event.id, delivery.event.event, delivery.url
+                        );
+                        outbox.deliveries.remove(pos);
+                    },
+                    Err(e) => {
+                        let attempts = delivery.attempts.saturating_add(1);
+                        let delay = retry_delay(&self.inner.config, attempts);
+                        warn!(
+                            target: LOG_TARGET,
+                            "Failed to deliver webhook event {} to {} (attempt {}), retrying in {:.0?}: {}",
+                            delivery.event.id,
+                            delivery.url,
+                            attempts,
+                            delay,
+                            e
+                        );
+                        let pending = &mut outbox.deliveries[pos];
+                        pending.attempts = attempts;
+                        pending.next_attempt_at = now_millis().saturating_add(delay.as_millis() as u64);
+                    },
+                }
+            }
+            if let Err(e) = outbox.save(&self.inner.config.outbox_file) {
+                error!(target: LOG_TARGET, "Failed to save webhook outbox: {}", e);
+            }
+        }
+    }
+
+    async fn deliver(&self, delivery: &Delivery) -> Result<(), WebhookError> {
+        let body = serde_json::to_string(&delivery.event)?;
+        let timestamp = (now_millis() / 1000).to_string();
+        let mut request = self
+            .inner
+            .client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, delivery.event.id.to_string())
+            .header(TIMESTAMP_HEADER, &timestamp);
+        if let Some(secret) = &self.inner.config.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret.reveal(), &timestamp, &body));
+        }
+        let response = request.body(body).send().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(WebhookError::UnexpectedStatus(response.status().as_u16()))
+        }
+    }
+}
+
+/// Returns the `sha256=<hex>` signature header value for a delivery
+fn sign(secret: &[u8], timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", mac.finalize().into_bytes().to_vec().to_hex())
+}
+
+/// The delay before the next attempt after `attempts` failed attempts
+fn retry_delay(config: &WebhookConfig, attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    config
+        .initial_retry_delay
+        .checked_mul(factor)
+        .unwrap_or(config.max_retry_delay)
+        .min(config.max_retry_delay)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use tari_common::configuration::StringList;
+    use tari_utilities::SafePassword;
+    use tempfile::tempdir;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    /// A request received by the HTTP stand-in
+    struct ReceivedRequest {
+        headers: HashMap<String, String>,
+        body: String,
+        responded_with: u16,
+    }
+
+    /// A minimal local HTTP server that responds to each request with the next scripted status (200 once the script
+    /// is exhausted) and reports every request it receives.
+    async fn spawn_stand_in(statuses: Vec<u16>) -> (String, mpsc::UnboundedReceiver<ReceivedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut statuses = statuses.into_iter();
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+                let header_end = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    assert!(n > 0, "connection closed before headers were received");
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let head = String::from_utf8(buf[..header_end].to_vec()).unwrap();
+                let headers = head
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| line.split_once(':'))
+                    .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+                    .collect::<HashMap<_, _>>();
+                let content_length = headers
+                    .get("content-length")
+                    .map(|v| v.parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                while buf.len() < header_end + content_length {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                let body = String::from_utf8(buf[header_end..header_end + content_length].to_vec()).unwrap();
+                let status = statuses.next().unwrap_or(200);
+                let response = format!(
+                    "HTTP/1.1 {} Stand-in\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+                let _ignored = tx.send(ReceivedRequest {
+                    headers,
+                    body,
+                    responded_with: status,
+                });
+            }
+        });
+        (url, rx)
+    }
+
+    fn test_config(url: String, outbox_file: &Path) -> WebhookConfig {
+        WebhookConfig {
+            urls: StringList::from(vec![url]),
+            secret: Some(SafePassword::from("hunter2")),
+            outbox_file: outbox_file.to_path_buf(),
+            initial_retry_delay: Duration::from_millis(50),
+            max_retry_delay: Duration::from_millis(200),
+            request_timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn sample_details(tx_id: u64) -> TransactionDetails {
+        TransactionDetails {
+            tx_id,
+            amount: 1_000_000,
+            status: "Completed".to_string(),
+            direction: "Inbound".to_string(),
+            payment_id: "None".to_string(),
+            source_address: Some("source".to_string()),
+            destination_address: Some("destination".to_string()),
+            excess: None,
+            public_nonce: None,
+            signature: None,
+            confirmations: None,
+        }
+    }
+
+    async fn next_request(rx: &mut mpsc::UnboundedReceiver<ReceivedRequest>) -> ReceivedRequest {
+        time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("timed out waiting for webhook delivery")
+            .unwrap()
+    }
+
+    #[test]
+    fn it_signs_with_hmac_sha256() {
+        // RFC 4231 test case 2
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"Jefe").unwrap();
+        mac.update(b"what do ya want for nothing?");
+        assert_eq!(
+            mac.finalize().into_bytes().to_vec().to_hex(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        // The timestamp is covered by the signature
+        let signature = sign(b"Jefe", "1700000000", "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_ne!(signature, sign(b"Jefe", "1700000001", "{}"));
+    }
+
+    #[test]
+    fn it_backs_off_exponentially_up_to_the_maximum() {
+        let config = WebhookConfig {
+            initial_retry_delay: Duration::from_secs(5),
+            max_retry_delay: Duration::from_secs(60),
+            ..Default::default()
+        };
+        assert_eq!(retry_delay(&config, 1), Duration::from_secs(5));
+        assert_eq!(retry_delay(&config, 2), Duration::from_secs(10));
+        assert_eq!(retry_delay(&config, 4), Duration::from_secs(40));
+        assert_eq!(retry_delay(&config, 5), Duration::from_secs(60));
+        assert_eq!(retry_delay(&config, u32::MAX), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn it_only_delivers_the_oldest_event_per_url() {
+        let delivery = |url: &str, id, next_attempt_at| Delivery {
+            url: url.to_string(),
+            event: WebhookEvent {
+                id,
+                event: "mined".to_string(),
+                timestamp: 0,
+                transaction: sample_details(id),
+            },
+            attempts: 0,
+            next_attempt_at,
+        };
+        let outbox = Outbox {
+            next_event_id: 3,
+            deliveries: vec![
+                delivery("a", 0, 100),
+                delivery("b", 0, 0),
+                delivery("a", 1, 0),
+                delivery("b", 1, 0),
+            ],
+        };
+        let (due, next_at) = outbox.due_deliveries(50);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].url, "b");
+        assert_eq!(due[0].event.id, 0);
+        assert_eq!(next_at, Some(100));
+    }
+
+    #[tokio::test]
+    async fn it_retries_failed_deliveries_with_a_valid_signature() {
+        let dir = tempdir().unwrap();
+        let (url, mut rx) = spawn_stand_in(vec![500, 503]).await;
+        let notifier = WebhookNotifier::new(test_config(url, &dir.path().join("outbox.json"))).unwrap();
+        notifier.start(&Handle::current());
+        notifier.enqueue("received", sample_details(1)).unwrap();
+
+        let mut bodies = Vec::new();
+        loop {
+            let request = next_request(&mut rx).await;
+            let timestamp = request.headers.get("x-tari-timestamp").unwrap();
+            assert_eq!(
+                request.headers.get("x-tari-signature").unwrap(),
+                &sign(b"hunter2", timestamp, &request.body)
+            );
+            assert_eq!(request.headers.get("x-tari-event-id").unwrap(), "0");
+            bodies.push(request.body);
+            if request.responded_with == 200 {
+                break;
+            }
+        }
+        assert_eq!(bodies.len(), 3);
+        let event: WebhookEvent = serde_json::from_str(&bodies[2]).unwrap();
+        assert_eq!(event.id, 0);
+        assert_eq!(event.event, "received");
+        assert_eq!(event.transaction, sample_details(1));
+        assert!(bodies.iter().all(|b| *b == bodies[0]));
+    }
+
+    #[tokio::test]
+    async fn it_delivers_persisted_events_after_a_restart() {
+        let dir = tempdir().unwrap();
+        let outbox_file = dir.path().join("outbox.json");
+        let (url, mut rx) = spawn_stand_in(vec![]).await;
+
+        // Events are queued but the first notifier is never started, as if the wallet shut down before delivering
+        let notifier = WebhookNotifier::new(test_config(url.clone(), &outbox_file)).unwrap();
+        notifier.enqueue("sent", sample_details(1)).unwrap();
+        notifier.enqueue("mined", sample_details(1)).unwrap();
+        drop(notifier);
+
+        let notifier = WebhookNotifier::new(test_config(url, &outbox_file)).unwrap();
+        notifier.start(&Handle::current());
+        let first: WebhookEvent = serde_json::from_str(&next_request(&mut rx).await.body).unwrap();
+        let second: WebhookEvent = serde_json::from_str(&next_request(&mut rx).await.body).unwrap();
+        assert_eq!((first.id, first.event.as_str()), (0, "sent"));
+        assert_eq!((second.id, second.event.as_str()), (1, "mined"));
+
+        // New events continue the id sequence
+        notifier.enqueue("confirmation", sample_details(1)).unwrap();
+        let third: WebhookEvent = serde_json::from_str(&next_request(&mut rx).await.body).unwrap();
+        assert_eq!(third.id, 2);
+    }
+
+    #[test]
+    fn it_discards_deliveries_for_removed_urls() {
+        let dir = tempdir().unwrap();
+        let outbox_file = dir.path().join("outbox.json");
+        let notifier = WebhookNotifier::new(test_config("http://127.0.0.1:1/old".to_string(), &outbox_file)).unwrap();
+        notifier.enqueue("sent", sample_details(1)).unwrap();
+        drop(notifier);
+
+        let notifier = WebhookNotifier::new(test_config("http://127.0.0.1:1/new".to_string(), &outbox_file)).unwrap();
+        assert!(notifier.inner.outbox.lock().unwrap().deliveries.is_empty());
+        assert!(Outbox::load(&outbox_file).unwrap().deliveries.is_empty());
+    }
+}
@@ -37,7 +37,7 @@ use minotari_wallet::{
 use rustyline::Editor;
 use tari_common::exit_codes::{ExitCode, ExitError};
 use tari_crypto::tari_utilities::Hidden;
-use tari_key_manager::{cipher_seed::CipherSeed, mnemonic::Mnemonic, SeedWords};
+use tari_key_manager::{bip39::is_bip39_mnemonic, cipher_seed::CipherSeed, seed_share::SeedShare, SeedWords};
 use tari_shutdown::Shutdown;
 use tari_utilities::{hex::Hex, SafePassword};
 use tokio::{runtime::Runtime, sync::broadcast};
@@ -47,25 +47,48 @@ use crate::wallet_modes::PeerConfig;
 
 pub const LOG_TARGET: &str = "wallet::recovery";
 
-/// Prompt the user to input their seed words in a single line.
-pub fn prompt_private_key_from_seed_words() -> Result<CipherSeed, ExitError> {
+/// Prompt the user to input their seed words in a single line. With `bip39` set, the words are read as a BIP-39
+/// phrase instead.
+pub fn prompt_private_key_from_seed_words(bip39: bool) -> Result<CipherSeed, ExitError> {
     debug!(target: LOG_TARGET, "Prompting for seed words.");
     let mut rl = Editor::<()>::new();
 
     loop {
         println!("Recovery Mode");
         println!();
-        println!("Type or paste all of your seed words on one line, only separated by spaces.");
-        let input = Hidden::hide(rl.readline(">> ").map_err(|e| ExitError::new(ExitCode::IOError, e))?);
-        let seed_words: SeedWords = SeedWords::new(
-            input
-                .reveal()
-                .split_whitespace()
-                .map(|s| Hidden::hide(s.to_string()))
-                .collect(),
-        );
+        if bip39 {
+            println!("Type or paste your 12 or 24 word BIP-39 phrase on one line, only separated by spaces.");
+        } else {
+            println!("Type or paste all of your seed words on one line, only separated by spaces.");
+            println!("To recover from a seed share backup, enter one of the shares instead.");
+        }
+        let seed_words = read_seed_words(&mut rl)?;
+
+        // Seed shares have a different length to seed words, so they cannot be mistaken for each other
+        if !bip39 {
+            if let Ok(share) = SeedShare::from_mnemonic(&seed_words) {
+                match prompt_remaining_seed_shares(&mut rl, share) {
+                    Ok(seed) => break Ok(seed),
+                    Err(e) => {
+                        println!("Failed to recover the seed from the shares: {}", e);
+                        continue;
+                    },
+                }
+            }
+        }
 
-        match CipherSeed::from_mnemonic(&seed_words, None) {
+        let seed = match CipherSeed::from_mnemonic_words(&seed_words, None, bip39) {
+            Ok(seed) if bip39 => {
+                notify_bip39_birthday();
+                Ok(seed)
+            },
+            Err(_) if !bip39 && confirm_bip39(&mut rl, &seed_words)? => {
+                notify_bip39_birthday();
+                CipherSeed::from_bip39_mnemonic(&seed_words)
+            },
+            result => result,
+        };
+        match seed {
             Ok(seed) => break Ok(seed),
             Err(e) => {
                 debug!(target: LOG_TARGET, "MnemonicError parsing seed words: {}", e);
@@ -76,14 +99,20 @@ pub fn prompt_private_key_from_seed_words() -> Result<CipherSeed, ExitError> {
     }
 }
 
-/// Return seed matching the seed words.
+/// Return seed matching the seed words, which are read as a BIP-39 phrase if `bip39` is set.
 pub fn get_seed_from_seed_words(
     seed_words: &SeedWords,
     passphrase: Option<SafePassword>,
+    bip39: bool,
 ) -> Result<CipherSeed, ExitError> {
     debug!(target: LOG_TARGET, "Return seed derived from the provided seed words");
-    match CipherSeed::from_mnemonic(seed_words, passphrase) {
-        Ok(seed) => Ok(seed),
+    match CipherSeed::from_mnemonic_words(seed_words, passphrase, bip39) {
+        Ok(seed) => {
+            if bip39 {
+                notify_bip39_birthday();
+            }
+            Ok(seed)
+        },
         Err(e) => {
             let err_msg = format!("MnemonicError parsing seed words: {}", e);
             warn!(target: LOG_TARGET, "{}", err_msg);
@@ -92,6 +121,65 @@ pub fn get_seed_from_seed_words(
     }
 }
 
+/// Read one line of space separated words
+fn read_seed_words(rl: &mut Editor<()>) -> Result<SeedWords, ExitError> {
+    let input = Hidden::hide(rl.readline(">> ").map_err(|e| ExitError::new(ExitCode::IOError, e))?);
+    Ok(SeedWords::new(
+        input
+            .reveal()
+            .split_whitespace()
+            .map(|s| Hidden::hide(s.to_string()))
+            .collect(),
+    ))
+}
+
+/// Prompt for further seed shares of the same backup as `first`, until enough have been entered to recover the seed
+fn prompt_remaining_seed_shares(rl: &mut Editor<()>, first: SeedShare) -> Result<CipherSeed, ExitError> {
+    let threshold = usize::from(first.threshold());
+    let mut shares = vec![first];
+    while shares.len() < threshold {
+        println!(
+            "Seed share accepted, {} of {} entered. Type or paste the next share on one line.",
+            shares.len(),
+            threshold
+        );
+        let seed_words = read_seed_words(rl)?;
+        match SeedShare::from_mnemonic(&seed_words) {
+            Ok(share) if share.identifier() != shares[0].identifier() => {
+                println!("That share belongs to a different backup.");
+            },
+            Ok(share) if shares.iter().any(|s| s.index() == share.index()) => {
+                println!("That share has already been entered.");
+            },
+            Ok(share) => shares.push(share),
+            Err(e) => {
+                debug!(target: LOG_TARGET, "Error parsing seed share: {}", e);
+                println!("Failed to parse the seed share! Did you type it correctly?");
+            },
+        }
+    }
+
+    CipherSeed::from_shares(&shares).map_err(|e| ExitError::new(ExitCode::RecoveryError, e))
+}
+
+/// Seed words that fail to restore as cipher seed words but form a valid BIP-39 phrase are only restored as such
+/// once the user confirms it, as a mistyped set of seed words would otherwise silently restore an empty wallet
+fn confirm_bip39(rl: &mut Editor<()>, seed_words: &SeedWords) -> Result<bool, ExitError> {
+    if !is_bip39_mnemonic(seed_words) {
+        return Ok(false);
+    }
+    println!("These are not wallet seed words, but they do form a valid BIP-39 phrase.");
+    let answer = rl
+        .readline("Restore the wallet from the BIP-39 phrase? (y/N) ")
+        .map_err(|e| ExitError::new(ExitCode::IOError, e))?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// A BIP-39 phrase does not record the wallet birthday, so let the user know why recovery starts at the genesis block
+fn notify_bip39_birthday() {
+    println!("A BIP-39 phrase has no wallet birthday, so recovery will scan from the genesis block.");
+}
+
 /// Recovers wallet funds by connecting to a given base node peer, downloading the transaction outputs stored in the
 /// blockchain, and attempting to rewind them. Any outputs that are successfully rewound are then imported into the
 /// wallet.
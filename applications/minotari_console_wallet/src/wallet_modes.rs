@@ -40,7 +40,7 @@ use crate::{
     automation::commands::command_runner,
     cli::{Cli, CliCommands},
     grpc::WalletGrpcServer,
-    notifier::Notifier,
+    notifier::{Notifier, WebhookNotifier},
     recovery::wallet_recovery,
     ui,
     ui::App,
@@ -356,8 +356,10 @@ pub fn tui_mode(
         ));
     }
 
+    // Webhooks are delivered for every wallet mode, see `start_webhooks`
     let notifier = Notifier::new(
         config.notify_file.clone(),
+        None,
         handle.clone(),
         wallet.clone(),
         events_broadcaster,
@@ -402,6 +404,24 @@ pub fn tui_mode(
     Ok(())
 }
 
+/// Starts delivering webhook notifications for the wallet's transaction events, whichever wallet mode is run
+pub fn start_webhooks(handle: &Handle, config: &WalletConfig, wallet: &WalletSqlite) -> Result<(), ExitError> {
+    if !config.webhooks.is_enabled() {
+        return Ok(());
+    }
+    let webhooks =
+        WebhookNotifier::new(config.webhooks.clone()).map_err(|e| ExitError::new(ExitCode::ConfigError, e))?;
+    webhooks.start(handle);
+
+    let (events_broadcaster, _events_listener) = broadcast::channel(100);
+    let notifier = Notifier::new(None, Some(webhooks), handle.clone(), wallet.clone(), events_broadcaster);
+    handle.spawn(notifier.run(
+        wallet.transaction_service.get_event_stream(),
+        wallet.comms.shutdown_signal(),
+    ));
+    Ok(())
+}
+
 pub fn recovery_mode(
     handle: Handle,
     base_node_config: &PeerConfig,
@@ -529,6 +549,8 @@ async fn run_grpc(
 mod test {
     use std::path::Path;
 
+    use minotari_wallet::output_manager_service::UtxoSelectionOrdering;
+
     use crate::{cli::CliCommands, wallet_modes::parse_command_file};
 
     #[test]
@@ -544,7 +566,7 @@ mod test {
 
             discover-peer f6b2ca781342a3ebe30ee1643655c96f1d7c14f4d49f077695395de98ae73665
 
-            send-minotari --payment-id Our_secret! 125T \
+            send-minotari --payment-id Our_secret! --selection branch-and-bound 125T \
              f425UWsDp714RiN53c1G6ek57rfFnotB5NCMyrn4iDgbR8i2sXVHa4xSsedd66o9KmkRgErQnyDdCaAdNLzcKrj7eUb
             
             burn-minotari --payment-id Ups_these_funds_will_be_burned! 100T
@@ -581,6 +603,16 @@ mod test {
 
             import-tx --input-file pie_this_message.txt
 
+            send-batch --input-file payouts.csv --selection largest
+
+            prepare-offline-transaction --input-file payouts.csv --output-file unsigned.json
+
+            sign-offline-transaction --input-file unsigned.json --output-file signed.json
+
+            broadcast-signed-transaction --input-file signed.json
+
+            export-seed-shares --threshold 2 --shares 3
+
             # End of script file
             "
             .to_string();
@@ -601,11 +633,17 @@ mod test {
         let mut discover_peer = false;
         let mut export_tx = false;
         let mut import_tx = false;
+        let mut send_batch = false;
+        let mut offline_signing = 0;
+        let mut export_seed_shares = false;
         let mut whois = false;
         for command in commands {
             match command {
                 CliCommands::GetBalance => get_balance = true,
-                CliCommands::SendMinotari(_) => send_tari = true,
+                CliCommands::SendMinotari(args) => {
+                    assert_eq!(args.selection, UtxoSelectionOrdering::BranchAndBound);
+                    send_tari = true
+                },
                 CliCommands::BurnMinotari(_) => burn_tari = true,
                 CliCommands::PreMineSpendGetOutputStatus => pre_mine_spend_get_output_status = true,
                 CliCommands::PreMineSpendSessionInfo(_) => pre_mine_spend_session_info = true,
@@ -614,6 +652,28 @@ mod test {
                 CliCommands::PreMineSpendInputOutputSigs(_) => pre_mine_spend_input_output_sigs = true,
                 CliCommands::PreMineSpendAggregateTransaction(_) => pre_mine_spend_aggregate_transaction = true,
                 CliCommands::SendOneSidedToStealthAddress(_) => {},
+                CliCommands::SendBatch(args) => {
+                    assert_eq!(args.selection, UtxoSelectionOrdering::Largest);
+                    if args.input_file == Path::new("payouts.csv") {
+                        send_batch = true
+                    }
+                },
+                CliCommands::PrepareOfflineTransaction(args) => {
+                    if args.input_file == Path::new("payouts.csv") && args.output_file == Path::new("unsigned.json") {
+                        offline_signing += 1
+                    }
+                },
+                CliCommands::SignOfflineTransaction(args) => {
+                    if args.input_file == Path::new("unsigned.json") && args.output_file == Path::new("signed.json") {
+                        offline_signing += 1
+                    }
+                },
+                CliCommands::BroadcastSignedTransaction(args) => {
+                    if args.input_file == Path::new("signed.json") {
+                        offline_signing += 1
+                    }
+                },
+                CliCommands::BumpFee(_) => {},
                 CliCommands::MakeItRain(_) => make_it_rain = true,
                 CliCommands::CoinSplit(_) => coin_split = true,
                 CliCommands::DiscoverPeer(_) => discover_peer = true,
@@ -644,6 +704,11 @@ mod test {
                 CliCommands::PreMineSpendBackupUtxo(_) => {},
                 CliCommands::Sync(_) => {},
                 CliCommands::ExportViewKeyAndSpendKey(_) => {},
+                CliCommands::ExportSeedShares(args) => {
+                    if args.threshold == 2 && args.shares == 3 && args.output_dir.is_none() {
+                        export_seed_shares = true
+                    }
+                },
             }
         }
         assert!(
@@ -661,7 +726,10 @@ mod test {
                 discover_peer &&
                 whois &&
                 export_tx &&
-                import_tx
+                import_tx &&
+                send_batch &&
+                offline_signing == 3 &&
+                export_seed_shares
         );
     }
 }
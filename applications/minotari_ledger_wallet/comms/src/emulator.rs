@@ -0,0 +1,830 @@
+// Copyright 2024 The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A software implementation of the 'Minotari Wallet' Ledger application.
+//!
+//! [`EmulatedLedgerDevice`] answers the same APDU instruction set as the firmware in
+//! `applications/minotari_ledger_wallet/wallet`, so that the wallet's `WalletType::Ledger` code paths can be exercised
+//! without hardware. Keys are derived from a 32-byte seed held in memory rather than from the device's BIP32 root, so
+//! an emulated device is *not* key-compatible with a physical one and must never be used to hold funds.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+    Mutex,
+};
+
+use blake2::Blake2b;
+use digest::consts::{U32, U64};
+use ledger_transport::{APDUAnswer, APDUCommand};
+use minotari_ledger_wallet_common::{
+    common_types::{AppSW, Branch, Instruction},
+    get_public_spend_key_bytes_from_tari_dual_address,
+    TARI_DUAL_ADDRESS_SIZE,
+};
+use once_cell::sync::Lazy;
+use rand::{rngs::OsRng, RngCore};
+use tari_common_types::types::{ComAndPubSignature, Commitment, CommitmentFactory, PrivateKey, PublicKey, Signature};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    dhke::DiffieHellmanSharedSecret,
+    hashing::DomainSeparatedHasher,
+    keys::{PublicKey as PublicKeyTrait, SecretKey},
+};
+use tari_hashing::{
+    DomainSeparatedBorshHasher,
+    KeyManagerTransactionsHashDomain,
+    LedgerHashDomain,
+    TransactionHashDomain,
+};
+use tari_script::{script, CheckSigSchnorrSignature};
+use tari_utilities::{ByteArray, Hidden};
+
+use crate::{
+    error::LedgerDeviceError,
+    ledger_wallet::{set_ledger_transport, LedgerTransport, EXPECTED_NAME, WALLET_CLA},
+};
+
+const RESPONSE_VERSION: u8 = 1;
+const STATIC_SPEND_INDEX: u64 = 42;
+const STATIC_VIEW_INDEX: u64 = 57311;
+const MIN_UNIQUE_KEYS: usize = 2;
+const SW_BAD_CLA: u16 = 0x6e00;
+
+static SHARED_DEVICE: Lazy<Arc<EmulatedLedgerDevice>> = Lazy::new(|| {
+    let device = Arc::new(EmulatedLedgerDevice::random());
+    set_ledger_transport(device.clone());
+    device
+});
+
+/// Install a process-wide emulated device as the ledger transport, and return it. Every call returns the same device,
+/// so independent tests in one process agree on the keys held by "the" device.
+pub fn use_emulated_ledger_device() -> Arc<EmulatedLedgerDevice> {
+    SHARED_DEVICE.clone()
+}
+
+/// Key types used by the firmware for key derivation, see `KeyType` in the ledger application.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeyType {
+    Spend = 0x01,
+    ViewKey = 0x03,
+    OneSidedSenderOffset = 0x04,
+    Random = 0x06,
+    PreMine = 0x07,
+    MetadataEphemeralNonce = 0x08,
+}
+
+impl KeyType {
+    fn from_branch_key(n: u64) -> Result<Self, AppSW> {
+        let n = u8::try_from(n).map_err(|_| AppSW::BadBranchKey)?;
+        match Branch::from_byte(n) {
+            Some(Branch::OneSidedSenderOffset) => Ok(Self::OneSidedSenderOffset),
+            Some(Branch::Spend) => Ok(Self::Spend),
+            Some(Branch::RandomKey) => Ok(Self::Random),
+            Some(Branch::PreMine) => Ok(Self::PreMine),
+            Some(Branch::MetadataEphemeralNonce) => Ok(Self::MetadataEphemeralNonce),
+            _ => Err(AppSW::BadBranchKey),
+        }
+    }
+}
+
+/// Running state for the multi-APDU `GetScriptOffset` instruction
+#[derive(Default)]
+struct ScriptOffsetCtx {
+    sender_offset_sum: PrivateKey,
+    script_private_key_sum: PrivateKey,
+    account: u64,
+    total_offset_indexes: u64,
+    total_script_indexes: u64,
+    total_derived_offset_keys: u64,
+    total_derived_script_keys: u64,
+    unique_keys: Vec<PrivateKey>,
+}
+
+impl ScriptOffsetCtx {
+    fn add_unique_key(&mut self, key: PrivateKey) {
+        if !self.unique_keys.contains(&key) {
+            self.unique_keys.push(key);
+        }
+    }
+}
+
+/// An in-process emulation of a Ledger device running the 'Minotari Wallet' application.
+pub struct EmulatedLedgerDevice {
+    seed: Hidden<[u8; 32]>,
+    approve_transactions: AtomicBool,
+    offset_ctx: Mutex<ScriptOffsetCtx>,
+}
+
+impl EmulatedLedgerDevice {
+    /// Create a device whose keys are derived from `seed`. Two devices created from the same seed derive the same keys.
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self {
+            seed: Hidden::hide(seed),
+            approve_transactions: AtomicBool::new(true),
+            offset_ctx: Mutex::new(ScriptOffsetCtx::default()),
+        }
+    }
+
+    /// Create a device with a random seed
+    pub fn random() -> Self {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self::new(seed)
+    }
+
+    /// Set whether the emulated user approves or rejects transactions shown on the device screen. Devices approve by
+    /// default.
+    pub fn set_approve_transactions(&self, approve: bool) {
+        self.approve_transactions.store(approve, Ordering::SeqCst);
+    }
+
+    /// Process a single APDU and return the raw response: the response data followed by the big-endian status word.
+    pub fn process_apdu(&self, command: &APDUCommand<Vec<u8>>) -> Vec<u8> {
+        let result = if command.cla == WALLET_CLA {
+            self.handle_apdu(command).map_err(|sw| sw as u16)
+        } else {
+            Err(SW_BAD_CLA)
+        };
+        match result {
+            Ok(mut data) => {
+                data.extend_from_slice(&(AppSW::Ok as u16).to_be_bytes());
+                data
+            },
+            Err(sw) => sw.to_be_bytes().to_vec(),
+        }
+    }
+
+    fn handle_apdu(&self, command: &APDUCommand<Vec<u8>>) -> Result<Vec<u8>, AppSW> {
+        let instruction = Instruction::from_byte(command.ins).ok_or(AppSW::InsNotSupported)?;
+        let data = command.data.as_slice();
+        if instruction == Instruction::GetScriptOffset {
+            return self.get_script_offset(data, command.p1, command.p2 == 0x01);
+        }
+        if command.p1 != 0 || command.p2 != 0 {
+            return Err(AppSW::WrongP1P2);
+        }
+        match instruction {
+            Instruction::GetVersion => Ok(env!("CARGO_PKG_VERSION").as_bytes().to_vec()),
+            Instruction::GetAppName => Ok(EXPECTED_NAME.as_bytes().to_vec()),
+            Instruction::GetPublicSpendKey => self.get_public_spend_key(data),
+            Instruction::GetPublicKey => self.get_public_key(data),
+            Instruction::GetScriptSignatureManaged => self.get_script_signature_managed(data),
+            Instruction::GetScriptSignatureDerived => self.get_script_signature_derived(data),
+            Instruction::GetViewKey => self.get_view_key(data),
+            Instruction::GetDHSharedSecret => self.get_dh_shared_secret(data),
+            Instruction::GetRawSchnorrSignature => self.get_raw_schnorr_signature(data),
+            Instruction::GetScriptSchnorrSignature => self.get_script_schnorr_signature(data),
+            Instruction::GetOneSidedMetadataSignature => self.get_one_sided_metadata_signature(data),
+            Instruction::GetScriptOffset => Err(AppSW::InsNotSupported),
+        }
+    }
+
+    fn derive_key(&self, account: u64, index: u64, key_type: KeyType) -> Result<PrivateKey, AppSW> {
+        let hash = DomainSeparatedHasher::<Blake2b<U64>, LedgerHashDomain>::new_with_label("emulated_device_key")
+            .chain(self.seed.reveal())
+            .chain(account.to_le_bytes())
+            .chain(index.to_le_bytes())
+            .chain([key_type as u8])
+            .finalize();
+        PrivateKey::from_uniform_bytes(hash.as_ref()).map_err(|_| AppSW::KeyDeriveFromUniform)
+    }
+
+    fn get_public_spend_key(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 8)?;
+        let key = self.derive_key(read_u64(data, 0), STATIC_SPEND_INDEX, KeyType::Spend)?;
+        Ok(response(&[PublicKey::from_secret_key(&key).as_bytes()]))
+    }
+
+    fn get_view_key(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 8)?;
+        let key = self.derive_key(read_u64(data, 0), STATIC_VIEW_INDEX, KeyType::ViewKey)?;
+        Ok(response(&[key.as_bytes()]))
+    }
+
+    fn get_public_key(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 24)?;
+        let key_type = KeyType::from_branch_key(read_u64(data, 16))?;
+        let key = self.derive_key(read_u64(data, 0), read_u64(data, 8), key_type)?;
+        Ok(response(&[PublicKey::from_secret_key(&key).as_bytes()]))
+    }
+
+    fn get_dh_shared_secret(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 56)?;
+        let key_type = KeyType::from_branch_key(read_u64(data, 16))?;
+        let key = self.derive_key(read_u64(data, 0), read_u64(data, 8), key_type)?;
+        let public_key = read_key::<PublicKey>(data, 24)?;
+        let shared_secret = DiffieHellmanSharedSecret::<PublicKey>::new(&key, &public_key);
+        Ok(response(&[shared_secret.as_bytes()]))
+    }
+
+    fn get_raw_schnorr_signature(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 104)?;
+        let account = read_u64(data, 0);
+        let private_key = self.derive_key(
+            account,
+            read_u64(data, 8),
+            KeyType::from_branch_key(read_u64(data, 16))?,
+        )?;
+        let nonce = self.derive_key(
+            account,
+            read_u64(data, 24),
+            KeyType::from_branch_key(read_u64(data, 32))?,
+        )?;
+        let signature = Signature::sign_raw_uniform(&private_key, nonce, &data[40..104])
+            .map_err(|_| AppSW::RawSchnorrSignatureFail)?;
+        Ok(response(&[
+            signature.get_public_nonce().as_bytes(),
+            signature.get_signature().as_bytes(),
+        ]))
+    }
+
+    fn get_script_schnorr_signature(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 56)?;
+        let key_type = KeyType::from_branch_key(read_u64(data, 16))?;
+        let private_key = self.derive_key(read_u64(data, 0), read_u64(data, 8), key_type)?;
+        let signature = CheckSigSchnorrSignature::sign_with_nonce_and_message(
+            &private_key,
+            PrivateKey::random(&mut OsRng),
+            &data[24..56],
+        )
+        .map_err(|_| AppSW::SchnorrSignatureFail)?;
+        Ok(response(&[
+            signature.get_public_nonce().as_bytes(),
+            signature.get_signature().as_bytes(),
+        ]))
+    }
+
+    fn get_script_signature_managed(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 168)?;
+        let key_type = KeyType::from_branch_key(read_u64(data, 152))?;
+        let script_private_key = self.derive_key(read_u64(data, 0), read_u64(data, 160), key_type)?;
+        self.script_signature(data, &script_private_key)
+    }
+
+    fn get_script_signature_derived(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 184)?;
+        let alpha = self.derive_key(read_u64(data, 0), STATIC_SPEND_INDEX, KeyType::Spend)?;
+        let script_private_key = alpha_hasher(alpha, &read_key::<PrivateKey>(data, 152)?)?;
+        self.script_signature(data, &script_private_key)
+    }
+
+    fn script_signature(&self, data: &[u8], script_private_key: &PrivateKey) -> Result<Vec<u8>, AppSW> {
+        let network = read_u64(data, 8);
+        let value = read_key::<PrivateKey>(data, 24)?;
+        let commitment_private_key = read_key::<PrivateKey>(data, 56)?;
+        let commitment = read_key::<Commitment>(data, 88)?;
+        let mut message = [0u8; 32];
+        message.copy_from_slice(&data[120..152]);
+
+        let r_a = PrivateKey::random(&mut OsRng);
+        let r_x = PrivateKey::random(&mut OsRng);
+        let r_y = PrivateKey::random(&mut OsRng);
+        let factory = CommitmentFactory::default();
+        let ephemeral_commitment = factory.commit(&r_x, &r_a);
+        let ephemeral_pubkey = PublicKey::from_secret_key(&r_y);
+        let challenge = script_signature_challenge(
+            network,
+            &ephemeral_commitment,
+            &ephemeral_pubkey,
+            &PublicKey::from_secret_key(script_private_key),
+            &commitment,
+            &message,
+        );
+        let signature = ComAndPubSignature::sign(
+            &value,
+            &commitment_private_key,
+            script_private_key,
+            &r_a,
+            &r_x,
+            &r_y,
+            &challenge,
+            &factory,
+        )
+        .map_err(|_| AppSW::ScriptSignatureFail)?;
+        Ok(com_and_pub_signature_response(&signature))
+    }
+
+    fn get_script_offset(&self, data: &[u8], chunk_number: u8, more: bool) -> Result<Vec<u8>, AppSW> {
+        let mut ctx = self.offset_ctx.lock().unwrap_or_else(|e| e.into_inner());
+
+        // 1. data sizes
+        if chunk_number == 0 {
+            check_length(data, 40)?;
+            *ctx = ScriptOffsetCtx {
+                account: read_u64(data, 0),
+                total_offset_indexes: read_u64(data, 8),
+                total_script_indexes: read_u64(data, 16),
+                total_derived_offset_keys: read_u64(data, 24),
+                total_derived_script_keys: read_u64(data, 32),
+                ..Default::default()
+            };
+            return Ok(vec![]);
+        }
+
+        // 2. partial_script_offset
+        if chunk_number == 1 {
+            check_length(data, 32)?;
+            ctx.script_private_key_sum = read_key::<PrivateKey>(data, 0)?;
+            return Ok(vec![]);
+        }
+
+        let chunk = u64::from(chunk_number);
+        let end_offset_indexes = 2 + ctx.total_offset_indexes;
+        let end_script_indexes = end_offset_indexes + ctx.total_script_indexes;
+        let end_derived_offset_keys = end_script_indexes + ctx.total_derived_offset_keys;
+        let end_derived_script_keys = end_derived_offset_keys + ctx.total_derived_script_keys;
+
+        if chunk < end_script_indexes {
+            // 3. and 4. indexed sender offsets and script keys
+            check_length(data, 16)?;
+            let key_type = KeyType::from_branch_key(read_u64(data, 0))?;
+            let key = self.derive_key(ctx.account, read_u64(data, 8), key_type)?;
+            ctx.add_unique_key(key.clone());
+            if chunk < end_offset_indexes {
+                ctx.sender_offset_sum = &ctx.sender_offset_sum + key;
+            } else {
+                ctx.script_private_key_sum = &ctx.script_private_key_sum + key;
+            }
+        } else if chunk < end_derived_script_keys {
+            // 5. and 6. derived sender offsets and script keys
+            check_length(data, 32)?;
+            let alpha = self.derive_key(ctx.account, STATIC_SPEND_INDEX, KeyType::Spend)?;
+            ctx.add_unique_key(alpha.clone());
+            let key = alpha_hasher(alpha, &read_key::<PrivateKey>(data, 0)?)?;
+            if chunk < end_derived_offset_keys {
+                ctx.sender_offset_sum = &ctx.sender_offset_sum + key;
+            } else {
+                ctx.script_private_key_sum = &ctx.script_private_key_sum + key;
+            }
+        } else {
+            return Err(AppSW::WrongP1P2);
+        }
+
+        if more {
+            return Ok(vec![]);
+        }
+
+        // Guard against attacks to extract the spending private key
+        let unique_keys = ctx.unique_keys.len();
+        let script_offset = &ctx.script_private_key_sum - &ctx.sender_offset_sum;
+        *ctx = ScriptOffsetCtx::default();
+        if unique_keys < MIN_UNIQUE_KEYS {
+            return Err(AppSW::ScriptOffsetNotUnique);
+        }
+        Ok(response(&[script_offset.as_bytes()]))
+    }
+
+    fn get_one_sided_metadata_signature(&self, data: &[u8]) -> Result<Vec<u8>, AppSW> {
+        check_length(data, 72 + TARI_DUAL_ADDRESS_SIZE + 32)?;
+        let account = read_u64(data, 0);
+        let network = read_u64(data, 8);
+        let sender_offset_key_index = read_u64(data, 24);
+        let value = PrivateKey::from(read_u64(data, 32));
+        let commitment_mask = read_key::<PrivateKey>(data, 40)?;
+        let mut receiver_address = [0u8; TARI_DUAL_ADDRESS_SIZE];
+        receiver_address.copy_from_slice(&data[72..72 + TARI_DUAL_ADDRESS_SIZE]);
+        let mut message_common = [0u8; 32];
+        message_common.copy_from_slice(&data[72 + TARI_DUAL_ADDRESS_SIZE..]);
+
+        let receiver_public_spend_key = get_public_spend_key_bytes_from_tari_dual_address(&receiver_address)
+            .map_err(|_| AppSW::MetadataSignatureFail)
+            .and_then(|bytes| read_key::<PublicKey>(&bytes, 0))?;
+
+        // This is where the physical device shows the amount and receiver for review
+        if !self.approve_transactions.load(Ordering::SeqCst) {
+            return Err(AppSW::UserCancelled);
+        }
+
+        let sender_offset_private_key =
+            self.derive_key(account, sender_offset_key_index, KeyType::OneSidedSenderOffset)?;
+        let sender_offset_public_key = PublicKey::from_secret_key(&sender_offset_private_key);
+
+        let r_a = PrivateKey::random(&mut OsRng);
+        let r_x = PrivateKey::random(&mut OsRng);
+        let ephemeral_private_key = PrivateKey::random(&mut OsRng);
+        let factory = CommitmentFactory::default();
+        let commitment = factory.commit(&commitment_mask, &value);
+        let ephemeral_commitment = factory.commit(&r_x, &r_a);
+        let ephemeral_pubkey = PublicKey::from_secret_key(&ephemeral_private_key);
+
+        let message =
+            metadata_signature_message(network, &receiver_public_spend_key, &commitment_mask, &message_common)?;
+        let challenge = metadata_signature_challenge(
+            network,
+            &sender_offset_public_key,
+            &ephemeral_commitment,
+            &ephemeral_pubkey,
+            &commitment,
+            &message,
+        );
+        let signature = ComAndPubSignature::sign(
+            &value,
+            &commitment_mask,
+            &sender_offset_private_key,
+            &r_a,
+            &r_x,
+            &ephemeral_private_key,
+            &challenge,
+            &factory,
+        )
+        .map_err(|_| AppSW::MetadataSignatureFail)?;
+        Ok(com_and_pub_signature_response(&signature))
+    }
+}
+
+impl LedgerTransport for EmulatedLedgerDevice {
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<APDUAnswer<Vec<u8>>, LedgerDeviceError> {
+        APDUAnswer::from_answer(self.process_apdu(command))
+            .map_err(|e| LedgerDeviceError::NativeTransportExchange(format!("{:?}", e)))
+    }
+}
+
+fn check_length(data: &[u8], expected: usize) -> Result<(), AppSW> {
+    if data.len() == expected {
+        Ok(())
+    } else {
+        Err(AppSW::WrongApduLength)
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn read_key<T: ByteArray>(data: &[u8], offset: usize) -> Result<T, AppSW> {
+    T::from_canonical_bytes(&data[offset..offset + 32]).map_err(|_| AppSW::KeyDeriveFromCanonical)
+}
+
+fn response(parts: &[&[u8]]) -> Vec<u8> {
+    let mut data = vec![RESPONSE_VERSION];
+    for part in parts {
+        data.extend_from_slice(part);
+    }
+    data
+}
+
+fn com_and_pub_signature_response(signature: &ComAndPubSignature) -> Vec<u8> {
+    response(&[
+        signature.ephemeral_commitment().as_bytes(),
+        signature.ephemeral_pubkey().as_bytes(),
+        signature.u_a().as_bytes(),
+        signature.u_x().as_bytes(),
+        signature.u_y().as_bytes(),
+    ])
+}
+
+fn script_key_hash(blinding_factor: &PrivateKey) -> Result<PrivateKey, AppSW> {
+    let hash = DomainSeparatedHasher::<Blake2b<U64>, KeyManagerTransactionsHashDomain>::new_with_label("script key")
+        .chain(blinding_factor.as_bytes())
+        .finalize();
+    PrivateKey::from_uniform_bytes(hash.as_ref()).map_err(|_| AppSW::KeyDeriveFromUniform)
+}
+
+fn alpha_hasher(alpha: PrivateKey, blinding_factor: &PrivateKey) -> Result<PrivateKey, AppSW> {
+    Ok(script_key_hash(blinding_factor)? + alpha)
+}
+
+// The firmware only encodes the network byte in the domain label
+#[allow(clippy::cast_possible_truncation)]
+fn consensus_hasher<D: digest::Digest + Default>(
+    label: &str,
+    network: u64,
+) -> DomainSeparatedBorshHasher<TransactionHashDomain, D> {
+    DomainSeparatedBorshHasher::new_with_label(&format!("{}.n{}", label, network as u8))
+}
+
+fn script_signature_challenge(
+    network: u64,
+    ephemeral_commitment: &Commitment,
+    ephemeral_pubkey: &PublicKey,
+    script_public_key: &PublicKey,
+    commitment: &Commitment,
+    message: &[u8; 32],
+) -> [u8; 64] {
+    let hash = consensus_hasher::<Blake2b<U64>>("script_challenge", network)
+        .chain(ephemeral_commitment)
+        .chain(ephemeral_pubkey)
+        .chain(script_public_key)
+        .chain(commitment)
+        .chain(message)
+        .finalize();
+    let mut challenge = [0u8; 64];
+    challenge.copy_from_slice(hash.as_slice());
+    challenge
+}
+
+/// The metadata signature message for a one-sided payment to the stealth address of `receiver_public_spend_key`
+fn metadata_signature_message(
+    network: u64,
+    receiver_public_spend_key: &PublicKey,
+    commitment_mask: &PrivateKey,
+    message_common: &[u8; 32],
+) -> Result<[u8; 32], AppSW> {
+    let stealth_key = receiver_public_spend_key + &PublicKey::from_secret_key(&script_key_hash(commitment_mask)?);
+    let script = script!(PushPubKey(Box::new(stealth_key))).map_err(|_| AppSW::MetadataSignatureFail)?;
+    Ok(consensus_hasher::<Blake2b<U32>>("metadata_message", network)
+        .chain(&script)
+        .chain(message_common)
+        .finalize()
+        .into())
+}
+
+fn metadata_signature_challenge(
+    network: u64,
+    sender_offset_public_key: &PublicKey,
+    ephemeral_commitment: &Commitment,
+    ephemeral_pubkey: &PublicKey,
+    commitment: &Commitment,
+    message: &[u8; 32],
+) -> [u8; 64] {
+    let hash = consensus_hasher::<Blake2b<U64>>("metadata_signature", network)
+        .chain(ephemeral_pubkey)
+        .chain(ephemeral_commitment)
+        .chain(sender_offset_public_key)
+        .chain(commitment)
+        .chain(message)
+        .finalize();
+    let mut challenge = [0u8; 64];
+    challenge.copy_from_slice(hash.as_slice());
+    challenge
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common::configuration::Network;
+    use tari_common_types::{key_branches::TransactionKeyManagerBranch, tari_address::TariAddress};
+
+    use super::*;
+    use crate::accessor_methods::{
+        ledger_get_app_name,
+        ledger_get_dh_shared_secret,
+        ledger_get_one_sided_metadata_signature,
+        ledger_get_public_key,
+        ledger_get_public_spend_key,
+        ledger_get_raw_schnorr_signature,
+        ledger_get_script_offset,
+        ledger_get_script_schnorr_signature,
+        ledger_get_script_signature,
+        ledger_get_view_key,
+        verify_ledger_application,
+        ScriptSignatureKey,
+    };
+
+    const ACCOUNT: u64 = 7;
+
+    // All tests share one device, since the transport is process-wide
+    static DEVICE: Lazy<Arc<EmulatedLedgerDevice>> = Lazy::new(|| {
+        let device = Arc::new(EmulatedLedgerDevice::new([42u8; 32]));
+        set_ledger_transport(device.clone());
+        device
+    });
+
+    fn device() -> &'static EmulatedLedgerDevice {
+        &DEVICE
+    }
+
+    fn random_message() -> [u8; 32] {
+        let mut message = [0u8; 32];
+        OsRng.fill_bytes(&mut message);
+        message
+    }
+
+    #[test]
+    fn it_passes_application_verification() {
+        device();
+        verify_ledger_application().unwrap();
+        assert_eq!(ledger_get_app_name().unwrap(), EXPECTED_NAME);
+    }
+
+    #[test]
+    fn it_derives_deterministic_keys_per_account() {
+        device();
+        let spend_key = ledger_get_public_spend_key(ACCOUNT).unwrap();
+        assert_eq!(
+            spend_key,
+            ledger_get_public_key(ACCOUNT, STATIC_SPEND_INDEX, TransactionKeyManagerBranch::Spend).unwrap()
+        );
+        assert_ne!(spend_key, ledger_get_public_spend_key(ACCOUNT + 1).unwrap());
+        assert_ne!(
+            PublicKey::from_secret_key(&ledger_get_view_key(ACCOUNT).unwrap()),
+            spend_key
+        );
+
+        // A second device with the same seed derives the same keys
+        let other = EmulatedLedgerDevice::new([42u8; 32]);
+        let answer = other.process_apdu(&APDUCommand {
+            cla: WALLET_CLA,
+            ins: Instruction::GetPublicSpendKey.as_byte(),
+            p1: 0,
+            p2: 0,
+            data: ACCOUNT.to_le_bytes().to_vec(),
+        });
+        assert_eq!(&answer[1..33], spend_key.as_bytes());
+        assert_eq!(&answer[33..], &(AppSW::Ok as u16).to_be_bytes());
+    }
+
+    #[test]
+    fn it_rejects_unsupported_branches_and_bad_lengths() {
+        let data = [ACCOUNT.to_le_bytes(), 1u64.to_le_bytes(), 0xffu64.to_le_bytes()].concat();
+        let answer = device().process_apdu(&APDUCommand {
+            cla: WALLET_CLA,
+            ins: Instruction::GetPublicKey.as_byte(),
+            p1: 0,
+            p2: 0,
+            data,
+        });
+        assert_eq!(answer, (AppSW::BadBranchKey as u16).to_be_bytes().to_vec());
+
+        let answer = device().process_apdu(&APDUCommand {
+            cla: WALLET_CLA,
+            ins: Instruction::GetViewKey.as_byte(),
+            p1: 0,
+            p2: 0,
+            data: vec![0u8; 3],
+        });
+        assert_eq!(answer, (AppSW::WrongApduLength as u16).to_be_bytes().to_vec());
+    }
+
+    #[test]
+    fn it_computes_dh_shared_secrets() {
+        device();
+        let branch = TransactionKeyManagerBranch::OneSidedSenderOffset;
+        let other_key = PrivateKey::random(&mut OsRng);
+        let shared_secret =
+            ledger_get_dh_shared_secret(ACCOUNT, 3, branch, &PublicKey::from_secret_key(&other_key)).unwrap();
+        let device_public_key = ledger_get_public_key(ACCOUNT, 3, branch).unwrap();
+        assert_eq!(
+            shared_secret.as_bytes(),
+            DiffieHellmanSharedSecret::<PublicKey>::new(&other_key, &device_public_key).as_bytes()
+        );
+    }
+
+    #[test]
+    fn it_creates_valid_schnorr_signatures() {
+        device();
+        let branch = TransactionKeyManagerBranch::RandomKey;
+        let public_key = ledger_get_public_key(ACCOUNT, 11, branch).unwrap();
+
+        let nonce = random_message();
+        let signature = ledger_get_script_schnorr_signature(ACCOUNT, 11, branch, &nonce).unwrap();
+        assert!(signature.verify(&public_key, nonce));
+
+        let mut challenge = [0u8; 64];
+        OsRng.fill_bytes(&mut challenge);
+        let nonce_branch = TransactionKeyManagerBranch::MetadataEphemeralNonce;
+        let signature = ledger_get_raw_schnorr_signature(ACCOUNT, 11, branch, 12, nonce_branch, &challenge).unwrap();
+        assert!(signature.verify_raw_uniform(&public_key, &challenge));
+        assert_eq!(
+            signature.get_public_nonce(),
+            &ledger_get_public_key(ACCOUNT, 12, nonce_branch).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_creates_valid_script_signatures() {
+        device();
+        let network = Network::LocalNet;
+        let factory = CommitmentFactory::default();
+        let value = PrivateKey::from(1000u64);
+        let mask = PrivateKey::random(&mut OsRng);
+        let commitment = factory.commit(&mask, &value);
+        let branch_key = PrivateKey::random(&mut OsRng);
+        let spend_key = ledger_get_public_spend_key(ACCOUNT).unwrap();
+
+        let cases = [
+            (
+                ScriptSignatureKey::Managed {
+                    branch: TransactionKeyManagerBranch::Spend,
+                    index: 5,
+                },
+                ledger_get_public_key(ACCOUNT, 5, TransactionKeyManagerBranch::Spend).unwrap(),
+            ),
+            (
+                ScriptSignatureKey::Derived {
+                    branch_key: branch_key.clone(),
+                },
+                &spend_key + &PublicKey::from_secret_key(&script_key_hash(&branch_key).unwrap()),
+            ),
+        ];
+        for (signature_key, script_public_key) in cases {
+            let message = random_message();
+            let signature =
+                ledger_get_script_signature(ACCOUNT, network, 0, &signature_key, &value, &mask, &commitment, message)
+                    .unwrap();
+            let challenge = script_signature_challenge(
+                u64::from(network.as_byte()),
+                signature.ephemeral_commitment(),
+                signature.ephemeral_pubkey(),
+                &script_public_key,
+                &commitment,
+                &message,
+            );
+            assert!(signature.verify_challenge(&commitment, &script_public_key, &challenge, &factory, &mut OsRng));
+        }
+    }
+
+    #[test]
+    fn it_computes_script_offsets() {
+        device();
+        let spend_key = ledger_get_public_spend_key(ACCOUNT).unwrap();
+        let partial_script_offset = PrivateKey::random(&mut OsRng);
+        let derived_script_key = PrivateKey::random(&mut OsRng);
+        let derived_sender_offset = PrivateKey::random(&mut OsRng);
+        let script_key_index = (TransactionKeyManagerBranch::Spend, 21);
+        let sender_offset_index = (TransactionKeyManagerBranch::OneSidedSenderOffset, 22);
+
+        let script_offset = ledger_get_script_offset(
+            ACCOUNT,
+            &partial_script_offset,
+            &[derived_script_key.clone()],
+            &[script_key_index],
+            &[derived_sender_offset.clone()],
+            &[sender_offset_index],
+        )
+        .unwrap();
+
+        let derived_public = |k: &PrivateKey| &spend_key + &PublicKey::from_secret_key(&script_key_hash(k).unwrap());
+        let expected = PublicKey::from_secret_key(&partial_script_offset) +
+            &ledger_get_public_key(ACCOUNT, script_key_index.1, script_key_index.0).unwrap() +
+            &derived_public(&derived_script_key) -
+            &ledger_get_public_key(ACCOUNT, sender_offset_index.1, sender_offset_index.0).unwrap() -
+            &derived_public(&derived_sender_offset);
+        assert_eq!(PublicKey::from_secret_key(&script_offset), expected);
+
+        // A single device key would leak that key through the offset
+        let err =
+            ledger_get_script_offset(ACCOUNT, &partial_script_offset, &[], &[script_key_index], &[], &[]).unwrap_err();
+        assert!(err.to_string().contains("ScriptOffsetNotUnique"));
+    }
+
+    #[test]
+    fn it_creates_one_sided_metadata_signatures_when_approved() {
+        let device = device();
+        let network = Network::LocalNet;
+        let receiver_spend_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let receiver_address = TariAddress::new_dual_address_with_default_features(
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            receiver_spend_key.clone(),
+            network,
+        );
+        let commitment_mask = PrivateKey::random(&mut OsRng);
+        let message_common = random_message();
+        let sign = || {
+            ledger_get_one_sided_metadata_signature(
+                ACCOUNT,
+                network,
+                0,
+                5000,
+                31,
+                &commitment_mask,
+                &receiver_address,
+                &message_common,
+            )
+        };
+
+        device.set_approve_transactions(false);
+        let result = sign();
+        device.set_approve_transactions(true);
+        assert_eq!(result.unwrap_err(), LedgerDeviceError::UserCancelled);
+
+        let signature = sign().unwrap();
+        let factory = CommitmentFactory::default();
+        let commitment = factory.commit(&commitment_mask, &PrivateKey::from(5000u64));
+        let sender_offset_public_key =
+            ledger_get_public_key(ACCOUNT, 31, TransactionKeyManagerBranch::OneSidedSenderOffset).unwrap();
+        let network = u64::from(network.as_byte());
+        let message =
+            metadata_signature_message(network, &receiver_spend_key, &commitment_mask, &message_common).unwrap();
+        let challenge = metadata_signature_challenge(
+            network,
+            &sender_offset_public_key,
+            signature.ephemeral_commitment(),
+            signature.ephemeral_pubkey(),
+            &commitment,
+            &message,
+        );
+        assert!(signature.verify_challenge(&commitment, &sender_offset_public_key, &challenge, &factory, &mut OsRng));
+    }
+}
@@ -20,7 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{ops::Deref, sync::Mutex};
+use std::{
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
 
 use ledger_transport::{APDUAnswer, APDUCommand};
 use ledger_transport_hid::{hidapi::HidApi, TransportNativeHID};
@@ -32,7 +35,7 @@ use crate::error::LedgerDeviceError;
 
 pub const EXPECTED_NAME: &str = "minotari_ledger_wallet";
 pub const MIN_LEDGER_APP_VERSION: &str = "1.4.0";
-const WALLET_CLA: u8 = 0x80;
+pub(crate) const WALLET_CLA: u8 = 0x80;
 
 struct HidManager {
     inner: Option<HidApi>,
@@ -63,6 +66,42 @@ impl HidManager {
 static HID_MANAGER: Lazy<Mutex<HidManager>> =
     Lazy::new(|| Mutex::new(HidManager::new().expect("Failed to initialize HidManager")));
 
+/// A device that exchanges APDUs with the 'Minotari Wallet' application. When one is registered with
+/// `set_ledger_transport` (requires the `emulator` feature), all commands are routed to it instead of a connected HID
+/// device.
+pub trait LedgerTransport: Send + Sync {
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<APDUAnswer<Vec<u8>>, LedgerDeviceError>;
+}
+
+#[cfg(any(test, feature = "emulator"))]
+static TRANSPORT_OVERRIDE: Lazy<std::sync::RwLock<Option<Arc<dyn LedgerTransport>>>> =
+    Lazy::new(|| std::sync::RwLock::new(None));
+
+/// Route all ledger commands in this process to `transport`, e.g. an
+/// [`EmulatedLedgerDevice`](crate::emulator::EmulatedLedgerDevice).
+#[cfg(any(test, feature = "emulator"))]
+pub fn set_ledger_transport(transport: Arc<dyn LedgerTransport>) {
+    let mut lock = TRANSPORT_OVERRIDE.write().unwrap_or_else(|e| e.into_inner());
+    *lock = Some(transport);
+}
+
+/// Remove a transport set with [`set_ledger_transport`], reverting to the HID transport
+#[cfg(any(test, feature = "emulator"))]
+pub fn clear_ledger_transport() {
+    let mut lock = TRANSPORT_OVERRIDE.write().unwrap_or_else(|e| e.into_inner());
+    *lock = None;
+}
+
+#[cfg(any(test, feature = "emulator"))]
+fn ledger_transport_override() -> Option<Arc<dyn LedgerTransport>> {
+    TRANSPORT_OVERRIDE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(not(any(test, feature = "emulator")))]
+fn ledger_transport_override() -> Option<Arc<dyn LedgerTransport>> {
+    None
+}
+
 pub fn get_transport() -> Result<TransportNativeHID, LedgerDeviceError> {
     let mut manager = HID_MANAGER
         .lock()
@@ -89,6 +128,9 @@ impl<D: Deref<Target = [u8]>> Command<D> {
     }
 
     pub fn execute(&self) -> Result<APDUAnswer<Vec<u8>>, LedgerDeviceError> {
+        if let Some(transport) = ledger_transport_override() {
+            return self.execute_with_device(transport.as_ref());
+        }
         get_transport()?
             .exchange(&self.inner)
             .map_err(|e| LedgerDeviceError::NativeTransportExchange(e.to_string()))
@@ -103,6 +145,16 @@ impl<D: Deref<Target = [u8]>> Command<D> {
             .map_err(|e| LedgerDeviceError::NativeTransportExchange(e.to_string()))
     }
 
+    pub fn execute_with_device(&self, device: &dyn LedgerTransport) -> Result<APDUAnswer<Vec<u8>>, LedgerDeviceError> {
+        device.exchange(&APDUCommand {
+            cla: self.inner.cla,
+            ins: self.inner.ins,
+            p1: self.inner.p1,
+            p2: self.inner.p2,
+            data: self.inner.data.to_vec(),
+        })
+    }
+
     pub fn build_command(account: u64, instruction: Instruction, data: Vec<u8>) -> Command<Vec<u8>> {
         let mut base_data = account.to_le_bytes().to_vec();
         base_data.extend_from_slice(&data);
@@ -21,6 +21,8 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 pub mod accessor_methods;
+#[cfg(any(test, feature = "emulator"))]
+pub mod emulator;
 pub mod error;
 pub mod ledger_wallet;
 
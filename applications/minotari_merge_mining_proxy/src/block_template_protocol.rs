@@ -425,7 +425,7 @@ pub fn calculate_aux_chain_merkle_root(hashes: AuxChainHashes) -> Result<(monero
 
 /// Build the [FinalBlockTemplateData] from [template](NewBlockTemplateData) and with
 /// [tari](grpc::GetNewBlockResult) and [monero data](MoneroMiningData).
-fn add_monero_data(
+pub fn add_monero_data(
     tari_block_result: grpc::GetNewBlockResult,
     monero_mining_data: MoneroMiningData,
     miner_data: MinerData,
@@ -20,12 +20,15 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use minotari_wallet_grpc_client::GrpcAuthentication;
 use serde::{Deserialize, Serialize};
 use tari_common::{
-    configuration::{Network, StringList},
+    configuration::{serializers, Network, StringList},
     SubConfigPath,
 };
 use tari_common_types::tari_address::TariAddress;
@@ -96,6 +99,20 @@ pub struct MergeMiningProxyConfig {
     pub range_proof_type: RangeProofType,
     /// Use p2pool to submit and get block templates
     pub p2pool_enabled: bool,
+    /// Serve merge mined jobs to RandomX miners that connect with stratum, e.g. XMRig in pool mode
+    pub stratum_enabled: bool,
+    /// Address of the stratum endpoint
+    pub stratum_listener_address: Multiaddr,
+    /// The Monero wallet address that the Monero block reward of stratum jobs is paid to - must be assigned if stratum
+    /// is enabled
+    pub stratum_monero_wallet_address: String,
+    /// The interval at which monerod and the base node are polled for a new tip, a new job is sent to stratum miners
+    /// when either tip changes
+    #[serde(with = "serializers::seconds")]
+    pub stratum_tip_poll_interval: Duration,
+    /// The maximum age of a stratum job before it is rebuilt to include new transactions
+    #[serde(with = "serializers::seconds")]
+    pub stratum_job_refresh_interval: Duration,
 }
 
 impl Default for MergeMiningProxyConfig {
@@ -157,6 +174,11 @@ impl Default for MergeMiningProxyConfig {
             wallet_payment_address: TariAddress::default().to_base58(),
             range_proof_type: RangeProofType::RevealedValue,
             p2pool_enabled: false,
+            stratum_enabled: false,
+            stratum_listener_address: "/ip4/127.0.0.1/tcp/18084".parse().unwrap(),
+            stratum_monero_wallet_address: String::new(),
+            stratum_tip_poll_interval: Duration::from_secs(2),
+            stratum_job_refresh_interval: Duration::from_secs(30),
         }
     }
 }
@@ -240,5 +262,6 @@ mod test {
         assert_eq!(config.base_node_grpc_address, None);
         assert!(!config.monerod_use_auth);
         assert!(config.submit_to_origin);
+        assert!(!config.stratum_enabled);
     }
 }
@@ -31,7 +31,7 @@ use minotari_wallet_grpc_client::BasicAuthError;
 use tari_common::{ConfigError, ConfigurationError};
 use tari_core::{
     consensus::ConsensusBuilderError,
-    proof_of_work::{monero_rx::MergeMineError, DifficultyError},
+    proof_of_work::{monero_rx::MergeMineError, randomx_factory::RandomXVMFactoryError, DifficultyError},
     transactions::{key_manager::CoreKeyManagerError, CoinbaseBuildError},
 };
 use tari_key_manager::key_manager_service::KeyManagerServiceError;
@@ -122,6 +122,10 @@ pub enum MmProxyError {
     MaxSizeBytesError(#[from] MaxSizeBytesError),
     #[error("Max sized vector error: {0}")]
     MaxSizeVecError(#[from] MaxSizeVecError),
+    #[error("RandomX error: {0}")]
+    RandomXError(#[from] RandomXVMFactoryError),
+    #[error("Invalid stratum configuration: {0}")]
+    InvalidStratumConfig(String),
 }
 
 impl From<tonic::Status> for MmProxyError {
@@ -34,6 +34,7 @@ mod proxy;
 mod run_merge_miner;
 use run_merge_miner::start_merge_miner;
 mod monero_fail;
+mod stratum;
 
 pub async fn merge_miner(cli: Cli) -> Result<(), anyhow::Error> {
     start_merge_miner(cli).await
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::Infallible, str::FromStr};
+use std::{convert::Infallible, str::FromStr, sync::Arc};
 
 use futures::future;
 use hyper::{service::make_service_fn, Server};
@@ -38,7 +38,7 @@ use minotari_wallet_grpc_client::ClientAuthenticationInterceptor;
 use tari_common::{configuration::StringList, load_configuration, DefaultConfigLoader};
 use tari_comms::utils::multiaddr::multiaddr_to_socketaddr;
 use tari_core::proof_of_work::randomx_factory::RandomXFactory;
-use tokio::time::Duration;
+use tokio::{task, time::Duration};
 use tonic::transport::{Certificate, ClientTlsConfig, Endpoint};
 
 use crate::{
@@ -47,6 +47,7 @@ use crate::{
     error::MmProxyError,
     monero_fail::{get_monerod_info, order_and_select_monerod_info, MonerodEntry},
     proxy::{MergeMiningProxyService, MONEROD_CONNECTION_TIMEOUT, NUMBER_OF_MONEROD_SERVERS},
+    stratum::StratumServer,
     Cli,
 };
 
@@ -150,6 +151,22 @@ pub async fn start_merge_miner(cli: Cli) -> Result<(), anyhow::Error> {
 
     let listen_addr = multiaddr_to_socketaddr(&config.listener_address)?;
     let randomx_factory = RandomXFactory::new(config.max_randomx_vms);
+    if config.stratum_enabled {
+        let stratum_server = StratumServer::new(
+            Arc::new(config.clone()),
+            client.clone(),
+            base_node_client.clone(),
+            p2pool_client.clone(),
+            randomx_factory.clone(),
+            wallet_payment_address.clone(),
+        )?;
+        task::spawn(async move {
+            if let Err(err) = stratum_server.run().await {
+                error!(target: LOG_TARGET, "Fatal: Stratum server stopped: {}", err);
+                println!("Fatal: Stratum server stopped: {}", err);
+            }
+        });
+    }
     let randomx_service = MergeMiningProxyService::new(
         config,
         client,
@@ -0,0 +1,643 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, convert::TryInto, mem, sync::Arc, time::Instant};
+
+use borsh::BorshSerialize;
+use log::*;
+use minotari_app_grpc::tari_rpc::SubmitBlockRequest;
+use minotari_app_utilities::{
+    parse_miner_input::{BaseNodeGrpcClient, ShaP2PoolGrpcClient},
+    stratum::{JobQueue, SubmittedNonces},
+};
+use minotari_node_grpc_client::grpc;
+use tari_common_types::tari_address::TariAddress;
+use tari_core::{
+    consensus::ConsensusManager,
+    proof_of_work::{
+        monero_rx,
+        monero_rx::{FixedByteArray, MergeMineError},
+        randomx_factory::RandomXFactory,
+        Difficulty,
+    },
+};
+use tari_utilities::hex::Hex;
+use tokio::{sync::watch, task, time, time::MissedTickBehavior};
+
+use crate::{
+    block_template_data::BlockTemplateRepository,
+    block_template_protocol::{BlockTemplateProtocol, FinalBlockTemplateData, MoneroMiningData},
+    config::MergeMiningProxyConfig,
+    error::MmProxyError,
+    stratum::{monerod::MonerodClient, types::JobParams},
+};
+
+const LOG_TARGET: &str = "minotari_mm_proxy::stratum::job";
+
+/// The name of the Monero RandomX variant in the stratum protocol
+const RANDOMX_ALGO: &str = "rx/0";
+
+/// The size of the extra nonce of a worker, for which space is reserved in the coinbase extra of the Monero template
+const EXTRA_NONCE_SIZE: usize = mem::size_of::<u64>();
+
+/// The tips of both chains that a job builds on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainTips {
+    monero: String,
+    tari: Vec<u8>,
+}
+
+/// A merge mined block template, mined by all workers. Each worker mines its own blockhashing blob, with the extra
+/// nonce of the worker in the space reserved in the coinbase. Shares must meet the lower of the Monero and Minotari
+/// difficulties.
+#[derive(Debug)]
+pub struct StratumJob {
+    pub id: u64,
+    pub template: FinalBlockTemplateData,
+    /// The offset of the space reserved for the extra nonce in the Monero block of the template
+    extra_nonce_offset: usize,
+    pub monero_height: u64,
+    tips: ChainTips,
+    created_at: Instant,
+    /// The extra nonces and nonces of the valid shares submitted for this job
+    submitted_nonces: SubmittedNonces<(u64, u32)>,
+}
+
+/// A share that met the difficulty of the job
+#[derive(Debug)]
+pub struct ValidShare {
+    /// The Monero block of the job with the extra nonce and nonce of the share
+    pub block: monero::Block,
+    pub achieved_difficulty: u64,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ShareRejection {
+    #[error("Invalid nonce")]
+    InvalidNonce,
+    #[error("Stale job")]
+    StaleJob,
+    #[error("Duplicate share")]
+    DuplicateShare,
+    #[error("Share hash does not match the job")]
+    HashMismatch,
+    #[error("Low difficulty share: achieved {achieved}, required {required}")]
+    LowDifficulty { achieved: u64, required: u64 },
+    #[error("Share could not be validated: {0}")]
+    ValidationFailed(String),
+}
+
+impl StratumJob {
+    fn new(
+        id: u64,
+        template: FinalBlockTemplateData,
+        extra_nonce_offset: usize,
+        monero_height: u64,
+        tips: ChainTips,
+    ) -> Self {
+        Self {
+            id,
+            template,
+            extra_nonce_offset,
+            monero_height,
+            tips,
+            created_at: Instant::now(),
+            submitted_nonces: SubmittedNonces::default(),
+        }
+    }
+
+    /// The job as sent to the worker with the given extra nonce
+    pub fn to_params(&self, extra_nonce: u64) -> Result<JobParams, MergeMineError> {
+        let block = block_with_extra_nonce(&self.template.blocktemplate_blob, self.extra_nonce_offset, extra_nonce)?;
+        Ok(JobParams {
+            job_id: self.id.to_string(),
+            blob: monero_rx::create_blockhashing_blob_from_block(&block)?,
+            target: difficulty_to_target(self.template.target_difficulty.as_u64()),
+            height: self.monero_height,
+            seed_hash: self.template.template.monero_seed.to_hex(),
+            algo: RANDOMX_ALGO,
+        })
+    }
+
+    /// Validates a share by hashing the blockhashing blob of the worker with the submitted nonce. The submitted result
+    /// must be that hash. This is CPU intensive, and the RandomX VM of a new seed takes a while to create.
+    pub fn validate_share(
+        &self,
+        extra_nonce: u64,
+        nonce: &str,
+        result: &str,
+        randomx_factory: &RandomXFactory,
+    ) -> Result<ValidShare, ShareRejection> {
+        let nonce = parse_nonce(nonce).ok_or(ShareRejection::InvalidNonce)?;
+        let (block, blockhashing_blob) = block_with_nonce(
+            &self.template.blocktemplate_blob,
+            self.extra_nonce_offset,
+            extra_nonce,
+            nonce,
+        )
+        .map_err(|e| ShareRejection::ValidationFailed(e.to_string()))?;
+        let hash = randomx_factory
+            .create(self.template.template.monero_seed.as_slice(), None, None)
+            .and_then(|vm| vm.calculate_hash(&blockhashing_blob))
+            .map_err(|e| ShareRejection::ValidationFailed(e.to_string()))?;
+        if !result.eq_ignore_ascii_case(&hash.to_hex()) {
+            return Err(ShareRejection::HashMismatch);
+        }
+
+        let achieved_difficulty = Difficulty::little_endian_difficulty(&hash)
+            .map(|d| d.as_u64())
+            .unwrap_or_default();
+        let required = self.template.target_difficulty.as_u64();
+        if achieved_difficulty < required {
+            return Err(ShareRejection::LowDifficulty {
+                achieved: achieved_difficulty,
+                required,
+            });
+        }
+
+        if !self.submitted_nonces.insert((extra_nonce, nonce)) {
+            return Err(ShareRejection::DuplicateShare);
+        }
+
+        Ok(ValidShare {
+            block,
+            achieved_difficulty,
+        })
+    }
+}
+
+/// Builds merge mined jobs from the block templates of monerod and the base node, and submits the blocks solved by
+/// workers to both chains
+pub struct JobManager {
+    config: Arc<MergeMiningProxyConfig>,
+    monerod: MonerodClient,
+    base_node_client: BaseNodeGrpcClient,
+    p2pool_client: Option<ShaP2PoolGrpcClient>,
+    block_templates: BlockTemplateRepository,
+    randomx_factory: RandomXFactory,
+    consensus_manager: ConsensusManager,
+    wallet_payment_address: TariAddress,
+    jobs: JobQueue<StratumJob>,
+}
+
+impl JobManager {
+    pub fn new(
+        config: Arc<MergeMiningProxyConfig>,
+        http_client: reqwest::Client,
+        base_node_client: BaseNodeGrpcClient,
+        p2pool_client: Option<ShaP2PoolGrpcClient>,
+        randomx_factory: RandomXFactory,
+        wallet_payment_address: TariAddress,
+    ) -> Result<Self, MmProxyError> {
+        if config.stratum_monero_wallet_address.trim().is_empty() {
+            return Err(MmProxyError::InvalidStratumConfig(
+                "`stratum_monero_wallet_address` must be assigned".to_string(),
+            ));
+        }
+        Ok(Self {
+            monerod: MonerodClient::new(http_client, &config)?,
+            consensus_manager: ConsensusManager::builder(config.network).build()?,
+            config,
+            base_node_client,
+            p2pool_client,
+            block_templates: BlockTemplateRepository::new(),
+            randomx_factory,
+            wallet_payment_address,
+            jobs: JobQueue::new(),
+        })
+    }
+
+    /// Subscribes to new jobs. The receiver holds `None` until the first job has been built.
+    pub fn subscribe(&self) -> watch::Receiver<Option<Arc<StratumJob>>> {
+        self.jobs.subscribe()
+    }
+
+    pub fn current_job(&self) -> Option<Arc<StratumJob>> {
+        self.jobs.current()
+    }
+
+    /// Returns the job with the given id if shares are still accepted for it
+    pub fn find_job(&self, job_id: &str) -> Option<Arc<StratumJob>> {
+        let job_id = job_id.parse::<u64>().ok()?;
+        self.jobs.find(|job| job.id == job_id)
+    }
+
+    /// Validates a share on a blocking thread, see [StratumJob::validate_share]
+    pub async fn validate_share(
+        &self,
+        job: Arc<StratumJob>,
+        extra_nonce: u64,
+        nonce: String,
+        result: String,
+    ) -> Result<ValidShare, ShareRejection> {
+        let randomx_factory = self.randomx_factory.clone();
+        task::spawn_blocking(move || job.validate_share(extra_nonce, &nonce, &result, &randomx_factory))
+            .await
+            .map_err(|e| ShareRejection::ValidationFailed(e.to_string()))?
+    }
+
+    /// Submits a share to each chain whose difficulty it meets
+    pub async fn submit_solution(&self, job: &StratumJob, share: ValidShare) {
+        let template = &job.template.template;
+        if share.achieved_difficulty >= template.monero_difficulty {
+            if self.config.submit_to_origin {
+                match self.submit_monero_block(&share.block).await {
+                    Ok(()) => info!(target: LOG_TARGET, "Submitted Monero block #{} to monerod", job.monero_height),
+                    Err(e) => warn!(
+                        target: LOG_TARGET,
+                        "Monero block #{} was not accepted: {}", job.monero_height, e
+                    ),
+                }
+            } else {
+                debug!(
+                    target: LOG_TARGET,
+                    "Not submitting Monero block #{}, `submit_to_origin` is disabled", job.monero_height
+                );
+            }
+        }
+
+        // Like the proxy, submit every solution to the Minotari chain if the difficulty is not checked before
+        // submission
+        if share.achieved_difficulty >= template.tari_difficulty || !self.config.check_tari_difficulty_before_submit {
+            let height = template
+                .tari_block
+                .header
+                .as_ref()
+                .map(|h| h.height)
+                .unwrap_or_default();
+            match self.submit_tari_block(job, share.block).await {
+                Ok(hash) => info!(
+                    target: LOG_TARGET,
+                    "Submitted Minotari block #{} ({}) with achieved difficulty {}",
+                    height,
+                    hash,
+                    share.achieved_difficulty
+                ),
+                Err(e) => warn!(target: LOG_TARGET, "Minotari block #{} was not accepted: {}", height, e),
+            }
+        }
+    }
+
+    async fn submit_monero_block(&self, block: &monero::Block) -> Result<(), MmProxyError> {
+        let blob = monero_rx::serialize_monero_block_to_hex(block)?;
+        self.monerod.submit_block(blob).await
+    }
+
+    /// Adds the Monero proof of work to the Minotari block of the job and submits it, returning the block hash
+    async fn submit_tari_block(&self, job: &StratumJob, block: monero::Block) -> Result<String, MmProxyError> {
+        let template = &job.template.template;
+        let monero_data = monero_rx::construct_monero_data(
+            block,
+            template.monero_seed.clone(),
+            job.template.aux_chain_hashes.clone(),
+            template.tari_merge_mining_hash,
+        )?;
+        let mut tari_block = template.tari_block.clone();
+        let pow = tari_block
+            .header
+            .as_mut()
+            .and_then(|header| header.pow.as_mut())
+            .ok_or(MmProxyError::UnexpectedMissingData("tari_block.header.pow".to_string()))?;
+        BorshSerialize::serialize(&monero_data, &mut pow.pow_data)
+            .map_err(|err| MmProxyError::ConversionError(err.to_string()))?;
+
+        let response = match self.p2pool_client.clone() {
+            Some(mut client) => {
+                client
+                    .submit_block(SubmitBlockRequest {
+                        block: Some(tari_block),
+                        wallet_payment_address: self.wallet_payment_address.to_hex(),
+                    })
+                    .await?
+            },
+            None => self.base_node_client.clone().submit_block(tari_block).await?,
+        };
+        Ok(response.into_inner().block_hash.to_hex())
+    }
+
+    /// Builds a new job on the given tips and sends it to all workers
+    async fn refresh(&self, tips: ChainTips) -> Result<Arc<StratumJob>, MmProxyError> {
+        let monero_template = self
+            .monerod
+            .get_block_template(&self.config.stratum_monero_wallet_address, EXTRA_NONCE_SIZE)
+            .await?;
+        let seed_hash = FixedByteArray::from_hex(&monero_template.seed_hash)
+            .map_err(|err| MmProxyError::InvalidMonerodResponse(format!("seed hash hex is invalid: {}", err)))?;
+        let mut base_node_client = self.base_node_client.clone();
+        let template = BlockTemplateProtocol::new(
+            &mut base_node_client,
+            self.p2pool_client.clone(),
+            self.config.clone(),
+            self.consensus_manager.clone(),
+            self.wallet_payment_address.clone(),
+        )
+        .await?
+        .get_next_block_template(
+            MoneroMiningData {
+                seed_hash,
+                blocktemplate_blob: monero_template.blocktemplate_blob.clone(),
+                difficulty: monero_template.difficulty,
+            },
+            &self.block_templates,
+        )
+        .await?;
+        // The job holds its own copy of the template. A template left in the repository is reused, with the Monero
+        // block it was built on, for as long as the Minotari tip does not change.
+        self.block_templates
+            .remove_final_block_template(template.aux_chain_mr.to_vec())
+            .await;
+        self.block_templates.remove_outdated().await;
+
+        let extra_nonce_offset = extra_nonce_offset(
+            &monero_template.blocktemplate_blob,
+            monero_template.reserved_offset,
+            &template.blocktemplate_blob,
+        )?;
+        Ok(self.publish_job(template, extra_nonce_offset, monero_template.height, tips))
+    }
+
+    /// Makes a job of the template on the given tips the current job
+    pub fn publish_job(
+        &self,
+        template: FinalBlockTemplateData,
+        extra_nonce_offset: usize,
+        monero_height: u64,
+        tips: ChainTips,
+    ) -> Arc<StratumJob> {
+        let job = Arc::new(StratumJob::new(
+            self.jobs.next_job_id(),
+            template,
+            extra_nonce_offset,
+            monero_height,
+            tips,
+        ));
+        // Jobs that do not build on the same tips can no longer produce a block on both chains
+        self.jobs.publish(job.clone(), |j| j.tips == job.tips);
+        job
+    }
+
+    async fn get_tips(&self) -> Result<ChainTips, MmProxyError> {
+        let monero_tip = self.monerod.get_tip().await?;
+        let grpc::TipInfoResponse {
+            metadata,
+            initial_sync_achieved,
+            ..
+        } = self
+            .base_node_client
+            .clone()
+            .get_tip_info(grpc::Empty {})
+            .await?
+            .into_inner();
+        if !initial_sync_achieved && self.config.wait_for_initial_sync_at_startup {
+            return Err(MmProxyError::MissingDataError(format!(
+                "Initial base node sync not achieved, current height at #{}",
+                metadata.as_ref().map(|m| m.best_block_height).unwrap_or_default()
+            )));
+        }
+        Ok(ChainTips {
+            monero: monero_tip.hash,
+            tari: metadata.map(|m| m.best_block_hash).unwrap_or_default(),
+        })
+    }
+
+    /// Refreshes the job whenever the tip of either chain changes, and when the job is older than the job refresh
+    /// interval so that new transactions are mined.
+    pub async fn run(self: Arc<Self>) {
+        let mut poll = time::interval(self.config.stratum_tip_poll_interval);
+        poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            poll.tick().await;
+            let tips = match self.get_tips().await {
+                Ok(tips) => tips,
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Could not get the chain tips for the stratum job: {}", e);
+                    continue;
+                },
+            };
+            let is_current = self.current_job().is_some_and(|job| {
+                job.tips == tips && job.created_at.elapsed() < self.config.stratum_job_refresh_interval
+            });
+            if is_current {
+                continue;
+            }
+
+            match self.refresh(tips).await {
+                Ok(job) => debug!(
+                    target: LOG_TARGET,
+                    "New stratum job {} for Monero height {} with target difficulty {}",
+                    job.id,
+                    job.monero_height,
+                    job.template.target_difficulty
+                ),
+                Err(e) => warn!(target: LOG_TARGET, "Could not refresh the stratum job: {}", e),
+            }
+        }
+    }
+}
+
+/// The little endian 64-bit target of a difficulty, hex encoded as expected by the miner
+fn difficulty_to_target(difficulty: u64) -> String {
+    hex::encode((u64::MAX / cmp::max(difficulty, 1)).to_le_bytes())
+}
+
+/// Parses the hex encoded, little endian 32-bit nonce of a share
+fn parse_nonce(nonce: &str) -> Option<u32> {
+    let bytes = hex::decode(nonce).ok()?;
+    let bytes: [u8; 4] = bytes.try_into().ok()?;
+    Some(u32::from_le_bytes(bytes))
+}
+
+/// The offset of the space that monerod reserved in the coinbase extra of its template blob, in the merge mined
+/// template blob. The merge mining tag is inserted in front of the reserved space, so the distance from the reserved
+/// space to the end of the block does not change.
+fn extra_nonce_offset(
+    monerod_blob: &str,
+    reserved_offset: usize,
+    blocktemplate_blob: &str,
+) -> Result<usize, MmProxyError> {
+    (monerod_blob.len() / 2)
+        .checked_sub(reserved_offset.saturating_add(EXTRA_NONCE_SIZE))
+        .and_then(|distance_to_end| (blocktemplate_blob.len() / 2).checked_sub(distance_to_end + EXTRA_NONCE_SIZE))
+        .ok_or_else(|| {
+            MmProxyError::InvalidMonerodResponse(format!(
+                "reserved offset {} is outside of the block template",
+                reserved_offset
+            ))
+        })
+}
+
+/// Writes the extra nonce of a worker into the reserved space in the coinbase extra of the Monero block in the
+/// template blob, so that every worker mines a distinct blockhashing blob
+fn block_with_extra_nonce(
+    blocktemplate_blob: &str,
+    extra_nonce_offset: usize,
+    extra_nonce: u64,
+) -> Result<monero::Block, MergeMineError> {
+    let reserved = extra_nonce_offset * 2..(extra_nonce_offset + EXTRA_NONCE_SIZE) * 2;
+    if blocktemplate_blob.get(reserved.clone()).is_none() {
+        return Err(MergeMineError::DeserializeError(
+            "Extra nonce offset is outside of the block template".to_string(),
+        ));
+    }
+    let mut blocktemplate_blob = blocktemplate_blob.to_string();
+    blocktemplate_blob.replace_range(reserved, &hex::encode(extra_nonce.to_le_bytes()));
+    monero_rx::deserialize_monero_block_from_hex(blocktemplate_blob)
+}
+
+/// Sets the extra nonce and nonce of the Monero block in the template blob, returning the block and its blockhashing
+/// blob
+fn block_with_nonce(
+    blocktemplate_blob: &str,
+    extra_nonce_offset: usize,
+    extra_nonce: u64,
+    nonce: u32,
+) -> Result<(monero::Block, Vec<u8>), MergeMineError> {
+    let mut block = block_with_extra_nonce(blocktemplate_blob, extra_nonce_offset, extra_nonce)?;
+    block.header.nonce = nonce;
+    let blockhashing_blob = monero_rx::create_blockhashing_blob_from_block(&block)?;
+    let blockhashing_blob = hex::decode(blockhashing_blob)
+        .map_err(|e| MergeMineError::DeserializeError(format!("Invalid blockhashing blob: {}", e)))?;
+    Ok((block, blockhashing_blob))
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use minotari_app_grpc::tari_rpc::MinerData;
+    use monero::blockdata::transaction::{ExtraField, SubField};
+
+    use super::*;
+    use crate::block_template_protocol::add_monero_data;
+
+    pub const BLOCKTEMPLATE_BLOB: &str = "0c0c8cd6a0fa057fe21d764e7abf004e975396a2160773b93712bf6118c3b4959ddd8ee0f76aad0000000002e1ea2701ffa5ea2701d5a299e2abb002028eb3066ced1b2cc82ea046f3716a48e9ae37144057d5fb48a97f941225a1957b2b0106225b7ec0a6544d8da39abe68d8bd82619b4a7c5bdae89c3783b256a8fa47820208f63aa86d2e857f070000";
+    /// The offset of the nonce in the blockhashing blobs of [BLOCKTEMPLATE_BLOB]
+    pub const NONCE_OFFSET: usize = 39;
+    /// The offset of the 8 byte extra nonce in the coinbase extra of [BLOCKTEMPLATE_BLOB]
+    const RESERVED_OFFSET: usize = 129;
+    const SEED_HASH: &str = "7fe21d764e7abf004e975396a2160773b93712bf6118c3b4959ddd8ee0f76aad";
+
+    /// A merge mined template of [BLOCKTEMPLATE_BLOB] and an empty Minotari block, with a difficulty of 1 on both
+    /// chains
+    pub fn template() -> FinalBlockTemplateData {
+        let tari_block = grpc::GetNewBlockResult {
+            block: Some(grpc::Block {
+                header: Some(grpc::BlockHeader {
+                    height: 1,
+                    pow: Some(grpc::ProofOfWork::default()),
+                    ..Default::default()
+                }),
+                body: None,
+            }),
+            merge_mining_hash: vec![1; 32],
+            ..Default::default()
+        };
+        let monero_mining_data = MoneroMiningData {
+            seed_hash: FixedByteArray::from_hex(SEED_HASH).unwrap(),
+            blocktemplate_blob: BLOCKTEMPLATE_BLOB.to_string(),
+            difficulty: 1,
+        };
+        let miner_data = MinerData {
+            target_difficulty: 1,
+            ..Default::default()
+        };
+        add_monero_data(tari_block, monero_mining_data, miner_data).unwrap()
+    }
+
+    /// The offset of the extra nonce in the Monero block of a [template]
+    pub fn extra_nonce_offset_of(template: &FinalBlockTemplateData) -> usize {
+        extra_nonce_offset(BLOCKTEMPLATE_BLOB, RESERVED_OFFSET, &template.blocktemplate_blob).unwrap()
+    }
+
+    #[test]
+    fn it_sets_the_nonce() {
+        let (block, blob) = block_with_nonce(BLOCKTEMPLATE_BLOB, RESERVED_OFFSET, 1, 0x0403_0201).unwrap();
+        assert_eq!(block.header.nonce, 0x0403_0201);
+        assert_eq!(&blob[NONCE_OFFSET..NONCE_OFFSET + 4], &[1, 2, 3, 4]);
+
+        let (_, other_blob) = block_with_nonce(BLOCKTEMPLATE_BLOB, RESERVED_OFFSET, 1, 0).unwrap();
+        assert_eq!(blob.len(), other_blob.len());
+        assert_eq!(blob[..NONCE_OFFSET], other_blob[..NONCE_OFFSET]);
+        assert_eq!(blob[NONCE_OFFSET + 4..], other_blob[NONCE_OFFSET + 4..]);
+    }
+
+    #[test]
+    fn it_gives_each_worker_a_distinct_blob() {
+        let template = template();
+        let aux_chain_mr = monero::Hash::from_slice(&template.aux_chain_mr.to_vec());
+        let offset = extra_nonce_offset_of(&template);
+        let (block, blob) = block_with_nonce(&template.blocktemplate_blob, offset, 1, 0).unwrap();
+        let (other_block, other_blob) = block_with_nonce(&template.blocktemplate_blob, offset, 2, 0).unwrap();
+        assert_ne!(blob, other_blob);
+        assert_eq!(blob.len(), other_blob.len());
+
+        for (block, extra_nonce) in [(block, 1u64), (other_block, 2)] {
+            // The extra nonce is written into the reserved nonce field, after the merge mining tag and the tx public
+            // key
+            let extra_field = ExtraField::try_parse(&block.miner_tx.prefix.extra).unwrap();
+            assert_eq!(extra_field.0.len(), 3);
+            assert_eq!(extra_field.0[2], SubField::Nonce(extra_nonce.to_le_bytes().to_vec()));
+            assert_eq!(
+                monero_rx::extract_aux_merkle_root_from_block(&block).unwrap(),
+                Some(aux_chain_mr)
+            );
+        }
+
+        let job = StratumJob::new(1, template, offset, 1, ChainTips::default());
+        assert_eq!(job.to_params(1).unwrap().blob, hex::encode(blob));
+    }
+
+    #[test]
+    fn it_finds_the_reserved_space() {
+        // The merge mining tag is inserted in front of the reserved space, that holds a nonce in this template
+        let template = template();
+        let reserved = &BLOCKTEMPLATE_BLOB[RESERVED_OFFSET * 2..(RESERVED_OFFSET + EXTRA_NONCE_SIZE) * 2];
+        let position = template.blocktemplate_blob.find(reserved).unwrap();
+        assert_eq!(extra_nonce_offset_of(&template) * 2, position);
+        assert!(extra_nonce_offset_of(&template) > RESERVED_OFFSET);
+        assert_eq!(
+            extra_nonce_offset(BLOCKTEMPLATE_BLOB, RESERVED_OFFSET, BLOCKTEMPLATE_BLOB).unwrap(),
+            RESERVED_OFFSET
+        );
+        assert!(extra_nonce_offset(
+            BLOCKTEMPLATE_BLOB,
+            BLOCKTEMPLATE_BLOB.len(),
+            &template.blocktemplate_blob
+        )
+        .is_err());
+        assert!(block_with_extra_nonce(BLOCKTEMPLATE_BLOB, BLOCKTEMPLATE_BLOB.len(), 1).is_err());
+    }
+
+    #[test]
+    fn it_parses_nonces() {
+        assert_eq!(parse_nonce("01020304"), Some(0x0403_0201));
+        assert_eq!(parse_nonce("010203"), None);
+        assert_eq!(parse_nonce("0102030405"), None);
+        assert_eq!(parse_nonce("not hex!"), None);
+    }
+
+    #[test]
+    fn it_encodes_the_target() {
+        assert_eq!(difficulty_to_target(1), "ffffffffffffffff");
+        assert_eq!(difficulty_to_target(0), "ffffffffffffffff");
+        // u64::MAX / 256 = 0x00ffffffffffffff, little endian
+        assert_eq!(difficulty_to_target(256), "ffffffffffffff00");
+    }
+}
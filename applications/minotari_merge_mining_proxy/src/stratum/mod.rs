@@ -0,0 +1,42 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A stratum endpoint that serves merge mined jobs directly to RandomX miners, e.g. XMRig in pool mode, as an
+//! alternative to proxying the monerod requests of XMRig in daemon mode.
+//!
+//! Methods:
+//! - `login {login, pass, agent}` returns the worker id and the current job
+//! - `getjob {id}` returns the current job
+//! - `submit {id, job_id, nonce, result}` submits a solution
+//! - `keepalived {id}`
+//!
+//! Jobs are Monero block templates from monerod with the Minotari merge mining tag in the coinbase, built with the
+//! [BlockTemplateProtocol](crate::block_template_protocol::BlockTemplateProtocol). New jobs are pushed to workers
+//! with a `job` notification when the tip of either chain changes. Solutions are verified with RandomX and submitted
+//! to monerod and to the base node (or p2pool) when they meet the difficulty of that chain.
+
+mod job;
+mod monerod;
+mod server;
+mod types;
+
+pub use server::StratumServer;
@@ -0,0 +1,288 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::*;
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json as json;
+use serde_json::json;
+
+use crate::{config::MergeMiningProxyConfig, error::MmProxyError};
+
+const LOG_TARGET: &str = "minotari_mm_proxy::stratum::monerod";
+
+/// The result of the monerod `get_block_template` request
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoneroBlockTemplate {
+    pub blocktemplate_blob: String,
+    pub difficulty: u64,
+    pub height: u64,
+    pub seed_hash: String,
+    /// The offset of the space reserved in the coinbase extra of the block template blob
+    pub reserved_offset: usize,
+}
+
+/// The tip of the Monero chain, as returned by the monerod `/get_height` request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MoneroTip {
+    pub hash: String,
+}
+
+/// A client for the monerod RPC that fails over to the next configured monerod server when a request fails
+pub struct MonerodClient {
+    http_client: reqwest::Client,
+    urls: Vec<String>,
+    current: AtomicUsize,
+    auth: Option<(String, String)>,
+}
+
+impl MonerodClient {
+    pub fn new(http_client: reqwest::Client, config: &MergeMiningProxyConfig) -> Result<Self, MmProxyError> {
+        let urls = config.monerod_url.clone().into_vec();
+        if urls.is_empty() {
+            return Err(MmProxyError::InvalidStratumConfig(
+                "no monerod servers configured".to_string(),
+            ));
+        }
+        Ok(Self {
+            http_client,
+            urls,
+            current: AtomicUsize::new(0),
+            auth: config
+                .monerod_use_auth
+                .then(|| (config.monerod_username.clone(), config.monerod_password.clone())),
+        })
+    }
+
+    /// Gets a block template that pays the wallet address, with `reserve_size` bytes reserved in the coinbase extra
+    pub async fn get_block_template(
+        &self,
+        wallet_address: &str,
+        reserve_size: usize,
+    ) -> Result<MoneroBlockTemplate, MmProxyError> {
+        let result = self
+            .json_rpc(
+                "get_block_template",
+                json!({ "wallet_address": wallet_address, "reserve_size": reserve_size }),
+            )
+            .await?;
+        json::from_value(result)
+            .map_err(|e| MmProxyError::InvalidMonerodResponse(format!("invalid `get_block_template` result: {}", e)))
+    }
+
+    /// Submits a hex encoded Monero block
+    pub async fn submit_block(&self, blocktemplate_blob: String) -> Result<(), MmProxyError> {
+        self.json_rpc("submit_block", json!([blocktemplate_blob])).await?;
+        Ok(())
+    }
+
+    pub async fn get_tip(&self) -> Result<MoneroTip, MmProxyError> {
+        let response = self.send(Method::GET, "/get_height", None).await?;
+        json::from_value(response)
+            .map_err(|e| MmProxyError::InvalidMonerodResponse(format!("invalid `get_height` response: {}", e)))
+    }
+
+    async fn json_rpc(&self, method: &str, params: json::Value) -> Result<json::Value, MmProxyError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "0",
+            "method": method,
+            "params": params,
+        });
+        let mut response = self.send(Method::POST, "/json_rpc", Some(request)).await?;
+        if !response["error"].is_null() {
+            return Err(MmProxyError::InvalidMonerodResponse(format!(
+                "`{}` failed: {}",
+                method, response["error"]
+            )));
+        }
+        Ok(response["result"].take())
+    }
+
+    async fn send(&self, method: Method, path: &str, body: Option<json::Value>) -> Result<json::Value, MmProxyError> {
+        let current = self.current.load(Ordering::Relaxed);
+        let url = format!("{}{}", self.urls[current % self.urls.len()].trim_end_matches('/'), path);
+        let mut builder = self.http_client.request(method, url.as_str());
+        if let Some((username, password)) = self.auth.as_ref() {
+            builder = builder.basic_auth(username, Some(password));
+        }
+        if let Some(body) = body {
+            builder = builder.json(&body);
+        }
+
+        let response = match builder.send().await.and_then(|resp| resp.error_for_status()) {
+            Ok(resp) => resp.json::<json::Value>().await,
+            Err(err) => Err(err),
+        };
+        response.map_err(|err| {
+            // The next request goes to the next server, unless another request has failed over already
+            let _ =
+                self.current
+                    .compare_exchange(current, current.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed);
+            warn!(target: LOG_TARGET, "Monerod request to {} failed: {}", url, err);
+            MmProxyError::MonerodRequestFailed(err)
+        })
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use std::{
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body,
+        Request,
+        Response,
+        Server,
+    };
+    use tari_common::configuration::StringList;
+
+    use super::*;
+    use crate::common::{json_rpc, monero_rpc::CoreRpcErrorCode};
+
+    const TIP_HASH: &str = "7fe21d764e7abf004e975396a2160773b93712bf6118c3b4959ddd8ee0f76aad";
+
+    /// A mock monerod that serves a fixed block template and tip, and records the blocks submitted to it
+    #[derive(Clone, Default)]
+    pub struct MockMonerod {
+        pub submitted_blocks: Arc<Mutex<Vec<String>>>,
+        reject_blocks: bool,
+    }
+
+    impl MockMonerod {
+        /// Starts the mock monerod and returns its URL
+        pub fn start(&self) -> String {
+            let mock = self.clone();
+            let service = make_service_fn(move |_conn| {
+                let mock = mock.clone();
+                async move { Ok::<_, Infallible>(service_fn(move |request| mock.clone().handle(request))) }
+            });
+            let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(service);
+            let address = server.local_addr();
+            tokio::spawn(server);
+            format!("http://{}", address)
+        }
+
+        /// Starts the mock monerod and returns a client for it, that first tries the servers in `unavailable_urls`
+        fn spawn(&self, unavailable_urls: &[&str]) -> MonerodClient {
+            let mut urls = unavailable_urls.iter().map(|url| url.to_string()).collect::<Vec<_>>();
+            urls.push(self.start());
+            let mut config = MergeMiningProxyConfig::default();
+            config.monerod_url = StringList::from(urls);
+            MonerodClient::new(reqwest::Client::new(), &config).unwrap()
+        }
+
+        async fn handle(self, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+            let path = request.uri().path().to_string();
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap_or_default();
+            let response = match path.as_str() {
+                "/get_height" => json!({ "height": 3_000_000, "hash": TIP_HASH, "status": "OK" }),
+                "/json_rpc" => self.handle_json_rpc(&json::from_slice(&body).unwrap_or_default()),
+                _ => json!({}),
+            };
+            Ok(Response::new(Body::from(response.to_string())))
+        }
+
+        fn handle_json_rpc(&self, request: &json::Value) -> json::Value {
+            let params = &request["params"];
+            match request["method"].as_str().unwrap_or_default() {
+                "get_block_template" if params["wallet_address"].is_string() && params["reserve_size"].is_u64() => {
+                    json_rpc::success_response(
+                        None,
+                        json!({
+                            "blocktemplate_blob": "0c0c",
+                            "blockhashing_blob": "0c0c",
+                            "difficulty": 300_000_000_000u64,
+                            "height": 3_000_001,
+                            "prev_hash": TIP_HASH,
+                            "reserved_offset": 1,
+                            "seed_hash": TIP_HASH,
+                            "status": "OK",
+                        }),
+                    )
+                },
+                "submit_block" if self.reject_blocks => json_rpc::error_response(
+                    None,
+                    CoreRpcErrorCode::BlockNotAccepted.into(),
+                    "Block not accepted",
+                    None,
+                ),
+                "submit_block" => {
+                    let blob = params[0].as_str().unwrap_or_default().to_string();
+                    self.submitted_blocks.lock().unwrap().push(blob);
+                    json_rpc::success_response(None, json!({ "status": "OK" }))
+                },
+                _ => json_rpc::error_response(None, CoreRpcErrorCode::WrongParam.into(), "Invalid request", None),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn it_gets_the_block_template_and_tip() {
+        let monerod = MockMonerod::default().spawn(&[]);
+        let template = monerod.get_block_template("wallet", 8).await.unwrap();
+        assert_eq!(template.blocktemplate_blob, "0c0c");
+        assert_eq!(template.difficulty, 300_000_000_000);
+        assert_eq!(template.height, 3_000_001);
+        assert_eq!(template.seed_hash, TIP_HASH);
+        assert_eq!(template.reserved_offset, 1);
+
+        let tip = monerod.get_tip().await.unwrap();
+        assert_eq!(tip, MoneroTip {
+            hash: TIP_HASH.to_string()
+        });
+    }
+
+    #[tokio::test]
+    async fn it_submits_blocks() {
+        let mock = MockMonerod::default();
+        let monerod = mock.spawn(&[]);
+        monerod.submit_block("0c0c01".to_string()).await.unwrap();
+        assert_eq!(*mock.submitted_blocks.lock().unwrap(), vec!["0c0c01".to_string()]);
+
+        let mock = MockMonerod {
+            reject_blocks: true,
+            ..Default::default()
+        };
+        let monerod = mock.spawn(&[]);
+        let err = monerod.submit_block("0c0c01".to_string()).await.unwrap_err();
+        assert!(matches!(err, MmProxyError::InvalidMonerodResponse(_)));
+        assert!(mock.submitted_blocks.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_fails_over_to_the_next_server() {
+        let monerod = MockMonerod::default().spawn(&["http://127.0.0.1:1"]);
+        assert!(matches!(
+            monerod.get_tip().await,
+            Err(MmProxyError::MonerodRequestFailed(_))
+        ));
+        assert_eq!(monerod.get_tip().await.unwrap().hash, TIP_HASH);
+    }
+}
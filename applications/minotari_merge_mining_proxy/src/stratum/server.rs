@@ -0,0 +1,493 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use log::*;
+use minotari_app_utilities::{
+    parse_miner_input::{BaseNodeGrpcClient, ShaP2PoolGrpcClient},
+    stratum::{
+        parse_params,
+        rpc_error,
+        to_result,
+        worker_name,
+        JsonRpcConnection,
+        LoginParams,
+        RpcError,
+        RpcRequest,
+        WorkerIdentifier,
+        INTERNAL_ERROR,
+        METHOD_NOT_FOUND,
+    },
+};
+use serde_json::{json, Value};
+use tari_common_types::tari_address::TariAddress;
+use tari_comms::{multiaddr::Multiaddr, utils::multiaddr::multiaddr_to_socketaddr};
+use tari_core::proof_of_work::randomx_factory::RandomXFactory;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task,
+};
+
+use crate::{
+    config::MergeMiningProxyConfig,
+    error::MmProxyError,
+    stratum::{
+        job::{JobManager, ShareRejection},
+        types::{JobParams, LoginResponse, StatusResponse, SubmitParams},
+    },
+};
+
+const LOG_TARGET: &str = "minotari_mm_proxy::stratum";
+
+/// Tells the miner to log in again
+const UNAUTHENTICATED: i32 = -1;
+const SHARE_REJECTED: i32 = -2;
+
+/// A stratum server for RandomX miners that connect directly, e.g. XMRig in pool mode. All workers mine the same merge
+/// mined job at the lower of the Monero and Minotari difficulties, each with its own extra nonce, so every valid share
+/// solves a block on at least one of the chains and is submitted to it.
+#[derive(Clone)]
+pub struct StratumServer {
+    jobs: Arc<JobManager>,
+    listener_address: Multiaddr,
+    next_worker_id: Arc<AtomicU64>,
+}
+
+impl StratumServer {
+    pub fn new(
+        config: Arc<MergeMiningProxyConfig>,
+        http_client: reqwest::Client,
+        base_node_client: BaseNodeGrpcClient,
+        p2pool_client: Option<ShaP2PoolGrpcClient>,
+        randomx_factory: RandomXFactory,
+        wallet_payment_address: TariAddress,
+    ) -> Result<Self, MmProxyError> {
+        Ok(Self {
+            listener_address: config.stratum_listener_address.clone(),
+            jobs: Arc::new(JobManager::new(
+                config,
+                http_client,
+                base_node_client,
+                p2pool_client,
+                randomx_factory,
+                wallet_payment_address,
+            )?),
+            next_worker_id: Arc::new(AtomicU64::new(1)),
+        })
+    }
+
+    /// Runs the stratum server, and builds jobs in the background
+    pub async fn run(self) -> Result<(), MmProxyError> {
+        let address = multiaddr_to_socketaddr(&self.listener_address)
+            .map_err(|e| MmProxyError::InvalidStratumConfig(format!("invalid listener address: {}", e)))?;
+        let listener = TcpListener::bind(address).await?;
+        info!(target: LOG_TARGET, "Stratum listening on {}...", address);
+        println!("Stratum listening on {}...", address);
+        self.serve(listener).await
+    }
+
+    /// Serves the workers that connect to the listener
+    async fn serve(self, listener: TcpListener) -> Result<(), MmProxyError> {
+        task::spawn(self.jobs.clone().run());
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    task::spawn(self.clone().handle_connection(stream, peer));
+                },
+                Err(err) => warn!(target: LOG_TARGET, "Stratum server failed to accept a connection: {}", err),
+            }
+        }
+    }
+
+    async fn handle_connection(self, stream: TcpStream, peer: SocketAddr) {
+        debug!(target: LOG_TARGET, "Stratum connection from {}", peer);
+        let mut job_rx = self.jobs.subscribe();
+        let mut session = Session {
+            server: self,
+            connection: JsonRpcConnection::new(stream, peer),
+            worker: None,
+        };
+
+        loop {
+            let result = tokio::select! {
+                request = session.connection.next_request() => match request {
+                    Some(request) => session.handle_request(request).await,
+                    None => break,
+                },
+                Ok(()) = job_rx.changed() => session.send_current_job().await,
+            };
+            if let Err(err) = result {
+                debug!(target: LOG_TARGET, "Closing stratum connection from {}: {}", peer, err);
+                break;
+            }
+        }
+        if let Some(worker) = session.worker {
+            info!(target: LOG_TARGET, "Stratum worker '{}' ({}) disconnected", worker.name, peer);
+        }
+    }
+}
+
+struct Worker {
+    id: String,
+    name: String,
+    /// Gives the worker its own blockhashing blob, so that workers do not mine the same nonces
+    extra_nonce: u64,
+}
+
+/// The connection of a single worker
+struct Session {
+    server: StratumServer,
+    connection: JsonRpcConnection,
+    worker: Option<Worker>,
+}
+
+impl Session {
+    async fn handle_request(&mut self, request: Result<RpcRequest, RpcError>) -> Result<(), MmProxyError> {
+        let request = match request {
+            Ok(request) => request,
+            Err(error) => {
+                self.connection.send_response(Value::Null, Err(error)).await?;
+                return Ok(());
+            },
+        };
+
+        let result = match request.method.as_str() {
+            "login" => self.login(request.params),
+            "getjob" => self.get_job(request.params),
+            "submit" => self.submit(request.params).await,
+            "keepalived" | "keepalive" => Ok(json!({ "status": "KEEPALIVED" })),
+            method => Err(rpc_error(METHOD_NOT_FOUND, format!("Method '{}' not found", method))),
+        };
+        self.connection
+            .send_response(request.id.unwrap_or_default(), result)
+            .await?;
+        Ok(())
+    }
+
+    fn login(&mut self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<LoginParams>(params)?;
+        let name = worker_name(&params.login)?;
+        let worker_id = self.server.next_worker_id.fetch_add(1, Ordering::Relaxed);
+        // Miners cannot start without a job, so the login fails until the first job is built
+        let job = self.current_job_params(worker_id)?;
+
+        let worker = Worker {
+            id: worker_id.to_string(),
+            name: name.to_string(),
+            extra_nonce: worker_id,
+        };
+        info!(
+            target: LOG_TARGET,
+            "Stratum worker '{}' ({}) logged in with agent '{}'",
+            worker.name,
+            self.connection.peer(),
+            params.agent
+        );
+        let id = worker.id.clone();
+        self.worker = Some(worker);
+        to_result(&LoginResponse {
+            id,
+            job,
+            status: "OK",
+            extensions: vec!["keepalive"],
+        })
+    }
+
+    fn get_job(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<WorkerIdentifier>(params)?;
+        let extra_nonce = self.authenticate(&params.id)?.extra_nonce;
+        to_result(&self.current_job_params(extra_nonce)?)
+    }
+
+    async fn submit(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<SubmitParams>(params)?;
+        // Shares are hashed with RandomX, so only the shares of the logged in worker for an active job are validated
+        let worker = self.authenticate(&params.id)?;
+        let (name, extra_nonce) = (worker.name.clone(), worker.extra_nonce);
+
+        let jobs = self.server.jobs.clone();
+        let outcome = match jobs.find_job(&params.job_id) {
+            Some(job) => jobs
+                .validate_share(job.clone(), extra_nonce, params.nonce, params.result)
+                .await
+                .map(|share| (job, share)),
+            None => Err(ShareRejection::StaleJob),
+        };
+        match outcome {
+            Ok((job, share)) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Stratum worker '{}' found a block for job {} with difficulty {}",
+                    name,
+                    job.id,
+                    share.achieved_difficulty
+                );
+                // The miner does not wait for the blocks to be submitted
+                task::spawn(async move { jobs.submit_solution(&job, share).await });
+                to_result(&StatusResponse { status: "OK" })
+            },
+            Err(rejection) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejected share of stratum worker '{}' for job {}: {}", name, params.job_id, rejection
+                );
+                Err(rpc_error(SHARE_REJECTED, rejection.to_string()))
+            },
+        }
+    }
+
+    fn authenticate(&self, id: &str) -> Result<&Worker, RpcError> {
+        match self.worker.as_ref() {
+            Some(worker) if worker.id == id => Ok(worker),
+            _ => Err(rpc_error(UNAUTHENTICATED, "Unauthenticated".to_string())),
+        }
+    }
+
+    /// Sends the current job to the worker, if it is logged in
+    async fn send_current_job(&mut self) -> Result<(), MmProxyError> {
+        let extra_nonce = match self.worker.as_ref() {
+            Some(worker) => worker.extra_nonce,
+            None => return Ok(()),
+        };
+        let job = match self.server.jobs.current_job() {
+            Some(job) => job,
+            None => return Ok(()),
+        };
+        self.connection
+            .send_notification("job", job.to_params(extra_nonce)?)
+            .await?;
+        Ok(())
+    }
+
+    fn current_job_params(&self, extra_nonce: u64) -> Result<JobParams, RpcError> {
+        self.server
+            .jobs
+            .current_job()
+            .ok_or_else(|| rpc_error(INTERNAL_ERROR, "No job available yet".to_string()))?
+            .to_params(extra_nonce)
+            .map_err(|err| rpc_error(INTERNAL_ERROR, err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::Mutex,
+        time::{Duration, Instant},
+    };
+
+    use minotari_app_grpc::tari_rpc::{
+        self as grpc,
+        base_node_client::BaseNodeClient,
+        sha_p2pool_client::ShaP2PoolClient,
+        sha_p2pool_server::{ShaP2Pool, ShaP2PoolServer},
+        GetNewBlockRequest,
+        GetNewBlockResponse,
+        SubmitBlockRequest,
+    };
+    use minotari_wallet_grpc_client::ClientAuthenticationInterceptor;
+    use monero::blockdata::transaction::{ExtraField, SubField};
+    use tari_common::configuration::StringList;
+    use tari_common_types::grpc_authentication::GrpcAuthentication;
+    use tari_core::proof_of_work::monero_rx;
+    use tari_utilities::hex::Hex;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+        net::tcp::{OwnedReadHalf, OwnedWriteHalf},
+        time,
+    };
+    use tonic::{
+        transport::{server::TcpIncoming, Endpoint, Server},
+        Request,
+        Response,
+        Status,
+    };
+
+    use super::*;
+    use crate::stratum::{
+        job::{
+            test::{extra_nonce_offset_of, template, NONCE_OFFSET},
+            ChainTips,
+        },
+        monerod::test::MockMonerod,
+    };
+
+    /// A p2pool node that records the Minotari blocks submitted to it
+    #[derive(Clone, Default)]
+    struct StubP2Pool {
+        submitted_blocks: Arc<Mutex<Vec<grpc::Block>>>,
+    }
+
+    #[tonic::async_trait]
+    impl ShaP2Pool for StubP2Pool {
+        async fn get_new_block(
+            &self,
+            _request: Request<GetNewBlockRequest>,
+        ) -> Result<Response<GetNewBlockResponse>, Status> {
+            Err(Status::unimplemented("Jobs are not built in this test"))
+        }
+
+        async fn submit_block(
+            &self,
+            request: Request<SubmitBlockRequest>,
+        ) -> Result<Response<grpc::SubmitBlockResponse>, Status> {
+            let block = request
+                .into_inner()
+                .block
+                .ok_or_else(|| Status::invalid_argument("No block"))?;
+            self.submitted_blocks.lock().unwrap().push(block);
+            Ok(Response::new(grpc::SubmitBlockResponse {
+                block_hash: vec![1; 32],
+            }))
+        }
+    }
+
+    impl StubP2Pool {
+        async fn start(&self) -> ShaP2PoolGrpcClient {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+            tokio::spawn(
+                Server::builder()
+                    .add_service(ShaP2PoolServer::new(self.clone()))
+                    .serve_with_incoming(incoming),
+            );
+            ShaP2PoolClient::with_interceptor(
+                Endpoint::from_shared(url).unwrap().connect_lazy(),
+                ClientAuthenticationInterceptor::create(&GrpcAuthentication::None).unwrap(),
+            )
+        }
+    }
+
+    /// Sends a request to the stratum server and returns the response, skipping job notifications
+    async fn request(
+        writer: &mut OwnedWriteHalf,
+        lines: &mut Lines<BufReader<OwnedReadHalf>>,
+        request: Value,
+    ) -> Value {
+        let mut line = request.to_string();
+        line.push('\n');
+        writer.write_all(line.as_bytes()).await.unwrap();
+        loop {
+            let line = lines.next_line().await.unwrap().expect("connection closed");
+            let response = serde_json::from_str::<Value>(&line).unwrap();
+            if response["method"].is_null() {
+                return response;
+            }
+        }
+    }
+
+    async fn wait_for<F: Fn() -> bool>(condition: F) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+            time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn it_forwards_solutions_to_both_chains() {
+        let monerod = MockMonerod::default();
+        let p2pool = StubP2Pool::default();
+        let mut config = MergeMiningProxyConfig::default();
+        config.monerod_url = StringList::from(vec![monerod.start()]);
+        config.stratum_monero_wallet_address = "wallet".to_string();
+        // The job is published by the test, the job manager does not get the tip of the unavailable base node
+        let base_node_client = BaseNodeClient::with_interceptor(
+            Endpoint::from_static("http://127.0.0.1:1").connect_lazy(),
+            ClientAuthenticationInterceptor::create(&GrpcAuthentication::None).unwrap(),
+        );
+        let randomx_factory = RandomXFactory::default();
+        let server = StratumServer::new(
+            Arc::new(config),
+            reqwest::Client::new(),
+            base_node_client,
+            Some(p2pool.start().await),
+            randomx_factory.clone(),
+            TariAddress::default(),
+        )
+        .unwrap();
+        let template = template();
+        let seed_hash = template.template.monero_seed.clone();
+        let extra_nonce_offset = extra_nonce_offset_of(&template);
+        server
+            .jobs
+            .publish_job(template, extra_nonce_offset, 3_000_001, ChainTips::default());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        task::spawn(server.serve(listener));
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        let login = json!({ "id": 1, "jsonrpc": "2.0", "method": "login", "params": { "login": "worker" } });
+        let login = request(&mut writer, &mut lines, login).await;
+        let worker_id = login["result"]["id"].as_str().unwrap().to_string();
+        let job = login["result"]["job"].clone();
+        let get_job = json!({ "id": 2, "jsonrpc": "2.0", "method": "getjob", "params": { "id": worker_id } });
+        assert_eq!(request(&mut writer, &mut lines, get_job).await["result"], job);
+
+        // Shares of unknown workers and jobs are rejected without hashing
+        let submit = |worker_id: &str, job_id: &Value, result: &str| {
+            json!({ "id": 3, "jsonrpc": "2.0", "method": "submit", "params": {
+                "id": worker_id, "job_id": job_id, "nonce": "01020304", "result": result
+            }})
+        };
+        let response = request(&mut writer, &mut lines, submit("unknown", &job["job_id"], "00")).await;
+        assert_eq!(response["error"]["code"], UNAUTHENTICATED);
+        let response = request(&mut writer, &mut lines, submit(&worker_id, &json!("unknown"), "00")).await;
+        assert_eq!(response["error"]["code"], SHARE_REJECTED);
+        assert_eq!(randomx_factory.get_count().unwrap(), 0);
+
+        let mut blob = hex::decode(job["blob"].as_str().unwrap()).unwrap();
+        blob[NONCE_OFFSET..NONCE_OFFSET + 4].copy_from_slice(&[1, 2, 3, 4]);
+        let hash = randomx_factory
+            .create(seed_hash.as_slice(), None, None)
+            .and_then(|vm| vm.calculate_hash(&blob))
+            .unwrap();
+        let submit = submit(&worker_id, &job["job_id"], &hash.to_hex());
+        let response = request(&mut writer, &mut lines, submit.clone()).await;
+        assert_eq!(response["result"]["status"], "OK");
+        let response = request(&mut writer, &mut lines, submit).await;
+        assert_eq!(response["error"]["code"], SHARE_REJECTED);
+
+        // The blocks are submitted in the background
+        wait_for(|| !monerod.submitted_blocks.lock().unwrap().is_empty()).await;
+        wait_for(|| !p2pool.submitted_blocks.lock().unwrap().is_empty()).await;
+        let monero_block =
+            monero_rx::deserialize_monero_block_from_hex(&monerod.submitted_blocks.lock().unwrap()[0]).unwrap();
+        assert_eq!(monero_block.header.nonce, 0x0403_0201);
+        let extra_nonce = worker_id.parse::<u64>().unwrap().to_le_bytes().to_vec();
+        let extra_field = ExtraField::try_parse(&monero_block.miner_tx.prefix.extra).unwrap();
+        assert!(extra_field.0.contains(&SubField::Nonce(extra_nonce)));
+        let tari_block = p2pool.submitted_blocks.lock().unwrap()[0].clone();
+        let pow = tari_block.header.and_then(|header| header.pow).unwrap();
+        assert!(!pow.pow_data.is_empty());
+    }
+}
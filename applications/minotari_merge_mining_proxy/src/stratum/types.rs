@@ -0,0 +1,65 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The JSON-RPC messages of the stratum protocol that are specific to RandomX miners such as XMRig. The common messages
+//! are in [minotari_app_utilities::stratum].
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    pub id: String,
+    pub job: JobParams,
+    pub status: &'static str,
+    /// The protocol extensions supported by the server
+    pub extensions: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobParams {
+    pub job_id: String,
+    /// The hex encoded Monero blockhashing blob of the worker, with the merge mining tag and the extra nonce of the
+    /// worker in the coinbase
+    pub blob: String,
+    /// The hex encoded, little endian 64-bit target of the job
+    pub target: String,
+    /// The Monero height of the job
+    pub height: u64,
+    /// The hex encoded RandomX key
+    pub seed_hash: String,
+    pub algo: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitParams {
+    pub id: String,
+    pub job_id: String,
+    /// The hex encoded, little endian 32-bit nonce
+    pub nonce: String,
+    /// The hex encoded RandomX hash of the blockhashing blob with the nonce set
+    pub result: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    pub status: &'static str,
+}
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS stratum_shares;
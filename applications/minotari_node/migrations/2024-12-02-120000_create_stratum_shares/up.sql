@@ -0,0 +1,14 @@
+CREATE TABLE stratum_shares (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    worker              TEXT     NOT NULL,
+    job_id              BIGINT   NOT NULL,
+    height              BIGINT   NOT NULL,
+    share_difficulty    BIGINT   NOT NULL,
+    achieved_difficulty BIGINT   NOT NULL,
+    accepted            BOOLEAN  NOT NULL,
+    reject_reason       TEXT     NULL,
+    block_hash          BLOB     NULL,
+    submitted_at        DATETIME NOT NULL
+);
+
+CREATE INDEX idx_stratum_shares_worker_submitted_at ON stratum_shares (worker, submitted_at);
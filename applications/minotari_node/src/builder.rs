@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 use log::*;
 use tari_common::{
@@ -38,7 +38,7 @@ use tari_core::{
     },
     chain_storage::{create_lmdb_database, BlockchainDatabase, ChainStorageError, LMDBDatabase, Validators},
     consensus::ConsensusManager,
-    mempool::{service::LocalMempoolService, Mempool},
+    mempool::{service::LocalMempoolService, Mempool, MempoolPersistence},
     proof_of_work::randomx_factory::RandomXFactory,
     transactions::CryptoFactories,
     validation::{
@@ -47,14 +47,18 @@ use tari_core::{
         transaction::TransactionFullValidator,
         DifficultyCalculator,
     },
-    OutputSmt,
 };
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
 use tari_service_framework::ServiceHandles;
 use tari_shutdown::ShutdownSignal;
-use tokio::sync::watch;
+use tokio::{sync::watch, task, task::JoinHandle};
 
-use crate::{bootstrap::BaseNodeBootstrapper, ApplicationConfig, DatabaseType};
+use crate::{
+    bootstrap::BaseNodeBootstrapper,
+    chain_events::{ChainEventPublisher, ChainEventsHandle},
+    ApplicationConfig,
+    DatabaseType,
+};
 
 const LOG_TARGET: &str = "c::bn::initialization";
 
@@ -68,6 +72,9 @@ pub struct BaseNodeContext {
     base_node_comms: CommsNode,
     base_node_dht: Dht,
     base_node_handles: ServiceHandles,
+    mempool: Mempool,
+    mempool_persistence: Option<JoinHandle<()>>,
+    chain_events: ChainEventsHandle,
 }
 
 impl BaseNodeContext {
@@ -75,6 +82,27 @@ impl BaseNodeContext {
         self.blockchain_db.start()
     }
 
+    /// Restores the mempool from its on-disk snapshot and starts writing snapshots, if mempool persistence is enabled.
+    /// This must be called after the blockchain database has been started, as restored transactions are revalidated
+    /// against the current chain.
+    pub async fn start_mempool_persistence(&mut self, shutdown_signal: ShutdownSignal) -> Result<(), ExitError> {
+        let config = &self.config.base_node.mempool.persistence;
+        if !config.enabled {
+            return Ok(());
+        }
+        let persistence = MempoolPersistence::new(self.mempool.clone(), config.clone());
+        let tip_height = self
+            .blockchain_db
+            .get_chain_metadata()
+            .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?
+            .best_block_height();
+        if let Err(e) = persistence.restore(tip_height).await {
+            warn!(target: LOG_TARGET, "Could not restore the mempool snapshot: {}", e);
+        }
+        self.mempool_persistence = Some(task::spawn(persistence.run(shutdown_signal)));
+        Ok(())
+    }
+
     /// Waits for shutdown of the base node state machine and comms.
     /// This call consumes the NodeContainer instance.
     pub async fn wait_for_shutdown(self) {
@@ -83,6 +111,12 @@ impl BaseNodeContext {
 
         self.base_node_comms.wait_until_shutdown().await;
         info!(target: LOG_TARGET, "Communications stack has shutdown");
+
+        if let Some(mempool_persistence) = self.mempool_persistence {
+            if let Err(e) = mempool_persistence.await {
+                warn!(target: LOG_TARGET, "Mempool persistence task failed: {}", e);
+            }
+        }
     }
 
     /// Return the node config
@@ -100,6 +134,16 @@ impl BaseNodeContext {
         self.base_node_handles.expect_handle()
     }
 
+    /// Returns the Mempool
+    pub fn mempool(&self) -> Mempool {
+        self.mempool.clone()
+    }
+
+    /// Returns the handle used to subscribe to new blocks, chain reorgs and accepted mempool transactions
+    pub fn chain_events(&self) -> ChainEventsHandle {
+        self.chain_events.clone()
+    }
+
     /// Returns the CommsNode.
     pub fn base_node_comms(&self) -> &CommsNode {
         &self.base_node_comms
@@ -224,7 +268,6 @@ async fn build_node_context(
     let factories = CryptoFactories::default();
     let randomx_factory = RandomXFactory::new(app_config.base_node.max_randomx_vms);
     let difficulty_calculator = DifficultyCalculator::new(rules.clone(), randomx_factory.clone());
-    let smt = Arc::new(RwLock::new(OutputSmt::new()));
     let validators = Validators::new(
         BlockBodyFullValidator::new(rules.clone(), true),
         HeaderFullValidator::new(rules.clone(), difficulty_calculator.clone()),
@@ -241,7 +284,6 @@ async fn build_node_context(
         validators,
         app_config.base_node.storage,
         difficulty_calculator,
-        smt.clone(),
     )
     .map_err(|err| {
         if let ChainStorageError::DatabaseResyncRequired(reason) = err {
@@ -273,7 +315,7 @@ async fn build_node_context(
         app_config: &app_config,
         node_identity: base_node_identity,
         db: blockchain_db.clone(),
-        mempool,
+        mempool: mempool.clone(),
         rules: rules.clone(),
         factories: factories.clone(),
         randomx_factory,
@@ -285,6 +327,17 @@ async fn build_node_context(
     let base_node_comms = base_node_handles.expect_handle::<CommsNode>();
     let base_node_dht = base_node_handles.expect_handle::<Dht>();
 
+    let chain_event_publisher = ChainEventPublisher::new(
+        base_node_handles
+            .expect_handle::<LocalNodeCommsInterface>()
+            .get_block_event_stream(),
+        base_node_handles
+            .expect_handle::<LocalMempoolService>()
+            .get_mempool_event_stream(),
+    );
+    let chain_events = chain_event_publisher.handle();
+    task::spawn(chain_event_publisher.run(interrupt_signal));
+
     Ok(BaseNodeContext {
         config: app_config,
         consensus_rules: rules,
@@ -292,5 +345,8 @@ async fn build_node_context(
         base_node_comms,
         base_node_dht,
         base_node_handles,
+        mempool,
+        mempool_persistence: None,
+        chain_events,
     })
 }
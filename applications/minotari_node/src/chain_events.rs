@@ -0,0 +1,482 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Publishes new blocks, chain reorgs and accepted mempool transactions to local subscribers, so that services do not
+//! have to poll the node to learn about them.
+//!
+//! Every event is published on a topic, and each topic numbers its events with its own sequence number, starting at 1.
+//! A subscriber that only listens to some topics can therefore detect missed events from a gap in the sequence numbers
+//! of a topic. When the publisher itself misses events from the node, it skips sequence numbers on the affected topics
+//! so that subscribers see the same gap and know to resync.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use chrono::Utc;
+use log::*;
+use tari_common_types::types::BlockHash;
+use tari_core::{
+    base_node::comms_interface::{BlockEvent, BlockEventReceiver},
+    blocks::{Block, ChainBlock},
+    chain_storage::{BlockAddResult, Reorg},
+    mempool::service::{MempoolEvent, MempoolEventReceiver},
+    transactions::transaction_components::Transaction,
+};
+use tari_shutdown::ShutdownSignal;
+use tokio::sync::{broadcast, broadcast::error::RecvError};
+
+const LOG_TARGET: &str = "minotari::base_node::chain_events";
+
+/// The number of events buffered for each subscriber. Subscribers that fall further behind than this miss events.
+const CHAIN_EVENTS_BUFFER_SIZE: usize = 1_000;
+
+/// The topics that chain events are published on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainEventTopic {
+    BlockHash,
+    Block,
+    Reorg,
+    MempoolTransaction,
+}
+
+impl ChainEventTopic {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        match self {
+            ChainEventTopic::BlockHash => 0,
+            ChainEventTopic::Block => 1,
+            ChainEventTopic::Reorg => 2,
+            ChainEventTopic::MempoolTransaction => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainEvent {
+    pub topic: ChainEventTopic,
+    /// The sequence number of this event within its topic
+    pub sequence: u64,
+    pub payload: ChainEventPayload,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChainEventPayload {
+    BlockHash {
+        height: u64,
+        hash: BlockHash,
+    },
+    Block(Arc<Block>),
+    Reorg {
+        reorg: Reorg,
+        /// Ordered from lowest to highest height
+        added: Vec<BlockHash>,
+        /// Ordered from highest to lowest height
+        removed: Vec<BlockHash>,
+    },
+    MempoolTransaction(Arc<Transaction>),
+}
+
+impl ChainEventPayload {
+    pub fn topic(&self) -> ChainEventTopic {
+        match self {
+            ChainEventPayload::BlockHash { .. } => ChainEventTopic::BlockHash,
+            ChainEventPayload::Block(_) => ChainEventTopic::Block,
+            ChainEventPayload::Reorg { .. } => ChainEventTopic::Reorg,
+            ChainEventPayload::MempoolTransaction(_) => ChainEventTopic::MempoolTransaction,
+        }
+    }
+}
+
+/// A cheaply cloneable handle used to subscribe to chain events
+#[derive(Clone)]
+pub struct ChainEventsHandle {
+    sender: broadcast::Sender<Arc<ChainEvent>>,
+}
+
+impl ChainEventsHandle {
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<ChainEvent>> {
+        self.sender.subscribe()
+    }
+}
+
+/// Converts the block events of the base node service and the events of the mempool service into sequenced chain
+/// events.
+pub struct ChainEventPublisher {
+    block_events: BlockEventReceiver,
+    mempool_events: MempoolEventReceiver,
+    sender: broadcast::Sender<Arc<ChainEvent>>,
+    sequences: [u64; ChainEventTopic::COUNT],
+}
+
+impl ChainEventPublisher {
+    pub fn new(block_events: BlockEventReceiver, mempool_events: MempoolEventReceiver) -> Self {
+        let (sender, _) = broadcast::channel(CHAIN_EVENTS_BUFFER_SIZE);
+        Self {
+            block_events,
+            mempool_events,
+            sender,
+            sequences: [0; ChainEventTopic::COUNT],
+        }
+    }
+
+    pub fn handle(&self) -> ChainEventsHandle {
+        ChainEventsHandle {
+            sender: self.sender.clone(),
+        }
+    }
+
+    pub async fn run(mut self, mut shutdown_signal: ShutdownSignal) {
+        debug!(target: LOG_TARGET, "Chain event publisher started");
+        loop {
+            tokio::select! {
+                event = self.block_events.recv() => match event {
+                    Ok(event) => self.handle_block_event(&event),
+                    Err(RecvError::Lagged(n)) => self.handle_missed_block_events(n),
+                    Err(RecvError::Closed) => break,
+                },
+                event = self.mempool_events.recv() => match event {
+                    Ok(event) => self.handle_mempool_event(&event),
+                    Err(RecvError::Lagged(n)) => self.handle_missed_mempool_events(n),
+                    Err(RecvError::Closed) => break,
+                },
+                _ = shutdown_signal.wait() => break,
+            }
+        }
+        debug!(target: LOG_TARGET, "Chain event publisher stopped");
+    }
+
+    fn handle_block_event(&mut self, event: &BlockEvent) {
+        match event {
+            BlockEvent::ValidBlockAdded(_, BlockAddResult::Ok(block)) => self.publish_block(block),
+            BlockEvent::ValidBlockAdded(_, BlockAddResult::ChainReorg { added, removed }) => {
+                let reorg = Reorg::from_reorged_blocks(&added.iter().rev().cloned().collect::<VecDeque<_>>(), removed);
+                self.publish_reorg(reorg, added, removed);
+                for block in added {
+                    self.publish_block(block);
+                }
+            },
+            BlockEvent::ValidBlockAdded(_, BlockAddResult::BlockExists | BlockAddResult::OrphanBlock) => {},
+            BlockEvent::BlockSyncRewind(removed) => {
+                // The new tip is the parent of the lowest removed block
+                if let Some(lowest) = removed.last() {
+                    let reorg = Reorg {
+                        new_height: lowest.height().saturating_sub(1),
+                        new_hash: lowest.header().prev_hash,
+                        prev_height: removed.first().map(|b| b.height()).unwrap_or_default(),
+                        prev_hash: removed.first().map(|b| *b.hash()).unwrap_or_default(),
+                        num_blocks_added: 0,
+                        num_blocks_removed: removed.len() as u64,
+                        local_time: Utc::now(),
+                    };
+                    self.publish_reorg(reorg, &[], removed);
+                }
+            },
+            // Block sync publishes every block it adds as a `ValidBlockAdded` event, so the tip was already published
+            BlockEvent::BlockSyncComplete(..) |
+            BlockEvent::AddBlockValidationFailed { .. } |
+            BlockEvent::AddBlockErrored { .. } => {},
+        }
+    }
+
+    /// A missed block event may have been published as block hash, block and reorg events. How many of each is not
+    /// known, so every block topic skips `n` sequence numbers.
+    fn handle_missed_block_events(&mut self, n: u64) {
+        warn!(target: LOG_TARGET, "Chain event publisher missed {} block event(s)", n);
+        for topic in [
+            ChainEventTopic::BlockHash,
+            ChainEventTopic::Block,
+            ChainEventTopic::Reorg,
+        ] {
+            self.sequences[topic.index()] += n;
+        }
+    }
+
+    /// Every mempool event is published as exactly one mempool transaction event
+    fn handle_missed_mempool_events(&mut self, n: u64) {
+        warn!(target: LOG_TARGET, "Chain event publisher missed {} mempool event(s)", n);
+        self.sequences[ChainEventTopic::MempoolTransaction.index()] += n;
+    }
+
+    fn handle_mempool_event(&mut self, event: &MempoolEvent) {
+        match event {
+            MempoolEvent::TransactionAccepted(tx) => {
+                self.publish(ChainEventPayload::MempoolTransaction(tx.clone()));
+            },
+        }
+    }
+
+    fn publish_block(&mut self, block: &ChainBlock) {
+        self.publish(ChainEventPayload::BlockHash {
+            height: block.height(),
+            hash: *block.hash(),
+        });
+        self.publish(ChainEventPayload::Block(block.to_arc_block()));
+    }
+
+    fn publish_reorg(&mut self, reorg: Reorg, added: &[Arc<ChainBlock>], removed: &[Arc<ChainBlock>]) {
+        self.publish(ChainEventPayload::Reorg {
+            reorg,
+            added: added.iter().map(|b| *b.hash()).collect(),
+            removed: removed.iter().map(|b| *b.hash()).collect(),
+        });
+    }
+
+    fn publish(&mut self, payload: ChainEventPayload) {
+        let topic = payload.topic();
+        let sequence = &mut self.sequences[topic.index()];
+        *sequence += 1;
+        let event = ChainEvent {
+            topic,
+            sequence: *sequence,
+            payload,
+        };
+        // Sending fails when there are no subscribers, the event is still counted so that sequence numbers always
+        // reflect the number of events published on a topic
+        let _size = self.sender.send(Arc::new(event)).unwrap_or(0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common_types::types::PrivateKey;
+    use tari_core::{
+        blocks::{BlockHeader, BlockHeaderAccumulatedData},
+        transactions::aggregated_body::AggregateBody,
+    };
+    use tari_shutdown::Shutdown;
+
+    use super::*;
+
+    fn publisher() -> ChainEventPublisher {
+        let (_, block_events) = broadcast::channel(1);
+        let (_, mempool_events) = broadcast::channel(1);
+        ChainEventPublisher::new(block_events, mempool_events)
+    }
+
+    fn transaction() -> Arc<Transaction> {
+        Arc::new(Transaction::new(
+            vec![],
+            vec![],
+            vec![],
+            PrivateKey::default(),
+            PrivateKey::default(),
+        ))
+    }
+
+    fn chain_block(height: u64, prev_hash: BlockHash) -> Arc<ChainBlock> {
+        let mut header = BlockHeader::new(0);
+        header.height = height;
+        header.prev_hash = prev_hash;
+        let block = Arc::new(Block::new(header, AggregateBody::empty()));
+        let accumulated_data = BlockHeaderAccumulatedData {
+            hash: block.hash(),
+            ..Default::default()
+        };
+        Arc::new(ChainBlock::try_construct(block, accumulated_data).unwrap())
+    }
+
+    #[tokio::test]
+    async fn it_numbers_events_per_topic() {
+        let mut publisher = publisher();
+        let mut subscriber = publisher.handle().subscribe();
+
+        publisher.handle_mempool_event(&MempoolEvent::TransactionAccepted(transaction()));
+        publisher.handle_block_event(&BlockEvent::BlockSyncRewind(vec![]));
+        publisher.handle_mempool_event(&MempoolEvent::TransactionAccepted(transaction()));
+
+        let first = subscriber.recv().await.unwrap();
+        assert_eq!(first.topic, ChainEventTopic::MempoolTransaction);
+        assert_eq!(first.sequence, 1);
+        let second = subscriber.recv().await.unwrap();
+        assert_eq!(second.topic, ChainEventTopic::MempoolTransaction);
+        assert_eq!(second.sequence, 2);
+        assert!(subscriber.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn it_counts_events_published_without_subscribers() {
+        let mut publisher = publisher();
+        publisher.handle_mempool_event(&MempoolEvent::TransactionAccepted(transaction()));
+
+        let mut subscriber = publisher.handle().subscribe();
+        publisher.handle_mempool_event(&MempoolEvent::TransactionAccepted(transaction()));
+        let event = subscriber.recv().await.unwrap();
+        assert_eq!(event.sequence, 2);
+    }
+
+    #[tokio::test]
+    async fn it_publishes_added_blocks() {
+        let mut publisher = publisher();
+        let mut subscriber = publisher.handle().subscribe();
+
+        let block = chain_block(1, BlockHash::default());
+        publisher.handle_block_event(&BlockEvent::ValidBlockAdded(
+            block.to_arc_block(),
+            BlockAddResult::Ok(block.clone()),
+        ));
+
+        let event = subscriber.recv().await.unwrap();
+        assert_eq!(event.topic, ChainEventTopic::BlockHash);
+        assert_eq!(event.sequence, 1);
+        match &event.payload {
+            ChainEventPayload::BlockHash { height, hash } => {
+                assert_eq!(*height, 1);
+                assert_eq!(hash, block.hash());
+            },
+            payload => panic!("Unexpected payload {:?}", payload),
+        }
+        let event = subscriber.recv().await.unwrap();
+        assert_eq!(event.topic, ChainEventTopic::Block);
+        assert_eq!(event.sequence, 1);
+        match &event.payload {
+            ChainEventPayload::Block(published) => assert_eq!(published.hash(), *block.hash()),
+            payload => panic!("Unexpected payload {:?}", payload),
+        }
+        assert!(subscriber.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn it_publishes_each_synced_block_once() {
+        let mut publisher = publisher();
+        let mut subscriber = publisher.handle().subscribe();
+
+        // Block sync publishes every block it adds, followed by the sync complete event with the new tip
+        let mut blocks = vec![chain_block(1, BlockHash::default())];
+        for height in 2..=3 {
+            let prev_hash = *blocks[blocks.len() - 1].hash();
+            blocks.push(chain_block(height, prev_hash));
+        }
+        for block in &blocks {
+            publisher.handle_block_event(&BlockEvent::ValidBlockAdded(
+                block.to_arc_block(),
+                BlockAddResult::Ok(block.clone()),
+            ));
+        }
+        publisher.handle_block_event(&BlockEvent::BlockSyncComplete(blocks[2].clone(), 1));
+
+        for (block, sequence) in blocks.iter().zip(1u64..) {
+            let event = subscriber.recv().await.unwrap();
+            assert_eq!(event.topic, ChainEventTopic::BlockHash);
+            assert_eq!(event.sequence, sequence);
+            let event = subscriber.recv().await.unwrap();
+            assert_eq!(event.topic, ChainEventTopic::Block);
+            assert_eq!(event.sequence, sequence);
+            match &event.payload {
+                ChainEventPayload::Block(published) => assert_eq!(published.hash(), *block.hash()),
+                payload => panic!("Unexpected payload {:?}", payload),
+            }
+        }
+        assert!(subscriber.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn it_publishes_reorgs_before_the_added_blocks() {
+        let mut publisher = publisher();
+        let mut subscriber = publisher.handle().subscribe();
+
+        let fork_point = chain_block(1, BlockHash::default());
+        let removed = chain_block(2, *fork_point.hash());
+        let mut added = vec![chain_block(2, *fork_point.hash())];
+        added.push(chain_block(3, *added[0].hash()));
+        publisher.handle_block_event(&BlockEvent::ValidBlockAdded(
+            added[1].to_arc_block(),
+            BlockAddResult::ChainReorg {
+                added: added.clone(),
+                removed: vec![removed.clone()],
+            },
+        ));
+
+        let event = subscriber.recv().await.unwrap();
+        assert_eq!(event.topic, ChainEventTopic::Reorg);
+        assert_eq!(event.sequence, 1);
+        match &event.payload {
+            ChainEventPayload::Reorg {
+                reorg,
+                added: added_hashes,
+                removed: removed_hashes,
+            } => {
+                assert_eq!(reorg.new_height, 3);
+                assert_eq!(reorg.new_hash, *added[1].hash());
+                assert_eq!(reorg.prev_height, 2);
+                assert_eq!(reorg.prev_hash, *removed.hash());
+                assert_eq!(reorg.num_blocks_added, 2);
+                assert_eq!(reorg.num_blocks_removed, 1);
+                assert_eq!(added_hashes, &vec![*added[0].hash(), *added[1].hash()]);
+                assert_eq!(removed_hashes, &vec![*removed.hash()]);
+            },
+            payload => panic!("Unexpected payload {:?}", payload),
+        }
+        for (block, sequence) in added.iter().zip(1u64..) {
+            let event = subscriber.recv().await.unwrap();
+            assert_eq!(event.topic, ChainEventTopic::BlockHash);
+            assert_eq!(event.sequence, sequence);
+            let event = subscriber.recv().await.unwrap();
+            assert_eq!(event.topic, ChainEventTopic::Block);
+            match &event.payload {
+                ChainEventPayload::Block(published) => assert_eq!(published.hash(), *block.hash()),
+                payload => panic!("Unexpected payload {:?}", payload),
+            }
+        }
+        assert!(subscriber.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn it_skips_sequence_numbers_for_missed_events() {
+        let (block_sender, block_events) = broadcast::channel(1);
+        let (mempool_sender, mempool_events) = broadcast::channel(1);
+        let publisher = ChainEventPublisher::new(block_events, mempool_events);
+        let mut subscriber = publisher.handle().subscribe();
+
+        // The publisher only sees the last of each of these
+        for _ in 0..3 {
+            mempool_sender
+                .send(Arc::new(MempoolEvent::TransactionAccepted(transaction())))
+                .unwrap();
+        }
+        let block = chain_block(1, BlockHash::default());
+        for _ in 0..2 {
+            block_sender
+                .send(Arc::new(BlockEvent::ValidBlockAdded(
+                    block.to_arc_block(),
+                    BlockAddResult::Ok(block.clone()),
+                )))
+                .unwrap();
+        }
+
+        let mut shutdown = Shutdown::new();
+        let task = tokio::spawn(publisher.run(shutdown.to_signal()));
+        let mut sequences = Vec::new();
+        for _ in 0..3 {
+            let event = subscriber.recv().await.unwrap();
+            sequences.push((event.topic.index(), event.sequence));
+        }
+        shutdown.trigger();
+        task.await.unwrap();
+
+        sequences.sort_unstable();
+        assert_eq!(sequences, vec![
+            (ChainEventTopic::BlockHash.index(), 2),
+            (ChainEventTopic::Block.index(), 2),
+            (ChainEventTopic::MempoolTransaction.index(), 3),
+        ]);
+    }
+}
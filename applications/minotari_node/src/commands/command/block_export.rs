@@ -0,0 +1,189 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::{path::PathBuf, sync::Arc, time::Instant};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use clap::Parser;
+use tari_core::{
+    base_node::comms_interface::BlockEvent,
+    chain_storage::{BlockAddResult, BlockExportError, BlockExportReader, BlockExportWriter},
+};
+use tokio::io::{self, AsyncWriteExt};
+
+use super::{CommandContext, HandleCommand};
+
+/// The number of blocks between progress updates
+const PROGRESS_INTERVAL: u64 = 100;
+
+/// Exports a range of blocks to a checksummed file that other nodes can import with `import-blocks`
+#[derive(Debug, Parser)]
+pub struct ArgsExport {
+    /// The file to write the blocks to. An interrupted export to the same file is resumed
+    path: PathBuf,
+    /// The height of the first block to export
+    #[clap(long, default_value_t = 1)]
+    start: u64,
+    /// The height of the last block to export. Defaults to the current tip
+    #[clap(long)]
+    end: Option<u64>,
+}
+
+#[async_trait]
+impl HandleCommand<ArgsExport> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsExport) -> Result<(), Error> {
+        self.export_blocks(args.path, args.start, args.end).await
+    }
+}
+
+/// Imports blocks from a file written by `export-blocks`, fully validating every block. Blocks that are already part
+/// of the local chain are skipped, so an interrupted import can be resumed by running it again
+#[derive(Debug, Parser)]
+pub struct ArgsImport {
+    /// The file to read the blocks from
+    path: PathBuf,
+}
+
+#[async_trait]
+impl HandleCommand<ArgsImport> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsImport) -> Result<(), Error> {
+        self.import_blocks(args.path).await
+    }
+}
+
+impl CommandContext {
+    /// Function to process the export-blocks command
+    pub async fn export_blocks(&mut self, path: PathBuf, start: u64, end: Option<u64>) -> Result<(), Error> {
+        let tip_height = self.blockchain_db.get_chain_metadata().await?.best_block_height();
+        let end = end.unwrap_or(tip_height);
+        if end > tip_height {
+            return Err(anyhow!("End height {} is above the tip height {}", end, tip_height));
+        }
+        if start > end {
+            return Err(anyhow!("Start height {} is above the end height {}", start, end));
+        }
+
+        let genesis_hash = *self.blockchain_db.fetch_chain_header(0).await?.hash();
+        let mut writer = BlockExportWriter::create_or_resume(&path, genesis_hash, start)?;
+        if writer.next_height() > start {
+            println!("Resuming export at block #{}", writer.next_height());
+        }
+        let timer = Instant::now();
+        let mut num_exported = 0;
+        for height in writer.next_height()..=end {
+            let block = self.blockchain_db.fetch_block(height, false).await?.into_block();
+            writer.write_block(&block)?;
+            num_exported += 1;
+            if height % PROGRESS_INTERVAL == 0 || height == end {
+                writer.flush()?;
+                print_progress("Exported", height, Some(end), num_exported, timer).await?;
+            }
+        }
+        writer.flush()?;
+        println!();
+        println!(
+            "Exported {} block(s) up to #{} to {} in {:.0?}",
+            num_exported,
+            end,
+            path.display(),
+            timer.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Function to process the import-blocks command
+    pub async fn import_blocks(&mut self, path: PathBuf) -> Result<(), Error> {
+        let mut reader = BlockExportReader::open(&path)?;
+        let genesis_hash = *self.blockchain_db.fetch_chain_header(0).await?.hash();
+        reader.check_genesis_hash(&genesis_hash)?;
+        let tip_height = self.blockchain_db.get_chain_metadata().await?.best_block_height();
+
+        let timer = Instant::now();
+        let mut num_imported = 0;
+        let mut num_skipped = 0;
+        let mut last_height = None;
+        loop {
+            let block = match reader.read_block() {
+                Ok(Some(block)) => block,
+                Ok(None) => break,
+                Err(err @ BlockExportError::Truncated { .. }) => {
+                    println!();
+                    println!("{}, stopping the import there", err);
+                    break;
+                },
+                Err(err) => return Err(err.into()),
+            };
+            let height = block.header.height;
+            last_height = Some(height);
+
+            // Blocks already in the local chain were imported before, e.g. by an import that was interrupted
+            if height <= tip_height && *self.blockchain_db.fetch_chain_header(height).await?.hash() == block.hash() {
+                num_skipped += 1;
+            } else {
+                let block = Arc::new(block);
+                let result = self
+                    .blockchain_db
+                    .add_block(block.clone())
+                    .await
+                    .map_err(|e| anyhow!("Failed to import block #{}: {}", height, e))?;
+                match result {
+                    BlockAddResult::BlockExists => num_skipped += 1,
+                    BlockAddResult::OrphanBlock => {
+                        return Err(anyhow!("Block #{} does not connect to the local chain", height));
+                    },
+                    result => {
+                        num_imported += 1;
+                        self.node_service
+                            .publish_block_event(BlockEvent::ValidBlockAdded(block, result));
+                    },
+                }
+            }
+            if height % PROGRESS_INTERVAL == 0 {
+                print_progress("Imported", height, None, num_imported, timer).await?;
+            }
+        }
+        println!();
+        match last_height {
+            Some(height) => println!(
+                "Imported {} block(s) up to #{} from {} in {:.0?} ({} already in the local chain)",
+                num_imported,
+                height,
+                path.display(),
+                timer.elapsed(),
+                num_skipped
+            ),
+            None => println!("{} does not contain any blocks", path.display()),
+        }
+        Ok(())
+    }
+}
+
+async fn print_progress(action: &str, height: u64, end: Option<u64>, count: u64, timer: Instant) -> Result<(), Error> {
+    let blocks_per_second = count as f64 / timer.elapsed().as_secs_f64().max(f64::EPSILON);
+    let end = end.map(|end| format!(" of #{}", end)).unwrap_or_default();
+    print!(
+        "\x1B[2K\r{} block #{}{} ({:.1} blocks/s)",
+        action, height, end, blocks_per_second
+    );
+    io::stdout().flush().await?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::{path::PathBuf, time::Instant};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use clap::Parser;
+use tari_core::chain_storage::create_utxo_snapshot;
+use tokio::task;
+
+use super::{CommandContext, HandleCommand};
+
+/// Writes a hash-committed snapshot of the kernels and unspent outputs at a height, which a new pruned node can load
+/// instead of syncing the horizon state from peers (see `blockchain_sync_config.utxo_snapshot_file`)
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// The file to write the snapshot to
+    path: PathBuf,
+    /// The height of the snapshot. Defaults to the current tip
+    #[clap(long)]
+    height: Option<u64>,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.create_utxo_snapshot(args.path, args.height).await
+    }
+}
+
+impl CommandContext {
+    /// Function to process the create-utxo-snapshot command
+    pub async fn create_utxo_snapshot(&mut self, path: PathBuf, height: Option<u64>) -> Result<(), Error> {
+        let height = match height {
+            Some(height) => height,
+            None => self.blockchain_db.get_chain_metadata().await?.best_block_height(),
+        };
+        println!("Creating UTXO snapshot at height #{}...", height);
+        let timer = Instant::now();
+        let db = self.blockchain_db.inner().clone();
+        let snapshot_path = path.clone();
+        let hash = task::spawn_blocking(move || create_utxo_snapshot(&db, height, snapshot_path))
+            .await?
+            .map_err(|e| anyhow!("Could not create the UTXO snapshot: {}", e))?;
+        println!(
+            "Wrote the UTXO snapshot at height #{} to {} in {:.0?}",
+            height,
+            path.display(),
+            timer.elapsed()
+        );
+        println!("Snapshot hash: {}", hash);
+        Ok(())
+    }
+}
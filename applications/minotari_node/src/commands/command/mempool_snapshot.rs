@@ -0,0 +1,81 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::path::PathBuf;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use clap::Parser;
+
+use super::{CommandContext, HandleCommand};
+
+/// Writes the contents of the mempool to a snapshot file
+#[derive(Debug, Parser)]
+pub struct ArgsDump {
+    /// The file to write to. Defaults to the configured mempool snapshot file
+    path: Option<PathBuf>,
+}
+
+#[async_trait]
+impl HandleCommand<ArgsDump> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsDump) -> Result<(), Error> {
+        let path = args
+            .path
+            .unwrap_or_else(|| self.config.base_node.mempool.persistence.snapshot_file.clone());
+        self.dump_mempool(path).await
+    }
+}
+
+/// Loads transactions from a mempool snapshot file into the mempool
+#[derive(Debug, Parser)]
+pub struct ArgsLoad {
+    /// The file to read from. Defaults to the configured mempool snapshot file
+    path: Option<PathBuf>,
+}
+
+#[async_trait]
+impl HandleCommand<ArgsLoad> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsLoad) -> Result<(), Error> {
+        let path = args
+            .path
+            .unwrap_or_else(|| self.config.base_node.mempool.persistence.snapshot_file.clone());
+        self.load_mempool(path).await
+    }
+}
+
+impl CommandContext {
+    /// Function to process the dump-mempool command
+    pub async fn dump_mempool(&mut self, path: PathBuf) -> Result<(), Error> {
+        let num_txs = self.mempool.save_snapshot_to_file(path.clone()).await?;
+        println!("Wrote {} transaction(s) to {}", num_txs, path.display());
+        Ok(())
+    }
+
+    /// Function to process the load-mempool command
+    pub async fn load_mempool(&mut self, path: PathBuf) -> Result<(), Error> {
+        let tip_height = self.blockchain_db.get_chain_metadata().await?.best_block_height();
+        let stats = self.mempool.load_snapshot_from_file(path.clone(), tip_height).await?;
+        println!("Loaded mempool snapshot from {}", path.display());
+        println!("{}", stats);
+        Ok(())
+    }
+}
@@ -22,10 +22,12 @@
 
 mod add_peer;
 mod ban_peer;
+mod block_export;
 mod block_timing;
 mod check_db;
 mod check_for_updates;
 mod create_tls_certs;
+mod create_utxo_snapshot;
 mod dial_peer;
 mod discover_peer;
 mod get_block;
@@ -43,6 +45,7 @@ mod list_headers;
 mod list_peers;
 mod list_reorgs;
 mod list_validator_nodes;
+mod mempool_snapshot;
 mod period_stats;
 mod ping_peer;
 mod quit;
@@ -79,7 +82,7 @@ use tari_core::{
     blocks::ChainHeader,
     chain_storage::{async_db::AsyncBlockchainDb, LMDBDatabase},
     consensus::ConsensusManager,
-    mempool::service::LocalMempoolService,
+    mempool::{service::LocalMempoolService, Mempool},
 };
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
 use tari_shutdown::Shutdown;
@@ -127,6 +130,9 @@ pub enum Command {
     ListConnections(list_connections::Args),
     ListHeaders(list_headers::Args),
     CheckDb(check_db::Args),
+    ExportBlocks(block_export::ArgsExport),
+    ImportBlocks(block_export::ArgsImport),
+    CreateUtxoSnapshot(create_utxo_snapshot::Args),
     PeriodStats(period_stats::Args),
     HeaderStats(header_stats::Args),
     BlockTiming(block_timing::Args),
@@ -138,6 +144,8 @@ pub enum Command {
     GetMempoolStats(get_mempool_stats::Args),
     GetMempoolState(get_mempool_state::Args),
     GetMempoolTx(get_mempool_state::ArgsTx),
+    DumpMempool(mempool_snapshot::ArgsDump),
+    LoadMempool(mempool_snapshot::ArgsLoad),
     Whoami(whoami::Args),
     GetStateInfo(get_state_info::Args),
     GetNetworkStats(get_network_stats::Args),
@@ -171,6 +179,7 @@ pub struct CommandContext {
     liveness: LivenessHandle,
     node_service: LocalNodeCommsInterface,
     mempool_service: LocalMempoolService,
+    mempool: Mempool,
     state_machine_info: watch::Receiver<StatusInfo>,
     pub software_updater: SoftwareUpdaterHandle,
     last_time_full: Instant,
@@ -191,6 +200,7 @@ impl CommandContext {
             liveness: ctx.liveness(),
             node_service: ctx.local_node(),
             mempool_service: ctx.local_mempool(),
+            mempool: ctx.mempool(),
             state_machine_info: ctx.get_state_machine_info_channel(),
             software_updater: ctx.software_updater(),
             last_time_full: Instant::now(),
@@ -235,6 +245,7 @@ impl CommandContext {
                 Command::GetMempoolStats(_) |
                 Command::GetMempoolState(_) |
                 Command::GetMempoolTx(_) |
+                Command::DumpMempool(_) |
                 Command::Status(_) |
                 Command::Watch(_) |
                 Command::ListValidatorNodes(_) |
@@ -245,6 +256,12 @@ impl CommandContext {
                 Command::TestPeerLiveness(_) => 240,
                 // These commands involve intense blockchain db operations and needs a lot of time to complete
                 Command::CheckDb(_) | Command::PeriodStats(_) | Command::RewindBlockchain(_) => 600,
+                // Reads every kernel and unspent output of the chain
+                Command::CreateUtxoSnapshot(_) => 60 * 60,
+                // Every transaction in the snapshot is revalidated
+                Command::LoadMempool(_) => 600,
+                // Exporting or importing a full chain can take hours, an interrupted run is resumed when run again
+                Command::ExportBlocks(_) | Command::ImportBlocks(_) => 24 * 60 * 60,
             };
             let fut = self.handle_command(args.command);
             if let Err(e) = time::timeout(Duration::from_secs(time_out), fut).await? {
@@ -290,6 +307,9 @@ impl HandleCommand<Command> for CommandContext {
             Command::UnbanAllPeers(args) => self.handle_command(args).await,
             Command::ListHeaders(args) => self.handle_command(args).await,
             Command::CheckDb(args) => self.handle_command(args).await,
+            Command::ExportBlocks(args) => self.handle_command(args).await,
+            Command::ImportBlocks(args) => self.handle_command(args).await,
+            Command::CreateUtxoSnapshot(args) => self.handle_command(args).await,
             Command::PeriodStats(args) => self.handle_command(args).await,
             Command::HeaderStats(args) => self.handle_command(args).await,
             Command::BlockTiming(args) => self.handle_command(args).await,
@@ -302,6 +322,8 @@ impl HandleCommand<Command> for CommandContext {
             Command::GetMempoolStats(args) => self.handle_command(args).await,
             Command::GetMempoolState(args) => self.handle_command(args).await,
             Command::GetMempoolTx(args) => self.handle_command(args).await,
+            Command::DumpMempool(args) => self.handle_command(args).await,
+            Command::LoadMempool(args) => self.handle_command(args).await,
             Command::Whoami(args) => self.handle_command(args).await,
             Command::ListBannedPeers(args) => self.handle_command(args).await,
             Command::Quit(args) | Command::Exit(args) => self.handle_command(args).await,
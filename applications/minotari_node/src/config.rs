@@ -39,6 +39,7 @@ use tari_core::{
     base_node::BaseNodeStateMachineConfig,
     chain_storage::BlockchainDatabaseConfig,
     mempool::MempoolConfig,
+    transactions::transaction_components::RangeProofType,
 };
 use tari_p2p::{auto_update::AutoUpdateConfig, P2pConfig, PeerSeedsConfig};
 use tari_storage::lmdb_store::LMDBConfig;
@@ -142,6 +143,14 @@ pub struct BaseNodeConfig {
     pub state_machine: BaseNodeStateMachineConfig,
     /// Obscure GRPC error responses
     pub report_grpc_error: bool,
+    /// Enable the read-only HTTP JSON API for block explorers
+    pub http_api_enabled: bool,
+    /// HTTP JSON API address of the base node, only used when `http_api_enabled = true`
+    pub http_api_address: Multiaddr,
+    /// The maximum number of items returned in a single page by the HTTP JSON API
+    pub http_api_max_page_size: usize,
+    /// The stratum server config settings
+    pub stratum: StratumConfig,
     // Interval to check if the base node is still in sync with the network
     #[serde(with = "serializers::seconds")]
     pub tari_pulse_interval: Duration,
@@ -183,6 +192,10 @@ impl Default for BaseNodeConfig {
             metadata_auto_ping_interval: Duration::from_secs(30),
             state_machine: Default::default(),
             report_grpc_error: false,
+            http_api_enabled: false,
+            http_api_address: "/ip4/127.0.0.1/tcp/18146".parse().unwrap(),
+            http_api_max_page_size: 100,
+            stratum: Default::default(),
             tari_pulse_interval: Duration::from_secs(120),
         }
     }
@@ -211,10 +224,65 @@ impl BaseNodeConfig {
         if !self.lmdb_path.is_absolute() {
             self.lmdb_path = self.data_dir.join(self.lmdb_path.as_path());
         }
+        if !self.mempool.persistence.snapshot_file.is_absolute() {
+            self.mempool.persistence.snapshot_file =
+                self.data_dir.join(self.mempool.persistence.snapshot_file.as_path());
+        }
+        if !self.stratum.share_db_path.is_absolute() {
+            self.stratum.share_db_path = self.data_dir.join(self.stratum.share_db_path.as_path());
+        }
         self.p2p.set_base_path(base_path);
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StratumConfig {
+    /// Enable the stratum server for SHA3x pool mining
+    pub enabled: bool,
+    /// The address the stratum server listens on, only used when `enabled = true`
+    pub listener_address: Multiaddr,
+    /// The Tari address that the coinbase of every block found by the pool is paid to
+    pub pool_wallet_address: String,
+    /// The extra data to store in the coinbase of blocks found by the pool
+    pub coinbase_extra: String,
+    /// Range proof type of the coinbase output - revealed_value or bullet_proof_plus
+    pub range_proof_type: RangeProofType,
+    /// The share difficulty assigned to a worker when it logs in
+    pub initial_share_difficulty: u64,
+    /// The lowest share difficulty that vardiff will assign to a worker
+    pub min_share_difficulty: u64,
+    /// The average time between shares of a worker that vardiff aims for
+    #[serde(with = "serializers::seconds")]
+    pub target_share_time: Duration,
+    /// The time between share difficulty adjustments of a worker
+    #[serde(with = "serializers::seconds")]
+    pub vardiff_retarget_time: Duration,
+    /// The interval at which a new job is built for the current tip, so that new mempool transactions are mined
+    #[serde(with = "serializers::seconds")]
+    pub job_refresh_interval: Duration,
+    /// The sqlite database in which shares are recorded, relative to the data directory
+    pub share_db_path: PathBuf,
+}
+
+impl Default for StratumConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listener_address: "/ip4/127.0.0.1/tcp/18147".parse().unwrap(),
+            pool_wallet_address: String::new(),
+            coinbase_extra: String::new(),
+            range_proof_type: RangeProofType::RevealedValue,
+            initial_share_difficulty: 1_000_000,
+            min_share_difficulty: 1_000,
+            target_share_time: Duration::from_secs(10),
+            vardiff_retarget_time: Duration::from_secs(60),
+            job_refresh_interval: Duration::from_secs(30),
+            share_db_path: PathBuf::from("stratum_shares.db"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DatabaseType {
@@ -53,7 +53,7 @@ use tari_core::{
     chain_storage::ChainStorageError,
     consensus::{emission::Emission, ConsensusManager, NetworkConsensus},
     iterators::NonOverlappingIntegerPairIter,
-    mempool::{service::LocalMempoolService, TxStorageResponse},
+    mempool::{service::LocalMempoolService, TxStorageResponse, DEFAULT_CONFIDENCE_PERCENT, MAX_CONFIRMATION_TARGET},
     proof_of_work::PowAlgorithm,
     transactions::{
         generate_coinbase_with_wallet_output,
@@ -72,11 +72,12 @@ use tari_core::{
 use tari_key_manager::key_manager_service::KeyManagerInterface;
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
 use tari_utilities::{hex::Hex, message_format::MessageFormat, ByteArray};
-use tokio::task;
+use tokio::{sync::broadcast::error::RecvError, task};
 use tonic::{Request, Response, Status};
 
 use crate::{
     builder::BaseNodeContext,
+    chain_events::{ChainEventTopic, ChainEventsHandle},
     grpc::{
         blocks::{block_fees, block_heights, block_size, GET_BLOCKS_MAX_HEIGHTS, GET_BLOCKS_PAGE_SIZE},
         hash_rate::HashRateMovingAverage,
@@ -104,6 +105,9 @@ const LIST_HEADERS_PAGE_SIZE: usize = 10;
 const LIST_HEADERS_DEFAULT_NUM_HEADERS: u64 = 10;
 
 const BLOCK_TIMING_MAX_BLOCKS: u64 = 10_000;
+// The number of chain events buffered per subscriber before the stream applies backpressure to the chain event
+// subscription. A subscriber that falls too far behind misses events, which shows as a gap in the sequence numbers.
+const SUBSCRIBE_CHAIN_EVENTS_BUFFER_SIZE: usize = 100;
 
 pub struct BaseNodeGrpcServer {
     node_service: LocalNodeCommsInterface,
@@ -116,6 +120,7 @@ pub struct BaseNodeGrpcServer {
     liveness: LivenessHandle,
     report_grpc_error: bool,
     tari_pulse: TariPulseHandle,
+    chain_events: ChainEventsHandle,
     config: BaseNodeConfig,
 }
 
@@ -132,6 +137,7 @@ impl BaseNodeGrpcServer {
             liveness: ctx.liveness(),
             report_grpc_error: ctx.get_report_grpc_error(),
             tari_pulse: ctx.tari_pulse(),
+            chain_events: ctx.chain_events(),
             config,
         }
     }
@@ -154,6 +160,7 @@ impl BaseNodeGrpcServer {
             GrpcMethod::GetTipInfo,
             GrpcMethod::Identify,
             GrpcMethod::GetSyncProgress,
+            GrpcMethod::SubscribeChainEvents,
         ];
 
         let second_layer_methods = [
@@ -219,6 +226,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type ListHeadersStream = mpsc::Receiver<Result<tari_rpc::BlockHeaderResponse, Status>>;
     type SearchKernelsStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
     type SearchUtxosStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type SubscribeChainEventsStream = mpsc::Receiver<Result<tari_rpc::ChainEvent, Status>>;
 
     #[allow(clippy::too_many_lines)]
     async fn get_network_difficulty(
@@ -1439,8 +1447,10 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             obscure_error_if_true(report_error_flag, Status::internal(e.to_string()))
         })?;
         let response = match res {
-            TxStorageResponse::UnconfirmedPool => tari_rpc::SubmitTransactionResponse {
-                result: tari_rpc::SubmitTransactionResult::Accepted.into(),
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplacedByFee => {
+                tari_rpc::SubmitTransactionResponse {
+                    result: tari_rpc::SubmitTransactionResult::Accepted.into(),
+                }
             },
             TxStorageResponse::ReorgPool |
             TxStorageResponse::NotStoredAlreadySpent |
@@ -1451,6 +1461,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredReplacementFeeTooLow |
             TxStorageResponse::NotStoredTimeLocked => tari_rpc::SubmitTransactionResponse {
                 result: tari_rpc::SubmitTransactionResult::Rejected.into(),
             },
@@ -1520,8 +1531,10 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
                 obscure_error_if_true(report_error_flag, Status::internal(e.to_string()))
             })?;
         let response = match res {
-            TxStorageResponse::UnconfirmedPool => tari_rpc::TransactionStateResponse {
-                result: tari_rpc::TransactionLocation::Mempool.into(),
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplacedByFee => {
+                tari_rpc::TransactionStateResponse {
+                    result: tari_rpc::TransactionLocation::Mempool.into(),
+                }
             },
             TxStorageResponse::ReorgPool | TxStorageResponse::NotStoredAlreadySpent => {
                 tari_rpc::TransactionStateResponse {
@@ -1534,6 +1547,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredReplacementFeeTooLow |
             TxStorageResponse::NotStoredTimeLocked |
             TxStorageResponse::NotStoredAlreadyMined => tari_rpc::TransactionStateResponse {
                 result: tari_rpc::TransactionLocation::NotStored.into(),
@@ -2277,6 +2291,120 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         Ok(Response::new(response))
     }
 
+    async fn estimate_fee(
+        &self,
+        request: Request<tari_rpc::EstimateFeeRequest>,
+    ) -> Result<Response<tari_rpc::EstimateFeeResponse>, Status> {
+        self.check_method_enabled(GrpcMethod::EstimateFee)?;
+        let request = request.into_inner();
+        let report_error_flag = self.report_error_flag();
+        if request.confirmation_target == 0 || request.confirmation_target > MAX_CONFIRMATION_TARGET {
+            return Err(Status::invalid_argument(format!(
+                "confirmation_target must be between 1 and {}",
+                MAX_CONFIRMATION_TARGET
+            )));
+        }
+        if request.confidence_percent > 100 {
+            return Err(Status::invalid_argument(
+                "confidence_percent must be less than or equal to 100",
+            ));
+        }
+        let confidence_percent = if request.confidence_percent == 0 {
+            DEFAULT_CONFIDENCE_PERCENT
+        } else {
+            request.confidence_percent
+        };
+
+        let mut handler = self.node_service.clone();
+        let meta = handler
+            .get_metadata()
+            .await
+            .map_err(|e| obscure_error_if_true(report_error_flag, Status::internal(e.to_string())))?;
+        let mut mempool_handle = self.mempool_service.clone();
+        let estimate = mempool_handle
+            .estimate_fee(
+                request.confirmation_target,
+                confidence_percent,
+                meta.best_block_height(),
+            )
+            .await
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "Error estimating fee: {}", e);
+                obscure_error_if_true(report_error_flag, Status::internal(e.to_string()))
+            })?;
+
+        Ok(Response::new(tari_rpc::EstimateFeeResponse {
+            fee_per_gram: estimate.fee_per_gram.as_u64(),
+            confirmation_target: estimate.confirmation_target,
+            is_historical: estimate.is_historical,
+        }))
+    }
+
+    async fn get_output_smt_proofs(
+        &self,
+        request: Request<tari_rpc::GetOutputSmtProofsRequest>,
+    ) -> Result<Response<tari_rpc::GetOutputSmtProofsResponse>, Status> {
+        self.check_method_enabled(GrpcMethod::GetOutputSmtProofs)?;
+        let request = request.into_inner();
+        let report_error_flag = self.report_error_flag();
+        let header_hash = FixedHash::try_from(request.header_hash).map_err(|e| {
+            obscure_error_if_true(
+                report_error_flag,
+                Status::invalid_argument(format!("Malformed header hash '{}'", e)),
+            )
+        })?;
+        let output_hashes = request
+            .output_hashes
+            .into_iter()
+            .map(FixedHash::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                obscure_error_if_true(
+                    report_error_flag,
+                    Status::invalid_argument(format!("Malformed output hash '{}'", e)),
+                )
+            })?;
+
+        let mut handler = self.node_service.clone();
+        let proofs = match handler.fetch_output_smt_proofs(output_hashes, header_hash).await {
+            Ok(proofs) => proofs,
+            Err(CommsInterfaceError::ChainStorageError(ChainStorageError::InvalidArguments { message, .. })) => {
+                return Err(obscure_error_if_true(
+                    report_error_flag,
+                    Status::failed_precondition(message),
+                ));
+            },
+            Err(e) => {
+                return Err(obscure_error_if_true(
+                    report_error_flag,
+                    Status::internal(e.to_string()),
+                ));
+            },
+        };
+        let header = handler
+            .get_header_by_hash(header_hash)
+            .await
+            .map_err(|e| obscure_error_if_true(report_error_flag, Status::internal(e.to_string())))?
+            .ok_or_else(|| {
+                obscure_error_if_true(
+                    report_error_flag,
+                    Status::not_found(format!("Header not found with hash `{}`", header_hash)),
+                )
+            })?;
+
+        Ok(Response::new(tari_rpc::GetOutputSmtProofsResponse {
+            proofs: proofs
+                .into_iter()
+                .map(|(output_hash, mined_at_height, proof)| tari_rpc::OutputSmtProof {
+                    output_hash: output_hash.to_vec(),
+                    mined_at_height,
+                    ..proof.into()
+                })
+                .collect(),
+            header: Some(header.into_header().into()),
+        }))
+    }
+
     async fn get_shard_key(
         &self,
         request: Request<tari_rpc::GetShardKeyRequest>,
@@ -2558,6 +2686,61 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         );
         Ok(Response::new(rx))
     }
+
+    async fn subscribe_chain_events(
+        &self,
+        request: Request<tari_rpc::SubscribeChainEventsRequest>,
+    ) -> Result<Response<Self::SubscribeChainEventsStream>, Status> {
+        self.check_method_enabled(GrpcMethod::SubscribeChainEvents)?;
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        let topics = request.topics().map(ChainEventTopic::from).collect::<Vec<_>>();
+        trace!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for SubscribeChainEvents: topics: {:?}",
+            topics
+        );
+
+        let mut events = self.chain_events.subscribe();
+        let (mut tx, rx) = mpsc::channel(SUBSCRIBE_CHAIN_EVENTS_BUFFER_SIZE);
+
+        task::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(n)) => {
+                        // The subscriber sees the missed events as a gap in the sequence numbers
+                        debug!(
+                            target: LOG_TARGET,
+                            "[subscribe_chain_events] Subscriber fell behind and missed {} event(s)", n
+                        );
+                        continue;
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                if !topics.is_empty() && !topics.contains(&event.topic) {
+                    continue;
+                }
+
+                let event = tari_rpc::ChainEvent::try_from(&*event).map_err(|e| {
+                    warn!(target: LOG_TARGET, "Error converting chain event for GRPC: {}", e);
+                    obscure_error_if_true(
+                        report_error_flag,
+                        Status::internal(format!("Error converting chain event: {}", e)),
+                    )
+                });
+                if tx.send(event).await.is_err() {
+                    trace!(
+                        target: LOG_TARGET,
+                        "[subscribe_chain_events] Client has disconnected"
+                    );
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(rx))
+    }
 }
 
 enum BlockGroupType {
@@ -0,0 +1,89 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::TryFrom;
+
+use minotari_app_grpc::tari_rpc;
+
+use crate::chain_events::{ChainEvent, ChainEventPayload, ChainEventTopic};
+
+impl From<tari_rpc::ChainEventTopic> for ChainEventTopic {
+    fn from(topic: tari_rpc::ChainEventTopic) -> Self {
+        match topic {
+            tari_rpc::ChainEventTopic::BlockHash => ChainEventTopic::BlockHash,
+            tari_rpc::ChainEventTopic::Block => ChainEventTopic::Block,
+            tari_rpc::ChainEventTopic::Reorg => ChainEventTopic::Reorg,
+            tari_rpc::ChainEventTopic::MempoolTransaction => ChainEventTopic::MempoolTransaction,
+        }
+    }
+}
+
+impl From<ChainEventTopic> for tari_rpc::ChainEventTopic {
+    fn from(topic: ChainEventTopic) -> Self {
+        match topic {
+            ChainEventTopic::BlockHash => tari_rpc::ChainEventTopic::BlockHash,
+            ChainEventTopic::Block => tari_rpc::ChainEventTopic::Block,
+            ChainEventTopic::Reorg => tari_rpc::ChainEventTopic::Reorg,
+            ChainEventTopic::MempoolTransaction => tari_rpc::ChainEventTopic::MempoolTransaction,
+        }
+    }
+}
+
+impl TryFrom<&ChainEvent> for tari_rpc::ChainEvent {
+    type Error = String;
+
+    fn try_from(event: &ChainEvent) -> Result<Self, Self::Error> {
+        let payload = match &event.payload {
+            ChainEventPayload::BlockHash { height, hash } => {
+                tari_rpc::chain_event::Event::BlockHash(tari_rpc::BlockHashEvent {
+                    height: *height,
+                    hash: hash.to_vec(),
+                })
+            },
+            ChainEventPayload::Block(block) => {
+                tari_rpc::chain_event::Event::Block(tari_rpc::Block::try_from(block.as_ref().clone())?)
+            },
+            ChainEventPayload::Reorg { reorg, added, removed } => {
+                tari_rpc::chain_event::Event::Reorg(tari_rpc::ReorgEvent {
+                    new_height: reorg.new_height,
+                    new_hash: reorg.new_hash.to_vec(),
+                    prev_height: reorg.prev_height,
+                    prev_hash: reorg.prev_hash.to_vec(),
+                    num_blocks_added: reorg.num_blocks_added,
+                    num_blocks_removed: reorg.num_blocks_removed,
+                    added_block_hashes: added.iter().map(|h| h.to_vec()).collect(),
+                    removed_block_hashes: removed.iter().map(|h| h.to_vec()).collect(),
+                    local_time: u64::try_from(reorg.local_time.timestamp()).unwrap_or_default(),
+                })
+            },
+            ChainEventPayload::MempoolTransaction(tx) => {
+                tari_rpc::chain_event::Event::MempoolTransaction(tari_rpc::Transaction::try_from(tx.clone())?)
+            },
+        };
+
+        Ok(Self {
+            topic: tari_rpc::ChainEventTopic::from(event.topic).into(),
+            sequence: event.sequence,
+            event: Some(payload),
+        })
+    }
+}
@@ -61,15 +61,18 @@ pub enum GrpcMethod {
     GetNetworkStatus,
     ListConnectedPeers,
     GetMempoolStats,
+    EstimateFee,
+    GetOutputSmtProofs,
     GetActiveValidatorNodes,
     GetShardKey,
     GetTemplateRegistrations,
     GetSideChainUtxos,
+    SubscribeChainEvents,
 }
 
 impl GrpcMethod {
     /// All the GRPC methods as a fixed array
-    pub const ALL_VARIANTS: [GrpcMethod; 36] = [
+    pub const ALL_VARIANTS: [GrpcMethod; 39] = [
         GrpcMethod::ListHeaders,
         GrpcMethod::GetHeaderByHash,
         GrpcMethod::GetBlocks,
@@ -102,15 +105,18 @@ impl GrpcMethod {
         GrpcMethod::GetNetworkStatus,
         GrpcMethod::ListConnectedPeers,
         GrpcMethod::GetMempoolStats,
+        GrpcMethod::EstimateFee,
+        GrpcMethod::GetOutputSmtProofs,
         GrpcMethod::GetActiveValidatorNodes,
         GrpcMethod::GetShardKey,
         GrpcMethod::GetTemplateRegistrations,
         GrpcMethod::GetSideChainUtxos,
+        GrpcMethod::SubscribeChainEvents,
     ];
 }
 
 impl IntoIterator for GrpcMethod {
-    type IntoIter = std::array::IntoIter<GrpcMethod, 36>;
+    type IntoIter = std::array::IntoIter<GrpcMethod, 39>;
     type Item = GrpcMethod;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -157,10 +163,13 @@ impl FromStr for GrpcMethod {
             "get_network_status" => Ok(GrpcMethod::GetNetworkStatus),
             "list_connected_peers" => Ok(GrpcMethod::ListConnectedPeers),
             "get_mempool_stats" => Ok(GrpcMethod::GetMempoolStats),
+            "estimate_fee" => Ok(GrpcMethod::EstimateFee),
+            "get_output_smt_proofs" => Ok(GrpcMethod::GetOutputSmtProofs),
             "get_active_validator_nodes" => Ok(GrpcMethod::GetActiveValidatorNodes),
             "get_shard_key" => Ok(GrpcMethod::GetShardKey),
             "get_template_registrations" => Ok(GrpcMethod::GetTemplateRegistrations),
             "get_side_chain_utxos" => Ok(GrpcMethod::GetSideChainUtxos),
+            "subscribe_chain_events" => Ok(GrpcMethod::SubscribeChainEvents),
             _ => Err(format!("'{}' not supported", s)),
         }
     }
@@ -252,10 +261,13 @@ mod tests {
                 GrpcMethod::GetNetworkStatus => count += 1,
                 GrpcMethod::ListConnectedPeers => count += 1,
                 GrpcMethod::GetMempoolStats => count += 1,
+                GrpcMethod::EstimateFee => count += 1,
+                GrpcMethod::GetOutputSmtProofs => count += 1,
                 GrpcMethod::GetActiveValidatorNodes => count += 1,
                 GrpcMethod::GetShardKey => count += 1,
                 GrpcMethod::GetTemplateRegistrations => count += 1,
                 GrpcMethod::GetSideChainUtxos => count += 1,
+                GrpcMethod::SubscribeChainEvents => count += 1,
             }
         }
         assert_eq!(count, GrpcMethod::ALL_VARIANTS.len());
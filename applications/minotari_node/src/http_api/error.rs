@@ -0,0 +1,60 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_core::{
+    base_node::comms_interface::CommsInterfaceError,
+    chain_storage::ChainStorageError,
+    mempool::MempoolServiceError,
+};
+use warp::{http::StatusCode, reject::Reject};
+
+#[derive(Debug, thiserror::Error)]
+pub enum HttpApiError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("Chain storage error: {0}")]
+    ChainStorageError(#[from] ChainStorageError),
+    #[error("Node service error: {0}")]
+    CommsInterfaceError(#[from] CommsInterfaceError),
+    #[error("Mempool service error: {0}")]
+    MempoolServiceError(#[from] MempoolServiceError),
+    #[error("Conversion error: {0}")]
+    ConversionError(String),
+}
+
+impl HttpApiError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            HttpApiError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            HttpApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            HttpApiError::ChainStorageError(ChainStorageError::ValueNotFound { .. }) => StatusCode::NOT_FOUND,
+            HttpApiError::ChainStorageError(_) |
+            HttpApiError::CommsInterfaceError(_) |
+            HttpApiError::MempoolServiceError(_) |
+            HttpApiError::ConversionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl Reject for HttpApiError {}
@@ -0,0 +1,190 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A read-only HTTP JSON API for block explorers. Blocks, kernels, outputs, mempool transactions and chain metadata
+//! are served in the same shape as the corresponding base node gRPC messages.
+//!
+//! Routes:
+//! - `GET /chain/metadata`
+//! - `GET /blocks?start=<height>&limit=<n>`
+//! - `GET /blocks/<height>`
+//! - `GET /blocks/hash/<hash>`
+//! - `GET /kernels/<excess_sig_public_nonce>/<excess_sig_signature>`
+//! - `GET /outputs/<commitment>`
+//! - `GET /mempool?start=<index>&limit=<n>`
+
+mod error;
+mod models;
+mod service;
+
+use std::convert::Infallible;
+
+use log::*;
+use serde::{Deserialize, Serialize};
+pub use service::HttpApiService;
+use tari_comms::{multiaddr::Multiaddr, utils::multiaddr::multiaddr_to_socketaddr};
+use tari_shutdown::ShutdownSignal;
+use warp::{http::StatusCode, reply::Json, Filter, Rejection, Reply};
+
+use crate::http_api::error::HttpApiError;
+
+const LOG_TARGET: &str = "minotari::base_node::http_api";
+
+#[derive(Debug, Deserialize)]
+struct PageQuery {
+    start: Option<u64>,
+    limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Runs the HTTP API server until the shutdown signal is triggered
+pub async fn run_http_api(
+    service: HttpApiService,
+    address: Multiaddr,
+    shutdown_signal: ShutdownSignal,
+) -> Result<(), anyhow::Error> {
+    info!(target: LOG_TARGET, "Starting HTTP API on {}", address);
+
+    let address = multiaddr_to_socketaddr(&address)?;
+    let routes = routes(service).with(warp::log(LOG_TARGET));
+    let (_, server) = warp::serve(routes)
+        .try_bind_with_graceful_shutdown(address, shutdown_signal)
+        .map_err(|err| {
+            error!(target: LOG_TARGET, "HTTP API failed to bind to {}: {}", address, err);
+            err
+        })?;
+    server.await;
+
+    info!(target: LOG_TARGET, "Stopping HTTP API");
+    Ok(())
+}
+
+fn routes(service: HttpApiService) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone {
+    let chain_metadata = warp::path!("chain" / "metadata")
+        .and(with(service.clone()))
+        .and_then(chain_metadata);
+    let blocks = warp::path!("blocks")
+        .and(warp::query::<PageQuery>())
+        .and(with(service.clone()))
+        .and_then(blocks);
+    let block_by_height = warp::path!("blocks" / u64)
+        .and(with(service.clone()))
+        .and_then(block_by_height);
+    let block_by_hash = warp::path!("blocks" / "hash" / String)
+        .and(with(service.clone()))
+        .and_then(block_by_hash);
+    let block_with_kernel = warp::path!("kernels" / String / String)
+        .and(with(service.clone()))
+        .and_then(block_with_kernel);
+    let block_with_output = warp::path!("outputs" / String)
+        .and(with(service.clone()))
+        .and_then(block_with_output);
+    let mempool = warp::path!("mempool")
+        .and(warp::query::<PageQuery>())
+        .and(with(service))
+        .and_then(mempool_transactions);
+
+    warp::get()
+        .and(
+            chain_metadata
+                .or(blocks)
+                .or(block_by_height)
+                .or(block_by_hash)
+                .or(block_with_kernel)
+                .or(block_with_output)
+                .or(mempool),
+        )
+        .recover(handle_rejection)
+}
+
+async fn chain_metadata(service: HttpApiService) -> Result<Json, Rejection> {
+    reply(service.chain_metadata().await)
+}
+
+async fn blocks(query: PageQuery, service: HttpApiService) -> Result<Json, Rejection> {
+    reply(service.blocks(query.start.unwrap_or(0), query.limit).await)
+}
+
+async fn block_by_height(height: u64, service: HttpApiService) -> Result<Json, Rejection> {
+    reply(service.block_by_height(height).await)
+}
+
+async fn block_by_hash(hash: String, service: HttpApiService) -> Result<Json, Rejection> {
+    reply(service.block_by_hash(&hash).await)
+}
+
+async fn block_with_kernel(
+    public_nonce: String,
+    signature: String,
+    service: HttpApiService,
+) -> Result<Json, Rejection> {
+    reply(service.block_with_kernel(&public_nonce, &signature).await)
+}
+
+async fn block_with_output(commitment: String, service: HttpApiService) -> Result<Json, Rejection> {
+    reply(service.block_with_output(&commitment).await)
+}
+
+async fn mempool_transactions(query: PageQuery, service: HttpApiService) -> Result<Json, Rejection> {
+    reply(
+        service
+            .mempool_transactions(query.start.unwrap_or(0), query.limit)
+            .await,
+    )
+}
+
+fn reply<T: Serialize>(result: Result<T, HttpApiError>) -> Result<Json, Rejection> {
+    result
+        .map(|value| warp::reply::json(&value))
+        .map_err(warp::reject::custom)
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let (status, error) = if let Some(err) = rejection.find::<HttpApiError>() {
+        let status = err.status_code();
+        if status.is_server_error() {
+            warn!(target: LOG_TARGET, "HTTP API request failed: {}", err);
+        }
+        (status, err.to_string())
+    } else if let Some(err) = rejection.find::<warp::reject::InvalidQuery>() {
+        (StatusCode::BAD_REQUEST, err.to_string())
+    } else if rejection.find::<warp::reject::MethodNotAllowed>().is_some() {
+        (StatusCode::METHOD_NOT_ALLOWED, "Method not allowed".to_string())
+    } else if rejection.is_not_found() {
+        (StatusCode::NOT_FOUND, "Not found".to_string())
+    } else {
+        warn!(target: LOG_TARGET, "Unhandled HTTP API rejection: {:?}", rejection);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+    };
+    Ok(warp::reply::with_status(
+        warp::reply::json(&ErrorResponse { error }),
+        status,
+    ))
+}
+
+fn with<T: Clone + Send>(t: T) -> impl Filter<Extract = (T,), Error = Infallible> + Clone {
+    warp::any().map(move || t.clone())
+}
@@ -0,0 +1,436 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! JSON representations of the base node gRPC messages served by the HTTP API. Each type mirrors its gRPC
+//! counterpart field for field, with byte fields encoded as hex strings.
+
+use minotari_app_grpc::tari_rpc as grpc;
+use serde::Serialize;
+use tari_utilities::hex::to_hex;
+
+/// A single page of results. `next` is the value of the `start` query parameter that returns the following page, or
+/// `None` if this is the last page.
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetaData {
+    pub best_block_height: u64,
+    pub best_block_hash: String,
+    pub accumulated_difficulty: String,
+    pub pruned_height: u64,
+    pub timestamp: u64,
+}
+
+impl From<grpc::MetaData> for MetaData {
+    fn from(metadata: grpc::MetaData) -> Self {
+        Self {
+            best_block_height: metadata.best_block_height,
+            best_block_hash: to_hex(&metadata.best_block_hash),
+            accumulated_difficulty: to_hex(&metadata.accumulated_difficulty),
+            pruned_height: metadata.pruned_height,
+            timestamp: metadata.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoricalBlock {
+    pub confirmations: u64,
+    pub block: Option<Block>,
+}
+
+impl From<grpc::HistoricalBlock> for HistoricalBlock {
+    fn from(block: grpc::HistoricalBlock) -> Self {
+        Self {
+            confirmations: block.confirmations,
+            block: block.block.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Block {
+    pub header: Option<BlockHeader>,
+    pub body: Option<AggregateBody>,
+}
+
+impl From<grpc::Block> for Block {
+    fn from(block: grpc::Block) -> Self {
+        Self {
+            header: block.header.map(Into::into),
+            body: block.body.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockHeader {
+    pub hash: String,
+    pub version: u32,
+    pub height: u64,
+    pub prev_hash: String,
+    pub timestamp: u64,
+    pub output_mr: String,
+    pub block_output_mr: String,
+    pub kernel_mr: String,
+    pub input_mr: String,
+    pub total_kernel_offset: String,
+    pub nonce: u64,
+    pub pow: Option<ProofOfWork>,
+    pub kernel_mmr_size: u64,
+    pub output_mmr_size: u64,
+    pub total_script_offset: String,
+    pub validator_node_mr: String,
+    pub validator_node_size: u64,
+}
+
+impl From<grpc::BlockHeader> for BlockHeader {
+    fn from(header: grpc::BlockHeader) -> Self {
+        Self {
+            hash: to_hex(&header.hash),
+            version: header.version,
+            height: header.height,
+            prev_hash: to_hex(&header.prev_hash),
+            timestamp: header.timestamp,
+            output_mr: to_hex(&header.output_mr),
+            block_output_mr: to_hex(&header.block_output_mr),
+            kernel_mr: to_hex(&header.kernel_mr),
+            input_mr: to_hex(&header.input_mr),
+            total_kernel_offset: to_hex(&header.total_kernel_offset),
+            nonce: header.nonce,
+            pow: header.pow.map(Into::into),
+            kernel_mmr_size: header.kernel_mmr_size,
+            output_mmr_size: header.output_mmr_size,
+            total_script_offset: to_hex(&header.total_script_offset),
+            validator_node_mr: to_hex(&header.validator_node_mr),
+            validator_node_size: header.validator_node_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofOfWork {
+    pub pow_algo: u64,
+    pub pow_data: String,
+}
+
+impl From<grpc::ProofOfWork> for ProofOfWork {
+    fn from(pow: grpc::ProofOfWork) -> Self {
+        Self {
+            pow_algo: pow.pow_algo,
+            pow_data: to_hex(&pow.pow_data),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transaction {
+    pub offset: String,
+    pub body: Option<AggregateBody>,
+    pub script_offset: String,
+}
+
+impl From<grpc::Transaction> for Transaction {
+    fn from(tx: grpc::Transaction) -> Self {
+        Self {
+            offset: to_hex(&tx.offset),
+            body: tx.body.map(Into::into),
+            script_offset: to_hex(&tx.script_offset),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregateBody {
+    pub inputs: Vec<TransactionInput>,
+    pub outputs: Vec<TransactionOutput>,
+    pub kernels: Vec<TransactionKernel>,
+}
+
+impl From<grpc::AggregateBody> for AggregateBody {
+    fn from(body: grpc::AggregateBody) -> Self {
+        Self {
+            inputs: body.inputs.into_iter().map(Into::into).collect(),
+            outputs: body.outputs.into_iter().map(Into::into).collect(),
+            kernels: body.kernels.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionInput {
+    pub features: Option<OutputFeatures>,
+    pub commitment: String,
+    pub hash: String,
+    pub script: String,
+    pub input_data: String,
+    pub script_signature: Option<ComAndPubSignature>,
+    pub sender_offset_public_key: String,
+    pub output_hash: String,
+    pub covenant: String,
+    pub version: u32,
+    pub encrypted_data: String,
+    pub minimum_value_promise: u64,
+    pub metadata_signature: Option<ComAndPubSignature>,
+    pub rangeproof_hash: String,
+}
+
+impl From<grpc::TransactionInput> for TransactionInput {
+    fn from(input: grpc::TransactionInput) -> Self {
+        Self {
+            features: input.features.map(Into::into),
+            commitment: to_hex(&input.commitment),
+            hash: to_hex(&input.hash),
+            script: to_hex(&input.script),
+            input_data: to_hex(&input.input_data),
+            script_signature: input.script_signature.map(Into::into),
+            sender_offset_public_key: to_hex(&input.sender_offset_public_key),
+            output_hash: to_hex(&input.output_hash),
+            covenant: to_hex(&input.covenant),
+            version: input.version,
+            encrypted_data: to_hex(&input.encrypted_data),
+            minimum_value_promise: input.minimum_value_promise,
+            metadata_signature: input.metadata_signature.map(Into::into),
+            rangeproof_hash: to_hex(&input.rangeproof_hash),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionOutput {
+    pub features: Option<OutputFeatures>,
+    pub commitment: String,
+    pub range_proof: Option<RangeProof>,
+    pub hash: String,
+    pub script: String,
+    pub sender_offset_public_key: String,
+    pub metadata_signature: Option<ComAndPubSignature>,
+    pub covenant: String,
+    pub version: u32,
+    pub encrypted_data: String,
+    pub minimum_value_promise: u64,
+}
+
+impl From<grpc::TransactionOutput> for TransactionOutput {
+    fn from(output: grpc::TransactionOutput) -> Self {
+        Self {
+            features: output.features.map(Into::into),
+            commitment: to_hex(&output.commitment),
+            range_proof: output.range_proof.map(Into::into),
+            hash: to_hex(&output.hash),
+            script: to_hex(&output.script),
+            sender_offset_public_key: to_hex(&output.sender_offset_public_key),
+            metadata_signature: output.metadata_signature.map(Into::into),
+            covenant: to_hex(&output.covenant),
+            version: output.version,
+            encrypted_data: to_hex(&output.encrypted_data),
+            minimum_value_promise: output.minimum_value_promise,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionKernel {
+    pub features: u32,
+    pub fee: u64,
+    pub lock_height: u64,
+    pub excess: String,
+    pub excess_sig: Option<Signature>,
+    pub hash: String,
+    pub version: u32,
+    pub burn_commitment: String,
+}
+
+impl From<grpc::TransactionKernel> for TransactionKernel {
+    fn from(kernel: grpc::TransactionKernel) -> Self {
+        Self {
+            features: kernel.features,
+            fee: kernel.fee,
+            lock_height: kernel.lock_height,
+            excess: to_hex(&kernel.excess),
+            excess_sig: kernel.excess_sig.map(Into::into),
+            hash: to_hex(&kernel.hash),
+            version: kernel.version,
+            burn_commitment: to_hex(&kernel.burn_commitment),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputFeatures {
+    pub version: u32,
+    pub output_type: u32,
+    pub maturity: u64,
+    pub coinbase_extra: String,
+    pub sidechain_feature: Option<SideChainFeature>,
+    pub range_proof_type: u32,
+}
+
+impl From<grpc::OutputFeatures> for OutputFeatures {
+    fn from(features: grpc::OutputFeatures) -> Self {
+        Self {
+            version: features.version,
+            output_type: features.output_type,
+            maturity: features.maturity,
+            coinbase_extra: to_hex(&features.coinbase_extra),
+            sidechain_feature: features
+                .sidechain_feature
+                .and_then(|f| f.side_chain_feature)
+                .map(Into::into),
+            range_proof_type: features.range_proof_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SideChainFeature {
+    ValidatorNodeRegistration {
+        public_key: String,
+        signature: Option<Signature>,
+    },
+    TemplateRegistration {
+        author_public_key: String,
+        author_signature: Option<Signature>,
+        template_name: String,
+        template_version: u32,
+        template_type: Option<TemplateType>,
+        build_info: Option<BuildInfo>,
+        binary_sha: String,
+        binary_url: String,
+    },
+    ConfidentialOutput {
+        claim_public_key: String,
+    },
+}
+
+impl From<grpc::side_chain_feature::SideChainFeature> for SideChainFeature {
+    fn from(feature: grpc::side_chain_feature::SideChainFeature) -> Self {
+        match feature {
+            grpc::side_chain_feature::SideChainFeature::ValidatorNodeRegistration(reg) => {
+                SideChainFeature::ValidatorNodeRegistration {
+                    public_key: to_hex(&reg.public_key),
+                    signature: reg.signature.map(Into::into),
+                }
+            },
+            grpc::side_chain_feature::SideChainFeature::TemplateRegistration(reg) => {
+                SideChainFeature::TemplateRegistration {
+                    author_public_key: to_hex(&reg.author_public_key),
+                    author_signature: reg.author_signature.map(Into::into),
+                    template_name: reg.template_name,
+                    template_version: reg.template_version,
+                    template_type: reg.template_type.and_then(|t| t.template_type).map(Into::into),
+                    build_info: reg.build_info.map(|info| BuildInfo {
+                        repo_url: info.repo_url,
+                        commit_hash: to_hex(&info.commit_hash),
+                    }),
+                    binary_sha: to_hex(&reg.binary_sha),
+                    binary_url: reg.binary_url,
+                }
+            },
+            grpc::side_chain_feature::SideChainFeature::ConfidentialOutput(data) => {
+                SideChainFeature::ConfidentialOutput {
+                    claim_public_key: to_hex(&data.claim_public_key),
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateType {
+    Wasm { abi_version: u32 },
+    Flow,
+    Manifest,
+}
+
+impl From<grpc::template_type::TemplateType> for TemplateType {
+    fn from(template_type: grpc::template_type::TemplateType) -> Self {
+        match template_type {
+            grpc::template_type::TemplateType::Wasm(info) => TemplateType::Wasm {
+                abi_version: info.abi_version,
+            },
+            grpc::template_type::TemplateType::Flow(_) => TemplateType::Flow,
+            grpc::template_type::TemplateType::Manifest(_) => TemplateType::Manifest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub repo_url: String,
+    pub commit_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeProof {
+    pub proof_bytes: String,
+}
+
+impl From<grpc::RangeProof> for RangeProof {
+    fn from(proof: grpc::RangeProof) -> Self {
+        Self {
+            proof_bytes: to_hex(&proof.proof_bytes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Signature {
+    pub public_nonce: String,
+    pub signature: String,
+}
+
+impl From<grpc::Signature> for Signature {
+    fn from(sig: grpc::Signature) -> Self {
+        Self {
+            public_nonce: to_hex(&sig.public_nonce),
+            signature: to_hex(&sig.signature),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComAndPubSignature {
+    pub ephemeral_commitment: String,
+    pub ephemeral_pubkey: String,
+    pub u_a: String,
+    pub u_x: String,
+    pub u_y: String,
+}
+
+impl From<grpc::ComAndPubSignature> for ComAndPubSignature {
+    fn from(sig: grpc::ComAndPubSignature) -> Self {
+        Self {
+            ephemeral_commitment: to_hex(&sig.ephemeral_commitment),
+            ephemeral_pubkey: to_hex(&sig.ephemeral_pubkey),
+            u_a: to_hex(&sig.u_a),
+            u_x: to_hex(&sig.u_x),
+            u_y: to_hex(&sig.u_y),
+        }
+    }
+}
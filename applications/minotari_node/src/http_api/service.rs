@@ -0,0 +1,217 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, convert::TryFrom, ops::Range};
+
+use minotari_app_grpc::tari_rpc as grpc;
+use tari_common_types::types::{Commitment, FixedHash, PrivateKey, PublicKey, Signature};
+use tari_core::{
+    base_node::LocalNodeCommsInterface,
+    blocks::HistoricalBlock,
+    chain_storage::{AsyncBlockchainDb, LMDBDatabase},
+    mempool::service::LocalMempoolService,
+};
+use tari_utilities::hex::Hex;
+
+use crate::{
+    builder::BaseNodeContext,
+    http_api::{error::HttpApiError, models},
+};
+
+/// Serves the read-only queries of the HTTP API from the blockchain database, node service and mempool service.
+#[derive(Clone)]
+pub struct HttpApiService {
+    db: AsyncBlockchainDb<LMDBDatabase>,
+    node_service: LocalNodeCommsInterface,
+    mempool_service: LocalMempoolService,
+    max_page_size: u64,
+}
+
+impl HttpApiService {
+    pub fn from_base_node_context(ctx: &BaseNodeContext, max_page_size: usize) -> Self {
+        Self {
+            db: ctx.blockchain_db().into(),
+            node_service: ctx.local_node(),
+            mempool_service: ctx.local_mempool(),
+            max_page_size: cmp::max(max_page_size, 1) as u64,
+        }
+    }
+
+    pub async fn chain_metadata(&self) -> Result<models::MetaData, HttpApiError> {
+        let metadata = self.db.get_chain_metadata().await?;
+        Ok(grpc::MetaData::from(metadata).into())
+    }
+
+    /// Returns a page of blocks in ascending height order, starting at height `start`.
+    pub async fn blocks(
+        &self,
+        start: u64,
+        limit: Option<u64>,
+    ) -> Result<models::Page<models::HistoricalBlock>, HttpApiError> {
+        let tip_height = self.db.get_chain_metadata().await?.best_block_height();
+        let (range, next) = page_range(start, limit, self.max_page_size, tip_height.saturating_add(1));
+        if range.is_empty() {
+            return Ok(models::Page { items: vec![], next });
+        }
+
+        let blocks = self
+            .node_service
+            .clone()
+            .get_blocks(range.start..=range.end - 1, false)
+            .await?;
+        let items = blocks.into_iter().map(convert_block).collect::<Result<Vec<_>, _>>()?;
+        Ok(models::Page { items, next })
+    }
+
+    pub async fn block_by_height(&self, height: u64) -> Result<models::HistoricalBlock, HttpApiError> {
+        let block = self.db.fetch_block(height, false).await?;
+        convert_block(block)
+    }
+
+    pub async fn block_by_hash(&self, hash: &str) -> Result<models::HistoricalBlock, HttpApiError> {
+        let hash = FixedHash::from_hex(hash).map_err(|e| HttpApiError::InvalidRequest(format!("Block hash: {}", e)))?;
+        let block = self
+            .db
+            .fetch_block_by_hash(hash, false)
+            .await?
+            .ok_or_else(|| HttpApiError::NotFound(format!("Block {}", hash)))?;
+        convert_block(block)
+    }
+
+    /// Returns the block containing the kernel with the given excess signature.
+    pub async fn block_with_kernel(
+        &self,
+        public_nonce: &str,
+        signature: &str,
+    ) -> Result<models::HistoricalBlock, HttpApiError> {
+        let excess_sig = parse_signature(public_nonce, signature)?;
+        let block = self
+            .db
+            .fetch_block_with_kernel(excess_sig)
+            .await?
+            .ok_or_else(|| HttpApiError::NotFound(format!("Kernel {}/{}", public_nonce, signature)))?;
+        convert_block(block)
+    }
+
+    /// Returns the block containing the unspent output with the given commitment.
+    pub async fn block_with_output(&self, commitment: &str) -> Result<models::HistoricalBlock, HttpApiError> {
+        let commitment =
+            Commitment::from_hex(commitment).map_err(|e| HttpApiError::InvalidRequest(format!("Commitment: {}", e)))?;
+        let block = self
+            .db
+            .fetch_block_with_utxo(commitment.clone())
+            .await?
+            .ok_or_else(|| HttpApiError::NotFound(format!("Unspent output {}", commitment.to_hex())))?;
+        convert_block(block)
+    }
+
+    /// Returns a page of the transactions in the unconfirmed pool of the mempool, starting at index `start`.
+    pub async fn mempool_transactions(
+        &self,
+        start: u64,
+        limit: Option<u64>,
+    ) -> Result<models::Page<models::Transaction>, HttpApiError> {
+        let state = self.mempool_service.clone().get_mempool_state().await?;
+        let (range, next) = page_range(start, limit, self.max_page_size, state.unconfirmed_pool.len() as u64);
+        let items = state
+            .unconfirmed_pool
+            .into_iter()
+            .skip(range.start as usize)
+            .take((range.end - range.start) as usize)
+            .map(|tx| {
+                grpc::Transaction::try_from(tx)
+                    .map(Into::into)
+                    .map_err(HttpApiError::ConversionError)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(models::Page { items, next })
+    }
+}
+
+fn convert_block(block: HistoricalBlock) -> Result<models::HistoricalBlock, HttpApiError> {
+    Ok(grpc::HistoricalBlock::try_from(block)?.into())
+}
+
+fn parse_signature(public_nonce: &str, signature: &str) -> Result<Signature, HttpApiError> {
+    let public_nonce = PublicKey::from_hex(public_nonce)
+        .map_err(|e| HttpApiError::InvalidRequest(format!("Signature public nonce: {}", e)))?;
+    let signature =
+        PrivateKey::from_hex(signature).map_err(|e| HttpApiError::InvalidRequest(format!("Signature: {}", e)))?;
+    Ok(Signature::new(public_nonce, signature))
+}
+
+/// Returns the range of item indexes in a page of at most `limit` items starting at `start`, out of `total` items,
+/// along with the start of the next page if there is one. The page size is capped at `max_page_size`.
+fn page_range(start: u64, limit: Option<u64>, max_page_size: u64, total: u64) -> (Range<u64>, Option<u64>) {
+    let limit = limit.unwrap_or(max_page_size).clamp(1, max_page_size);
+    let start = cmp::min(start, total);
+    let end = cmp::min(start.saturating_add(limit), total);
+    let next = if end < total { Some(end) } else { None };
+    (start..end, next)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_pages_through_all_items() {
+        let (range, next) = page_range(0, None, 10, 25);
+        assert_eq!(range, 0..10);
+        assert_eq!(next, Some(10));
+        let (range, next) = page_range(10, Some(10), 10, 25);
+        assert_eq!(range, 10..20);
+        assert_eq!(next, Some(20));
+        let (range, next) = page_range(20, Some(10), 10, 25);
+        assert_eq!(range, 20..25);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn it_caps_the_page_size() {
+        let (range, next) = page_range(5, Some(1000), 10, 100);
+        assert_eq!(range, 5..15);
+        assert_eq!(next, Some(15));
+        let (range, _) = page_range(5, Some(0), 10, 100);
+        assert_eq!(range, 5..6);
+    }
+
+    #[test]
+    fn it_returns_an_empty_page_past_the_end() {
+        let (range, next) = page_range(30, Some(10), 10, 25);
+        assert!(range.is_empty());
+        assert_eq!(next, None);
+        let (range, next) = page_range(0, None, 10, 0);
+        assert!(range.is_empty());
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn it_rejects_invalid_signatures() {
+        let err = parse_signature("zz", "00").unwrap_err();
+        assert!(matches!(err, HttpApiError::InvalidRequest(_)));
+        let nonce = PublicKey::default().to_hex();
+        let sig = PrivateKey::default().to_hex();
+        let parsed = parse_signature(&nonce, &sig).unwrap();
+        assert_eq!(parsed.get_public_nonce(), &PublicKey::default());
+    }
+}
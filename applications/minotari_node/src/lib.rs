@@ -28,14 +28,18 @@ mod table;
 
 mod bootstrap;
 mod builder;
+mod chain_events;
 pub mod cli;
 mod commands;
 pub mod config;
 mod grpc;
 mod grpc_method;
+mod http_api;
 #[cfg(feature = "metrics")]
 mod metrics;
 mod recovery;
+mod schema;
+mod stratum;
 mod utils;
 
 use std::{process, sync::Arc};
@@ -130,7 +134,7 @@ pub async fn run_base_node_with_cli(
     };
 
     // Build, node, build!
-    let ctx = builder::configure_and_initialize_node(config.clone(), node_identity, shutdown.to_signal()).await?;
+    let mut ctx = builder::configure_and_initialize_node(config.clone(), node_identity, shutdown.to_signal()).await?;
 
     if config.base_node.grpc_enabled {
         let grpc_address = config.base_node.grpc_address.clone().unwrap_or_else(|| {
@@ -152,8 +156,34 @@ pub async fn run_base_node_with_cli(
         task::spawn(run_grpc(grpc, grpc_address, auth, tls_identity, shutdown.to_signal()));
     }
 
+    if config.base_node.http_api_enabled {
+        let http_api = http_api::HttpApiService::from_base_node_context(&ctx, config.base_node.http_api_max_page_size);
+        task::spawn(http_api::run_http_api(
+            http_api,
+            config.base_node.http_api_address.clone(),
+            shutdown.to_signal(),
+        ));
+    }
+
+    if config.base_node.stratum.enabled {
+        let stratum = stratum::StratumServer::from_base_node_context(&ctx, &config.base_node).map_err(|e| {
+            ExitError::new(
+                ExitCode::ConfigError,
+                format!("Could not start the stratum server: {}", e),
+            )
+        })?;
+        let listener = stratum.bind().await.map_err(|e| {
+            ExitError::new(
+                ExitCode::NetworkError,
+                format!("Could not bind the stratum server listener: {}", e),
+            )
+        })?;
+        task::spawn(stratum.run(listener, shutdown.to_signal()));
+    }
+
     ctx.start()
         .map_err(|e| ExitError::new(ExitCode::UnknownError, &format!("Could not start database.{:?}", e)))?;
+    ctx.start_mempool_persistence(shutdown.to_signal()).await?;
 
     // Run, node, run!
     let context = CommandContext::new(&ctx, shutdown.clone());
@@ -0,0 +1,389 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, convert::TryFrom, str::FromStr, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use log::*;
+use minotari_app_grpc::tari_rpc::{self as grpc, base_node_server::BaseNode, pow_algo::PowAlgos};
+use minotari_app_utilities::stratum::{JobQueue, SubmittedNonces};
+use tari_common_types::{tari_address::TariAddress, types::FixedHash};
+use tari_core::{
+    blocks::{Block, BlockHeader},
+    proof_of_work::{sha3x_difficulty, Difficulty},
+    transactions::transaction_components::RangeProofType,
+};
+use tari_shutdown::ShutdownSignal;
+use tari_utilities::hex::Hex;
+use tokio::{
+    sync::{broadcast::error::RecvError, watch},
+    time,
+    time::MissedTickBehavior,
+};
+use tonic::{Request, Status};
+
+use crate::{
+    chain_events::{ChainEventTopic, ChainEventsHandle},
+    config::StratumConfig,
+    grpc::base_node_grpc_server::BaseNodeGrpcServer,
+    stratum::{error::StratumError, types::JobParams},
+};
+
+const LOG_TARGET: &str = "minotari::base_node::stratum::job";
+
+/// The header of a block template that pays the block reward to the pool, mined by all workers
+#[derive(Debug)]
+pub struct StratumJob {
+    pub id: u64,
+    pub block: Block,
+    pub network_difficulty: Difficulty,
+    submitted_nonces: SubmittedNonces<u64>,
+}
+
+/// A share that met the share difficulty of the worker
+#[derive(Debug)]
+pub struct ValidShare {
+    /// The job header with the nonce of the share
+    pub header: BlockHeader,
+    pub achieved_difficulty: Difficulty,
+    /// Whether the share also met the network difficulty, i.e. solved the block
+    pub is_block: bool,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ShareRejection {
+    #[error("Invalid proof of work: {0}")]
+    InvalidPow(String),
+    #[error("Stale job")]
+    StaleJob,
+    #[error("Duplicate share")]
+    DuplicateShare,
+    #[error("Share hash does not match the job header")]
+    HashMismatch,
+    #[error("Low difficulty share: achieved {achieved}, required {required}")]
+    LowDifficulty { achieved: u64, required: u64 },
+}
+
+impl ShareRejection {
+    /// The stratum error code of the rejection. The `minotari_miner` client requests a new job when it receives one of
+    /// these codes.
+    pub fn code(&self) -> i32 {
+        match self {
+            ShareRejection::InvalidPow(_) => 20,
+            ShareRejection::StaleJob => 21,
+            ShareRejection::DuplicateShare => 22,
+            ShareRejection::HashMismatch => 23,
+            ShareRejection::LowDifficulty { .. } => 25,
+        }
+    }
+
+    /// The difficulty achieved by the rejected share, zero if it is not known
+    pub fn achieved_difficulty(&self) -> u64 {
+        match self {
+            ShareRejection::LowDifficulty { achieved, .. } => *achieved,
+            _ => 0,
+        }
+    }
+}
+
+impl StratumJob {
+    fn new(id: u64, block: Block, network_difficulty: Difficulty) -> Self {
+        Self {
+            id,
+            block,
+            network_difficulty,
+            submitted_nonces: SubmittedNonces::default(),
+        }
+    }
+
+    pub fn height(&self) -> u64 {
+        self.block.header.height
+    }
+
+    /// The share difficulty of a worker on this job. A share difficulty above the network difficulty would hide blocks
+    /// from the pool, so it is capped at the network difficulty.
+    pub fn share_difficulty(&self, worker_difficulty: u64) -> u64 {
+        cmp::min(worker_difficulty, self.network_difficulty.as_u64())
+    }
+
+    /// The job as sent to a worker with the given share difficulty
+    pub fn to_params(&self, worker_difficulty: u64) -> Result<JobParams, StratumError> {
+        let blob = borsh::to_vec(&self.block.header)?;
+        Ok(JobParams {
+            job_id: self.id.to_string(),
+            blob: base64::encode(blob),
+            target: self.share_difficulty(worker_difficulty).to_string(),
+            height: self.height(),
+        })
+    }
+
+    /// Validates a share submitted for this job by a worker with the given share difficulty. The submitted hash must be
+    /// the hash of the job header with the submitted nonce.
+    pub fn validate_share(&self, nonce: u64, hash: &str, worker_difficulty: u64) -> Result<ValidShare, ShareRejection> {
+        let mut header = self.block.header.clone();
+        header.nonce = nonce;
+        let hash = FixedHash::from_hex(hash).map_err(|_| ShareRejection::HashMismatch)?;
+        if hash != header.hash() {
+            return Err(ShareRejection::HashMismatch);
+        }
+
+        let achieved_difficulty = sha3x_difficulty(&header).map_err(|e| ShareRejection::InvalidPow(e.to_string()))?;
+        let required = self.share_difficulty(worker_difficulty);
+        if achieved_difficulty.as_u64() < required {
+            return Err(ShareRejection::LowDifficulty {
+                achieved: achieved_difficulty.as_u64(),
+                required,
+            });
+        }
+
+        if !self.submitted_nonces.insert(nonce) {
+            return Err(ShareRejection::DuplicateShare);
+        }
+
+        Ok(ValidShare {
+            header,
+            achieved_difficulty,
+            is_block: achieved_difficulty >= self.network_difficulty,
+        })
+    }
+}
+
+/// The mining methods of the base node that jobs are built and blocks are submitted with
+#[async_trait]
+pub trait MiningRpc: Send + Sync + 'static {
+    async fn get_new_block_template_with_coinbases(
+        &self,
+        request: grpc::GetNewBlockTemplateWithCoinbasesRequest,
+    ) -> Result<grpc::GetNewBlockResult, Status>;
+
+    async fn submit_block(&self, block: grpc::Block) -> Result<grpc::SubmitBlockResponse, Status>;
+}
+
+#[async_trait]
+impl MiningRpc for BaseNodeGrpcServer {
+    async fn get_new_block_template_with_coinbases(
+        &self,
+        request: grpc::GetNewBlockTemplateWithCoinbasesRequest,
+    ) -> Result<grpc::GetNewBlockResult, Status> {
+        BaseNode::get_new_block_template_with_coinbases(self, Request::new(request))
+            .await
+            .map(|response| response.into_inner())
+    }
+
+    async fn submit_block(&self, block: grpc::Block) -> Result<grpc::SubmitBlockResponse, Status> {
+        BaseNode::submit_block(self, Request::new(block))
+            .await
+            .map(|response| response.into_inner())
+    }
+}
+
+/// Builds jobs from the block templates of the base node, and submits the blocks solved by workers
+pub struct JobManager {
+    grpc: Box<dyn MiningRpc>,
+    coinbase: grpc::NewBlockCoinbase,
+    jobs: JobQueue<StratumJob>,
+}
+
+impl JobManager {
+    pub fn new<G: MiningRpc>(grpc: G, config: &StratumConfig) -> Result<Self, StratumError> {
+        let address = TariAddress::from_str(&config.pool_wallet_address)
+            .map_err(|e| StratumError::InvalidPoolWalletAddress(e.to_string()))?;
+        // The whole block reward is paid to the single coinbase of the pool
+        let coinbase = grpc::NewBlockCoinbase {
+            address: address.to_base58(),
+            value: 1,
+            stealth_payment: false,
+            revealed_value_proof: config.range_proof_type == RangeProofType::RevealedValue,
+            coinbase_extra: config.coinbase_extra.as_bytes().to_vec(),
+        };
+        Ok(Self {
+            grpc: Box::new(grpc),
+            coinbase,
+            jobs: JobQueue::new(),
+        })
+    }
+
+    /// Subscribes to new jobs. The receiver holds `None` until the first job has been built.
+    pub fn subscribe(&self) -> watch::Receiver<Option<Arc<StratumJob>>> {
+        self.jobs.subscribe()
+    }
+
+    pub fn current_job(&self) -> Option<Arc<StratumJob>> {
+        self.jobs.current()
+    }
+
+    /// Returns the job with the given id if shares are still accepted for it
+    pub fn find_job(&self, job_id: u64) -> Option<Arc<StratumJob>> {
+        self.jobs.find(|job| job.id == job_id)
+    }
+
+    /// Builds a new job from a block template on the current tip and sends it to all workers
+    pub async fn refresh(&self) -> Result<Arc<StratumJob>, StratumError> {
+        let request = grpc::GetNewBlockTemplateWithCoinbasesRequest {
+            algo: Some(grpc::PowAlgo {
+                pow_algo: PowAlgos::Sha3x.into(),
+            }),
+            max_weight: 0,
+            coinbases: vec![self.coinbase.clone()],
+        };
+        let response = self
+            .grpc
+            .get_new_block_template_with_coinbases(request)
+            .await
+            .map_err(|e| StratumError::BlockTemplate(e.message().to_string()))?;
+        let block = response
+            .block
+            .ok_or_else(|| StratumError::BlockTemplate("Block not provided".to_string()))
+            .and_then(|block| Block::try_from(block).map_err(StratumError::BlockTemplate))?;
+        let miner_data = response
+            .miner_data
+            .ok_or_else(|| StratumError::BlockTemplate("Miner data not provided".to_string()))?;
+        let network_difficulty = Difficulty::from_u64(miner_data.target_difficulty)
+            .map_err(|e| StratumError::BlockTemplate(e.to_string()))?;
+
+        let job = Arc::new(StratumJob::new(self.jobs.next_job_id(), block, network_difficulty));
+        let prev_hash = job.block.header.prev_hash;
+        // Jobs that do not build on the same tip can no longer produce a block
+        self.jobs
+            .publish(job.clone(), |j| j.block.header.prev_hash == prev_hash);
+        Ok(job)
+    }
+
+    /// Submits the block of a job solved by a share to the base node, returning the hash of the block
+    pub async fn submit_block(&self, job: &StratumJob, header: BlockHeader) -> Result<FixedHash, StratumError> {
+        let block = Block::new(header, job.block.body.clone());
+        let block = grpc::Block::try_from(block).map_err(StratumError::SubmitBlock)?;
+        let response = self
+            .grpc
+            .submit_block(block)
+            .await
+            .map_err(|e| StratumError::SubmitBlock(e.message().to_string()))?;
+        FixedHash::try_from(response.block_hash).map_err(|e| StratumError::ConversionError(e.to_string()))
+    }
+
+    /// Refreshes the job whenever the tip of the chain changes, and at least every `refresh_interval` so that new
+    /// mempool transactions are mined.
+    pub async fn run(
+        self: Arc<Self>,
+        chain_events: ChainEventsHandle,
+        refresh_interval: Duration,
+        mut shutdown_signal: ShutdownSignal,
+    ) {
+        let mut chain_events = chain_events.subscribe();
+        let mut refresh = time::interval(refresh_interval);
+        refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = refresh.tick() => {},
+                event = chain_events.recv() => match event {
+                    Ok(event) if matches!(event.topic, ChainEventTopic::BlockHash | ChainEventTopic::Reorg) => {},
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(n)) => {
+                        warn!(target: LOG_TARGET, "Stratum job manager missed {} chain event(s)", n);
+                    },
+                    Err(RecvError::Closed) => break,
+                },
+                _ = shutdown_signal.wait() => break,
+            }
+
+            match self.refresh().await {
+                Ok(job) => debug!(
+                    target: LOG_TARGET,
+                    "New stratum job {} for height {} with network difficulty {}",
+                    job.id,
+                    job.height(),
+                    job.network_difficulty
+                ),
+                Err(e) => warn!(target: LOG_TARGET, "Could not refresh the stratum job: {}", e),
+            }
+            refresh.reset();
+        }
+        debug!(target: LOG_TARGET, "Stratum job manager stopped");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_core::transactions::aggregated_body::AggregateBody;
+
+    use super::*;
+
+    fn job(network_difficulty: u64) -> StratumJob {
+        let block = Block::new(BlockHeader::new(0), AggregateBody::empty());
+        StratumJob::new(1, block, Difficulty::from_u64(network_difficulty).unwrap())
+    }
+
+    fn mine(job: &StratumJob, min_difficulty: u64, max_difficulty: u64) -> (u64, String) {
+        let mut header = job.block.header.clone();
+        loop {
+            let difficulty = sha3x_difficulty(&header).unwrap().as_u64();
+            if difficulty >= min_difficulty && difficulty < max_difficulty {
+                return (header.nonce, header.hash().to_hex());
+            }
+            header.nonce += 1;
+        }
+    }
+
+    #[test]
+    fn it_accepts_valid_shares_once() {
+        let job = job(1_000_000);
+        let (nonce, hash) = mine(&job, 2, 1_000_000);
+        let share = job.validate_share(nonce, &hash, 2).unwrap();
+        assert!(!share.is_block);
+        assert_eq!(share.header.nonce, nonce);
+        assert!(matches!(
+            job.validate_share(nonce, &hash, 2),
+            Err(ShareRejection::DuplicateShare)
+        ));
+    }
+
+    #[test]
+    fn it_rejects_invalid_shares() {
+        let job = job(1_000_000);
+        let (nonce, hash) = mine(&job, 1, 2);
+        assert!(matches!(
+            job.validate_share(nonce, &hash, 2),
+            Err(ShareRejection::LowDifficulty {
+                achieved: 1,
+                required: 2
+            })
+        ));
+        assert!(matches!(
+            job.validate_share(nonce + 1, &hash, 1),
+            Err(ShareRejection::HashMismatch)
+        ));
+        assert!(matches!(
+            job.validate_share(nonce, "not a hash", 1),
+            Err(ShareRejection::HashMismatch)
+        ));
+    }
+
+    #[test]
+    fn it_detects_blocks() {
+        let job = job(4);
+        // The share difficulty of a worker is capped at the network difficulty
+        assert_eq!(job.share_difficulty(1_000), 4);
+        let (nonce, hash) = mine(&job, 4, u64::MAX);
+        let share = job.validate_share(nonce, &hash, 1_000).unwrap();
+        assert!(share.is_block);
+    }
+}
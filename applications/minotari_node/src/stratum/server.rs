@@ -0,0 +1,531 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    mem,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use chrono::Utc;
+use log::*;
+use minotari_app_utilities::stratum::{
+    parse_params,
+    rpc_error,
+    to_result,
+    worker_name,
+    JsonRpcConnection,
+    LoginParams,
+    RpcError,
+    RpcRequest,
+    WorkerIdentifier,
+    INTERNAL_ERROR,
+    METHOD_NOT_FOUND,
+};
+use serde_json::{json, Value};
+use tari_common_types::types::FixedHash;
+use tari_comms::utils::multiaddr::multiaddr_to_socketaddr;
+use tari_core::blocks::BlockHeader;
+use tari_shutdown::ShutdownSignal;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task,
+    time,
+};
+
+use crate::{
+    builder::BaseNodeContext,
+    chain_events::ChainEventsHandle,
+    config::{BaseNodeConfig, StratumConfig},
+    grpc::base_node_grpc_server::BaseNodeGrpcServer,
+    stratum::{
+        error::StratumError,
+        job::{JobManager, ShareRejection, StratumJob},
+        share_db::{ShareDatabase, ShareRecord},
+        types::{JobParams, LoginResponse, SubmitParams, SubmitResponse},
+        vardiff::Vardiff,
+    },
+};
+
+const LOG_TARGET: &str = "minotari::base_node::stratum";
+
+/// Tells the `minotari_miner` client to log in again
+const UNAUTHORIZED: i32 = -1;
+
+/// A stratum server for SHA3x pool mining. All workers mine the same job, a block template that pays the block reward
+/// to the pool wallet address. Each worker is assigned a share difficulty with vardiff, every submitted share is
+/// recorded in the share database and shares that meet the network difficulty are submitted to the base node.
+#[derive(Clone)]
+pub struct StratumServer {
+    jobs: Arc<JobManager>,
+    share_db: ShareDatabase,
+    chain_events: ChainEventsHandle,
+    config: StratumConfig,
+    next_worker_id: Arc<AtomicU64>,
+}
+
+impl StratumServer {
+    pub fn from_base_node_context(ctx: &BaseNodeContext, config: &BaseNodeConfig) -> Result<Self, StratumError> {
+        // Jobs are built and blocks are submitted with the mining methods of an in-process gRPC server, which are
+        // enabled regardless of the methods the gRPC server is configured to allow
+        let grpc_config = BaseNodeConfig {
+            mining_enabled: true,
+            ..config.clone()
+        };
+        let grpc = BaseNodeGrpcServer::from_base_node_context(ctx, grpc_config);
+        Ok(Self {
+            jobs: Arc::new(JobManager::new(grpc, &config.stratum)?),
+            share_db: ShareDatabase::connect(&config.stratum.share_db_path)?,
+            chain_events: ctx.chain_events(),
+            config: config.stratum.clone(),
+            next_worker_id: Arc::new(AtomicU64::new(1)),
+        })
+    }
+
+    /// Binds the listener of the stratum server, so that a bind error is reported before the server is started
+    pub async fn bind(&self) -> Result<TcpListener, StratumError> {
+        let address = multiaddr_to_socketaddr(&self.config.listener_address)?;
+        let listener = TcpListener::bind(address).await.map_err(|err| {
+            error!(target: LOG_TARGET, "Stratum server failed to bind to {}: {}", address, err);
+            err
+        })?;
+        Ok(listener)
+    }
+
+    /// Runs the stratum server on the bound listener until the shutdown signal is triggered
+    pub async fn run(self, listener: TcpListener, mut shutdown_signal: ShutdownSignal) {
+        info!(target: LOG_TARGET, "Starting stratum server on {}", self.config.listener_address);
+
+        task::spawn(self.jobs.clone().run(
+            self.chain_events.clone(),
+            self.config.job_refresh_interval,
+            shutdown_signal.clone(),
+        ));
+
+        loop {
+            tokio::select! {
+                result = listener.accept() => match result {
+                    Ok((stream, peer)) => {
+                        task::spawn(self.clone().handle_connection(stream, peer, shutdown_signal.clone()));
+                    },
+                    Err(err) => warn!(target: LOG_TARGET, "Stratum server failed to accept a connection: {}", err),
+                },
+                _ = shutdown_signal.wait() => break,
+            }
+        }
+
+        info!(target: LOG_TARGET, "Stopping stratum server");
+    }
+
+    async fn handle_connection(self, stream: TcpStream, peer: SocketAddr, mut shutdown_signal: ShutdownSignal) {
+        debug!(target: LOG_TARGET, "Stratum connection from {}", peer);
+        let mut job_rx = self.jobs.subscribe();
+        let mut retarget = time::interval(self.config.vardiff_retarget_time);
+        let mut session = Session {
+            server: self,
+            connection: JsonRpcConnection::new(stream, peer),
+            worker: None,
+            send_job: false,
+        };
+
+        loop {
+            let result = tokio::select! {
+                request = session.connection.next_request() => match request {
+                    Some(request) => session.handle_request(request).await,
+                    None => break,
+                },
+                Ok(()) = job_rx.changed() => session.send_current_job().await,
+                _ = retarget.tick() => session.retarget().await,
+                _ = shutdown_signal.wait() => break,
+            };
+            if let Err(err) = result {
+                debug!(target: LOG_TARGET, "Closing stratum connection from {}: {}", peer, err);
+                break;
+            }
+        }
+        if let Some(worker) = session.worker {
+            info!(target: LOG_TARGET, "Stratum worker '{}' ({}) disconnected", worker.name, peer);
+        }
+    }
+}
+
+struct Worker {
+    id: String,
+    name: String,
+    vardiff: Vardiff,
+}
+
+/// The connection of a single worker
+struct Session {
+    server: StratumServer,
+    connection: JsonRpcConnection,
+    worker: Option<Worker>,
+    /// Set when the share difficulty of the worker changed and the job must be sent again
+    send_job: bool,
+}
+
+impl Session {
+    async fn handle_request(&mut self, request: Result<RpcRequest, RpcError>) -> Result<(), StratumError> {
+        // The `minotari_miner` client expects a string id in every response
+        let request = match request {
+            Ok(request) => request,
+            Err(error) => {
+                self.connection.send_response(Value::from(""), Err(error)).await?;
+                return Ok(());
+            },
+        };
+
+        let result = match request.method.as_str() {
+            "login" => self.login(request.params),
+            "getjob" => self.get_job(request.params),
+            "submit" => self.submit(request.params).await,
+            "keepalive" => Ok(json!({ "status": "KEEPALIVED" })),
+            method => Err(rpc_error(METHOD_NOT_FOUND, format!("Method '{}' not found", method))),
+        };
+        self.connection
+            .send_response(request.id.unwrap_or_else(|| Value::from("")), result)
+            .await?;
+        if mem::take(&mut self.send_job) {
+            self.send_current_job().await?;
+        }
+        Ok(())
+    }
+
+    fn login(&mut self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<LoginParams>(params)?;
+        let name = worker_name(&params.login)?;
+
+        let config = &self.server.config;
+        let worker = Worker {
+            id: self.server.next_worker_id.fetch_add(1, Ordering::Relaxed).to_string(),
+            name: name.to_string(),
+            vardiff: Vardiff::new(
+                config.initial_share_difficulty,
+                config.min_share_difficulty,
+                config.target_share_time,
+                config.vardiff_retarget_time,
+                Instant::now(),
+            ),
+        };
+        info!(
+            target: LOG_TARGET,
+            "Stratum worker '{}' ({}) logged in with agent '{}'",
+            worker.name,
+            self.connection.peer(),
+            params.agent
+        );
+        let id = worker.id.clone();
+        let difficulty = worker.vardiff.difficulty();
+        self.worker = Some(worker);
+
+        // Without a job the worker receives the first job when it is built
+        let job = self.current_job_params(difficulty)?;
+        to_result(&LoginResponse { id, job })
+    }
+
+    fn get_job(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<WorkerIdentifier>(params)?;
+        let difficulty = match self.worker.as_ref() {
+            Some(worker) if worker.id == params.id => worker.vardiff.difficulty(),
+            _ => return unauthorized(),
+        };
+        to_result(&self.current_job_params(difficulty)?)
+    }
+
+    async fn submit(&mut self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<SubmitParams>(params)?;
+        let (name, worker_difficulty) = match self.worker.as_ref() {
+            Some(worker) if worker.id == params.id => (worker.name.clone(), worker.vardiff.accepted_difficulty()),
+            _ => return unauthorized(),
+        };
+
+        let job = self.server.jobs.find_job(params.job_id);
+        let mut share = ShareRecord {
+            worker: name,
+            job_id: params.job_id,
+            height: job.as_ref().map(|job| job.height()).unwrap_or_default(),
+            share_difficulty: job
+                .as_ref()
+                .map(|job| job.share_difficulty(worker_difficulty))
+                .unwrap_or(worker_difficulty),
+            achieved_difficulty: 0,
+            reject_reason: None,
+            block_hash: None,
+            submitted_at: Utc::now().naive_utc(),
+        };
+        let outcome = job.ok_or(ShareRejection::StaleJob).and_then(|job| {
+            job.validate_share(params.nonce, &params.hash, worker_difficulty)
+                .map(|valid| (job, valid))
+        });
+
+        let response = match outcome {
+            Ok((job, valid)) => {
+                share.achieved_difficulty = valid.achieved_difficulty.as_u64();
+                if valid.is_block {
+                    share.block_hash = self.submit_block(&job, valid.header, &share.worker).await;
+                }
+                if let Some(worker) = self.worker.as_mut() {
+                    if let Some(difficulty) = worker.vardiff.record_share(Instant::now()) {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Share difficulty of stratum worker '{}' changed to {}", worker.name, difficulty
+                        );
+                        self.send_job = true;
+                    }
+                }
+                SubmitResponse {
+                    status: Some("OK".to_string()),
+                    error: None,
+                }
+            },
+            Err(rejection) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejected share of stratum worker '{}' for job {}: {}", share.worker, params.job_id, rejection
+                );
+                share.achieved_difficulty = rejection.achieved_difficulty();
+                share.reject_reason = Some(rejection.to_string());
+                SubmitResponse {
+                    status: None,
+                    error: Some(rpc_error(rejection.code(), rejection.to_string())),
+                }
+            },
+        };
+
+        if let Err(err) = self.server.share_db.insert_share(share).await {
+            warn!(target: LOG_TARGET, "Failed to record stratum share: {}", err);
+        }
+        to_result(&response)
+    }
+
+    /// Submits a solved block to the base node, returning its hash if it was accepted
+    async fn submit_block(&self, job: &StratumJob, header: BlockHeader, worker: &str) -> Option<FixedHash> {
+        let height = header.height;
+        match self.server.jobs.submit_block(job, header).await {
+            Ok(hash) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Stratum worker '{}' found block #{} ({})", worker, height, hash
+                );
+                Some(hash)
+            },
+            Err(err) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Block #{} found by stratum worker '{}' was not accepted: {}", height, worker, err
+                );
+                None
+            },
+        }
+    }
+
+    /// Sends the current job to the worker, if it is logged in
+    async fn send_current_job(&mut self) -> Result<(), StratumError> {
+        let difficulty = match self.worker.as_ref() {
+            Some(worker) => worker.vardiff.difficulty(),
+            None => return Ok(()),
+        };
+        let job = match self.server.jobs.current_job() {
+            Some(job) => job,
+            None => return Ok(()),
+        };
+        self.connection
+            .send_notification("job", job.to_params(difficulty)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Retargets the share difficulty of the worker, sending it the current job if the difficulty changed
+    async fn retarget(&mut self) -> Result<(), StratumError> {
+        let difficulty = self
+            .worker
+            .as_mut()
+            .and_then(|worker| worker.vardiff.retarget(Instant::now()));
+        match difficulty {
+            Some(difficulty) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Share difficulty of stratum worker at {} changed to {}",
+                    self.connection.peer(),
+                    difficulty
+                );
+                self.send_current_job().await
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn current_job_params(&self, difficulty: u64) -> Result<JobParams, RpcError> {
+        self.server
+            .jobs
+            .current_job()
+            .ok_or_else(|| rpc_error(INTERNAL_ERROR, "No job available yet".to_string()))?
+            .to_params(difficulty)
+            .map_err(|err| rpc_error(INTERNAL_ERROR, err.to_string()))
+    }
+}
+
+/// Errors that the miner acts on, such as rejected shares, are returned in the result of a response in the shape of a
+/// submit response, as that is where the `minotari_miner` client looks for them.
+fn unauthorized() -> Result<Value, RpcError> {
+    to_result(&SubmitResponse {
+        status: None,
+        error: Some(rpc_error(UNAUTHORIZED, "Unauthorized".to_string())),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use minotari_app_grpc::tari_rpc as grpc;
+    use tari_common_types::tari_address::TariAddress;
+    use tari_core::{blocks::Block, transactions::aggregated_body::AggregateBody};
+    use tari_shutdown::Shutdown;
+    use tari_utilities::hex::Hex;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+        net::tcp::{OwnedReadHalf, OwnedWriteHalf},
+        sync::broadcast,
+    };
+    use tonic::Status;
+
+    use super::*;
+    use crate::{chain_events::ChainEventPublisher, stratum::job::MiningRpc};
+
+    /// A base node that returns a block template with a network difficulty of one, so that every share solves the
+    /// block, and records the blocks submitted to it
+    #[derive(Clone, Default)]
+    struct StubBaseNode {
+        submitted_blocks: Arc<Mutex<Vec<grpc::Block>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MiningRpc for StubBaseNode {
+        async fn get_new_block_template_with_coinbases(
+            &self,
+            _request: grpc::GetNewBlockTemplateWithCoinbasesRequest,
+        ) -> Result<grpc::GetNewBlockResult, Status> {
+            let block = Block::new(BlockHeader::new(0), AggregateBody::empty());
+            Ok(grpc::GetNewBlockResult {
+                block: Some(grpc::Block::try_from(block).map_err(Status::internal)?),
+                miner_data: Some(grpc::MinerData {
+                    target_difficulty: 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        }
+
+        async fn submit_block(&self, block: grpc::Block) -> Result<grpc::SubmitBlockResponse, Status> {
+            self.submitted_blocks.lock().unwrap().push(block);
+            Ok(grpc::SubmitBlockResponse {
+                block_hash: vec![1; 32],
+            })
+        }
+    }
+
+    /// Sends a request to the stratum server and returns the response, skipping job notifications
+    async fn request(
+        writer: &mut OwnedWriteHalf,
+        lines: &mut Lines<BufReader<OwnedReadHalf>>,
+        request: Value,
+    ) -> Value {
+        let mut line = request.to_string();
+        line.push('\n');
+        writer.write_all(line.as_bytes()).await.unwrap();
+        loop {
+            let line = lines.next_line().await.unwrap().expect("connection closed");
+            let response = serde_json::from_str::<Value>(&line).unwrap();
+            if response["method"].is_null() {
+                return response;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn it_accepts_shares_of_logged_in_workers_and_submits_blocks() {
+        let base_node = StubBaseNode::default();
+        let config = StratumConfig {
+            listener_address: "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+            pool_wallet_address: TariAddress::default().to_base58(),
+            ..Default::default()
+        };
+        let (_block_events, block_rx) = broadcast::channel(1);
+        let (_mempool_events, mempool_rx) = broadcast::channel(1);
+        let server = StratumServer {
+            jobs: Arc::new(JobManager::new(base_node.clone(), &config).unwrap()),
+            share_db: ShareDatabase::connect_memory("stratum_server"),
+            chain_events: ChainEventPublisher::new(block_rx, mempool_rx).handle(),
+            config,
+            next_worker_id: Arc::new(AtomicU64::new(1)),
+        };
+        server.jobs.refresh().await.unwrap();
+        let listener = server.bind().await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let shutdown = Shutdown::new();
+        task::spawn(server.run(listener, shutdown.to_signal()));
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        let submit = |worker_id: &str, job: &Value, nonce: u64| {
+            let blob = base64::decode(job["blob"].as_str().unwrap()).unwrap();
+            let mut header = borsh::from_slice::<BlockHeader>(&blob).unwrap();
+            header.nonce = nonce;
+            json!({ "id": "3", "jsonrpc": "2.0", "method": "submit", "params": {
+                "id": worker_id, "job_id": job["job_id"].as_str().unwrap().parse::<u64>().unwrap(),
+                "nonce": nonce, "hash": header.hash().to_hex()
+            }})
+        };
+
+        // Shares are only validated for logged in workers
+        let login = json!({ "id": "1", "jsonrpc": "2.0", "method": "login", "params": { "login": "worker" } });
+        let login = request(&mut writer, &mut lines, login).await;
+        let worker_id = login["result"]["id"].as_str().unwrap().to_string();
+        let job = login["result"]["job"].clone();
+        let response = request(&mut writer, &mut lines, submit("unknown", &job, 1)).await;
+        assert_eq!(response["result"]["error"]["code"], UNAUTHORIZED);
+        let get_job = json!({ "id": "2", "jsonrpc": "2.0", "method": "getjob", "params": { "id": worker_id } });
+        assert_eq!(
+            request(&mut writer, &mut lines, get_job).await["result"]["job_id"],
+            job["job_id"]
+        );
+
+        let response = request(&mut writer, &mut lines, submit(&worker_id, &job, 1)).await;
+        assert_eq!(response["result"]["status"], "OK");
+        let response = request(&mut writer, &mut lines, submit(&worker_id, &job, 1)).await;
+        assert_eq!(
+            response["result"]["error"]["code"],
+            ShareRejection::DuplicateShare.code()
+        );
+
+        // The share met the network difficulty, so the block was submitted before the share was accepted
+        let submitted_blocks = base_node.submitted_blocks.lock().unwrap().clone();
+        assert_eq!(submitted_blocks.len(), 1);
+        assert_eq!(submitted_blocks[0].header.as_ref().unwrap().nonce, 1);
+        shutdown.trigger();
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{convert::TryFrom, fs, path::Path};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
+use tari_common_sqlite::connection::{DbConnection, DbConnectionUrl};
+use tari_common_types::types::FixedHash;
+use tokio::task;
+
+use crate::{schema::stratum_shares, stratum::error::StratumError};
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
+
+/// A share submitted by a worker
+#[derive(Debug, Clone)]
+pub struct ShareRecord {
+    pub worker: String,
+    pub job_id: u64,
+    pub height: u64,
+    /// The difficulty the share was accepted or rejected at
+    pub share_difficulty: u64,
+    /// The difficulty achieved by the share, zero if it could not be calculated
+    pub achieved_difficulty: u64,
+    /// The reason the share was rejected, `None` if it was accepted
+    pub reject_reason: Option<String>,
+    /// The hash of the block, if the share solved a block that was accepted by the node
+    pub block_hash: Option<FixedHash>,
+    pub submitted_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = stratum_shares)]
+struct ShareRecordSql {
+    worker: String,
+    job_id: i64,
+    height: i64,
+    share_difficulty: i64,
+    achieved_difficulty: i64,
+    accepted: bool,
+    reject_reason: Option<String>,
+    block_hash: Option<Vec<u8>>,
+    submitted_at: NaiveDateTime,
+}
+
+impl TryFrom<ShareRecord> for ShareRecordSql {
+    type Error = StratumError;
+
+    fn try_from(share: ShareRecord) -> Result<Self, Self::Error> {
+        let to_i64 = |value: u64, field: &str| {
+            i64::try_from(value)
+                .map_err(|_| StratumError::ConversionError(format!("{} {} overflows i64", field, value)))
+        };
+        Ok(Self {
+            job_id: to_i64(share.job_id, "Job id")?,
+            height: to_i64(share.height, "Height")?,
+            share_difficulty: to_i64(share.share_difficulty, "Share difficulty")?,
+            achieved_difficulty: to_i64(share.achieved_difficulty, "Achieved difficulty")?,
+            accepted: share.reject_reason.is_none(),
+            reject_reason: share.reject_reason,
+            block_hash: share.block_hash.map(|hash| hash.to_vec()),
+            worker: share.worker,
+            submitted_at: share.submitted_at,
+        })
+    }
+}
+
+/// The sqlite database in which the shares submitted to the stratum server are recorded for share accounting
+#[derive(Clone)]
+pub struct ShareDatabase {
+    connection: DbConnection,
+}
+
+impl ShareDatabase {
+    /// Opens the share database at the given path, creating and migrating it if required
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, StratumError> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let connection = DbConnection::connect_and_migrate(&DbConnectionUrl::file(path), MIGRATIONS)?;
+        Ok(Self { connection })
+    }
+
+    #[cfg(test)]
+    pub fn connect_memory(name: &str) -> Self {
+        let connection = DbConnection::connect_memory(name.to_string()).unwrap();
+        connection.migrate(MIGRATIONS).unwrap();
+        Self { connection }
+    }
+
+    pub async fn insert_share(&self, share: ShareRecord) -> Result<(), StratumError> {
+        let share = ShareRecordSql::try_from(share)?;
+        let connection = self.connection.clone();
+        task::spawn_blocking(move || {
+            let mut conn = connection.get_pooled_connection()?;
+            diesel::insert_into(stratum_shares::table)
+                .values(&share)
+                .execute(&mut conn)?;
+            Ok::<_, StratumError>(())
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_records_accepted_and_rejected_shares() {
+        let connection = DbConnection::connect_memory("stratum_shares".to_string()).unwrap();
+        connection.migrate(MIGRATIONS).unwrap();
+        let db = ShareDatabase {
+            connection: connection.clone(),
+        };
+
+        let share = ShareRecord {
+            worker: "worker1".to_string(),
+            job_id: 1,
+            height: 10,
+            share_difficulty: 1_000,
+            achieved_difficulty: 1_500,
+            reject_reason: None,
+            block_hash: Some(FixedHash::zero()),
+            submitted_at: Utc::now().naive_utc(),
+        };
+        db.insert_share(share.clone()).await.unwrap();
+        db.insert_share(ShareRecord {
+            achieved_difficulty: 500,
+            reject_reason: Some("Low difficulty share".to_string()),
+            block_hash: None,
+            ..share.clone()
+        })
+        .await
+        .unwrap();
+
+        let mut conn = connection.get_pooled_connection().unwrap();
+        let accepted = stratum_shares::table
+            .select((stratum_shares::accepted, stratum_shares::block_hash))
+            .order_by(stratum_shares::id)
+            .load::<(bool, Option<Vec<u8>>)>(&mut conn)
+            .unwrap();
+        assert_eq!(accepted, vec![(true, Some(vec![0u8; 32])), (false, None)]);
+
+        let too_large = ShareRecord {
+            height: u64::MAX,
+            ..share
+        };
+        assert!(db.insert_share(too_large).await.is_err());
+    }
+}
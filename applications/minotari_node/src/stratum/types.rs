@@ -0,0 +1,58 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The JSON-RPC messages of the stratum protocol that are specific to the stratum client in `minotari_miner`. The
+//! common messages are in [minotari_app_utilities::stratum].
+
+use minotari_app_utilities::stratum::RpcError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    pub id: String,
+    pub job: JobParams,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobParams {
+    pub job_id: String,
+    /// The base64 encoded, borsh serialized block header to mine on
+    pub blob: String,
+    /// The share difficulty of the worker
+    pub target: String,
+    pub height: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitParams {
+    pub id: String,
+    pub job_id: u64,
+    pub nonce: u64,
+    /// The hex encoded hash of the block header with the nonce set
+    pub hash: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmitResponse {
+    pub status: Option<String>,
+    pub error: Option<RpcError>,
+}
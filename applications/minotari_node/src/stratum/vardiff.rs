@@ -0,0 +1,170 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    cmp,
+    time::{Duration, Instant},
+};
+
+/// The largest factor by which the share difficulty of a worker changes in a single retarget
+const MAX_RETARGET_FACTOR: u64 = 4;
+
+/// Variable share difficulty of a single worker. The share difficulty is retargeted so that the worker submits a share
+/// every `target_share_time` on average, regardless of its hash rate.
+#[derive(Debug, Clone)]
+pub struct Vardiff {
+    difficulty: u64,
+    previous_difficulty: u64,
+    min_difficulty: u64,
+    target_share_time: Duration,
+    retarget_time: Duration,
+    last_retarget: Instant,
+    shares_since_retarget: u64,
+}
+
+impl Vardiff {
+    pub fn new(
+        initial_difficulty: u64,
+        min_difficulty: u64,
+        target_share_time: Duration,
+        retarget_time: Duration,
+        now: Instant,
+    ) -> Self {
+        let min_difficulty = cmp::max(min_difficulty, 1);
+        let difficulty = cmp::max(initial_difficulty, min_difficulty);
+        Self {
+            difficulty,
+            previous_difficulty: difficulty,
+            min_difficulty,
+            target_share_time: cmp::max(target_share_time, Duration::from_millis(1)),
+            retarget_time,
+            last_retarget: now,
+            shares_since_retarget: 0,
+        }
+    }
+
+    /// The current share difficulty of the worker
+    pub fn difficulty(&self) -> u64 {
+        self.difficulty
+    }
+
+    /// The lowest difficulty at which a share of the worker is accepted. Shares that were mined on a job sent before
+    /// the last retarget are accepted at the previous share difficulty until the next retarget.
+    pub fn accepted_difficulty(&self) -> u64 {
+        cmp::min(self.difficulty, self.previous_difficulty)
+    }
+
+    /// Records an accepted share of the worker. Returns the new share difficulty if the share caused a retarget.
+    pub fn record_share(&mut self, now: Instant) -> Option<u64> {
+        self.shares_since_retarget = self.shares_since_retarget.saturating_add(1);
+        self.retarget(now)
+    }
+
+    /// Retargets the share difficulty once the retarget time has elapsed, or earlier if the worker submits shares much
+    /// faster than the target share time. Returns the new share difficulty if it changed.
+    pub fn retarget(&mut self, now: Instant) -> Option<u64> {
+        let elapsed = now.saturating_duration_since(self.last_retarget);
+        if elapsed < self.retarget_time && self.shares_since_retarget < self.early_retarget_shares() {
+            return None;
+        }
+
+        // The hash rate of the worker is estimated from the shares it submitted since the last retarget
+        let current = u128::from(self.difficulty);
+        let estimated = current
+            .saturating_mul(u128::from(self.shares_since_retarget))
+            .saturating_mul(self.target_share_time.as_millis()) /
+            cmp::max(elapsed.as_millis(), 1);
+        let new_difficulty = estimated.clamp(
+            current / u128::from(MAX_RETARGET_FACTOR),
+            current.saturating_mul(u128::from(MAX_RETARGET_FACTOR)),
+        );
+        let new_difficulty = cmp::max(u64::try_from(new_difficulty).unwrap_or(u64::MAX), self.min_difficulty);
+
+        self.last_retarget = now;
+        self.shares_since_retarget = 0;
+        self.previous_difficulty = self.difficulty;
+        // Small adjustments are not worth sending the worker a new job for
+        if new_difficulty.abs_diff(self.difficulty) <= self.difficulty / 10 {
+            return None;
+        }
+        self.difficulty = new_difficulty;
+        Some(new_difficulty)
+    }
+
+    fn early_retarget_shares(&self) -> u64 {
+        let expected_shares = self.retarget_time.as_millis() / self.target_share_time.as_millis();
+        u64::try_from(expected_shares)
+            .unwrap_or(u64::MAX)
+            .saturating_mul(MAX_RETARGET_FACTOR)
+            .max(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vardiff(now: Instant) -> Vardiff {
+        Vardiff::new(1_000, 10, Duration::from_secs(10), Duration::from_secs(60), now)
+    }
+
+    #[test]
+    fn it_increases_the_difficulty_of_a_fast_worker() {
+        let start = Instant::now();
+        let mut vardiff = vardiff(start);
+        // 6 shares are expected per retarget, a worker that submits 24 shares within a second is retargeted early
+        for i in 1..24 {
+            assert_eq!(vardiff.record_share(start + Duration::from_millis(i * 10)), None);
+        }
+        assert_eq!(vardiff.record_share(start + Duration::from_secs(1)), Some(4_000));
+        assert_eq!(vardiff.difficulty(), 4_000);
+        assert_eq!(vardiff.accepted_difficulty(), 1_000);
+    }
+
+    #[test]
+    fn it_decreases_the_difficulty_of_a_slow_worker() {
+        let start = Instant::now();
+        let mut vardiff = vardiff(start);
+        assert_eq!(vardiff.retarget(start + Duration::from_secs(30)), None);
+        assert_eq!(vardiff.record_share(start + Duration::from_secs(40)), None);
+        // One share in 60 seconds is a sixth of the target share rate
+        assert_eq!(vardiff.retarget(start + Duration::from_secs(60)), Some(250));
+        assert_eq!(vardiff.accepted_difficulty(), 250);
+
+        let mut now = start + Duration::from_secs(60);
+        for _ in 0..10 {
+            now += Duration::from_secs(60);
+            vardiff.retarget(now);
+        }
+        assert_eq!(vardiff.difficulty(), 10);
+    }
+
+    #[test]
+    fn it_keeps_the_difficulty_of_a_worker_on_target() {
+        let start = Instant::now();
+        let mut vardiff = vardiff(start);
+        for i in 1..=6 {
+            assert_eq!(vardiff.record_share(start + Duration::from_secs(i * 10)), None);
+        }
+        assert_eq!(vardiff.difficulty(), 1_000);
+    }
+}
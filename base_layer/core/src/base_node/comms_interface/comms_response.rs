@@ -35,6 +35,7 @@ use crate::{
     chain_storage::TemplateRegistrationEntry,
     proof_of_work::Difficulty,
     transactions::transaction_components::{Transaction, TransactionKernel, TransactionOutput},
+    OutputSmtProof,
 };
 
 /// API Response enum
@@ -61,6 +62,7 @@ pub enum NodeCommsResponse {
     FetchValidatorNodesKeysResponse(Vec<(PublicKey, [u8; 32])>),
     GetShardKeyResponse(Option<[u8; 32]>),
     FetchTemplateRegistrationsResponse(Vec<TemplateRegistrationEntry>),
+    OutputSmtProofs(Vec<(HashOutput, u64, OutputSmtProof)>),
 }
 
 impl Display for NodeCommsResponse {
@@ -98,6 +100,7 @@ impl Display for NodeCommsResponse {
             FetchValidatorNodesKeysResponse(_) => write!(f, "FetchValidatorNodesKeysResponse"),
             GetShardKeyResponse(_) => write!(f, "GetShardKeyResponse"),
             FetchTemplateRegistrationsResponse(_) => write!(f, "FetchTemplateRegistrationsResponse"),
+            OutputSmtProofs(proofs) => write!(f, "OutputSmtProofs({})", proofs.len()),
         }
     }
 }
@@ -434,6 +434,16 @@ where B: BlockchainBackend + 'static
                 let utxos = self.blockchain_db.fetch_outputs_in_block(block_hash).await?;
                 Ok(NodeCommsResponse::TransactionOutputs(utxos))
             },
+            NodeCommsRequest::FetchOutputSmtProofs {
+                output_hashes,
+                header_hash,
+            } => {
+                let proofs = self
+                    .blockchain_db
+                    .fetch_output_smt_proofs(output_hashes, header_hash)
+                    .await?;
+                Ok(NodeCommsResponse::OutputSmtProofs(proofs))
+            },
         }
     }
 
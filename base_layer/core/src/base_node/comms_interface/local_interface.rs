@@ -41,6 +41,7 @@ use crate::{
     chain_storage::TemplateRegistrationEntry,
     proof_of_work::PowAlgorithm,
     transactions::transaction_components::{TransactionKernel, TransactionOutput},
+    OutputSmtProof,
 };
 
 pub type BlockEventSender = broadcast::Sender<Arc<BlockEvent>>;
@@ -342,4 +343,24 @@ impl LocalNodeCommsInterface {
             _ => Err(CommsInterfaceError::UnexpectedApiResponse),
         }
     }
+
+    /// Fetches output SMT proofs of the spent status of the given outputs against the `output_mr` of `header_hash`,
+    /// which must be the current chain tip. Outputs that are not known are omitted.
+    pub async fn fetch_output_smt_proofs(
+        &mut self,
+        output_hashes: Vec<HashOutput>,
+        header_hash: BlockHash,
+    ) -> Result<Vec<(HashOutput, u64, OutputSmtProof)>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchOutputSmtProofs {
+                output_hashes,
+                header_hash,
+            })
+            .await??
+        {
+            NodeCommsResponse::OutputSmtProofs(proofs) => Ok(proofs),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
 }
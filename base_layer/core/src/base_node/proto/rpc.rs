@@ -24,7 +24,11 @@ use std::convert::{TryFrom, TryInto};
 
 use tari_utilities::ByteArray;
 
-use crate::{blocks::Block, mempool::FeePerGramStat, proto::base_node as proto};
+use crate::{
+    blocks::Block,
+    mempool::{FeeEstimate, FeePerGramStat},
+    proto::base_node as proto,
+};
 
 impl TryFrom<Block> for proto::BlockBodyResponse {
     type Error = String;
@@ -45,6 +49,16 @@ impl From<Vec<FeePerGramStat>> for proto::GetMempoolFeePerGramStatsResponse {
     }
 }
 
+impl From<FeeEstimate> for proto::EstimateFeeResponse {
+    fn from(estimate: FeeEstimate) -> Self {
+        Self {
+            fee_per_gram: estimate.fee_per_gram.as_u64(),
+            confirmation_target: estimate.confirmation_target,
+            is_historical: estimate.is_historical,
+        }
+    }
+}
+
 impl From<FeePerGramStat> for proto::MempoolFeePerGramStat {
     fn from(stat: FeePerGramStat) -> Self {
         Self {
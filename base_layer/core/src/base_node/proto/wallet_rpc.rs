@@ -27,9 +27,10 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::{BlockHash, Signature};
+use tari_mmr::sparse_merkle_tree::{ExclusionProof, InclusionProof, LeafNode, NodeHash, NodeKey, ValueHash};
 use tari_utilities::ByteArray;
 
-use crate::proto::base_node as proto;
+use crate::{proto::base_node as proto, OutputSmtProof};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TxSubmissionResponse {
@@ -270,3 +271,50 @@ impl TryFrom<proto::TxQueryBatchResponse> for TxQueryBatchResponse {
         })
     }
 }
+
+impl TryFrom<&proto::OutputSmtProof> for OutputSmtProof {
+    type Error = String;
+
+    fn try_from(proto_proof: &proto::OutputSmtProof) -> Result<Self, Self::Error> {
+        let siblings = proto_proof
+            .siblings
+            .iter()
+            .map(|sibling| smt_hash_from_bytes(sibling).map(NodeHash::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        if proto_proof.is_unspent {
+            return Ok(OutputSmtProof::Inclusion(InclusionProof::new(siblings)));
+        }
+        let leaf = if proto_proof.leaf_key.is_empty() && proto_proof.leaf_value_hash.is_empty() {
+            None
+        } else {
+            Some(LeafNode::new(
+                NodeKey::from(smt_hash_from_bytes(&proto_proof.leaf_key)?),
+                ValueHash::from(smt_hash_from_bytes(&proto_proof.leaf_value_hash)?),
+            ))
+        };
+        Ok(OutputSmtProof::Exclusion(ExclusionProof::new(siblings, leaf)))
+    }
+}
+
+impl From<OutputSmtProof> for proto::OutputSmtProof {
+    fn from(proof: OutputSmtProof) -> Self {
+        match proof {
+            OutputSmtProof::Inclusion(proof) => Self {
+                is_unspent: true,
+                siblings: proof.siblings().iter().map(|s| s.as_slice().to_vec()).collect(),
+                ..Default::default()
+            },
+            OutputSmtProof::Exclusion(proof) => Self {
+                is_unspent: false,
+                siblings: proof.siblings().iter().map(|s| s.as_slice().to_vec()).collect(),
+                leaf_key: proof.leaf().map(|l| l.key().as_slice().to_vec()).unwrap_or_default(),
+                leaf_value_hash: proof.leaf().map(|l| l.value().as_slice().to_vec()).unwrap_or_default(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+fn smt_hash_from_bytes(bytes: &[u8]) -> Result<[u8; 32], String> {
+    <[u8; 32]>::try_from(bytes).map_err(|_| format!("Malformed SMT proof hash of length {}", bytes.len()))
+}
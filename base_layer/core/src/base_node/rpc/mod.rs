@@ -41,10 +41,16 @@ use crate::{
     proto,
     proto::{
         base_node::{
+            EstimateFeeRequest,
+            EstimateFeeResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
+            GetBlockFiltersRequest,
+            GetBlockFiltersResponse,
             GetMempoolFeePerGramStatsRequest,
             GetMempoolFeePerGramStatsResponse,
+            GetOutputSmtProofsRequest,
+            GetOutputSmtProofsResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
             Signatures,
@@ -119,6 +125,24 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
         &self,
         request: Request<GetMempoolFeePerGramStatsRequest>,
     ) -> Result<Response<GetMempoolFeePerGramStatsResponse>, RpcStatus>;
+
+    #[rpc(method = 13)]
+    async fn estimate_fee(
+        &self,
+        request: Request<EstimateFeeRequest>,
+    ) -> Result<Response<EstimateFeeResponse>, RpcStatus>;
+
+    #[rpc(method = 14)]
+    async fn get_output_smt_proofs(
+        &self,
+        request: Request<GetOutputSmtProofsRequest>,
+    ) -> Result<Response<GetOutputSmtProofsResponse>, RpcStatus>;
+
+    #[rpc(method = 15)]
+    async fn get_block_filters(
+        &self,
+        request: Request<GetBlockFiltersRequest>,
+    ) -> Result<Response<GetBlockFiltersResponse>, RpcStatus>;
 }
 
 #[cfg(feature = "base_node")]
@@ -35,15 +35,23 @@ use crate::{
         state_machine_service::states::StateInfo,
         StateMachineHandle,
     },
-    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
-    mempool::{service::MempoolHandle, TxStorageResponse},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError},
+    mempool::{service::MempoolHandle, TxStorageResponse, DEFAULT_CONFIDENCE_PERCENT, MAX_CONFIRMATION_TARGET},
     proto,
     proto::{
         base_node::{
+            BlockFilter as BlockFilterProto,
+            EstimateFeeRequest,
+            EstimateFeeResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
+            GetBlockFiltersRequest,
+            GetBlockFiltersResponse,
             GetMempoolFeePerGramStatsRequest,
             GetMempoolFeePerGramStatsResponse,
+            GetOutputSmtProofsRequest,
+            GetOutputSmtProofsResponse,
+            OutputSmtProof as OutputSmtProofProto,
             QueryDeletedData,
             QueryDeletedRequest,
             QueryDeletedResponse,
@@ -68,6 +76,8 @@ use crate::{
 
 const LOG_TARGET: &str = "c::base_node::rpc";
 const MAX_QUERY_DELETED_HASHES: usize = 1000;
+const MAX_OUTPUT_SMT_PROOF_HASHES: usize = 1000;
+const MAX_BLOCK_FILTERS: u64 = 1000;
 
 pub struct BaseNodeWalletRpcService<B> {
     db: AsyncBlockchainDb<B>,
@@ -146,7 +156,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             .await
             .rpc_status_internal_error(LOG_TARGET)?
         {
-            TxStorageResponse::UnconfirmedPool => TxQueryResponse {
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplacedByFee => TxQueryResponse {
                 location: TxLocation::InMempool as i32,
                 best_block_hash: vec![],
                 confirmations: 0,
@@ -161,6 +171,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredReplacementFeeTooLow |
             TxStorageResponse::NotStoredAlreadyMined => TxQueryResponse {
                 location: TxLocation::NotStored as i32,
                 best_block_hash: vec![],
@@ -198,10 +209,12 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
             .await
             .rpc_status_internal_error(LOG_TARGET)?
         {
-            TxStorageResponse::UnconfirmedPool => TxSubmissionResponse {
-                accepted: true,
-                rejection_reason: TxSubmissionRejectionReason::None.into(),
-                is_synced,
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplacedByFee => {
+                TxSubmissionResponse {
+                    accepted: true,
+                    rejection_reason: TxSubmissionRejectionReason::None.into(),
+                    is_synced,
+                }
             },
 
             TxStorageResponse::NotStoredOrphan => TxSubmissionResponse {
@@ -209,10 +222,12 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
                 rejection_reason: TxSubmissionRejectionReason::Orphan.into(),
                 is_synced,
             },
-            TxStorageResponse::NotStoredFeeTooLow => TxSubmissionResponse {
-                accepted: false,
-                rejection_reason: TxSubmissionRejectionReason::FeeTooLow.into(),
-                is_synced,
+            TxStorageResponse::NotStoredFeeTooLow | TxStorageResponse::NotStoredReplacementFeeTooLow => {
+                TxSubmissionResponse {
+                    accepted: false,
+                    rejection_reason: TxSubmissionRejectionReason::FeeTooLow.into(),
+                    is_synced,
+                }
             },
             TxStorageResponse::NotStoredTimeLocked => TxSubmissionResponse {
                 accepted: false,
@@ -677,4 +692,136 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         Ok(Response::new(stats.into()))
     }
+
+    async fn estimate_fee(
+        &self,
+        request: Request<EstimateFeeRequest>,
+    ) -> Result<Response<EstimateFeeResponse>, RpcStatus> {
+        let req = request.into_message();
+        if req.confirmation_target == 0 || req.confirmation_target > MAX_CONFIRMATION_TARGET {
+            return Err(RpcStatus::bad_request(&format!(
+                "confirmation_target must be between 1 and {}",
+                MAX_CONFIRMATION_TARGET
+            )));
+        }
+        if req.confidence_percent > 100 {
+            return Err(RpcStatus::bad_request(
+                "confidence_percent must be less than or equal to 100",
+            ));
+        }
+        let confidence_percent = if req.confidence_percent == 0 {
+            DEFAULT_CONFIDENCE_PERCENT
+        } else {
+            req.confidence_percent
+        };
+
+        let metadata = self
+            .db
+            .get_chain_metadata()
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+        let estimate = self
+            .mempool()
+            .estimate_fee(
+                req.confirmation_target,
+                confidence_percent,
+                metadata.best_block_height(),
+            )
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+
+        Ok(Response::new(estimate.into()))
+    }
+
+    async fn get_output_smt_proofs(
+        &self,
+        request: Request<GetOutputSmtProofsRequest>,
+    ) -> Result<Response<GetOutputSmtProofsResponse>, RpcStatus> {
+        let message = request.into_message();
+        if message.output_hashes.len() > MAX_OUTPUT_SMT_PROOF_HASHES {
+            return Err(RpcStatus::bad_request(&format!(
+                "Received more hashes than we allow (max: {})",
+                MAX_OUTPUT_SMT_PROOF_HASHES
+            )));
+        }
+        let header_hash = FixedHash::try_from(message.header_hash)
+            .map_err(|_| RpcStatus::bad_request(&"Malformed block hash received".to_string()))?;
+        let hashes: Vec<FixedHash> = message
+            .output_hashes
+            .into_iter()
+            .map(FixedHash::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(|_| RpcStatus::bad_request(&"Malformed utxo hash received".to_string()))?;
+
+        let db = self.db();
+        let proofs = match db.fetch_output_smt_proofs(hashes, header_hash).await {
+            Ok(proofs) => proofs,
+            Err(ChainStorageError::InvalidArguments { message, .. }) => {
+                return Err(RpcStatus::bad_request(&message));
+            },
+            Err(e) => return Err(e).rpc_status_internal_error(LOG_TARGET),
+        };
+        let header = db
+            .fetch_header_by_block_hash(header_hash)
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?
+            .ok_or_else(|| RpcStatus::not_found(&format!("Header {} not found", header_hash)))?;
+
+        Ok(Response::new(GetOutputSmtProofsResponse {
+            proofs: proofs
+                .into_iter()
+                .map(|(output_hash, mined_at_height, proof)| OutputSmtProofProto {
+                    output_hash: output_hash.to_vec(),
+                    mined_at_height,
+                    ..proof.into()
+                })
+                .collect(),
+            header: Some(header.into()),
+        }))
+    }
+
+    async fn get_block_filters(
+        &self,
+        request: Request<GetBlockFiltersRequest>,
+    ) -> Result<Response<GetBlockFiltersResponse>, RpcStatus> {
+        let message = request.into_message();
+        if message.end_height < message.start_height {
+            return Err(RpcStatus::bad_request(
+                &"end_height is less than start_height".to_string(),
+            ));
+        }
+        if message.end_height - message.start_height >= MAX_BLOCK_FILTERS {
+            return Err(RpcStatus::bad_request(&format!(
+                "Requested more block filters than we allow (max: {})",
+                MAX_BLOCK_FILTERS
+            )));
+        }
+
+        let filters = match self
+            .db()
+            .fetch_block_filters(message.start_height, message.end_height)
+            .await
+        {
+            Ok(filters) => filters,
+            Err(ChainStorageError::InvalidArguments { message, .. }) => {
+                return Err(RpcStatus::bad_request(&message));
+            },
+            Err(e) => return Err(e).rpc_status_internal_error(LOG_TARGET),
+        };
+
+        Ok(Response::new(GetBlockFiltersResponse {
+            filters: filters
+                .into_iter()
+                .map(|(header_hash, height, filter)| {
+                    let (num_elements, data) = filter.into_parts();
+                    BlockFilterProto {
+                        header_hash: header_hash.to_vec(),
+                        height,
+                        num_elements,
+                        data,
+                    }
+                })
+                .collect(),
+        }))
+    }
 }
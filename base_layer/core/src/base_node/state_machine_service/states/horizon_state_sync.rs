@@ -24,12 +24,14 @@
 //! Horizon state synchronisation module for pruned mode.
 
 use log::*;
+use tari_common_types::types::FixedHash;
+use tari_utilities::hex::Hex;
 
 use super::{StateEvent, StateInfo};
 use crate::{
     base_node::{
         state_machine_service::states::StatusInfo,
-        sync::{HorizonStateSynchronization, SyncPeer},
+        sync::{HorizonStateSynchronization, SyncPeer, UtxoSnapshotSync},
         BaseNodeStateMachine,
     },
     chain_storage::BlockchainBackend,
@@ -84,6 +86,17 @@ impl HorizonStateSync {
             );
             return StateEvent::HorizonStateSynchronized;
         }
+        if local_metadata.best_block_height() == 0 {
+            if let Some(height) = Self::load_utxo_snapshot(shared).await {
+                if height >= target_horizon_sync_height {
+                    info!(
+                        target: LOG_TARGET,
+                        "UTXO snapshot at height {} loaded. Horizon state is already synchronized.", height
+                    );
+                    return StateEvent::HorizonStateSynchronized;
+                }
+            }
+        }
 
         let db = shared.db.clone();
         let config = shared.config.blockchain_sync_config.clone();
@@ -142,6 +155,50 @@ impl HorizonStateSync {
             },
         }
     }
+
+    /// Loads the configured UTXO snapshot, if any, into a node without blocks. Returns the snapshot height if it was
+    /// loaded. If the snapshot cannot be loaded, the horizon state is synced from peers instead.
+    async fn load_utxo_snapshot<B: BlockchainBackend + 'static>(shared: &BaseNodeStateMachine<B>) -> Option<u64> {
+        let config = &shared.config.blockchain_sync_config;
+        let path = config.utxo_snapshot_file.clone()?;
+        let expected_hash = match config
+            .utxo_snapshot_hash
+            .as_deref()
+            .filter(|hash| !hash.is_empty())
+            .map(FixedHash::from_hex)
+            .transpose()
+        {
+            Ok(hash) => hash,
+            Err(err) => {
+                warn!(target: LOG_TARGET, "Ignoring UTXO snapshot: invalid snapshot hash in config. {}", err);
+                return None;
+            },
+        };
+
+        let snapshot_sync = UtxoSnapshotSync::new(
+            shared.db.clone(),
+            shared.consensus_rules.clone(),
+            path,
+            expected_hash,
+            CryptoFactories::default().range_proof,
+            shared.sync_validators.final_horizon_state.clone(),
+        );
+        match snapshot_sync.load().await {
+            Ok(height) => {
+                if config.utxo_snapshot_back_validation {
+                    snapshot_sync.spawn_back_validation(height);
+                }
+                Some(height)
+            },
+            Err(err) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not load the UTXO snapshot, syncing horizon state from peers instead. {}", err
+                );
+                None
+            },
+        }
+    }
 }
 
 impl From<Vec<SyncPeer>> for HorizonStateSync {
@@ -75,6 +75,8 @@ pub enum BlockSyncError {
     PeerNotFound,
     #[error("Peer did not supply all the blocks they claimed they had: {0}")]
     PeerDidNotSupplyAllClaimedBlocks(String),
+    #[error("Peer sent more blocks than were requested: {0}")]
+    PeerSentTooManyBlocks(String),
 }
 
 impl BlockSyncError {
@@ -100,6 +102,7 @@ impl BlockSyncError {
             BlockSyncError::SyncRoundFailed => "SyncRoundFailed",
             BlockSyncError::PeerNotFound => "PeerNotFound",
             BlockSyncError::PeerDidNotSupplyAllClaimedBlocks(_) => "PeerDidNotSupplyAllClaimedBlocks",
+            BlockSyncError::PeerSentTooManyBlocks(_) => "PeerSentTooManyBlocks",
         }
     }
 }
@@ -129,6 +132,7 @@ impl BlockSyncError {
             err @ BlockSyncError::BlockWithoutParent { .. } |
             err @ BlockSyncError::UnknownHeaderHash(_) |
             err @ BlockSyncError::InvalidBlockBody(_) |
+            err @ BlockSyncError::PeerSentTooManyBlocks(_) |
             err @ BlockSyncError::FixedHashSizeError(_) => Some(BanReason {
                 reason: format!("{}", err),
                 ban_duration: BanPeriod::Long,
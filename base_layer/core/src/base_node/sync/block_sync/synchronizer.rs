@@ -21,27 +21,31 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    cmp,
+    cmp::Ordering,
+    collections::BTreeMap,
     convert::{TryFrom, TryInto},
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use futures::StreamExt;
+use futures::{stream::FuturesUnordered, StreamExt};
 use log::*;
+use tari_common_types::types::HashOutput;
 use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, protocol::rpc::RpcClient, PeerConnection};
 use tari_utilities::hex::Hex;
-use tokio::task;
+use tokio::{task, task::JoinHandle};
 
 use super::error::BlockSyncError;
 use crate::{
     base_node::{
-        sync::{ban::PeerBanManager, hooks::Hooks, rpc, SyncPeer},
+        sync::{ban::PeerBanManager, hooks::Hooks, rpc, sync_peer::is_sync_peer_too_slow, SyncPeer},
         BlockchainSyncConfig,
     },
     blocks::{Block, ChainBlock},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
     common::{rolling_avg::RollingAverageTime, BanPeriod},
-    proto::base_node::SyncBlocksRequest,
+    proto::base_node::{BlockBodyResponse, SyncBlocksRequest},
     transactions::aggregated_body::AggregateBody,
     validation::{BlockBodyValidator, ValidationError},
 };
@@ -60,6 +64,41 @@ pub struct BlockSynchronizer<'a, B> {
     peer_ban_manager: PeerBanManager,
 }
 
+/// A contiguous range of blocks that is requested from a single sync peer
+struct BlockChunk {
+    start_height: u64,
+    end_height: u64,
+    /// The hash of the block preceding `start_height`
+    start_hash: HashOutput,
+    end_hash: HashOutput,
+}
+
+impl BlockChunk {
+    fn num_blocks(&self) -> u64 {
+        self.end_height - self.start_height + 1
+    }
+}
+
+/// A connected sync peer that downloads block chunks
+struct SyncWorker {
+    sync_peer: SyncPeer,
+    client: rpc::BaseNodeSyncRpcClient,
+}
+
+struct ChunkDownload {
+    worker: SyncWorker,
+    chunk: BlockChunk,
+    result: Result<Vec<BlockBodyResponse>, BlockSyncError>,
+}
+
+/// A downloaded block body that is waiting to be validated and stored
+struct DownloadedBlock {
+    hash: Vec<u8>,
+    body: Result<AggregateBody, BlockSyncError>,
+    /// Set if the signatures and range proofs of the body have already been verified
+    proofs_verified: bool,
+}
+
 impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
     pub fn new(
         config: BlockchainSyncConfig,
@@ -135,14 +174,19 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
     }
 
     async fn attempt_block_sync(&mut self, max_latency: Duration) -> Result<(), BlockSyncError> {
-        let sync_peer_node_ids = self.sync_peers.iter().map(|p| p.node_id()).cloned().collect::<Vec<_>>();
+        let sync_peer_node_ids = self.select_sync_peers();
         info!(
             target: LOG_TARGET,
             "Attempting to sync blocks({} sync peers)",
             sync_peer_node_ids.len()
         );
         let mut latency_counter = 0usize;
+        let max_workers = cmp::max(self.config.max_concurrent_block_sync_peers, 1);
+        let mut workers = Vec::with_capacity(max_workers);
         for node_id in sync_peer_node_ids {
+            if workers.len() >= max_workers {
+                break;
+            }
             let peer_index = self.get_sync_peer_index(&node_id).ok_or(BlockSyncError::PeerNotFound)?;
             let sync_peer = &self.sync_peers[peer_index];
             self.hooks.call_on_starting_hook(sync_peer);
@@ -160,7 +204,7 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
             let config = RpcClient::builder()
                 .with_deadline(self.config.rpc_deadline)
                 .with_deadline_grace_period(Duration::from_secs(5));
-            let mut client = match conn
+            let client = match conn
                 .connect_rpc_using_builder::<rpc::BaseNodeSyncRpcClient>(config)
                 .await
             {
@@ -183,27 +227,17 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
                 target: LOG_TARGET,
                 "Attempting to synchronize blocks with `{}` latency: {:.2?}", node_id, latency
             );
-            match self.synchronize_blocks(sync_peer, client, max_latency).await {
+            workers.push(SyncWorker { sync_peer, client });
+        }
+
+        if !workers.is_empty() {
+            match self
+                .synchronize_blocks(workers, max_latency, &mut latency_counter)
+                .await
+            {
                 Ok(_) => return Ok(()),
-                Err(err) => {
-                    warn!(target: LOG_TARGET, "{}", err);
-                    let ban_reason = BlockSyncError::get_ban_reason(&err);
-                    if let Some(reason) = ban_reason {
-                        let duration = match reason.ban_duration {
-                            BanPeriod::Short => self.config.short_ban_period,
-                            BanPeriod::Long => self.config.ban_period,
-                        };
-                        warn!(target: LOG_TARGET, "{}", err);
-                        self.peer_ban_manager
-                            .ban_peer_if_required(&node_id, reason.reason, duration)
-                            .await;
-                    }
-                    if let BlockSyncError::MaxLatencyExceeded { .. } = err {
-                        latency_counter += 1;
-                    } else {
-                        self.remove_sync_peer(&node_id);
-                    }
-                },
+                Err(BlockSyncError::SyncRoundFailed) => {},
+                Err(err) => return Err(err),
             }
         }
 
@@ -221,15 +255,15 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
         Ok(connection)
     }
 
-    #[allow(clippy::too_many_lines)]
+    /// Downloads the missing blocks in chunks from all the given sync peers concurrently, and validates and stores
+    /// them in height order. Errors caused by a sync peer are handled here, by banning and dropping that sync peer; if
+    /// the blocks cannot be synced with the remaining sync peers, `SyncRoundFailed` is returned.
     async fn synchronize_blocks(
         &mut self,
-        mut sync_peer: SyncPeer,
-        mut client: rpc::BaseNodeSyncRpcClient,
+        workers: Vec<SyncWorker>,
         max_latency: Duration,
+        latency_counter: &mut usize,
     ) -> Result<(), BlockSyncError> {
-        info!(target: LOG_TARGET, "Starting block sync from peer {}", sync_peer);
-
         let tip_header = self.db.fetch_last_header().await?;
         let local_metadata = self.db.get_chain_metadata().await?;
 
@@ -249,188 +283,413 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
         let best_full_block_hash = chain_header.accumulated_data().hash;
         debug!(
             target: LOG_TARGET,
-            "Starting block sync from peer `{}`. Current best block is #{} `{}`. Syncing to #{} ({}).",
-            sync_peer,
+            "Starting block sync from {} sync peer(s). Current best block is #{} `{}`. Syncing to #{} ({}).",
+            workers.len(),
             best_height,
             best_full_block_hash.to_hex(),
             tip_height,
             tip_hash.to_hex()
         );
-        let request = SyncBlocksRequest {
-            start_hash: best_full_block_hash.to_vec(),
-            // To the tip!
-            end_hash: tip_hash.to_vec(),
-        };
 
-        let mut block_stream = client.sync_blocks(request).await?;
-        let mut prev_hash = best_full_block_hash;
-        let mut current_block = None;
-        let mut last_sync_timer = Instant::now();
-        let mut avg_latency = RollingAverageTime::new(20);
-        while let Some(block_result) = block_stream.next().await {
-            let latency = last_sync_timer.elapsed();
-            avg_latency.add_sample(latency);
-            let block_body_response = block_result?;
-
-            let header = self
-                .db
-                .fetch_chain_header_by_block_hash(block_body_response.hash.clone().try_into()?)
-                .await?
-                .ok_or_else(|| {
-                    BlockSyncError::UnknownHeaderHash(format!(
-                        "Peer sent hash ({}) for block header we do not have",
-                        block_body_response.hash.to_hex()
-                    ))
-                })?;
-
-            let current_height = header.height();
-            let header_hash = *header.hash();
-            let timestamp = header.timestamp();
-
-            if header.header().prev_hash != prev_hash {
-                return Err(BlockSyncError::BlockWithoutParent {
-                    expected: prev_hash.to_hex(),
-                    got: header.header().prev_hash.to_hex(),
-                });
+        let chunks = self
+            .split_into_chunks(best_height, best_full_block_hash, tip_height, tip_hash)
+            .await?;
+        let participants = workers
+            .iter()
+            .map(|w| w.sync_peer.node_id().clone())
+            .collect::<Vec<_>>();
+        let mut downloads = FuturesUnordered::new();
+        let result = self
+            .sync_chunks(
+                chunks,
+                workers,
+                &mut downloads,
+                best_full_block_hash,
+                tip_height,
+                max_latency,
+                latency_counter,
+            )
+            .await;
+        // Stop any downloads that are still in flight if the sync round ended early
+        downloads.iter().for_each(|download| download.abort());
+        let (next_height, current_block) = result?;
+
+        if next_height <= tip_height {
+            warn!(
+                target: LOG_TARGET,
+                "Block sync stopped at height {} of {}: no remaining sync peers could supply the missing blocks",
+                next_height.saturating_sub(1),
+                tip_height
+            );
+            return Err(BlockSyncError::SyncRoundFailed);
+        }
+
+        let accumulated_difficulty = self.db.get_chain_metadata().await?.accumulated_difficulty();
+        let mut all_claimed_blocks_supplied = true;
+        for node_id in participants {
+            let claimed_difficulty = match self.get_sync_peer_index(&node_id) {
+                Some(index) => self.sync_peers[index].claimed_chain_metadata().accumulated_difficulty(),
+                None => continue,
+            };
+            if accumulated_difficulty < claimed_difficulty {
+                all_claimed_blocks_supplied = false;
+                let err = BlockSyncError::PeerDidNotSupplyAllClaimedBlocks(format!(
+                    "Their claimed difficulty: {}, our local difficulty after block sync: {}",
+                    claimed_difficulty, accumulated_difficulty
+                ));
+                self.handle_sync_peer_error(&node_id, err, latency_counter).await;
             }
+        }
+        if !all_claimed_blocks_supplied {
+            return Err(BlockSyncError::SyncRoundFailed);
+        }
 
-            prev_hash = header_hash;
+        if let Some(block) = current_block {
+            self.hooks.call_on_complete_hooks(block, best_height);
+        }
 
-            let body = block_body_response
-                .body
-                .map(AggregateBody::try_from)
-                .ok_or_else(|| BlockSyncError::InvalidBlockBody("Peer sent empty block".to_string()))?
-                .map_err(BlockSyncError::InvalidBlockBody)?;
+        debug!(target: LOG_TARGET, "Completed block sync at height {}", tip_height);
 
-            debug!(
-                target: LOG_TARGET,
-                "Validating block body #{} (PoW = {}, {}, latency: {:.2?})",
-                current_height,
-                header.header().pow_algo(),
-                body.to_counts_string(),
-                latency
-            );
+        Ok(())
+    }
 
-            let timer = Instant::now();
-            let (header, header_accum_data) = header.into_parts();
-            let block = Block::new(header, body);
-
-            // Validate the block inside a tokio task
-            let task_block = block.clone();
-            let db = self.db.inner().clone();
-            let validator = self.block_validator.clone();
-            let res = task::spawn_blocking(move || {
-                let txn = db.db_read_access()?;
-                let smt = db.smt().clone();
-                validator.validate_body(&*txn, &task_block, smt)
-            })
-            .await?;
+    /// Hands out chunks to idle sync peers and reassembles the downloaded chunks in order for validation. The
+    /// signatures and range proofs of each downloaded chunk are verified in a separate task, so up to
+    /// `validation_concurrency` buffered chunks are verified concurrently while blocks are stored in order. Sync peers
+    /// that are much slower than the others are deprioritised: they are only handed chunks once no other sync peer is
+    /// downloading. Returns the next height that still needs to be synced and the last block that was stored.
+    #[allow(clippy::too_many_arguments)]
+    async fn sync_chunks(
+        &mut self,
+        mut pending_chunks: BTreeMap<u64, BlockChunk>,
+        mut idle_workers: Vec<SyncWorker>,
+        downloads: &mut FuturesUnordered<JoinHandle<ChunkDownload>>,
+        mut prev_hash: HashOutput,
+        tip_height: u64,
+        max_latency: Duration,
+        latency_counter: &mut usize,
+    ) -> Result<(u64, Option<Arc<ChainBlock>>), BlockSyncError> {
+        let participants = idle_workers
+            .iter()
+            .map(|w| w.sync_peer.node_id().clone())
+            .collect::<Vec<_>>();
+        let mut num_workers = idle_workers.len();
+        let max_buffered_chunks = cmp::max(self.config.validation_concurrency, 1);
+        let mut downloaded_chunks = BTreeMap::<u64, (SyncPeer, JoinHandle<Vec<DownloadedBlock>>)>::new();
+        let mut deprioritised_workers = Vec::new();
+        let mut next_height = pending_chunks.keys().next().copied().unwrap_or(tip_height + 1);
+        let mut current_block = None;
 
-            let block = match res {
-                Ok(block) => block,
-                Err(err @ ValidationError::BadBlockFound { .. }) | Err(err @ ValidationError::FatalStorageError(_)) => {
-                    return Err(err.into());
+        loop {
+            // Hand out chunks to the fastest idle sync peers while there is room to buffer them for validation. The
+            // chunk that validation is waiting on is always handed out.
+            while let Some((&start_height, chunk)) = pending_chunks.first_key_value() {
+                if downloaded_chunks.len() >= max_buffered_chunks && start_height != next_height {
+                    break;
+                }
+                let index = match fastest_idle_worker(&idle_workers, chunk.end_height) {
+                    Some(index) => index,
+                    None => break,
+                };
+                let worker = idle_workers.swap_remove(index);
+                let (_, chunk) = pending_chunks
+                    .pop_first()
+                    .expect("unreachable panic: first chunk was checked above");
+                downloads.push(task::spawn(download_chunk(worker, chunk, max_latency)));
+            }
+
+            let download = match downloads.next().await {
+                Some(download) => download?,
+                // Only the deprioritised sync peers are left to download the remaining chunks
+                None if !deprioritised_workers.is_empty() && !pending_chunks.is_empty() => {
+                    num_workers += deprioritised_workers.len();
+                    idle_workers.append(&mut deprioritised_workers);
+                    continue;
+                },
+                // Nothing is in flight and none of the remaining sync peers can download the remaining chunks
+                None => break,
+            };
+            let ChunkDownload { worker, chunk, result } = download;
+            let node_id = worker.sync_peer.node_id().clone();
+            self.update_sync_peer(&worker.sync_peer);
+            match result {
+                Ok(blocks) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Downloaded blocks #{} to #{} from peer `{}` ({:.2} blocks/s)",
+                        chunk.start_height,
+                        chunk.end_height,
+                        node_id,
+                        worker.sync_peer.items_per_second().unwrap_or_default()
+                    );
+                    let validator = self.block_validator.clone();
+                    let verification = task::spawn_blocking(move || verify_downloaded_blocks(&*validator, blocks));
+                    downloaded_chunks.insert(chunk.start_height, (worker.sync_peer.clone(), verification));
+                    if self.is_sync_peer_too_slow(&worker.sync_peer, &participants, num_workers) {
+                        num_workers -= 1;
+                        deprioritised_workers.push(worker);
+                    } else {
+                        idle_workers.push(worker);
+                    }
                 },
                 Err(err) => {
-                    // Add to bad blocks
-                    if let Err(err) = self
-                        .db
-                        .write_transaction()
-                        .delete_orphan(header_hash)
-                        .insert_bad_block(header_hash, current_height, err.to_string())
-                        .commit()
+                    pending_chunks.insert(chunk.start_height, chunk);
+                    num_workers -= 1;
+                    self.handle_sync_peer_error(&node_id, err, latency_counter).await;
+                },
+            }
+
+            // Validate and store the downloaded chunks that continue our chain, in order
+            while let Some((sync_peer, verification)) = downloaded_chunks.remove(&next_height) {
+                let blocks = verification.await?;
+                let num_blocks = blocks.len() as u64;
+                for downloaded_block in blocks {
+                    match self
+                        .validate_and_store_block(downloaded_block, &mut prev_hash, tip_height, &sync_peer)
                         .await
                     {
-                        error!(target: LOG_TARGET, "Failed to insert bad block: {}", err);
+                        Ok(block) => current_block = Some(block),
+                        Err(err) => {
+                            self.handle_sync_peer_error(sync_peer.node_id(), err, latency_counter)
+                                .await;
+                            return Err(BlockSyncError::SyncRoundFailed);
+                        },
                     }
-                    return Err(err.into());
-                },
+                }
+                next_height += num_blocks;
+            }
+        }
+
+        Ok((next_height, current_block))
+    }
+
+    /// Splits the blocks after `best_height` up to and including `tip_height` into chunks of the configured size
+    async fn split_into_chunks(
+        &self,
+        best_height: u64,
+        best_hash: HashOutput,
+        tip_height: u64,
+        tip_hash: HashOutput,
+    ) -> Result<BTreeMap<u64, BlockChunk>, BlockSyncError> {
+        let chunk_size = cmp::max(self.config.block_sync_chunk_size, 1);
+        let mut chunks = BTreeMap::new();
+        let mut start_height = best_height + 1;
+        let mut start_hash = best_hash;
+        while start_height <= tip_height {
+            let end_height = cmp::min(start_height.saturating_add(chunk_size - 1), tip_height);
+            let end_hash = if end_height == tip_height {
+                tip_hash
+            } else {
+                *self.db.fetch_chain_header(end_height).await?.hash()
             };
+            chunks.insert(start_height, BlockChunk {
+                start_height,
+                end_height,
+                start_hash,
+                end_hash,
+            });
+            start_height = end_height + 1;
+            start_hash = end_hash;
+        }
+        Ok(chunks)
+    }
 
-            let block = ChainBlock::try_construct(Arc::new(block), header_accum_data)
-                .map(Arc::new)
-                .ok_or(BlockSyncError::FailedToConstructChainBlock)?;
+    async fn validate_and_store_block(
+        &mut self,
+        downloaded_block: DownloadedBlock,
+        prev_hash: &mut HashOutput,
+        tip_height: u64,
+        sync_peer: &SyncPeer,
+    ) -> Result<Arc<ChainBlock>, BlockSyncError> {
+        let header = self
+            .db
+            .fetch_chain_header_by_block_hash(downloaded_block.hash.clone().try_into()?)
+            .await?
+            .ok_or_else(|| {
+                BlockSyncError::UnknownHeaderHash(format!(
+                    "Peer sent hash ({}) for block header we do not have",
+                    downloaded_block.hash.to_hex()
+                ))
+            })?;
+
+        let current_height = header.height();
+        let header_hash = *header.hash();
+        let timestamp = header.timestamp();
+
+        if header.header().prev_hash != *prev_hash {
+            return Err(BlockSyncError::BlockWithoutParent {
+                expected: prev_hash.to_hex(),
+                got: header.header().prev_hash.to_hex(),
+            });
+        }
 
-            debug!(
-                target: LOG_TARGET,
-                "Validated in {:.0?}. Storing block body #{} (PoW = {}, {})",
-                timer.elapsed(),
-                block.header().height,
-                block.header().pow_algo(),
-                block.block().body.to_counts_string(),
-            );
-            trace!(
-                target: LOG_TARGET,
-                "{}",block
-            );
+        *prev_hash = header_hash;
+
+        let body = downloaded_block.body?;
 
-            let timer = Instant::now();
-            self.db
-                .write_transaction()
-                .delete_orphan(header_hash)
-                .insert_tip_block_body(block.clone(), self.db.inner().smt())
-                .set_best_block(
-                    block.height(),
-                    header_hash,
-                    block.accumulated_data().total_accumulated_difficulty,
-                    block.header().prev_hash,
-                    timestamp,
-                )
-                .commit()
-                .await?;
-
-            // Average time between receiving blocks from the peer - used to detect a slow sync peer
-            let last_avg_latency = avg_latency.calculate_average_with_min_samples(5);
-            if let Some(latency) = last_avg_latency {
-                sync_peer.set_latency(latency);
+        debug!(
+            target: LOG_TARGET,
+            "Validating block body #{} (PoW = {}, {})",
+            current_height,
+            header.header().pow_algo(),
+            body.to_counts_string(),
+        );
+
+        let timer = Instant::now();
+        let (header, header_accum_data) = header.into_parts();
+        let block = Block::new(header, body);
+
+        // Validate the block inside a tokio task
+        let task_block = block.clone();
+        let db = self.db.inner().clone();
+        let validator = self.block_validator.clone();
+        let proofs_verified = downloaded_block.proofs_verified;
+        let res = task::spawn_blocking(move || {
+            let txn = db.db_read_access()?;
+            if proofs_verified {
+                validator.validate_body_with_verified_proofs(&*txn, &task_block)
+            } else {
+                validator.validate_body(&*txn, &task_block)
             }
-            // Includes time to add block to database, used to show blocks/s on status line
-            sync_peer.add_sample(last_sync_timer.elapsed());
-            self.hooks
-                .call_on_progress_block_hooks(block.clone(), tip_height, &sync_peer);
+        })
+        .await?;
+
+        let block = match res {
+            Ok(block) => block,
+            Err(err @ ValidationError::BadBlockFound { .. }) | Err(err @ ValidationError::FatalStorageError(_)) => {
+                return Err(err.into());
+            },
+            Err(err) => {
+                // Add to bad blocks
+                if let Err(err) = self
+                    .db
+                    .write_transaction()
+                    .delete_orphan(header_hash)
+                    .insert_bad_block(header_hash, current_height, err.to_string())
+                    .commit()
+                    .await
+                {
+                    error!(target: LOG_TARGET, "Failed to insert bad block: {}", err);
+                }
+                return Err(err.into());
+            },
+        };
+
+        let block = ChainBlock::try_construct(Arc::new(block), header_accum_data)
+            .map(Arc::new)
+            .ok_or(BlockSyncError::FailedToConstructChainBlock)?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Validated in {:.0?}. Storing block body #{} (PoW = {}, {})",
+            timer.elapsed(),
+            block.header().height,
+            block.header().pow_algo(),
+            block.block().body.to_counts_string(),
+        );
+        trace!(
+            target: LOG_TARGET,
+            "{}",block
+        );
+
+        let timer = Instant::now();
+        self.db
+            .write_transaction()
+            .delete_orphan(header_hash)
+            .insert_tip_block_body(block.clone())
+            .set_best_block(
+                block.height(),
+                header_hash,
+                block.accumulated_data().total_accumulated_difficulty,
+                block.header().prev_hash,
+                timestamp,
+            )
+            .commit()
+            .await?;
+
+        self.hooks
+            .call_on_progress_block_hooks(block.clone(), tip_height, sync_peer);
+
+        debug!(
+            target: LOG_TARGET,
+            "Block body #{} added in {:.0?}, Tot_acc_diff {}, Monero {}, SHA3 {}",
+            block.height(),
+            timer.elapsed(),
+            block
+                .accumulated_data()
+                .total_accumulated_difficulty,
+            block.accumulated_data().accumulated_randomx_difficulty,
+            block.accumulated_data().accumulated_sha3x_difficulty,
+        );
+
+        Ok(block)
+    }
 
+    /// Returns true if the sync peer's throughput is far below that of the fastest participating sync peer. The last
+    /// remaining sync peer is never considered too slow.
+    fn is_sync_peer_too_slow(&self, sync_peer: &SyncPeer, participants: &[NodeId], num_workers: usize) -> bool {
+        if num_workers < 2 {
+            return false;
+        }
+        let items_per_second = match sync_peer.items_per_second() {
+            Some(items_per_second) => items_per_second,
+            None => return false,
+        };
+        let fastest_items_per_second = self
+            .sync_peers
+            .iter()
+            .filter(|p| participants.contains(p.node_id()))
+            .filter_map(|p| p.items_per_second())
+            .fold(0.0, f64::max);
+        let too_slow = is_sync_peer_too_slow(items_per_second, fastest_items_per_second);
+        if too_slow {
             debug!(
                 target: LOG_TARGET,
-                "Block body #{} added in {:.0?}, Tot_acc_diff {}, Monero {}, SHA3 {}, latency: {:.2?}",
-                block.height(),
-                timer.elapsed(),
-                block
-                    .accumulated_data()
-                    .total_accumulated_difficulty,
-                block.accumulated_data().accumulated_randomx_difficulty,
-                block.accumulated_data().accumulated_sha3x_difficulty,
-                latency
+                "Deprioritising sync peer `{}`: {:.2} blocks/s, fastest: {:.2} blocks/s",
+                sync_peer.node_id(),
+                items_per_second,
+                fastest_items_per_second
             );
-            if let Some(avg_latency) = last_avg_latency {
-                if avg_latency > max_latency {
-                    return Err(BlockSyncError::MaxLatencyExceeded {
-                        peer: sync_peer.node_id().clone(),
-                        latency: avg_latency,
-                        max_latency,
-                    });
-                }
-            }
-
-            current_block = Some(block);
-            last_sync_timer = Instant::now();
         }
+        too_slow
+    }
 
-        let accumulated_difficulty = self.db.get_chain_metadata().await?.accumulated_difficulty();
-        if accumulated_difficulty < sync_peer.claimed_chain_metadata().accumulated_difficulty() {
-            return Err(BlockSyncError::PeerDidNotSupplyAllClaimedBlocks(format!(
-                "Their claimed difficulty: {}, our local difficulty after block sync: {}",
-                sync_peer.claimed_chain_metadata().accumulated_difficulty(),
-                accumulated_difficulty
-            )));
+    async fn handle_sync_peer_error(&mut self, node_id: &NodeId, err: BlockSyncError, latency_counter: &mut usize) {
+        warn!(target: LOG_TARGET, "{}", err);
+        if let Some(reason) = err.get_ban_reason() {
+            let duration = match reason.ban_duration {
+                BanPeriod::Short => self.config.short_ban_period,
+                BanPeriod::Long => self.config.ban_period,
+            };
+            self.peer_ban_manager
+                .ban_peer_if_required(node_id, reason.reason, duration)
+                .await;
         }
-
-        if let Some(block) = current_block {
-            self.hooks.call_on_complete_hooks(block, best_height);
+        if let BlockSyncError::MaxLatencyExceeded { .. } = err {
+            *latency_counter += 1;
+        } else {
+            self.remove_sync_peer(node_id);
         }
+    }
 
-        debug!(target: LOG_TARGET, "Completed block sync with peer `{}`", sync_peer);
+    // Sync peers with a known block throughput are tried first, fastest first. The remaining sync peers keep their
+    // order.
+    fn select_sync_peers(&self) -> Vec<NodeId> {
+        let mut sync_peers = self.sync_peers.iter().collect::<Vec<_>>();
+        sync_peers.sort_by(|a, b| match (a.items_per_second(), b.items_per_second()) {
+            (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        sync_peers.into_iter().map(|p| p.node_id().clone()).collect()
+    }
 
-        Ok(())
+    // Updates the sync peer list with the latency and throughput measured by a sync worker
+    fn update_sync_peer(&mut self, sync_peer: &SyncPeer) {
+        if let Some(index) = self.get_sync_peer_index(sync_peer.node_id()) {
+            self.sync_peers[index] = sync_peer.clone();
+        }
     }
 
     // Sync peers are also removed from the list of sync peers if the ban duration is longer than the short ban period.
@@ -445,3 +704,113 @@ impl<'a, B: BlockchainBackend + 'static> BlockSynchronizer<'a, B> {
         self.sync_peers.iter().position(|p| p.node_id() == node_id)
     }
 }
+
+/// Returns the index of the idle sync worker with the highest block throughput that claims to have the block at
+/// `height`
+fn fastest_idle_worker(workers: &[SyncWorker], height: u64) -> Option<usize> {
+    workers
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| w.sync_peer.claimed_chain_metadata().best_block_height() >= height)
+        .max_by(|(_, a), (_, b)| {
+            let a = a.sync_peer.items_per_second().unwrap_or_default();
+            let b = b.sync_peer.items_per_second().unwrap_or_default();
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        })
+        .map(|(index, _)| index)
+}
+
+/// Converts the downloaded block bodies and verifies their signatures and range proofs. Bodies that fail verification
+/// are left unverified, so that full validation reports and records the failure.
+fn verify_downloaded_blocks<B>(
+    validator: &dyn BlockBodyValidator<B>,
+    blocks: Vec<BlockBodyResponse>,
+) -> Vec<DownloadedBlock> {
+    blocks
+        .into_iter()
+        .map(|response| {
+            let body = response
+                .body
+                .map(AggregateBody::try_from)
+                .ok_or_else(|| BlockSyncError::InvalidBlockBody("Peer sent empty block".to_string()))
+                .and_then(|body| body.map_err(BlockSyncError::InvalidBlockBody));
+            let proofs_verified = body
+                .as_ref()
+                .map(|body| validator.verify_body_proofs(body).is_ok())
+                .unwrap_or(false);
+            DownloadedBlock {
+                hash: response.hash,
+                body,
+                proofs_verified,
+            }
+        })
+        .collect()
+}
+
+async fn download_chunk(mut worker: SyncWorker, chunk: BlockChunk, max_latency: Duration) -> ChunkDownload {
+    let result = download_chunk_blocks(&mut worker, &chunk, max_latency).await;
+    ChunkDownload { worker, chunk, result }
+}
+
+async fn download_chunk_blocks(
+    worker: &mut SyncWorker,
+    chunk: &BlockChunk,
+    max_latency: Duration,
+) -> Result<Vec<BlockBodyResponse>, BlockSyncError> {
+    debug!(
+        target: LOG_TARGET,
+        "Downloading blocks #{} to #{} from peer `{}`",
+        chunk.start_height,
+        chunk.end_height,
+        worker.sync_peer.node_id()
+    );
+    let request = SyncBlocksRequest {
+        start_hash: chunk.start_hash.to_vec(),
+        end_hash: chunk.end_hash.to_vec(),
+    };
+
+    let mut block_stream = worker.client.sync_blocks(request).await?;
+    let mut blocks = Vec::with_capacity(usize::try_from(chunk.num_blocks()).unwrap_or_default());
+    let mut last_sync_timer = Instant::now();
+    let mut avg_latency = RollingAverageTime::new(20);
+    while let Some(block_result) = block_stream.next().await {
+        let latency = last_sync_timer.elapsed();
+        avg_latency.add_sample(latency);
+        if blocks.len() as u64 >= chunk.num_blocks() {
+            return Err(BlockSyncError::PeerSentTooManyBlocks(format!(
+                "Requested {} blocks from #{} to #{}",
+                chunk.num_blocks(),
+                chunk.start_height,
+                chunk.end_height
+            )));
+        }
+        blocks.push(block_result?);
+
+        // Time between receiving blocks from the peer - used to rank sync peers and to show blocks/s on the status line
+        worker.sync_peer.add_sample(latency);
+        // Average time between receiving blocks from the peer - used to detect a slow sync peer
+        if let Some(avg_latency) = avg_latency.calculate_average_with_min_samples(5) {
+            worker.sync_peer.set_latency(avg_latency);
+            if avg_latency > max_latency {
+                return Err(BlockSyncError::MaxLatencyExceeded {
+                    peer: worker.sync_peer.node_id().clone(),
+                    latency: avg_latency,
+                    max_latency,
+                });
+            }
+        }
+        last_sync_timer = Instant::now();
+    }
+
+    if blocks.len() as u64 != chunk.num_blocks() {
+        return Err(BlockSyncError::PeerDidNotSupplyAllClaimedBlocks(format!(
+            "Requested {} blocks from #{} to #{}, received {}",
+            chunk.num_blocks(),
+            chunk.start_height,
+            chunk.end_height,
+            blocks.len()
+        )));
+    }
+
+    Ok(blocks)
+}
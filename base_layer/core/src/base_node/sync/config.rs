@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use tari_common::configuration::serializers;
@@ -46,12 +46,26 @@ pub struct BlockchainSyncConfig {
     /// An allowlist of sync peers from which to sync. No other peers will be selected for sync. If empty, sync peers
     /// are chosen based on their advertised chain metadata.
     pub forced_sync_peers: Vec<NodeId>,
-    /// Number of threads to use for validation
+    /// Number of threads to use for validation. During block sync this limits the number of downloaded block chunks
+    /// that are buffered ahead of in-order validation, and whose signatures and range proofs are verified
+    /// concurrently.
     pub validation_concurrency: usize,
+    /// The maximum number of sync peers from which blocks are downloaded concurrently during block sync
+    pub max_concurrent_block_sync_peers: usize,
+    /// The number of blocks requested from a sync peer in a single block sync request
+    pub block_sync_chunk_size: u64,
     /// The RPC deadline to set on sync clients. If this deadline is reached, a new sync peer will be selected for
     /// sync.
     #[serde(with = "serializers::seconds")]
     pub rpc_deadline: Duration,
+    /// Path to a UTXO snapshot, created with the `create-utxo-snapshot` command, from which a new pruned node loads
+    /// its horizon state instead of downloading it from sync peers
+    pub utxo_snapshot_file: Option<PathBuf>,
+    /// The hex encoded hash of the UTXO snapshot, as printed when it was created. If set, a snapshot with a different
+    /// hash is rejected.
+    pub utxo_snapshot_hash: Option<String>,
+    /// Verify the kernel signatures and range proofs of a loaded UTXO snapshot in the background
+    pub utxo_snapshot_back_validation: bool,
 }
 
 impl Default for BlockchainSyncConfig {
@@ -63,7 +77,12 @@ impl Default for BlockchainSyncConfig {
             short_ban_period: Duration::from_secs(240),         // 4 mins
             forced_sync_peers: Default::default(),
             validation_concurrency: 6,
+            max_concurrent_block_sync_peers: 4,
+            block_sync_chunk_size: 100,
             rpc_deadline: Duration::from_secs(240), // Syncing many full blocks over tor require this
+            utxo_snapshot_file: None,
+            utxo_snapshot_hash: None,
+            utxo_snapshot_back_validation: true,
         }
     }
 }
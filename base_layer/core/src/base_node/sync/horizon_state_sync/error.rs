@@ -35,7 +35,7 @@ use thiserror::Error;
 use tokio::task;
 
 use crate::{
-    chain_storage::ChainStorageError,
+    chain_storage::{ChainStorageError, UtxoSnapshotError},
     common::{BanPeriod, BanReason},
     transactions::transaction_components::TransactionError,
     validation::ValidationError,
@@ -103,6 +103,10 @@ pub enum HorizonSyncError {
     ByteArrayError(String),
     #[error("FixedHash size error: {0}")]
     MrHashError(#[from] MrHashError),
+    #[error("UTXO snapshot error: {0}")]
+    UtxoSnapshotError(#[from] UtxoSnapshotError),
+    #[error("Invalid UTXO snapshot: {0}")]
+    InvalidUtxoSnapshot(String),
 }
 
 impl From<ByteArrayError> for HorizonSyncError {
@@ -135,7 +139,9 @@ impl HorizonSyncError {
             HorizonSyncError::NoMoreSyncPeers(_) |
             HorizonSyncError::PeerNotFound |
             HorizonSyncError::JoinError(_) |
-            HorizonSyncError::MrHashError(_) => None,
+            HorizonSyncError::MrHashError(_) |
+            HorizonSyncError::UtxoSnapshotError(_) |
+            HorizonSyncError::InvalidUtxoSnapshot(_) => None,
 
             // short ban
             err @ HorizonSyncError::MaxLatencyExceeded { .. } |
@@ -26,5 +26,8 @@ pub use error::HorizonSyncError;
 mod events;
 pub use events::{HorizonSyncInfo, HorizonSyncStatus};
 
+mod snapshot;
+pub use snapshot::UtxoSnapshotSync;
+
 mod synchronizer;
 pub use synchronizer::HorizonStateSynchronization;
@@ -0,0 +1,474 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Bootstraps the horizon state from a UTXO snapshot file (see [crate::chain_storage::UtxoSnapshotReader]) instead of
+//! downloading it from sync peers.
+//!
+//! The snapshot is not trusted. Its kernels are checked against the `kernel_mr` of every header, its outputs against
+//! the `output_mr` of the snapshot block and the resulting commitment sums are checked by the final horizon state
+//! validator, exactly as for horizon sync. Kernel signatures and range proofs are assumed valid, as they are committed
+//! to by the proof of work of the header chain, and can optionally be verified in the background once the node is
+//! running.
+
+use std::{
+    collections::HashMap,
+    mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use log::*;
+use tari_common_types::types::{FixedHash, RangeProofService};
+use tari_mmr::sparse_merkle_tree::NodeKey;
+use tari_utilities::{hex::Hex, ByteArray};
+use tokio::task;
+
+use super::{
+    error::HorizonSyncError,
+    synchronizer::{calculate_commitment_sums, check_output_smt_root_hash},
+};
+use crate::{
+    blocks::{ChainHeader, UpdateBlockAccumulatedData},
+    chain_storage::{
+        async_db::AsyncBlockchainDb,
+        BlockchainBackend,
+        BlockchainDatabase,
+        DbTransaction,
+        MmrTree,
+        OutputSmtChange,
+        UtxoSnapshotOutput,
+        UtxoSnapshotReader,
+        UtxoSnapshotSummary,
+    },
+    consensus::ConsensusManager,
+    transactions::transaction_components::{transaction_output::batch_verify_range_proofs, TransactionOutput},
+    validation::{helpers, FinalHorizonStateValidation},
+    PrunedKernelMmr,
+};
+
+const LOG_TARGET: &str = "c::bn::state_machine_service::states::horizon_state_sync::snapshot";
+
+/// Outputs are committed to the database in batches of this size
+const OUTPUT_BATCH_SIZE: u64 = 1000;
+
+pub struct UtxoSnapshotSync<B> {
+    db: AsyncBlockchainDb<B>,
+    rules: ConsensusManager,
+    path: PathBuf,
+    expected_hash: Option<FixedHash>,
+    prover: Arc<RangeProofService>,
+    final_state_validator: Arc<dyn FinalHorizonStateValidation<B>>,
+}
+
+impl<B: BlockchainBackend + 'static> UtxoSnapshotSync<B> {
+    pub fn new(
+        db: AsyncBlockchainDb<B>,
+        rules: ConsensusManager,
+        path: PathBuf,
+        expected_hash: Option<FixedHash>,
+        prover: Arc<RangeProofService>,
+        final_state_validator: Arc<dyn FinalHorizonStateValidation<B>>,
+    ) -> Self {
+        Self {
+            db,
+            rules,
+            path,
+            expected_hash,
+            prover,
+            final_state_validator,
+        }
+    }
+
+    /// Loads the snapshot into a node that has synced headers but no blocks, and sets the tip and pruned height to
+    /// the snapshot height. Returns the snapshot height.
+    pub async fn load(&self) -> Result<u64, HorizonSyncError> {
+        let db = self.db.inner().clone();
+        let rules = self.rules.clone();
+        let path = self.path.clone();
+        let expected_hash = self.expected_hash;
+        let final_state_validator = self.final_state_validator.clone();
+        task::spawn_blocking(move || load_utxo_snapshot(&db, &rules, &*final_state_validator, &path, expected_hash))
+            .await?
+    }
+
+    /// Verifies the kernel signatures and range proofs of the state loaded from a snapshot in the background. A
+    /// failure is logged; there is nothing else to be done, as the header chain has committed to the invalid state.
+    pub fn spawn_back_validation(&self, height: u64) {
+        let db = self.db.inner().clone();
+        let prover = self.prover.clone();
+        task::spawn_blocking(move || {
+            let timer = Instant::now();
+            match back_validate_utxo_snapshot(&db, &prover, height) {
+                Ok(()) => info!(
+                    target: LOG_TARGET,
+                    "Back-validation of the UTXO snapshot at height {} succeeded in {:.2?}",
+                    height,
+                    timer.elapsed()
+                ),
+                Err(err) => error!(
+                    target: LOG_TARGET,
+                    "Back-validation of the UTXO snapshot at height {} FAILED: {}. The chain state cannot be trusted, \
+                     delete the database and resync without the snapshot.",
+                    height,
+                    err
+                ),
+            }
+        });
+    }
+}
+
+fn load_utxo_snapshot<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    rules: &ConsensusManager,
+    final_state_validator: &dyn FinalHorizonStateValidation<B>,
+    path: &Path,
+    expected_hash: Option<FixedHash>,
+) -> Result<u64, HorizonSyncError> {
+    let timer = Instant::now();
+    let metadata = db.get_chain_metadata()?;
+    if metadata.best_block_height() != 0 {
+        return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+            "A UTXO snapshot can only be loaded into a node without blocks, but the local tip is at height {}",
+            metadata.best_block_height()
+        )));
+    }
+
+    let reader = UtxoSnapshotReader::open(path)?;
+    let genesis_header = db.fetch_chain_header(0)?;
+    reader.header().check_genesis_hash(genesis_header.hash())?;
+    let snapshot_header = reader.header().clone();
+    let chain_header = db.fetch_chain_header(snapshot_header.height)?;
+    if *chain_header.hash() != snapshot_header.block_hash {
+        return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+            "The snapshot block {} at height {} is not in the local header chain",
+            snapshot_header.block_hash, snapshot_header.height
+        )));
+    }
+    if snapshot_header.num_kernels != chain_header.header().kernel_mmr_size {
+        return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+            "The snapshot contains {} kernels, but the kernel MMR size at height {} is {}",
+            snapshot_header.num_kernels,
+            snapshot_header.height,
+            chain_header.header().kernel_mmr_size
+        )));
+    }
+    info!(
+        target: LOG_TARGET,
+        "Loading UTXO snapshot at height {} from '{}'",
+        snapshot_header.height,
+        path.display()
+    );
+
+    let (smt_changes, spent_genesis_outputs, summary, hash) =
+        verify_utxo_snapshot(db, rules, reader, &chain_header, expected_hash)?;
+    debug!(
+        target: LOG_TARGET,
+        "UTXO snapshot {} verified against the output MR at height {}", hash, snapshot_header.height
+    );
+    insert_snapshot_kernels(db, UtxoSnapshotReader::open(path)?)?;
+    insert_snapshot_outputs(
+        db,
+        UtxoSnapshotReader::open(path)?,
+        hash,
+        smt_changes,
+        spent_genesis_outputs,
+    )?;
+    finalize_utxo_snapshot(db, final_state_validator, &chain_header, &summary)?;
+    info!(
+        target: LOG_TARGET,
+        "Loaded UTXO snapshot at height {} ({} kernels, {} outputs) in {:.2?}",
+        snapshot_header.height,
+        snapshot_header.num_kernels,
+        summary.num_outputs,
+        timer.elapsed()
+    );
+    Ok(snapshot_header.height)
+}
+
+/// Checks the commitment hash of the snapshot, that its outputs were mined in the local header chain and that they
+/// match the output MR of the snapshot block, without writing to the database. Returns the output SMT changes from the
+/// tip to the snapshot block and the genesis outputs that were spent.
+fn verify_utxo_snapshot<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    rules: &ConsensusManager,
+    mut reader: UtxoSnapshotReader,
+    chain_header: &ChainHeader,
+    expected_hash: Option<FixedHash>,
+) -> Result<
+    (
+        Vec<OutputSmtChange>,
+        Vec<TransactionOutput>,
+        UtxoSnapshotSummary,
+        FixedHash,
+    ),
+    HorizonSyncError,
+> {
+    while reader.read_kernel()?.is_some() {}
+
+    let mut smt_changes = Vec::new();
+    let mut genesis_outputs = db
+        .fetch_outputs_in_block(*db.fetch_chain_header(0)?.hash())?
+        .into_iter()
+        .map(|output| (output.commitment.to_vec(), output))
+        .collect::<HashMap<_, _>>();
+    let mut current_header = db.fetch_chain_header(0)?;
+    while let Some(UtxoSnapshotOutput {
+        mined_height,
+        mined_header_hash,
+        output,
+    }) = reader.read_output()?
+    {
+        if mined_height > chain_header.height() {
+            return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+                "Output {} was mined at height {}, after the snapshot height",
+                output.commitment.to_hex(),
+                mined_height
+            )));
+        }
+        if current_header.height() != mined_height {
+            current_header = db.fetch_chain_header(mined_height)?;
+        }
+        if *current_header.hash() != mined_header_hash {
+            return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+                "Output {} was mined in block {}, which is not the local block at height {}",
+                output.commitment.to_hex(),
+                mined_header_hash,
+                mined_height
+            )));
+        }
+        // Genesis outputs are already in the database and the SMT
+        if mined_height == 0 {
+            if genesis_outputs.remove(output.commitment.as_bytes()).is_none() {
+                return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+                    "Genesis output {} is not in the local genesis block",
+                    output.commitment.to_hex()
+                )));
+            }
+            continue;
+        }
+        let constants = rules.consensus_constants(mined_height);
+        helpers::check_tari_script_byte_size(&output.script, constants.max_script_byte_size())?;
+        smt_changes.push(OutputSmtChange::insert_output(&output, mined_height)?);
+    }
+
+    // The remaining genesis outputs were spent before the snapshot height
+    let spent_genesis_outputs = genesis_outputs.into_values().collect::<Vec<_>>();
+    for output in &spent_genesis_outputs {
+        let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+        if !db.db_read_access()?.output_smt_contains(&smt_key)? {
+            return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+                "Genesis output {} is not in the output SMT",
+                output.commitment.to_hex()
+            )));
+        }
+        smt_changes.push(OutputSmtChange::delete_output(&output.commitment)?);
+    }
+
+    let (summary, hash) = reader.finish()?;
+    if let Some(expected_hash) = expected_hash {
+        if hash != expected_hash {
+            return Err(HorizonSyncError::InvalidUtxoSnapshot(format!(
+                "The snapshot hash {} does not match the configured hash {}",
+                hash, expected_hash
+            )));
+        }
+    }
+    let (root, _) = db.calculate_output_smt_root(&smt_changes)?;
+    check_output_smt_root_hash(&root, chain_header.header())?;
+    Ok((smt_changes, spent_genesis_outputs, summary, hash))
+}
+
+/// Inserts the kernels that are not yet in the database, checking the kernel MR of each block as it is completed
+fn insert_snapshot_kernels<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    mut reader: UtxoSnapshotReader,
+) -> Result<(), HorizonSyncError> {
+    let local_num_kernels = db.fetch_mmr_size(MmrTree::Kernel)?;
+    let num_kernels = reader.header().num_kernels;
+    if local_num_kernels >= num_kernels {
+        return Ok(());
+    }
+
+    let mut current_header = db.fetch_header_containing_kernel_mmr(local_num_kernels)?;
+    let mut kernel_hashes = vec![];
+    let mut txn = DbTransaction::new();
+    let mut mmr_position = 0u64;
+    while let Some(kernel) = reader.read_kernel()? {
+        if mmr_position < local_num_kernels {
+            mmr_position += 1;
+            continue;
+        }
+        kernel_hashes.push(kernel.hash());
+        txn.insert_kernel(kernel, *current_header.hash(), mmr_position);
+        if mmr_position == current_header.header().kernel_mmr_size.saturating_sub(1) {
+            let kernel_pruned_set = db
+                .fetch_block_accumulated_data(current_header.header().prev_hash)?
+                .dissolve();
+            let mut kernel_mmr = PrunedKernelMmr::new(kernel_pruned_set);
+            for hash in kernel_hashes.drain(..) {
+                kernel_mmr.push(hash.to_vec())?;
+            }
+
+            let mmr_root = kernel_mmr.get_merkle_root()?;
+            if mmr_root.as_slice() != current_header.header().kernel_mr.as_slice() {
+                return Err(HorizonSyncError::InvalidMrRoot {
+                    mr_tree: MmrTree::Kernel.to_string(),
+                    at_height: current_header.height(),
+                    expected_hex: current_header.header().kernel_mr.to_hex(),
+                    actual_hex: mmr_root.to_hex(),
+                });
+            }
+            txn.update_block_accumulated_data(*current_header.hash(), UpdateBlockAccumulatedData {
+                kernel_hash_set: Some(kernel_mmr.get_pruned_hash_set()?),
+                ..Default::default()
+            });
+            db.write(mem::take(&mut txn))?;
+            if mmr_position < num_kernels.saturating_sub(1) {
+                current_header = db.fetch_chain_header(current_header.height() + 1)?;
+            }
+        }
+        mmr_position += 1;
+    }
+    debug!(
+        target: LOG_TARGET,
+        "Inserted {} kernel(s) from the UTXO snapshot",
+        num_kernels - local_num_kernels
+    );
+    Ok(())
+}
+
+/// Inserts the outputs of the verified snapshot, prunes the spent genesis outputs and updates the output SMT
+fn insert_snapshot_outputs<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    mut reader: UtxoSnapshotReader,
+    verified_hash: FixedHash,
+    smt_changes: Vec<OutputSmtChange>,
+    spent_genesis_outputs: Vec<TransactionOutput>,
+) -> Result<(), HorizonSyncError> {
+    while reader.read_kernel()?.is_some() {}
+
+    let mut txn = DbTransaction::new();
+    let mut current_header = db.fetch_chain_header(0)?;
+    let mut num_inserted = 0u64;
+    while let Some(UtxoSnapshotOutput {
+        mined_height,
+        mined_header_hash,
+        output,
+    }) = reader.read_output()?
+    {
+        if mined_height == 0 {
+            continue;
+        }
+        if current_header.height() != mined_height {
+            current_header = db.fetch_chain_header(mined_height)?;
+        }
+        txn.insert_utxo(output, mined_header_hash, mined_height, current_header.timestamp());
+        num_inserted += 1;
+        if num_inserted % OUTPUT_BATCH_SIZE == 0 {
+            db.write(mem::take(&mut txn))?;
+        }
+    }
+    let (_, hash) = reader.finish()?;
+    if hash != verified_hash {
+        return Err(HorizonSyncError::InvalidUtxoSnapshot(
+            "The snapshot file changed while it was being loaded".to_string(),
+        ));
+    }
+
+    for output in spent_genesis_outputs {
+        txn.prune_output_from_all_dbs(output.hash(), output.commitment.clone(), output.features.output_type);
+    }
+    txn.update_output_smt(smt_changes);
+    db.write(txn)?;
+    debug!(
+        target: LOG_TARGET,
+        "Inserted {} output(s) from the UTXO snapshot", num_inserted
+    );
+    Ok(())
+}
+
+/// Validates the loaded state as horizon sync does and sets the tip and pruned height to the snapshot block
+fn finalize_utxo_snapshot<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    final_state_validator: &dyn FinalHorizonStateValidation<B>,
+    chain_header: &ChainHeader,
+    summary: &UtxoSnapshotSummary,
+) -> Result<(), HorizonSyncError> {
+    let (utxo_sum, kernel_sum, burned_sum) =
+        calculate_commitment_sums(db, chain_header.height(), *chain_header.hash())?;
+    if utxo_sum != summary.utxo_sum || kernel_sum != summary.kernel_sum {
+        return Err(HorizonSyncError::InvalidUtxoSnapshot(
+            "The commitment sums of the loaded state do not match the snapshot".to_string(),
+        ));
+    }
+    final_state_validator
+        .validate(
+            &*db.db_read_access()?,
+            chain_header.height(),
+            &utxo_sum,
+            &kernel_sum,
+            &burned_sum,
+        )
+        .map_err(HorizonSyncError::FinalStateValidationFailed)?;
+
+    let metadata = db.get_chain_metadata()?;
+    let mut txn = DbTransaction::new();
+    txn.set_best_block(
+        chain_header.height(),
+        *chain_header.hash(),
+        chain_header.accumulated_data().total_accumulated_difficulty,
+        *metadata.best_block_hash(),
+        chain_header.timestamp(),
+    )
+    .set_pruned_height(chain_header.height())
+    .set_horizon_data(kernel_sum, utxo_sum);
+    db.write(txn)?;
+    Ok(())
+}
+
+/// Verifies the kernel signatures and the range proofs of the unspent outputs up to `height`
+fn back_validate_utxo_snapshot<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    prover: &RangeProofService,
+    height: u64,
+) -> Result<(), HorizonSyncError> {
+    for h in 0..=height {
+        let header = db.fetch_chain_header(h)?;
+        for kernel in db.fetch_kernels_in_block(*header.hash())? {
+            kernel.verify_signature()?;
+        }
+        let outputs = db.fetch_outputs_in_block(*header.hash())?;
+        if !outputs.is_empty() {
+            batch_verify_range_proofs(prover, &outputs.iter().collect::<Vec<_>>())?;
+        }
+        if h % 1000 == 0 && height != 0 {
+            debug!(
+                target: LOG_TARGET,
+                "UTXO snapshot back-validation: {:.2}% complete. Height: {}",
+                (h as f32 / height as f32) * 100.0,
+                h,
+            );
+        }
+    }
+    Ok(())
+}
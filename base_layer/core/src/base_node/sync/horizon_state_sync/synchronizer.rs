@@ -32,7 +32,6 @@ use log::*;
 use tari_common_types::types::{Commitment, FixedHash, RangeProofService};
 use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, protocol::rpc::RpcClient, PeerConnection};
 use tari_crypto::commitment::HomomorphicCommitment;
-use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, ValueHash};
 use tari_utilities::{hex::Hex, ByteArray};
 use tokio::task;
 
@@ -48,10 +47,16 @@ use crate::{
         SyncPeer,
     },
     blocks::{BlockHeader, ChainHeader, UpdateBlockAccumulatedData},
-    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError, MmrTree},
+    chain_storage::{
+        async_db::AsyncBlockchainDb,
+        BlockchainBackend,
+        BlockchainDatabase,
+        ChainStorageError,
+        MmrTree,
+        OutputSmtChange,
+    },
     common::{rolling_avg::RollingAverageTime, BanPeriod},
     consensus::ConsensusManager,
-    output_mr_hash_from_smt,
     proto::base_node::{sync_utxos_response::Txo, SyncKernelsRequest, SyncUtxosRequest, SyncUtxosResponse},
     transactions::transaction_components::{
         transaction_output::batch_verify_range_proofs,
@@ -60,7 +65,6 @@ use crate::{
         TransactionOutput,
     },
     validation::{helpers, FinalHorizonStateValidation},
-    OutputSmt,
     PrunedKernelMmr,
 };
 
@@ -299,14 +303,11 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         debug!(target: LOG_TARGET, "Synchronizing kernels");
         self.synchronize_kernels(sync_peer.clone(), client, to_header).await?;
         debug!(target: LOG_TARGET, "Synchronizing outputs");
-        let cloned_backup_smt = self.db.inner().smt_read_access()?.clone();
         match self.synchronize_outputs(sync_peer, client, to_header).await {
             Ok(_) => Ok(()),
             Err(err) => {
-                // We need to clean up the outputs
+                // We need to clean up the outputs. The output SMT is only updated once it has been verified.
                 let _ = self.clean_up_failed_output_sync(to_header).await;
-                let mut smt = self.db.inner().smt_write_access()?;
-                *smt = cloned_backup_smt;
                 Err(err)
             },
         }
@@ -622,7 +623,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         let mut utxo_counter = 0u64;
         let mut stxo_counter = 0u64;
         let timer = Instant::now();
-        let mut output_smt = (*db.inner().smt_write_access()?).clone();
+        let mut smt_changes = Vec::new();
         let mut last_sync_timer = Instant::now();
         let mut avg_latency = RollingAverageTime::new(20);
 
@@ -666,16 +667,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
                         helpers::check_tari_script_byte_size(&output.script, constants.max_script_byte_size())?;
 
                         batch_verify_range_proofs(&self.prover, &[&output])?;
-                        let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
-                        let smt_node = ValueHash::try_from(output.smt_hash(current_header.height).as_slice())?;
-                        if let Err(e) = output_smt.insert(smt_key, smt_node) {
-                            error!(
-                                target: LOG_TARGET,
-                                "Output commitment({}) already in SMT",
-                                output.commitment.to_hex(),
-                            );
-                            return Err(e.into());
-                        }
+                        smt_changes.push(OutputSmtChange::insert_output(&output, current_header.height)?);
                         txn.insert_output_via_horizon_sync(
                             output,
                             current_header.hash(),
@@ -703,20 +695,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
                                 output_hash,
                                 stxo_counter,
                             );
-                            let smt_key = NodeKey::try_from(commitment_bytes.as_slice())?;
-                            match output_smt.delete(&smt_key)? {
-                                DeleteResult::Deleted(_value_hash) => {},
-                                DeleteResult::KeyNotFound => {
-                                    error!(
-                                        target: LOG_TARGET,
-                                        "Could not find input({}) in SMT",
-                                        commitment.to_hex(),
-                                    );
-                                    return Err(HorizonSyncError::ChainStorageError(
-                                        ChainStorageError::UnspendableInput,
-                                    ));
-                                },
-                            };
+                            smt_changes.push(OutputSmtChange::delete_output(&commitment)?);
                             // This will only be committed once the SMT has been verified due to rewind difficulties if
                             // we need to abort the sync
                             inputs_to_delete.push((output_hash, commitment));
@@ -760,7 +739,10 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         //      it.
         // 3. In both cases it would be impossible to verify the SMT per block, as we would not be able to update the
         //    SMT with the outputs that were created and spent within the tranche.
-        HorizonStateSynchronization::<B>::check_output_smt_root_hash(&mut output_smt, to_header)?;
+        let (root, _) = db.inner().calculate_output_smt_root(&smt_changes)?;
+        check_output_smt_root_hash(&root, to_header)?;
+        txn.update_output_smt(smt_changes);
+        txn.commit().await?;
 
         // Commit in chunks to avoid locking the database for too long
         let inputs_to_delete_len = inputs_to_delete.len();
@@ -770,8 +752,6 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
                 txn.commit().await?;
             }
         }
-        let mut writing_lock_output_smt = db.inner().smt_write_access()?;
-        *writing_lock_output_smt = output_smt;
         debug!(
             target: LOG_TARGET,
             "Finished syncing TXOs: {} unspent and {} spent downloaded in {:.2?}",
@@ -782,26 +762,6 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         Ok(())
     }
 
-    // Helper function to check the output SMT root hash against the expected root hash.
-    fn check_output_smt_root_hash(output_smt: &mut OutputSmt, header: &BlockHeader) -> Result<(), HorizonSyncError> {
-        let root = output_mr_hash_from_smt(output_smt)?;
-        if root != header.output_mr {
-            warn!(
-                target: LOG_TARGET,
-                "Target root(#{}) did not match expected (#{})",
-                    header.output_mr.to_hex(),
-                    root.to_hex(),
-            );
-            return Err(HorizonSyncError::InvalidMrRoot {
-                mr_tree: "UTXO SMT".to_string(),
-                at_height: header.height,
-                expected_hex: header.output_mr.to_hex(),
-                actual_hex: root.to_hex(),
-            });
-        }
-        Ok(())
-    }
-
     // Finalize the horizon state synchronization by setting the chain metadata to the local tip and committing
     // the horizon state to the blockchain backend.
     async fn finalize_horizon_sync(&mut self, sync_peer: &SyncPeer) -> Result<(), HorizonSyncError> {
@@ -847,74 +807,15 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         Ok(())
     }
 
-    /// (UTXO sum, Kernel sum)
+    /// (UTXO sum, Kernel sum, Burned sum)
     async fn calculate_commitment_sums(
         &mut self,
         header: &ChainHeader,
     ) -> Result<(Commitment, Commitment, Commitment), HorizonSyncError> {
-        let mut utxo_sum = HomomorphicCommitment::default();
-        let mut kernel_sum = HomomorphicCommitment::default();
-        let mut burned_sum = HomomorphicCommitment::default();
-
-        let mut prev_kernel_mmr = 0;
-
         let height = header.height();
         let db = self.db().inner().clone();
         let header_hash = *header.hash();
-        task::spawn_blocking(move || {
-            for h in 0..=height {
-                let curr_header = db.fetch_chain_header(h)?;
-                trace!(
-                    target: LOG_TARGET,
-                    "Fetching utxos from db: height:{}",
-                    curr_header.height(),
-                );
-                let utxos = db.fetch_outputs_in_block_with_spend_state(*curr_header.hash(), Some(header_hash))?;
-                debug!(
-                    target: LOG_TARGET,
-                    "{} output(s) loaded for height {}",
-                    utxos.len(),
-                    curr_header.height()
-                );
-                trace!(
-                    target: LOG_TARGET,
-                    "Fetching kernels from db: height:{}, header.kernel_mmr:{}, prev_mmr:{}, end:{}",
-                    curr_header.height(),
-                    curr_header.header().kernel_mmr_size,
-                    prev_kernel_mmr,
-                    curr_header.header().kernel_mmr_size.saturating_sub(1)
-                );
-
-                trace!(target: LOG_TARGET, "Number of utxos returned: {}", utxos.len());
-                for (u, spent) in utxos {
-                    if !spent {
-                        utxo_sum = &u.commitment + &utxo_sum;
-                    }
-                }
-
-                let kernels = db.fetch_kernels_in_block(*curr_header.hash())?;
-                trace!(target: LOG_TARGET, "Number of kernels returned: {}", kernels.len());
-                for k in kernels {
-                    kernel_sum = &k.excess + &kernel_sum;
-                    if k.is_burned() {
-                        burned_sum = k.get_burn_commitment()? + &burned_sum;
-                    }
-                }
-                prev_kernel_mmr = curr_header.header().kernel_mmr_size;
-
-                if h % 1000 == 0 && height != 0 {
-                    debug!(
-                        target: LOG_TARGET,
-                        "Final Validation: {:.2}% complete. Height: {} sync",
-                        (h as f32 / height as f32) * 100.0,
-                        h,
-                    );
-                }
-            }
-
-            Ok((utxo_sum, kernel_sum, burned_sum))
-        })
-        .await?
+        task::spawn_blocking(move || calculate_commitment_sums(&db, height, header_hash)).await?
     }
 
     // Sync peers are also removed from the list of sync peers if the ban duration is longer than the short ban period.
@@ -934,3 +835,88 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         &self.db
     }
 }
+
+/// Calculates the (UTXO sum, Kernel sum, Burned sum) of the chain state at the given block from the outputs and kernels
+/// in the database.
+pub(super) fn calculate_commitment_sums<B: BlockchainBackend>(
+    db: &BlockchainDatabase<B>,
+    height: u64,
+    header_hash: FixedHash,
+) -> Result<(Commitment, Commitment, Commitment), HorizonSyncError> {
+    let mut utxo_sum = HomomorphicCommitment::default();
+    let mut kernel_sum = HomomorphicCommitment::default();
+    let mut burned_sum = HomomorphicCommitment::default();
+
+    let mut prev_kernel_mmr = 0;
+
+    for h in 0..=height {
+        let curr_header = db.fetch_chain_header(h)?;
+        trace!(
+            target: LOG_TARGET,
+            "Fetching utxos from db: height:{}",
+            curr_header.height(),
+        );
+        let utxos = db.fetch_outputs_in_block_with_spend_state(*curr_header.hash(), Some(header_hash))?;
+        debug!(
+            target: LOG_TARGET,
+            "{} output(s) loaded for height {}",
+            utxos.len(),
+            curr_header.height()
+        );
+        trace!(
+            target: LOG_TARGET,
+            "Fetching kernels from db: height:{}, header.kernel_mmr:{}, prev_mmr:{}, end:{}",
+            curr_header.height(),
+            curr_header.header().kernel_mmr_size,
+            prev_kernel_mmr,
+            curr_header.header().kernel_mmr_size.saturating_sub(1)
+        );
+
+        trace!(target: LOG_TARGET, "Number of utxos returned: {}", utxos.len());
+        for (u, spent) in utxos {
+            if !spent {
+                utxo_sum = &u.commitment + &utxo_sum;
+            }
+        }
+
+        let kernels = db.fetch_kernels_in_block(*curr_header.hash())?;
+        trace!(target: LOG_TARGET, "Number of kernels returned: {}", kernels.len());
+        for k in kernels {
+            kernel_sum = &k.excess + &kernel_sum;
+            if k.is_burned() {
+                burned_sum = k.get_burn_commitment()? + &burned_sum;
+            }
+        }
+        prev_kernel_mmr = curr_header.header().kernel_mmr_size;
+
+        if h % 1000 == 0 && height != 0 {
+            debug!(
+                target: LOG_TARGET,
+                "Final Validation: {:.2}% complete. Height: {} sync",
+                (h as f32 / height as f32) * 100.0,
+                h,
+            );
+        }
+    }
+
+    Ok((utxo_sum, kernel_sum, burned_sum))
+}
+
+// Helper function to check the output SMT root hash against the expected root hash.
+pub(super) fn check_output_smt_root_hash(root: &FixedHash, header: &BlockHeader) -> Result<(), HorizonSyncError> {
+    if *root != header.output_mr {
+        warn!(
+            target: LOG_TARGET,
+            "Target root(#{}) did not match expected (#{})",
+                header.output_mr.to_hex(),
+                root.to_hex(),
+        );
+        return Err(HorizonSyncError::InvalidMrRoot {
+            mr_tree: "UTXO SMT".to_string(),
+            at_height: header.height,
+            expected_hex: header.output_mr.to_hex(),
+            actual_hex: root.to_hex(),
+        });
+    }
+    Ok(())
+}
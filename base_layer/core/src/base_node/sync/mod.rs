@@ -41,7 +41,13 @@ pub use header_sync::{AttemptSyncResult, BlockHeaderSyncError, HeaderSyncStatus,
 #[cfg(feature = "base_node")]
 mod horizon_state_sync;
 #[cfg(feature = "base_node")]
-pub use horizon_state_sync::{HorizonStateSynchronization, HorizonSyncError, HorizonSyncInfo, HorizonSyncStatus};
+pub use horizon_state_sync::{
+    HorizonStateSynchronization,
+    HorizonSyncError,
+    HorizonSyncInfo,
+    HorizonSyncStatus,
+    UtxoSnapshotSync,
+};
 
 #[cfg(feature = "base_node")]
 mod hooks;
@@ -31,6 +31,16 @@ use tari_comms::peer_manager::NodeId;
 
 use crate::{base_node::chain_metadata_service::PeerChainMetadata, common::rolling_avg::RollingAverageTime};
 
+/// A sync peer that downloads at less than this fraction of the fastest concurrent sync peer's throughput is
+/// deprioritised during block sync
+const MIN_RELATIVE_SYNC_THROUGHPUT: f64 = 0.2;
+
+/// Returns true if a sync peer delivering `items_per_second` is too slow compared to the fastest concurrent sync peer,
+/// which delivers `fastest_items_per_second`.
+pub(super) fn is_sync_peer_too_slow(items_per_second: f64, fastest_items_per_second: f64) -> bool {
+    items_per_second < fastest_items_per_second * MIN_RELATIVE_SYNC_THROUGHPUT
+}
+
 #[derive(Debug, Clone)]
 pub struct SyncPeer {
     peer_metadata: PeerChainMetadata,
@@ -134,6 +144,14 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn it_detects_slow_sync_peers() {
+        assert!(!is_sync_peer_too_slow(10.0, 10.0));
+        assert!(!is_sync_peer_too_slow(2.0, 10.0));
+        assert!(is_sync_peer_too_slow(1.9, 10.0));
+        assert!(is_sync_peer_too_slow(0.0, 10.0));
+    }
+
     mod sort_by_latency {
         use tari_common_types::types::FixedHash;
         use tari_comms::types::{CommsPublicKey, CommsSecretKey};
@@ -0,0 +1,343 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Compact block filters.
+//!
+//! A block filter is a Golomb-coded set (as described in BIP-158) over a handful of elements taken from each output in
+//! a block: the exact script, the sender offset public key and the commitment. A light client can test a filter
+//! against the elements it is interested in and skip downloading any block that cannot contain one of its outputs.
+//! Filters have no false negatives, and a false positive rate of roughly `1 / BLOCK_FILTER_M`.
+
+use std::convert::TryFrom;
+
+use blake2::Blake2b;
+use digest::consts::U32;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{Commitment, FixedHash, PublicKey};
+use tari_crypto::{hash_domain, hashing::DomainSeparatedHasher};
+use tari_script::{Opcode, TariScript};
+use tari_utilities::ByteArray;
+
+use crate::transactions::transaction_components::{OutputType, TransactionOutput};
+
+/// The number of low bits of each delta that are written verbatim in the Golomb-Rice encoding
+pub const BLOCK_FILTER_P: u8 = 19;
+/// The inverse of the target false positive rate
+pub const BLOCK_FILTER_M: u64 = 784_931;
+
+hash_domain!(BlockFilterHashDomain, "com.tari.base_layer.core.blocks.block_filter", 0);
+type BlockFilterHasher = DomainSeparatedHasher<Blake2b<U32>, BlockFilterHashDomain>;
+
+const ELEMENT_TAG_SCRIPT: u8 = 0;
+const ELEMENT_TAG_SENDER_OFFSET_PUBLIC_KEY: u8 = 1;
+const ELEMENT_TAG_COMMITMENT: u8 = 2;
+const ELEMENT_TAG_STEALTH_MARKER: u8 = 3;
+
+/// An item that can be added to, or looked up in, a [BlockFilter].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BlockFilterElement(Vec<u8>);
+
+impl BlockFilterElement {
+    pub fn script(script: &TariScript) -> Self {
+        Self::tagged(ELEMENT_TAG_SCRIPT, &script.to_bytes())
+    }
+
+    pub fn sender_offset_public_key(public_key: &PublicKey) -> Self {
+        Self::tagged(ELEMENT_TAG_SENDER_OFFSET_PUBLIC_KEY, public_key.as_bytes())
+    }
+
+    pub fn commitment(commitment: &Commitment) -> Self {
+        Self::tagged(ELEMENT_TAG_COMMITMENT, commitment.as_bytes())
+    }
+
+    /// Marks an output of `output_type` whose script has the form of a stealth address payment. The keys in a stealth
+    /// script are derived per output, so a wallet cannot look for them and matches this marker instead.
+    pub fn stealth_marker(output_type: OutputType) -> Self {
+        Self::tagged(ELEMENT_TAG_STEALTH_MARKER, &[output_type.as_byte()])
+    }
+
+    /// All the elements that an output contributes to its block's filter
+    pub fn for_output(output: &TransactionOutput) -> Vec<Self> {
+        let mut elements = vec![
+            Self::script(&output.script),
+            Self::sender_offset_public_key(&output.sender_offset_public_key),
+            Self::commitment(&output.commitment),
+        ];
+        if matches!(
+            output.script.as_slice(),
+            [Opcode::PushPubKey(_)] | [Opcode::PushPubKey(_), Opcode::Drop, Opcode::PushPubKey(_)]
+        ) {
+            elements.push(Self::stealth_marker(output.features.output_type));
+        }
+        elements
+    }
+
+    fn tagged(tag: u8, bytes: &[u8]) -> Self {
+        let mut buf = Vec::with_capacity(bytes.len() + 1);
+        buf.push(tag);
+        buf.extend_from_slice(bytes);
+        Self(buf)
+    }
+
+    /// Maps the element uniformly onto `[0, range)`, keyed by the block hash so that the same element collides
+    /// differently in each block.
+    fn hash_to_range(&self, block_hash: &FixedHash, range: u64) -> u64 {
+        let hash = BlockFilterHasher::new_with_label("element")
+            .chain(block_hash.as_slice())
+            .chain(&self.0)
+            .finalize();
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&hash.as_ref()[..8]);
+        let value = u128::from(u64::from_le_bytes(buf));
+        // (value * range) / 2^64 is always less than range
+        u64::try_from((value * u128::from(range)) >> 64).unwrap_or(range.saturating_sub(1))
+    }
+}
+
+/// A Golomb-coded set over the [BlockFilterElement]s of a block's outputs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFilter {
+    num_elements: u64,
+    data: Vec<u8>,
+}
+
+impl BlockFilter {
+    /// Builds the filter for the block with hash `block_hash` from the given elements. Duplicate elements are only
+    /// encoded once.
+    pub fn new<I: IntoIterator<Item = BlockFilterElement>>(block_hash: &FixedHash, elements: I) -> Self {
+        let mut elements = elements.into_iter().collect::<Vec<_>>();
+        elements.sort();
+        elements.dedup();
+        let num_elements = elements.len() as u64;
+        let range = num_elements.saturating_mul(BLOCK_FILTER_M);
+        let mut values = elements
+            .iter()
+            .map(|e| e.hash_to_range(block_hash, range))
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+
+        let mut writer = BitWriter::default();
+        let mut last = 0u64;
+        for value in values {
+            writer.write_golomb_rice(value - last);
+            last = value;
+        }
+
+        Self {
+            num_elements,
+            data: writer.finish(),
+        }
+    }
+
+    /// Builds the filter for a block from its outputs
+    pub fn from_outputs<'a, I: IntoIterator<Item = &'a TransactionOutput>>(block_hash: &FixedHash, outputs: I) -> Self {
+        Self::new(block_hash, outputs.into_iter().flat_map(BlockFilterElement::for_output))
+    }
+
+    /// Reconstructs a filter from its encoded parts, e.g. as received from a base node
+    pub fn from_parts(num_elements: u64, data: Vec<u8>) -> Self {
+        Self { num_elements, data }
+    }
+
+    pub fn num_elements(&self) -> u64 {
+        self.num_elements
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_parts(self) -> (u64, Vec<u8>) {
+        (self.num_elements, self.data)
+    }
+
+    /// Returns true if any of `elements` may be in the filter for the block with hash `block_hash`. A filter that
+    /// cannot be decoded matches everything, so that a block is never skipped because of bad filter data.
+    pub fn matches_any(&self, block_hash: &FixedHash, elements: &[BlockFilterElement]) -> bool {
+        if self.num_elements == 0 || elements.is_empty() {
+            return false;
+        }
+        let range = self.num_elements.saturating_mul(BLOCK_FILTER_M);
+        let mut queries = elements
+            .iter()
+            .map(|e| e.hash_to_range(block_hash, range))
+            .collect::<Vec<_>>();
+        queries.sort_unstable();
+
+        let mut reader = BitReader::new(&self.data);
+        let mut value = 0u64;
+        let mut queries = queries.into_iter().peekable();
+        for _ in 0..self.num_elements {
+            let delta = match reader.read_golomb_rice() {
+                Some(delta) => delta,
+                None => return true,
+            };
+            value = match value.checked_add(delta) {
+                Some(value) => value,
+                None => return true,
+            };
+            while let Some(query) = queries.peek() {
+                if *query == value {
+                    return true;
+                }
+                if *query > value {
+                    break;
+                }
+                queries.next();
+            }
+            if queries.peek().is_none() {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    num_bits: usize,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        if self.num_bits % 8 == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            if let Some(byte) = self.bytes.last_mut() {
+                *byte |= 0x80 >> (self.num_bits % 8);
+            }
+        }
+        self.num_bits += 1;
+    }
+
+    fn write_golomb_rice(&mut self, value: u64) {
+        for _ in 0..(value >> BLOCK_FILTER_P) {
+            self.write_bit(true);
+        }
+        self.write_bit(false);
+        for i in (0..BLOCK_FILTER_P).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Some(bit)
+    }
+
+    fn read_golomb_rice(&mut self) -> Option<u64> {
+        let mut quotient = 0u64;
+        while self.read_bit()? {
+            quotient = quotient.checked_add(1)?;
+        }
+        let mut remainder = 0u64;
+        for _ in 0..BLOCK_FILTER_P {
+            remainder = (remainder << 1) | u64::from(self.read_bit()?);
+        }
+        quotient
+            .checked_mul(1 << BLOCK_FILTER_P)
+            .and_then(|q| q.checked_add(remainder))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn elements(n: u8) -> Vec<BlockFilterElement> {
+        (0..n)
+            .map(|i| BlockFilterElement::tagged(ELEMENT_TAG_COMMITMENT, &[i; 32]))
+            .collect()
+    }
+
+    #[test]
+    fn it_matches_every_element_in_the_filter() {
+        let block_hash = FixedHash::from([1u8; 32]);
+        let filter = BlockFilter::new(&block_hash, elements(100));
+        assert_eq!(filter.num_elements(), 100);
+        for element in elements(100) {
+            assert!(filter.matches_any(&block_hash, &[element]));
+        }
+    }
+
+    #[test]
+    fn it_does_not_match_missing_elements() {
+        let block_hash = FixedHash::from([1u8; 32]);
+        let filter = BlockFilter::new(&block_hash, elements(10));
+        let missing = (100..200u8)
+            .map(|i| BlockFilterElement::tagged(ELEMENT_TAG_COMMITMENT, &[i; 32]))
+            .collect::<Vec<_>>();
+        assert!(!filter.matches_any(&block_hash, &missing));
+        assert!(!BlockFilter::default().matches_any(&block_hash, &missing));
+    }
+
+    #[test]
+    fn it_deduplicates_elements() {
+        let block_hash = FixedHash::from([1u8; 32]);
+        let mut items = elements(5);
+        items.extend(elements(5));
+        let filter = BlockFilter::new(&block_hash, items);
+        assert_eq!(filter, BlockFilter::new(&block_hash, elements(5)));
+    }
+
+    #[test]
+    fn stealth_payments_contribute_a_marker() {
+        let mut output = TransactionOutput::default();
+        assert!(!BlockFilterElement::for_output(&output)
+            .contains(&BlockFilterElement::stealth_marker(OutputType::Standard)));
+
+        output.script = tari_script::push_pubkey_script(&PublicKey::default());
+        assert!(
+            BlockFilterElement::for_output(&output).contains(&BlockFilterElement::stealth_marker(OutputType::Standard))
+        );
+        output.features.output_type = OutputType::Coinbase;
+        assert!(
+            BlockFilterElement::for_output(&output).contains(&BlockFilterElement::stealth_marker(OutputType::Coinbase))
+        );
+    }
+
+    #[test]
+    fn malformed_filters_match_everything() {
+        let block_hash = FixedHash::from([1u8; 32]);
+        let filter = BlockFilter::from_parts(10, vec![0u8; 2]);
+        assert!(filter.matches_any(&block_hash, &elements(1)));
+    }
+}
@@ -39,6 +39,11 @@ pub use error::BlockError;
 mod block;
 pub use block::{Block, BlockBuilder, BlockValidationError, NewBlock};
 
+#[cfg(any(feature = "base_node", feature = "base_node_proto"))]
+mod block_filter;
+#[cfg(any(feature = "base_node", feature = "base_node_proto"))]
+pub use block_filter::{BlockFilter, BlockFilterElement, BLOCK_FILTER_M, BLOCK_FILTER_P};
+
 #[cfg(any(feature = "base_node", feature = "base_node_proto"))]
 mod block_header;
 #[cfg(any(feature = "base_node", feature = "base_node_proto"))]
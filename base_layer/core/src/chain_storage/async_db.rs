@@ -19,12 +19,7 @@
 // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-use std::{
-    mem,
-    ops::RangeBounds,
-    sync::{Arc, RwLock},
-    time::Instant,
-};
+use std::{mem, ops::RangeBounds, sync::Arc, time::Instant};
 
 use log::*;
 use primitive_types::U256;
@@ -40,6 +35,7 @@ use crate::{
     blocks::{
         Block,
         BlockAccumulatedData,
+        BlockFilter,
         BlockHeader,
         BlockHeaderAccumulatedData,
         ChainBlock,
@@ -65,7 +61,7 @@ use crate::{
     common::rolling_vec::RollingVec,
     proof_of_work::{PowAlgorithm, TargetDifficultyWindow},
     transactions::transaction_components::{OutputType, TransactionInput, TransactionKernel, TransactionOutput},
-    OutputSmt,
+    OutputSmtProof,
 };
 
 const LOG_TARGET: &str = "c::bn::async_db";
@@ -172,6 +168,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_outputs_with_spend_status_at_tip(hashes: Vec<HashOutput>) -> Vec<Option<(TransactionOutput, bool)>>, "fetch_outputs_with_spend_status_at_tip");
 
+    make_async_fn!(fetch_output_smt_proofs(hashes: Vec<HashOutput>, header_hash: HashOutput) -> Vec<(HashOutput, u64, OutputSmtProof)>, "fetch_output_smt_proofs");
+
     make_async_fn!(fetch_outputs_mined_info(hashes: Vec<HashOutput>) -> Vec<Option<OutputMinedInfo>>, "fetch_outputs_mined_info");
 
     make_async_fn!(fetch_inputs_mined_info(hashes: Vec<HashOutput>) -> Vec<Option<InputMinedInfo>>, "fetch_inputs_mined_info");
@@ -180,6 +178,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_outputs_in_block(header_hash: HashOutput) -> Vec<TransactionOutput>, "fetch_outputs_in_block");
 
+    make_async_fn!(fetch_block_filters(start_height: u64, end_height: u64) -> Vec<(HashOutput, u64, BlockFilter)>, "fetch_block_filters");
+
     make_async_fn!(fetch_inputs_in_block(header_hash: HashOutput) -> Vec<TransactionInput>, "fetch_inputs_in_block");
 
     make_async_fn!(utxo_count() -> usize, "utxo_count");
@@ -394,8 +394,8 @@ impl<'a, B: BlockchainBackend + 'static> AsyncDbTransaction<'a, B> {
         self
     }
 
-    pub fn insert_tip_block_body(&mut self, block: Arc<ChainBlock>, smt: Arc<RwLock<OutputSmt>>) -> &mut Self {
-        self.transaction.insert_tip_block_body(block, smt);
+    pub fn insert_tip_block_body(&mut self, block: Arc<ChainBlock>) -> &mut Self {
+        self.transaction.insert_tip_block_body(block);
         self
     }
 
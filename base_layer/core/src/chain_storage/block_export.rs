@@ -0,0 +1,438 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A portable flat file format for exporting a range of blocks from one node and importing them into another, used to
+//! bootstrap nodes without syncing over the network.
+//!
+//! The file starts with the magic bytes `TARIBLKS`, a format version byte and a borsh encoded [BlockExportHeader].
+//! This is followed by one record per block, in height order: the length of the encoded block as a little endian
+//! `u32`, a 32 byte checksum of the encoded block and the borsh encoded [Block].
+
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use blake2::Blake2b;
+use borsh::{BorshDeserialize, BorshSerialize};
+use digest::consts::U32;
+use tari_common_types::types::FixedHash;
+use tari_crypto::hashing::DomainSeparatedHasher;
+use tari_utilities::hex::Hex;
+use thiserror::Error;
+
+use crate::blocks::{Block, BlocksHashDomain};
+
+const BLOCK_EXPORT_MAGIC: [u8; 8] = *b"TARIBLKS";
+const BLOCK_EXPORT_VERSION: u8 = 1;
+/// Records larger than this are rejected, so that a corrupt length cannot cause a huge allocation
+const MAX_RECORD_SIZE: u32 = 32 * 1024 * 1024;
+const CHECKSUM_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum BlockExportError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("The file is not a block export")]
+    InvalidMagic,
+    #[error("Unsupported block export version {0} (expected {})", BLOCK_EXPORT_VERSION)]
+    UnsupportedVersion(u8),
+    #[error("The block export is for a chain with genesis block {found}, expected {expected}")]
+    GenesisMismatch { expected: String, found: String },
+    #[error("The block export starts at height {found}, expected {expected}")]
+    StartHeightMismatch { expected: u64, found: u64 },
+    #[error("The block export ends with an incomplete record for the block at height {height}")]
+    Truncated { height: u64 },
+    #[error("The record for the block at height {height} is too large ({size} bytes)")]
+    RecordTooLarge { height: u64, size: u32 },
+    #[error("Checksum mismatch for the block at height {height}")]
+    ChecksumMismatch { height: u64 },
+    #[error("Could not decode the block at height {height}: {details}")]
+    InvalidBlock { height: u64, details: String },
+    #[error("Expected the block at height {expected}, got height {found}")]
+    UnexpectedHeight { expected: u64, found: u64 },
+}
+
+/// Describes the chain and range that a block export was taken from
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct BlockExportHeader {
+    /// The hash of the genesis block of the exporting node's chain
+    pub genesis_hash: FixedHash,
+    /// The height of the first block in the export
+    pub start_height: u64,
+}
+
+impl BlockExportHeader {
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), BlockExportError> {
+        writer.write_all(&BLOCK_EXPORT_MAGIC)?;
+        writer.write_all(&[BLOCK_EXPORT_VERSION])?;
+        self.serialize(writer)?;
+        Ok(())
+    }
+
+    fn read<R: Read>(reader: &mut R) -> Result<Self, BlockExportError> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != BLOCK_EXPORT_MAGIC {
+            return Err(BlockExportError::InvalidMagic);
+        }
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != BLOCK_EXPORT_VERSION {
+            return Err(BlockExportError::UnsupportedVersion(version[0]));
+        }
+        Ok(Self::deserialize_reader(reader)?)
+    }
+
+    fn check_genesis_hash(&self, genesis_hash: &FixedHash) -> Result<(), BlockExportError> {
+        if self.genesis_hash != *genesis_hash {
+            return Err(BlockExportError::GenesisMismatch {
+                expected: genesis_hash.to_hex(),
+                found: self.genesis_hash.to_hex(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Appends blocks to a block export file
+pub struct BlockExportWriter {
+    writer: BufWriter<File>,
+    next_height: u64,
+}
+
+impl BlockExportWriter {
+    /// Creates a new block export at `path` starting at `start_height`. If the file already contains an export of the
+    /// same chain and range, e.g. because a previous export was interrupted, it is resumed: any incomplete trailing
+    /// record is discarded and blocks are appended from [BlockExportWriter::next_height].
+    pub fn create_or_resume<P: AsRef<Path>>(
+        path: P,
+        genesis_hash: FixedHash,
+        start_height: u64,
+    ) -> Result<Self, BlockExportError> {
+        let path = path.as_ref();
+        let is_resume = path.metadata().map(|m| m.len() > 0).unwrap_or(false);
+        if !is_resume {
+            let mut writer = BufWriter::new(File::create(path)?);
+            BlockExportHeader {
+                genesis_hash,
+                start_height,
+            }
+            .write(&mut writer)?;
+            return Ok(Self {
+                writer,
+                next_height: start_height,
+            });
+        }
+
+        let mut reader = BlockExportReader::open(path)?;
+        reader.header().check_genesis_hash(&genesis_hash)?;
+        if reader.header().start_height != start_height {
+            return Err(BlockExportError::StartHeightMismatch {
+                expected: start_height,
+                found: reader.header().start_height,
+            });
+        }
+        loop {
+            match reader.read_block() {
+                Ok(Some(_)) => {},
+                Ok(None) | Err(BlockExportError::Truncated { .. }) => break,
+                Err(err) => return Err(err),
+            }
+        }
+        let valid_len = reader.position;
+        let next_height = reader.next_height;
+        drop(reader);
+
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(valid_len)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            next_height,
+        })
+    }
+
+    /// The height of the next block that must be written
+    pub fn next_height(&self) -> u64 {
+        self.next_height
+    }
+
+    /// Appends a block to the export. Blocks must be written in height order.
+    pub fn write_block(&mut self, block: &Block) -> Result<(), BlockExportError> {
+        if block.header.height != self.next_height {
+            return Err(BlockExportError::UnexpectedHeight {
+                expected: self.next_height,
+                found: block.header.height,
+            });
+        }
+        let bytes = borsh::to_vec(block)?;
+        let size = u32::try_from(bytes.len()).map_err(|_| BlockExportError::RecordTooLarge {
+            height: block.header.height,
+            size: u32::MAX,
+        })?;
+        if size > MAX_RECORD_SIZE {
+            return Err(BlockExportError::RecordTooLarge {
+                height: block.header.height,
+                size,
+            });
+        }
+        self.writer.write_all(&size.to_le_bytes())?;
+        self.writer.write_all(&checksum(&bytes))?;
+        self.writer.write_all(&bytes)?;
+        self.next_height += 1;
+        Ok(())
+    }
+
+    /// Flushes the written blocks to the file
+    pub fn flush(&mut self) -> Result<(), BlockExportError> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()?;
+        Ok(())
+    }
+}
+
+/// Reads blocks from a block export file
+pub struct BlockExportReader {
+    reader: BufReader<File>,
+    header: BlockExportHeader,
+    next_height: u64,
+    /// The number of bytes read up to the end of the last complete record
+    position: u64,
+}
+
+impl BlockExportReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, BlockExportError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let header = BlockExportHeader::read(&mut reader)?;
+        let position = reader.stream_position()?;
+        Ok(Self {
+            next_height: header.start_height,
+            reader,
+            header,
+            position,
+        })
+    }
+
+    pub fn header(&self) -> &BlockExportHeader {
+        &self.header
+    }
+
+    /// Checks that the export was taken from the chain with the given genesis block
+    pub fn check_genesis_hash(&self, genesis_hash: &FixedHash) -> Result<(), BlockExportError> {
+        self.header.check_genesis_hash(genesis_hash)
+    }
+
+    /// Reads the next block from the export, or returns `None` once all blocks have been read
+    pub fn read_block(&mut self) -> Result<Option<Block>, BlockExportError> {
+        let height = self.next_height;
+        let mut size = [0u8; 4];
+        if !read_record_part(&mut self.reader, &mut size, height, true)? {
+            return Ok(None);
+        }
+        let size = u32::from_le_bytes(size);
+        if size > MAX_RECORD_SIZE {
+            return Err(BlockExportError::RecordTooLarge { height, size });
+        }
+        let mut expected_checksum = [0u8; CHECKSUM_SIZE];
+        read_record_part(&mut self.reader, &mut expected_checksum, height, false)?;
+        let mut bytes = vec![0u8; size as usize];
+        read_record_part(&mut self.reader, &mut bytes, height, false)?;
+        if checksum(&bytes) != expected_checksum {
+            return Err(BlockExportError::ChecksumMismatch { height });
+        }
+
+        let block = Block::try_from_slice(&bytes).map_err(|e| BlockExportError::InvalidBlock {
+            height,
+            details: e.to_string(),
+        })?;
+        if block.header.height != height {
+            return Err(BlockExportError::UnexpectedHeight {
+                expected: height,
+                found: block.header.height,
+            });
+        }
+        self.position += 4 + CHECKSUM_SIZE as u64 + u64::from(size);
+        self.next_height += 1;
+        Ok(Some(block))
+    }
+}
+
+/// Fills `buf` from the reader. Returns false if the reader is at the end of the file and `allow_eof` is set, and a
+/// `Truncated` error if the file ends part way through the record.
+fn read_record_part<R: Read>(
+    reader: &mut R,
+    buf: &mut [u8],
+    height: u64,
+    allow_eof: bool,
+) -> Result<bool, BlockExportError> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) if read == 0 && allow_eof => return Ok(false),
+            Ok(0) => return Err(BlockExportError::Truncated { height }),
+            Ok(n) => read += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(true)
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let hash = DomainSeparatedHasher::<Blake2b<U32>, BlocksHashDomain>::new_with_label("block_export")
+        .chain(bytes)
+        .finalize();
+    let mut checksum = [0u8; CHECKSUM_SIZE];
+    checksum.copy_from_slice(hash.as_ref());
+    checksum
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{blocks::BlockHeader, transactions::aggregated_body::AggregateBody};
+
+    fn create_blocks(start_height: u64, num_blocks: u64) -> Vec<Block> {
+        (start_height..start_height + num_blocks)
+            .map(|height| {
+                let mut header = BlockHeader::new(0);
+                header.height = height;
+                Block::new(header, AggregateBody::empty())
+            })
+            .collect()
+    }
+
+    fn read_all(path: &Path) -> Result<Vec<Block>, BlockExportError> {
+        let mut reader = BlockExportReader::open(path)?;
+        let mut blocks = Vec::new();
+        while let Some(block) = reader.read_block()? {
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    #[test]
+    fn it_round_trips_blocks_through_a_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("blocks.bin");
+        let genesis_hash = FixedHash::from([1u8; 32]);
+        let blocks = create_blocks(5, 4);
+
+        let mut writer = BlockExportWriter::create_or_resume(&path, genesis_hash, 5).unwrap();
+        blocks.iter().for_each(|block| writer.write_block(block).unwrap());
+        writer.flush().unwrap();
+
+        let reader = BlockExportReader::open(&path).unwrap();
+        assert_eq!(reader.header().start_height, 5);
+        reader.check_genesis_hash(&genesis_hash).unwrap();
+        assert!(matches!(
+            reader.check_genesis_hash(&FixedHash::zero()),
+            Err(BlockExportError::GenesisMismatch { .. })
+        ));
+        assert_eq!(read_all(&path).unwrap(), blocks);
+    }
+
+    #[test]
+    fn it_rejects_blocks_out_of_order() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("blocks.bin");
+        let mut writer = BlockExportWriter::create_or_resume(&path, FixedHash::zero(), 1).unwrap();
+        let blocks = create_blocks(2, 1);
+        assert!(matches!(
+            writer.write_block(&blocks[0]),
+            Err(BlockExportError::UnexpectedHeight { expected: 1, found: 2 })
+        ));
+    }
+
+    #[test]
+    fn it_resumes_an_interrupted_export() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("blocks.bin");
+        let genesis_hash = FixedHash::from([2u8; 32]);
+        let blocks = create_blocks(1, 6);
+
+        let mut writer = BlockExportWriter::create_or_resume(&path, genesis_hash, 1).unwrap();
+        blocks[..3].iter().for_each(|block| writer.write_block(block).unwrap());
+        writer.flush().unwrap();
+        drop(writer);
+
+        // Simulate an export that was interrupted part way through writing the fourth block
+        let complete_len = fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[10, 0, 0, 0, 1, 2, 3]).unwrap();
+        drop(file);
+        assert!(matches!(
+            read_all(&path),
+            Err(BlockExportError::Truncated { height: 4 })
+        ));
+
+        let mut writer = BlockExportWriter::create_or_resume(&path, genesis_hash, 1).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), complete_len);
+        assert_eq!(writer.next_height(), 4);
+        blocks[3..].iter().for_each(|block| writer.write_block(block).unwrap());
+        writer.flush().unwrap();
+
+        assert_eq!(read_all(&path).unwrap(), blocks);
+        assert!(matches!(
+            BlockExportWriter::create_or_resume(&path, FixedHash::zero(), 1),
+            Err(BlockExportError::GenesisMismatch { .. })
+        ));
+        assert!(matches!(
+            BlockExportWriter::create_or_resume(&path, genesis_hash, 2),
+            Err(BlockExportError::StartHeightMismatch { expected: 2, found: 1 })
+        ));
+    }
+
+    #[test]
+    fn it_detects_corrupt_blocks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("blocks.bin");
+        let mut writer = BlockExportWriter::create_or_resume(&path, FixedHash::zero(), 1).unwrap();
+        create_blocks(1, 2)
+            .iter()
+            .for_each(|block| writer.write_block(block).unwrap());
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            read_all(&path),
+            Err(BlockExportError::ChecksumMismatch { height: 2 })
+        ));
+
+        fs::write(&path, b"NOTBLOCKS").unwrap();
+        assert!(matches!(
+            BlockExportReader::open(&path),
+            Err(BlockExportError::InvalidMagic)
+        ));
+    }
+}
@@ -3,12 +3,21 @@
 
 use tari_common_types::{
     chain_metadata::ChainMetadata,
-    types::{Commitment, HashOutput, PublicKey, Signature},
+    types::{Commitment, FixedHash, HashOutput, PublicKey, Signature},
 };
+use tari_mmr::sparse_merkle_tree::{NodeKey, ValueHash};
 
 use super::TemplateRegistrationEntry;
 use crate::{
-    blocks::{Block, BlockAccumulatedData, BlockHeader, BlockHeaderAccumulatedData, ChainBlock, ChainHeader},
+    blocks::{
+        Block,
+        BlockAccumulatedData,
+        BlockFilter,
+        BlockHeader,
+        BlockHeaderAccumulatedData,
+        ChainBlock,
+        ChainHeader,
+    },
     chain_storage::{
         ChainStorageError,
         DbBasicStats,
@@ -20,10 +29,11 @@ use crate::{
         InputMinedInfo,
         MmrTree,
         OutputMinedInfo,
+        OutputSmtChange,
         Reorg,
     },
     transactions::transaction_components::{TransactionInput, TransactionKernel, TransactionOutput},
-    OutputSmt,
+    OutputSmtProof,
 };
 
 /// Identify behaviour for Blockchain database backends. Implementations must support `Send` and `Sync` so that
@@ -110,6 +120,9 @@ pub trait BlockchainBackend: Send + Sync {
     /// Fetch all outputs in a block
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError>;
 
+    /// Fetch the compact filter stored for a block, if the block was committed with one
+    fn fetch_block_filter(&self, header_hash: &HashOutput) -> Result<Option<BlockFilter>, ChainStorageError>;
+
     /// Fetch all inputs in a block
     fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError>;
 
@@ -181,6 +194,16 @@ pub trait BlockchainBackend: Send + Sync {
         start_height: u64,
         end_height: u64,
     ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError>;
-    /// Calculates the tip utxo smt
-    fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError>;
+    /// Returns the root and number of leaves that the output SMT at the tip would have after applying `changes` in
+    /// order. The stored tree is not changed.
+    fn calculate_output_smt_root(&self, changes: &[OutputSmtChange]) -> Result<(FixedHash, u64), ChainStorageError>;
+    /// Returns true if the output SMT at the tip has a leaf for `key`, i.e. an output with that commitment is unspent.
+    fn output_smt_contains(&self, key: &NodeKey) -> Result<bool, ChainStorageError>;
+    /// Returns a proof of each of the `leaves` against the output SMT at the tip after applying `changes` in order. An
+    /// entry is `None` if neither an inclusion nor an exclusion proof can be made for the leaf.
+    fn fetch_output_smt_proofs(
+        &self,
+        changes: &[OutputSmtChange],
+        leaves: &[(NodeKey, ValueHash)],
+    ) -> Result<Vec<Option<OutputSmtProof>>, ChainStorageError>;
 }
@@ -43,7 +43,7 @@ use tari_common_types::{
 use tari_hashing::TransactionHashDomain;
 use tari_mmr::{
     pruned_hashset::PrunedHashSet,
-    sparse_merkle_tree::{DeleteResult, NodeKey, ValueHash},
+    sparse_merkle_tree::{NodeKey, ValueHash},
 };
 use tari_utilities::{epoch_time::EpochTime, hex::Hex, ByteArray};
 
@@ -53,6 +53,7 @@ use crate::{
     blocks::{
         Block,
         BlockAccumulatedData,
+        BlockFilter,
         BlockHeader,
         BlockHeaderAccumulatedData,
         BlockHeaderValidationError,
@@ -80,6 +81,7 @@ use crate::{
         MmrTree,
         Optional,
         OrNotFound,
+        OutputSmtChange,
         Reorg,
         TargetDifficulties,
     },
@@ -92,7 +94,6 @@ use crate::{
     },
     input_mr_hash_from_pruned_mmr,
     kernel_mr_hash_from_pruned_mmr,
-    output_mr_hash_from_smt,
     proof_of_work::{monero_rx::MoneroPowData, PowAlgorithm, TargetDifficultyWindow},
     transactions::transaction_components::{TransactionInput, TransactionKernel, TransactionOutput},
     validation::{
@@ -103,7 +104,7 @@ use crate::{
         InternalConsistencyValidator,
         ValidationError,
     },
-    OutputSmt,
+    OutputSmtProof,
     PrunedInputMmr,
     PrunedKernelMmr,
     PrunedOutputMmr,
@@ -111,6 +112,9 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "c::cs::database";
+/// The deepest block below the tip at which output SMT proofs are generated. The tree has to be rewound to the block
+/// for every request, so this is kept small; wallets ask for proofs at the tip.
+pub const MAX_OUTPUT_SMT_PROOF_DEPTH: u64 = 10;
 
 /// Configuration for the BlockchainDatabase.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -213,7 +217,6 @@ pub struct BlockchainDatabase<B> {
     consensus_manager: ConsensusManager,
     difficulty_calculator: Arc<DifficultyCalculator>,
     disable_add_block_flag: Arc<AtomicBool>,
-    smt: Arc<RwLock<OutputSmt>>,
 }
 
 #[allow(clippy::ptr_arg)]
@@ -227,7 +230,6 @@ where B: BlockchainBackend
         validators: Validators<B>,
         config: BlockchainDatabaseConfig,
         difficulty_calculator: DifficultyCalculator,
-        smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<Self, ChainStorageError> {
         debug!(target: LOG_TARGET, "BlockchainDatabase config: {:?}", config);
         let blockchain_db = BlockchainDatabase {
@@ -237,7 +239,6 @@ where B: BlockchainBackend
             consensus_manager,
             difficulty_calculator: Arc::new(difficulty_calculator),
             disable_add_block_flag: Arc::new(AtomicBool::new(false)),
-            smt,
         };
         Ok(blockchain_db)
     }
@@ -248,7 +249,6 @@ where B: BlockchainBackend
         validators: Validators<B>,
         config: BlockchainDatabaseConfig,
         difficulty_calculator: DifficultyCalculator,
-        smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<Self, ChainStorageError> {
         let blockchain_db = BlockchainDatabase {
             db: Arc::new(RwLock::new(db)),
@@ -257,7 +257,6 @@ where B: BlockchainBackend
             consensus_manager,
             difficulty_calculator: Arc::new(difficulty_calculator),
             disable_add_block_flag: Arc::new(AtomicBool::new(false)),
-            smt,
         };
         blockchain_db.start()?;
         Ok(blockchain_db)
@@ -312,12 +311,6 @@ where B: BlockchainBackend
                  resync your blockchain database."
                     .into(),
             ));
-        } else {
-            // lets load the smt into memory
-            let mut smt = self.smt_write_access()?;
-            warn!(target: LOG_TARGET, "Loading SMT into memory from stored db");
-            *smt = self.db_write_access()?.calculate_tip_smt()?;
-            warn!(target: LOG_TARGET, "Finished loading SMT into memory from stored db");
         }
         if config.cleanup_orphans_at_startup {
             match self.cleanup_all_orphans() {
@@ -373,30 +366,6 @@ where B: BlockchainBackend
         })
     }
 
-    pub fn smt_write_access(&self) -> Result<RwLockWriteGuard<OutputSmt>, ChainStorageError> {
-        self.smt.write().map_err(|e| {
-            error!(
-                target: LOG_TARGET,
-                "An attempt to get a write lock on the smt failed. {:?}", e
-            );
-            ChainStorageError::AccessError("write lock on smt".into())
-        })
-    }
-
-    pub fn smt(&self) -> Arc<RwLock<OutputSmt>> {
-        self.smt.clone()
-    }
-
-    pub fn smt_read_access(&self) -> Result<RwLockReadGuard<OutputSmt>, ChainStorageError> {
-        self.smt.read().map_err(|e| {
-            error!(
-                target: LOG_TARGET,
-                "An attempt to get a read lock on the smt failed. {:?}", e
-            );
-            ChainStorageError::AccessError("read lock on smt".into())
-        })
-    }
-
     #[cfg(test)]
     pub fn test_db_write_access(&self) -> Result<RwLockWriteGuard<B>, ChainStorageError> {
         self.db.write().map_err(|e| {
@@ -483,20 +452,83 @@ where B: BlockchainBackend
         hashes: Vec<HashOutput>,
     ) -> Result<Vec<Option<(TransactionOutput, bool)>>, ChainStorageError> {
         let db = self.db_read_access()?;
-        let smt = self.smt_read_access()?;
         let mut result = Vec::with_capacity(hashes.len());
         for hash in hashes {
-            let output = db.fetch_output(&hash)?;
-
-            result.push(output.map(|mined_info| {
-                let smt_key = NodeKey::try_from(mined_info.output.commitment.as_bytes()).unwrap();
-                let spent = !smt.contains(&smt_key);
-                (mined_info.output, spent)
-            }));
+            let output = match db.fetch_output(&hash)? {
+                Some(mined_info) => {
+                    let smt_key = NodeKey::try_from(mined_info.output.commitment.as_bytes())?;
+                    let spent = !db.output_smt_contains(&smt_key)?;
+                    Some((mined_info.output, spent))
+                },
+                None => None,
+            };
+            result.push(output);
         }
         Ok(result)
     }
 
+    /// Returns an output SMT proof of the spent status of each output known to the node that was mined at or before
+    /// `header_hash`, together with its hash and mined height. The proofs are against the `output_mr` of
+    /// `header_hash`, which must be a main chain block at most `MAX_OUTPUT_SMT_PROOF_DEPTH` blocks below the tip.
+    pub fn fetch_output_smt_proofs(
+        &self,
+        hashes: Vec<HashOutput>,
+        header_hash: HashOutput,
+    ) -> Result<Vec<(HashOutput, u64, OutputSmtProof)>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        let metadata = db.fetch_chain_metadata()?;
+        let header = match fetch_header_by_block_hash(&*db, header_hash)? {
+            Some(header)
+                if header.height <= metadata.best_block_height() &&
+                    *db.fetch_chain_header_by_height(header.height)?.hash() == header_hash =>
+            {
+                header
+            },
+            _ => {
+                return Err(ChainStorageError::InvalidArguments {
+                    func: "fetch_output_smt_proofs",
+                    arg: "header_hash",
+                    message: format!("Block {} is not in the main chain", header_hash),
+                })
+            },
+        };
+        let depth = metadata.best_block_height() - header.height;
+        if depth > MAX_OUTPUT_SMT_PROOF_DEPTH || header.height < metadata.pruned_height() {
+            return Err(ChainStorageError::InvalidArguments {
+                func: "fetch_output_smt_proofs",
+                arg: "header_hash",
+                message: format!(
+                    "Proofs can only be generated for the last {} unpruned blocks, block {} is {} blocks below the tip",
+                    MAX_OUTPUT_SMT_PROOF_DEPTH, header_hash, depth
+                ),
+            });
+        }
+
+        let mut outputs = Vec::with_capacity(hashes.len());
+        let mut leaves = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            match db.fetch_output(&hash)? {
+                Some(mined_info) if mined_info.mined_height <= header.height => {
+                    let smt_key = NodeKey::try_from(mined_info.output.commitment.as_bytes())?;
+                    let smt_value =
+                        ValueHash::try_from(mined_info.output.smt_hash(mined_info.mined_height).as_slice())?;
+                    leaves.push((smt_key, smt_value));
+                    outputs.push((hash, mined_info.mined_height));
+                },
+                _ => {},
+            }
+        }
+        let changes = output_smt_changes_to_rewind(&*db, header.height, metadata.best_block_height())?;
+        let proofs = db.fetch_output_smt_proofs(&changes, &leaves)?;
+        // A proof is missing if a later unspent output reuses the commitment, so the status of this one cannot be
+        // proven
+        Ok(outputs
+            .into_iter()
+            .zip(proofs)
+            .filter_map(|((hash, mined_height), proof)| proof.map(|proof| (hash, mined_height, proof)))
+            .collect())
+    }
+
     pub fn fetch_outputs_mined_info(
         &self,
         hashes: Vec<HashOutput>,
@@ -547,6 +579,42 @@ where B: BlockchainBackend
         db.fetch_outputs_in_block_with_spend_state(&header_hash, spend_status_at_header.as_ref())
     }
 
+    /// Returns the compact filter of each main chain block from `start_height` to `end_height` (inclusive, capped at
+    /// the tip), together with its block hash and height. Filters for blocks that were committed before filters were
+    /// indexed are built from the block's outputs.
+    pub fn fetch_block_filters(
+        &self,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<(HashOutput, u64, BlockFilter)>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        let end_height = cmp::min(end_height, db.fetch_chain_metadata()?.best_block_height());
+        if start_height > end_height {
+            return Err(ChainStorageError::InvalidArguments {
+                func: "fetch_block_filters",
+                arg: "start_height",
+                message: format!(
+                    "start_height ({}) is greater than end_height ({})",
+                    start_height, end_height
+                ),
+            });
+        }
+
+        let mut filters = Vec::with_capacity(usize::try_from(end_height - start_height + 1).unwrap_or_default());
+        for height in start_height..=end_height {
+            let header = db.fetch_chain_header_by_height(height)?;
+            let filter = match db.fetch_block_filter(header.hash())? {
+                Some(filter) => filter,
+                None => {
+                    let outputs = db.fetch_outputs_in_block_with_spend_state(header.hash(), None)?;
+                    BlockFilter::from_outputs(header.hash(), outputs.iter().map(|(output, _)| output))
+                },
+            };
+            filters.push((*header.hash(), height, filter));
+        }
+        Ok(filters)
+    }
+
     pub fn fetch_outputs_in_block(&self, header_hash: HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError> {
         let db = self.db_read_access()?;
         db.fetch_outputs_in_block(&header_hash)
@@ -929,15 +997,7 @@ where B: BlockchainBackend
                 .ok_or(ChainStorageError::UnexpectedResult("Timestamp overflowed".to_string()))?;
         }
         let mut block = Block { header, body };
-        let mut smt = self.smt_write_access()?;
-        let roots = match calculate_mmr_roots(&*db, self.rules(), &block, &mut smt) {
-            Ok(v) => v,
-            Err(e) => {
-                // some error happend, lets rewind the smt
-                *smt = db.calculate_tip_smt()?;
-                return Err(e);
-            },
-        };
+        let roots = calculate_mmr_roots(&*db, self.rules(), &block)?;
         block.header.kernel_mr = roots.kernel_mr;
         block.header.kernel_mmr_size = roots.kernel_mmr_size;
         block.header.input_mr = roots.input_mr;
@@ -957,18 +1017,20 @@ where B: BlockchainBackend
                 "calculate_mmr_roots expected a sorted block body, however the block body was not sorted".to_string(),
             ));
         };
-        let mut smt = self.smt_write_access()?;
-        let mmr_roots = match calculate_mmr_roots(&*db, self.rules(), &block, &mut smt) {
-            Ok(v) => v,
-            Err(e) => {
-                // some error happend, lets reset the smt to its starting state
-                *smt = db.calculate_tip_smt()?;
-                return Err(e);
-            },
-        };
+        let mmr_roots = calculate_mmr_roots(&*db, self.rules(), &block)?;
         Ok((block, mmr_roots))
     }
 
+    /// Calculates the output SMT root and size that result from applying `changes` to the output SMT at the tip,
+    /// without persisting them.
+    pub fn calculate_output_smt_root(
+        &self,
+        changes: &[OutputSmtChange],
+    ) -> Result<(FixedHash, u64), ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.calculate_output_smt_root(changes)
+    }
+
     /// Fetches the total merkle mountain range node count up to the specified height.
     pub fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
         let db = self.db_read_access()?;
@@ -1072,7 +1134,6 @@ where B: BlockchainBackend
             &*self.validators.header,
             self.consensus_manager.chain_strength_comparer(),
             candidate_block,
-            self.smt(),
         )?;
 
         // If blocks were added and the node is in pruned mode, perform pruning
@@ -1122,7 +1183,7 @@ where B: BlockchainBackend
         let mut db = self.db_write_access()?;
 
         let mut txn = DbTransaction::new();
-        insert_best_block(&mut txn, block, &self.consensus_manager, self.smt())?;
+        insert_best_block(&mut txn, block, &self.consensus_manager)?;
         db.write(txn)
     }
 
@@ -1253,9 +1314,8 @@ where B: BlockchainBackend
     /// The operation will fail if
     /// * The block height is in the future
     pub fn rewind_to_height(&self, height: u64) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
-        let smt = self.smt().clone();
         let mut db = self.db_write_access()?;
-        rewind_to_height(&mut *db, height, smt)
+        rewind_to_height(&mut *db, height)
     }
 
     /// Rewind the blockchain state to the block hash making the block at that hash the new tip.
@@ -1266,7 +1326,7 @@ where B: BlockchainBackend
     /// * The block hash is before the horizon block height determined by the pruning horizon
     pub fn rewind_to_hash(&self, hash: BlockHash) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
         let mut db = self.db_write_access()?;
-        rewind_to_hash(&mut *db, hash, self.smt.clone())
+        rewind_to_hash(&mut *db, hash)
     }
 
     /// This method will compare all chain tips the node currently knows about. This includes
@@ -1282,7 +1342,6 @@ where B: BlockchainBackend
             &*self.validators.block,
             self.consensus_manager.chain_strength_comparer(),
             &self.consensus_manager,
-            self.smt(),
         )?;
         Ok(())
     }
@@ -1378,8 +1437,6 @@ pub fn calculate_mmr_roots<T: BlockchainBackend>(
     db: &T,
     rules: &ConsensusManager,
     block: &Block,
-    // we dont want to clone the SMT, so we rather change it and change it back after we are done.
-    output_smt: &mut OutputSmt,
 ) -> Result<MmrRoots, ChainStorageError> {
     let header = &block.header;
     let body = &block.body;
@@ -1412,7 +1469,7 @@ pub fn calculate_mmr_roots<T: BlockchainBackend>(
         kernel_mmr.push(kernel.hash().to_vec())?;
     }
 
-    let mut outputs_to_remove = Vec::new();
+    let mut smt_changes = Vec::with_capacity(body.outputs().len() + body.inputs().len());
     for output in body.outputs() {
         if output.features.is_coinbase() {
             block_output_mmr.push(output.hash().to_vec())?;
@@ -1420,37 +1477,16 @@ pub fn calculate_mmr_roots<T: BlockchainBackend>(
             normal_output_mmr.push(output.hash().to_vec())?;
         }
         if !output.is_burned() {
-            let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
-            let smt_node = ValueHash::try_from(output.smt_hash(header.height).as_slice())?;
-            outputs_to_remove.push(smt_key.clone());
-            if let Err(e) = output_smt.insert(smt_key, smt_node) {
-                error!(
-                    target: LOG_TARGET,
-                    "Output commitment({}) already in SMT",
-                    output.commitment.to_hex(),
-                );
-                return Err(e.into());
-            }
+            smt_changes.push(OutputSmtChange::insert_output(output, header.height)?);
         }
     }
     block_output_mmr.push(normal_output_mmr.get_merkle_root()?.to_vec())?;
 
-    let mut outputs_to_add = Vec::new();
     for input in body.inputs() {
         input_mmr.push(input.canonical_hash().to_vec())?;
-        let smt_key = NodeKey::try_from(input.commitment()?.as_bytes())?;
-        match output_smt.delete(&smt_key)? {
-            DeleteResult::Deleted(value_hash) => outputs_to_add.push((smt_key, value_hash)),
-            DeleteResult::KeyNotFound => {
-                error!(
-                    target: LOG_TARGET,
-                    "Could not find input({}) in SMT",
-                    input.commitment()?.to_hex(),
-                );
-                return Err(ChainStorageError::UnspendableInput);
-            },
-        };
+        smt_changes.push(OutputSmtChange::delete_output(input.commitment()?)?);
     }
+    let (output_mr, output_smt_size) = db.calculate_output_smt_root(&smt_changes)?;
 
     let block_height = block.header.height;
     let epoch_len = rules.consensus_constants(block_height).epoch_length();
@@ -1477,44 +1513,43 @@ pub fn calculate_mmr_roots<T: BlockchainBackend>(
         kernel_mr: kernel_mr_hash_from_pruned_mmr(&kernel_mmr)?,
         kernel_mmr_size: kernel_mmr.get_leaf_count()? as u64,
         input_mr: input_mr_hash_from_pruned_mmr(&input_mmr)?,
-        output_mr: output_mr_hash_from_smt(output_smt)?,
+        output_mr,
         block_output_mr,
-        output_smt_size: output_smt.size(),
+        output_smt_size,
         validator_node_mr,
         validator_node_size: validator_node_size as u64,
     };
-    // We have made changes to the SMT that we dont want, sp lets rewind the SMT back to tip again as we want to have
-    // the SMT at tip.
-    for output in outputs_to_add {
-        if output_smt.insert(output.0.clone(), output.1).is_err() {
-            error!(
-                target: LOG_TARGET,
-                "Output commitment({}) already in SMT",
-                output.0,
-            );
-            return Err(ChainStorageError::AccessError(format!(
-                "Could not add output ({}) in SMT",
-                output.0
-            )));
+    Ok(mmr_roots)
+}
+
+/// Returns the changes that take the output SMT at `tip_height` back to the output SMT at `height`, by undoing each
+/// block from the tip down: its spent outputs are added back to the tree before its new outputs are removed.
+fn output_smt_changes_to_rewind<T: BlockchainBackend>(
+    db: &T,
+    height: u64,
+    tip_height: u64,
+) -> Result<Vec<OutputSmtChange>, ChainStorageError> {
+    let mut changes = Vec::new();
+    for block_height in (height + 1..=tip_height).rev() {
+        let block_hash = *db.fetch_chain_header_by_height(block_height)?.hash();
+        for input in db.fetch_inputs_in_block(&block_hash)? {
+            let spent_output = db.fetch_output(&input.output_hash()).or_not_found(
+                "TransactionOutput",
+                "hash",
+                input.output_hash().to_hex(),
+            )?;
+            changes.push(OutputSmtChange::insert_output(
+                &spent_output.output,
+                spent_output.mined_height,
+            )?);
+        }
+        for output in db.fetch_outputs_in_block(&block_hash)? {
+            if !output.is_burned() {
+                changes.push(OutputSmtChange::delete_output(&output.commitment)?);
+            }
         }
     }
-    for output in outputs_to_remove {
-        match output_smt.delete(&output)? {
-            DeleteResult::Deleted(_value_hash) => {},
-            DeleteResult::KeyNotFound => {
-                error!(
-                    target: LOG_TARGET,
-                    "Could not find input({}) in SMT when reseting back to tip",
-                    output,
-                );
-                return Err(ChainStorageError::AccessError(format!(
-                    "Could not find input({}) in SMT when reseting back to tip",
-                    output
-                )));
-            },
-        };
-    }
-    Ok(mmr_roots)
+    Ok(changes)
 }
 
 pub fn calculate_validator_node_mr(validator_nodes: &[(PublicKey, [u8; 32])]) -> tari_mmr::Hash {
@@ -1618,7 +1653,6 @@ fn add_block<T: BlockchainBackend>(
     header_validator: &dyn HeaderChainLinkedValidator<T>,
     chain_strength_comparer: &dyn ChainStrengthComparer,
     candidate_block: Arc<Block>,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<BlockAddResult, ChainStorageError> {
     handle_possible_reorg(
         db,
@@ -1628,7 +1662,6 @@ fn add_block<T: BlockchainBackend>(
         header_validator,
         chain_strength_comparer,
         candidate_block,
-        smt,
     )
 }
 
@@ -1637,7 +1670,6 @@ fn insert_best_block(
     txn: &mut DbTransaction,
     block: Arc<ChainBlock>,
     consensus: &ConsensusManager,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<(), ChainStorageError> {
     let block_hash = block.accumulated_data().hash;
     debug!(
@@ -1661,7 +1693,7 @@ fn insert_best_block(
     let accumulated_difficulty = block.accumulated_data().total_accumulated_difficulty;
     let expected_prev_best_block = block.block().header.prev_hash;
     txn.insert_chain_header(block.to_chain_header())
-        .insert_tip_block_body(block, smt)
+        .insert_tip_block_body(block)
         .set_best_block(
             height,
             block_hash,
@@ -1837,7 +1869,6 @@ fn check_for_valid_height<T: BlockchainBackend>(db: &T, height: u64) -> Result<(
 fn rewind_to_height<T: BlockchainBackend>(
     db: &mut T,
     target_height: u64,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
     let last_header = db.fetch_last_header()?;
 
@@ -1907,7 +1938,7 @@ fn rewind_to_height<T: BlockchainBackend>(
         let block = fetch_block(db, last_block_height - h, false)?;
         let block = Arc::new(block.try_into_chain_block()?);
         let block_hash = *block.hash();
-        txn.delete_tip_block(block_hash, smt.clone());
+        txn.delete_tip_block(block_hash);
         txn.delete_header(last_block_height - h);
         if !prune_past_horizon && !db.contains(&DbKey::OrphanBlock(*block.hash()))? {
             // Because we know we will remove blocks we can't recover, this will be a destructive rewind, so we
@@ -1964,7 +1995,7 @@ fn rewind_to_height<T: BlockchainBackend>(
             let header = fetch_header(db, last_block_height - h - steps_back)?;
             // Although we do not have this full block, this method  will remove all remaining data that is linked to
             // the specific header hash
-            txn.delete_tip_block(header.hash(), smt.clone());
+            txn.delete_tip_block(header.hash());
             db.write(txn)?;
         }
     }
@@ -1975,7 +2006,6 @@ fn rewind_to_height<T: BlockchainBackend>(
 fn rewind_to_hash<T: BlockchainBackend>(
     db: &mut T,
     block_hash: BlockHash,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
     let block_hash_hex = block_hash.to_hex();
     let target_header = fetch_header_by_block_hash(&*db, block_hash)?.ok_or(ChainStorageError::ValueNotFound {
@@ -1983,7 +2013,7 @@ fn rewind_to_hash<T: BlockchainBackend>(
         field: "block_hash",
         value: block_hash_hex,
     })?;
-    rewind_to_height(db, target_header.height, smt)
+    rewind_to_height(db, target_header.height)
 }
 
 // Checks whether we should add the block as an orphan. If it is the case, the orphan block is added and the chain
@@ -1996,21 +2026,13 @@ fn handle_possible_reorg<T: BlockchainBackend>(
     header_validator: &dyn HeaderChainLinkedValidator<T>,
     chain_strength_comparer: &dyn ChainStrengthComparer,
     candidate_block: Arc<Block>,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<BlockAddResult, ChainStorageError> {
     let timer = Instant::now();
     let height = candidate_block.header.height;
     let hash = candidate_block.header.hash();
     insert_orphan_and_find_new_tips(db, candidate_block, header_validator, consensus_manager)?;
     let after_orphans = timer.elapsed();
-    let res = swap_to_highest_pow_chain(
-        db,
-        config,
-        block_validator,
-        chain_strength_comparer,
-        consensus_manager,
-        smt,
-    );
+    let res = swap_to_highest_pow_chain(db, config, block_validator, chain_strength_comparer, consensus_manager);
     trace!(
         target: LOG_TARGET,
         "[handle_possible_reorg] block #{}, insert_orphans in {:.2?}, swap_to_highest in {:.2?} '{}'",
@@ -2030,9 +2052,8 @@ fn reorganize_chain<T: BlockchainBackend>(
     fork_hash: HashOutput,
     new_chain_from_fork: &VecDeque<Arc<ChainBlock>>,
     consensus: &ConsensusManager,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
-    let removed_blocks = rewind_to_hash(backend, fork_hash, smt.clone())?;
+    let removed_blocks = rewind_to_hash(backend, fork_hash)?;
     debug!(
         target: LOG_TARGET,
         "Validate and add {} chain block(s) from block {}. Rewound blocks: [{}]",
@@ -2049,7 +2070,7 @@ fn reorganize_chain<T: BlockchainBackend>(
         let block_hash = *block.hash();
         txn.delete_orphan(block_hash);
         let chain_metadata = backend.fetch_chain_metadata()?;
-        if let Err(e) = block_validator.validate_body_with_metadata(backend, block, &chain_metadata, smt.clone()) {
+        if let Err(e) = block_validator.validate_body_with_metadata(backend, block, &chain_metadata) {
             warn!(
                 target: LOG_TARGET,
                 "Orphan block {} ({}) failed validation during chain reorg: {:?}",
@@ -2068,21 +2089,11 @@ fn reorganize_chain<T: BlockchainBackend>(
             backend.write(txn)?;
 
             info!(target: LOG_TARGET, "Restoring previous chain after failed reorg.");
-            restore_reorged_chain(backend, fork_hash, removed_blocks, consensus, smt.clone())?;
+            restore_reorged_chain(backend, fork_hash, removed_blocks, consensus)?;
             return Err(e.into());
         }
 
-        if let Err(e) = insert_best_block(&mut txn, block.clone(), consensus, smt.clone()) {
-            let mut write_smt = smt.write().map_err(|e| {
-                error!(
-                    target: LOG_TARGET,
-                    "reorganize_chain could not get a write lock on the smt. {:?}", e
-                );
-                ChainStorageError::AccessError("write lock on smt".into())
-            })?;
-            *write_smt = backend.calculate_tip_smt()?;
-            return Err(e);
-        }
+        insert_best_block(&mut txn, block.clone(), consensus)?;
         // Failed to store the block - this should typically never happen unless there is a bug in the validator
         // (e.g. does not catch a double spend). In any case, we still need to restore the chain to a
         // good state before returning.
@@ -2092,7 +2103,7 @@ fn reorganize_chain<T: BlockchainBackend>(
                 "Failed to commit reorg chain: {:?}. Restoring last chain.", e
             );
 
-            restore_reorged_chain(backend, fork_hash, removed_blocks, consensus, smt)?;
+            restore_reorged_chain(backend, fork_hash, removed_blocks, consensus)?;
             return Err(e);
         }
     }
@@ -2106,13 +2117,12 @@ fn swap_to_highest_pow_chain<T: BlockchainBackend>(
     block_validator: &dyn CandidateBlockValidator<T>,
     chain_strength_comparer: &dyn ChainStrengthComparer,
     consensus: &ConsensusManager,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<BlockAddResult, ChainStorageError> {
     let metadata = db.fetch_chain_metadata()?;
     // lets clear out all remaining headers that dont have a matching block
     // rewind to height will first delete the headers, then try delete from blocks, if we call this to the current
     // height it will only trim the extra headers with no blocks
-    rewind_to_height(db, metadata.best_block_height(), smt.clone())?;
+    rewind_to_height(db, metadata.best_block_height())?;
     let strongest_orphan_tips = db.fetch_strongest_orphan_chain_tips()?;
     if strongest_orphan_tips.is_empty() {
         // we have no orphan chain tips, we have trimmed remaining headers, we are on the best tip we have, so lets
@@ -2163,7 +2173,7 @@ fn swap_to_highest_pow_chain<T: BlockchainBackend>(
         .prev_hash;
 
     let num_added_blocks = reorg_chain.len();
-    let removed_blocks = reorganize_chain(db, block_validator, fork_hash, &reorg_chain, consensus, smt)?;
+    let removed_blocks = reorganize_chain(db, block_validator, fork_hash, &reorg_chain, consensus)?;
     let num_removed_blocks = removed_blocks.len();
 
     // reorg is required when any blocks are removed or more than one are added
@@ -2216,9 +2226,8 @@ fn restore_reorged_chain<T: BlockchainBackend>(
     to_hash: HashOutput,
     previous_chain: Vec<Arc<ChainBlock>>,
     consensus: &ConsensusManager,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> Result<(), ChainStorageError> {
-    let invalid_chain = rewind_to_hash(db, to_hash, smt.clone())?;
+    let invalid_chain = rewind_to_hash(db, to_hash)?;
     debug!(
         target: LOG_TARGET,
         "Removed {} blocks during chain restore: {:?}.",
@@ -2232,7 +2241,7 @@ fn restore_reorged_chain<T: BlockchainBackend>(
 
     for block in previous_chain.into_iter().rev() {
         txn.delete_orphan(block.accumulated_data().hash);
-        insert_best_block(&mut txn, block, consensus, smt.clone())?;
+        insert_best_block(&mut txn, block, consensus)?;
     }
     db.write(txn)?;
     Ok(())
@@ -2628,7 +2637,6 @@ impl<T> Clone for BlockchainDatabase<T> {
             consensus_manager: self.consensus_manager.clone(),
             difficulty_calculator: self.difficulty_calculator.clone(),
             disable_add_block_flag: self.disable_add_block_flag.clone(),
-            smt: self.smt.clone(),
         }
     }
 }
@@ -2676,6 +2684,7 @@ mod test {
                 create_new_blockchain,
                 create_orphan_chain,
                 create_test_blockchain_db,
+                fetch_tip_output_smt,
                 update_block_and_smt,
                 TempDatabase,
             },
@@ -2727,7 +2736,7 @@ mod test {
                 .try_into_chain_block()
                 .map(Arc::new)
                 .unwrap();
-            let mut smt = db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&db);
             let (_, chain) = create_orphan_chain(
                 &db,
                 &[("A->GB", 1, 120), ("B->A", 1, 120), ("C->B", 1, 120)],
@@ -2757,7 +2766,7 @@ mod test {
             // Create reorg chain
             // we only need a smt, this one will not be technically correct, but due to the use of mockvalidators(true),
             // they will pass all mr tests
-            let mut smt = db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&db);
             let fork_root = mainchain.get("B").unwrap().clone();
             let (_, reorg_chain) = create_orphan_chain(
                 &db,
@@ -2803,7 +2812,7 @@ mod test {
                 .try_into_chain_block()
                 .map(Arc::new)
                 .unwrap();
-            let mut smt = db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&db);
             let (_, chain) = create_chained_blocks(&[("A->GB", 1u64, 120u64)], genesis_block, &mut smt).await;
             let block = chain.get("A").unwrap().clone();
             let mut access = db.db_write_access().unwrap();
@@ -2821,7 +2830,7 @@ mod test {
             let (_, main_chain) = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120)]).await;
 
             let block_b = main_chain.get("B").unwrap().clone();
-            let mut smt = db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&db);
             let (_, orphan_chain) = create_chained_blocks(
                 &[("C2->GB", 1, 120), ("D2->C2", 1, 120), ("E2->D2", 1, 120)],
                 block_b,
@@ -2849,7 +2858,7 @@ mod test {
             let (_, main_chain) = create_main_chain(&db, &[("A->GB", 1, 120)]).await;
 
             let fork_root = main_chain.get("A").unwrap().clone();
-            let mut smt = db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&db);
             let (_, orphan_chain) = create_chained_blocks(&[("B2->GB", 1, 120)], fork_root, &mut smt).await;
             let mut access = db.db_write_access().unwrap();
 
@@ -2889,7 +2898,7 @@ mod test {
             let fork_root_1 = main_chain.get("A").unwrap().clone();
             // we only need a smt, this one will not be technically correct, but due to the use of mockvalidators(true),
             // they will pass all mr tests
-            let mut smt = db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&db);
 
             let (_, orphan_chain_1) = create_chained_blocks(
                 &[("B2->GB", 1, 120), ("C2->B2", 1, 120), ("D2->C2", 1, 120)],
@@ -2975,7 +2984,7 @@ mod test {
         #[tokio::test]
         async fn it_links_many_orphan_branches_to_main_chain() {
             let test = TestHarness::setup();
-            let mut smt = test.db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&test.db);
             let (_, main_chain) =
                 create_main_chain(&test.db, block_specs!(["1a->GB"], ["2a->1a"], ["3a->2a"], ["4a->3a"])).await;
             let genesis = main_chain.get("GB").unwrap().clone();
@@ -3071,7 +3080,7 @@ mod test {
             let test = TestHarness::setup();
             // This test assumes a MTC of 11
             assert_eq!(test.consensus.consensus_constants(0).median_timestamp_count(), 11);
-            let mut smt = test.db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&test.db);
             let (_, main_chain) = create_main_chain(
                 &test.db,
                 block_specs!(
@@ -3163,7 +3172,7 @@ mod test {
         #[tokio::test]
         async fn it_errors_if_reorging_to_an_invalid_height() {
             let test = TestHarness::setup();
-            let mut smt = test.db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&test.db);
             let (_, main_chain) =
                 create_main_chain(&test.db, block_specs!(["1a->GB"], ["2a->1a"], ["3a->2a"], ["4a->3a"])).await;
 
@@ -3185,7 +3194,7 @@ mod test {
         #[tokio::test]
         async fn it_allows_orphan_blocks_with_any_height() {
             let test = TestHarness::setup();
-            let mut smt = test.db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&test.db);
             let (_, main_chain) = create_main_chain(
                 &test.db,
                 block_specs!(["1a->GB", difficulty: Difficulty::from_u64(2).unwrap()]),
@@ -3303,7 +3312,7 @@ mod test {
     #[tokio::test]
     async fn test_handle_possible_reorg_case6_orphan_chain_link() {
         let db = create_new_blockchain();
-        let mut smt = db.smt_read_access().unwrap().clone();
+        let mut smt = fetch_tip_output_smt(&db);
         let (_, mainchain) = create_main_chain(&db, &[
             ("A->GB", 1, 120),
             ("B->A", 1, 120),
@@ -3329,7 +3338,6 @@ mod test {
 
         // Add true orphans
         let mut access = db.db_write_access().unwrap();
-        let smt = db.smt().clone();
         let result = handle_possible_reorg(
             &mut *access,
             &Default::default(),
@@ -3338,13 +3346,11 @@ mod test {
             &mock_validator,
             &*chain_strength_comparer,
             reorg_chain.get("E2").unwrap().to_arc_block(),
-            smt,
         )
         .unwrap();
         result.assert_orphaned();
 
         // Test adding a duplicate orphan
-        let smt = db.smt().clone();
         let result = handle_possible_reorg(
             &mut *access,
             &Default::default(),
@@ -3353,12 +3359,9 @@ mod test {
             &mock_validator,
             &*chain_strength_comparer,
             reorg_chain.get("E2").unwrap().to_arc_block(),
-            smt,
         )
         .unwrap();
         result.assert_orphaned();
-
-        let smt = db.smt().clone();
         let result = handle_possible_reorg(
             &mut *access,
             &Default::default(),
@@ -3367,15 +3370,12 @@ mod test {
             &mock_validator,
             &*chain_strength_comparer,
             reorg_chain.get("D2").unwrap().to_arc_block(),
-            smt,
         )
         .unwrap();
         result.assert_orphaned();
 
         let tip = access.fetch_last_header().unwrap();
         assert_eq!(&tip, mainchain.get("D").unwrap().header());
-
-        let smt = db.smt().clone();
         let result = handle_possible_reorg(
             &mut *access,
             &Default::default(),
@@ -3384,7 +3384,6 @@ mod test {
             &mock_validator,
             &*chain_strength_comparer,
             reorg_chain.get("C2").unwrap().to_arc_block(),
-            smt,
         )
         .unwrap();
         result.assert_reorg(3, 2);
@@ -3409,14 +3408,13 @@ mod test {
         let chain_strength_comparer = strongest_chain().by_sha3x_difficulty().build();
         // we only need a smt, this one will not be technically correct, but due to the use of mockvalidators(true),
         // they will pass all mr tests
-        let mut smt = db.smt_read_access().unwrap().clone();
+        let mut smt = fetch_tip_output_smt(&db);
         let fork_block = mainchain.get("C").unwrap().clone();
         let (_, reorg_chain) =
             create_chained_blocks(&[("D2->GB", 1, 120), ("E2->D2", 2, 120)], fork_block, &mut smt).await;
 
         // Add true orphans
         let mut access = db.db_write_access().unwrap();
-        let smt = db.smt().clone();
         let result = handle_possible_reorg(
             &mut *access,
             &Default::default(),
@@ -3425,12 +3423,9 @@ mod test {
             &mock_validator,
             &*chain_strength_comparer,
             reorg_chain.get("E2").unwrap().to_arc_block(),
-            smt,
         )
         .unwrap();
         result.assert_orphaned();
-
-        let smt = db.smt().clone();
         let _error = handle_possible_reorg(
             &mut *access,
             &Default::default(),
@@ -3439,7 +3434,6 @@ mod test {
             &mock_validator,
             &*chain_strength_comparer,
             reorg_chain.get("D2").unwrap().to_arc_block(),
-            smt,
         )
         .unwrap_err();
 
@@ -3673,7 +3667,6 @@ mod test {
 
         pub fn handle_possible_reorg(&self, block: Arc<Block>) -> Result<BlockAddResult, ChainStorageError> {
             let mut access = self.db_write_access();
-            let smt = self.db.smt().clone();
             handle_possible_reorg(
                 &mut *access,
                 &self.config,
@@ -3682,7 +3675,6 @@ mod test {
                 &*self.header_validator,
                 &*self.chain_strength_comparer,
                 block,
-                smt,
             )
         }
     }
@@ -3700,7 +3692,7 @@ mod test {
             .map(Arc::new)
             .unwrap();
         let (block_names, chain) = {
-            let mut smt = test.db.smt_read_access().unwrap().clone();
+            let mut smt = fetch_tip_output_smt(&test.db);
             create_chained_blocks(blocks, genesis_block, &mut smt).await
         };
 
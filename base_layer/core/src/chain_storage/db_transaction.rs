@@ -23,7 +23,7 @@
 use std::{
     fmt,
     fmt::{Display, Error, Formatter},
-    sync::{Arc, RwLock},
+    sync::Arc,
 };
 
 use primitive_types::U256;
@@ -32,9 +32,8 @@ use tari_utilities::hex::Hex;
 
 use crate::{
     blocks::{Block, BlockHeader, BlockHeaderAccumulatedData, ChainBlock, ChainHeader, UpdateBlockAccumulatedData},
-    chain_storage::{error::ChainStorageError, HorizonData, Reorg},
+    chain_storage::{error::ChainStorageError, HorizonData, OutputSmtChange, Reorg},
     transactions::transaction_components::{OutputType, TransactionKernel, TransactionOutput},
-    OutputSmt,
 };
 
 #[derive(Debug)]
@@ -81,8 +80,8 @@ impl DbTransaction {
     }
 
     /// Delete a block
-    pub fn delete_tip_block(&mut self, block_hash: HashOutput, smt: Arc<RwLock<OutputSmt>>) -> &mut Self {
-        self.operations.push(WriteOperation::DeleteTipBlock(block_hash, smt));
+    pub fn delete_tip_block(&mut self, block_hash: HashOutput) -> &mut Self {
+        self.operations.push(WriteOperation::DeleteTipBlock(block_hash));
         self
     }
 
@@ -171,8 +170,14 @@ impl DbTransaction {
     /// Add the BlockHeader and contents of a `Block` (i.e. inputs, outputs and kernels) to the database.
     /// If the `BlockHeader` already exists, then just the contents are updated along with the relevant accumulated
     /// data.
-    pub fn insert_tip_block_body(&mut self, block: Arc<ChainBlock>, smt: Arc<RwLock<OutputSmt>>) -> &mut Self {
-        self.operations.push(WriteOperation::InsertTipBlockBody { block, smt });
+    pub fn insert_tip_block_body(&mut self, block: Arc<ChainBlock>) -> &mut Self {
+        self.operations.push(WriteOperation::InsertTipBlockBody { block });
+        self
+    }
+
+    /// Applies the changes, in order, to the stored output SMT
+    pub fn update_output_smt(&mut self, changes: Vec<OutputSmtChange>) -> &mut Self {
+        self.operations.push(WriteOperation::UpdateOutputSmt(changes));
         self
     }
 
@@ -291,7 +296,6 @@ pub enum WriteOperation {
     },
     InsertTipBlockBody {
         block: Arc<ChainBlock>,
-        smt: Arc<RwLock<OutputSmt>>,
     },
     InsertKernel {
         header_hash: HashOutput,
@@ -311,7 +315,7 @@ pub enum WriteOperation {
     },
     DeleteHeader(u64),
     DeleteOrphan(HashOutput),
-    DeleteTipBlock(HashOutput, Arc<RwLock<OutputSmt>>),
+    DeleteTipBlock(HashOutput),
     DeleteOrphanChainTip(HashOutput),
     InsertOrphanChainTip(HashOutput, U256),
     InsertMoneroSeedHeight(Vec<u8>, u64),
@@ -333,6 +337,7 @@ pub enum WriteOperation {
     DeleteAllInputsInBlock {
         block_hash: BlockHash,
     },
+    UpdateOutputSmt(Vec<OutputSmtChange>),
     SetAccumulatedDataForOrphan(BlockHeaderAccumulatedData),
     SetBestBlock {
         height: u64,
@@ -368,7 +373,7 @@ impl fmt::Display for WriteOperation {
             InsertChainHeader { header } => {
                 write!(f, "InsertChainHeader(#{} {})", header.height(), header.hash())
             },
-            InsertTipBlockBody { block, smt: _ } => write!(
+            InsertTipBlockBody { block } => write!(
                 f,
                 "InsertTipBlockBody({}, {})",
                 block.accumulated_data().hash,
@@ -401,7 +406,7 @@ impl fmt::Display for WriteOperation {
             InsertOrphanChainTip(hash, total_accumulated_difficulty) => {
                 write!(f, "InsertOrphanChainTip({}, {})", hash, total_accumulated_difficulty)
             },
-            DeleteTipBlock(hash, _) => write!(f, "DeleteTipBlock({})", hash),
+            DeleteTipBlock(hash) => write!(f, "DeleteTipBlock({})", hash),
             InsertMoneroSeedHeight(data, height) => {
                 write!(f, "Insert Monero seed string {} for height: {}", data.to_hex(), height)
             },
@@ -423,6 +428,7 @@ impl fmt::Display for WriteOperation {
             ),
             DeleteAllKernelsInBlock { block_hash } => write!(f, "Delete kernels in block {}", block_hash),
             DeleteAllInputsInBlock { block_hash } => write!(f, "Delete outputs in block {}", block_hash),
+            UpdateOutputSmt(changes) => write!(f, "Update output SMT with {} change(s)", changes.len()),
             SetAccumulatedDataForOrphan(accumulated_data) => {
                 write!(f, "Set accumulated data for orphan {}", accumulated_data)
             },
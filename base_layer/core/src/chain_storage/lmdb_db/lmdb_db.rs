@@ -20,17 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    cmp::max,
-    convert::TryFrom,
-    fmt,
-    fs,
-    fs::File,
-    ops::Deref,
-    path::Path,
-    sync::{Arc, RwLock},
-    time::Instant,
-};
+use std::{cmp::max, convert::TryFrom, fmt, fs, fs::File, ops::Deref, path::Path, sync::Arc, time::Instant};
 
 use fs2::FileExt;
 use lmdb_zero::{open, ConstTransaction, Database, Environment, ReadTransaction, WriteTransaction};
@@ -42,7 +32,7 @@ use tari_common_types::{
     epoch::VnEpoch,
     types::{BlockHash, Commitment, FixedHash, HashOutput, PublicKey, Signature},
 };
-use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, ValueHash};
+use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, OverlayNodeStore, ValueHash};
 use tari_storage::lmdb_store::{db, LMDBBuilder, LMDBConfig, LMDBStore, BYTES_PER_MB};
 use tari_utilities::{
     hex::{to_hex, Hex},
@@ -54,6 +44,7 @@ use crate::{
     blocks::{
         Block,
         BlockAccumulatedData,
+        BlockFilter,
         BlockHeader,
         BlockHeaderAccumulatedData,
         ChainBlock,
@@ -84,12 +75,14 @@ use crate::{
                 lmdb_len,
                 lmdb_replace,
             },
+            smt_node_store::{LmdbSmtNodeReader, LmdbSmtNodeStore},
             validator_node_store::ValidatorNodeStore,
             TransactionInputRowData,
             TransactionInputRowDataRef,
             TransactionKernelRowData,
             TransactionOutputRowData,
         },
+        output_smt::{apply_output_smt_changes, output_smt_proof, output_smt_root},
         stats::DbTotalSizeStats,
         utxo_mined_info::OutputMinedInfo,
         BlockchainBackend,
@@ -99,12 +92,12 @@ use crate::{
         HorizonData,
         InputMinedInfo,
         MmrTree,
+        OutputSmtChange,
         Reorg,
         TemplateRegistrationEntry,
         ValidatorNodeEntry,
     },
     consensus::{ConsensusConstants, ConsensusManager},
-    output_mr_hash_from_smt,
     transactions::{
         aggregated_body::AggregateBody,
         transaction_components::{
@@ -116,7 +109,8 @@ use crate::{
             ValidatorNodeRegistration,
         },
     },
-    OutputSmt,
+    OutputSmtProof,
+    PersistentOutputSmt,
     PrunedKernelMmr,
 };
 
@@ -150,6 +144,12 @@ const LMDB_DB_REORGS: &str = "reorgs";
 const LMDB_DB_VALIDATOR_NODES: &str = "validator_nodes";
 const LMDB_DB_VALIDATOR_NODES_MAPPING: &str = "validator_nodes_mapping";
 const LMDB_DB_TEMPLATE_REGISTRATIONS: &str = "template_registrations";
+const LMDB_DB_SMT_NODES: &str = "smt_nodes";
+const LMDB_DB_BLOCK_FILTERS: &str = "block_filters";
+
+/// The version of the output SMT stored in `smt_nodes`. Changing it rebuilds the stored tree when the database is
+/// opened.
+const OUTPUT_SMT_VERSION: u64 = 1;
 
 /// HeaderHash(32), mmr_pos(8), hash(32)
 type KernelKey = CompositeKey<72>;
@@ -199,6 +199,8 @@ pub fn create_lmdb_database<P: AsRef<Path>>(
         .add_database(LMDB_DB_VALIDATOR_NODES, flags)
         .add_database(LMDB_DB_VALIDATOR_NODES_MAPPING, flags)
         .add_database(LMDB_DB_TEMPLATE_REGISTRATIONS, flags | db::DUPSORT)
+        .add_database(LMDB_DB_SMT_NODES, flags)
+        .add_database(LMDB_DB_BLOCK_FILTERS, flags)
         .build()
         .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))?;
     debug!(target: LOG_TARGET, "LMDB database creation successful");
@@ -261,6 +263,10 @@ pub struct LMDBDatabase {
     validator_nodes_mapping: DatabaseRef,
     /// Maps CodeTemplateRegistration <block_height, hash> -> TemplateRegistration
     template_registrations: DatabaseRef,
+    /// Maps NodePosition -> StoredNode for the output SMT at the best block
+    smt_nodes_db: DatabaseRef,
+    /// Maps BlockHash -> BlockFilter
+    block_filters_db: DatabaseRef,
     _file_lock: Arc<File>,
     consensus_manager: ConsensusManager,
 }
@@ -300,6 +306,8 @@ impl LMDBDatabase {
             validator_nodes: get_database(store, LMDB_DB_VALIDATOR_NODES)?,
             validator_nodes_mapping: get_database(store, LMDB_DB_VALIDATOR_NODES_MAPPING)?,
             template_registrations: get_database(store, LMDB_DB_TEMPLATE_REGISTRATIONS)?,
+            smt_nodes_db: get_database(store, LMDB_DB_SMT_NODES)?,
+            block_filters_db: get_database(store, LMDB_DB_BLOCK_FILTERS)?,
             env,
             env_config: store.env_config(),
             _file_lock: Arc::new(file_lock),
@@ -307,6 +315,7 @@ impl LMDBDatabase {
         };
 
         run_migrations(&db)?;
+        db.rebuild_output_smt_if_stale()?;
 
         Ok(db)
     }
@@ -337,8 +346,8 @@ impl LMDBDatabase {
                 InsertChainHeader { header } => {
                     self.insert_header(&write_txn, header.header(), header.accumulated_data())?;
                 },
-                InsertTipBlockBody { block, smt } => {
-                    self.insert_tip_block_body(&write_txn, block.header(), block.block().body.clone(), smt.clone())?;
+                InsertTipBlockBody { block } => {
+                    self.insert_tip_block_body(&write_txn, block.header(), block.block().body.clone())?;
                 },
                 InsertKernel {
                     header_hash,
@@ -381,8 +390,11 @@ impl LMDBDatabase {
                         "orphan_chain_tips_db",
                     )?;
                 },
-                DeleteTipBlock(hash, smt) => {
-                    self.delete_tip_block_body(&write_txn, hash, smt.clone())?;
+                DeleteTipBlock(hash) => {
+                    self.delete_tip_block_body(&write_txn, hash)?;
+                },
+                UpdateOutputSmt(changes) => {
+                    apply_output_smt_changes(&mut self.persisted_output_smt(&write_txn), changes)?;
                 },
                 InsertMoneroSeedHeight(data, height) => {
                     self.insert_monero_seed_height(&write_txn, data, *height)?;
@@ -495,7 +507,7 @@ impl LMDBDatabase {
         Ok(())
     }
 
-    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 26] {
+    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 28] {
         [
             (LMDB_DB_METADATA, &self.metadata_db),
             (LMDB_DB_HEADERS, &self.headers_db),
@@ -529,6 +541,8 @@ impl LMDBDatabase {
             (LMDB_DB_VALIDATOR_NODES, &self.validator_nodes),
             (LMDB_DB_VALIDATOR_NODES_MAPPING, &self.validator_nodes_mapping),
             (LMDB_DB_TEMPLATE_REGISTRATIONS, &self.template_registrations),
+            (LMDB_DB_SMT_NODES, &self.smt_nodes_db),
+            (LMDB_DB_BLOCK_FILTERS, &self.block_filters_db),
         ]
     }
 
@@ -885,7 +899,6 @@ impl LMDBDatabase {
         &self,
         write_txn: &WriteTransaction<'_>,
         block_hash: &HashOutput,
-        smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<(), ChainStorageError> {
         let hash_hex = block_hash.to_hex();
         debug!(target: LOG_TARGET, "Deleting block `{}`", hash_hex);
@@ -909,18 +922,11 @@ impl LMDBDatabase {
             "block_accumulated_data_db",
         )?;
 
-        let mut output_smt = smt.write().map_err(|e| {
-            error!(
-                target: LOG_TARGET,
-                "delete_tip_block_body could not get a write lock on the smt. {:?}", e
-            );
-            ChainStorageError::AccessError("write lock on smt".into())
-        })?;
-
+        let mut output_smt = self.persisted_output_smt(write_txn);
         self.delete_block_inputs_outputs(write_txn, block_hash.as_slice(), &mut output_smt)?;
 
         let new_tip_header = self.fetch_chain_header_by_height(prev_height)?;
-        let root = output_mr_hash_from_smt(&mut output_smt)?;
+        let root = FixedHash::try_from(output_smt.hash()?.as_slice())?;
         if root != new_tip_header.header().output_mr {
             error!(
                 target: LOG_TARGET,
@@ -934,6 +940,15 @@ impl LMDBDatabase {
         }
 
         self.delete_block_kernels(write_txn, block_hash.as_slice())?;
+        // Blocks inserted before filters were indexed have no filter to delete
+        if lmdb_exists(write_txn, &self.block_filters_db, block_hash.as_slice())? {
+            lmdb_delete(
+                write_txn,
+                &self.block_filters_db,
+                block_hash.as_slice(),
+                "block_filters_db",
+            )?;
+        }
 
         Ok(())
     }
@@ -942,7 +957,7 @@ impl LMDBDatabase {
         &self,
         txn: &WriteTransaction<'_>,
         block_hash: &[u8],
-        output_smt: &mut OutputSmt,
+        output_smt: &mut PersistentOutputSmt<LmdbSmtNodeStore<'_, '_>>,
     ) -> Result<(), ChainStorageError> {
         let output_rows = lmdb_delete_keys_starting_with::<TransactionOutputRowData>(txn, &self.utxos_db, block_hash)?;
         debug!(target: LOG_TARGET, "Deleted {} outputs...", output_rows.len());
@@ -1172,15 +1187,7 @@ impl LMDBDatabase {
         txn: &WriteTransaction<'_>,
         header: &BlockHeader,
         body: AggregateBody,
-        smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<(), ChainStorageError> {
-        let mut output_smt = smt.write().map_err(|e| {
-            error!(
-                target: LOG_TARGET,
-                "insert_tip_block_body could not get a write lock on the smt. {:?}", e
-            );
-            ChainStorageError::AccessError("write lock on smt".into())
-        })?;
         if self.fetch_block_accumulated_data(txn, header.height + 1)?.is_some() {
             return Err(ChainStorageError::InvalidOperation(format!(
                 "Attempted to insert block at height {} while next block already exists",
@@ -1213,7 +1220,16 @@ impl LMDBDatabase {
             )));
         }
 
+        lmdb_insert(
+            txn,
+            &self.block_filters_db,
+            block_hash.as_slice(),
+            &BlockFilter::from_outputs(&block_hash, body.outputs()),
+            "block_filters_db",
+        )?;
+
         let (inputs, outputs, kernels) = body.dissolve();
+        let mut output_smt = self.persisted_output_smt(txn);
 
         let data = if header.height == 0 {
             BlockAccumulatedData::default()
@@ -1345,6 +1361,13 @@ impl LMDBDatabase {
         Ok(())
     }
 
+    fn persisted_output_smt<'a, 'txn>(
+        &self,
+        txn: &'a WriteTransaction<'txn>,
+    ) -> PersistentOutputSmt<LmdbSmtNodeStore<'a, 'txn>> {
+        PersistentOutputSmt::new(LmdbSmtNodeStore::new(txn, self.smt_nodes_db.clone()))
+    }
+
     fn validator_node_store<'a, T: Deref<Target = ConstTransaction<'a>>>(
         &'a self,
         txn: &'a T,
@@ -1736,9 +1759,9 @@ impl BlockchainBackend for LMDBDatabase {
             return Ok(());
         }
 
-        // Ensure there will be enough space in the database to insert the block and replace the SMT before it is
-        // attempted; this is more efficient than relying on an error if the LMDB environment map size was reached with
-        // the write operation, with cleanup, resize and re-try afterwards.
+        // Ensure there will be enough space in the database to insert the block and update the SMT nodes before it
+        // is attempted; this is more efficient than relying on an error if the LMDB environment map size was reached
+        // with the write operation, with cleanup, resize and re-try afterwards.
         let block_operations = txn.operations().iter().filter(|op| {
             matches!(op, WriteOperation::InsertOrphanBlock { .. }) ||
                 matches!(op, WriteOperation::InsertTipBlockBody { .. }) ||
@@ -2086,6 +2109,11 @@ impl BlockchainBackend for LMDBDatabase {
         lmdb_fetch_matching_after(&txn, &self.utxos_db, header_hash.as_slice())
     }
 
+    fn fetch_block_filter(&self, header_hash: &HashOutput) -> Result<Option<BlockFilter>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        lmdb_get(&txn, &self.block_filters_db, header_hash.as_slice())
+    }
+
     fn fetch_inputs_in_block(
         &self,
         previous_header_hash: &HashOutput,
@@ -2490,8 +2518,8 @@ impl BlockchainBackend for LMDBDatabase {
     ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError> {
         let txn = self.read_transaction()?;
         let mut result = vec![];
-        for _ in start_height..=end_height {
-            let height = start_height.to_le_bytes();
+        for height in start_height..=end_height {
+            let height = height.to_le_bytes();
             let mut cursor: KeyPrefixCursor<TemplateRegistrationEntry> =
                 lmdb_get_prefix_cursor(&txn, &self.template_registrations, &height)?;
             while let Some((_, val)) = cursor.next()? {
@@ -2501,41 +2529,130 @@ impl BlockchainBackend for LMDBDatabase {
         Ok(result)
     }
 
-    fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
-        let start = Instant::now();
-        let metadata = self.fetch_chain_metadata()?;
-        let mut smt = OutputSmt::new();
-        trace!(
-            target: LOG_TARGET,
-            "Calculating new smt at height: #{}",
-            metadata.pruned_height(),
-        );
+    fn calculate_output_smt_root(&self, changes: &[OutputSmtChange]) -> Result<(FixedHash, u64), ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let store = LmdbSmtNodeReader::new(&txn, self.smt_nodes_db.clone());
+        let mut smt = PersistentOutputSmt::new(OverlayNodeStore::new(&store));
+        apply_output_smt_changes(&mut smt, changes)?;
+        output_smt_root(&smt)
+    }
+
+    fn output_smt_contains(&self, key: &NodeKey) -> Result<bool, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let smt = PersistentOutputSmt::new(LmdbSmtNodeReader::new(&txn, self.smt_nodes_db.clone()));
+        Ok(smt.contains(key)?)
+    }
+
+    fn fetch_output_smt_proofs(
+        &self,
+        changes: &[OutputSmtChange],
+        leaves: &[(NodeKey, ValueHash)],
+    ) -> Result<Vec<Option<OutputSmtProof>>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let store = LmdbSmtNodeReader::new(&txn, self.smt_nodes_db.clone());
+        let mut smt = PersistentOutputSmt::new(OverlayNodeStore::new(&store));
+        apply_output_smt_changes(&mut smt, changes)?;
+        leaves
+            .iter()
+            .map(|(key, value)| output_smt_proof(&smt, key, value))
+            .collect()
+    }
+}
+
+impl LMDBDatabase {
+    /// Collects the SMT leaves of all unspent, unburned outputs at the tip by walking every block in the chain.
+    fn fetch_tip_smt_leaves(&self, metadata: &ChainMetadata) -> Result<Vec<OutputSmtChange>, ChainStorageError> {
+        let mut leaves = Vec::new();
         for height in 0..=metadata.best_block_height() {
             let header = self.fetch_chain_header_by_height(height)?;
             let outputs =
                 self.fetch_outputs_in_block_with_spend_state(header.hash(), Some(metadata.best_block_hash()))?;
-            for output in outputs {
-                if !output.1 && !output.0.is_burned() {
-                    let smt_key = NodeKey::try_from(output.0.commitment.as_bytes())?;
-                    let smt_node = ValueHash::try_from(output.0.smt_hash(header.header().height).as_slice())?;
-                    if let Err(e) = smt.insert(smt_key, smt_node) {
-                        error!(
-                            target: LOG_TARGET,
-                            "Output commitment({}) already in SMT",
-                            output.0.commitment.to_hex(),
-                        );
-                        return Err(e.into());
-                    }
+            for (output, spent) in outputs {
+                if !spent && !output.is_burned() {
+                    leaves.push(OutputSmtChange::insert_output(&output, height)?);
                 }
             }
         }
-        trace!(
+        Ok(leaves)
+    }
+
+    /// Rebuilds the output SMT from the UTXO set if it was stored by another version, or if its root does not match
+    /// the `output_mr` of the best block, e.g. on a database created before the SMT nodes were stored.
+    fn rebuild_output_smt_if_stale(&self) -> Result<(), ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let version = fetch_smt_version(&txn, &self.metadata_db)?;
+        let best_block = match lmdb_get(&txn, &self.metadata_db, &MetadataKey::BestBlock.as_u32())? {
+            Some(MetadataValue::BestBlock(hash)) => hash,
+            _ => {
+                drop(txn);
+                // A new database, the tree is built as blocks are added
+                if version != Some(OUTPUT_SMT_VERSION) {
+                    let txn = self.write_transaction()?;
+                    self.set_metadata(
+                        &txn,
+                        MetadataKey::SmtVersion,
+                        &MetadataValue::SmtVersion(OUTPUT_SMT_VERSION),
+                    )?;
+                    txn.commit()?;
+                }
+                return Ok(());
+            },
+        };
+        let tip_height =
+            self.fetch_height_from_hash(&txn, &best_block)
+                .or_not_found("Block", "hash", best_block.to_hex())?;
+        let tip_header: BlockHeader = lmdb_get(&txn, &self.headers_db, &tip_height).or_not_found(
+            "BlockHeader",
+            "height",
+            tip_height.to_string(),
+        )?;
+        let smt = PersistentOutputSmt::new(LmdbSmtNodeReader::new(&txn, self.smt_nodes_db.clone()));
+        let (root, _) = output_smt_root(&smt)?;
+        if version == Some(OUTPUT_SMT_VERSION) && root == tip_header.output_mr {
+            return Ok(());
+        }
+        drop(txn);
+
+        let start = Instant::now();
+        info!(
             target: LOG_TARGET,
-            "Finished calculating new smt (size: {}), took: {:.2?}",
-            smt.size(),
+            "Rebuilding the output SMT at block {} (stored version: {:?}, root: {})",
+            best_block.to_hex(),
+            version,
+            root.to_hex()
+        );
+        let metadata = self.fetch_chain_metadata()?;
+        let changes = self.fetch_tip_smt_leaves(&metadata)?;
+        let txn = self.write_transaction()?;
+        lmdb_clear(&txn, &self.smt_nodes_db)?;
+        let mut smt = self.persisted_output_smt(&txn);
+        apply_output_smt_changes(&mut smt, &changes)?;
+        let (root, _) = output_smt_root(&smt)?;
+        if root != tip_header.output_mr {
+            error!(
+                target: LOG_TARGET,
+                "Rebuilt output SMT root ({}) does not match the output_mr ({}) of block {}",
+                root.to_hex(),
+                tip_header.output_mr.to_hex(),
+                best_block.to_hex()
+            );
+            return Err(ChainStorageError::CorruptedDatabase(
+                "The output SMT rebuilt from the UTXO set does not match the best block".to_string(),
+            ));
+        }
+        self.set_metadata(
+            &txn,
+            MetadataKey::SmtVersion,
+            &MetadataValue::SmtVersion(OUTPUT_SMT_VERSION),
+        )?;
+        txn.commit()?;
+        info!(
+            target: LOG_TARGET,
+            "Rebuilt the output SMT with {} leaves in {:.2?}",
+            changes.len(),
             start.elapsed()
         );
-        Ok(smt)
+        Ok(())
     }
 }
 
@@ -2606,6 +2723,20 @@ fn fetch_best_block(txn: &ConstTransaction<'_>, db: &Database) -> Result<BlockHa
     }
 }
 
+// Fetches the version of the stored output SMT from the provided metadata db, if one was stored.
+fn fetch_smt_version(txn: &ConstTransaction<'_>, db: &Database) -> Result<Option<u64>, ChainStorageError> {
+    let k = MetadataKey::SmtVersion;
+    let val: Option<MetadataValue> = lmdb_get(txn, db, &k.as_u32())?;
+    match val {
+        Some(MetadataValue::SmtVersion(version)) => Ok(Some(version)),
+        None => Ok(None),
+        Some(k) => Err(ChainStorageError::DataInconsistencyDetected {
+            function: "fetch_smt_version",
+            details: format!("Received incorrect value {:?} for key smt version", k),
+        }),
+    }
+}
+
 // Fetches the timestamp of the best block from the provided metadata db.
 fn fetch_best_block_timestamp(txn: &ConstTransaction<'_>, db: &Database) -> Result<u64, ChainStorageError> {
     let k = MetadataKey::BestBlockTimestamp;
@@ -2661,6 +2792,7 @@ enum MetadataKey {
     HorizonData,
     BestBlockTimestamp,
     MigrationVersion,
+    SmtVersion,
 }
 
 impl MetadataKey {
@@ -2681,6 +2813,7 @@ impl fmt::Display for MetadataKey {
             MetadataKey::HorizonData => write!(f, "Database info"),
             MetadataKey::BestBlockTimestamp => write!(f, "Chain tip block timestamp"),
             MetadataKey::MigrationVersion => write!(f, "Migration version"),
+            MetadataKey::SmtVersion => write!(f, "Output SMT version"),
         }
     }
 }
@@ -2696,6 +2829,7 @@ enum MetadataValue {
     HorizonData(HorizonData),
     BestBlockTimestamp(u64),
     MigrationVersion(u64),
+    SmtVersion(u64),
 }
 
 impl fmt::Display for MetadataValue {
@@ -2709,6 +2843,7 @@ impl fmt::Display for MetadataValue {
             MetadataValue::HorizonData(_) => write!(f, "Horizon data"),
             MetadataValue::BestBlockTimestamp(timestamp) => write!(f, "Chain tip block timestamp is {}", timestamp),
             MetadataValue::MigrationVersion(n) => write!(f, "Migration version {}", n),
+            MetadataValue::SmtVersion(n) => write!(f, "Output SMT version {}", n),
         }
     }
 }
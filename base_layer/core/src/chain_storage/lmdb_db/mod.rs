@@ -33,9 +33,10 @@ pub(crate) mod helpers;
 mod lmdb;
 #[allow(clippy::module_inception)]
 mod lmdb_db;
+mod smt_node_store;
 mod validator_node_store;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct TransactionOutputRowData {
     pub output: TransactionOutput,
     pub header_hash: HashOutput,
@@ -57,7 +58,7 @@ pub(crate) struct TransactionInputRowDataRef<'a> {
     pub hash: &'a HashOutput,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct TransactionInputRowData {
     pub input: TransactionInput,
     pub header_hash: HashOutput,
@@ -66,7 +67,7 @@ pub(crate) struct TransactionInputRowData {
     pub hash: HashOutput,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct TransactionKernelRowData {
     pub kernel: TransactionKernel,
     pub header_hash: HashOutput,
@@ -0,0 +1,91 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use lmdb_zero::{error::LmdbResultExt, ConstTransaction, WriteTransaction};
+use tari_mmr::sparse_merkle_tree::{NodePosition, SMTError, SmtNodeStore, StoredNode};
+use tari_storage::lmdb_store::DatabaseRef;
+
+use crate::chain_storage::lmdb_db::lmdb::{lmdb_get, lmdb_replace};
+
+/// Stores the nodes of the output SMT in an LMDB table, keyed by node position, as part of an open write transaction.
+pub struct LmdbSmtNodeStore<'a, 'txn> {
+    txn: &'a WriteTransaction<'txn>,
+    db: DatabaseRef,
+}
+
+impl<'a, 'txn> LmdbSmtNodeStore<'a, 'txn> {
+    pub fn new(txn: &'a WriteTransaction<'txn>, db: DatabaseRef) -> Self {
+        Self { txn, db }
+    }
+}
+
+impl SmtNodeStore for LmdbSmtNodeStore<'_, '_> {
+    fn get_node(&self, position: &NodePosition) -> Result<Option<StoredNode>, SMTError> {
+        lmdb_get(self.txn, &self.db, &position.to_bytes()[..]).map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+
+    fn put_node(&mut self, position: NodePosition, node: StoredNode) -> Result<(), SMTError> {
+        lmdb_replace(self.txn, &self.db, &position.to_bytes()[..], &node, None)
+            .map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+
+    fn delete_node(&mut self, position: &NodePosition) -> Result<(), SMTError> {
+        // Empty nodes are not stored, so a missing key is not an error
+        self.txn
+            .access()
+            .del_key(&self.db, &position.to_bytes()[..])
+            .to_opt()
+            .map(|_| ())
+            .map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+}
+
+/// Reads the nodes of the output SMT from an LMDB table as part of an open transaction. The nodes cannot be changed,
+/// wrap the store in an `OverlayNodeStore` to make changes that are not stored.
+pub struct LmdbSmtNodeReader<'a, 'txn> {
+    txn: &'a ConstTransaction<'txn>,
+    db: DatabaseRef,
+}
+
+impl<'a, 'txn> LmdbSmtNodeReader<'a, 'txn> {
+    pub fn new(txn: &'a ConstTransaction<'txn>, db: DatabaseRef) -> Self {
+        Self { txn, db }
+    }
+}
+
+impl SmtNodeStore for LmdbSmtNodeReader<'_, '_> {
+    fn get_node(&self, position: &NodePosition) -> Result<Option<StoredNode>, SMTError> {
+        lmdb_get(self.txn, &self.db, &position.to_bytes()[..]).map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+
+    fn put_node(&mut self, _position: NodePosition, _node: StoredNode) -> Result<(), SMTError> {
+        Err(SMTError::StoreError(
+            "The output SMT node reader is read-only".to_string(),
+        ))
+    }
+
+    fn delete_node(&mut self, _position: &NodePosition) -> Result<(), SMTError> {
+        Err(SMTError::StoreError(
+            "The output SMT node reader is read-only".to_string(),
+        ))
+    }
+}
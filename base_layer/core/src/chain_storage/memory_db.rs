@@ -0,0 +1,2014 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An in-memory implementation of [BlockchainBackend].
+//!
+//! Every LMDB table has an equivalent ordered map here, keyed in the same order as the LMDB composite keys, so that
+//! iteration order, duplicate-key errors and missing-key errors match the LMDB backend. This makes the backend
+//! suitable for tests and tooling that want the full `BlockchainDatabase` behaviour without touching disk.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap},
+    convert::TryFrom,
+    fmt::Debug,
+    mem,
+    ops::RangeInclusive,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Instant,
+};
+
+use log::*;
+use primitive_types::U256;
+use tari_common_types::{
+    chain_metadata::ChainMetadata,
+    epoch::VnEpoch,
+    types::{BlockHash, Commitment, FixedHash, HashOutput, PublicKey, Signature},
+};
+use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, NodePosition, OverlayNodeStore, StoredNode, ValueHash};
+use tari_utilities::{hex::Hex, ByteArray};
+
+use crate::{
+    blocks::{
+        Block,
+        BlockAccumulatedData,
+        BlockFilter,
+        BlockHeader,
+        BlockHeaderAccumulatedData,
+        ChainBlock,
+        ChainHeader,
+        UpdateBlockAccumulatedData,
+    },
+    chain_storage::{
+        db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation},
+        lmdb_db::{TransactionInputRowData, TransactionKernelRowData, TransactionOutputRowData},
+        output_smt::{apply_output_smt_changes, output_smt_proof, output_smt_root},
+        BlockchainBackend,
+        ChainStorageError,
+        ChainTipData,
+        DbBasicStats,
+        DbSize,
+        DbTotalSizeStats,
+        HorizonData,
+        InputMinedInfo,
+        MmrTree,
+        OutputMinedInfo,
+        OutputSmtChange,
+        Reorg,
+        TemplateRegistrationEntry,
+        ValidatorNodeEntry,
+    },
+    consensus::ConsensusManager,
+    transactions::{
+        aggregated_body::AggregateBody,
+        transaction_components::{
+            OutputType,
+            SpentOutput,
+            TransactionInput,
+            TransactionKernel,
+            TransactionOutput,
+            ValidatorNodeRegistration,
+        },
+    },
+    OutputSmtProof,
+    PersistentOutputSmt,
+    PrunedKernelMmr,
+};
+
+const LOG_TARGET: &str = "c::cs::memory_db";
+
+type ShardKey = [u8; 32];
+/// Header hash, output hash
+type OutputKey = (HashOutput, HashOutput);
+/// Header hash, input hash
+type InputKey = (HashOutput, HashOutput);
+/// Header hash, mmr position, kernel hash
+type KernelKey = (HashOutput, u64, HashOutput);
+/// Height, public key, commitment
+type ValidatorNodeKey = (u64, Vec<u8>, Vec<u8>);
+/// Public key, height, commitment
+type ValidatorNodeMappingKey = (Vec<u8>, u64, Vec<u8>);
+type OutputSmtNodes = HashMap<NodePosition, StoredNode>;
+
+pub fn create_memory_database(consensus_manager: ConsensusManager) -> MemoryDatabase {
+    MemoryDatabase::new(consensus_manager)
+}
+
+/// A volatile blockchain database that keeps the full chain state in memory.
+///
+/// Writes are atomic: a [DbTransaction] is applied to a copy of the state which replaces the current state only if
+/// every operation succeeds. Because each write copies the state, this backend is intended for short test chains and
+/// tooling rather than for syncing a full node.
+pub struct MemoryDatabase {
+    inner: RwLock<MemoryDbInner>,
+    consensus_manager: ConsensusManager,
+}
+
+impl MemoryDatabase {
+    pub fn new(consensus_manager: ConsensusManager) -> Self {
+        Self {
+            inner: RwLock::new(MemoryDbInner::default()),
+            consensus_manager,
+        }
+    }
+
+    fn read_access(&self) -> Result<RwLockReadGuard<'_, MemoryDbInner>, ChainStorageError> {
+        self.inner.read().map_err(|e| {
+            error!(target: LOG_TARGET, "Could not get a read lock on the memory database. {:?}", e);
+            ChainStorageError::AccessError("read lock on memory database".into())
+        })
+    }
+
+    fn write_access(&self) -> Result<RwLockWriteGuard<'_, MemoryDbInner>, ChainStorageError> {
+        self.inner.write().map_err(|e| {
+            error!(target: LOG_TARGET, "Could not get a write lock on the memory database. {:?}", e);
+            ChainStorageError::AccessError("write lock on memory database".into())
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct MemoryMetadata {
+    chain_height: Option<u64>,
+    best_block: Option<BlockHash>,
+    accumulated_work: Option<U256>,
+    best_block_timestamp: Option<u64>,
+    pruning_horizon: Option<u64>,
+    pruned_height: Option<u64>,
+    horizon_data: Option<HorizonData>,
+}
+
+impl MemoryMetadata {
+    fn len(&self) -> usize {
+        [
+            self.chain_height.is_some(),
+            self.best_block.is_some(),
+            self.accumulated_work.is_some(),
+            self.best_block_timestamp.is_some(),
+            self.pruning_horizon.is_some(),
+            self.pruned_height.is_some(),
+            self.horizon_data.is_some(),
+        ]
+        .iter()
+        .filter(|v| **v)
+        .count()
+    }
+}
+
+#[derive(Clone, Default)]
+struct MemoryDbInner {
+    metadata: MemoryMetadata,
+    /// Maps height -> BlockHeader
+    headers: BTreeMap<u64, BlockHeader>,
+    /// Maps height -> BlockHeaderAccumulatedData
+    header_accumulated_data: BTreeMap<u64, BlockHeaderAccumulatedData>,
+    /// Maps height -> BlockAccumulatedData
+    block_accumulated_data: BTreeMap<u64, BlockAccumulatedData>,
+    /// Maps block_hash -> height
+    block_hashes: BTreeMap<HashOutput, u64>,
+    /// Maps OutputKey -> TransactionOutputRowData
+    utxos: BTreeMap<OutputKey, TransactionOutputRowData>,
+    /// Maps InputKey -> TransactionInputRowData
+    inputs: BTreeMap<InputKey, TransactionInputRowData>,
+    /// Maps output hash -> OutputKey
+    txos_hash_to_index: BTreeMap<HashOutput, OutputKey>,
+    /// Maps KernelKey -> TransactionKernelRowData
+    kernels: BTreeMap<KernelKey, TransactionKernelRowData>,
+    /// Maps excess -> KernelKey
+    kernel_excess_index: BTreeMap<Vec<u8>, KernelKey>,
+    /// Maps excess_sig -> KernelKey
+    kernel_excess_sig_index: BTreeMap<Vec<u8>, KernelKey>,
+    /// Maps kernel_mmr_size -> height
+    kernel_mmr_size_index: BTreeMap<u64, u64>,
+    /// Maps commitment -> output_hash
+    utxo_commitment_index: BTreeMap<Vec<u8>, HashOutput>,
+    /// Maps output hash -> InputKey
+    deleted_txo_hash_to_header_index: BTreeMap<HashOutput, InputKey>,
+    /// Maps block_hash -> Block
+    orphans: BTreeMap<HashOutput, Block>,
+    /// Maps block_hash -> BlockHeaderAccumulatedData
+    orphan_header_accumulated_data: BTreeMap<HashOutput, BlockHeaderAccumulatedData>,
+    /// Stores the orphan tip block hashes
+    orphan_chain_tips: BTreeMap<HashOutput, ChainTipData>,
+    /// Maps parent_block_hash -> block_hashes
+    orphan_parent_map: BTreeMap<HashOutput, BTreeSet<HashOutput>>,
+    /// Maps randomx_seed -> height
+    monero_seed_height: BTreeMap<Vec<u8>, u64>,
+    /// Maps block_hash -> (height, reason)
+    bad_blocks: BTreeMap<HashOutput, (u64, String)>,
+    /// Maps epoch time -> Reorg
+    reorgs: BTreeMap<i64, Reorg>,
+    /// Maps ValidatorNodeKey -> ValidatorNodeEntry
+    validator_nodes: BTreeMap<ValidatorNodeKey, ValidatorNodeEntry>,
+    /// Maps ValidatorNodeMappingKey -> shard key
+    validator_nodes_mapping: BTreeMap<ValidatorNodeMappingKey, ShardKey>,
+    /// Maps (block_height, output_hash) -> TemplateRegistrationEntry
+    template_registrations: BTreeMap<(u64, HashOutput), TemplateRegistrationEntry>,
+    /// Maps NodePosition -> StoredNode for the output SMT at the best block
+    output_smt: OutputSmtNodes,
+}
+
+/// Inserts a value, failing with `KeyExists` if the key is already present (the equivalent of `lmdb_insert`).
+fn insert_new<K: Ord + Debug, V>(
+    map: &mut BTreeMap<K, V>,
+    key: K,
+    value: V,
+    table_name: &'static str,
+) -> Result<(), ChainStorageError> {
+    match map.entry(key) {
+        Entry::Occupied(entry) => Err(ChainStorageError::KeyExists {
+            table_name,
+            key: format!("{:?}", entry.key()),
+        }),
+        Entry::Vacant(entry) => {
+            entry.insert(value);
+            Ok(())
+        },
+    }
+}
+
+/// Removes a value, failing with `ValueNotFound` if the key is not present (the equivalent of `lmdb_delete`).
+fn remove_existing<K: Ord + Debug, V>(
+    map: &mut BTreeMap<K, V>,
+    key: &K,
+    table_name: &'static str,
+) -> Result<V, ChainStorageError> {
+    map.remove(key).ok_or_else(|| ChainStorageError::ValueNotFound {
+        entity: table_name,
+        field: "<unknown>",
+        value: format!("{:?}", key),
+    })
+}
+
+fn block_key_range(block_hash: &HashOutput) -> RangeInclusive<(HashOutput, HashOutput)> {
+    (*block_hash, FixedHash::zero())..=(*block_hash, FixedHash::from([0xff; 32]))
+}
+
+fn kernel_key_range(block_hash: &HashOutput) -> RangeInclusive<KernelKey> {
+    (*block_hash, 0, FixedHash::zero())..=(*block_hash, u64::MAX, FixedHash::from([0xff; 32]))
+}
+
+/// Removes and returns every entry whose key falls within `range`.
+fn remove_range<K: Ord + Clone, V>(map: &mut BTreeMap<K, V>, range: RangeInclusive<K>) -> Vec<V> {
+    let keys = map.range(range).map(|(k, _)| k.clone()).collect::<Vec<_>>();
+    keys.iter().filter_map(|k| map.remove(k)).collect()
+}
+
+fn excess_sig_key(excess_sig: &Signature) -> Vec<u8> {
+    let mut key = Vec::<u8>::with_capacity(32 * 2);
+    key.extend(excess_sig.get_public_nonce().as_bytes());
+    key.extend(excess_sig.get_signature().as_bytes());
+    key
+}
+
+impl MemoryDbInner {
+    // Moves the output SMT out of the state so that it can be changed while the other tables are. Callers put it back
+    // when they are done, and if they fail part way the copy of the state is discarded anyway.
+    fn take_output_smt(&mut self) -> PersistentOutputSmt<OutputSmtNodes> {
+        PersistentOutputSmt::new(mem::take(&mut self.output_smt))
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn apply_db_transaction(
+        &mut self,
+        txn: &DbTransaction,
+        consensus_manager: &ConsensusManager,
+    ) -> Result<(), ChainStorageError> {
+        #[allow(clippy::enum_glob_use)]
+        use WriteOperation::*;
+
+        let number_of_operations = txn.operations().len();
+        for (i, op) in txn.operations().iter().enumerate() {
+            trace!(target: LOG_TARGET, "[apply_db_transaction] WriteOperation: {} ({} of {})", op, i + 1, number_of_operations);
+            match op {
+                InsertOrphanBlock(block) => self.insert_orphan_block(block)?,
+                InsertChainHeader { header } => {
+                    self.insert_header(header.header(), header.accumulated_data())?;
+                },
+                InsertTipBlockBody { block } => {
+                    self.insert_tip_block_body(consensus_manager, block.header(), block.block().body.clone())?;
+                },
+                InsertKernel {
+                    header_hash,
+                    kernel,
+                    mmr_position,
+                } => {
+                    self.insert_kernel(header_hash, kernel, *mmr_position)?;
+                },
+                InsertOutput {
+                    header_hash,
+                    header_height,
+                    timestamp,
+                    output,
+                } => {
+                    self.insert_output(header_hash, *header_height, *timestamp, output)?;
+                },
+                DeleteHeader(height) => {
+                    self.delete_header(*height)?;
+                },
+                DeleteOrphan(hash) => {
+                    self.delete_orphan(hash)?;
+                },
+                DeleteOrphanChainTip(hash) => {
+                    remove_existing(&mut self.orphan_chain_tips, hash, "orphan_chain_tips_db")?;
+                },
+                InsertOrphanChainTip(hash, total_accumulated_difficulty) => {
+                    insert_new(
+                        &mut self.orphan_chain_tips,
+                        *hash,
+                        ChainTipData {
+                            hash: *hash,
+                            total_accumulated_difficulty: *total_accumulated_difficulty,
+                        },
+                        "orphan_chain_tips_db",
+                    )?;
+                },
+                DeleteTipBlock(hash) => {
+                    self.delete_tip_block_body(hash)?;
+                },
+                UpdateOutputSmt(changes) => {
+                    let mut output_smt = self.take_output_smt();
+                    apply_output_smt_changes(&mut output_smt, changes)?;
+                    self.output_smt = output_smt.into_store();
+                },
+                InsertMoneroSeedHeight(data, height) => {
+                    let current_height = self.monero_seed_height.get(data).copied().unwrap_or(u64::MAX);
+                    if *height < current_height {
+                        self.monero_seed_height.insert(data.clone(), *height);
+                    }
+                },
+                SetAccumulatedDataForOrphan(accumulated_data) => {
+                    self.set_accumulated_data_for_orphan(accumulated_data)?;
+                },
+                InsertChainOrphanBlock(chain_block) => {
+                    self.insert_orphan_block(chain_block.block())?;
+                    self.set_accumulated_data_for_orphan(chain_block.accumulated_data())?;
+                },
+                UpdateBlockAccumulatedData { header_hash, values } => {
+                    self.update_block_accumulated_data(header_hash, values.clone())?;
+                },
+                PruneOutputsSpentAtHash { block_hash } => {
+                    self.prune_outputs_spent_at_hash(block_hash)?;
+                },
+                PruneOutputFromAllDbs {
+                    output_hash,
+                    commitment,
+                    output_type,
+                } => {
+                    self.prune_output_from_all_dbs(output_hash, commitment, *output_type)?;
+                },
+                DeleteAllKernelsInBlock { block_hash } => {
+                    self.delete_block_kernels(block_hash)?;
+                },
+                DeleteAllInputsInBlock { block_hash } => {
+                    let inputs = remove_range(&mut self.inputs, block_key_range(block_hash));
+                    debug!(target: LOG_TARGET, "Deleted {} input(s)", inputs.len());
+                },
+                SetBestBlock {
+                    height,
+                    hash,
+                    accumulated_difficulty,
+                    expected_prev_best_block,
+                    timestamp,
+                } => {
+                    // As with LMDB, the previous best block is only checked once it has been set
+                    if *height > 0 {
+                        let prev = self.fetch_best_block()?;
+                        if *expected_prev_best_block != prev {
+                            return Err(ChainStorageError::InvalidOperation(format!(
+                                "There was a change in best_block, the best block is suppose to be: ({}), but it \
+                                 currently is: ({})",
+                                expected_prev_best_block.to_hex(),
+                                prev.to_hex(),
+                            )));
+                        };
+                    }
+                    if !self.block_hashes.contains_key(hash) {
+                        return Err(ChainStorageError::InvalidOperation(format!(
+                            "There is no Blockheader hash ({}) in db",
+                            expected_prev_best_block.to_hex(),
+                        )));
+                    };
+                    self.metadata.chain_height = Some(*height);
+                    self.metadata.best_block = Some(*hash);
+                    self.metadata.accumulated_work = Some(*accumulated_difficulty);
+                    self.metadata.best_block_timestamp = Some(*timestamp);
+                },
+                SetPruningHorizonConfig(pruning_horizon) => {
+                    self.metadata.pruning_horizon = Some(*pruning_horizon);
+                },
+                SetPrunedHeight { height } => {
+                    self.metadata.pruned_height = Some(*height);
+                },
+                SetHorizonData { horizon_data } => {
+                    self.metadata.horizon_data = Some(horizon_data.clone());
+                },
+                InsertBadBlock { hash, height, reason } => {
+                    self.insert_bad_block_and_cleanup(hash, *height, reason.to_string())?;
+                },
+                InsertReorg { reorg } => {
+                    self.reorgs.insert(reorg.local_time.timestamp(), reorg.clone());
+                },
+                ClearAllReorgs => {
+                    self.reorgs.clear();
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn table_entry_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("metadata", self.metadata.len()),
+            ("headers", self.headers.len()),
+            ("header_accumulated_data", self.header_accumulated_data.len()),
+            ("mmr_peak_data", self.block_accumulated_data.len()),
+            ("block_hashes", self.block_hashes.len()),
+            ("utxos", self.utxos.len()),
+            ("inputs", self.inputs.len()),
+            ("txos_hash_to_index", self.txos_hash_to_index.len()),
+            ("kernels", self.kernels.len()),
+            ("kernel_excess_index", self.kernel_excess_index.len()),
+            ("kernel_excess_sig_index", self.kernel_excess_sig_index.len()),
+            ("kernel_mmr_size_index", self.kernel_mmr_size_index.len()),
+            ("utxo_commitment_index", self.utxo_commitment_index.len()),
+            (
+                "deleted_txo_hash_to_header_index",
+                self.deleted_txo_hash_to_header_index.len(),
+            ),
+            ("orphans", self.orphans.len()),
+            ("orphan_accumulated_data", self.orphan_header_accumulated_data.len()),
+            ("monero_seed_height", self.monero_seed_height.len()),
+            ("orphan_chain_tips", self.orphan_chain_tips.len()),
+            (
+                "orphan_parent_map_index",
+                self.orphan_parent_map.values().map(BTreeSet::len).sum(),
+            ),
+            ("bad_blocks", self.bad_blocks.len()),
+            ("reorgs", self.reorgs.len()),
+            ("validator_nodes", self.validator_nodes.len()),
+            ("validator_nodes_mapping", self.validator_nodes_mapping.len()),
+            ("template_registrations", self.template_registrations.len()),
+        ]
+    }
+
+    fn insert_output(
+        &mut self,
+        header_hash: &HashOutput,
+        header_height: u64,
+        header_timestamp: u64,
+        output: &TransactionOutput,
+    ) -> Result<(), ChainStorageError> {
+        let output_hash = output.hash();
+        let output_key = (*header_hash, output_hash);
+
+        if !output.is_burned() {
+            insert_new(
+                &mut self.utxo_commitment_index,
+                output.commitment.to_vec(),
+                output_hash,
+                "utxo_commitment_index",
+            )?;
+        }
+        insert_new(
+            &mut self.txos_hash_to_index,
+            output_hash,
+            output_key,
+            "txos_hash_to_index_db",
+        )?;
+        insert_new(
+            &mut self.utxos,
+            output_key,
+            TransactionOutputRowData {
+                output: output.clone(),
+                header_hash: *header_hash,
+                hash: output_hash,
+                mined_height: header_height,
+                mined_timestamp: header_timestamp,
+            },
+            "utxos",
+        )
+    }
+
+    fn insert_kernel(
+        &mut self,
+        header_hash: &HashOutput,
+        kernel: &TransactionKernel,
+        mmr_position: u64,
+    ) -> Result<(), ChainStorageError> {
+        let hash = kernel.hash();
+        let key = (*header_hash, mmr_position, hash);
+
+        insert_new(
+            &mut self.kernel_excess_index,
+            kernel.excess.to_vec(),
+            key,
+            "kernel_excess_index",
+        )?;
+        insert_new(
+            &mut self.kernel_excess_sig_index,
+            excess_sig_key(&kernel.excess_sig),
+            key,
+            "kernel_excess_sig_index",
+        )?;
+        insert_new(
+            &mut self.kernels,
+            key,
+            TransactionKernelRowData {
+                kernel: kernel.clone(),
+                header_hash: *header_hash,
+                mmr_position,
+                hash,
+            },
+            "kernels_db",
+        )
+    }
+
+    fn input_with_output_data(&self, input: TransactionInput) -> Result<TransactionInput, ChainStorageError> {
+        match input.spent_output {
+            SpentOutput::OutputData { .. } => Ok(input),
+            SpentOutput::OutputHash(output_hash) => match self.fetch_output(&output_hash) {
+                Some(utxo_mined_info) => Ok(TransactionInput {
+                    version: input.version,
+                    spent_output: SpentOutput::create_from_output(utxo_mined_info.output),
+                    input_data: input.input_data,
+                    script_signature: input.script_signature,
+                }),
+                None => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not retrieve output data from input's output_hash `{}`",
+                        output_hash.to_hex()
+                    );
+                    Err(ChainStorageError::ValueNotFound {
+                        entity: "UTXO",
+                        field: "hash",
+                        value: output_hash.to_hex(),
+                    })
+                },
+            },
+        }
+    }
+
+    fn insert_input(
+        &mut self,
+        height: u64,
+        header_timestamp: u64,
+        header_hash: &HashOutput,
+        input: TransactionInput,
+    ) -> Result<(), ChainStorageError> {
+        let input_with_output_data = self.input_with_output_data(input)?;
+        remove_existing(
+            &mut self.utxo_commitment_index,
+            &input_with_output_data.commitment()?.to_vec(),
+            "utxo_commitment_index",
+        )?;
+
+        let hash = input_with_output_data.canonical_hash();
+        let output_hash = input_with_output_data.output_hash();
+        let key = (*header_hash, hash);
+        insert_new(
+            &mut self.deleted_txo_hash_to_header_index,
+            output_hash,
+            key,
+            "deleted_txo_hash_to_header_index",
+        )?;
+        insert_new(
+            &mut self.inputs,
+            key,
+            TransactionInputRowData {
+                input: input_with_output_data.to_compact(),
+                header_hash: *header_hash,
+                spent_timestamp: header_timestamp,
+                spent_height: height,
+                hash,
+            },
+            "inputs_db",
+        )
+    }
+
+    fn insert_orphan_block(&mut self, block: &Block) -> Result<(), ChainStorageError> {
+        let k = block.hash();
+        self.orphan_parent_map
+            .entry(block.header.prev_hash)
+            .or_default()
+            .insert(k);
+        insert_new(&mut self.orphans, k, block.clone(), "orphans_db")
+    }
+
+    fn set_accumulated_data_for_orphan(
+        &mut self,
+        accumulated_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if !self.orphans.contains_key(&accumulated_data.hash) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "set_accumulated_data_for_orphan: orphan {} does not exist",
+                accumulated_data.hash.to_hex()
+            )));
+        }
+
+        insert_new(
+            &mut self.orphan_header_accumulated_data,
+            accumulated_data.hash,
+            accumulated_data.clone(),
+            "orphan_header_accumulated_data_db",
+        )
+    }
+
+    /// Inserts the header and header accumulated data.
+    fn insert_header(
+        &mut self,
+        header: &BlockHeader,
+        accum_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if let Some(current_header_at_height) = self.headers.get(&header.height) {
+            let hash = current_header_at_height.hash();
+            if hash != accum_data.hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "There is a different header stored at height {} already. New header ({}), current header: ({})",
+                    header.height,
+                    accum_data.hash.to_hex(),
+                    hash.to_hex(),
+                )));
+            }
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The header at height {} already exists. Existing header hash: {}",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+
+        match self.fetch_last_header() {
+            Some(last_header) => {
+                if last_header.height != header.height.saturating_sub(1) {
+                    return Err(ChainStorageError::InvalidOperation(format!(
+                        "Attempted to insert a header out of order. The last header height is {} but attempted to \
+                         insert a header with height {}",
+                        last_header.height, header.height,
+                    )));
+                }
+
+                let hash = last_header.hash();
+                if hash != header.prev_hash {
+                    return Err(ChainStorageError::InvalidOperation(format!(
+                        "Attempted to insert a block header at height {} that didn't form a chain. Previous block \
+                         hash:{}, new block's previous hash:{}",
+                        header.height,
+                        hash.to_hex(),
+                        header.prev_hash.to_hex()
+                    )));
+                }
+            },
+            None => {
+                if header.height != 0 {
+                    return Err(ChainStorageError::InvalidOperation(format!(
+                        "The first header inserted must have height 0. Height provided: {}",
+                        header.height
+                    )));
+                }
+            },
+        }
+
+        insert_new(
+            &mut self.header_accumulated_data,
+            header.height,
+            accum_data.clone(),
+            "header_accumulated_data_db",
+        )?;
+        insert_new(&mut self.block_hashes, header.hash(), header.height, "block_hashes_db")?;
+        insert_new(&mut self.headers, header.height, header.clone(), "headers_db")?;
+        insert_new(
+            &mut self.kernel_mmr_size_index,
+            header.kernel_mmr_size,
+            header.height,
+            "kernel_mmr_size_index",
+        )
+    }
+
+    fn delete_header(&mut self, height: u64) -> Result<(), ChainStorageError> {
+        if self.block_accumulated_data.contains_key(&height) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete header at height {} while block accumulated data still exists",
+                height
+            )));
+        }
+
+        let header = self
+            .fetch_last_header()
+            .cloned()
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHeader",
+                field: "height",
+                value: "last_header".to_string(),
+            })?;
+        if header.height != height {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete a header at height {} that was not the last header (which is at height {}). \
+                 Headers must be deleted in reverse order.",
+                height, header.height
+            )));
+        }
+
+        let hash = header.hash();
+
+        // Check that there are no utxos or kernels linked to this.
+        if self.kernels.range(kernel_key_range(&hash)).next().is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header {} ({}) because there are kernels linked to it",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+        if self.utxos.range(block_key_range(&hash)).next().is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header at height {} ({}) because there are UTXOs linked to it",
+                height,
+                hash.to_hex()
+            )));
+        }
+
+        remove_existing(&mut self.block_hashes, &hash, "block_hashes_db")?;
+        remove_existing(&mut self.headers, &height, "headers_db")?;
+        remove_existing(&mut self.header_accumulated_data, &height, "header_accumulated_data_db")?;
+        remove_existing(
+            &mut self.kernel_mmr_size_index,
+            &header.kernel_mmr_size,
+            "kernel_mmr_size_index",
+        )?;
+
+        Ok(())
+    }
+
+    fn delete_tip_block_body(&mut self, block_hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let hash_hex = block_hash.to_hex();
+        debug!(target: LOG_TARGET, "Deleting block `{}`", hash_hex);
+        let height = self
+            .block_hashes
+            .get(block_hash)
+            .copied()
+            .ok_or(ChainStorageError::ValueNotFound {
+                entity: "Block",
+                field: "hash",
+                value: hash_hex,
+            })?;
+        let next_height = height.saturating_add(1);
+        let prev_height = height.saturating_sub(1);
+        if self.block_accumulated_data.contains_key(&next_height) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete block at height {} while next block still exists",
+                height
+            )));
+        }
+
+        remove_existing(&mut self.block_accumulated_data, &height, "block_accumulated_data_db")?;
+
+        let mut output_smt = self.take_output_smt();
+        self.delete_block_inputs_outputs(block_hash, &mut output_smt)?;
+        let (root, _) = output_smt_root(&output_smt)?;
+        self.output_smt = output_smt.into_store();
+
+        let new_tip_header = self.fetch_chain_header_by_height(prev_height)?;
+        if root != new_tip_header.header().output_mr {
+            error!(
+                target: LOG_TARGET,
+                "Deleting block, new smt root(#{}) did not match expected (#{}) smt root",
+                    root.to_hex(),
+                    new_tip_header.header().output_mr.to_hex(),
+            );
+            return Err(ChainStorageError::InvalidOperation(
+                "Deleting block, new smt root did not match expected smt root".to_string(),
+            ));
+        }
+
+        self.delete_block_kernels(block_hash)?;
+
+        Ok(())
+    }
+
+    fn delete_block_inputs_outputs(
+        &mut self,
+        block_hash: &HashOutput,
+        output_smt: &mut PersistentOutputSmt<OutputSmtNodes>,
+    ) -> Result<(), ChainStorageError> {
+        let output_rows = remove_range(&mut self.utxos, block_key_range(block_hash));
+        debug!(target: LOG_TARGET, "Deleted {} outputs...", output_rows.len());
+        let inputs = remove_range(&mut self.inputs, block_key_range(block_hash));
+        debug!(target: LOG_TARGET, "Deleted {} input(s)...", inputs.len());
+
+        for utxo in &output_rows {
+            remove_existing(&mut self.txos_hash_to_index, &utxo.hash, "txos_hash_to_index_db")?;
+
+            let output_hash = utxo.output.hash();
+            // if an output was already spent in the block, it was never created as unspent, so dont delete it as it
+            // does not exist here
+            if inputs.iter().any(|r| r.input.output_hash() == output_hash) {
+                continue;
+            }
+            // if an output was burned, it was never created as an unspent utxo
+            if utxo.output.is_burned() {
+                continue;
+            }
+            let smt_key = NodeKey::try_from(utxo.output.commitment.as_bytes())?;
+            match output_smt.delete(&smt_key)? {
+                DeleteResult::Deleted(_value_hash) => {},
+                DeleteResult::KeyNotFound => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not find input({}) in SMT",
+                        utxo.output.commitment.to_hex(),
+                    );
+                    return Err(ChainStorageError::UnspendableInput);
+                },
+            };
+            remove_existing(
+                &mut self.utxo_commitment_index,
+                &utxo.output.commitment.to_vec(),
+                "utxo_commitment_index",
+            )?;
+        }
+        // Move inputs in this block back into the unspent set
+        for row in inputs {
+            let output_hash = row.input.output_hash();
+            remove_existing(
+                &mut self.deleted_txo_hash_to_header_index,
+                &output_hash,
+                "deleted_txo_hash_to_header_index",
+            )?;
+            // If input spends an output in this block, don't add it to the utxo set
+            if output_rows.iter().any(|r| r.hash == output_hash) {
+                continue;
+            }
+
+            let mut input = row.input;
+            let utxo_mined_info = self
+                .fetch_output(&output_hash)
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "UTXO",
+                    field: "hash",
+                    value: output_hash.to_hex(),
+                })?;
+
+            let rp_hash = match utxo_mined_info.output.proof {
+                Some(proof) => proof.hash(),
+                None => FixedHash::zero(),
+            };
+            input.add_output_data(
+                utxo_mined_info.output.version,
+                utxo_mined_info.output.features,
+                utxo_mined_info.output.commitment,
+                utxo_mined_info.output.script,
+                utxo_mined_info.output.sender_offset_public_key,
+                utxo_mined_info.output.covenant,
+                utxo_mined_info.output.encrypted_data,
+                utxo_mined_info.output.metadata_signature,
+                rp_hash,
+                utxo_mined_info.output.minimum_value_promise,
+            );
+            let smt_key = NodeKey::try_from(input.commitment()?.as_bytes())?;
+            let smt_node = ValueHash::try_from(input.smt_hash(utxo_mined_info.mined_height).as_slice())?;
+            if let Err(e) = output_smt.insert(smt_key, smt_node) {
+                error!(
+                    target: LOG_TARGET,
+                    "Output commitment({}) already in SMT",
+                    input.commitment()?.to_hex(),
+                );
+                return Err(e.into());
+            }
+
+            trace!(target: LOG_TARGET, "Input moved to UTXO set: {}", input);
+            insert_new(
+                &mut self.utxo_commitment_index,
+                input.commitment()?.to_vec(),
+                input.output_hash(),
+                "utxo_commitment_index",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_block_kernels(&mut self, block_hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let kernels = remove_range(&mut self.kernels, kernel_key_range(block_hash));
+        debug!(target: LOG_TARGET, "Deleted {} kernels...", kernels.len());
+        for row in kernels {
+            remove_existing(
+                &mut self.kernel_excess_index,
+                &row.kernel.excess.to_vec(),
+                "kernel_excess_index",
+            )?;
+            remove_existing(
+                &mut self.kernel_excess_sig_index,
+                &excess_sig_key(&row.kernel.excess_sig),
+                "kernel_excess_sig_index",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_orphan(&mut self, hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let parent_hash = match self.orphans.get(hash) {
+            Some(orphan) => orphan.header.prev_hash,
+            None => {
+                // delete_orphan is idempotent
+                debug!(
+                    target: LOG_TARGET,
+                    "delete_orphan: request to delete orphan block {} that was not found.",
+                    hash.to_hex()
+                );
+                return Ok(());
+            },
+        };
+
+        let removed = match self.orphan_parent_map.get_mut(&parent_hash) {
+            Some(children) => {
+                let removed = children.remove(hash);
+                if children.is_empty() {
+                    self.orphan_parent_map.remove(&parent_hash);
+                }
+                removed
+            },
+            None => false,
+        };
+        if !removed {
+            return Err(ChainStorageError::ValueNotFound {
+                entity: "orphan_parent_map_index",
+                field: "hash",
+                value: hash.to_hex(),
+            });
+        }
+
+        // Orphan is a tip hash
+        if self.orphan_chain_tips.remove(hash).is_some() {
+            // If an orphan parent exists, it must be promoted
+            match (
+                self.orphans.contains_key(&parent_hash),
+                self.orphan_header_accumulated_data.get(&parent_hash),
+            ) {
+                (true, Some(parent_accum)) => {
+                    // Parent becomes a tip hash
+                    let tip = ChainTipData {
+                        hash: parent_hash,
+                        total_accumulated_difficulty: parent_accum.total_accumulated_difficulty,
+                    };
+                    insert_new(&mut self.orphan_chain_tips, parent_hash, tip, "orphan_chain_tips_db")?;
+                },
+                (false, None) => {
+                    // No entries, nothing here
+                },
+                (has_orphan, has_accum) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "'orphans' ({}) and 'orphan_header_accumulated_data' ({}) out of sync, missing parent hash \
+                         '{}' entry",
+                        has_orphan,
+                        has_accum.is_some(),
+                        parent_hash.to_hex()
+                    );
+                },
+            }
+        }
+
+        self.orphan_header_accumulated_data.remove(hash);
+        remove_existing(&mut self.orphans, hash, "orphans_db")?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn insert_tip_block_body(
+        &mut self,
+        consensus_manager: &ConsensusManager,
+        header: &BlockHeader,
+        body: AggregateBody,
+    ) -> Result<(), ChainStorageError> {
+        if self.block_accumulated_data.contains_key(&(header.height + 1)) {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to insert block at height {} while next block already exists",
+                header.height
+            )));
+        }
+        let block_hash = header.hash();
+        debug!(
+            target: LOG_TARGET,
+            "Inserting block body for header `{}`: {}",
+            block_hash.to_hex(),
+            body.to_counts_string()
+        );
+        let mut output_smt = self.take_output_smt();
+
+        let current_header_at_height =
+            self.headers
+                .get(&header.height)
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockHeader",
+                    field: "height",
+                    value: header.height.to_string(),
+                })?;
+        let hash = current_header_at_height.hash();
+        if hash != block_hash {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Could not insert this block body because there is a different header stored at height {}. New header \
+                 ({}), current header: ({})",
+                header.height,
+                hash.to_hex(),
+                block_hash.to_hex()
+            )));
+        }
+
+        let (inputs, outputs, kernels) = body.dissolve();
+
+        let data = if header.height == 0 {
+            BlockAccumulatedData::default()
+        } else {
+            self.block_accumulated_data
+                .get(&(header.height - 1))
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockAccumulatedData",
+                    field: "height",
+                    value: (header.height - 1).to_string(),
+                })?
+        };
+
+        let mut total_kernel_sum = Commitment::default();
+        let mut kernel_mmr = PrunedKernelMmr::new(data.dissolve());
+
+        for kernel in kernels {
+            total_kernel_sum = &total_kernel_sum + &kernel.excess;
+            let pos =
+                u64::try_from(kernel_mmr.push(kernel.hash().to_vec())?).map_err(|_| ChainStorageError::OutOfRange)?;
+            self.insert_kernel(&block_hash, &kernel, pos)?;
+        }
+
+        for output in outputs {
+            if !output.is_burned() {
+                let smt_key = NodeKey::try_from(output.commitment.as_bytes())?;
+                let smt_node = ValueHash::try_from(output.smt_hash(header.height).as_slice())?;
+                if let Err(e) = output_smt.insert(smt_key, smt_node) {
+                    error!(
+                        target: LOG_TARGET,
+                        "Output commitment({}) already in SMT",
+                        output.commitment.to_hex(),
+                    );
+                    return Err(e.into());
+                }
+            }
+
+            let output_hash = output.hash();
+            if let Some(vn_reg) = output
+                .features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.validator_node_registration())
+            {
+                self.insert_validator_node(consensus_manager, header, &output.commitment, vn_reg)?;
+            }
+            if let Some(template_reg) = output
+                .features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.code_template_registration())
+            {
+                let record = TemplateRegistrationEntry {
+                    registration_data: template_reg.clone(),
+                    output_hash,
+                    block_height: header.height,
+                    block_hash,
+                };
+                insert_new(
+                    &mut self.template_registrations,
+                    (header.height, output_hash),
+                    record,
+                    "template_registrations",
+                )?;
+            }
+            self.insert_output(&block_hash, header.height, header.timestamp().as_u64(), &output)?;
+        }
+
+        for input in inputs {
+            let input_with_output_data = self.input_with_output_data(input)?;
+            let smt_key = NodeKey::try_from(input_with_output_data.commitment()?.as_bytes())?;
+            match output_smt.delete(&smt_key)? {
+                DeleteResult::Deleted(_value_hash) => {},
+                DeleteResult::KeyNotFound => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not find input({}) in SMT",
+                        input_with_output_data.commitment()?.to_hex(),
+                    );
+                    return Err(ChainStorageError::UnspendableInput);
+                },
+            };
+
+            let features = input_with_output_data.features()?;
+            if let Some(vn_reg) = features
+                .sidechain_feature
+                .as_ref()
+                .and_then(|f| f.validator_node_registration())
+            {
+                self.delete_validator_node(header.height, vn_reg.public_key(), input_with_output_data.commitment()?)?;
+            }
+            self.insert_input(
+                current_header_at_height.height,
+                current_header_at_height.timestamp.as_u64(),
+                &block_hash,
+                input_with_output_data,
+            )?;
+        }
+        self.output_smt = output_smt.into_store();
+
+        insert_new(
+            &mut self.block_accumulated_data,
+            header.height,
+            BlockAccumulatedData::new(kernel_mmr.get_pruned_hash_set()?, total_kernel_sum),
+            "block_accumulated_data_db",
+        )
+    }
+
+    fn insert_validator_node(
+        &mut self,
+        consensus_manager: &ConsensusManager,
+        header: &BlockHeader,
+        commitment: &Commitment,
+        vn_reg: &ValidatorNodeRegistration,
+    ) -> Result<(), ChainStorageError> {
+        let constants = consensus_manager.consensus_constants(header.height);
+        let current_epoch = constants.block_height_to_epoch(header.height);
+
+        let prev_shard_key = self.get_shard_key(
+            current_epoch
+                .as_u64()
+                .saturating_sub(constants.validator_node_validity_period_epochs().as_u64()) *
+                constants.epoch_length(),
+            current_epoch.as_u64() * constants.epoch_length(),
+            vn_reg.public_key(),
+        );
+        let shard_key = vn_reg.derive_shard_key(
+            prev_shard_key,
+            current_epoch,
+            constants.validator_node_registration_shuffle_interval(),
+            &header.prev_hash,
+        );
+
+        let next_epoch = constants.block_height_to_epoch(header.height) + VnEpoch(1);
+        let validator_node = ValidatorNodeEntry {
+            shard_key,
+            start_epoch: next_epoch,
+            end_epoch: next_epoch + constants.validator_node_validity_period_epochs(),
+            public_key: vn_reg.public_key().clone(),
+            commitment: commitment.clone(),
+        };
+
+        let public_key = validator_node.public_key.to_vec();
+        let commitment = validator_node.commitment.to_vec();
+        insert_new(
+            &mut self.validator_nodes_mapping,
+            (public_key.clone(), header.height, commitment.clone()),
+            validator_node.shard_key,
+            "Validator node",
+        )?;
+        insert_new(
+            &mut self.validator_nodes,
+            (header.height, public_key, commitment),
+            validator_node,
+            "Validator node",
+        )
+    }
+
+    fn delete_validator_node(
+        &mut self,
+        height: u64,
+        public_key: &PublicKey,
+        commitment: &Commitment,
+    ) -> Result<(), ChainStorageError> {
+        remove_existing(
+            &mut self.validator_nodes,
+            &(height, public_key.to_vec(), commitment.to_vec()),
+            "validator_nodes",
+        )?;
+        remove_existing(
+            &mut self.validator_nodes_mapping,
+            &(public_key.to_vec(), height, commitment.to_vec()),
+            "validator_nodes_mapping",
+        )?;
+        Ok(())
+    }
+
+    fn update_block_accumulated_data(
+        &mut self,
+        header_hash: &HashOutput,
+        values: UpdateBlockAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        let height = self
+            .block_hashes
+            .get(header_hash)
+            .copied()
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHash",
+                field: "hash",
+                value: header_hash.to_hex(),
+            })?;
+
+        let block_accum_data = self.block_accumulated_data.entry(height).or_default();
+        if let Some(kernel_sum) = values.kernel_sum {
+            block_accum_data.kernel_sum = kernel_sum;
+        }
+        if let Some(kernel_hash_set) = values.kernel_hash_set {
+            block_accum_data.kernels = kernel_hash_set;
+        }
+        Ok(())
+    }
+
+    fn prune_outputs_spent_at_hash(&mut self, block_hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let inputs = self
+            .inputs
+            .range(block_key_range(block_hash))
+            .map(|(_, row)| row.input.clone())
+            .collect::<Vec<_>>();
+
+        for input in inputs {
+            if let SpentOutput::OutputData { ref commitment, .. } = input.spent_output {
+                debug!(target: LOG_TARGET, "Pruning output from 'utxo_commitment_index': key '{}'", commitment.to_hex());
+                remove_existing(
+                    &mut self.utxo_commitment_index,
+                    &commitment.to_vec(),
+                    "utxo_commitment_index",
+                )?;
+            }
+            let output_hash = input.output_hash();
+            if let Some(key) = self.txos_hash_to_index.get(&output_hash).copied() {
+                debug!(target: LOG_TARGET, "Pruning output from 'utxos': key '{}'", output_hash.to_hex());
+                remove_existing(&mut self.utxos, &key, "utxos")?;
+            }
+            remove_existing(&mut self.txos_hash_to_index, &output_hash, "utxos")?;
+        }
+
+        Ok(())
+    }
+
+    fn prune_output_from_all_dbs(
+        &mut self,
+        output_hash: &HashOutput,
+        commitment: &Commitment,
+        output_type: OutputType,
+    ) -> Result<(), ChainStorageError> {
+        let key = self
+            .txos_hash_to_index
+            .get(output_hash)
+            .copied()
+            .ok_or_else(|| ChainStorageError::InvalidOperation("Output key not found".to_string()))?;
+        if !matches!(output_type, OutputType::Burn) {
+            debug!(target: LOG_TARGET, "Pruning output from 'utxo_commitment_index': key '{}'", commitment.to_hex());
+            remove_existing(
+                &mut self.utxo_commitment_index,
+                &commitment.to_vec(),
+                "utxo_commitment_index",
+            )?;
+        }
+        remove_existing(&mut self.txos_hash_to_index, output_hash, "utxos")?;
+        remove_existing(&mut self.utxos, &key, "utxos")?;
+        Ok(())
+    }
+
+    fn insert_bad_block_and_cleanup(
+        &mut self,
+        hash: &HashOutput,
+        height: u64,
+        reason: String,
+    ) -> Result<(), ChainStorageError> {
+        #[cfg(test)]
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 10000;
+        #[cfg(not(test))]
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 0;
+
+        self.bad_blocks.insert(*hash, (height, reason));
+        // Clean up bad blocks that are far from the tip
+        let metadata = self.fetch_chain_metadata()?;
+        let deleted_before_height = metadata
+            .best_block_height()
+            .saturating_sub(CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT);
+        if deleted_before_height == 0 {
+            return Ok(());
+        }
+
+        let num_before = self.bad_blocks.len();
+        self.bad_blocks.retain(|_, (h, _)| *h >= deleted_before_height);
+        debug!(
+            target: LOG_TARGET,
+            "Cleaned out {} stale bad blocks",
+            num_before - self.bad_blocks.len()
+        );
+
+        Ok(())
+    }
+
+    fn fetch_best_block(&self) -> Result<BlockHash, ChainStorageError> {
+        self.metadata
+            .best_block
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "ChainMetadata",
+                field: "BestBlock",
+                value: "".to_string(),
+            })
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        fn required<T: Clone>(value: &Option<T>, field: &'static str) -> Result<T, ChainStorageError> {
+            value.clone().ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "ChainMetadata",
+                field,
+                value: "".to_string(),
+            })
+        }
+
+        Ok(ChainMetadata::new(
+            required(&self.metadata.chain_height, "ChainHeight")?,
+            required(&self.metadata.best_block, "BestBlock")?,
+            self.metadata.pruning_horizon.unwrap_or(0),
+            self.metadata.pruned_height.unwrap_or(0),
+            required(&self.metadata.accumulated_work, "AccumulatedWork")?,
+            required(&self.metadata.best_block_timestamp, "BestBlockTimestamp")?,
+        )?)
+    }
+
+    fn fetch_last_header(&self) -> Option<&BlockHeader> {
+        self.headers.values().next_back()
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        let header = self
+            .headers
+            .get(&height)
+            .cloned()
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHeader",
+                field: "height",
+                value: height.to_string(),
+            })?;
+        let accum_data =
+            self.header_accumulated_data
+                .get(&height)
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockHeaderAccumulatedData",
+                    field: "height",
+                    value: height.to_string(),
+                })?;
+
+        ChainHeader::try_construct(header, accum_data).ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+            function: "fetch_chain_header_by_height",
+            details: format!("Mismatch in accumulated data at height #{}", height),
+        })
+    }
+
+    fn fetch_orphan_chain_header(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        let orphan = self.orphans.get(hash).ok_or_else(|| ChainStorageError::ValueNotFound {
+            entity: "Orphan",
+            field: "hash",
+            value: hash.to_hex(),
+        })?;
+        let accumulated_data =
+            self.orphan_header_accumulated_data
+                .get(hash)
+                .cloned()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "Orphan accumulated data",
+                    field: "hash",
+                    value: hash.to_hex(),
+                })?;
+        let height = orphan.header.height;
+        ChainHeader::try_construct(orphan.header.clone(), accumulated_data).ok_or_else(|| {
+            ChainStorageError::DataInconsistencyDetected {
+                function: "fetch_orphan_chain_tip_by_hash",
+                details: format!("Accumulated data mismatch at height #{}", height),
+            }
+        })
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Option<OutputMinedInfo> {
+        self.txos_hash_to_index
+            .get(output_hash)
+            .and_then(|key| self.utxos.get(key))
+            .map(|row| OutputMinedInfo {
+                output: row.output.clone(),
+                mined_height: row.mined_height,
+                header_hash: row.header_hash,
+                mined_timestamp: row.mined_timestamp,
+            })
+    }
+
+    fn fetch_input(&self, output_hash: &HashOutput) -> Option<InputMinedInfo> {
+        self.deleted_txo_hash_to_header_index
+            .get(output_hash)
+            .and_then(|key| self.inputs.get(key))
+            .map(|row| InputMinedInfo {
+                input: row.input.clone(),
+                spent_height: row.spent_height,
+                header_hash: row.header_hash,
+                spent_timestamp: row.spent_timestamp,
+            })
+    }
+
+    fn fetch_outputs_in_block_with_spend_state(
+        &self,
+        header_hash: &HashOutput,
+        spend_status_at_header: Option<&HashOutput>,
+    ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
+        let mut outputs = self
+            .utxos
+            .range(block_key_range(header_hash))
+            .map(|(_, row)| (row.output.clone(), false))
+            .collect::<Vec<_>>();
+        if let Some(header_hash) = spend_status_at_header {
+            let header_height =
+                self.block_hashes
+                    .get(header_hash)
+                    .copied()
+                    .ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "Header",
+                        field: "hash",
+                        value: header_hash.to_hex(),
+                    })?;
+            for output in &mut outputs {
+                if let Some(key) = self.deleted_txo_hash_to_header_index.get(&output.0.hash()) {
+                    let input = self.inputs.get(key).ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "input",
+                        field: "hash",
+                        value: header_hash.to_hex(),
+                    })?;
+                    if input.spent_height <= header_height {
+                        output.1 = true;
+                    }
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Returns a set of <public key, shard id> tuples ordered by shard key. If a duplicate registration is found, the
+    /// last registration is included.
+    fn get_vn_set(&self, start_height: u64, end_height: u64) -> Vec<(PublicKey, ShardKey)> {
+        let mut nodes = Vec::new();
+        let mut dedup_map = BTreeMap::new();
+        for (i, ((_, pk, _), vn)) in self
+            .validator_nodes
+            .range((start_height, Vec::new(), Vec::new())..)
+            .take_while(|((height, _, _), _)| *height <= end_height)
+            .enumerate()
+        {
+            if let Some(dup_idx) = dedup_map.insert(pk.clone(), i) {
+                // Remove duplicate registrations within the set without changing index order
+                nodes[dup_idx] = None;
+            }
+            nodes.push(Some((vn.public_key.clone(), vn.shard_key)));
+        }
+
+        let mut vn_set = nodes.into_iter().flatten().collect::<Vec<_>>();
+        vn_set.sort_by(|(_, a), (_, b)| a.cmp(b));
+        vn_set
+    }
+
+    fn get_shard_key(&self, start_height: u64, end_height: u64, public_key: &PublicKey) -> Option<ShardKey> {
+        let public_key = public_key.to_vec();
+        self.validator_nodes_mapping
+            .range((public_key.clone(), start_height, Vec::new())..)
+            .take_while(|((pk, height, _), _)| *pk == public_key && *height <= end_height)
+            .last()
+            .map(|(_, shard_key)| *shard_key)
+    }
+
+    fn clear_all_pending_headers(&mut self) -> Result<usize, ChainStorageError> {
+        let last_header_height = match self.fetch_last_header() {
+            Some(h) => h.height,
+            None => return Ok(0),
+        };
+        let metadata = self.fetch_chain_metadata()?;
+        if metadata.best_block_height() == last_header_height {
+            return Ok(0);
+        }
+
+        let start = metadata.best_block_height() + 1;
+        let mut num_deleted = 0;
+        for h in (start..=last_header_height).rev() {
+            self.delete_header(h)?;
+            num_deleted += 1;
+        }
+        Ok(num_deleted)
+    }
+}
+
+impl BlockchainBackend for MemoryDatabase {
+    fn write(&mut self, txn: DbTransaction) -> Result<(), ChainStorageError> {
+        if txn.operations().is_empty() {
+            return Ok(());
+        }
+
+        let mark = Instant::now();
+        let mut inner = self.write_access()?;
+        let mut staged = inner.clone();
+        if let Err(e) = staged.apply_db_transaction(&txn, &self.consensus_manager) {
+            error!(target: LOG_TARGET, "Failed to apply DB transaction: {:?}", e);
+            return Err(e);
+        }
+        *inner = staged;
+        trace!(
+            target: LOG_TARGET,
+            "Database completed {} operation(s) in {:.0?}",
+            txn.operations().len(),
+            mark.elapsed()
+        );
+        Ok(())
+    }
+
+    fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
+        let inner = self.read_access()?;
+        let res = match key {
+            DbKey::HeaderHeight(k) => inner
+                .headers
+                .get(k)
+                .map(|val| DbValue::HeaderHeight(Box::new(val.clone()))),
+            DbKey::HeaderHash(hash) => inner
+                .block_hashes
+                .get(hash)
+                .and_then(|k| inner.headers.get(k))
+                .map(|val| DbValue::HeaderHash(Box::new(val.clone()))),
+            DbKey::OrphanBlock(k) => inner
+                .orphans
+                .get(k)
+                .map(|val| DbValue::OrphanBlock(Box::new(val.clone()))),
+        };
+        Ok(res)
+    }
+
+    fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
+        let inner = self.read_access()?;
+        Ok(match key {
+            DbKey::HeaderHeight(k) => inner.headers.contains_key(k),
+            DbKey::HeaderHash(h) => inner.block_hashes.contains_key(h),
+            DbKey::OrphanBlock(k) => inner.orphans.contains_key(k),
+        })
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        self.read_access()?.fetch_chain_header_by_height(height)
+    }
+
+    fn fetch_header_accumulated_data(
+        &self,
+        hash: &HashOutput,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        let inner = self.read_access()?;
+        Ok(inner
+            .block_hashes
+            .get(hash)
+            .and_then(|h| inner.header_accumulated_data.get(h))
+            .cloned())
+    }
+
+    fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        let inner = self.read_access()?;
+        if let Some(h) = inner.block_hashes.get(hash) {
+            return inner.fetch_chain_header_by_height(*h);
+        }
+
+        if let Some(accum) = inner.orphan_header_accumulated_data.get(hash) {
+            let orphan = inner
+                .orphans
+                .get(hash)
+                .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                    function: "fetch_chain_header_in_all_chains",
+                    details: format!(
+                        "Orphan accumulated data exists but the corresponding orphan header {} does not",
+                        hash.to_hex()
+                    ),
+                })?;
+            return ChainHeader::try_construct(orphan.header.clone(), accum.clone()).ok_or_else(|| {
+                ChainStorageError::DataInconsistencyDetected {
+                    function: "fetch_chain_header_in_all_chains",
+                    details: format!("accumulated data mismatch for orphan header {}", hash.to_hex()),
+                }
+            });
+        }
+
+        Err(ChainStorageError::ValueNotFound {
+            entity: "chain header (in chain_header_in_all_chains)",
+            field: "hash",
+            value: hash.to_hex(),
+        })
+    }
+
+    fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        let inner = self.read_access()?;
+        // The index is keyed by kernel mmr size, so offset the position by 1 so that the mmr_position arg is an index
+        // starting from 0
+        let mmr_position = mmr_position + 1;
+        let height = inner
+            .kernel_mmr_size_index
+            .range(mmr_position..)
+            .next()
+            .map(|(_, height)| *height)
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "kernel_mmr_size_index",
+                field: "mmr_position",
+                value: mmr_position.to_string(),
+            })?;
+        inner.fetch_chain_header_by_height(height)
+    }
+
+    fn is_empty(&self) -> Result<bool, ChainStorageError> {
+        Ok(self.read_access()?.headers.is_empty())
+    }
+
+    fn fetch_block_accumulated_data(
+        &self,
+        header_hash: &HashOutput,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        let inner = self.read_access()?;
+        Ok(inner
+            .block_hashes
+            .get(header_hash)
+            .and_then(|h| inner.block_accumulated_data.get(h))
+            .cloned())
+    }
+
+    fn fetch_block_accumulated_data_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        Ok(self.read_access()?.block_accumulated_data.get(&height).cloned())
+    }
+
+    fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .kernels
+            .range(kernel_key_range(header_hash))
+            .map(|(_, row)| row.kernel.clone())
+            .collect())
+    }
+
+    fn fetch_kernel_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        let inner = self.read_access()?;
+        Ok(inner
+            .kernel_excess_sig_index
+            .get(&excess_sig_key(excess_sig))
+            .and_then(|key| inner.kernels.get(key))
+            .map(|row| (row.kernel.clone(), row.header_hash)))
+    }
+
+    fn fetch_outputs_in_block_with_spend_state(
+        &self,
+        header_hash: &HashOutput,
+        spend_status_at_header: Option<&HashOutput>,
+    ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
+        self.read_access()?
+            .fetch_outputs_in_block_with_spend_state(header_hash, spend_status_at_header)
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<OutputMinedInfo>, ChainStorageError> {
+        Ok(self.read_access()?.fetch_output(output_hash))
+    }
+
+    fn fetch_input(&self, output_hash: &HashOutput) -> Result<Option<InputMinedInfo>, ChainStorageError> {
+        Ok(self.read_access()?.fetch_input(output_hash))
+    }
+
+    fn fetch_unspent_output_hash_by_commitment(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<HashOutput>, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .utxo_commitment_index
+            .get(commitment.as_bytes())
+            .copied())
+    }
+
+    fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .utxos
+            .range(block_key_range(header_hash))
+            .map(|(_, row)| row.output.clone())
+            .collect())
+    }
+
+    fn fetch_block_filter(&self, header_hash: &HashOutput) -> Result<Option<BlockFilter>, ChainStorageError> {
+        let inner = self.read_access()?;
+        let has_body = inner
+            .block_hashes
+            .get(header_hash)
+            .map(|height| inner.block_accumulated_data.contains_key(height))
+            .unwrap_or(false);
+        if !has_body {
+            return Ok(None);
+        }
+        let filter = BlockFilter::from_outputs(
+            header_hash,
+            inner
+                .utxos
+                .range(block_key_range(header_hash))
+                .map(|(_, row)| &row.output),
+        );
+        Ok(Some(filter))
+    }
+
+    fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .inputs
+            .range(block_key_range(header_hash))
+            .map(|(_, row)| row.input.clone())
+            .collect())
+    }
+
+    fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
+        match tree {
+            MmrTree::Kernel => Ok(self.read_access()?.kernels.len() as u64),
+        }
+    }
+
+    fn orphan_count(&self) -> Result<usize, ChainStorageError> {
+        Ok(self.read_access()?.orphans.len())
+    }
+
+    fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
+        self.read_access()?.fetch_last_header().cloned().ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })
+    }
+
+    fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
+        let mut inner = self.write_access()?;
+        let mut staged = inner.clone();
+        let num_deleted = staged.clear_all_pending_headers()?;
+        *inner = staged;
+        Ok(num_deleted)
+    }
+
+    fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let inner = self.read_access()?;
+        let height = inner.fetch_last_header().map(|h| h.height).ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })?;
+        inner.fetch_chain_header_by_height(height)
+    }
+
+    fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let inner = self.read_access()?;
+        let metadata = inner.fetch_chain_metadata()?;
+        inner.fetch_chain_header_by_height(metadata.best_block_height())
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        self.read_access()?.fetch_chain_metadata()
+    }
+
+    fn utxo_count(&self) -> Result<usize, ChainStorageError> {
+        Ok(self.read_access()?.utxo_commitment_index.len())
+    }
+
+    fn kernel_count(&self) -> Result<usize, ChainStorageError> {
+        Ok(self.read_access()?.kernels.len())
+    }
+
+    fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
+        let inner = self.read_access()?;
+        if !inner.orphan_chain_tips.contains_key(hash) {
+            return Ok(None);
+        }
+        inner.fetch_orphan_chain_header(hash).map(Some)
+    }
+
+    fn fetch_strongest_orphan_chain_tips(&self) -> Result<Vec<ChainHeader>, ChainStorageError> {
+        let inner = self.read_access()?;
+        let max_value = match inner
+            .orphan_chain_tips
+            .values()
+            .map(|tip| tip.total_accumulated_difficulty)
+            .max()
+        {
+            Some(val) => val,
+            None => return Ok(Vec::new()),
+        };
+
+        inner
+            .orphan_chain_tips
+            .values()
+            .filter(|tip| tip.total_accumulated_difficulty == max_value)
+            .map(|tip| inner.fetch_orphan_chain_header(&tip.hash))
+            .collect()
+    }
+
+    fn fetch_orphan_children_of(&self, hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
+        let inner = self.read_access()?;
+        let children = match inner.orphan_parent_map.get(&hash) {
+            Some(children) => children,
+            None => return Ok(Vec::new()),
+        };
+        children
+            .iter()
+            .map(|child| {
+                inner
+                    .orphans
+                    .get(child)
+                    .cloned()
+                    .ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "Orphan",
+                        field: "hash",
+                        value: child.to_hex(),
+                    })
+            })
+            .collect()
+    }
+
+    fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
+        let inner = self.read_access()?;
+        match (
+            inner.orphans.get(&hash),
+            inner.orphan_header_accumulated_data.get(&hash),
+        ) {
+            (Some(block), Some(accumulated_data)) => {
+                let chain_block = ChainBlock::try_construct(Arc::new(block.clone()), accumulated_data.clone())
+                    .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_orphan_chain_block",
+                        details: format!("Accumulated data mismatch for hash {}", hash.to_hex()),
+                    })?;
+                Ok(Some(chain_block))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn delete_oldest_orphans(
+        &mut self,
+        horizon_height: u64,
+        orphan_storage_capacity: usize,
+    ) -> Result<(), ChainStorageError> {
+        let (num_over_limit, mut orphans) = {
+            let inner = self.read_access()?;
+            let num_over_limit = inner.orphans.len().saturating_sub(orphan_storage_capacity);
+            if num_over_limit == 0 {
+                return Ok(());
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Orphan block storage limit of {} reached, performing cleanup of {} entries.",
+                orphan_storage_capacity,
+                num_over_limit,
+            );
+            let orphans = inner
+                .orphans
+                .iter()
+                .map(|(hash, block)| (block.header.height, *hash))
+                .collect::<Vec<_>>();
+            (num_over_limit, orphans)
+        };
+
+        // Sort the orphans by age, oldest first
+        orphans.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut txn = DbTransaction::new();
+        for (removed_count, (height, block_hash)) in orphans.into_iter().enumerate() {
+            if height > horizon_height && removed_count >= num_over_limit {
+                break;
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Discarding orphan block #{} ({}).",
+                height,
+                block_hash.to_hex()
+            );
+            txn.delete_orphan(block_hash);
+        }
+        self.write(txn)
+    }
+
+    fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
+        Ok(self.read_access()?.monero_seed_height.get(seed).copied().unwrap_or(0))
+    }
+
+    fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
+        let horizon_data =
+            self.read_access()?
+                .metadata
+                .horizon_data
+                .clone()
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "HorizonData",
+                    field: "metadata",
+                    value: "".to_string(),
+                })?;
+        Ok(Some(horizon_data))
+    }
+
+    fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
+        Ok(DbBasicStats::from_entry_counts(
+            self.read_access()?.table_entry_counts(),
+        ))
+    }
+
+    fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .table_entry_counts()
+            .into_iter()
+            .map(|(name, num_entries)| DbSize {
+                name,
+                num_entries: num_entries as u64,
+                total_key_size: 0,
+                total_value_size: 0,
+            })
+            .collect())
+    }
+
+    fn bad_block_exists(&self, block_hash: HashOutput) -> Result<(bool, String), ChainStorageError> {
+        match self.read_access()?.bad_blocks.get(&block_hash) {
+            Some((_height, reason)) => Ok((true, reason.clone())),
+            None => Ok((false, "".to_string())),
+        }
+    }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        Ok(self.read_access()?.reorgs.values().cloned().collect())
+    }
+
+    fn fetch_active_validator_nodes(&self, height: u64) -> Result<Vec<(PublicKey, [u8; 32])>, ChainStorageError> {
+        let constants = self.consensus_manager.consensus_constants(height);
+        let end_epoch = constants.block_height_to_epoch(height);
+        let start_epoch = end_epoch.saturating_sub(constants.validator_node_validity_period_epochs());
+        let start_height = start_epoch.as_u64() * constants.epoch_length();
+        let end_height = end_epoch.as_u64() * constants.epoch_length();
+        Ok(self.read_access()?.get_vn_set(start_height, end_height))
+    }
+
+    fn get_shard_key(&self, height: u64, public_key: PublicKey) -> Result<Option<[u8; 32]>, ChainStorageError> {
+        let constants = self.consensus_manager.consensus_constants(height);
+        let current_epoch = constants.block_height_to_epoch(height);
+        let start_epoch = current_epoch.saturating_sub(constants.validator_node_validity_period_epochs());
+        let start_height = start_epoch.as_u64() * constants.epoch_length();
+        let end_height = current_epoch.as_u64() * constants.epoch_length();
+        Ok(self.read_access()?.get_shard_key(start_height, end_height, &public_key))
+    }
+
+    fn fetch_template_registrations(
+        &self,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError> {
+        Ok(self
+            .read_access()?
+            .template_registrations
+            .range((start_height, FixedHash::zero())..=(end_height, FixedHash::from([0xff; 32])))
+            .map(|(_, entry)| entry.clone())
+            .collect())
+    }
+
+    fn calculate_output_smt_root(&self, changes: &[OutputSmtChange]) -> Result<(FixedHash, u64), ChainStorageError> {
+        let inner = self.read_access()?;
+        let mut smt = PersistentOutputSmt::new(OverlayNodeStore::new(&inner.output_smt));
+        apply_output_smt_changes(&mut smt, changes)?;
+        output_smt_root(&smt)
+    }
+
+    fn output_smt_contains(&self, key: &NodeKey) -> Result<bool, ChainStorageError> {
+        let inner = self.read_access()?;
+        let smt = PersistentOutputSmt::new(OverlayNodeStore::new(&inner.output_smt));
+        Ok(smt.contains(key)?)
+    }
+
+    fn fetch_output_smt_proofs(
+        &self,
+        changes: &[OutputSmtChange],
+        leaves: &[(NodeKey, ValueHash)],
+    ) -> Result<Vec<Option<OutputSmtProof>>, ChainStorageError> {
+        let inner = self.read_access()?;
+        let mut smt = PersistentOutputSmt::new(OverlayNodeStore::new(&inner.output_smt));
+        apply_output_smt_changes(&mut smt, changes)?;
+        leaves
+            .iter()
+            .map(|(key, value)| output_smt_proof(&smt, key, value))
+            .collect()
+    }
+}
@@ -34,6 +34,9 @@ pub mod async_db;
 mod block_add_result;
 
 pub use block_add_result::BlockAddResult;
+
+mod block_export;
+pub use block_export::{BlockExportError, BlockExportHeader, BlockExportReader, BlockExportWriter};
 use primitive_types::U256;
 use serde::{Deserialize, Serialize};
 
@@ -48,6 +51,7 @@ pub use blockchain_database::{
     BlockchainDatabaseConfig,
     MmrRoots,
     Validators,
+    MAX_OUTPUT_SMT_PROOF_DEPTH,
 };
 
 mod blockchain_backend;
@@ -73,6 +77,12 @@ pub use reorg::Reorg;
 mod lmdb_db;
 pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
 
+mod memory_db;
+pub use memory_db::{create_memory_database, MemoryDatabase};
+
+mod output_smt;
+pub use output_smt::OutputSmtChange;
+
 mod stats;
 pub use stats::{DbBasicStats, DbSize, DbStat, DbTotalSizeStats};
 
@@ -81,6 +91,17 @@ mod utxo_mined_info;
 pub use target_difficulties::TargetDifficulties;
 pub use utxo_mined_info::*;
 
+mod utxo_snapshot;
+pub use utxo_snapshot::{
+    create_utxo_snapshot,
+    UtxoSnapshotError,
+    UtxoSnapshotHeader,
+    UtxoSnapshotOutput,
+    UtxoSnapshotReader,
+    UtxoSnapshotSummary,
+    UtxoSnapshotWriter,
+};
+
 mod active_validator_node;
 pub use active_validator_node::ValidatorNodeEntry;
 use tari_common_types::types::HashOutput;
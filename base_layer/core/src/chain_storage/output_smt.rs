@@ -0,0 +1,115 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The output SMT commits to the unspent output set in each block header's `output_mr`. Backends store the tree at
+//! the tip and apply [`OutputSmtChange`]s to it, or to an overlay of it to work out roots and proofs for other states.
+
+use std::convert::TryFrom;
+
+use log::*;
+use tari_common_types::types::{Commitment, FixedHash};
+use tari_mmr::sparse_merkle_tree::{DeleteResult, NodeKey, SMTError, SmtNodeStore, ValueHash};
+use tari_utilities::ByteArray;
+
+use crate::{
+    chain_storage::ChainStorageError,
+    transactions::transaction_components::TransactionOutput,
+    OutputSmtProof,
+    PersistentOutputSmt,
+};
+
+const LOG_TARGET: &str = "c::cs::output_smt";
+
+/// A change to the leaves of the output SMT, which are keyed by output commitment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputSmtChange {
+    /// Adds the leaf of an unspent output. The key must not be in the tree.
+    Insert(NodeKey, ValueHash),
+    /// Removes the leaf of a spent output. The key must be in the tree.
+    Delete(NodeKey),
+}
+
+impl OutputSmtChange {
+    /// The change that adds `output`, mined at `height`, to the tree
+    pub fn insert_output(output: &TransactionOutput, height: u64) -> Result<Self, ChainStorageError> {
+        Ok(OutputSmtChange::Insert(
+            NodeKey::try_from(output.commitment.as_bytes())?,
+            ValueHash::try_from(output.smt_hash(height).as_slice())?,
+        ))
+    }
+
+    /// The change that removes the output with `commitment` from the tree
+    pub fn delete_output(commitment: &Commitment) -> Result<Self, ChainStorageError> {
+        Ok(OutputSmtChange::Delete(NodeKey::try_from(commitment.as_bytes())?))
+    }
+}
+
+/// Applies `changes` to `smt` in order. Deleting a key that is not in the tree fails with
+/// `ChainStorageError::UnspendableInput`.
+pub(crate) fn apply_output_smt_changes<S: SmtNodeStore>(
+    smt: &mut PersistentOutputSmt<S>,
+    changes: &[OutputSmtChange],
+) -> Result<(), ChainStorageError> {
+    for change in changes {
+        match change {
+            OutputSmtChange::Insert(key, value) => {
+                if let Err(e) = smt.insert(key.clone(), value.clone()) {
+                    error!(target: LOG_TARGET, "Output commitment({}) already in SMT", key);
+                    return Err(e.into());
+                }
+            },
+            OutputSmtChange::Delete(key) => {
+                if let DeleteResult::KeyNotFound = smt.delete(key)? {
+                    error!(target: LOG_TARGET, "Could not find input({}) in SMT", key);
+                    return Err(ChainStorageError::UnspendableInput);
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Returns the root of `smt`, as committed to by `output_mr`, and its number of leaves
+pub(crate) fn output_smt_root<S: SmtNodeStore>(
+    smt: &PersistentOutputSmt<S>,
+) -> Result<(FixedHash, u64), ChainStorageError> {
+    Ok((FixedHash::try_from(smt.hash()?.as_slice())?, smt.size()?))
+}
+
+/// Returns an inclusion proof if `smt` has the leaf `key` with `value`, or an exclusion proof if it has no leaf for
+/// `key`. Returns `None` if the key has a different value, e.g. when a later output reuses the commitment.
+pub(crate) fn output_smt_proof<S: SmtNodeStore>(
+    smt: &PersistentOutputSmt<S>,
+    key: &NodeKey,
+    value: &ValueHash,
+) -> Result<Option<OutputSmtProof>, ChainStorageError> {
+    match smt.inclusion_proof(key, value) {
+        Ok(proof) => return Ok(Some(OutputSmtProof::Inclusion(proof))),
+        Err(SMTError::NonViableProof) => {},
+        Err(e) => return Err(e.into()),
+    }
+    match smt.exclusion_proof(key) {
+        Ok(proof) => Ok(Some(OutputSmtProof::Exclusion(proof))),
+        Err(SMTError::NonViableProof) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -47,6 +47,42 @@ impl DbBasicStats {
         }
     }
 
+    /// Builds stats for a backend that has no pages or memory map, such as the in-memory backend. Only the number of
+    /// entries in each table is reported.
+    pub(super) fn from_entry_counts<I: IntoIterator<Item = (&'static str, usize)>>(db_stats: I) -> Self {
+        let db_stats = db_stats
+            .into_iter()
+            .map(|(name, entries)| DbStat {
+                name,
+                psize: 0,
+                depth: 1,
+                branch_pages: 0,
+                leaf_pages: 0,
+                overflow_pages: 0,
+                entries,
+            })
+            .collect::<Vec<_>>();
+        Self {
+            root: DbStat {
+                name: "[root]",
+                psize: 0,
+                depth: 1,
+                branch_pages: 0,
+                leaf_pages: 0,
+                overflow_pages: 0,
+                entries: db_stats.len(),
+            },
+            env_info: EnvInfo {
+                mapsize: 0,
+                last_pgno: 0,
+                last_txnid: 0,
+                maxreaders: 0,
+                numreaders: 0,
+            },
+            db_stats,
+        }
+    }
+
     pub fn root(&self) -> &DbStat {
         &self.root
     }
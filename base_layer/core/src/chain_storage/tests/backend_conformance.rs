@@ -0,0 +1,446 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Conformance tests that are run against every [BlockchainBackend] implementation. Each case is written once and
+//! instantiated for each backend by the `backend_conformance_tests!` macro at the bottom of this file.
+
+use std::sync::Arc;
+
+use tari_common::configuration::Network;
+use tari_test_utils::paths::create_temporary_data_path;
+
+use crate::{
+    blocks::{BlockHeader, ChainBlock, ChainHeader},
+    chain_storage::{
+        BlockchainBackend,
+        BlockchainDatabase,
+        BlockchainDatabaseConfig,
+        ChainStorageError,
+        DbTransaction,
+        Validators,
+    },
+    consensus::{chain_strength_comparer::ChainStrengthComparerBuilder, ConsensusConstantsBuilder, ConsensusManager},
+    output_mr_hash_from_smt,
+    test_helpers::blockchain::{
+        create_chained_blocks,
+        create_main_chain,
+        create_orphan_chain,
+        fetch_tip_output_smt,
+        TempDatabase,
+    },
+    validation::{mocks::MockValidator, DifficultyCalculator},
+};
+
+fn setup(backend: TempDatabase) -> BlockchainDatabase<TempDatabase> {
+    let network = Network::LocalNet;
+    let consensus_constants = ConsensusConstantsBuilder::new(network).build();
+    let rules = ConsensusManager::builder(network)
+        .add_consensus_constants(consensus_constants)
+        .on_ties(ChainStrengthComparerBuilder::new().by_height().build())
+        .build()
+        .unwrap();
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    let config = BlockchainDatabaseConfig {
+        track_reorgs: true,
+        ..Default::default()
+    };
+    BlockchainDatabase::start_new(
+        backend,
+        rules.clone(),
+        validators,
+        config,
+        DifficultyCalculator::new(rules, Default::default()),
+    )
+    .unwrap()
+}
+
+fn genesis_block(db: &BlockchainDatabase<TempDatabase>) -> Arc<ChainBlock> {
+    db.fetch_block(0, true)
+        .unwrap()
+        .try_into_chain_block()
+        .map(Arc::new)
+        .unwrap()
+}
+
+fn assert_tip_smt_matches(db: &BlockchainDatabase<TempDatabase>) {
+    let (root, size) = db.calculate_output_smt_root(&[]).unwrap();
+    let tip = db.fetch_tip_header().unwrap();
+    assert_eq!(root, tip.header().output_mr);
+    let mut rebuilt = fetch_tip_output_smt(db);
+    assert_eq!(output_mr_hash_from_smt(&mut rebuilt).unwrap(), root);
+    assert_eq!(rebuilt.size(), size);
+}
+
+fn it_starts_with_the_genesis_block(backend: TempDatabase) {
+    let db = setup(backend);
+    let metadata = db.get_chain_metadata().unwrap();
+    assert_eq!(metadata.best_block_height(), 0);
+    let genesis = genesis_block(&db);
+    assert_eq!(metadata.best_block_hash(), genesis.hash());
+
+    let access = db.db_read_access().unwrap();
+    assert!(!access.is_empty().unwrap());
+    assert_eq!(
+        access.utxo_count().unwrap(),
+        genesis.block().body.outputs().iter().filter(|o| !o.is_burned()).count()
+    );
+    assert_eq!(access.kernel_count().unwrap(), genesis.block().body.kernels().len());
+    assert_eq!(access.fetch_last_header().unwrap().hash(), genesis.hash());
+    assert!(access.fetch_block_accumulated_data_by_height(0).unwrap().is_some());
+}
+
+async fn it_stores_and_fetches_a_main_chain(backend: TempDatabase) {
+    let db = setup(backend);
+    let (names, blocks) = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120), ("C->B", 1, 120)]).await;
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 3);
+
+    for (height, name) in names.iter().enumerate() {
+        let block = blocks.get(name).unwrap();
+        let height = height as u64 + 1;
+        assert_eq!(db.fetch_chain_header(height).unwrap().hash(), block.hash());
+
+        let outputs = db.fetch_outputs_in_block(*block.hash()).unwrap();
+        assert_eq!(outputs.len(), block.block().body.outputs().len());
+        for output in outputs {
+            let info = db.fetch_output(output.hash()).unwrap().unwrap();
+            assert_eq!(info.mined_height, height);
+            assert_eq!(info.header_hash, *block.hash());
+        }
+
+        let kernels = db.fetch_kernels_in_block(*block.hash()).unwrap();
+        assert_eq!(kernels.len(), block.block().body.kernels().len());
+        for kernel in kernels {
+            let (found, header_hash) = db
+                .fetch_kernel_by_excess_sig(kernel.excess_sig.clone())
+                .unwrap()
+                .unwrap();
+            assert_eq!(found, kernel);
+            assert_eq!(header_hash, *block.hash());
+        }
+    }
+
+    let access = db.db_read_access().unwrap();
+    let tip = blocks.get("C").unwrap();
+    assert_eq!(
+        access.fetch_chain_header_in_all_chains(tip.hash()).unwrap().hash(),
+        tip.hash()
+    );
+    let kernel_mmr_size = tip.header().kernel_mmr_size;
+    assert_eq!(
+        access
+            .fetch_header_containing_kernel_mmr(kernel_mmr_size - 1)
+            .unwrap()
+            .height(),
+        3
+    );
+    assert!(access.fetch_header_containing_kernel_mmr(kernel_mmr_size).is_err());
+    drop(access);
+    assert_tip_smt_matches(&db);
+}
+
+async fn it_tracks_orphan_chain_tips(backend: TempDatabase) {
+    let db = setup(backend);
+    let genesis = genesis_block(&db);
+    let mut smt = fetch_tip_output_smt(&db);
+    let (_, chain) = create_orphan_chain(
+        &db,
+        &[("A->GB", 1, 120), ("B->A", 2, 120), ("B2->A", 1, 120)],
+        genesis,
+        &mut smt,
+    )
+    .await;
+    let a = chain.get("A").unwrap();
+    let b = chain.get("B").unwrap();
+    let b2 = chain.get("B2").unwrap();
+
+    let mut txn = DbTransaction::new();
+    txn.insert_orphan_chain_tip(*b.hash(), b.accumulated_data().total_accumulated_difficulty);
+    txn.insert_orphan_chain_tip(*b2.hash(), b2.accumulated_data().total_accumulated_difficulty);
+    db.write(txn).unwrap();
+    assert_eq!(db.orphan_count().unwrap(), 3);
+
+    {
+        let access = db.db_read_access().unwrap();
+        let strongest = access.fetch_strongest_orphan_chain_tips().unwrap();
+        assert_eq!(strongest.len(), 1);
+        assert_eq!(strongest[0].hash(), b.hash());
+        let mut children = access
+            .fetch_orphan_children_of(*a.hash())
+            .unwrap()
+            .into_iter()
+            .map(|b| b.hash())
+            .collect::<Vec<_>>();
+        children.sort();
+        let mut expected = vec![*b.hash(), *b2.hash()];
+        expected.sort();
+        assert_eq!(children, expected);
+        assert!(access.fetch_orphan_chain_tip_by_hash(a.hash()).unwrap().is_none());
+        assert!(access.fetch_orphan_chain_block(*a.hash()).unwrap().is_some());
+    }
+
+    // Deleting a tip promotes its parent to a tip
+    let mut txn = DbTransaction::new();
+    txn.delete_orphan(*b.hash());
+    db.write(txn).unwrap();
+    let access = db.db_read_access().unwrap();
+    assert_eq!(access.orphan_count().unwrap(), 2);
+    assert!(access.fetch_orphan_chain_tip_by_hash(a.hash()).unwrap().is_some());
+    let strongest = access.fetch_strongest_orphan_chain_tips().unwrap();
+    assert_eq!(strongest.len(), 1);
+    assert_eq!(strongest[0].hash(), b2.hash());
+    drop(access);
+
+    let mut access = db.test_db_write_access().unwrap();
+    access.delete_oldest_orphans(0, 0).unwrap();
+    assert_eq!(access.orphan_count().unwrap(), 0);
+    assert!(access.fetch_strongest_orphan_chain_tips().unwrap().is_empty());
+}
+
+async fn it_reorgs_to_a_stronger_chain(backend: TempDatabase) {
+    let db = setup(backend);
+    let genesis = genesis_block(&db);
+    // Each branch is built on its own copy of the genesis SMT so that every block has the correct output root
+    let mut fork_smt = fetch_tip_output_smt(&db);
+    let (_, fork) = create_chained_blocks(&[("A2->GB", 3, 120)], genesis, &mut fork_smt).await;
+    let (_, main) = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120)]).await;
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 2);
+
+    let a2 = fork.get("A2").unwrap();
+    let result = db.add_block(a2.to_arc_block()).unwrap();
+    result.assert_reorg(1, 2);
+
+    let metadata = db.get_chain_metadata().unwrap();
+    assert_eq!(metadata.best_block_height(), 1);
+    assert_eq!(metadata.best_block_hash(), a2.hash());
+    for name in ["A", "B"] {
+        let block = main.get(name).unwrap();
+        assert!(db.fetch_outputs_in_block(*block.hash()).unwrap().is_empty());
+        assert!(db.fetch_kernels_in_block(*block.hash()).unwrap().is_empty());
+    }
+    assert_eq!(db.fetch_all_reorgs().unwrap().len(), 1);
+    assert_tip_smt_matches(&db);
+}
+
+async fn it_rewinds_to_a_height(backend: TempDatabase) {
+    let db = setup(backend);
+    let (_, blocks) = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120), ("C->B", 1, 120)]).await;
+    let removed = db.rewind_to_height(1).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 1);
+    assert_eq!(db.fetch_last_header().unwrap().height, 1);
+
+    for name in ["B", "C"] {
+        let block = blocks.get(name).unwrap();
+        for output in block.block().body.outputs() {
+            assert!(db.fetch_output(output.hash()).unwrap().is_none());
+        }
+        assert!(db.fetch_kernels_in_block(*block.hash()).unwrap().is_empty());
+    }
+    let a = blocks.get("A").unwrap();
+    for output in a.block().body.outputs() {
+        assert!(db.fetch_output(output.hash()).unwrap().is_some());
+    }
+    assert_tip_smt_matches(&db);
+}
+
+async fn it_rejects_headers_that_do_not_form_a_chain(backend: TempDatabase) {
+    let db = setup(backend);
+    let (_, blocks) = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120)]).await;
+    let a = blocks.get("A").unwrap();
+
+    // A header at a height that is already occupied
+    let mut txn = DbTransaction::new();
+    txn.insert_chain_header(a.to_chain_header());
+    let err = db.write(txn).unwrap_err();
+    assert!(matches!(err, ChainStorageError::InvalidOperation(_)));
+
+    // A header that skips a height
+    let b = blocks.get("B").unwrap();
+    let mut header = BlockHeader::from_previous(b.header());
+    header.height += 1;
+    let mut accum = b.accumulated_data().clone();
+    accum.hash = header.hash();
+    let chain_header = ChainHeader::try_construct(header, accum).unwrap();
+    let mut txn = DbTransaction::new();
+    txn.insert_chain_header(chain_header);
+    let err = db.write(txn).unwrap_err();
+    assert!(matches!(err, ChainStorageError::InvalidOperation(_)));
+    assert_eq!(db.fetch_last_header().unwrap().height, 2);
+    assert_eq!(db.clear_all_pending_headers().unwrap(), 0);
+}
+
+fn it_rolls_back_failed_transactions(backend: TempDatabase) {
+    let db = setup(backend);
+    let genesis = genesis_block(&db);
+    let mut txn = DbTransaction::new();
+    txn.insert_orphan_chain_tip(*genesis.hash(), 1.into());
+    txn.insert_orphan_chain_tip(*genesis.hash(), 1.into());
+    let err = db.write(txn).unwrap_err();
+    assert!(matches!(err, ChainStorageError::KeyExists { .. }));
+
+    // Nothing from the failed transaction was committed
+    let access = db.db_read_access().unwrap();
+    assert!(access.fetch_strongest_orphan_chain_tips().unwrap().is_empty());
+}
+
+fn it_stores_bad_blocks_and_monero_seeds(backend: TempDatabase) {
+    let db = setup(backend);
+    let genesis = genesis_block(&db);
+    assert!(!db.bad_block_exists(*genesis.hash()).unwrap().0);
+    db.add_bad_block(*genesis.hash(), 0, "test".to_string()).unwrap();
+    let (exists, reason) = db.bad_block_exists(*genesis.hash()).unwrap();
+    assert!(exists);
+    assert_eq!(reason, "test");
+
+    let seed = b"test1";
+    for (height, expected) in [(5, 5), (7, 5), (2, 2)] {
+        let mut txn = DbTransaction::new();
+        txn.insert_monero_seed_height(seed.to_vec(), height);
+        db.test_db_write_access().unwrap().write(txn).unwrap();
+        assert_eq!(
+            db.db_read_access()
+                .unwrap()
+                .fetch_monero_seed_first_seen_height(&seed[..])
+                .unwrap(),
+            expected
+        );
+    }
+    assert_eq!(
+        db.db_read_access()
+            .unwrap()
+            .fetch_monero_seed_first_seen_height(b"unknown")
+            .unwrap(),
+        0
+    );
+}
+
+async fn it_reports_table_entry_counts(backend: TempDatabase) {
+    let db = setup(backend);
+    let genesis_output_count = genesis_block(&db).block().body.outputs().len() as u64;
+    let _chain = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120)]).await;
+    let stats = db.fetch_total_size_stats().unwrap();
+    assert_eq!(
+        stats.sizes().iter().find(|s| s.name == "utxos").unwrap().num_entries,
+        genesis_output_count + 2
+    );
+    let stats = db.get_stats().unwrap();
+    assert_eq!(stats.root().depth, 1);
+    assert_eq!(
+        stats.db_stats().iter().find(|s| s.name == "headers").unwrap().entries,
+        3
+    );
+}
+
+macro_rules! backend_conformance_tests {
+    ($($backend:ident => $create:expr),+ $(,)?) => {
+        $(
+            mod $backend {
+                use super::*;
+
+                #[test]
+                fn it_starts_with_the_genesis_block() {
+                    super::it_starts_with_the_genesis_block($create);
+                }
+
+                #[tokio::test]
+                async fn it_stores_and_fetches_a_main_chain() {
+                    super::it_stores_and_fetches_a_main_chain($create).await;
+                }
+
+                #[tokio::test]
+                async fn it_tracks_orphan_chain_tips() {
+                    super::it_tracks_orphan_chain_tips($create).await;
+                }
+
+                #[tokio::test]
+                async fn it_reorgs_to_a_stronger_chain() {
+                    super::it_reorgs_to_a_stronger_chain($create).await;
+                }
+
+                #[tokio::test]
+                async fn it_rewinds_to_a_height() {
+                    super::it_rewinds_to_a_height($create).await;
+                }
+
+                #[tokio::test]
+                async fn it_rejects_headers_that_do_not_form_a_chain() {
+                    super::it_rejects_headers_that_do_not_form_a_chain($create).await;
+                }
+
+                #[test]
+                fn it_rolls_back_failed_transactions() {
+                    super::it_rolls_back_failed_transactions($create);
+                }
+
+                #[test]
+                fn it_stores_bad_blocks_and_monero_seeds() {
+                    super::it_stores_bad_blocks_and_monero_seeds($create);
+                }
+
+                #[tokio::test]
+                async fn it_reports_table_entry_counts() {
+                    super::it_reports_table_entry_counts($create).await;
+                }
+            }
+        )+
+    };
+}
+
+backend_conformance_tests! {
+    lmdb => TempDatabase::new_lmdb(),
+    memory => TempDatabase::new_in_memory(),
+}
+
+#[tokio::test]
+async fn it_reopens_lmdb_with_the_persisted_output_smt() {
+    let temp_path = create_temporary_data_path();
+    {
+        let mut backend = TempDatabase::from_path(&temp_path);
+        backend.disable_delete_on_drop();
+        let db = setup(backend);
+        let _chain = create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 2, 120)]).await;
+        assert_tip_smt_matches(&db);
+    }
+
+    let db = setup(TempDatabase::from_path(&temp_path));
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 2);
+    assert_tip_smt_matches(&db);
+
+    // The reopened tree keeps tracking new blocks and rewinds
+    let tip = db
+        .fetch_block(2, true)
+        .unwrap()
+        .try_into_chain_block()
+        .map(Arc::new)
+        .unwrap();
+    let mut smt = fetch_tip_output_smt(&db);
+    let (_, chain) = create_chained_blocks(&[("C->GB", 1, 120)], tip, &mut smt).await;
+    db.add_block(chain.get("C").unwrap().to_arc_block()).unwrap();
+    assert_eq!(db.get_chain_metadata().unwrap().best_block_height(), 3);
+    assert_tip_smt_matches(&db);
+    db.rewind_to_height(1).unwrap();
+    assert_tip_smt_matches(&db);
+}
@@ -405,6 +405,44 @@ mod fetch_block_hashes_from_header_tip {
     }
 }
 
+mod fetch_block_filters {
+    use super::*;
+    use crate::{
+        blocks::{BlockFilter, BlockFilterElement},
+        transactions::key_manager::create_memory_db_key_manager,
+    };
+
+    #[tokio::test]
+    async fn it_returns_a_filter_matching_each_block() {
+        let db = setup();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (blocks, _) = add_many_chained_blocks(2, &db, &key_manager).await;
+        let filters = db.fetch_block_filters(1, 10).unwrap();
+        assert_eq!(filters.len(), 2);
+        for ((hash, height, filter), block) in filters.iter().zip(&blocks) {
+            assert_eq!(*hash, block.hash());
+            assert_eq!(*height, block.header.height);
+            assert_eq!(*filter, BlockFilter::from_outputs(hash, block.body.outputs()));
+            let elements = block
+                .body
+                .outputs()
+                .iter()
+                .map(|o| BlockFilterElement::commitment(&o.commitment))
+                .collect::<Vec<_>>();
+            assert!(filter.matches_any(hash, &elements));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_errors_if_start_is_beyond_the_tip() {
+        let db = setup();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        add_many_chained_blocks(1, &db, &key_manager).await;
+        let err = db.fetch_block_filters(2, 5).unwrap_err();
+        assert!(matches!(err, ChainStorageError::InvalidArguments { .. }));
+    }
+}
+
 mod get_stats {
     use super::*;
 
@@ -654,3 +692,73 @@ mod validator_node_merkle_root {
         assert_eq!(tip.header().validator_node_mr, merkle_root);
     }
 }
+
+mod fetch_output_smt_proofs {
+    use tari_common_types::types::FixedHash;
+
+    use super::*;
+    use crate::{chain_storage::MAX_OUTPUT_SMT_PROOF_DEPTH, transactions::key_manager::create_memory_db_key_manager};
+
+    #[tokio::test]
+    async fn it_proves_the_spent_status_at_a_recent_header() {
+        let db = setup();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (blocks, outputs) = add_many_chained_blocks(1, &db, &key_manager).await;
+        let (tx, _) = schema_to_transaction(
+            &[txn_schema!(from: vec![outputs[0].clone()], to: vec![50 * T])],
+            &key_manager,
+        )
+        .await;
+        let (script_key_id, wallet_payment_address) = default_coinbase_entities(&key_manager).await;
+        let (block, _) = create_next_block(
+            &db,
+            &blocks[0],
+            tx,
+            &key_manager,
+            &script_key_id,
+            &wallet_payment_address,
+        )
+        .await;
+        db.add_block(block.clone()).unwrap().assert_added();
+        let spent_hash = block.body.inputs()[0].output_hash();
+        let spent_output = db.fetch_output(spent_hash).unwrap().unwrap().output;
+        let new_output_hash = block.body.outputs()[0].hash();
+
+        // The output mined after the header is omitted
+        let proofs = db
+            .fetch_output_smt_proofs(vec![spent_hash, new_output_hash], blocks[0].hash())
+            .unwrap();
+        assert_eq!(proofs.len(), 1);
+        let (hash, mined_height, proof) = &proofs[0];
+        assert_eq!(*hash, spent_hash);
+        assert_eq!(*mined_height, 1);
+        assert!(proof.proves_unspent(
+            &spent_output.commitment,
+            &spent_output.smt_hash(1),
+            &blocks[0].header.output_mr
+        ));
+
+        let proofs = db.fetch_output_smt_proofs(vec![spent_hash], block.hash()).unwrap();
+        assert_eq!(proofs.len(), 1);
+        assert!(proofs[0]
+            .2
+            .proves_not_unspent(&spent_output.commitment, &block.header.output_mr));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_a_header_too_far_below_the_tip() {
+        let db = setup();
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (blocks, _) = add_many_chained_blocks(MAX_OUTPUT_SMT_PROOF_DEPTH as usize + 2, &db, &key_manager).await;
+        let err = db.fetch_output_smt_proofs(vec![], blocks[0].hash()).unwrap_err();
+        assert!(matches!(err, ChainStorageError::InvalidArguments { .. }));
+        assert!(db.fetch_output_smt_proofs(vec![], blocks[1].hash()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_a_header_not_in_the_main_chain() {
+        let db = setup();
+        let err = db.fetch_output_smt_proofs(vec![], FixedHash::zero()).unwrap_err();
+        assert!(matches!(err, ChainStorageError::InvalidArguments { .. }));
+    }
+}
@@ -0,0 +1,550 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A hash-committed snapshot of the pruned chain state at a given height, used to bootstrap a pruned node without
+//! downloading the horizon state from peers.
+//!
+//! The file starts with the magic bytes `TARIUTXO`, a format version byte and a length-prefixed, borsh encoded
+//! [UtxoSnapshotHeader]. This is followed by every kernel in kernel MMR order and every output that is unspent at the
+//! snapshot height in mined height order, each as a little endian `u32` length followed by the borsh encoded record.
+//! The outputs are terminated by a zero length, followed by a length-prefixed [UtxoSnapshotSummary]. The file ends
+//! with a 32 byte hash committing to all of the preceding bytes; this hash identifies the snapshot and can be pinned
+//! in the config of the node loading it.
+//!
+//! The snapshot contents are not trusted: the loading node checks the kernels and outputs against the `kernel_mr` and
+//! `output_mr` of its own header chain before using them.
+
+use std::{
+    fs::File,
+    io,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use blake2::Blake2b;
+use borsh::{BorshDeserialize, BorshSerialize};
+use digest::consts::U32;
+use tari_common_types::types::{Commitment, FixedHash};
+use tari_crypto::hashing::DomainSeparatedHasher;
+use tari_utilities::hex::Hex;
+use thiserror::Error;
+
+use crate::{
+    blocks::BlocksHashDomain,
+    chain_storage::{BlockchainBackend, BlockchainDatabase, ChainStorageError},
+    transactions::transaction_components::{TransactionKernel, TransactionOutput},
+};
+
+const UTXO_SNAPSHOT_MAGIC: [u8; 8] = *b"TARIUTXO";
+const UTXO_SNAPSHOT_VERSION: u8 = 1;
+/// Records larger than this are rejected, so that a corrupt length cannot cause a huge allocation
+const MAX_RECORD_SIZE: u32 = 1024 * 1024;
+
+type UtxoSnapshotHasher = DomainSeparatedHasher<Blake2b<U32>, BlocksHashDomain>;
+
+#[derive(Debug, Error)]
+pub enum UtxoSnapshotError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Chain storage error: {0}")]
+    ChainStorageError(#[from] ChainStorageError),
+    #[error("The file is not a UTXO snapshot")]
+    InvalidMagic,
+    #[error("Unsupported UTXO snapshot version {0} (expected {})", UTXO_SNAPSHOT_VERSION)]
+    UnsupportedVersion(u8),
+    #[error("The UTXO snapshot is for a chain with genesis block {found}, expected {expected}")]
+    GenesisMismatch { expected: String, found: String },
+    #[error(
+        "Cannot create a UTXO snapshot at height {height}. The height must be between the pruned height \
+         {pruned_height} and the tip height {best_block_height}"
+    )]
+    InvalidHeight {
+        height: u64,
+        pruned_height: u64,
+        best_block_height: u64,
+    },
+    #[error("The UTXO snapshot ends unexpectedly")]
+    Truncated,
+    #[error("The UTXO snapshot contains data after the commitment hash")]
+    TrailingData,
+    #[error("A UTXO snapshot record is too large ({0} bytes)")]
+    RecordTooLarge(u32),
+    #[error("Could not decode a UTXO snapshot record: {0}")]
+    InvalidRecord(String),
+    #[error("Expected {expected} kernels in the UTXO snapshot, found {found}")]
+    KernelCountMismatch { expected: u64, found: u64 },
+    #[error("The UTXO snapshot summary does not match its contents")]
+    SummaryMismatch,
+    #[error("The UTXO snapshot commitment hash {found} does not match its contents ({expected})")]
+    HashMismatch { expected: String, found: String },
+}
+
+/// Describes the chain and height that a UTXO snapshot was taken at
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct UtxoSnapshotHeader {
+    /// The hash of the genesis block of the chain the snapshot was taken from
+    pub genesis_hash: FixedHash,
+    /// The height of the block the snapshot was taken at
+    pub height: u64,
+    /// The hash of the block the snapshot was taken at
+    pub block_hash: FixedHash,
+    /// The number of kernels in the snapshot, i.e. the kernel MMR size at the snapshot height
+    pub num_kernels: u64,
+}
+
+impl UtxoSnapshotHeader {
+    /// Checks that the snapshot was taken from the chain with the given genesis block
+    pub fn check_genesis_hash(&self, genesis_hash: &FixedHash) -> Result<(), UtxoSnapshotError> {
+        if self.genesis_hash != *genesis_hash {
+            return Err(UtxoSnapshotError::GenesisMismatch {
+                expected: genesis_hash.to_hex(),
+                found: self.genesis_hash.to_hex(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// An output that is unspent at the snapshot height, along with the block it was mined in
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct UtxoSnapshotOutput {
+    pub mined_height: u64,
+    pub mined_header_hash: FixedHash,
+    pub output: TransactionOutput,
+}
+
+/// The totals of the records in a UTXO snapshot, written after the last output
+#[derive(Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct UtxoSnapshotSummary {
+    pub num_outputs: u64,
+    pub kernel_sum: Commitment,
+    pub utxo_sum: Commitment,
+}
+
+/// Writes a UTXO snapshot file
+pub struct UtxoSnapshotWriter {
+    writer: BufWriter<File>,
+    hasher: UtxoSnapshotHasher,
+    header: UtxoSnapshotHeader,
+    num_kernels: u64,
+    summary: UtxoSnapshotSummary,
+}
+
+impl UtxoSnapshotWriter {
+    /// Creates a new snapshot at `path`, replacing any existing file
+    pub fn create<P: AsRef<Path>>(path: P, header: UtxoSnapshotHeader) -> Result<Self, UtxoSnapshotError> {
+        let mut writer = Self {
+            writer: BufWriter::new(File::create(path)?),
+            hasher: UtxoSnapshotHasher::new_with_label("utxo_snapshot"),
+            header: header.clone(),
+            num_kernels: 0,
+            summary: UtxoSnapshotSummary::default(),
+        };
+        writer.write_bytes(&UTXO_SNAPSHOT_MAGIC)?;
+        writer.write_bytes(&[UTXO_SNAPSHOT_VERSION])?;
+        writer.write_record(&header)?;
+        Ok(writer)
+    }
+
+    /// Appends a kernel. All kernels must be written, in kernel MMR order, before any outputs.
+    pub fn write_kernel(&mut self, kernel: &TransactionKernel) -> Result<(), UtxoSnapshotError> {
+        if self.num_kernels >= self.header.num_kernels {
+            return Err(UtxoSnapshotError::KernelCountMismatch {
+                expected: self.header.num_kernels,
+                found: self.num_kernels + 1,
+            });
+        }
+        self.write_record(kernel)?;
+        self.summary.kernel_sum = &self.summary.kernel_sum + &kernel.excess;
+        self.num_kernels += 1;
+        Ok(())
+    }
+
+    /// Appends an unspent output
+    pub fn write_output(&mut self, output: &UtxoSnapshotOutput) -> Result<(), UtxoSnapshotError> {
+        self.check_kernel_count()?;
+        self.write_record(output)?;
+        self.summary.utxo_sum = &self.summary.utxo_sum + &output.output.commitment;
+        self.summary.num_outputs += 1;
+        Ok(())
+    }
+
+    /// Writes the summary and commitment hash and flushes the file. Returns the commitment hash of the snapshot.
+    pub fn finish(mut self) -> Result<FixedHash, UtxoSnapshotError> {
+        self.check_kernel_count()?;
+        self.write_bytes(&0u32.to_le_bytes())?;
+        let summary = self.summary.clone();
+        self.write_record(&summary)?;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(self.hasher.finalize().as_ref());
+        self.writer.write_all(&hash)?;
+        self.writer.flush()?;
+        self.writer.get_ref().sync_all()?;
+        Ok(FixedHash::from(hash))
+    }
+
+    fn check_kernel_count(&self) -> Result<(), UtxoSnapshotError> {
+        if self.num_kernels != self.header.num_kernels {
+            return Err(UtxoSnapshotError::KernelCountMismatch {
+                expected: self.header.num_kernels,
+                found: self.num_kernels,
+            });
+        }
+        Ok(())
+    }
+
+    fn write_record<T: BorshSerialize>(&mut self, record: &T) -> Result<(), UtxoSnapshotError> {
+        let bytes = borsh::to_vec(record)?;
+        let size = u32::try_from(bytes.len()).unwrap_or(u32::MAX);
+        if size > MAX_RECORD_SIZE {
+            return Err(UtxoSnapshotError::RecordTooLarge(size));
+        }
+        self.write_bytes(&size.to_le_bytes())?;
+        self.write_bytes(&bytes)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), UtxoSnapshotError> {
+        self.writer.write_all(bytes)?;
+        self.hasher.update(bytes);
+        Ok(())
+    }
+}
+
+/// Reads a UTXO snapshot file. The commitment hash and summary are only checked by [UtxoSnapshotReader::finish], so
+/// callers must not treat the records as authentic until it has succeeded.
+pub struct UtxoSnapshotReader {
+    reader: BufReader<File>,
+    hasher: UtxoSnapshotHasher,
+    header: UtxoSnapshotHeader,
+    num_kernels: u64,
+    is_outputs_done: bool,
+    summary: UtxoSnapshotSummary,
+}
+
+impl UtxoSnapshotReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, UtxoSnapshotError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut hasher = UtxoSnapshotHasher::new_with_label("utxo_snapshot");
+        let mut magic = [0u8; 8];
+        read_exact(&mut reader, &mut magic)?;
+        if magic != UTXO_SNAPSHOT_MAGIC {
+            return Err(UtxoSnapshotError::InvalidMagic);
+        }
+        let mut version = [0u8; 1];
+        read_exact(&mut reader, &mut version)?;
+        if version[0] != UTXO_SNAPSHOT_VERSION {
+            return Err(UtxoSnapshotError::UnsupportedVersion(version[0]));
+        }
+        hasher.update(magic);
+        hasher.update(version);
+
+        let mut snapshot_reader = Self {
+            reader,
+            hasher,
+            header: UtxoSnapshotHeader {
+                genesis_hash: FixedHash::zero(),
+                height: 0,
+                block_hash: FixedHash::zero(),
+                num_kernels: 0,
+            },
+            num_kernels: 0,
+            is_outputs_done: false,
+            summary: UtxoSnapshotSummary::default(),
+        };
+        snapshot_reader.header = snapshot_reader
+            .read_record()?
+            .ok_or_else(|| UtxoSnapshotError::InvalidRecord("Missing snapshot header".to_string()))?;
+        Ok(snapshot_reader)
+    }
+
+    pub fn header(&self) -> &UtxoSnapshotHeader {
+        &self.header
+    }
+
+    /// Reads the next kernel, or returns `None` once all kernels have been read
+    pub fn read_kernel(&mut self) -> Result<Option<TransactionKernel>, UtxoSnapshotError> {
+        if self.num_kernels == self.header.num_kernels {
+            return Ok(None);
+        }
+        let kernel: TransactionKernel = self.read_record()?.ok_or(UtxoSnapshotError::KernelCountMismatch {
+            expected: self.header.num_kernels,
+            found: self.num_kernels,
+        })?;
+        self.summary.kernel_sum = &self.summary.kernel_sum + &kernel.excess;
+        self.num_kernels += 1;
+        Ok(Some(kernel))
+    }
+
+    /// Reads the next unspent output, or returns `None` once all outputs have been read. All kernels must have been
+    /// read first.
+    pub fn read_output(&mut self) -> Result<Option<UtxoSnapshotOutput>, UtxoSnapshotError> {
+        if self.num_kernels != self.header.num_kernels {
+            return Err(UtxoSnapshotError::KernelCountMismatch {
+                expected: self.header.num_kernels,
+                found: self.num_kernels,
+            });
+        }
+        if self.is_outputs_done {
+            return Ok(None);
+        }
+        match self.read_record::<UtxoSnapshotOutput>()? {
+            Some(output) => {
+                self.summary.utxo_sum = &self.summary.utxo_sum + &output.output.commitment;
+                self.summary.num_outputs += 1;
+                Ok(Some(output))
+            },
+            None => {
+                self.is_outputs_done = true;
+                Ok(None)
+            },
+        }
+    }
+
+    /// Reads any remaining records and checks the summary and commitment hash. Returns the commitment hash of the
+    /// snapshot.
+    pub fn finish(mut self) -> Result<(UtxoSnapshotSummary, FixedHash), UtxoSnapshotError> {
+        while self.read_kernel()?.is_some() {}
+        while self.read_output()?.is_some() {}
+        let summary: UtxoSnapshotSummary = self
+            .read_record()?
+            .ok_or_else(|| UtxoSnapshotError::InvalidRecord("Missing snapshot summary".to_string()))?;
+        if summary != self.summary {
+            return Err(UtxoSnapshotError::SummaryMismatch);
+        }
+
+        let expected = self.hasher.finalize();
+        let mut found = [0u8; 32];
+        read_exact(&mut self.reader, &mut found)?;
+        if expected.as_ref() != found.as_slice() {
+            return Err(UtxoSnapshotError::HashMismatch {
+                expected: expected.as_ref().to_hex(),
+                found: found.to_hex(),
+            });
+        }
+        if self.reader.read(&mut [0u8; 1])? != 0 {
+            return Err(UtxoSnapshotError::TrailingData);
+        }
+        Ok((summary, FixedHash::from(found)))
+    }
+
+    /// Reads a length-prefixed record, returning `None` for the zero length record that terminates the outputs
+    fn read_record<T: BorshDeserialize>(&mut self) -> Result<Option<T>, UtxoSnapshotError> {
+        let mut size = [0u8; 4];
+        read_exact(&mut self.reader, &mut size)?;
+        self.hasher.update(size);
+        let size = u32::from_le_bytes(size);
+        if size == 0 {
+            return Ok(None);
+        }
+        if size > MAX_RECORD_SIZE {
+            return Err(UtxoSnapshotError::RecordTooLarge(size));
+        }
+        let mut bytes = vec![0u8; size as usize];
+        read_exact(&mut self.reader, &mut bytes)?;
+        self.hasher.update(&bytes);
+        let record = T::try_from_slice(&bytes).map_err(|e| UtxoSnapshotError::InvalidRecord(e.to_string()))?;
+        Ok(Some(record))
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), UtxoSnapshotError> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => UtxoSnapshotError::Truncated,
+        _ => e.into(),
+    })
+}
+
+/// Writes a snapshot of the chain state at `height` to `path`: all kernels up to and including that block and all
+/// outputs that are unspent at that block. The height may not be below the pruned height of the database. Returns
+/// the commitment hash of the snapshot.
+pub fn create_utxo_snapshot<B: BlockchainBackend, P: AsRef<Path>>(
+    db: &BlockchainDatabase<B>,
+    height: u64,
+    path: P,
+) -> Result<FixedHash, UtxoSnapshotError> {
+    let metadata = db.get_chain_metadata()?;
+    if height < metadata.pruned_height() || height > metadata.best_block_height() {
+        return Err(UtxoSnapshotError::InvalidHeight {
+            height,
+            pruned_height: metadata.pruned_height(),
+            best_block_height: metadata.best_block_height(),
+        });
+    }
+    let genesis = db.fetch_chain_header(0)?;
+    let snapshot_header = db.fetch_chain_header(height)?;
+    let mut writer = UtxoSnapshotWriter::create(path, UtxoSnapshotHeader {
+        genesis_hash: *genesis.hash(),
+        height,
+        block_hash: *snapshot_header.hash(),
+        num_kernels: snapshot_header.header().kernel_mmr_size,
+    })?;
+
+    for h in 0..=height {
+        let header = db.fetch_chain_header(h)?;
+        for kernel in db.fetch_kernels_in_block(*header.hash())? {
+            writer.write_kernel(&kernel)?;
+        }
+    }
+    for h in 0..=height {
+        let header = db.fetch_chain_header(h)?;
+        let outputs = db.fetch_outputs_in_block_with_spend_state(*header.hash(), Some(*snapshot_header.hash()))?;
+        for (output, spent) in outputs {
+            if spent || output.is_burned() {
+                continue;
+            }
+            writer.write_output(&UtxoSnapshotOutput {
+                mined_height: h,
+                mined_header_hash: *header.hash(),
+                output,
+            })?;
+        }
+    }
+    writer.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn create_header(num_kernels: u64) -> UtxoSnapshotHeader {
+        UtxoSnapshotHeader {
+            genesis_hash: FixedHash::from([1u8; 32]),
+            height: 10,
+            block_hash: FixedHash::from([2u8; 32]),
+            num_kernels,
+        }
+    }
+
+    fn create_output(mined_height: u64) -> UtxoSnapshotOutput {
+        UtxoSnapshotOutput {
+            mined_height,
+            mined_header_hash: FixedHash::from([3u8; 32]),
+            output: TransactionOutput::default(),
+        }
+    }
+
+    fn write_snapshot(path: &Path) -> FixedHash {
+        let mut writer = UtxoSnapshotWriter::create(path, create_header(2)).unwrap();
+        writer.write_kernel(&TransactionKernel::default()).unwrap();
+        writer.write_kernel(&TransactionKernel::default()).unwrap();
+        for height in [1, 4, 9] {
+            writer.write_output(&create_output(height)).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn it_round_trips_a_snapshot_through_a_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("utxos.bin");
+        let hash = write_snapshot(&path);
+
+        let mut reader = UtxoSnapshotReader::open(&path).unwrap();
+        assert_eq!(*reader.header(), create_header(2));
+        reader.header().check_genesis_hash(&FixedHash::from([1u8; 32])).unwrap();
+        assert!(matches!(
+            reader.header().check_genesis_hash(&FixedHash::zero()),
+            Err(UtxoSnapshotError::GenesisMismatch { .. })
+        ));
+        assert!(matches!(
+            reader.read_output(),
+            Err(UtxoSnapshotError::KernelCountMismatch { expected: 2, found: 0 })
+        ));
+        assert_eq!(reader.read_kernel().unwrap(), Some(TransactionKernel::default()));
+        assert_eq!(reader.read_kernel().unwrap(), Some(TransactionKernel::default()));
+        assert_eq!(reader.read_kernel().unwrap(), None);
+        let heights = std::iter::from_fn(|| reader.read_output().unwrap())
+            .map(|o| o.mined_height)
+            .collect::<Vec<_>>();
+        assert_eq!(heights, vec![1, 4, 9]);
+        let (summary, found_hash) = reader.finish().unwrap();
+        assert_eq!(summary.num_outputs, 3);
+        assert_eq!(found_hash, hash);
+
+        // The hash is deterministic
+        assert_eq!(write_snapshot(&path), hash);
+    }
+
+    #[test]
+    fn it_requires_all_kernels_to_be_written() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("utxos.bin");
+        let mut writer = UtxoSnapshotWriter::create(&path, create_header(1)).unwrap();
+        assert!(matches!(
+            writer.write_output(&create_output(1)),
+            Err(UtxoSnapshotError::KernelCountMismatch { expected: 1, found: 0 })
+        ));
+        writer.write_kernel(&TransactionKernel::default()).unwrap();
+        assert!(matches!(
+            writer.write_kernel(&TransactionKernel::default()),
+            Err(UtxoSnapshotError::KernelCountMismatch { expected: 1, found: 2 })
+        ));
+    }
+
+    #[test]
+    fn it_detects_a_tampered_snapshot() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("utxos.bin");
+        write_snapshot(&path);
+        let bytes = fs::read(&path).unwrap();
+
+        // Changing any byte invalidates the commitment hash
+        let mut tampered = bytes.clone();
+        let mid = tampered.len() / 2;
+        tampered[mid] ^= 0x01;
+        fs::write(&path, &tampered).unwrap();
+        assert!(UtxoSnapshotReader::open(&path).and_then(|r| r.finish()).is_err());
+
+        let mut tampered = bytes.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        fs::write(&path, &tampered).unwrap();
+        assert!(matches!(
+            UtxoSnapshotReader::open(&path).and_then(|r| r.finish()),
+            Err(UtxoSnapshotError::HashMismatch { .. })
+        ));
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        fs::write(&path, &extended).unwrap();
+        assert!(matches!(
+            UtxoSnapshotReader::open(&path).and_then(|r| r.finish()),
+            Err(UtxoSnapshotError::TrailingData)
+        ));
+
+        fs::write(&path, &bytes[..bytes.len() - 10]).unwrap();
+        assert!(matches!(
+            UtxoSnapshotReader::open(&path).and_then(|r| r.finish()),
+            Err(UtxoSnapshotError::Truncated)
+        ));
+
+        fs::write(&path, b"NOTUTXOS").unwrap();
+        assert!(matches!(
+            UtxoSnapshotReader::open(&path),
+            Err(UtxoSnapshotError::InvalidMagic)
+        ));
+    }
+}
@@ -34,6 +34,7 @@ use tari_utilities::epoch_time::EpochTime;
 use crate::{
     borsh::SerializedSize,
     consensus::network::NetworkConsensus,
+    covenants::CovenantFilterVersion,
     proof_of_work::{Difficulty, PowAlgorithm},
     transactions::{
         tari_amount::{uT, MicroMinotari},
@@ -130,6 +131,7 @@ pub struct OutputVersionRange {
     pub outputs: RangeInclusive<TransactionOutputVersion>,
     pub features: RangeInclusive<OutputFeaturesVersion>,
     pub opcode: RangeInclusive<OpcodeVersion>,
+    pub covenant: RangeInclusive<CovenantFilterVersion>,
 }
 
 /// All V0 for Inputs, Outputs + Features, Kernels
@@ -144,6 +146,7 @@ fn version_zero() -> (
         outputs: TransactionOutputVersion::V0..=TransactionOutputVersion::V0,
         features: OutputFeaturesVersion::V0..=OutputFeaturesVersion::V0,
         opcode: OpcodeVersion::V0..=OpcodeVersion::V0,
+        covenant: CovenantFilterVersion::V0..=CovenantFilterVersion::V0,
     };
 
     (input_version_range, output_version_range, kernel_version_range)
@@ -389,7 +392,10 @@ impl ConsensusConstants {
             max_difficulty: Difficulty::min(),
             target_time: 240,
         });
-        let (input_version_range, output_version_range, kernel_version_range) = version_zero();
+        let (input_version_range, mut output_version_range, kernel_version_range) = version_zero();
+        // Localnet permits the latest covenant filters and script opcodes
+        output_version_range.covenant = CovenantFilterVersion::V0..=CovenantFilterVersion::V1;
+        output_version_range.opcode = OpcodeVersion::V0..=OpcodeVersion::V1;
         let consensus_constants = vec![ConsensusConstants {
             effective_from_height: 0,
             coinbase_min_maturity: 2,
@@ -905,6 +911,16 @@ impl ConsensusConstantsBuilder {
         self
     }
 
+    pub fn with_opcode_version_range(mut self, range: RangeInclusive<OpcodeVersion>) -> Self {
+        self.consensus.output_version_range.opcode = range;
+        self
+    }
+
+    pub fn with_covenant_version_range(mut self, range: RangeInclusive<CovenantFilterVersion>) -> Self {
+        self.consensus.output_version_range.covenant = range;
+        self
+    }
+
     pub fn with_blockchain_version(mut self, version: u16) -> Self {
         self.consensus.blockchain_version = version;
         self
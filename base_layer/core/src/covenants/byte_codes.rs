@@ -67,7 +67,7 @@ pub(super) fn is_valid_filter_code(code: u8) -> bool {
 }
 
 /// Array with all possible covenant filter bytecodes.
-pub(super) const ALL_FILTERS: [u8; 10] = [
+pub(super) const ALL_FILTERS: [u8; 14] = [
     FILTER_IDENTITY,
     FILTER_AND,
     FILTER_OR,
@@ -78,6 +78,10 @@ pub(super) const ALL_FILTERS: [u8; 10] = [
     FILTER_FIELDS_HASHED_EQ,
     FILTER_FIELD_EQ,
     FILTER_ABSOLUTE_HEIGHT,
+    FILTER_MINIMUM_VALUE_PROMISE_RANGE,
+    FILTER_RELATIVE_MATURITY,
+    FILTER_SCRIPT_TEMPLATE,
+    FILTER_OUTPUT_COUNT,
 ];
 
 /// Identity filter.
@@ -101,6 +105,14 @@ pub const FILTER_FIELDS_HASHED_EQ: u8 = 0x32;
 pub const FILTER_FIELD_EQ: u8 = 0x33;
 /// Absolute height filter.
 pub const FILTER_ABSOLUTE_HEIGHT: u8 = 0x34;
+/// Minimum value promise range filter.
+pub const FILTER_MINIMUM_VALUE_PROMISE_RANGE: u8 = 0x35;
+/// Relative maturity filter.
+pub const FILTER_RELATIVE_MATURITY: u8 = 0x36;
+/// Script template filter.
+pub const FILTER_SCRIPT_TEMPLATE: u8 = 0x37;
+/// Output count filter.
+pub const FILTER_OUTPUT_COUNT: u8 = 0x38;
 
 //---------------------------------- FIELD byte codes --------------------------------------------//
 /// Field commitment.
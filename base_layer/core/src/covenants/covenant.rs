@@ -33,11 +33,12 @@ use super::decoder::CovenantDecodeError;
 use crate::{
     common::byte_counter::ByteCounter,
     covenants::{
+        arguments::CovenantArg,
         context::CovenantContext,
         decoder::CovenantTokenDecoder,
         encoder::CovenantTokenEncoder,
         error::CovenantError,
-        filters::Filter,
+        filters::{CovenantFilter, Filter},
         output_set::OutputSet,
         token::{CovenantToken, CovenantTokenCollection},
     },
@@ -164,6 +165,22 @@ impl Covenant {
         &self.tokens
     }
 
+    /// Returns all filters in this covenant, including the filters of any nested covenant arguments.
+    pub fn filters(&self) -> Vec<&CovenantFilter> {
+        let mut filters = Vec::new();
+        for token in self.tokens.iter() {
+            match token {
+                CovenantToken::Filter(filter) => filters.push(filter),
+                CovenantToken::Arg(arg) => {
+                    if let CovenantArg::Covenant(covenant) = &**arg {
+                        filters.extend(covenant.filters());
+                    }
+                },
+            }
+        }
+        filters
+    }
+
     /// Outputs the length of `tokens` field.
     pub fn num_tokens(&self) -> usize {
         self.tokens.len()
@@ -29,9 +29,13 @@ use super::{
     fields_hashed_eq::FieldsHashedEqFilter,
     fields_preserved::FieldsPreservedFilter,
     identity::IdentityFilter,
+    minimum_value_promise_range::MinimumValuePromiseRangeFilter,
     not::NotFilter,
     or::OrFilter,
+    output_count::OutputCountFilter,
     output_hash_eq::OutputHashEqFilter,
+    relative_maturity::RelativeMaturityFilter,
+    script_template::ScriptTemplateFilter,
     xor::XorFilter,
 };
 use crate::covenants::{
@@ -61,6 +65,19 @@ pub enum CovenantFilter {
     FieldEq(FieldEqFilter),
     FieldsHashedEq(FieldsHashedEqFilter),
     AbsoluteHeight(AbsoluteHeightFilter),
+    MinimumValuePromiseRange(MinimumValuePromiseRangeFilter),
+    RelativeMaturity(RelativeMaturityFilter),
+    ScriptTemplate(ScriptTemplateFilter),
+    OutputCount(OutputCountFilter),
+}
+
+/// The covenant filter version. Filters introduced in a later version are only accepted once the consensus constants
+/// permit that version.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[repr(u8)]
+pub enum CovenantFilterVersion {
+    V0 = 0,
+    V1 = 1,
 }
 
 impl CovenantFilter {
@@ -75,6 +92,20 @@ impl CovenantFilter {
         Ok(())
     }
 
+    /// Returns the version in which this filter was introduced.
+    pub fn get_version(&self) -> CovenantFilterVersion {
+        #[allow(clippy::enum_glob_use)]
+        use CovenantFilter::*;
+
+        match self {
+            Identity(_) | And(_) | Or(_) | Xor(_) | Not(_) | OutputHashEq(_) | FieldsPreserved(_) | FieldEq(_) |
+            FieldsHashedEq(_) | AbsoluteHeight(_) => CovenantFilterVersion::V0,
+            MinimumValuePromiseRange(_) | RelativeMaturity(_) | ScriptTemplate(_) | OutputCount(_) => {
+                CovenantFilterVersion::V1
+            },
+        }
+    }
+
     fn as_byte_code(&self) -> u8 {
         use byte_codes::*;
         #[allow(clippy::enum_glob_use)]
@@ -91,6 +122,10 @@ impl CovenantFilter {
             FieldEq(_) => FILTER_FIELD_EQ,
             FieldsHashedEq(_) => FILTER_FIELDS_HASHED_EQ,
             AbsoluteHeight(_) => FILTER_ABSOLUTE_HEIGHT,
+            MinimumValuePromiseRange(_) => FILTER_MINIMUM_VALUE_PROMISE_RANGE,
+            RelativeMaturity(_) => FILTER_RELATIVE_MATURITY,
+            ScriptTemplate(_) => FILTER_SCRIPT_TEMPLATE,
+            OutputCount(_) => FILTER_OUTPUT_COUNT,
         }
     }
 
@@ -108,6 +143,10 @@ impl CovenantFilter {
             FILTER_FIELD_EQ => Ok(Self::field_eq()),
             FILTER_FIELDS_HASHED_EQ => Ok(Self::fields_hashed_eq()),
             FILTER_ABSOLUTE_HEIGHT => Ok(Self::absolute_height()),
+            FILTER_MINIMUM_VALUE_PROMISE_RANGE => Ok(Self::minimum_value_promise_range()),
+            FILTER_RELATIVE_MATURITY => Ok(Self::relative_maturity()),
+            FILTER_SCRIPT_TEMPLATE => Ok(Self::script_template()),
+            FILTER_OUTPUT_COUNT => Ok(Self::output_count()),
             _ => Err(CovenantDecodeError::UnknownFilterByteCode { code }),
         }
     }
@@ -161,6 +200,26 @@ impl CovenantFilter {
     pub fn absolute_height() -> Self {
         CovenantFilter::AbsoluteHeight(AbsoluteHeightFilter)
     }
+
+    /// Return the "minimum value promise range" covenant filter.
+    pub fn minimum_value_promise_range() -> Self {
+        CovenantFilter::MinimumValuePromiseRange(MinimumValuePromiseRangeFilter)
+    }
+
+    /// Return the "relative maturity" covenant filter.
+    pub fn relative_maturity() -> Self {
+        CovenantFilter::RelativeMaturity(RelativeMaturityFilter)
+    }
+
+    /// Return the "script template" covenant filter.
+    pub fn script_template() -> Self {
+        CovenantFilter::ScriptTemplate(ScriptTemplateFilter)
+    }
+
+    /// Return the "output count" covenant filter.
+    pub fn output_count() -> Self {
+        CovenantFilter::OutputCount(OutputCountFilter)
+    }
 }
 
 impl Filter for CovenantFilter {
@@ -179,6 +238,10 @@ impl Filter for CovenantFilter {
             FieldEq(fields_eq) => fields_eq.filter(context, output_set),
             FieldsHashedEq(fields_hashed_eq) => fields_hashed_eq.filter(context, output_set),
             AbsoluteHeight(abs_height) => abs_height.filter(context, output_set),
+            MinimumValuePromiseRange(value_range) => value_range.filter(context, output_set),
+            RelativeMaturity(rel_maturity) => rel_maturity.filter(context, output_set),
+            ScriptTemplate(script_template) => script_template.filter(context, output_set),
+            OutputCount(output_count) => output_count.filter(context, output_set),
         }
     }
 }
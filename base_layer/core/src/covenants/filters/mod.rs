@@ -26,9 +26,13 @@ mod field_eq;
 mod fields_hashed_eq;
 mod fields_preserved;
 mod identity;
+mod minimum_value_promise_range;
 mod not;
 mod or;
+mod output_count;
 mod output_hash_eq;
+mod relative_maturity;
+mod script_template;
 mod xor;
 
 pub use absolute_height::AbsoluteHeightFilter;
@@ -37,13 +41,17 @@ pub use field_eq::FieldEqFilter;
 pub use fields_hashed_eq::FieldsHashedEqFilter;
 pub use fields_preserved::FieldsPreservedFilter;
 pub use identity::IdentityFilter;
+pub use minimum_value_promise_range::MinimumValuePromiseRangeFilter;
 pub use not::NotFilter;
 pub use or::OrFilter;
+pub use output_count::OutputCountFilter;
 pub use output_hash_eq::OutputHashEqFilter;
+pub use relative_maturity::RelativeMaturityFilter;
+pub use script_template::ScriptTemplateFilter;
 pub use xor::XorFilter;
 
 mod filter;
-pub use filter::{CovenantFilter, Filter};
+pub use filter::{CovenantFilter, CovenantFilterVersion, Filter};
 
 #[cfg(test)]
 mod test;
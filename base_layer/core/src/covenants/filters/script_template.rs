@@ -0,0 +1,60 @@
+//  Copyright 2024, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_common_types::types::PublicKey;
+use tari_script::{Opcode, TariScript};
+
+use crate::covenants::{context::CovenantContext, error::CovenantError, filters::Filter, output_set::OutputSet};
+
+/// Holding struct for the "script template" filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptTemplateFilter;
+
+impl Filter for ScriptTemplateFilter {
+    // The script template filter removes all outputs in the mutable output set whose script does not match the
+    // template script provided in the covenant context. See `matches_template` for the placeholder rules.
+    fn filter(&self, context: &mut CovenantContext<'_>, output_set: &mut OutputSet<'_>) -> Result<(), CovenantError> {
+        let template = context.next_arg()?.require_tariscript()?;
+        output_set.retain(|output| Ok(matches_template(&template, &output.script)))?;
+
+        Ok(())
+    }
+}
+
+/// Returns true if `script` matches `template` opcode for opcode. A `PushPubKey` of the default (zero) public key and
+/// a `PushHash` of all zero bytes in the template are placeholders that match any `PushPubKey` and `PushHash`
+/// respectively. All other opcodes must be equal.
+fn matches_template(template: &TariScript, script: &TariScript) -> bool {
+    let template = template.as_slice();
+    let script = script.as_slice();
+    template.len() == script.len() &&
+        template
+            .iter()
+            .zip(script)
+            .all(|(expected, actual)| match (expected, actual) {
+                (Opcode::PushPubKey(placeholder), Opcode::PushPubKey(_)) if **placeholder == PublicKey::default() => {
+                    true
+                },
+                (Opcode::PushHash(placeholder), Opcode::PushHash(_)) if **placeholder == [0u8; 32] => true,
+                _ => expected == actual,
+            })
+}
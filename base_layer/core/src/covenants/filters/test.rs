@@ -52,3 +52,313 @@ where
     output_mod(&mut outputs);
     (context, outputs)
 }
+
+mod minimum_value_promise_range {
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::{
+            error::CovenantError,
+            filters::{Filter, MinimumValuePromiseRangeFilter},
+            output_set::OutputSet,
+            test::create_input,
+        },
+        transactions::{key_manager::create_memory_db_key_manager, tari_amount::MicroMinotari},
+    };
+
+    #[tokio::test]
+    async fn it_filters_outputs_outside_of_the_range() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let covenant = covenant!(minimum_value_promise_range(@uint(100), @uint(200))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(
+            &covenant,
+            &input,
+            0,
+            |outputs| {
+                outputs[3].minimum_value_promise = MicroMinotari(100);
+                outputs[4].minimum_value_promise = MicroMinotari(150);
+                outputs[5].minimum_value_promise = MicroMinotari(200);
+                outputs[6].minimum_value_promise = MicroMinotari(201);
+            },
+            &key_manager,
+        )
+        .await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        MinimumValuePromiseRangeFilter
+            .filter(&mut context, &mut output_set)
+            .unwrap();
+
+        assert_eq!(output_set.len(), 3);
+        assert_eq!(output_set.get_selected_indexes(), vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn it_errors_if_the_range_is_inverted() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let covenant = covenant!(minimum_value_promise_range(@uint(200), @uint(100))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 0, |_| {}, &key_manager).await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        let err = MinimumValuePromiseRangeFilter
+            .filter(&mut context, &mut output_set)
+            .unwrap_err();
+        assert!(matches!(err, CovenantError::InvalidArgument { .. }));
+    }
+}
+
+mod relative_maturity {
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::{
+            filters::{Filter, RelativeMaturityFilter},
+            output_set::OutputSet,
+            test::create_input,
+        },
+        transactions::key_manager::create_memory_db_key_manager,
+    };
+
+    #[tokio::test]
+    async fn it_filters_outputs_that_mature_too_soon_after_the_spending_block() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let covenant = covenant!(relative_maturity(@uint(10))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(
+            &covenant,
+            &input,
+            100,
+            |outputs| {
+                outputs[2].features.maturity = 110;
+                outputs[3].features.maturity = 200;
+                outputs[4].features.maturity = 109;
+            },
+            &key_manager,
+        )
+        .await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        RelativeMaturityFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert_eq!(output_set.get_selected_indexes(), vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn it_errors_if_the_relative_height_overflows() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let covenant = covenant!(relative_maturity(@uint(u64::MAX))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 1, |_| {}, &key_manager).await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        RelativeMaturityFilter
+            .filter(&mut context, &mut output_set)
+            .unwrap_err();
+    }
+}
+
+mod script_template {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::PublicKey;
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_script::script;
+
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::{
+            filters::{Filter, ScriptTemplateFilter},
+            output_set::OutputSet,
+            test::create_input,
+        },
+        transactions::key_manager::create_memory_db_key_manager,
+    };
+
+    #[tokio::test]
+    async fn it_matches_public_key_placeholders() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (_, pk) = PublicKey::random_keypair(&mut OsRng);
+        let template = script!(Nop PushPubKey(Box::default())).unwrap();
+        let covenant = covenant!(script_template(@script(template))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(
+            &covenant,
+            &input,
+            0,
+            |outputs| {
+                outputs[5].script = script!(Nop PushPubKey(Box::new(pk.clone()))).unwrap();
+                outputs[6].script = script!(Nop PushPubKey(Box::new(pk.clone())) Drop).unwrap();
+                outputs[7].script = script!(PushPubKey(Box::new(pk.clone()))).unwrap();
+                outputs[8].script = script!(Nop PushHash(Box::new([1u8; 32]))).unwrap();
+            },
+            &key_manager,
+        )
+        .await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        ScriptTemplateFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert_eq!(output_set.get_selected_indexes(), vec![5]);
+    }
+
+    #[tokio::test]
+    async fn it_matches_hash_placeholders_and_exact_opcodes() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let (_, pk) = PublicKey::random_keypair(&mut OsRng);
+        let template = script!(PushHash(Box::new([0u8; 32])) Drop PushPubKey(Box::new(pk.clone()))).unwrap();
+        let covenant = covenant!(script_template(@script(template))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(
+            &covenant,
+            &input,
+            0,
+            |outputs| {
+                outputs[1].script =
+                    script!(PushHash(Box::new([1u8; 32])) Drop PushPubKey(Box::new(pk.clone()))).unwrap();
+                outputs[2].script = script!(PushHash(Box::new([2u8; 32])) Drop PushPubKey(Box::default())).unwrap();
+                outputs[3].script =
+                    script!(PushHash(Box::new([3u8; 32])) Dup PushPubKey(Box::new(pk.clone()))).unwrap();
+            },
+            &key_manager,
+        )
+        .await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        ScriptTemplateFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert_eq!(output_set.get_selected_indexes(), vec![1]);
+    }
+}
+
+mod output_count {
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::{
+            filters::{Filter, OutputCountFilter},
+            output_set::OutputSet,
+            test::create_input,
+        },
+        transactions::key_manager::create_memory_db_key_manager,
+    };
+
+    #[tokio::test]
+    async fn it_filters_all_out_if_count_is_outside_of_the_range() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let covenant = covenant!(output_count(@uint(1), @uint(5))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 0, |_| {}, &key_manager).await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        OutputCountFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert!(output_set.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_filters_all_in_if_count_is_within_the_range() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let covenant = covenant!(output_count(@uint(10), @uint(10))).unwrap();
+        let input = create_input(&key_manager).await;
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 0, |_| {}, &key_manager).await;
+
+        let mut output_set = OutputSet::new(&outputs);
+        OutputCountFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert_eq!(output_set.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn it_counts_the_outputs_matched_by_a_preceding_filter() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let mut outputs = create_outputs(10, Default::default(), &key_manager).await;
+        outputs[3].features.maturity = 42;
+        outputs[7].features.maturity = 42;
+        let input = create_input(&key_manager).await;
+
+        let covenant =
+            covenant!(and(field_eq(@field::features_maturity, @uint(42)), output_count(@uint(1), @uint(1)))).unwrap();
+        covenant.execute(0, &input, &outputs).unwrap_err();
+
+        let covenant =
+            covenant!(and(field_eq(@field::features_maturity, @uint(42)), output_count(@uint(1), @uint(2)))).unwrap();
+        assert_eq!(covenant.execute(0, &input, &outputs).unwrap(), 2);
+    }
+}
+
+mod byte_codes_and_versioning {
+    use tari_common::configuration::Network;
+    use tari_script::script;
+
+    use super::*;
+    use crate::{
+        consensus::ConsensusConstantsBuilder,
+        covenant,
+        covenants::{
+            byte_codes::*,
+            filters::{CovenantFilter, CovenantFilterVersion},
+        },
+        transactions::key_manager::create_memory_db_key_manager,
+        validation::helpers::validate_output_version,
+    };
+
+    #[test]
+    fn it_encodes_and_decodes_the_new_filters() {
+        let covenant = covenant!(and(
+            and(minimum_value_promise_range(@uint(1), @uint(2)), relative_maturity(@uint(3))),
+            and(script_template(@script(script!(Nop).unwrap())), output_count(@uint(1), @uint(1)))
+        ))
+        .unwrap();
+        let bytes = covenant.to_bytes();
+        for code in [
+            FILTER_MINIMUM_VALUE_PROMISE_RANGE,
+            FILTER_RELATIVE_MATURITY,
+            FILTER_SCRIPT_TEMPLATE,
+            FILTER_OUTPUT_COUNT,
+        ] {
+            assert!(bytes.contains(&code));
+        }
+        assert_eq!(Covenant::from_bytes(&mut bytes.as_slice()).unwrap(), covenant);
+    }
+
+    #[test]
+    fn it_versions_the_new_filters() {
+        assert_eq!(
+            CovenantFilter::absolute_height().get_version(),
+            CovenantFilterVersion::V0
+        );
+        for filter in [
+            CovenantFilter::minimum_value_promise_range(),
+            CovenantFilter::relative_maturity(),
+            CovenantFilter::script_template(),
+            CovenantFilter::output_count(),
+        ] {
+            assert_eq!(filter.get_version(), CovenantFilterVersion::V1);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_gates_the_new_filters_by_consensus() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let mut outputs = create_outputs(2, Default::default(), &key_manager).await;
+        outputs[0].covenant = covenant!(absolute_height(@uint(1))).unwrap();
+        // New filters nested in a covenant argument are also gated
+        outputs[1].covenant = covenant!(field_eq(
+            @field::covenant,
+            @covenant(covenant!(relative_maturity(@uint(1))).unwrap())
+        ))
+        .unwrap();
+
+        let v0_only = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_covenant_version_range(CovenantFilterVersion::V0..=CovenantFilterVersion::V0)
+            .build();
+        validate_output_version(&v0_only, &outputs[0]).unwrap();
+        validate_output_version(&v0_only, &outputs[1]).unwrap_err();
+
+        let v1 = ConsensusConstantsBuilder::new(Network::LocalNet).build();
+        validate_output_version(&v1, &outputs[0]).unwrap();
+        validate_output_version(&v1, &outputs[1]).unwrap();
+    }
+}
@@ -44,6 +44,7 @@ pub use error::CovenantError;
 // Used in macro
 #[allow(unused_imports)]
 pub(crate) use fields::OutputField;
+pub use filters::{CovenantFilter, CovenantFilterVersion};
 pub use token::CovenantToken;
 
 #[macro_use]
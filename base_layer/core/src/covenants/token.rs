@@ -38,9 +38,13 @@ use crate::{
             FieldsHashedEqFilter,
             FieldsPreservedFilter,
             IdentityFilter,
+            MinimumValuePromiseRangeFilter,
             NotFilter,
             OrFilter,
+            OutputCountFilter,
             OutputHashEqFilter,
+            RelativeMaturityFilter,
+            ScriptTemplateFilter,
             XorFilter,
         },
         Covenant,
@@ -162,6 +166,30 @@ impl CovenantToken {
         CovenantFilter::AbsoluteHeight(AbsoluteHeightFilter).into()
     }
 
+    #[allow(dead_code)]
+    /// Helper for creating a new instance wrapping an `MinimumValuePromiseRangeFilter`.
+    pub fn minimum_value_promise_range() -> Self {
+        CovenantFilter::MinimumValuePromiseRange(MinimumValuePromiseRangeFilter).into()
+    }
+
+    #[allow(dead_code)]
+    /// Helper for creating a new instance wrapping an `RelativeMaturityFilter`.
+    pub fn relative_maturity() -> Self {
+        CovenantFilter::RelativeMaturity(RelativeMaturityFilter).into()
+    }
+
+    #[allow(dead_code)]
+    /// Helper for creating a new instance wrapping an `ScriptTemplateFilter`.
+    pub fn script_template() -> Self {
+        CovenantFilter::ScriptTemplate(ScriptTemplateFilter).into()
+    }
+
+    #[allow(dead_code)]
+    /// Helper for creating a new instance wrapping an `OutputCountFilter`.
+    pub fn output_count() -> Self {
+        CovenantFilter::OutputCount(OutputCountFilter).into()
+    }
+
     #[allow(dead_code)]
     /// Helper for creating a new instance wrapping an `HashFilter`.
     pub fn hash(hash: FixedHash) -> Self {
@@ -51,9 +51,13 @@ pub mod transactions;
 
 mod common;
 
+#[cfg(any(feature = "base_node", feature = "base_node_proto"))]
+mod output_smt_proof;
 #[cfg(feature = "base_node")]
 pub use common::AuxChainHashes;
 pub use common::{borsh, one_sided, ConfidentialOutputHasher};
+#[cfg(any(feature = "base_node", feature = "base_node_proto"))]
+pub use output_smt_proof::{OutputSmtHashDomain, OutputSmtHasherBlake256, OutputSmtProof};
 
 #[cfg(feature = "base_node")]
 mod domain_hashing {
@@ -67,27 +71,27 @@ mod domain_hashing {
     use tari_mmr::{
         error::MerkleMountainRangeError,
         pruned_hashset::PrunedHashSet,
-        sparse_merkle_tree::SparseMerkleTree,
+        sparse_merkle_tree::{PersistentSparseMerkleTree, SparseMerkleTree},
         BalancedBinaryMerkleTree,
         Hash,
         MerkleMountainRange,
     };
 
+    use crate::OutputSmtHasherBlake256;
+
     hash_domain!(KernelMmrHashDomain, "com.tari.base_layer.core.kernel_mmr", 1);
 
     pub type KernelMmrHasherBlake256 = DomainSeparatedHasher<Blake2b<U32>, KernelMmrHashDomain>;
     pub type KernelMmr = MerkleMountainRange<KernelMmrHasherBlake256, Vec<Hash>>;
     pub type PrunedKernelMmr = MerkleMountainRange<KernelMmrHasherBlake256, PrunedHashSet>;
 
-    hash_domain!(OutputSmtHashDomain, "com.tari.base_layer.core.output_smt", 1);
-    pub type OutputSmtHasherBlake256 = DomainSeparatedHasher<Blake2b<U32>, OutputSmtHashDomain>;
-
     hash_domain!(InputMmrHashDomain, "com.tari.base_layer.core.input_mmr", 1);
     pub type InputMmrHasherBlake256 = DomainSeparatedHasher<Blake2b<U32>, InputMmrHashDomain>;
     pub type PrunedInputMmr = MerkleMountainRange<InputMmrHasherBlake256, PrunedHashSet>;
     pub type PrunedOutputMmr = MerkleMountainRange<InputMmrHasherBlake256, PrunedHashSet>;
 
     pub type OutputSmt = SparseMerkleTree<OutputSmtHasherBlake256>;
+    pub type PersistentOutputSmt<S> = PersistentSparseMerkleTree<OutputSmtHasherBlake256, S>;
 
     pub type ValidatorNodeBmtHasherBlake256 = DomainSeparatedHasher<Blake2b<U32>, ValidatorNodeBmtHashDomain>;
     pub type ValidatorNodeBMT = BalancedBinaryMerkleTree<ValidatorNodeBmtHasherBlake256>;
@@ -20,8 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::{path::PathBuf, time::Duration};
+
 use serde::{Deserialize, Serialize};
-use tari_common::SubConfigPath;
+use tari_common::{configuration::serializers, SubConfigPath};
 
 use crate::mempool::{reorg_pool::ReorgPoolConfig, unconfirmed_pool::UnconfirmedPoolConfig};
 
@@ -33,6 +35,7 @@ pub struct MempoolConfig {
     pub unconfirmed_pool: UnconfirmedPoolConfig,
     pub reorg_pool: ReorgPoolConfig,
     pub service: MempoolServiceConfig,
+    pub persistence: MempoolPersistenceConfig,
 }
 
 impl SubConfigPath for MempoolConfig {
@@ -64,8 +67,34 @@ impl Default for MempoolServiceConfig {
     }
 }
 
+/// Configuration for the on-disk mempool snapshot.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MempoolPersistenceConfig {
+    /// Write the mempool to disk periodically and on shutdown, and restore it on startup. Default: false
+    pub enabled: bool,
+    /// The snapshot file. Relative paths are resolved against the base node data directory.
+    /// Default: mempool_snapshot.bin
+    pub snapshot_file: PathBuf,
+    /// The interval between periodic snapshots. Default: 300 seconds
+    #[serde(with = "serializers::seconds")]
+    pub snapshot_interval: Duration,
+}
+
+impl Default for MempoolPersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snapshot_file: PathBuf::from("mempool_snapshot.bin"),
+            snapshot_interval: Duration::from_secs(300),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::time::Duration;
+
     use config::Config;
     use tari_common::DefaultConfigLoader;
 
@@ -77,12 +106,16 @@ mod test {
         let config = Config::builder()
             .set_override("mempool.unconfirmed_pool.storage_capacity", 3)
             .unwrap()
+            .set_override("mempool.persistence.snapshot_interval", 60)
+            .unwrap()
             .build()
             .unwrap();
 
         let my_config = MempoolConfig::load_from(&config).expect("Could not load configuration");
         // [ ] mempool.mainnet, [X]  mempool = 3, [X] Default
         assert_eq!(my_config.unconfirmed_pool.storage_capacity, 3);
+        assert_eq!(my_config.persistence.snapshot_interval, Duration::from_secs(60));
+        assert!(!my_config.persistence.enabled);
         // [ ] mempool.mainnet, [ ]  mempool, [X] Default = 512
         // [ ] mempool.mainnet, [ ]  mempool, [X] Default = 10s
         assert_eq!(
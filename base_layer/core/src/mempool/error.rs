@@ -48,6 +48,8 @@ pub enum MempoolError {
     InternalError(String),
     #[error("Mempool indexes out of sync: transaction exists in txs_by_signature but not in tx_by_key")]
     IndexOutOfSync,
+    #[error("Mempool snapshot error: {0}")]
+    SnapshotError(String),
 }
 impl MempoolError {
     pub fn get_ban_reason(&self) -> Option<BanReason> {
@@ -64,7 +66,8 @@ impl MempoolError {
             _err @ MempoolError::RwLockPoisonError |
             _err @ MempoolError::BlockingTaskError(_) |
             _err @ MempoolError::InternalError(_) |
-            _err @ MempoolError::IndexOutOfSync => None,
+            _err @ MempoolError::IndexOutOfSync |
+            _err @ MempoolError::SnapshotError(_) => None,
         }
     }
 }
@@ -0,0 +1,300 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashMap;
+
+use tari_common_types::types::PrivateKey;
+
+use crate::{blocks::Block, transactions::tari_amount::MicroMinotari};
+
+/// The largest confirmation target, in blocks, that the estimator tracks.
+pub const MAX_CONFIRMATION_TARGET: u64 = 48;
+/// The confidence used when the caller does not specify one.
+pub const DEFAULT_CONFIDENCE_PERCENT: u32 = 85;
+
+/// The lower bound of the first fee bucket, in µT per gram
+const MIN_BUCKET_FEE_PER_GRAM: f64 = 1.0;
+/// The lower bound of the last fee bucket, in µT per gram
+const MAX_BUCKET_FEE_PER_GRAM: f64 = 100_000.0;
+/// Each bucket's lower bound is this factor larger than the previous one
+const BUCKET_SPACING: f64 = 1.1;
+/// Historical data points are multiplied by this factor every block, giving a half-life of roughly 350 blocks
+const DECAY: f64 = 0.998;
+/// The minimum number of (decayed) data points a range of buckets must have before it is used for an estimate
+const MIN_SAMPLES: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedTransaction {
+    bucket: usize,
+    entry_height: u64,
+}
+
+/// Estimates the fee per gram required for a transaction to be mined within a number of blocks.
+///
+/// Transactions are sorted into exponentially spaced fee-per-gram buckets when they enter the unconfirmed pool. When
+/// they are mined, the number of blocks they waited is recorded against their bucket; transactions that are not mined
+/// within [MAX_CONFIRMATION_TARGET] blocks are recorded as failures. An estimate for a target is the lowest bucket
+/// for which, together with all higher buckets, the required fraction of transactions were mined within the target.
+pub struct FeeEstimator {
+    bucket_bounds: Vec<u64>,
+    /// `confirmed[bucket][target - 1]`: transactions in the bucket that were mined within `target` blocks
+    confirmed: Vec<Vec<f64>>,
+    /// Transactions in the bucket that were either mined or failed to be mined
+    total: Vec<f64>,
+    tracked: HashMap<PrivateKey, TrackedTransaction>,
+    best_height: u64,
+}
+
+impl FeeEstimator {
+    pub fn new() -> Self {
+        let mut bucket_bounds = Vec::new();
+        let mut bound = MIN_BUCKET_FEE_PER_GRAM;
+        while bound <= MAX_BUCKET_FEE_PER_GRAM {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let value = bound.ceil() as u64;
+            if bucket_bounds.last() != Some(&value) {
+                bucket_bounds.push(value);
+            }
+            bound *= BUCKET_SPACING;
+        }
+        let num_buckets = bucket_bounds.len();
+        #[allow(clippy::cast_possible_truncation)]
+        let num_targets = MAX_CONFIRMATION_TARGET as usize;
+        Self {
+            bucket_bounds,
+            confirmed: vec![vec![0.0; num_targets]; num_buckets],
+            total: vec![0.0; num_buckets],
+            tracked: HashMap::new(),
+            best_height: 0,
+        }
+    }
+
+    /// Starts tracking a transaction that has entered the unconfirmed pool. `excess_sig` is the signature of its first
+    /// kernel.
+    pub fn track_transaction(&mut self, excess_sig: PrivateKey, fee_per_gram: u64) {
+        // Until the first block has been processed we do not know how long the transaction will have waited
+        if self.best_height == 0 {
+            return;
+        }
+        let bucket = self.bucket_index(fee_per_gram);
+        self.tracked.entry(excess_sig).or_insert(TrackedTransaction {
+            bucket,
+            entry_height: self.best_height,
+        });
+    }
+
+    /// Records the confirmation times of all tracked transactions mined in the block.
+    pub fn process_block(&mut self, block: &Block) {
+        let height = block.header.height;
+        if height <= self.best_height {
+            // Re-orgs and re-processed blocks would skew the data, so only blocks that extend the chain are counted
+            return;
+        }
+        self.best_height = height;
+
+        for confirmed in &mut self.confirmed {
+            confirmed.iter_mut().for_each(|c| *c *= DECAY);
+        }
+        self.total.iter_mut().for_each(|t| *t *= DECAY);
+
+        for kernel in block.body.kernels() {
+            if let Some(tracked) = self.tracked.remove(kernel.excess_sig.get_signature()) {
+                let blocks_to_confirm = height.saturating_sub(tracked.entry_height).max(1);
+                if blocks_to_confirm <= MAX_CONFIRMATION_TARGET {
+                    #[allow(clippy::cast_possible_truncation)]
+                    for count in &mut self.confirmed[tracked.bucket][(blocks_to_confirm - 1) as usize..] {
+                        *count += 1.0;
+                    }
+                }
+                self.total[tracked.bucket] += 1.0;
+            }
+        }
+
+        // Transactions that have waited longer than the largest target are recorded as failures
+        let total = &mut self.total;
+        self.tracked.retain(|_, tracked| {
+            if height.saturating_sub(tracked.entry_height) > MAX_CONFIRMATION_TARGET {
+                total[tracked.bucket] += 1.0;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Returns the lowest fee per gram for which at least `confidence_percent` percent of transactions were mined
+    /// within `confirmation_target` blocks, or None if there is not enough data.
+    pub fn estimate(&self, confirmation_target: u64, confidence_percent: u32) -> Option<MicroMinotari> {
+        if confirmation_target == 0 || confirmation_target > MAX_CONFIRMATION_TARGET {
+            return None;
+        }
+        let threshold = f64::from(confidence_percent.min(100)) / 100.0;
+        #[allow(clippy::cast_possible_truncation)]
+        let target_index = (confirmation_target - 1) as usize;
+
+        // Transactions still waiting for longer than the target count as failures for that target
+        let mut pending_too_long = vec![0.0; self.bucket_bounds.len()];
+        for tracked in self.tracked.values() {
+            if self.best_height.saturating_sub(tracked.entry_height) >= confirmation_target {
+                pending_too_long[tracked.bucket] += 1.0;
+            }
+        }
+
+        let mut best = None;
+        let mut confirmed = 0.0;
+        let mut total = 0.0;
+        for bucket in (0..self.bucket_bounds.len()).rev() {
+            confirmed += self.confirmed[bucket][target_index];
+            total += self.total[bucket] + pending_too_long[bucket];
+            if total < MIN_SAMPLES {
+                continue;
+            }
+            if confirmed / total < threshold {
+                break;
+            }
+            best = Some(bucket);
+            confirmed = 0.0;
+            total = 0.0;
+        }
+        best.map(|bucket| MicroMinotari::from(self.bucket_bounds[bucket]))
+    }
+
+    /// The number of transactions currently being tracked
+    pub fn num_tracked(&self) -> usize {
+        self.tracked.len()
+    }
+
+    fn bucket_index(&self, fee_per_gram: u64) -> usize {
+        self.bucket_bounds
+            .partition_point(|bound| *bound <= fee_per_gram)
+            .saturating_sub(1)
+    }
+}
+
+impl Default for FeeEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::Signature;
+    use tari_crypto::keys::SecretKey;
+
+    use super::*;
+    use crate::{
+        blocks::BlockHeader,
+        transactions::{
+            aggregated_body::AggregateBody,
+            transaction_components::{KernelFeatures, TransactionKernel},
+        },
+    };
+
+    fn block_with_kernels(height: u64, excess_sigs: &[PrivateKey]) -> Block {
+        let kernels = excess_sigs
+            .iter()
+            .map(|sig| {
+                let mut kernel = TransactionKernel::new_current_version(
+                    KernelFeatures::empty(),
+                    MicroMinotari::from(0),
+                    0,
+                    Default::default(),
+                    Default::default(),
+                    None,
+                );
+                kernel.excess_sig = Signature::new(Default::default(), sig.clone());
+                kernel
+            })
+            .collect();
+        let mut header = BlockHeader::new(0);
+        header.height = height;
+        Block::new(header, AggregateBody::new(vec![], vec![], kernels))
+    }
+
+    fn random_sig() -> PrivateKey {
+        PrivateKey::random(&mut OsRng)
+    }
+
+    #[test]
+    fn it_has_no_estimate_without_data() {
+        let estimator = FeeEstimator::new();
+        assert!(estimator.estimate(1, DEFAULT_CONFIDENCE_PERCENT).is_none());
+        assert!(estimator.estimate(0, DEFAULT_CONFIDENCE_PERCENT).is_none());
+        assert!(estimator.estimate(MAX_CONFIRMATION_TARGET + 1, 50).is_none());
+    }
+
+    #[test]
+    fn it_ignores_transactions_seen_before_the_first_block() {
+        let mut estimator = FeeEstimator::new();
+        estimator.track_transaction(random_sig(), 10);
+        assert_eq!(estimator.num_tracked(), 0);
+    }
+
+    #[test]
+    fn it_estimates_the_fee_required_for_a_target() {
+        let mut estimator = FeeEstimator::new();
+        estimator.process_block(&block_with_kernels(1, &[]));
+
+        // High fee transactions are mined in the next block, low fee transactions take 5 blocks
+        let mut height = 1;
+        for _ in 0..10 {
+            let high = (0..5).map(|_| random_sig()).collect::<Vec<_>>();
+            let low = (0..5).map(|_| random_sig()).collect::<Vec<_>>();
+            high.iter()
+                .for_each(|sig| estimator.track_transaction(sig.clone(), 100));
+            low.iter().for_each(|sig| estimator.track_transaction(sig.clone(), 5));
+            height += 1;
+            estimator.process_block(&block_with_kernels(height, &high));
+            for _ in 0..3 {
+                height += 1;
+                estimator.process_block(&block_with_kernels(height, &[]));
+            }
+            height += 1;
+            estimator.process_block(&block_with_kernels(height, &low));
+        }
+
+        let next_block = estimator.estimate(1, DEFAULT_CONFIDENCE_PERCENT).unwrap();
+        assert!(next_block.as_u64() > 5 && next_block.as_u64() <= 100);
+        let five_blocks = estimator.estimate(5, DEFAULT_CONFIDENCE_PERCENT).unwrap();
+        assert!(five_blocks.as_u64() <= 5);
+        assert!(five_blocks < next_block);
+    }
+
+    #[test]
+    fn it_counts_long_pending_transactions_as_failures() {
+        let mut estimator = FeeEstimator::new();
+        estimator.process_block(&block_with_kernels(1, &[]));
+        let mined = (0..5).map(|_| random_sig()).collect::<Vec<_>>();
+        mined
+            .iter()
+            .for_each(|sig| estimator.track_transaction(sig.clone(), 20));
+        estimator.process_block(&block_with_kernels(2, &mined));
+        assert!(estimator.estimate(1, 100).unwrap().as_u64() <= 20);
+
+        // Many more transactions in the same bucket are stuck
+        (0..20).for_each(|_| estimator.track_transaction(random_sig(), 20));
+        estimator.process_block(&block_with_kernels(3, &[]));
+        assert!(estimator.estimate(1, DEFAULT_CONFIDENCE_PERCENT).is_none());
+    }
+}
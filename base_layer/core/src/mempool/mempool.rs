@@ -20,7 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::{Arc, RwLock};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
 use log::debug;
 use tari_common_types::types::{FixedHash, PrivateKey, Signature};
@@ -32,8 +35,11 @@ use crate::{
     mempool::{
         error::MempoolError,
         mempool_storage::MempoolStorage,
+        FeeEstimate,
         FeePerGramStat,
         MempoolConfig,
+        MempoolSnapshot,
+        SnapshotRestoreStats,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -70,6 +76,16 @@ impl Mempool {
         .await
     }
 
+    /// Insert an ordered package of dependent transactions into the Mempool, evaluating them by their combined fee.
+    pub async fn insert_package(&self, txs: Vec<Arc<Transaction>>) -> Result<TxStorageResponse, MempoolError> {
+        self.with_write_access(|storage| {
+            storage
+                .insert_package(txs)
+                .map_err(|e| MempoolError::InternalError(e.to_string()))
+        })
+        .await
+    }
+
     /// Inserts all transactions into the mempool.
     pub async fn insert_all(&self, transactions: Vec<Arc<Transaction>>) -> Result<(), MempoolError> {
         self.with_write_access(|storage| {
@@ -117,6 +133,41 @@ impl Mempool {
         self.with_read_access(|storage| Ok(storage.snapshot())).await
     }
 
+    /// Returns a serializable copy of the unconfirmed and reorg pools.
+    pub async fn create_snapshot(&self) -> Result<MempoolSnapshot, MempoolError> {
+        self.with_read_access(|storage| Ok(storage.create_snapshot())).await
+    }
+
+    /// Restores a snapshot into the Mempool, revalidating all unconfirmed transactions.
+    pub async fn restore_snapshot(
+        &self,
+        snapshot: MempoolSnapshot,
+        tip_height: u64,
+    ) -> Result<SnapshotRestoreStats, MempoolError> {
+        self.with_write_access(move |storage| storage.restore_snapshot(snapshot, tip_height))
+            .await
+    }
+
+    /// Writes a snapshot of the Mempool to the given file, returning the number of transactions written.
+    pub async fn save_snapshot_to_file(&self, path: PathBuf) -> Result<usize, MempoolError> {
+        let snapshot = self.create_snapshot().await?;
+        task::spawn_blocking(move || {
+            snapshot.write_to_file(&path)?;
+            Ok(snapshot.len())
+        })
+        .await?
+    }
+
+    /// Reads a snapshot from the given file and restores it into the Mempool.
+    pub async fn load_snapshot_from_file(
+        &self,
+        path: PathBuf,
+        tip_height: u64,
+    ) -> Result<SnapshotRestoreStats, MempoolError> {
+        let snapshot = task::spawn_blocking(move || MempoolSnapshot::read_from_file(&path)).await??;
+        self.restore_snapshot(snapshot, tip_height).await
+    }
+
     /// Returns a list of transaction ranked by transaction priority up to a given weight.
     /// Only transactions that fit into a block will be returned
     pub async fn retrieve(&self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, MempoolError> {
@@ -188,6 +239,17 @@ impl Mempool {
             .await
     }
 
+    /// Estimates the fee per gram required for a transaction to be mined within `confirmation_target` blocks.
+    pub async fn estimate_fee(
+        &self,
+        confirmation_target: u64,
+        confidence_percent: u32,
+        tip_height: u64,
+    ) -> Result<FeeEstimate, MempoolError> {
+        self.with_read_access(move |storage| storage.estimate_fee(confirmation_target, confidence_percent, tip_height))
+            .await
+    }
+
     async fn with_read_access<F, T>(&self, callback: F) -> Result<T, MempoolError>
     where
         F: FnOnce(&MempoolStorage) -> Result<T, MempoolError> + Send + 'static,
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{sync::Arc, time::Instant};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 
 use log::*;
 use tari_common_types::types::{FixedHash, PrivateKey, Signature};
@@ -31,15 +31,20 @@ use crate::{
     consensus::ConsensusManager,
     mempool::{
         error::MempoolError,
+        fee_estimator::{FeeEstimator, MAX_CONFIRMATION_TARGET},
         reorg_pool::ReorgPool,
         unconfirmed_pool::{RetrieveResults, TransactionKey, UnconfirmedPool, UnconfirmedPoolError},
+        FeeEstimate,
         FeePerGramStat,
         MempoolConfig,
+        MempoolSnapshot,
+        SnapshotRestoreStats,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
     },
     transactions::{
+        tari_amount::MicroMinotari,
         transaction_components::{Transaction, TransactionError},
         weight::TransactionWeight,
     },
@@ -56,6 +61,7 @@ pub struct MempoolStorage {
     reorg_pool: ReorgPool,
     validator: Box<dyn TransactionValidator>,
     rules: ConsensusManager,
+    fee_estimator: FeeEstimator,
     last_seen_height: u64,
     pub(crate) last_seen_hash: FixedHash,
 }
@@ -68,6 +74,7 @@ impl MempoolStorage {
             reorg_pool: ReorgPool::new(config.reorg_pool),
             validator,
             rules,
+            fee_estimator: FeeEstimator::new(),
             last_seen_height: 0,
             last_seen_hash: Default::default(),
         }
@@ -105,49 +112,162 @@ impl MempoolStorage {
                 );
                 let timer = Instant::now();
                 let weight = self.get_transaction_weighting();
-                self.unconfirmed_pool.insert(tx, None, &weight)?;
+                let storage_response = self.unconfirmed_pool.insert(tx.clone(), None, &weight)?;
+                if storage_response.is_stored() {
+                    self.track_fee(&tx, tx_fee.as_u64());
+                }
                 debug!(
                     target: LOG_TARGET,
-                    "Transaction {} inserted in {:.2?}",
+                    "Transaction {} processed by unconfirmed pool ({}) in {:.2?}",
                     tx_id,
+                    storage_response,
                     timer.elapsed()
                 );
-                Ok(TxStorageResponse::UnconfirmedPool)
+                Ok(storage_response)
             },
             Err(ValidationError::UnknownInputs(dependent_outputs)) => {
                 if self.unconfirmed_pool.contains_all_outputs(&dependent_outputs) {
                     let weight = self.get_transaction_weighting();
-                    self.unconfirmed_pool.insert(tx, Some(dependent_outputs), &weight)?;
-                    Ok(TxStorageResponse::UnconfirmedPool)
+                    let storage_response =
+                        self.unconfirmed_pool
+                            .insert(tx.clone(), Some(dependent_outputs), &weight)?;
+                    if storage_response.is_stored() {
+                        self.track_fee(&tx, tx_fee.as_u64());
+                    }
+                    Ok(storage_response)
                 } else {
                     warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
                     Ok(TxStorageResponse::NotStoredOrphan)
                 }
             },
-            Err(ValidationError::ContainsSTxO) => {
+            Err(e) => Ok(Self::validation_error_storage_response(e)),
+        }
+    }
+
+    /// Insert an ordered package of dependent transactions, parents before children, into the Mempool. Every
+    /// transaction is validated, but inputs may spend outputs of earlier transactions in the package. The package is
+    /// stored as a whole based on its combined fee, so a low-fee parent can be paid for by its children.
+    pub fn insert_package(&mut self, txs: Vec<Arc<Transaction>>) -> Result<TxStorageResponse, UnconfirmedPoolError> {
+        debug!(target: LOG_TARGET, "Inserting package of {} tx(s) into mempool", txs.len());
+        if txs.is_empty() {
+            return Ok(TxStorageResponse::NotStored);
+        }
+        let timer = Instant::now();
+
+        let mut package_fee = 0u64;
+        let mut package_inputs = HashSet::new();
+        for tx in &txs {
+            let tx_fee = match tx.body.get_total_fee() {
+                Ok(fee) => fee,
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Invalid transaction in package: {}", e);
+                    return Ok(TxStorageResponse::NotStoredConsensus);
+                },
+            };
+            package_fee = package_fee.saturating_add(tx_fee.as_u64());
+            for input in tx.body.inputs() {
+                if !package_inputs.insert(input.output_hash()) {
+                    debug!(target: LOG_TARGET, "Package contains a double spend, rejecting");
+                    return Ok(TxStorageResponse::NotStoredAlreadySpent);
+                }
+            }
+        }
+        // The minimum fee applies to the package on average, rather than to each of its transactions
+        if package_fee < self.unconfirmed_pool.config.min_fee.saturating_mul(txs.len() as u64) {
+            debug!(target: LOG_TARGET, "Package fee too low, rejecting");
+            return Ok(TxStorageResponse::NotStoredFeeTooLow);
+        }
+
+        let mut package_outputs = HashSet::new();
+        let mut package = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let dependent_outputs = match self.validator.validate(&tx) {
+                Ok(()) => None,
+                Err(ValidationError::UnknownInputs(dependent_outputs)) => {
+                    let outputs_not_in_package = dependent_outputs
+                        .iter()
+                        .filter(|hash| !package_outputs.contains(*hash))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if !self.unconfirmed_pool.contains_all_outputs(&outputs_not_in_package) {
+                        warn!(target: LOG_TARGET, "Package validation failed due to unknown inputs");
+                        return Ok(TxStorageResponse::NotStoredOrphan);
+                    }
+                    Some(dependent_outputs)
+                },
+                Err(e) => return Ok(Self::validation_error_storage_response(e)),
+            };
+            package_outputs.extend(tx.body.outputs().iter().map(|output| output.hash()));
+            package.push((tx, dependent_outputs));
+        }
+
+        let weight = self.get_transaction_weighting();
+        let package_txs = package.iter().map(|(tx, _)| tx.clone()).collect::<Vec<_>>();
+        let storage_response = self.unconfirmed_pool.insert_package(package, &weight)?;
+        if storage_response.is_stored() {
+            // Package members are mined together, so they are all tracked at the package fee rate
+            let package_weight = package_txs
+                .iter()
+                .map(|tx| tx.calculate_weight(&weight).unwrap_or(0))
+                .sum::<u64>();
+            let package_fee_per_gram = package_fee.checked_div(package_weight).unwrap_or(0);
+            for tx in &package_txs {
+                if let Some(excess_sig) = tx.first_kernel_excess_sig() {
+                    self.fee_estimator
+                        .track_transaction(excess_sig.get_signature().clone(), package_fee_per_gram);
+                }
+            }
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Package processed by unconfirmed pool ({}) in {:.2?}",
+            storage_response,
+            timer.elapsed()
+        );
+        Ok(storage_response)
+    }
+
+    fn validation_error_storage_response(error: ValidationError) -> TxStorageResponse {
+        match error {
+            ValidationError::ContainsSTxO => {
                 warn!(target: LOG_TARGET, "Validation failed due to already spent input");
-                Ok(TxStorageResponse::NotStoredAlreadySpent)
+                TxStorageResponse::NotStoredAlreadySpent
             },
-            Err(ValidationError::MaturityError) => {
+            ValidationError::MaturityError => {
                 warn!(target: LOG_TARGET, "Validation failed due to maturity error");
-                Ok(TxStorageResponse::NotStoredTimeLocked)
+                TxStorageResponse::NotStoredTimeLocked
             },
-            Err(ValidationError::ConsensusError(msg)) => {
+            ValidationError::ConsensusError(msg) => {
                 warn!(target: LOG_TARGET, "Validation failed due to consensus rule: {}", msg);
-                Ok(TxStorageResponse::NotStoredConsensus)
+                TxStorageResponse::NotStoredConsensus
             },
-            Err(ValidationError::DuplicateKernelError(msg)) => {
+            ValidationError::DuplicateKernelError(msg) => {
                 debug!(
                     target: LOG_TARGET,
                     "Validation failed due to already mined kernel: {}", msg
                 );
-                Ok(TxStorageResponse::NotStoredAlreadyMined)
+                TxStorageResponse::NotStoredAlreadyMined
             },
-            Err(e) => {
+            e => {
                 eprintln!("Validation failed due to error: {}", e);
                 warn!(target: LOG_TARGET, "Validation failed due to error: {}", e);
-                Ok(TxStorageResponse::NotStored)
+                TxStorageResponse::NotStored
+            },
+        }
+    }
+
+    /// Starts tracking a transaction that entered the unconfirmed pool in the fee estimator.
+    fn track_fee(&mut self, tx: &Transaction, fee: u64) {
+        let weight = match tx.calculate_weight(&self.get_transaction_weighting()) {
+            Ok(weight) => weight,
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Could not calculate transaction weight: {}", e);
+                return;
             },
+        };
+        if let Some(excess_sig) = tx.first_kernel_excess_sig() {
+            self.fee_estimator
+                .track_transaction(excess_sig.get_signature().clone(), fee.checked_div(weight).unwrap_or(0));
         }
     }
 
@@ -221,6 +341,8 @@ impl MempoolStorage {
         self.unconfirmed_pool.compact();
         self.reorg_pool.compact();
 
+        self.fee_estimator.process_block(published_block);
+
         self.last_seen_height = published_block.header.height;
         self.last_seen_hash = published_block.header.hash();
         debug!(target: LOG_TARGET, "Compaction took {:.2?}", timer.elapsed());
@@ -304,6 +426,54 @@ impl MempoolStorage {
         self.unconfirmed_pool.snapshot()
     }
 
+    /// Returns a serializable copy of the transactions held in both the unconfirmed pool and the reorg pool.
+    pub fn create_snapshot(&self) -> MempoolSnapshot {
+        MempoolSnapshot::new(self.unconfirmed_pool.snapshot(), self.reorg_pool.snapshot_by_height())
+    }
+
+    /// Restores the transactions of a snapshot into the Mempool. Unconfirmed transactions are revalidated and inserted
+    /// as if they were newly received, so transactions that were mined or became invalid while the node was offline
+    /// are discarded. Reorg pool transactions published at a height that has since expired are dropped.
+    pub fn restore_snapshot(
+        &mut self,
+        snapshot: MempoolSnapshot,
+        tip_height: u64,
+    ) -> Result<SnapshotRestoreStats, MempoolError> {
+        let mut stats = SnapshotRestoreStats::default();
+        let expiry_height = self.reorg_pool.expiry_height();
+        for (height, txs) in snapshot.reorg_pool {
+            if height.saturating_add(expiry_height) <= tip_height {
+                stats.reorg_expired += txs.len();
+                continue;
+            }
+            stats.reorg_restored += txs.len();
+            self.reorg_pool
+                .insert_all(height, txs.into_iter().map(Arc::new).collect());
+        }
+
+        // The snapshot does not preserve the order in which transactions were received, so children may come before
+        // their parents. Keep retrying orphans for as long as each pass makes progress.
+        let mut pending = snapshot.unconfirmed_pool.into_iter().map(Arc::new).collect::<Vec<_>>();
+        loop {
+            let num_pending = pending.len();
+            let mut orphans = Vec::new();
+            for tx in pending {
+                match self.insert(tx.clone())? {
+                    response if response.is_stored() => stats.unconfirmed_restored += 1,
+                    TxStorageResponse::NotStoredOrphan => orphans.push(tx),
+                    _ => stats.unconfirmed_rejected += 1,
+                }
+            }
+            if orphans.is_empty() || orphans.len() == num_pending {
+                stats.unconfirmed_rejected += orphans.len();
+                break;
+            }
+            pending = orphans;
+        }
+        debug!(target: LOG_TARGET, "Restored mempool snapshot: {}", stats);
+        Ok(stats)
+    }
+
     /// Returns a list of transaction ranked by transaction priority up to a given weight.
     /// Will only return transactions that will fit into the given weight
     pub fn retrieve(&self, total_weight: u64) -> Result<RetrieveResults, MempoolError> {
@@ -395,6 +565,42 @@ impl MempoolStorage {
         }
     }
 
+    /// Estimates the fee per gram required for a transaction to be mined within `confirmation_target` blocks with the
+    /// given confidence. If the fee estimator has not yet observed enough transactions being mined, the estimate is
+    /// derived from the fees of the transactions currently in the mempool.
+    pub fn estimate_fee(
+        &self,
+        confirmation_target: u64,
+        confidence_percent: u32,
+        tip_height: u64,
+    ) -> Result<FeeEstimate, MempoolError> {
+        let confirmation_target = confirmation_target.clamp(1, MAX_CONFIRMATION_TARGET);
+        if let Some(fee_per_gram) = self.fee_estimator.estimate(confirmation_target, confidence_percent) {
+            return Ok(FeeEstimate {
+                fee_per_gram,
+                confirmation_target,
+                is_historical: true,
+            });
+        }
+
+        // If the mempool holds more transactions than fit into the target number of blocks, paying more than the
+        // cheapest transaction in the last of those blocks should get us in, assuming the mempool does not grow in the
+        // meantime. Otherwise the mempool will be cleared before then and the minimum will do.
+        #[allow(clippy::cast_possible_truncation)]
+        let count = confirmation_target as usize;
+        let stats = self.get_fee_per_gram_stats(count + 1, tip_height)?;
+        let fee_per_gram = if stats.len() > count {
+            stats[count - 1].min_fee_per_gram + MicroMinotari::from(1)
+        } else {
+            MicroMinotari::from(1)
+        };
+        Ok(FeeEstimate {
+            fee_per_gram,
+            confirmation_target,
+            is_historical: false,
+        })
+    }
+
     pub fn get_fee_per_gram_stats(&self, count: usize, tip_height: u64) -> Result<Vec<FeePerGramStat>, MempoolError> {
         let target_weight = self
             .rules
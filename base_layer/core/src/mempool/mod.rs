@@ -28,11 +28,19 @@ mod config;
 #[cfg(feature = "base_node")]
 mod error;
 #[cfg(feature = "base_node")]
+mod fee_estimator;
+#[cfg(feature = "base_node")]
 #[allow(clippy::module_inception)]
 mod mempool;
 #[cfg(feature = "base_node")]
 mod mempool_storage;
 #[cfg(feature = "base_node")]
+pub use fee_estimator::{DEFAULT_CONFIDENCE_PERCENT, MAX_CONFIRMATION_TARGET};
+#[cfg(feature = "base_node")]
+mod persistence;
+#[cfg(feature = "base_node")]
+pub use persistence::MempoolPersistence;
+#[cfg(feature = "base_node")]
 mod priority;
 #[cfg(feature = "base_node")]
 mod reorg_pool;
@@ -47,6 +55,8 @@ mod metrics;
 #[cfg(feature = "base_node")]
 mod shrink_hashmap;
 #[cfg(feature = "base_node")]
+mod snapshot;
+#[cfg(feature = "base_node")]
 mod unconfirmed_pool;
 
 // Public re-exports
@@ -54,9 +64,11 @@ mod unconfirmed_pool;
 pub use error::MempoolError;
 #[cfg(feature = "base_node")]
 pub use mempool::Mempool;
+#[cfg(feature = "base_node")]
+pub use snapshot::{MempoolSnapshot, SnapshotRestoreStats};
 
 #[cfg(feature = "base_node")]
-pub use self::config::{MempoolConfig, MempoolServiceConfig};
+pub use self::config::{MempoolConfig, MempoolPersistenceConfig, MempoolServiceConfig};
 
 #[cfg(any(feature = "base_node", feature = "mempool_proto"))]
 pub mod proto;
@@ -107,6 +119,7 @@ pub struct StateResponse {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TxStorageResponse {
     UnconfirmedPool,
+    UnconfirmedPoolReplacedByFee,
     ReorgPool,
     NotStoredOrphan,
     NotStoredTimeLocked,
@@ -115,11 +128,15 @@ pub enum TxStorageResponse {
     NotStored,
     NotStoredAlreadyMined,
     NotStoredFeeTooLow,
+    NotStoredReplacementFeeTooLow,
 }
 
 impl TxStorageResponse {
     pub fn is_stored(&self) -> bool {
-        matches!(self, Self::UnconfirmedPool | Self::ReorgPool)
+        matches!(
+            self,
+            Self::UnconfirmedPool | Self::UnconfirmedPoolReplacedByFee | Self::ReorgPool
+        )
     }
 }
 
@@ -127,6 +144,9 @@ impl Display for TxStorageResponse {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         let storage = match self {
             TxStorageResponse::UnconfirmedPool => "Unconfirmed pool",
+            TxStorageResponse::UnconfirmedPoolReplacedByFee => {
+                "Unconfirmed pool, replacing conflicting transactions paying a lower fee"
+            },
             TxStorageResponse::ReorgPool => "Reorg pool",
             TxStorageResponse::NotStoredOrphan => "Not stored orphan transaction",
             TxStorageResponse::NotStoredTimeLocked => "Not stored time locked transaction",
@@ -135,6 +155,9 @@ impl Display for TxStorageResponse {
             TxStorageResponse::NotStored => "Not stored",
             TxStorageResponse::NotStoredAlreadyMined => "Not stored tx already mined",
             TxStorageResponse::NotStoredFeeTooLow => "Not stored tx fee is below the minimum accepted by this mempool",
+            TxStorageResponse::NotStoredReplacementFeeTooLow => {
+                "Not stored tx conflicts with transactions in the mempool and does not pay enough fees to replace them"
+            },
         };
         fmt.write_str(storage)
     }
@@ -158,3 +181,25 @@ impl From<base_node_proto::MempoolFeePerGramStat> for FeePerGramStat {
         }
     }
 }
+
+/// A fee-per-gram estimate for a confirmation target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// The estimated fee per gram
+    pub fee_per_gram: MicroMinotari,
+    /// The number of blocks within which a transaction paying `fee_per_gram` is expected to be mined
+    pub confirmation_target: u64,
+    /// True if the estimate is based on observed confirmation times, false if the estimator did not have enough data
+    /// and the estimate was derived from the current contents of the mempool instead
+    pub is_historical: bool,
+}
+
+impl From<base_node_proto::EstimateFeeResponse> for FeeEstimate {
+    fn from(value: base_node_proto::EstimateFeeResponse) -> Self {
+        Self {
+            fee_per_gram: value.fee_per_gram.into(),
+            confirmation_target: value.confirmation_target,
+            is_historical: value.is_historical,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use log::*;
+use tari_shutdown::ShutdownSignal;
+use tokio::{
+    time,
+    time::{Instant, MissedTickBehavior},
+};
+
+use crate::mempool::{Mempool, MempoolError, MempoolPersistenceConfig, SnapshotRestoreStats};
+
+const LOG_TARGET: &str = "c::mp::persistence";
+
+/// Keeps an on-disk snapshot of the mempool so that its contents survive a node restart. The snapshot is written
+/// periodically and once more when the node shuts down.
+pub struct MempoolPersistence {
+    mempool: Mempool,
+    config: MempoolPersistenceConfig,
+}
+
+impl MempoolPersistence {
+    pub fn new(mempool: Mempool, config: MempoolPersistenceConfig) -> Self {
+        Self { mempool, config }
+    }
+
+    /// Restores the mempool from the configured snapshot file. Returns `None` if no snapshot exists yet.
+    pub async fn restore(&self, tip_height: u64) -> Result<Option<SnapshotRestoreStats>, MempoolError> {
+        if !self.config.snapshot_file.exists() {
+            debug!(
+                target: LOG_TARGET,
+                "No mempool snapshot found at {}",
+                self.config.snapshot_file.display()
+            );
+            return Ok(None);
+        }
+        let stats = self
+            .mempool
+            .load_snapshot_from_file(self.config.snapshot_file.clone(), tip_height)
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Restored mempool from {}: {}",
+            self.config.snapshot_file.display(),
+            stats
+        );
+        Ok(Some(stats))
+    }
+
+    /// Writes the snapshot every `snapshot_interval` until the shutdown signal is triggered, after which a final
+    /// snapshot is written.
+    pub async fn run(self, mut shutdown_signal: ShutdownSignal) {
+        let mut interval = time::interval_at(
+            Instant::now() + self.config.snapshot_interval,
+            self.config.snapshot_interval,
+        );
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.save().await;
+                },
+                _ = shutdown_signal.wait() => {
+                    info!(target: LOG_TARGET, "Writing mempool snapshot before shutdown");
+                    self.save().await;
+                    break;
+                },
+            }
+        }
+    }
+
+    async fn save(&self) {
+        match self
+            .mempool
+            .save_snapshot_to_file(self.config.snapshot_file.clone())
+            .await
+        {
+            Ok(num_txs) => debug!(
+                target: LOG_TARGET,
+                "Wrote {} mempool transaction(s) to {}",
+                num_txs,
+                self.config.snapshot_file.display()
+            ),
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Failed to write mempool snapshot to {}: {}",
+                self.config.snapshot_file.display(),
+                e
+            ),
+        }
+    }
+}
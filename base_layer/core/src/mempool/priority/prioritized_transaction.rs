@@ -73,6 +73,21 @@ impl FeePriority {
         priority[48..80].copy_from_slice(agg_nonce.as_bytes());
         Ok(Self(priority))
     }
+
+    /// The fee per byte component of this priority
+    pub fn fee_per_byte(&self) -> u64 {
+        let mut fee_priority = [0u8; 8];
+        fee_priority.copy_from_slice(&self.0[..8]);
+        u64::from_be_bytes(fee_priority)
+    }
+
+    /// Returns this priority with its fee component replaced by `fee_per_byte`, keeping the age and excess_sig
+    /// components. This is used to rank the members of a transaction package by the fee rate of the whole package.
+    pub fn with_fee_per_byte(&self, fee_per_byte: u64) -> Self {
+        let mut priority = self.0.clone();
+        priority[..8].copy_from_slice(&fee_per_byte.to_be_bytes());
+        Self(priority)
+    }
 }
 
 /// A prioritized transaction includes a transaction and the calculated priority of the transaction.
@@ -175,6 +190,24 @@ mod tests {
         assert!(p2 > p1);
     }
 
+    #[tokio::test]
+    async fn package_fee_per_byte_overrides_fee_priority() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let weighting = TransactionWeight::latest();
+        let epoch = u64::MAX / 2;
+        let tx = create_tx_with_fee(2 * uT, &key_manager).await;
+        let weight = tx.calculate_weight(&weighting).expect("Failed to get tx");
+        let p1 = FeePriority::new(&tx, epoch, weight).unwrap();
+
+        let tx = create_tx_with_fee(3 * uT, &key_manager).await;
+        let p2 = FeePriority::new(&tx, epoch, weight).unwrap();
+
+        // A package paying 4 uT per gram ranks the 2 uT per gram transaction above the 3 uT per gram one
+        assert!(p1.with_fee_per_byte(4 * 1000) > p2);
+        assert_eq!(p1.with_fee_per_byte(4 * 1000).fee_per_byte(), 4 * 1000);
+        assert!(p1.with_fee_per_byte(0) < p1);
+    }
+
     #[test]
     fn prioritized_from_empty_transaction() {
         let weighting = TransactionWeight::latest();
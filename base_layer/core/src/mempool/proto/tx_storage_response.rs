@@ -44,6 +44,7 @@ impl From<TxStorageResponse> for proto::TxStorageResponse {
         use TxStorageResponse::*;
         match response {
             UnconfirmedPool => proto::TxStorageResponse::UnconfirmedPool,
+            UnconfirmedPoolReplacedByFee => proto::TxStorageResponse::UnconfirmedPool,
             ReorgPool => proto::TxStorageResponse::ReorgPool,
             NotStored => proto::TxStorageResponse::NotStored,
             NotStoredOrphan => proto::TxStorageResponse::NotStored,
@@ -52,6 +53,7 @@ impl From<TxStorageResponse> for proto::TxStorageResponse {
             NotStoredConsensus => proto::TxStorageResponse::NotStored,
             NotStoredAlreadyMined => proto::TxStorageResponse::NotStored,
             NotStoredFeeTooLow => proto::TxStorageResponse::NotStored,
+            NotStoredReplacementFeeTooLow => proto::TxStorageResponse::NotStored,
         }
     }
 }
@@ -276,6 +276,29 @@ impl ReorgPool {
         self.tx_by_key.values().cloned().collect()
     }
 
+    /// Returns all transactions stored in the ReorgPool grouped by the height at which they were published, in
+    /// ascending height order.
+    pub fn snapshot_by_height(&self) -> Vec<(u64, Vec<Arc<Transaction>>)> {
+        let mut result = self
+            .txs_by_height
+            .iter()
+            .map(|(height, keys)| {
+                let txs = keys
+                    .iter()
+                    .filter_map(|key| self.tx_by_key.get(key).cloned())
+                    .collect::<Vec<_>>();
+                (*height, txs)
+            })
+            .collect::<Vec<_>>();
+        result.sort_by_key(|(height, _)| *height);
+        result
+    }
+
+    /// Returns the number of blocks after which published transactions are discarded.
+    pub fn expiry_height(&self) -> u64 {
+        self.config.expiry_height
+    }
+
     fn get_next_key(&mut self) -> usize {
         let key = self.key_counter;
         self.key_counter = (self.key_counter + 1) % usize::MAX;
@@ -32,7 +32,7 @@ use tari_comms_rpc_macros::tari_rpc;
 use crate::{
     mempool::service::MempoolHandle,
     proto::{
-        mempool::{StateResponse, StatsResponse, TxStorage},
+        mempool::{StateResponse, StatsResponse, TransactionPackage, TxStorage},
         types::{Signature, Transaction},
     },
 };
@@ -53,6 +53,12 @@ pub trait MempoolService: Send + Sync + 'static {
 
     #[rpc(method = 4)]
     async fn submit_transaction(&self, request: Request<Transaction>) -> Result<Response<TxStorage>, RpcStatus>;
+
+    #[rpc(method = 5)]
+    async fn submit_transaction_package(
+        &self,
+        request: Request<TransactionPackage>,
+    ) -> Result<Response<TxStorage>, RpcStatus>;
 }
 
 pub fn create_mempool_rpc_service(mempool: MempoolHandle) -> MempoolRpcServer<MempoolRpcService> {
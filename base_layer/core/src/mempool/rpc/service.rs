@@ -32,6 +32,8 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "c::mempool::rpc";
+/// The maximum number of transactions accepted in a single transaction package
+const MAX_TRANSACTION_PACKAGE_SIZE: usize = 25;
 
 pub struct MempoolRpcService {
     mempool: MempoolHandle,
@@ -105,4 +107,41 @@ impl MempoolService for MempoolRpcService {
         let tx_storage = self.mempool().submit_transaction(tx).await.map_err(to_internal_error)?;
         Ok(Response::new(tx_storage.into()))
     }
+
+    async fn submit_transaction_package(
+        &self,
+        request: Request<proto::mempool::TransactionPackage>,
+    ) -> Result<Response<proto::mempool::TxStorage>, RpcStatus> {
+        let (context, message) = request.into_parts();
+        if message.transactions.is_empty() || message.transactions.len() > MAX_TRANSACTION_PACKAGE_SIZE {
+            return Err(RpcStatus::bad_request(&format!(
+                "Transaction package must contain between 1 and {} transactions",
+                MAX_TRANSACTION_PACKAGE_SIZE
+            )));
+        }
+        let txs = match message
+            .transactions
+            .into_iter()
+            .map(Transaction::try_from)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(txs) => txs,
+            Err(err) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Received invalid package from peer `{}`: {}",
+                    context.peer_node_id(),
+                    err
+                );
+                // These error messages are safe to send back to the requester
+                return Err(RpcStatus::bad_request(&format!("Malformed transaction: {}", err)));
+            },
+        };
+        let tx_storage = self
+            .mempool()
+            .submit_transaction_package(txs)
+            .await
+            .map_err(to_internal_error)?;
+        Ok(Response::new(tx_storage.into()))
+    }
 }
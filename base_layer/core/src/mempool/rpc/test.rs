@@ -181,3 +181,78 @@ mod submit_transaction {
         unpack_enum!(RpcStatusCode::BadRequest = status.as_status_code());
     }
 }
+
+mod submit_transaction_package {
+    use tari_comms::protocol::rpc::RpcStatusCode;
+    use tari_crypto::ristretto::RistrettoSecretKey;
+    use tari_test_utils::unpack_enum;
+    use tari_utilities::ByteArray;
+
+    use super::*;
+    use crate::{
+        mempool::{MempoolService, TxStorageResponse},
+        proto::{
+            mempool::TransactionPackage,
+            types::{AggregateBody, PrivateKey, Transaction},
+        },
+    };
+
+    fn empty_transaction() -> Transaction {
+        Transaction {
+            offset: Some(PrivateKey {
+                data: RistrettoSecretKey::default().to_vec(),
+            }),
+            body: Some(AggregateBody {
+                inputs: vec![],
+                outputs: vec![],
+                kernels: vec![],
+            }),
+            script_offset: Some(PrivateKey {
+                data: RistrettoSecretKey::default().to_vec(),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_submits_transaction_package() {
+        let (service, mempool, req_mock, _tmpdir) = setup();
+        let expected = TxStorageResponse::UnconfirmedPool;
+        mempool.set_submit_transaction_response(expected.clone()).await;
+        let package = TransactionPackage {
+            transactions: vec![empty_transaction(), empty_transaction()],
+        };
+        let resp = service
+            .submit_transaction_package(req_mock.request_with_context(Default::default(), package))
+            .await
+            .unwrap();
+        let resp = resp.into_message();
+        assert_eq!(resp, expected.into());
+        assert_eq!(mempool.get_call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_errors_on_empty_package() {
+        let (service, mempool, req_mock, _tmpdir) = setup();
+        let status = service
+            .submit_transaction_package(req_mock.request_with_context(Default::default(), Default::default()))
+            .await
+            .unwrap_err();
+
+        unpack_enum!(RpcStatusCode::BadRequest = status.as_status_code());
+        assert_eq!(mempool.get_call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_errors_on_invalid_transaction() {
+        let (service, _, req_mock, _tmpdir) = setup();
+        let package = TransactionPackage {
+            transactions: vec![empty_transaction(), Default::default()],
+        };
+        let status = service
+            .submit_transaction_package(req_mock.request_with_context(Default::default(), package))
+            .await
+            .unwrap_err();
+
+        unpack_enum!(RpcStatusCode::BadRequest = status.as_status_code());
+    }
+}
@@ -26,6 +26,7 @@ use tari_service_framework::{reply_channel::TrySenderService, Service};
 use crate::{
     mempool::{
         service::{MempoolRequest, MempoolResponse},
+        FeeEstimate,
         FeePerGramStat,
         MempoolServiceError,
         StateResponse,
@@ -83,6 +84,20 @@ impl MempoolHandle {
         }
     }
 
+    pub async fn submit_transaction_package(
+        &mut self,
+        transactions: Vec<Transaction>,
+    ) -> Result<TxStorageResponse, MempoolServiceError> {
+        match self
+            .inner
+            .call(MempoolRequest::SubmitTransactionPackage(transactions))
+            .await??
+        {
+            MempoolResponse::TxStorage(response) => Ok(response),
+            _ => Err(MempoolServiceError::InvalidResponse("Incorrect response".to_string())),
+        }
+    }
+
     pub async fn get_fee_per_gram_stats(
         &mut self,
         count: usize,
@@ -97,4 +112,24 @@ impl MempoolHandle {
             _ => Err(MempoolServiceError::InvalidResponse("Incorrect response".to_string())),
         }
     }
+
+    pub async fn estimate_fee(
+        &mut self,
+        confirmation_target: u64,
+        confidence_percent: u32,
+        tip_height: u64,
+    ) -> Result<FeeEstimate, MempoolServiceError> {
+        match self
+            .inner
+            .call(MempoolRequest::EstimateFee {
+                confirmation_target,
+                confidence_percent,
+                tip_height,
+            })
+            .await??
+        {
+            MempoolResponse::FeeEstimate(estimate) => Ok(estimate),
+            _ => Err(MempoolServiceError::InvalidResponse("Incorrect response".to_string())),
+        }
+    }
 }
@@ -32,7 +32,13 @@ use crate::{
     base_node::comms_interface::{BlockEvent, BlockEvent::AddBlockErrored},
     chain_storage::BlockAddResult,
     mempool::{
-        service::{MempoolRequest, MempoolResponse, MempoolServiceError, OutboundMempoolServiceInterface},
+        service::{
+            MempoolEventSender,
+            MempoolRequest,
+            MempoolResponse,
+            MempoolServiceError,
+            OutboundMempoolServiceInterface,
+        },
         Mempool,
         TxStorageResponse,
     },
@@ -41,27 +47,48 @@ use crate::{
 
 pub const LOG_TARGET: &str = "c::mp::service::inbound_handlers";
 
+/// Events published by the mempool service
+#[derive(Debug, Clone)]
+pub enum MempoolEvent {
+    /// A transaction was accepted into the unconfirmed pool
+    TransactionAccepted(Arc<Transaction>),
+}
+
 /// The MempoolInboundHandlers is used to handle all received inbound mempool requests and transactions from remote
 /// nodes.
 #[derive(Clone)]
 pub struct MempoolInboundHandlers {
     mempool: Mempool,
     outbound_service: OutboundMempoolServiceInterface,
+    mempool_event_sender: MempoolEventSender,
 }
 
 impl MempoolInboundHandlers {
     /// Construct the MempoolInboundHandlers.
-    pub fn new(mempool: Mempool, outbound_service: OutboundMempoolServiceInterface) -> Self {
+    pub fn new(
+        mempool: Mempool,
+        outbound_service: OutboundMempoolServiceInterface,
+        mempool_event_sender: MempoolEventSender,
+    ) -> Self {
         Self {
             mempool,
             outbound_service,
+            mempool_event_sender,
         }
     }
 
     /// Handle inbound Mempool service requests from remote nodes and local services.
     pub async fn handle_request(&mut self, request: MempoolRequest) -> Result<MempoolResponse, MempoolServiceError> {
         trace!(target: LOG_TARGET, "Handling remote request: {}", request);
-        use MempoolRequest::{GetFeePerGramStats, GetState, GetStats, GetTxStateByExcessSig, SubmitTransaction};
+        use MempoolRequest::{
+            EstimateFee,
+            GetFeePerGramStats,
+            GetState,
+            GetStats,
+            GetTxStateByExcessSig,
+            SubmitTransaction,
+            SubmitTransactionPackage,
+        };
         match request {
             GetStats => Ok(MempoolResponse::Stats(self.mempool.stats().await?)),
             GetState => Ok(MempoolResponse::State(self.mempool.state().await?)),
@@ -81,10 +108,29 @@ impl MempoolInboundHandlers {
                 );
                 Ok(MempoolResponse::TxStorage(self.submit_transaction(tx, None).await?))
             },
+            SubmitTransactionPackage(txs) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Package of {} transaction(s) submitted using request.",
+                    txs.len(),
+                );
+                Ok(MempoolResponse::TxStorage(self.submit_transaction_package(txs).await?))
+            },
             GetFeePerGramStats { count, tip_height } => {
                 let stats = self.mempool.get_fee_per_gram_stats(count, tip_height).await?;
                 Ok(MempoolResponse::FeePerGramStats { response: stats })
             },
+            EstimateFee {
+                confirmation_target,
+                confidence_percent,
+                tip_height,
+            } => {
+                let estimate = self
+                    .mempool
+                    .estimate_fee(confirmation_target, confidence_percent, tip_height)
+                    .await?;
+                Ok(MempoolResponse::FeeEstimate(estimate))
+            },
         }
     }
 
@@ -149,7 +195,11 @@ impl MempoolInboundHandlers {
                     "Transaction inserted into mempool: {}, pool: {}.", kernel_excess_sig, tx_storage
                 );
                 // propagate the tx if it was accepted to the unconfirmed pool
-                if matches!(tx_storage, TxStorageResponse::UnconfirmedPool) {
+                if matches!(
+                    tx_storage,
+                    TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplacedByFee
+                ) {
+                    self.publish_mempool_event(MempoolEvent::TransactionAccepted(tx.clone()));
                     debug!(
                         target: LOG_TARGET,
                         "Propagate transaction ({}) to network.", kernel_excess_sig,
@@ -164,6 +214,42 @@ impl MempoolInboundHandlers {
         }
     }
 
+    /// Submits an ordered package of dependent transactions to the mempool and propagates them if the package was
+    /// accepted.
+    async fn submit_transaction_package(
+        &mut self,
+        txs: Vec<Transaction>,
+    ) -> Result<TxStorageResponse, MempoolServiceError> {
+        if txs.iter().any(|tx| tx.first_kernel_excess_sig().is_none()) {
+            return Err(MempoolServiceError::TransactionNoKernels);
+        }
+        let txs = txs.into_iter().map(Arc::new).collect::<Vec<_>>();
+        let tx_storage = self.mempool.insert_package(txs.clone()).await?;
+        self.update_pool_size_metrics().await;
+        debug!(
+            target: LOG_TARGET,
+            "Package of {} transaction(s) processed by mempool, pool: {}.",
+            txs.len(),
+            tx_storage
+        );
+        if matches!(
+            tx_storage,
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplacedByFee
+        ) {
+            // Parents are propagated before their children so that peers can validate them in order
+            for tx in txs {
+                self.publish_mempool_event(MempoolEvent::TransactionAccepted(tx.clone()));
+                self.outbound_service.propagate_tx(tx, vec![]).await?;
+            }
+        }
+        Ok(tx_storage)
+    }
+
+    fn publish_mempool_event(&self, event: MempoolEvent) -> usize {
+        // If event send fails, that means that there are no receivers (i.e. it was sent to zero receivers)
+        self.mempool_event_sender.send(Arc::new(event)).unwrap_or(0)
+    }
+
     #[allow(clippy::cast_possible_wrap)]
     async fn update_pool_size_metrics(&self) {
         #[cfg(feature = "metrics")]
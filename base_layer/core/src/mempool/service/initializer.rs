@@ -37,7 +37,7 @@ use tari_service_framework::{
     ServiceInitializer,
     ServiceInitializerContext,
 };
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 
 use crate::{
     base_node::comms_interface::LocalNodeCommsInterface,
@@ -126,8 +126,13 @@ impl ServiceInitializer for MempoolServiceInitializer {
         let (outbound_tx_sender, outbound_tx_stream) = mpsc::unbounded_channel();
         let (local_request_sender_service, local_request_stream) = reply_channel::unbounded();
         let outbound_mp_interface = OutboundMempoolServiceInterface::new(outbound_tx_sender);
-        let local_mp_interface = LocalMempoolService::new(local_request_sender_service);
-        let inbound_handlers = MempoolInboundHandlers::new(self.mempool.clone(), outbound_mp_interface.clone());
+        let (mempool_event_sender, _) = broadcast::channel(100);
+        let local_mp_interface = LocalMempoolService::new(local_request_sender_service, mempool_event_sender.clone());
+        let inbound_handlers = MempoolInboundHandlers::new(
+            self.mempool.clone(),
+            outbound_mp_interface.clone(),
+            mempool_event_sender,
+        );
 
         // Register handle to OutboundMempoolServiceInterface before waiting for handles to be ready
         context.register_handle(outbound_mp_interface);
@@ -20,12 +20,16 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::sync::Arc;
+
 use tari_common_types::types::Signature;
 use tari_service_framework::{reply_channel::SenderService, Service};
+use tokio::sync::broadcast;
 
 use crate::{
     mempool::{
-        service::{MempoolRequest, MempoolResponse, MempoolServiceError},
+        service::{MempoolEvent, MempoolRequest, MempoolResponse, MempoolServiceError},
+        FeeEstimate,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -34,6 +38,8 @@ use crate::{
 };
 
 pub type LocalMempoolRequester = SenderService<MempoolRequest, Result<MempoolResponse, MempoolServiceError>>;
+pub type MempoolEventSender = broadcast::Sender<Arc<MempoolEvent>>;
+pub type MempoolEventReceiver = broadcast::Receiver<Arc<MempoolEvent>>;
 
 /// A local interface into the mempool service.
 ///
@@ -45,6 +51,7 @@ pub type LocalMempoolRequester = SenderService<MempoolRequest, Result<MempoolRes
 #[derive(Clone)]
 pub struct LocalMempoolService {
     request_sender: LocalMempoolRequester,
+    mempool_event_sender: MempoolEventSender,
 }
 
 impl LocalMempoolService {
@@ -54,8 +61,17 @@ impl LocalMempoolService {
     ///
     /// To make things a little more ergonomic, the channel handling is done for you in the other member functions,
     /// such that the request behaves like a standard future.
-    pub fn new(request_sender: LocalMempoolRequester) -> Self {
-        LocalMempoolService { request_sender }
+    pub fn new(request_sender: LocalMempoolRequester, mempool_event_sender: MempoolEventSender) -> Self {
+        LocalMempoolService {
+            request_sender,
+            mempool_event_sender,
+        }
+    }
+
+    /// Returns a stream of events published by the mempool service, such as transactions accepted into the
+    /// unconfirmed pool
+    pub fn get_mempool_event_stream(&self) -> MempoolEventReceiver {
+        self.mempool_event_sender.subscribe()
     }
 
     /// Returns a future that resolves to the current mempool statistics
@@ -87,6 +103,20 @@ impl LocalMempoolService {
         }
     }
 
+    pub async fn submit_transaction_package(
+        &mut self,
+        transactions: Vec<Transaction>,
+    ) -> Result<TxStorageResponse, MempoolServiceError> {
+        match self
+            .request_sender
+            .call(MempoolRequest::SubmitTransactionPackage(transactions))
+            .await??
+        {
+            MempoolResponse::TxStorage(s) => Ok(s),
+            _ => Err(MempoolServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_transaction_state_by_excess_sig(
         &mut self,
         sig: Signature,
@@ -100,13 +130,33 @@ impl LocalMempoolService {
             _ => Err(MempoolServiceError::UnexpectedApiResponse),
         }
     }
+
+    pub async fn estimate_fee(
+        &mut self,
+        confirmation_target: u64,
+        confidence_percent: u32,
+        tip_height: u64,
+    ) -> Result<FeeEstimate, MempoolServiceError> {
+        match self
+            .request_sender
+            .call(MempoolRequest::EstimateFee {
+                confirmation_target,
+                confidence_percent,
+                tip_height,
+            })
+            .await??
+        {
+            MempoolResponse::FeeEstimate(estimate) => Ok(estimate),
+            _ => Err(MempoolServiceError::UnexpectedApiResponse),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use futures::StreamExt;
     use tari_service_framework::reply_channel::{unbounded, Receiver};
-    use tokio::task;
+    use tokio::{sync::broadcast, task};
 
     use crate::mempool::{
         service::{local_service::LocalMempoolService, MempoolRequest, MempoolResponse},
@@ -138,7 +188,7 @@ mod test {
     #[tokio::test]
     async fn mempool_stats() {
         let (tx, rx) = unbounded();
-        let mut service = LocalMempoolService::new(tx);
+        let mut service = LocalMempoolService::new(tx, broadcast::channel(1).0);
         task::spawn(mock_handler(rx));
         let stats = service.get_mempool_stats().await;
         let stats = stats.expect("get_mempool_stats should have succeeded");
@@ -148,7 +198,7 @@ mod test {
     #[tokio::test]
     async fn mempool_stats_from_multiple() {
         let (tx, rx) = unbounded();
-        let mut service = LocalMempoolService::new(tx);
+        let mut service = LocalMempoolService::new(tx, broadcast::channel(1).0);
         let mut service2 = service.clone();
         task::spawn(mock_handler(rx));
         let stats = service.get_mempool_stats().await;
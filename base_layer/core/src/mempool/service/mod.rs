@@ -27,6 +27,8 @@ pub use error::MempoolServiceError;
 
 #[cfg(feature = "base_node")]
 mod inbound_handlers;
+#[cfg(feature = "base_node")]
+pub use inbound_handlers::MempoolEvent;
 
 #[cfg(feature = "base_node")]
 mod initializer;
@@ -36,7 +38,7 @@ pub use initializer::MempoolServiceInitializer;
 #[cfg(feature = "base_node")]
 mod local_service;
 #[cfg(feature = "base_node")]
-pub use local_service::LocalMempoolService;
+pub use local_service::{LocalMempoolService, MempoolEventReceiver, MempoolEventSender};
 
 #[cfg(feature = "base_node")]
 mod outbound_interface;
@@ -36,7 +36,16 @@ pub enum MempoolRequest {
     GetState,
     GetTxStateByExcessSig(Signature),
     SubmitTransaction(Transaction),
-    GetFeePerGramStats { count: usize, tip_height: u64 },
+    SubmitTransactionPackage(Vec<Transaction>),
+    GetFeePerGramStats {
+        count: usize,
+        tip_height: u64,
+    },
+    EstimateFee {
+        confirmation_target: u64,
+        confidence_percent: u32,
+        tip_height: u64,
+    },
 }
 
 impl Display for MempoolRequest {
@@ -54,9 +63,21 @@ impl Display for MempoolRequest {
                     .unwrap_or_else(|| "No kernels!".to_string());
                 write!(f, "SubmitTransaction ({})", sig_hex)
             },
+            MempoolRequest::SubmitTransactionPackage(txs) => {
+                write!(f, "SubmitTransactionPackage ({} transaction(s))", txs.len())
+            },
             MempoolRequest::GetFeePerGramStats { count, tip_height } => {
                 write!(f, "GetFeePerGramStats(count: {}, tip_height: {})", *count, *tip_height)
             },
+            MempoolRequest::EstimateFee {
+                confirmation_target,
+                confidence_percent,
+                tip_height,
+            } => write!(
+                f,
+                "EstimateFee(confirmation_target: {}, confidence_percent: {}, tip_height: {})",
+                confirmation_target, confidence_percent, tip_height
+            ),
         }
     }
 }
@@ -0,0 +1,172 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    fmt::{Display, Error, Formatter},
+    fs,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{mempool::error::MempoolError, transactions::transaction_components::Transaction};
+
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// A serializable copy of the contents of the mempool, used to persist the unconfirmed and reorg pools across node
+/// restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MempoolSnapshot {
+    version: u8,
+    /// The transactions held in the unconfirmed pool
+    pub unconfirmed_pool: Vec<Transaction>,
+    /// The transactions held in the reorg pool, grouped by the height of the block they were published in
+    pub reorg_pool: Vec<(u64, Vec<Transaction>)>,
+}
+
+impl MempoolSnapshot {
+    pub fn new(unconfirmed_pool: Vec<Arc<Transaction>>, reorg_pool: Vec<(u64, Vec<Arc<Transaction>>)>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            unconfirmed_pool: unconfirmed_pool.iter().map(|tx| tx.as_ref().clone()).collect(),
+            reorg_pool: reorg_pool
+                .into_iter()
+                .map(|(height, txs)| (height, txs.iter().map(|tx| tx.as_ref().clone()).collect()))
+                .collect(),
+        }
+    }
+
+    /// The total number of transactions contained in the snapshot
+    pub fn len(&self) -> usize {
+        self.unconfirmed_pool.len() + self.reorg_pool.iter().map(|(_, txs)| txs.len()).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the snapshot to the given file. The snapshot is first written to a temporary file alongside the target
+    /// and then moved into place, so that an interrupted write never leaves a truncated snapshot behind.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), MempoolError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        {
+            let file = File::create(&tmp_path).map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+            let mut writer = BufWriter::new(file);
+            bincode::serialize_into(&mut writer, self).map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+            writer.flush().map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+        }
+        fs::rename(&tmp_path, path).map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Reads a snapshot previously written with [MempoolSnapshot::write_to_file].
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, MempoolError> {
+        let file = File::open(path.as_ref()).map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+        let snapshot: Self =
+            bincode::deserialize_from(BufReader::new(file)).map_err(|e| MempoolError::SnapshotError(e.to_string()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(MempoolError::SnapshotError(format!(
+                "Unsupported snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+}
+
+/// The outcome of restoring a [MempoolSnapshot] into the mempool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotRestoreStats {
+    /// Transactions that passed validation and were added to the unconfirmed pool
+    pub unconfirmed_restored: usize,
+    /// Transactions that are no longer valid, e.g. because they were mined or double spent while the node was offline
+    pub unconfirmed_rejected: usize,
+    /// Published transactions added back to the reorg pool
+    pub reorg_restored: usize,
+    /// Published transactions that have expired from the reorg pool since the snapshot was taken
+    pub reorg_expired: usize,
+}
+
+impl Display for SnapshotRestoreStats {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            fmt,
+            "Unconfirmed: {} restored, {} rejected. Reorg pool: {} restored, {} expired",
+            self.unconfirmed_restored, self.unconfirmed_rejected, self.reorg_restored, self.reorg_expired
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        transactions::{key_manager::create_memory_db_key_manager, tari_amount::MicroMinotari},
+        tx,
+    };
+
+    #[tokio::test]
+    async fn it_round_trips_through_a_file() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let tx1 = Arc::new(
+            tx!(MicroMinotari(100_000), fee: MicroMinotari(100), lock: 0, inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let tx2 = Arc::new(
+            tx!(MicroMinotari(100_000), fee: MicroMinotari(60), lock: 0, inputs: 1, outputs: 2, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let snapshot = MempoolSnapshot::new(vec![tx1.clone()], vec![(5, vec![tx2.clone()])]);
+        assert_eq!(snapshot.len(), 2);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mempool").join("snapshot.bin");
+        snapshot.write_to_file(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        let restored = MempoolSnapshot::read_from_file(&path).unwrap();
+        assert_eq!(restored.unconfirmed_pool, vec![tx1.as_ref().clone()]);
+        assert_eq!(restored.reorg_pool, vec![(5, vec![tx2.as_ref().clone()])]);
+    }
+
+    #[test]
+    fn it_rejects_a_corrupt_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("snapshot.bin");
+        fs::write(&path, [0xffu8; 16]).unwrap();
+        assert!(matches!(
+            MempoolSnapshot::read_from_file(&path),
+            Err(MempoolError::SnapshotError(_))
+        ));
+    }
+}
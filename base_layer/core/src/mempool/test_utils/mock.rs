@@ -124,7 +124,15 @@ impl MempoolServiceMock {
     }
 
     async fn handle_request(&self, req: MempoolRequest) -> Result<MempoolResponse, MempoolServiceError> {
-        use MempoolRequest::{GetFeePerGramStats, GetState, GetStats, GetTxStateByExcessSig, SubmitTransaction};
+        use MempoolRequest::{
+            EstimateFee,
+            GetFeePerGramStats,
+            GetState,
+            GetStats,
+            GetTxStateByExcessSig,
+            SubmitTransaction,
+            SubmitTransactionPackage,
+        };
 
         self.state.inc_call_count();
         match req {
@@ -133,10 +141,10 @@ impl MempoolServiceMock {
             GetTxStateByExcessSig(_) => Ok(MempoolResponse::TxStorage(
                 self.state.get_tx_state_by_excess_sig.lock().await.clone(),
             )),
-            SubmitTransaction(_) => Ok(MempoolResponse::TxStorage(
+            SubmitTransaction(_) | SubmitTransactionPackage(_) => Ok(MempoolResponse::TxStorage(
                 self.state.submit_transaction.lock().await.clone(),
             )),
-            GetFeePerGramStats { .. } => {
+            GetFeePerGramStats { .. } | EstimateFee { .. } => {
                 unimplemented!()
             },
         }
@@ -38,6 +38,7 @@ use crate::{
         unconfirmed_pool::UnconfirmedPoolError,
         FeePerGramStat,
         MempoolError,
+        TxStorageResponse,
     },
     transactions::{
         tari_amount::MicroMinotari,
@@ -61,6 +62,14 @@ pub struct UnconfirmedPoolConfig {
     pub weight_tx_skip_count: usize,
     /// The minimum fee accepted by this mempool
     pub min_fee: u64,
+    /// When enabled, a transaction spending an input that is already spent by a transaction in the pool replaces that
+    /// transaction (and all of its descendants) if it pays a strictly higher fee per byte and at least
+    /// `replace_by_fee_min_increment` more in absolute fees. When disabled, conflicting transactions are kept side by
+    /// side and the conflict is only resolved when a block template is built.
+    pub replace_by_fee: bool,
+    /// The minimum absolute fee (in µT) that a replacement must pay over and above the total fees of all the
+    /// transactions it evicts
+    pub replace_by_fee_min_increment: u64,
 }
 
 impl Default for UnconfirmedPoolConfig {
@@ -69,6 +78,8 @@ impl Default for UnconfirmedPoolConfig {
             storage_capacity: 40_000,
             weight_tx_skip_count: 20,
             min_fee: 0,
+            replace_by_fee: false,
+            replace_by_fee_min_increment: 100,
         }
     }
 }
@@ -88,6 +99,7 @@ pub struct UnconfirmedPool {
     txs_by_signature: HashMap<PrivateKey, Vec<TransactionKey>>,
     tx_by_priority: BTreeMap<FeePriority, TransactionKey>,
     txs_by_output: HashMap<HashOutput, Vec<TransactionKey>>,
+    txs_by_input: HashMap<HashOutput, Vec<TransactionKey>>,
     txs_by_unique_id: HashMap<[u8; 32], Vec<TransactionKey>>,
 }
 
@@ -110,6 +122,7 @@ impl UnconfirmedPool {
             txs_by_signature: HashMap::new(),
             tx_by_priority: BTreeMap::new(),
             txs_by_output: HashMap::new(),
+            txs_by_input: HashMap::new(),
             txs_by_unique_id: HashMap::new(),
         }
     }
@@ -117,27 +130,178 @@ impl UnconfirmedPool {
     /// Insert a new transaction into the UnconfirmedPool. Low priority transactions will be removed to make space for
     /// higher priority transactions. The lowest priority transactions will be removed when the maximum capacity is
     /// reached and the new transaction has a higher priority than the currently stored lowest priority transaction.
+    /// If replace-by-fee is enabled, transactions that conflict with the new transaction are evicted if the new
+    /// transaction is an acceptable replacement, otherwise the new transaction is rejected.
     pub fn insert(
         &mut self,
         tx: Arc<Transaction>,
         dependent_outputs: Option<Vec<HashOutput>>,
         transaction_weighting: &TransactionWeight,
-    ) -> Result<(), UnconfirmedPoolError> {
+    ) -> Result<TxStorageResponse, UnconfirmedPoolError> {
         if tx
             .body
             .kernels()
             .iter()
             .all(|k| self.txs_by_signature.contains_key(k.excess_sig.get_signature()))
         {
-            return Ok(());
+            return Ok(TxStorageResponse::UnconfirmedPool);
         }
 
         let new_key = self.get_next_key();
         let prioritized_tx = PrioritizedTransaction::new(new_key, transaction_weighting, tx, dependent_outputs)?;
-        if self.tx_by_key.len() >= self.config.storage_capacity {
-            if prioritized_tx.priority < *self.lowest_priority()? {
-                return Ok(());
+        if self.tx_by_key.len() >= self.config.storage_capacity && prioritized_tx.priority < *self.lowest_priority()? {
+            return Ok(TxStorageResponse::UnconfirmedPool);
+        }
+        if let Some(rejection) = self.check_replacement(&prioritized_tx)? {
+            return Ok(rejection);
+        }
+
+        self.insert_prioritized(prioritized_tx)
+    }
+
+    /// Insert an ordered package of dependent transactions, parents before children, into the UnconfirmedPool. The
+    /// package is evaluated as a unit: members are ranked by the combined fee per byte of the package (if that is
+    /// higher than their own), so that a low-fee parent is admitted and kept in the pool because of the fees paid by
+    /// its children. The package is accepted or rejected as a whole.
+    pub fn insert_package(
+        &mut self,
+        package: Vec<(Arc<Transaction>, Option<Vec<HashOutput>>)>,
+        transaction_weighting: &TransactionWeight,
+    ) -> Result<TxStorageResponse, UnconfirmedPoolError> {
+        let mut prioritized_txs = Vec::with_capacity(package.len());
+        for (tx, dependent_outputs) in package {
+            if tx
+                .body
+                .kernels()
+                .iter()
+                .all(|k| self.txs_by_signature.contains_key(k.excess_sig.get_signature()))
+            {
+                continue;
+            }
+            let new_key = self.get_next_key();
+            prioritized_txs.push(PrioritizedTransaction::new(
+                new_key,
+                transaction_weighting,
+                tx,
+                dependent_outputs,
+            )?);
+        }
+        if prioritized_txs.is_empty() {
+            return Ok(TxStorageResponse::UnconfirmedPool);
+        }
+
+        let mut package_fees = 0u64;
+        let mut package_weight = 0u64;
+        for prioritized_tx in &prioritized_txs {
+            package_fees = package_fees
+                .checked_add(prioritized_tx.transaction.body.get_total_fee()?.as_u64())
+                .ok_or(UnconfirmedPoolError::InternalError(
+                    "Overflow when calculating package fees".to_string(),
+                ))?;
+            package_weight =
+                package_weight
+                    .checked_add(prioritized_tx.weight)
+                    .ok_or(UnconfirmedPoolError::InternalError(
+                        "Overflow when calculating package weight".to_string(),
+                    ))?;
+        }
+        let package_fee_per_byte = package_fees
+            .saturating_mul(1000)
+            .checked_div(package_weight)
+            .ok_or(TransactionError::ZeroWeight)?;
+        for prioritized_tx in &mut prioritized_txs {
+            if prioritized_tx.fee_per_byte < package_fee_per_byte {
+                prioritized_tx.priority = prioritized_tx.priority.with_fee_per_byte(package_fee_per_byte);
+            }
+        }
+
+        if self.tx_by_key.len() + prioritized_txs.len() > self.config.storage_capacity {
+            let lowest_package_priority = prioritized_txs
+                .iter()
+                .map(|tx| &tx.priority)
+                .min()
+                .ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            if self.tx_by_key.is_empty() || lowest_package_priority < self.lowest_priority()? {
+                debug!(
+                    target: LOG_TARGET,
+                    "Package of {} transaction(s) does not pay enough fees to be stored in a full unconfirmed pool",
+                    prioritized_txs.len()
+                );
+                return Ok(TxStorageResponse::NotStoredFeeTooLow);
+            }
+        }
+        for prioritized_tx in &prioritized_txs {
+            if let Some(rejection) = self.check_replacement(prioritized_tx)? {
+                return Ok(rejection);
+            }
+        }
+
+        let mut storage_response = TxStorageResponse::UnconfirmedPool;
+        for prioritized_tx in prioritized_txs {
+            if self.insert_prioritized(prioritized_tx)? == TxStorageResponse::UnconfirmedPoolReplacedByFee {
+                storage_response = TxStorageResponse::UnconfirmedPoolReplacedByFee;
+            }
+        }
+        Ok(storage_response)
+    }
+
+    /// If replace-by-fee is enabled and the transaction conflicts with transactions in the pool, checks that it is an
+    /// acceptable replacement. Returns the storage response to reject the transaction with if it is not.
+    fn check_replacement(
+        &self,
+        prioritized_tx: &PrioritizedTransaction,
+    ) -> Result<Option<TxStorageResponse>, UnconfirmedPoolError> {
+        if !self.config.replace_by_fee {
+            return Ok(None);
+        }
+        let conflicting_keys = self.find_conflicting_transactions(&prioritized_tx.transaction);
+        if conflicting_keys.is_empty() {
+            return Ok(None);
+        }
+        let keys_to_evict = self.with_all_descendants(&conflicting_keys)?;
+        if self.spends_outputs_of(prioritized_tx, &keys_to_evict)? {
+            debug!(
+                target: LOG_TARGET,
+                "Replacement transaction {} spends outputs of a transaction it replaces, rejecting", prioritized_tx
+            );
+            return Ok(Some(TxStorageResponse::NotStoredOrphan));
+        }
+        if !self.is_valid_replacement(prioritized_tx, &conflicting_keys, &keys_to_evict)? {
+            debug!(
+                target: LOG_TARGET,
+                "Replacement transaction {} does not pay enough fees to replace {} transaction(s), rejecting",
+                prioritized_tx,
+                keys_to_evict.len()
+            );
+            return Ok(Some(TxStorageResponse::NotStoredReplacementFeeTooLow));
+        }
+        Ok(None)
+    }
+
+    /// Stores a transaction that has already been accepted, evicting the transactions it replaces and the lowest
+    /// priority transaction if the pool is full.
+    fn insert_prioritized(
+        &mut self,
+        prioritized_tx: PrioritizedTransaction,
+    ) -> Result<TxStorageResponse, UnconfirmedPoolError> {
+        let new_key = prioritized_tx.key;
+        let mut storage_response = TxStorageResponse::UnconfirmedPool;
+        if self.config.replace_by_fee {
+            let conflicting_keys = self.find_conflicting_transactions(&prioritized_tx.transaction);
+            if !conflicting_keys.is_empty() {
+                for key in self.with_all_descendants(&conflicting_keys)? {
+                    if let Some(evicted) = self.remove_transaction(key)? {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Evicted transaction {} from unconfirmed pool, replaced by {}", evicted, prioritized_tx
+                        );
+                    }
+                }
+                storage_response = TxStorageResponse::UnconfirmedPoolReplacedByFee;
             }
+        }
+
+        if self.tx_by_key.len() >= self.config.storage_capacity {
             self.remove_lowest_priority_tx()?;
         }
 
@@ -145,6 +309,9 @@ impl UnconfirmedPool {
         for output in prioritized_tx.transaction.body.outputs() {
             self.txs_by_output.entry(output.hash()).or_default().push(new_key);
         }
+        for input in prioritized_tx.transaction.body.inputs() {
+            self.txs_by_input.entry(input.output_hash()).or_default().push(new_key);
+        }
         for kernel in prioritized_tx.transaction.body.kernels() {
             let sig = kernel.excess_sig.get_signature();
             self.txs_by_signature.entry(sig.clone()).or_default().push(new_key);
@@ -156,7 +323,93 @@ impl UnconfirmedPool {
         );
         self.tx_by_key.insert(new_key, prioritized_tx);
 
-        Ok(())
+        Ok(storage_response)
+    }
+
+    /// Returns the keys of all transactions in the pool that spend at least one of the inputs of the given transaction
+    fn find_conflicting_transactions(&self, transaction: &Transaction) -> Vec<TransactionKey> {
+        let mut conflicting_keys = Vec::new();
+        for input in transaction.body.inputs() {
+            if let Some(keys) = self.txs_by_input.get(&input.output_hash()) {
+                for key in keys {
+                    if !conflicting_keys.contains(key) {
+                        conflicting_keys.push(*key);
+                    }
+                }
+            }
+        }
+        conflicting_keys
+    }
+
+    /// Returns the given transaction keys together with the keys of all transactions in the pool that (directly or
+    /// indirectly) spend their outputs.
+    fn with_all_descendants(&self, keys: &[TransactionKey]) -> Result<Vec<TransactionKey>, UnconfirmedPoolError> {
+        let mut all_keys = keys.to_vec();
+        let mut index = 0;
+        while let Some(key) = all_keys.get(index).copied() {
+            let prioritized_transaction = self.tx_by_key.get(&key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            for output in prioritized_transaction.transaction.body.outputs() {
+                if let Some(spending_keys) = self.txs_by_input.get(&output.hash()) {
+                    for spending_key in spending_keys {
+                        if !all_keys.contains(spending_key) {
+                            all_keys.push(*spending_key);
+                        }
+                    }
+                }
+            }
+            index += 1;
+        }
+        Ok(all_keys)
+    }
+
+    /// Returns true if the transaction spends any output created by one of the given transactions
+    fn spends_outputs_of(
+        &self,
+        transaction: &PrioritizedTransaction,
+        keys: &[TransactionKey],
+    ) -> Result<bool, UnconfirmedPoolError> {
+        for key in keys {
+            let prioritized_transaction = self.tx_by_key.get(key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            if prioritized_transaction
+                .transaction
+                .body
+                .outputs()
+                .iter()
+                .any(|output| transaction.dependent_output_hashes.contains(&output.hash()))
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// A replacement is only valid if it pays a strictly higher fee per byte than every transaction it directly
+    /// conflicts with, and an absolute fee that covers the fees of all evicted transactions plus the configured
+    /// minimum increment.
+    fn is_valid_replacement(
+        &self,
+        replacement: &PrioritizedTransaction,
+        conflicting_keys: &[TransactionKey],
+        keys_to_evict: &[TransactionKey],
+    ) -> Result<bool, UnconfirmedPoolError> {
+        for key in conflicting_keys {
+            let conflicting = self.tx_by_key.get(key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            if replacement.fee_per_byte <= conflicting.fee_per_byte {
+                return Ok(false);
+            }
+        }
+
+        let mut evicted_fees = 0u64;
+        for key in keys_to_evict {
+            let evicted = self.tx_by_key.get(key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            evicted_fees = evicted_fees
+                .checked_add(evicted.transaction.body.get_total_fee()?.as_u64())
+                .ok_or(UnconfirmedPoolError::InternalError(
+                    "Overflow when calculating total fees".to_string(),
+                ))?;
+        }
+        let replacement_fee = replacement.transaction.body.get_total_fee()?.as_u64();
+        Ok(replacement_fee >= evicted_fees.saturating_add(self.config.replace_by_fee_min_increment))
     }
 
     /// This will search the unconfirmed pool for the set of outputs and return true if all of them are found
@@ -202,6 +455,10 @@ impl UnconfirmedPool {
         // fee_per_byte(TX_a+dependents) > fee_per_byte(TX_a+dependents-TX_b), that would mean that
         // fee_per_byte(TX_b)<fee_per_byte(TX_a+dependents), but if this would be the case then we would not
         // process TX_b before TX_a.
+        //
+        // Transactions inserted as part of a package are visited at the fee per byte of the whole package (see
+        // `insert_package`), so a low-fee parent is considered together with the ancestor set of the child paying
+        // for it rather than at its own fee per byte.
 
         let mut selected_txs = HashMap::new();
         let mut curr_weight = 0;
@@ -214,7 +471,7 @@ impl UnconfirmedPool {
         // for recomputing.
         let mut depended_on: HashMap<TransactionKey, Vec<&TransactionKey>> = HashMap::new();
         let mut recompute = HashSet::new();
-        for (_, tx_key) in self.tx_by_priority.iter().rev() {
+        for (fee_priority, tx_key) in self.tx_by_priority.iter().rev() {
             if selected_txs.contains_key(tx_key) {
                 continue;
             }
@@ -231,7 +488,7 @@ impl UnconfirmedPool {
                 &mut potentional_to_add,
                 &mut depended_on,
                 &mut recompute,
-                prioritized_transaction.fee_per_byte,
+                fee_priority.fee_per_byte(),
             )?;
             if curr_skip_count >= self.config.weight_tx_skip_count {
                 break;
@@ -560,6 +817,7 @@ impl UnconfirmedPool {
         self.txs_by_signature.clear();
         self.tx_by_priority.clear();
         self.txs_by_output.clear();
+        self.txs_by_input.clear();
         self.tx_by_key.drain().map(|(_, val)| val.transaction).collect()
     }
 
@@ -727,6 +985,20 @@ impl UnconfirmedPool {
             }
         }
 
+        for input in prioritized_transaction.transaction.body.inputs() {
+            let output_hash = input.output_hash();
+            if let Some(keys) = self.txs_by_input.get_mut(&output_hash) {
+                if let Some(pos) = keys.iter().position(|k| *k == tx_key) {
+                    keys.remove(pos);
+                }
+                if keys.is_empty() {
+                    self.txs_by_input.remove(&output_hash);
+                }
+            }
+        }
+
+        self.restore_ancestor_priorities(&prioritized_transaction.dependent_output_hashes)?;
+
         trace!(
             target: LOG_TARGET,
             "Deleted transaction: {}",
@@ -735,6 +1007,60 @@ impl UnconfirmedPool {
         Ok(Some(prioritized_transaction.transaction))
     }
 
+    /// Recomputes the priority of the in-pool ancestors of a removed transaction that are ranked by the fee per byte
+    /// of a package (see `insert_package`). They fall back to the fee per byte of what remains of their package, or to
+    /// their own fee per byte if that is higher.
+    fn restore_ancestor_priorities(&mut self, dependent_outputs: &[HashOutput]) -> Result<(), UnconfirmedPoolError> {
+        let mut ancestors = dependent_outputs
+            .iter()
+            .filter_map(|output_hash| self.txs_by_output.get(output_hash))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        let mut index = 0;
+        while let Some(key) = ancestors.get(index).copied() {
+            index += 1;
+            let prioritized_transaction = self.tx_by_key.get(&key).ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            for output_hash in &prioritized_transaction.dependent_output_hashes {
+                for ancestor in self.txs_by_output.get(output_hash).into_iter().flatten() {
+                    if !ancestors.contains(ancestor) {
+                        ancestors.push(*ancestor);
+                    }
+                }
+            }
+            if prioritized_transaction.priority.fee_per_byte() == prioritized_transaction.fee_per_byte {
+                continue;
+            }
+
+            let mut package_fees = 0u64;
+            let mut package_weight = 0u64;
+            for package_key in self.with_all_descendants(&[key])? {
+                let member = self
+                    .tx_by_key
+                    .get(&package_key)
+                    .ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+                package_fees = package_fees.saturating_add(member.transaction.body.get_total_fee()?.as_u64());
+                package_weight = package_weight.saturating_add(member.weight);
+            }
+            let package_fee_per_byte = package_fees
+                .saturating_mul(1000)
+                .checked_div(package_weight)
+                .ok_or(TransactionError::ZeroWeight)?;
+
+            let prioritized_transaction = self
+                .tx_by_key
+                .get_mut(&key)
+                .ok_or(UnconfirmedPoolError::StorageOutofSync)?;
+            let priority = prioritized_transaction
+                .priority
+                .with_fee_per_byte(package_fee_per_byte.max(prioritized_transaction.fee_per_byte));
+            self.tx_by_priority.remove(&prioritized_transaction.priority);
+            self.tx_by_priority.insert(priority.clone(), key);
+            prioritized_transaction.priority = priority;
+        }
+        Ok(())
+    }
+
     /// Returns the total number of unconfirmed transactions stored in the UnconfirmedPool.
     pub fn len(&self) -> usize {
         self.txs_by_signature.len()
@@ -827,6 +1153,9 @@ impl UnconfirmedPool {
             self.txs_by_output
                 .values()
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
+            self.txs_by_input
+                .values()
+                .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
             self.txs_by_unique_id
                 .values()
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key)))
@@ -844,6 +1173,7 @@ impl UnconfirmedPool {
         let (old, new) = shrink_hashmap(&mut self.tx_by_key);
         shrink_hashmap(&mut self.txs_by_signature);
         shrink_hashmap(&mut self.txs_by_output);
+        shrink_hashmap(&mut self.txs_by_input);
         shrink_hashmap(&mut self.txs_by_unique_id);
 
         if old > new {
@@ -943,6 +1273,7 @@ mod test {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
@@ -1042,6 +1373,7 @@ mod test {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
@@ -1102,6 +1434,7 @@ mod test {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -1178,6 +1511,7 @@ mod test {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -1208,6 +1542,241 @@ mod test {
         assert!(unconfirmed_pool.check_data_consistency());
     }
 
+    #[tokio::test]
+    async fn test_replace_by_fee() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let tx1 = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(5), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let tx2 = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(4), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let mut tx3 = tx!(MicroMinotari(5_000), fee: MicroMinotari(20), inputs: 2, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        // tx3 spends one of the inputs of tx1
+        let mut inputs = tx3.body.inputs().clone();
+        inputs[0] = tx1.body.inputs()[0].clone();
+        tx3.body = AggregateBody::new(inputs, tx3.body().outputs().clone(), tx3.body().kernels().clone());
+        let tx3 = Arc::new(tx3);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            replace_by_fee: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            unconfirmed_pool.insert(tx1.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::UnconfirmedPool
+        );
+        assert_eq!(
+            unconfirmed_pool.insert(tx2.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::UnconfirmedPool
+        );
+        assert_eq!(
+            unconfirmed_pool.insert(tx3.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::UnconfirmedPoolReplacedByFee
+        );
+
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx3.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[tokio::test]
+    async fn test_replace_by_fee_rejects_insufficient_fee() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let tx1 = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(10), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let mut tx2 = tx!(MicroMinotari(5_000), fee: MicroMinotari(5), inputs: 2, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        let mut tx3 = tx!(MicroMinotari(5_000), fee: MicroMinotari(11), inputs: 2, outputs: 1, &key_manager)
+            .expect("Failed to get tx")
+            .0;
+        // tx2 and tx3 both spend one of the inputs of tx1
+        let mut inputs = tx2.body.inputs().clone();
+        inputs[0] = tx1.body.inputs()[0].clone();
+        tx2.body = AggregateBody::new(inputs, tx2.body().outputs().clone(), tx2.body().kernels().clone());
+        let mut inputs = tx3.body.inputs().clone();
+        inputs[1] = tx1.body.inputs()[1].clone();
+        tx3.body = AggregateBody::new(inputs, tx3.body().outputs().clone(), tx3.body().kernels().clone());
+        let tx2 = Arc::new(tx2);
+        let tx3 = Arc::new(tx3);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            replace_by_fee: true,
+            replace_by_fee_min_increment: tx1.body.get_total_fee().unwrap().as_u64(),
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(tx1.clone(), None, &tx_weight).unwrap();
+        // tx2 pays a lower fee per byte than tx1
+        assert_eq!(
+            unconfirmed_pool.insert(tx2.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::NotStoredReplacementFeeTooLow
+        );
+        // tx3 pays a higher fee per byte than tx1, but does not meet the minimum absolute fee increment
+        assert_eq!(
+            unconfirmed_pool.insert(tx3.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::NotStoredReplacementFeeTooLow
+        );
+
+        assert_eq!(unconfirmed_pool.len(), 1);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx3.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[tokio::test]
+    async fn test_insert_package_child_pays_for_parent() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let parent = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(1), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let child = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(80), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let other = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(20), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let filler = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(5), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let cheap_child = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(2), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let child_dependent_outputs = Some(vec![parent.body.outputs()[0].hash()]);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 3,
+            weight_tx_skip_count: 3,
+            min_fee: 0,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many([other.clone(), filler.clone()], &tx_weight)
+            .expect("Failed to insert many");
+
+        // On its own the parent pays too little to be stored in a full pool
+        let mut full_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 2,
+            ..unconfirmed_pool.config
+        });
+        full_pool
+            .insert_many([other.clone(), filler.clone()], &tx_weight)
+            .expect("Failed to insert many");
+        full_pool.insert(parent.clone(), None, &tx_weight).unwrap();
+        assert!(!full_pool.has_tx_with_excess_sig(&parent.body.kernels()[0].excess_sig));
+
+        // A package whose combined fee per byte is too low is rejected as a whole
+        assert_eq!(
+            unconfirmed_pool
+                .insert_package(
+                    vec![
+                        (parent.clone(), None),
+                        (cheap_child.clone(), child_dependent_outputs.clone())
+                    ],
+                    &tx_weight
+                )
+                .unwrap(),
+            TxStorageResponse::NotStoredFeeTooLow
+        );
+        assert_eq!(unconfirmed_pool.len(), 2);
+
+        // The child pays for the parent, so the package is stored and the lowest priority transaction is evicted
+        assert_eq!(
+            unconfirmed_pool
+                .insert_package(
+                    vec![(parent.clone(), None), (child.clone(), child_dependent_outputs)],
+                    &tx_weight
+                )
+                .unwrap(),
+            TxStorageResponse::UnconfirmedPool
+        );
+        assert_eq!(unconfirmed_pool.len(), 3);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&parent.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&child.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&other.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&filler.body.kernels()[0].excess_sig));
+
+        // The parent and child are selected together ahead of the transaction with the higher fee than the parent
+        let desired_weight = parent.calculate_weight(&tx_weight).expect("Failed to get tx") +
+            child.calculate_weight(&tx_weight).expect("Failed to get tx");
+        let results = unconfirmed_pool.fetch_highest_priority_txs(desired_weight).unwrap();
+        assert_eq!(results.retrieved_transactions.len(), 2);
+        assert!(results.retrieved_transactions.contains(&parent));
+        assert!(results.retrieved_transactions.contains(&child));
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[tokio::test]
+    async fn test_removing_package_child_restores_parent_priority() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let parent = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(1), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let child = Arc::new(
+            tx!(MicroMinotari(5_000), fee: MicroMinotari(80), inputs: 2, outputs: 1, &key_manager)
+                .expect("Failed to get tx")
+                .0,
+        );
+        let child_dependent_outputs = Some(vec![parent.body.outputs()[0].hash()]);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            min_fee: 0,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_package(
+                vec![(parent.clone(), None), (child.clone(), child_dependent_outputs)],
+                &tx_weight,
+            )
+            .unwrap();
+
+        let parent_key = unconfirmed_pool.txs_by_signature[parent.body.kernels()[0].excess_sig.get_signature()][0];
+        let child_key = unconfirmed_pool.txs_by_signature[child.body.kernels()[0].excess_sig.get_signature()][0];
+        let prioritized_parent = &unconfirmed_pool.tx_by_key[&parent_key];
+        assert!(prioritized_parent.priority.fee_per_byte() > prioritized_parent.fee_per_byte);
+
+        // Once the child is gone, nothing pays for the parent any more
+        unconfirmed_pool.remove_transaction(child_key).unwrap();
+        let prioritized_parent = &unconfirmed_pool.tx_by_key[&parent_key];
+        assert_eq!(
+            prioritized_parent.priority.fee_per_byte(),
+            prioritized_parent.fee_per_byte
+        );
+        assert_eq!(
+            unconfirmed_pool.tx_by_priority.get(&prioritized_parent.priority),
+            Some(&parent_key)
+        );
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
     #[tokio::test]
     async fn test_multiple_transactions_with_same_outputs_in_mempool() {
         let key_manager = create_memory_db_key_manager().unwrap();
@@ -1233,6 +1802,7 @@ mod test {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
             min_fee: 0,
+            ..Default::default()
         });
         let txns = vec![
             Arc::new(tx1.clone()),
@@ -0,0 +1,145 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Proofs that an output is, or is not, in the unspent output SMT that a block header commits to in `output_mr`.
+//! These let a light client confirm the spent status of its outputs without trusting the base node's answer.
+
+use std::convert::TryFrom;
+
+use blake2::Blake2b;
+use digest::consts::U32;
+use tari_common_types::types::{Commitment, FixedHash};
+use tari_crypto::{hash_domain, hashing::DomainSeparatedHasher};
+use tari_mmr::sparse_merkle_tree::{ExclusionProof, InclusionProof, NodeHash, NodeKey, ValueHash};
+use tari_utilities::ByteArray;
+
+hash_domain!(OutputSmtHashDomain, "com.tari.base_layer.core.output_smt", 1);
+pub type OutputSmtHasherBlake256 = DomainSeparatedHasher<Blake2b<U32>, OutputSmtHashDomain>;
+
+/// A proof against the output SMT, keyed by output commitment.
+#[derive(Debug, Clone)]
+pub enum OutputSmtProof {
+    /// The output is unspent
+    Inclusion(InclusionProof<OutputSmtHasherBlake256>),
+    /// No unspent output with the commitment exists
+    Exclusion(ExclusionProof<OutputSmtHasherBlake256>),
+}
+
+impl OutputSmtProof {
+    pub fn is_inclusion(&self) -> bool {
+        matches!(self, OutputSmtProof::Inclusion(_))
+    }
+
+    /// Returns true if this is a valid inclusion proof for the output with `commitment` and `smt_hash` (see
+    /// `TransactionOutput::smt_hash`) against the output SMT root `output_mr`.
+    pub fn proves_unspent(&self, commitment: &Commitment, smt_hash: &FixedHash, output_mr: &FixedHash) -> bool {
+        let (key, root) = match smt_key_and_root(commitment, output_mr) {
+            Some(v) => v,
+            None => return false,
+        };
+        match self {
+            OutputSmtProof::Inclusion(proof) => ValueHash::try_from(smt_hash.as_slice())
+                .map(|value| proof.validate(&key, &value, &root))
+                .unwrap_or(false),
+            OutputSmtProof::Exclusion(_) => false,
+        }
+    }
+
+    /// Returns true if this is a valid exclusion proof for `commitment` against the output SMT root `output_mr`, i.e.
+    /// the output is either spent or was never mined.
+    pub fn proves_not_unspent(&self, commitment: &Commitment, output_mr: &FixedHash) -> bool {
+        let (key, root) = match smt_key_and_root(commitment, output_mr) {
+            Some(v) => v,
+            None => return false,
+        };
+        match self {
+            OutputSmtProof::Inclusion(_) => false,
+            OutputSmtProof::Exclusion(proof) => proof.validate(&key, &root),
+        }
+    }
+}
+
+fn smt_key_and_root(commitment: &Commitment, output_mr: &FixedHash) -> Option<(NodeKey, NodeHash)> {
+    let key = NodeKey::try_from(commitment.as_bytes()).ok()?;
+    let root = NodeHash::try_from(output_mr.as_slice()).ok()?;
+    Some((key, root))
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common_types::types::{CommitmentFactory, PrivateKey};
+    use tari_crypto::commitment::HomomorphicCommitmentFactory;
+    use tari_mmr::sparse_merkle_tree::SparseMerkleTree;
+
+    use super::*;
+
+    fn commitment(value: u64) -> Commitment {
+        CommitmentFactory::default().commit_value(&PrivateKey::from(value), value)
+    }
+
+    fn smt_hash(n: u8) -> FixedHash {
+        FixedHash::from([n; 32])
+    }
+
+    fn build_tree(outputs: &[(Commitment, FixedHash)]) -> (SparseMerkleTree<OutputSmtHasherBlake256>, FixedHash) {
+        let mut tree = SparseMerkleTree::<OutputSmtHasherBlake256>::new();
+        for (commitment, hash) in outputs {
+            let key = NodeKey::try_from(commitment.as_bytes()).unwrap();
+            let value = ValueHash::try_from(hash.as_slice()).unwrap();
+            tree.insert(key, value).unwrap();
+        }
+        let root = FixedHash::try_from(tree.hash().as_slice()).unwrap();
+        (tree, root)
+    }
+
+    #[test]
+    fn it_proves_unspent_outputs() {
+        let outputs = vec![(commitment(1), smt_hash(1)), (commitment(2), smt_hash(2))];
+        let (tree, root) = build_tree(&outputs);
+        let (commitment, hash) = &outputs[0];
+        let key = NodeKey::try_from(commitment.as_bytes()).unwrap();
+        let value = ValueHash::try_from(hash.as_slice()).unwrap();
+        let proof = OutputSmtProof::Inclusion(InclusionProof::from_tree(&tree, &key, &value).unwrap());
+
+        assert!(proof.is_inclusion());
+        assert!(proof.proves_unspent(commitment, hash, &root));
+        assert!(!proof.proves_unspent(commitment, &smt_hash(9), &root));
+        assert!(!proof.proves_unspent(&outputs[1].0, hash, &root));
+        assert!(!proof.proves_unspent(commitment, hash, &smt_hash(9)));
+        assert!(!proof.proves_not_unspent(commitment, &root));
+    }
+
+    #[test]
+    fn it_proves_missing_outputs_are_not_unspent() {
+        let outputs = vec![(commitment(1), smt_hash(1)), (commitment(2), smt_hash(2))];
+        let (tree, root) = build_tree(&outputs);
+        let missing = commitment(3);
+        let key = NodeKey::try_from(missing.as_bytes()).unwrap();
+        let proof = OutputSmtProof::Exclusion(ExclusionProof::from_tree(&tree, &key).unwrap());
+
+        assert!(!proof.is_inclusion());
+        assert!(proof.proves_not_unspent(&missing, &root));
+        assert!(!proof.proves_not_unspent(&missing, &smt_hash(9)));
+        assert!(!proof.proves_not_unspent(&outputs[0].0, &root));
+        assert!(!proof.proves_unspent(&missing, &smt_hash(3), &root));
+    }
+}
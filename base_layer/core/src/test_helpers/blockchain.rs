@@ -22,10 +22,10 @@
 use std::{
     collections::HashMap,
     convert::TryFrom,
+    env,
     fs,
-    ops::Deref,
     path::{Path, PathBuf},
-    sync::{Arc, RwLock},
+    sync::Arc,
 };
 
 use tari_common::configuration::Network;
@@ -41,9 +41,18 @@ use tari_utilities::ByteArray;
 
 use super::{create_block, mine_to_difficulty};
 use crate::{
-    blocks::{Block, BlockAccumulatedData, BlockHeader, BlockHeaderAccumulatedData, ChainBlock, ChainHeader},
+    blocks::{
+        Block,
+        BlockAccumulatedData,
+        BlockFilter,
+        BlockHeader,
+        BlockHeaderAccumulatedData,
+        ChainBlock,
+        ChainHeader,
+    },
     chain_storage::{
         create_lmdb_database,
+        create_memory_database,
         BlockAddResult,
         BlockchainBackend,
         BlockchainDatabase,
@@ -57,8 +66,10 @@ use crate::{
         HorizonData,
         InputMinedInfo,
         LMDBDatabase,
+        MemoryDatabase,
         MmrTree,
         OutputMinedInfo,
+        OutputSmtChange,
         Reorg,
         TemplateRegistrationEntry,
         Validators,
@@ -83,6 +94,7 @@ use crate::{
         DifficultyCalculator,
     },
     OutputSmt,
+    OutputSmtProof,
 };
 
 /// Create a new blockchain database containing the genesis block
@@ -107,23 +119,20 @@ pub fn create_custom_blockchain(rules: ConsensusManager) -> BlockchainDatabase<T
         MockValidator::new(true),
         MockValidator::new(true),
     );
-    let smt = Arc::new(RwLock::new(OutputSmt::new()));
-    create_store_with_consensus_and_validators(rules, validators, smt)
+    create_store_with_consensus_and_validators(rules, validators)
 }
 
 pub fn create_store_with_consensus_and_validators(
     rules: ConsensusManager,
     validators: Validators<TempDatabase>,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> BlockchainDatabase<TempDatabase> {
-    create_store_with_consensus_and_validators_and_config(rules, validators, BlockchainDatabaseConfig::default(), smt)
+    create_store_with_consensus_and_validators_and_config(rules, validators, BlockchainDatabaseConfig::default())
 }
 
 pub fn create_store_with_consensus_and_validators_and_config(
     rules: ConsensusManager,
     validators: Validators<TempDatabase>,
     config: BlockchainDatabaseConfig,
-    smt: Arc<RwLock<OutputSmt>>,
 ) -> BlockchainDatabase<TempDatabase> {
     let backend = create_test_db();
     BlockchainDatabase::start_new(
@@ -132,7 +141,6 @@ pub fn create_store_with_consensus_and_validators_and_config(
         validators,
         config,
         DifficultyCalculator::new(rules, Default::default()),
-        smt,
     )
     .unwrap()
 }
@@ -144,8 +152,7 @@ pub fn create_store_with_consensus(rules: ConsensusManager) -> BlockchainDatabas
         MockValidator::new(true),
         BlockBodyInternalConsistencyValidator::new(rules.clone(), false, factories),
     );
-    let smt = Arc::new(RwLock::new(OutputSmt::new()));
-    create_store_with_consensus_and_validators(rules, validators, smt)
+    create_store_with_consensus_and_validators(rules, validators)
 }
 pub fn create_test_blockchain_db() -> BlockchainDatabase<TempDatabase> {
     let rules = create_consensus_rules();
@@ -156,20 +163,40 @@ pub fn create_test_db() -> TempDatabase {
     TempDatabase::new()
 }
 
+/// The environment variable that selects the backend used by [TempDatabase::new]. Set it to `memory` to run tests
+/// against the in-memory backend instead of LMDB.
+pub const TEST_DB_BACKEND_ENV_VAR: &str = "TARI_TEST_DB_BACKEND";
+
+enum TempBackend {
+    Lmdb(LMDBDatabase),
+    Memory(MemoryDatabase),
+}
+
 pub struct TempDatabase {
-    path: PathBuf,
-    db: Option<LMDBDatabase>,
+    path: Option<PathBuf>,
+    db: Option<TempBackend>,
     delete_on_drop: bool,
 }
 
 impl TempDatabase {
+    /// Creates a temporary database. LMDB is used unless the `TARI_TEST_DB_BACKEND` environment variable is set to
+    /// `memory`.
     pub fn new() -> Self {
-        let temp_path = create_temporary_data_path();
-        let rules = create_consensus_rules();
+        match env::var(TEST_DB_BACKEND_ENV_VAR) {
+            Ok(backend) if backend.eq_ignore_ascii_case("memory") => Self::new_in_memory(),
+            _ => Self::new_lmdb(),
+        }
+    }
+
+    pub fn new_lmdb() -> Self {
+        Self::from_path(create_temporary_data_path())
+    }
 
+    pub fn new_in_memory() -> Self {
+        let rules = create_consensus_rules();
         Self {
-            db: Some(create_lmdb_database(&temp_path, LMDBConfig::default(), rules).unwrap()),
-            path: temp_path,
+            db: Some(TempBackend::Memory(create_memory_database(rules))),
+            path: None,
             delete_on_drop: true,
         }
     }
@@ -177,8 +204,10 @@ impl TempDatabase {
     pub fn from_path<P: AsRef<Path>>(temp_path: P) -> Self {
         let rules = create_consensus_rules();
         Self {
-            db: Some(create_lmdb_database(&temp_path, LMDBConfig::default(), rules).unwrap()),
-            path: temp_path.as_ref().to_path_buf(),
+            db: Some(TempBackend::Lmdb(
+                create_lmdb_database(&temp_path, LMDBConfig::default(), rules).unwrap(),
+            )),
+            path: Some(temp_path.as_ref().to_path_buf()),
             delete_on_drop: true,
         }
     }
@@ -188,8 +217,18 @@ impl TempDatabase {
         self
     }
 
-    pub fn db(&self) -> &LMDBDatabase {
-        self.db.as_ref().unwrap()
+    fn backend(&self) -> &dyn BlockchainBackend {
+        match self.db.as_ref().unwrap() {
+            TempBackend::Lmdb(db) => db,
+            TempBackend::Memory(db) => db,
+        }
+    }
+
+    fn backend_mut(&mut self) -> &mut dyn BlockchainBackend {
+        match self.db.as_mut().unwrap() {
+            TempBackend::Lmdb(db) => db,
+            TempBackend::Memory(db) => db,
+        }
     }
 }
 
@@ -199,86 +238,77 @@ impl Default for TempDatabase {
     }
 }
 
-impl Deref for TempDatabase {
-    type Target = LMDBDatabase;
-
-    fn deref(&self) -> &Self::Target {
-        self.db.as_ref().unwrap()
-    }
-}
-
 impl Drop for TempDatabase {
     fn drop(&mut self) {
         // force a drop on the LMDB db
         self.db = None;
-        if self.delete_on_drop && Path::new(&self.path).exists() {
-            fs::remove_dir_all(&self.path).expect("Could not delete temporary file");
+        if let Some(path) = self.path.as_ref() {
+            if self.delete_on_drop && path.exists() {
+                fs::remove_dir_all(path).expect("Could not delete temporary file");
+            }
         }
     }
 }
 
 impl BlockchainBackend for TempDatabase {
     fn write(&mut self, tx: DbTransaction) -> Result<(), ChainStorageError> {
-        self.db.as_mut().unwrap().write(tx)
+        self.backend_mut().write(tx)
     }
 
     fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch(key)
+        self.backend().fetch(key)
     }
 
     fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
-        self.db.as_ref().unwrap().contains(key)
+        self.backend().contains(key)
     }
 
     fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_chain_header_by_height(height)
+        self.backend().fetch_chain_header_by_height(height)
     }
 
     fn fetch_header_accumulated_data(
         &self,
         hash: &HashOutput,
     ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_header_accumulated_data(hash)
+        self.backend().fetch_header_accumulated_data(hash)
     }
 
     fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_chain_header_in_all_chains(hash)
+        self.backend().fetch_chain_header_in_all_chains(hash)
     }
 
     fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
-        self.db
-            .as_ref()
-            .unwrap()
-            .fetch_header_containing_kernel_mmr(mmr_position)
+        self.backend().fetch_header_containing_kernel_mmr(mmr_position)
     }
 
     fn is_empty(&self) -> Result<bool, ChainStorageError> {
-        self.db.as_ref().unwrap().is_empty()
+        self.backend().is_empty()
     }
 
     fn fetch_block_accumulated_data(
         &self,
         header_hash: &HashOutput,
     ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_block_accumulated_data(header_hash)
+        self.backend().fetch_block_accumulated_data(header_hash)
     }
 
     fn fetch_block_accumulated_data_by_height(
         &self,
         height: u64,
     ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_block_accumulated_data_by_height(height)
+        self.backend().fetch_block_accumulated_data_by_height(height)
     }
 
     fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_kernels_in_block(header_hash)
+        self.backend().fetch_kernels_in_block(header_hash)
     }
 
     fn fetch_kernel_by_excess_sig(
         &self,
         excess_sig: &Signature,
     ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_kernel_by_excess_sig(excess_sig)
+        self.backend().fetch_kernel_by_excess_sig(excess_sig)
     }
 
     fn fetch_outputs_in_block_with_spend_state(
@@ -286,88 +316,87 @@ impl BlockchainBackend for TempDatabase {
         header_hash: &HashOutput,
         spend_status_at_header: Option<&HashOutput>,
     ) -> Result<Vec<(TransactionOutput, bool)>, ChainStorageError> {
-        self.db
-            .as_ref()
-            .unwrap()
+        self.backend()
             .fetch_outputs_in_block_with_spend_state(header_hash, spend_status_at_header)
     }
 
     fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<OutputMinedInfo>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_output(output_hash)
+        self.backend().fetch_output(output_hash)
     }
 
     fn fetch_input(&self, output_hash: &HashOutput) -> Result<Option<InputMinedInfo>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_input(output_hash)
+        self.backend().fetch_input(output_hash)
     }
 
     fn fetch_unspent_output_hash_by_commitment(
         &self,
         commitment: &Commitment,
     ) -> Result<Option<HashOutput>, ChainStorageError> {
-        self.db
-            .as_ref()
-            .unwrap()
-            .fetch_unspent_output_hash_by_commitment(commitment)
+        self.backend().fetch_unspent_output_hash_by_commitment(commitment)
     }
 
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionOutput>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_outputs_in_block(header_hash)
+        self.backend().fetch_outputs_in_block(header_hash)
+    }
+
+    fn fetch_block_filter(&self, header_hash: &HashOutput) -> Result<Option<BlockFilter>, ChainStorageError> {
+        self.backend().fetch_block_filter(header_hash)
     }
 
     fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_inputs_in_block(header_hash)
+        self.backend().fetch_inputs_in_block(header_hash)
     }
 
     fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_mmr_size(tree)
+        self.backend().fetch_mmr_size(tree)
     }
 
     fn orphan_count(&self) -> Result<usize, ChainStorageError> {
-        self.db.as_ref().unwrap().orphan_count()
+        self.backend().orphan_count()
     }
 
     fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_last_header()
+        self.backend().fetch_last_header()
     }
 
     fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
-        self.db.as_ref().unwrap().clear_all_pending_headers()
+        self.backend().clear_all_pending_headers()
     }
 
     fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_last_chain_header()
+        self.backend().fetch_last_chain_header()
     }
 
     fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_tip_header()
+        self.backend().fetch_tip_header()
     }
 
     fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_chain_metadata()
+        self.backend().fetch_chain_metadata()
     }
 
     fn utxo_count(&self) -> Result<usize, ChainStorageError> {
-        self.db.as_ref().unwrap().utxo_count()
+        self.backend().utxo_count()
     }
 
     fn kernel_count(&self) -> Result<usize, ChainStorageError> {
-        self.db.as_ref().unwrap().kernel_count()
+        self.backend().kernel_count()
     }
 
     fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_orphan_chain_tip_by_hash(hash)
+        self.backend().fetch_orphan_chain_tip_by_hash(hash)
     }
 
     fn fetch_strongest_orphan_chain_tips(&self) -> Result<Vec<ChainHeader>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_strongest_orphan_chain_tips()
+        self.backend().fetch_strongest_orphan_chain_tips()
     }
 
     fn fetch_orphan_children_of(&self, hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_orphan_children_of(hash)
+        self.backend().fetch_orphan_children_of(hash)
     }
 
     fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_orphan_chain_block(hash)
+        self.backend().fetch_orphan_chain_block(hash)
     }
 
     fn delete_oldest_orphans(
@@ -375,42 +404,40 @@ impl BlockchainBackend for TempDatabase {
         horizon_height: u64,
         orphan_storage_capacity: usize,
     ) -> Result<(), ChainStorageError> {
-        self.db
-            .as_mut()
-            .unwrap()
+        self.backend_mut()
             .delete_oldest_orphans(horizon_height, orphan_storage_capacity)
     }
 
     fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_monero_seed_first_seen_height(seed)
+        self.backend().fetch_monero_seed_first_seen_height(seed)
     }
 
     fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_horizon_data()
+        self.backend().fetch_horizon_data()
     }
 
     fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
-        self.db.as_ref().unwrap().get_stats()
+        self.backend().get_stats()
     }
 
     fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_total_size_stats()
+        self.backend().fetch_total_size_stats()
     }
 
     fn bad_block_exists(&self, block_hash: HashOutput) -> Result<(bool, String), ChainStorageError> {
-        self.db.as_ref().unwrap().bad_block_exists(block_hash)
+        self.backend().bad_block_exists(block_hash)
     }
 
     fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_all_reorgs()
+        self.backend().fetch_all_reorgs()
     }
 
     fn fetch_active_validator_nodes(&self, height: u64) -> Result<Vec<(PublicKey, [u8; 32])>, ChainStorageError> {
-        self.db.as_ref().unwrap().fetch_active_validator_nodes(height)
+        self.backend().fetch_active_validator_nodes(height)
     }
 
     fn get_shard_key(&self, height: u64, public_key: PublicKey) -> Result<Option<[u8; 32]>, ChainStorageError> {
-        self.db.as_ref().unwrap().get_shard_key(height, public_key)
+        self.backend().get_shard_key(height, public_key)
     }
 
     fn fetch_template_registrations(
@@ -418,14 +445,23 @@ impl BlockchainBackend for TempDatabase {
         start_height: u64,
         end_height: u64,
     ) -> Result<Vec<TemplateRegistrationEntry>, ChainStorageError> {
-        self.db
-            .as_ref()
-            .unwrap()
-            .fetch_template_registrations(start_height, end_height)
+        self.backend().fetch_template_registrations(start_height, end_height)
+    }
+
+    fn calculate_output_smt_root(&self, changes: &[OutputSmtChange]) -> Result<(FixedHash, u64), ChainStorageError> {
+        self.backend().calculate_output_smt_root(changes)
     }
 
-    fn calculate_tip_smt(&self) -> Result<OutputSmt, ChainStorageError> {
-        self.db.as_ref().unwrap().calculate_tip_smt()
+    fn output_smt_contains(&self, key: &NodeKey) -> Result<bool, ChainStorageError> {
+        self.backend().output_smt_contains(key)
+    }
+
+    fn fetch_output_smt_proofs(
+        &self,
+        changes: &[OutputSmtChange],
+        leaves: &[(NodeKey, ValueHash)],
+    ) -> Result<Vec<Option<OutputSmtProof>>, ChainStorageError> {
+        self.backend().fetch_output_smt_proofs(changes, leaves)
     }
 }
 
@@ -489,7 +525,7 @@ pub async fn create_main_chain<T: Into<BlockSpecs>>(
         .map(Arc::new)
         .unwrap();
     let (names, chain) = {
-        let mut smt = db.smt_read_access().unwrap().clone();
+        let mut smt = fetch_tip_output_smt(db);
         create_chained_blocks(blocks, genesis_block, &mut smt).await
     };
     names.iter().for_each(|name| {
@@ -532,6 +568,27 @@ pub fn update_block_and_smt(block: &mut Block, smt: &mut OutputSmt) {
     block.header.output_mr = root;
 }
 
+/// Rebuilds an in-memory copy of the output SMT at the tip of `db`, so that tests can calculate the `output_mr` of
+/// blocks that do not build on the tip.
+pub fn fetch_tip_output_smt<B: BlockchainBackend>(db: &BlockchainDatabase<B>) -> OutputSmt {
+    let metadata = db.get_chain_metadata().unwrap();
+    let mut smt = OutputSmt::new();
+    for height in 0..=metadata.best_block_height() {
+        let header = db.fetch_header(height).unwrap().unwrap();
+        let outputs = db
+            .fetch_outputs_in_block_with_spend_state(header.hash(), Some(*metadata.best_block_hash()))
+            .unwrap();
+        for (output, spent) in outputs {
+            if !spent && !output.is_burned() {
+                let smt_key = NodeKey::try_from(output.commitment.as_bytes()).unwrap();
+                let smt_node = ValueHash::try_from(output.smt_hash(height).as_slice()).unwrap();
+                smt.insert(smt_key, smt_node).unwrap();
+            }
+        }
+    }
+    smt
+}
+
 pub struct TestBlockchain {
     db: BlockchainDatabase<TempDatabase>,
     chain: Vec<(&'static str, Arc<ChainBlock>, OutputSmt)>,
@@ -561,7 +618,7 @@ impl TestBlockchain {
             wallet_payment_address,
             range_proof_type: RangeProofType::BulletProofPlus,
         };
-        let smt = blockchain.db.smt_read_access().unwrap().clone();
+        let smt = fetch_tip_output_smt(&blockchain.db);
 
         blockchain.chain.push(("GB", genesis, smt));
         blockchain
@@ -598,9 +655,9 @@ impl TestBlockchain {
         Ok(())
     }
 
-    pub async fn with_validators(validators: Validators<TempDatabase>, smt: Arc<RwLock<OutputSmt>>) -> Self {
+    pub async fn with_validators(validators: Validators<TempDatabase>) -> Self {
         let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
-        let db = create_store_with_consensus_and_validators(rules.clone(), validators, smt);
+        let db = create_store_with_consensus_and_validators(rules.clone(), validators);
         Self::new(db, rules).await
     }
 
@@ -640,7 +697,7 @@ impl TestBlockchain {
         block: Arc<ChainBlock>,
     ) -> Result<BlockAddResult, ChainStorageError> {
         let result = self.db.add_block(block.to_arc_block())?;
-        let smt = self.db.smt().read().unwrap().clone();
+        let smt = fetch_tip_output_smt(&self.db);
         self.chain.push((name, block, smt));
         Ok(result)
     }
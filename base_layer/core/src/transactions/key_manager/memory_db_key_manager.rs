@@ -89,3 +89,41 @@ pub fn create_memory_db_key_manager_from_seed(
 pub fn create_memory_db_key_manager() -> Result<MemoryDbKeyManager, KeyManagerServiceError> {
     create_memory_db_key_manager_with_range_proof_size(64)
 }
+
+/// Create a key manager for a `WalletType::Ledger` wallet on `account`, with all device requests answered by the
+/// process-wide emulated ledger device.
+#[cfg(all(feature = "ledger", test))]
+pub fn create_memory_db_key_manager_with_emulated_ledger(
+    account: u64,
+) -> Result<MemoryDbKeyManager, KeyManagerServiceError> {
+    use minotari_ledger_wallet_comms::{
+        accessor_methods::{ledger_get_public_spend_key, ledger_get_view_key},
+        emulator::use_emulated_ledger_device,
+    };
+    use tari_common::configuration::Network;
+    use tari_common_types::wallet_types::LedgerWallet;
+
+    use_emulated_ledger_device();
+    let public_alpha =
+        ledger_get_public_spend_key(account).map_err(|e| KeyManagerServiceError::LedgerError(e.to_string()))?;
+    let view_key = ledger_get_view_key(account).map_err(|e| KeyManagerServiceError::LedgerError(e.to_string()))?;
+    let wallet_type = WalletType::Ledger(LedgerWallet::new(
+        account,
+        Network::get_current_or_user_setting_or_default(),
+        Some(public_alpha),
+        Some(view_key),
+    ));
+
+    let connection = DbConnection::connect_url(&DbConnectionUrl::MemoryShared(random_string(8)))?;
+    let mut key = Zeroizing::new([0u8; size_of::<Key>()]);
+    OsRng.fill_bytes(key.as_mut());
+    let key_ga = Key::from_slice(key.as_ref());
+    let db_cipher = XChaCha20Poly1305::new(key_ga);
+
+    TransactionKeyManagerWrapper::<KeyManagerSqliteDatabase<DbConnection>>::new(
+        CipherSeed::new(),
+        KeyManagerDatabase::new(KeyManagerSqliteDatabase::init(connection, db_cipher)),
+        CryptoFactories::default(),
+        Arc::new(wallet_type),
+    )
+}
@@ -40,6 +40,8 @@ pub use inner::LEDGER_NOT_SUPPORTED;
 /// This is a memory database implementation of the `TransactionKeyManager` trait.
 mod memory_db_key_manager;
 pub use inner::TransactionKeyManagerInner;
+#[cfg(all(feature = "ledger", test))]
+pub use memory_db_key_manager::create_memory_db_key_manager_with_emulated_ledger;
 pub use memory_db_key_manager::{
     create_memory_db_key_manager,
     create_memory_db_key_manager_from_seed,
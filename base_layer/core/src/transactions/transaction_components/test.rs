@@ -285,6 +285,31 @@ async fn sender_signature_verification() {
     assert!(tx_output.verify_metadata_signature().is_err());
 }
 
+#[cfg(feature = "ledger")]
+#[tokio::test]
+async fn emulated_ledger_signatures_verify() {
+    use crate::transactions::key_manager::create_memory_db_key_manager_with_emulated_ledger;
+
+    let key_manager = create_memory_db_key_manager_with_emulated_ledger(3).unwrap();
+    let factories = CryptoFactories::default();
+    let test_params = TestParams::new(&key_manager).await;
+    let wallet_output = test_params
+        .create_output(Default::default(), &key_manager)
+        .await
+        .unwrap();
+
+    // The script key is derived from the device's spend key, so the script signature is made by the device
+    let input = wallet_output.to_transaction_input(&key_manager).await.unwrap();
+    input
+        .validate_script_signature(&test_params.script_key_pk, &factories.commitment)
+        .unwrap();
+
+    let output = wallet_output.to_transaction_output(&key_manager).await.unwrap();
+    output.verify_metadata_signature().unwrap();
+    let (_, value, _) = key_manager.try_output_key_recovery(&output, None).await.unwrap();
+    assert_eq!(value, wallet_output.value);
+}
+
 #[test]
 fn kernel_hash() {
     #[cfg(tari_target_network_mainnet)]
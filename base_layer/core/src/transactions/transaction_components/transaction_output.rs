@@ -225,12 +225,20 @@ impl TransactionOutput {
     }
 
     pub fn smt_hash(&self, mined_height: u64) -> FixedHash {
-        let utxo_hash = self.hash();
+        Self::smt_hash_from_parts(self.version, &self.hash(), mined_height)
+    }
+
+    /// The value hash of an output in the output SMT, for when only the output hash is known
+    pub fn smt_hash_from_parts(
+        version: TransactionOutputVersion,
+        output_hash: &FixedHash,
+        mined_height: u64,
+    ) -> FixedHash {
         let smt_hash = DomainSeparatedConsensusHasher::<TransactionHashDomain, Blake2b<U32>>::new("smt_hash")
-            .chain(&utxo_hash)
+            .chain(output_hash)
             .chain(&mined_height);
 
-        match self.version {
+        match version {
             TransactionOutputVersion::V0 | TransactionOutputVersion::V1 => smt_hash.finalize().into(),
         }
     }
@@ -98,6 +98,7 @@ pub mod recipient;
 pub mod sender;
 pub mod single_receiver;
 pub mod transaction_initializer;
+pub mod unsigned_transaction;
 use tari_common_types::types::Commitment;
 use tari_key_manager::key_manager_service::KeyManagerServiceError;
 
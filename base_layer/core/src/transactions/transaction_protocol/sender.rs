@@ -55,6 +55,12 @@ use crate::{
         transaction_protocol::{
             recipient::RecipientSignedMessage,
             transaction_initializer::{RecipientDetails, SenderTransactionInitializer},
+            unsigned_transaction::{
+                UnsignedInput,
+                UnsignedRecipient,
+                UnsignedTransactionBundle,
+                TRANSACTION_BUNDLE_VERSION,
+            },
             TransactionMetadata,
             TransactionProtocolError as TPE,
         },
@@ -377,6 +383,51 @@ impl SenderTransactionProtocol {
         }
     }
 
+    /// Captures the inputs, fee and change of a built, but not yet finalized, transaction in a portable
+    /// [UnsignedTransactionBundle] so that it can be signed by a wallet holding the spend key. The recipient outputs
+    /// of this protocol are not captured, they are recreated by the signer for `recipients`. `fee_per_gram` must be
+    /// the fee per gram this protocol was built with.
+    pub async fn to_unsigned_bundle<KM: TransactionKeyManagerInterface>(
+        &self,
+        recipients: Vec<UnsignedRecipient>,
+        fee_per_gram: MicroMinotari,
+        key_manager: &KM,
+    ) -> Result<UnsignedTransactionBundle, TPE> {
+        let info = match &self.state {
+            SenderState::Initializing(info) |
+            SenderState::Finalizing(info) |
+            SenderState::SingleRoundMessageReady(info) |
+            SenderState::CollectingSingleSignature(info) => info,
+            SenderState::FinalizedTransaction(_) | SenderState::Failed(_) => return Err(TPE::InvalidStateError),
+        };
+        let mut inputs = Vec::with_capacity(info.inputs.len());
+        for input in &info.inputs {
+            let wallet_output = input.output.clone();
+            inputs.push(UnsignedInput {
+                output: wallet_output.to_transaction_output(key_manager).await?,
+                script_public_key: key_manager
+                    .get_public_key_at_key_id(&wallet_output.script_key_id)
+                    .await?,
+                wallet_output,
+            });
+        }
+        Ok(UnsignedTransactionBundle {
+            version: TRANSACTION_BUNDLE_VERSION,
+            tx_id: info.tx_id,
+            fee_per_gram,
+            fee: info.metadata.fee,
+            change: info
+                .change_output
+                .as_ref()
+                .map(|output| output.output.value)
+                .unwrap_or(MicroMinotari::zero()),
+            lock_height: info.metadata.lock_height,
+            inputs,
+            recipients,
+            payment_id: info.payment_id.clone(),
+        })
+    }
+
     /// Build the sender's message for the single-round protocol (one recipient) and move to next State
     pub async fn build_single_round_message<KM: TransactionKeyManagerInterface>(
         &mut self,
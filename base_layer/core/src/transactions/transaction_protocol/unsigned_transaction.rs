@@ -0,0 +1,236 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Portable formats for signing a transaction on a different machine from the wallet that prepared it.
+//!
+//! A wallet that can see its funds but does not hold the spend key (e.g. a `WalletType::ProvidedKeys` view wallet)
+//! selects the inputs and builds a [SenderTransactionProtocol](super::sender::SenderTransactionProtocol), which is then
+//! captured in an [UnsignedTransactionBundle]. The bundle is carried to an offline wallet holding the seed, which
+//! recovers the input keys, builds and signs the transaction and returns it in a [SignedTransactionBundle] for the
+//! online wallet to broadcast.
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::{tari_address::TariAddress, transaction::TxId, types::PublicKey};
+
+use crate::transactions::{
+    key_manager::TariKeyId,
+    tari_amount::MicroMinotari,
+    transaction_components::{encrypted_data::PaymentId, Transaction, TransactionOutput, WalletOutput},
+    transaction_protocol::TransactionProtocolError as TPE,
+};
+
+/// The version of the unsigned and signed transaction bundle formats produced by this code
+pub const TRANSACTION_BUNDLE_VERSION: u8 = 1;
+
+/// A one-sided payment to be made by an offline-signed transaction
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UnsignedRecipient {
+    pub destination: TariAddress,
+    pub amount: MicroMinotari,
+    pub payment_id: PaymentId,
+}
+
+/// An output being spent by an unsigned transaction
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnsignedInput {
+    /// The output as it was mined. The signer recovers the value and commitment mask from it with its view key.
+    pub output: TransactionOutput,
+    /// The spending data of the output, with the key ids known to the preparing wallet
+    pub wallet_output: WalletOutput,
+    /// The public script key that the signer must produce the script signature for
+    pub script_public_key: PublicKey,
+}
+
+/// A transaction that has been prepared, but not signed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnsignedTransactionBundle {
+    pub version: u8,
+    pub tx_id: TxId,
+    pub fee_per_gram: MicroMinotari,
+    /// The fee of the transaction as prepared. The signed transaction must pay exactly this fee.
+    pub fee: MicroMinotari,
+    /// The change the prepared transaction returns to the wallet
+    pub change: MicroMinotari,
+    pub lock_height: u64,
+    pub inputs: Vec<UnsignedInput>,
+    pub recipients: Vec<UnsignedRecipient>,
+    pub payment_id: PaymentId,
+}
+
+impl UnsignedTransactionBundle {
+    /// Returns an error if the bundle was produced in a format this code does not understand
+    pub fn check_version(&self) -> Result<(), TPE> {
+        check_version(self.version)
+    }
+
+    /// The total amount paid to the recipients
+    pub fn total_amount(&self) -> Result<MicroMinotari, TPE> {
+        self.recipients
+            .iter()
+            .map(|r| r.amount)
+            .try_fold(MicroMinotari::zero(), |acc, v| acc.checked_add(v))
+            .ok_or_else(|| TPE::ValidationError("Total amount overflow".to_string()))
+    }
+
+    /// The total value of the inputs
+    pub fn total_input_value(&self) -> Result<MicroMinotari, TPE> {
+        self.inputs
+            .iter()
+            .map(|i| i.wallet_output.value)
+            .try_fold(MicroMinotari::zero(), |acc, v| acc.checked_add(v))
+            .ok_or_else(|| TPE::ValidationError("Total input value overflow".to_string()))
+    }
+
+    /// The commitment mask and script key ids, as known to the preparing wallet, that the signer must be able to
+    /// resolve
+    pub fn required_key_ids(&self) -> Vec<TariKeyId> {
+        self.inputs
+            .iter()
+            .flat_map(|i| {
+                [
+                    i.wallet_output.spending_key_id.clone(),
+                    i.wallet_output.script_key_id.clone(),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// A transaction signed from an [UnsignedTransactionBundle], ready to be broadcast by the wallet that prepared it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedTransactionBundle {
+    pub version: u8,
+    pub tx_id: TxId,
+    pub transaction: Transaction,
+    pub recipients: Vec<UnsignedRecipient>,
+    pub payment_id: PaymentId,
+}
+
+impl SignedTransactionBundle {
+    pub fn new(unsigned: &UnsignedTransactionBundle, transaction: Transaction) -> Self {
+        Self {
+            version: TRANSACTION_BUNDLE_VERSION,
+            tx_id: unsigned.tx_id,
+            transaction,
+            recipients: unsigned.recipients.clone(),
+            payment_id: unsigned.payment_id.clone(),
+        }
+    }
+
+    /// Returns an error if the bundle was produced in a format this code does not understand
+    pub fn check_version(&self) -> Result<(), TPE> {
+        check_version(self.version)
+    }
+}
+
+fn check_version(version: u8) -> Result<(), TPE> {
+    if version == TRANSACTION_BUNDLE_VERSION {
+        Ok(())
+    } else {
+        Err(TPE::UnsupportedError(format!(
+            "Transaction bundle version {} (expected {})",
+            version, TRANSACTION_BUNDLE_VERSION
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_script::script;
+
+    use super::*;
+    use crate::{
+        covenants::Covenant,
+        test_helpers::create_consensus_constants,
+        transactions::{
+            key_manager::{create_memory_db_key_manager, TransactionKeyManagerInterface},
+            test_helpers::{TestParams, UtxoTestParams},
+            transaction_protocol::sender::SenderTransactionProtocol,
+        },
+    };
+
+    #[tokio::test]
+    async fn it_captures_a_built_sender_protocol() {
+        let key_manager = create_memory_db_key_manager().unwrap();
+        let input = TestParams::new(&key_manager)
+            .await
+            .create_input(
+                UtxoTestParams {
+                    value: MicroMinotari(50_000),
+                    ..Default::default()
+                },
+                &key_manager,
+            )
+            .await;
+        let change = TestParams::new(&key_manager).await;
+        let mut builder = SenderTransactionProtocol::builder(create_consensus_constants(0), key_manager.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroMinotari(5))
+            .with_change_data(
+                script!(Nop).unwrap(),
+                Default::default(),
+                change.script_key_id.clone(),
+                change.commitment_mask_key_id.clone(),
+                Covenant::default(),
+                TariAddress::default(),
+            );
+        builder.with_input(input.clone()).await.unwrap();
+        let stp = builder.build().await.unwrap();
+
+        let recipients = vec![UnsignedRecipient {
+            destination: TariAddress::default(),
+            amount: MicroMinotari(1_000),
+            payment_id: PaymentId::Empty,
+        }];
+        let bundle = stp
+            .to_unsigned_bundle(recipients.clone(), MicroMinotari(5), &key_manager)
+            .await
+            .unwrap();
+        assert_eq!(bundle.fee, stp.get_fee_amount().unwrap());
+        assert_eq!(bundle.change, stp.get_change_amount().unwrap());
+        assert_eq!(bundle.total_input_value().unwrap(), MicroMinotari(50_000));
+        assert_eq!(bundle.total_amount().unwrap(), MicroMinotari(1_000));
+        assert_eq!(
+            bundle.inputs[0].output,
+            input.to_transaction_output(&key_manager).await.unwrap()
+        );
+        assert_eq!(
+            bundle.inputs[0].script_public_key,
+            key_manager
+                .get_public_key_at_key_id(&input.script_key_id)
+                .await
+                .unwrap()
+        );
+        assert_eq!(bundle.required_key_ids(), vec![
+            input.spending_key_id.clone(),
+            input.script_key_id.clone()
+        ]);
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let mut bundle: UnsignedTransactionBundle = serde_json::from_str(&json).unwrap();
+        bundle.check_version().unwrap();
+        assert_eq!(bundle.recipients, recipients);
+        bundle.version = TRANSACTION_BUNDLE_VERSION + 1;
+        assert!(bundle.check_version().is_err());
+    }
+}
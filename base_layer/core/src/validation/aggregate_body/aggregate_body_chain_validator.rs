@@ -20,10 +20,11 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use log::warn;
-use tari_common_types::types::FixedHash;
+use tari_common_types::types::{CommitmentFactory, FixedHash, HashOutput, PrivateKey, PublicKey};
+use tari_crypto::keys::PublicKey as PublicKeyTrait;
 use tari_utilities::hex::Hex;
 
 use crate::{
@@ -34,6 +35,10 @@ use crate::{
         transaction_components::{TransactionError, TransactionInput, TransactionOutput},
     },
     validation::{
+        aggregate_body::aggregate_body_internal_validator::{
+            requires_input_mined_heights,
+            validate_script_and_script_offset,
+        },
         helpers::{
             check_input_is_utxo,
             check_not_duplicate_txo,
@@ -57,9 +62,15 @@ impl AggregateBodyChainLinkedValidator {
         Self { consensus_manager }
     }
 
+    /// Validates the body against the chain and returns it with any compact inputs resolved. The script offset and
+    /// previous header are used to run the input scripts of bodies with relative height locks, which the internal
+    /// consistency validator defers to this point because they need the heights at which the spent outputs were
+    /// mined.
     pub fn validate<B: BlockchainBackend>(
         &self,
         body: &AggregateBody,
+        script_offset: &PrivateKey,
+        prev_header: Option<HashOutput>,
         height: u64,
         db: &B,
     ) -> Result<AggregateBody, ValidationError> {
@@ -67,10 +78,48 @@ impl AggregateBodyChainLinkedValidator {
 
         self.validate_consensus(body, db, constants)?;
         let body = self.validate_input_and_maturity(body, db, constants, height)?;
+        self.validate_relative_height_locks(&body, script_offset, prev_header, height, db)?;
 
         Ok(body)
     }
 
+    /// Validates the input scripts and script offset of a body that spends outputs with relative height locks, using
+    /// the heights at which the spent outputs were mined. Bodies without relative height locks are fully validated by
+    /// the internal consistency validator and are skipped. Outputs spent in the same body are treated as mined at
+    /// `height`. The inputs of the body must not be compact.
+    fn validate_relative_height_locks<B: BlockchainBackend>(
+        &self,
+        body: &AggregateBody,
+        script_offset: &PrivateKey,
+        prev_header: Option<HashOutput>,
+        height: u64,
+        db: &B,
+    ) -> Result<(), ValidationError> {
+        if !requires_input_mined_heights(body)? {
+            return Ok(());
+        }
+
+        let mut input_mined_heights = HashMap::with_capacity(body.inputs().len());
+        for input in body.inputs() {
+            let output_hash = input.output_hash();
+            let mined_height = db
+                .fetch_output(&output_hash)?
+                .map(|output_mined_info| output_mined_info.mined_height)
+                .unwrap_or(height);
+            input_mined_heights.insert(output_hash, mined_height);
+        }
+
+        let script_offset_g = PublicKey::from_secret_key(script_offset);
+        validate_script_and_script_offset(
+            body,
+            script_offset_g,
+            &CommitmentFactory::default(),
+            prev_header,
+            height,
+            &input_mined_heights,
+        )
+    }
+
     fn validate_consensus<B: BlockchainBackend>(
         &self,
         body: &AggregateBody,
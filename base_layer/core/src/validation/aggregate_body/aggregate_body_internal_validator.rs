@@ -20,7 +20,10 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{collections::HashSet, convert::TryInto};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+};
 
 use log::{trace, warn};
 use tari_common_types::types::{Commitment, CommitmentFactory, HashOutput, PrivateKey, PublicKey, RangeProofService};
@@ -101,11 +104,51 @@ impl AggregateBodyInternalConsistencyValidator {
         total_reward: Option<MicroMinotari>,
         prev_header: Option<HashOutput>,
         height: u64,
+    ) -> Result<(), ValidationError> {
+        self.validate_body(body, tx_offset, script_offset, total_reward, prev_header, height, false)
+    }
+
+    /// Validates the body like `validate`, except for the kernel signatures, metadata signatures and range proofs,
+    /// which must already have been checked with `verify_proofs`
+    pub fn validate_with_verified_proofs(
+        &self,
+        body: &AggregateBody,
+        tx_offset: &PrivateKey,
+        script_offset: &PrivateKey,
+        total_reward: Option<MicroMinotari>,
+        prev_header: Option<HashOutput>,
+        height: u64,
+    ) -> Result<(), ValidationError> {
+        self.validate_body(body, tx_offset, script_offset, total_reward, prev_header, height, true)
+    }
+
+    /// Verifies the kernel signatures, metadata signatures and range proofs of the body. These checks do not depend on
+    /// the inputs or the chain, so they can be run for several bodies concurrently.
+    pub fn verify_proofs(&self, body: &AggregateBody) -> Result<(), ValidationError> {
+        verify_kernel_signatures(body)?;
+        if !self.bypass_range_proof_verification {
+            validate_range_proofs(body, &self.factories.range_proof)?;
+        }
+        verify_metadata_signatures(body)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn validate_body(
+        &self,
+        body: &AggregateBody,
+        tx_offset: &PrivateKey,
+        script_offset: &PrivateKey,
+        total_reward: Option<MicroMinotari>,
+        prev_header: Option<HashOutput>,
+        height: u64,
+        proofs_verified: bool,
     ) -> Result<(), ValidationError> {
         let total_reward = total_reward.unwrap_or(MicroMinotari::zero());
 
         // old internal validator
-        verify_kernel_signatures(body)?;
+        if !proofs_verified {
+            verify_kernel_signatures(body)?;
+        }
 
         let constants = self.consensus_manager.consensus_constants(height);
 
@@ -130,13 +173,31 @@ impl AggregateBodyInternalConsistencyValidator {
         let total_offset = self.factories.commitment.commit_value(tx_offset, total_reward.0);
         validate_kernel_sum(body, total_offset, &self.factories.commitment)?;
 
-        if !self.bypass_range_proof_verification {
-            validate_range_proofs(body, &self.factories.range_proof)?;
+        if !proofs_verified {
+            if !self.bypass_range_proof_verification {
+                validate_range_proofs(body, &self.factories.range_proof)?;
+            }
+            verify_metadata_signatures(body)?;
         }
-        verify_metadata_signatures(body)?;
 
-        let script_offset_g = PublicKey::from_secret_key(script_offset);
-        validate_script_and_script_offset(body, script_offset_g, &self.factories.commitment, prev_header, height)?;
+        if requires_input_mined_heights(body)? {
+            // Relative height locks depend on when the spent outputs were mined, so the input scripts and script
+            // offset are validated by `AggregateBodyChainLinkedValidator::validate` once those heights are known
+            trace!(
+                target: LOG_TARGET,
+                "Deferring script validation of inputs with relative height locks"
+            );
+        } else {
+            let script_offset_g = PublicKey::from_secret_key(script_offset);
+            validate_script_and_script_offset(
+                body,
+                script_offset_g,
+                &self.factories.commitment,
+                prev_header,
+                height,
+                &HashMap::new(),
+            )?;
+        }
         validate_covenants(body, height)?;
 
         check_total_burned(body)?;
@@ -277,19 +338,35 @@ fn verify_metadata_signatures(body: &AggregateBody) -> Result<(), ValidationErro
 }
 
 /// this will validate the script and script offset of the aggregate body.
-fn validate_script_and_script_offset(
+/// Returns true if any input script contains an opcode that requires the mined height of the output being spent.
+pub(super) fn requires_input_mined_heights(body: &AggregateBody) -> Result<bool, ValidationError> {
+    for input in body.inputs() {
+        if input.script()?.as_slice().iter().any(|op| op.requires_mined_height()) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Runs the input scripts and checks the script offset. The mined heights of the spent outputs, keyed by output hash,
+/// are added to the script context where available.
+pub(super) fn validate_script_and_script_offset(
     body: &AggregateBody,
     script_offset: PublicKey,
     factory: &CommitmentFactory,
     prev_header: Option<HashOutput>,
     height: u64,
+    input_mined_heights: &HashMap<HashOutput, u64>,
 ) -> Result<(), ValidationError> {
     trace!(target: LOG_TARGET, "Checking script and script offset");
     // lets count up the input script public keys
     let mut input_keys = PublicKey::default();
     let prev_hash: [u8; 32] = prev_header.unwrap_or_default().as_slice().try_into().unwrap_or([0; 32]);
     for input in body.inputs() {
-        let context = ScriptContext::new(height, &prev_hash, input.commitment()?);
+        let mut context = ScriptContext::new(height, &prev_hash, input.commitment()?);
+        if let Some(mined_height) = input_mined_heights.get(&input.output_hash()) {
+            context = context.with_mined_height(*mined_height);
+        }
         input_keys = input_keys + input.run_and_verify_script(factory, Some(context))?;
     }
 
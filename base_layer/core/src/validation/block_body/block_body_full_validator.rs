@@ -20,18 +20,15 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::{Arc, RwLock};
-
-use log::error;
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_utilities::hex::Hex;
 
 use super::BlockBodyInternalConsistencyValidator;
 use crate::{
     blocks::{Block, ChainBlock},
-    chain_storage::{self, BlockchainBackend, ChainStorageError},
+    chain_storage::{self, BlockchainBackend},
     consensus::ConsensusManager,
-    transactions::CryptoFactories,
+    transactions::{aggregated_body::AggregateBody, CryptoFactories},
     validation::{
         aggregate_body::AggregateBodyChainLinkedValidator,
         helpers::check_mmr_roots,
@@ -39,11 +36,8 @@ use crate::{
         CandidateBlockValidator,
         ValidationError,
     },
-    OutputSmt,
 };
 
-const LOG_TARGET: &str = "c::val::block_body_full_validator";
-
 pub struct BlockBodyFullValidator {
     consensus_manager: ConsensusManager,
     block_internal_validator: BlockBodyInternalConsistencyValidator,
@@ -68,7 +62,16 @@ impl BlockBodyFullValidator {
         backend: &B,
         block: &Block,
         metadata_option: Option<&ChainMetadata>,
-        smt: Arc<RwLock<OutputSmt>>,
+    ) -> Result<Block, ValidationError> {
+        self.validate_block(backend, block, metadata_option, false)
+    }
+
+    fn validate_block<B: BlockchainBackend>(
+        &self,
+        backend: &B,
+        block: &Block,
+        metadata_option: Option<&ChainMetadata>,
+        proofs_verified: bool,
     ) -> Result<Block, ValidationError> {
         if let Some(metadata) = metadata_option {
             validate_block_metadata(block, metadata)?;
@@ -79,21 +82,24 @@ impl BlockBodyFullValidator {
         let height = block.header.height;
         // the inputs may be only references to outputs, that's why the validator returns a new body and we need a new
         // block
-        let body = self.aggregate_body_chain_validator.validate(body, height, backend)?;
+        let body = self.aggregate_body_chain_validator.validate(
+            body,
+            &block.header.total_script_offset,
+            Some(block.header.prev_hash),
+            height,
+            backend,
+        )?;
         let block = Block::new(block.header.clone(), body);
 
         // validate the internal consistency of the block body
-        self.block_internal_validator.validate(&block)?;
+        if proofs_verified {
+            self.block_internal_validator.validate_with_verified_proofs(&block)?;
+        } else {
+            self.block_internal_validator.validate(&block)?;
+        }
 
         // validate the merkle mountain range roots+
-        let mut output_smt = smt.write().map_err(|e| {
-            error!(
-                target: LOG_TARGET,
-                "Validator could not get a write lock on the smt {:?}", e
-            );
-            ChainStorageError::AccessError("write lock on smt".into())
-        })?;
-        let mmr_roots = chain_storage::calculate_mmr_roots(backend, &self.consensus_manager, &block, &mut output_smt)?;
+        let mmr_roots = chain_storage::calculate_mmr_roots(backend, &self.consensus_manager, &block)?;
         check_mmr_roots(&block.header, &mmr_roots)?;
 
         Ok(block)
@@ -106,16 +112,23 @@ impl<B: BlockchainBackend> CandidateBlockValidator<B> for BlockBodyFullValidator
         backend: &B,
         block: &ChainBlock,
         metadata: &ChainMetadata,
-        smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<(), ValidationError> {
-        self.validate(backend, block.block(), Some(metadata), smt)?;
+        self.validate(backend, block.block(), Some(metadata))?;
         Ok(())
     }
 }
 
 impl<B: BlockchainBackend> BlockBodyValidator<B> for BlockBodyFullValidator {
-    fn validate_body(&self, backend: &B, block: &Block, smt: Arc<RwLock<OutputSmt>>) -> Result<Block, ValidationError> {
-        self.validate(backend, block, None, smt)
+    fn validate_body(&self, backend: &B, block: &Block) -> Result<Block, ValidationError> {
+        self.validate(backend, block, None)
+    }
+
+    fn verify_body_proofs(&self, body: &AggregateBody) -> Result<(), ValidationError> {
+        self.block_internal_validator.verify_proofs(body)
+    }
+
+    fn validate_body_with_verified_proofs(&self, backend: &B, block: &Block) -> Result<Block, ValidationError> {
+        self.validate_block(backend, block, None, true)
     }
 }
 
@@ -63,10 +63,24 @@ impl BlockBodyInternalConsistencyValidator {
 
     pub fn validate(&self, block: &Block) -> Result<(), ValidationError> {
         validate_block_specific_checks(block, &self.consensus_manager, &self.factories)?;
-        validate_block_aggregate_body(block, &self.aggregate_body_validator, &self.consensus_manager)?;
+        validate_block_aggregate_body(block, &self.aggregate_body_validator, &self.consensus_manager, false)?;
 
         Ok(())
     }
+
+    /// Validates the block like `validate`, except for the signatures and range proofs already checked with
+    /// `verify_proofs`
+    pub fn validate_with_verified_proofs(&self, block: &Block) -> Result<(), ValidationError> {
+        validate_block_specific_checks(block, &self.consensus_manager, &self.factories)?;
+        validate_block_aggregate_body(block, &self.aggregate_body_validator, &self.consensus_manager, true)?;
+
+        Ok(())
+    }
+
+    /// Verifies the kernel signatures, metadata signatures and range proofs of the block body
+    pub fn verify_proofs(&self, body: &AggregateBody) -> Result<(), ValidationError> {
+        self.aggregate_body_validator.verify_proofs(body)
+    }
 }
 
 impl InternalConsistencyValidator for BlockBodyInternalConsistencyValidator {
@@ -104,6 +118,7 @@ fn validate_block_aggregate_body(
     block: &Block,
     validator: &AggregateBodyInternalConsistencyValidator,
     consensus_manager: &ConsensusManager,
+    proofs_verified: bool,
 ) -> Result<(), ValidationError> {
     let offset = &block.header.total_kernel_offset;
     let script_offset = &block.header.total_script_offset;
@@ -118,8 +133,8 @@ fn validate_block_aggregate_body(
             );
             ValidationError::CoinbaseExceedsMaxLimit
         })?;
-    validator
-        .validate(
+    let result = if proofs_verified {
+        validator.validate_with_verified_proofs(
             &block.body,
             offset,
             script_offset,
@@ -127,15 +142,25 @@ fn validate_block_aggregate_body(
             Some(block.header.prev_hash),
             block.header.height,
         )
-        .map_err(|err| {
-            warn!(
-                target: LOG_TARGET,
-                "Validation failed on block:{}:{:?}",
-                block.hash().to_hex(),
-                err
-            );
+    } else {
+        validator.validate(
+            &block.body,
+            offset,
+            script_offset,
+            Some(total_coinbase),
+            Some(block.header.prev_hash),
+            block.header.height,
+        )
+    };
+    result.map_err(|err| {
+        warn!(
+            target: LOG_TARGET,
+            "Validation failed on block:{}:{:?}",
+            block.hash().to_hex(),
             err
-        })?;
+        );
+        err
+    })?;
 
     Ok(())
 }
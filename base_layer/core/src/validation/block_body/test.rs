@@ -24,7 +24,7 @@ use std::sync::Arc;
 use tari_common::configuration::Network;
 use tari_common_types::{key_branches::TransactionKeyManagerBranch, tari_address::TariAddress};
 use tari_key_manager::key_manager_service::KeyId;
-use tari_script::{push_pubkey_script, script};
+use tari_script::{push_pubkey_script, script, ScriptError};
 use tari_test_utils::unpack_enum;
 use tokio::time::Instant;
 
@@ -104,8 +104,7 @@ async fn it_passes_if_large_output_block_is_valid() {
 
     let txn = blockchain.db().db_read_access().unwrap();
     let start = Instant::now();
-    let smt = blockchain.db().smt().clone();
-    assert!(validator.validate_body(&*txn, &block, smt).is_ok());
+    assert!(validator.validate_body(&*txn, &block).is_ok());
     let finished = start.elapsed();
     // this here here for benchmarking purposes.
     // we can extrapolate full block validation by multiplying the time by 4.6, this we get from the max_weight /weight
@@ -140,8 +139,7 @@ async fn it_validates_when_a_coinbase_is_spent() {
     block.header.validator_node_size = mmr_roots.validator_node_size;
 
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt().clone();
-    assert!(validator.validate_body(&*txn, &block, smt).is_ok());
+    assert!(validator.validate_body(&*txn, &block).is_ok());
 }
 
 #[tokio::test]
@@ -184,8 +182,7 @@ async fn it_passes_if_large_block_is_valid() {
 
     let txn = blockchain.db().db_read_access().unwrap();
     let start = Instant::now();
-    let smt = blockchain.db().smt();
-    validator.validate_body(&*txn, &block, smt).unwrap();
+    validator.validate_body(&*txn, &block).unwrap();
     // assert!(validator.validate_body(&*txn, &block).is_ok());
     let finished = start.elapsed();
     // this here here for benchmarking purposes.
@@ -214,8 +211,7 @@ async fn it_passes_if_block_is_valid() {
     block.header.validator_node_size = mmr_roots.validator_node_size;
 
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    assert!(validator.validate_body(&*txn, &block, smt).is_ok());
+    assert!(validator.validate_body(&*txn, &block).is_ok());
 }
 
 #[tokio::test]
@@ -226,8 +222,7 @@ async fn it_checks_the_coinbase_reward() {
         .create_chained_block(block_spec!("A", parent: "GB", reward: 10 * T, ))
         .await;
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     println!("err {:?}", err);
     assert!(matches!(
         err,
@@ -273,9 +268,8 @@ async fn it_allows_multiple_coinbases() {
         .create_unmined_block(block_spec!("A2", parent: "GB", skip_coinbase: true,))
         .await;
     let block = blockchain.mine_block("GB", block, Difficulty::min());
-    let smt = blockchain.db().smt();
     let txn = blockchain.db().db_read_access().unwrap();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(
         err,
         ValidationError::BlockError(BlockValidationError::TransactionError(TransactionError::NoCoinbase))
@@ -305,8 +299,7 @@ async fn it_checks_duplicate_kernel() {
         )
         .await;
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(err, ValidationError::DuplicateKernelError(_)));
 }
 
@@ -339,8 +332,7 @@ async fn it_checks_double_spends() {
         )
         .await;
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(err, ValidationError::ContainsSTxO));
 }
 
@@ -361,8 +353,7 @@ async fn it_checks_input_maturity() {
         )
         .await;
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(
         err,
         ValidationError::TransactionError(TransactionError::InputMaturity)
@@ -370,6 +361,36 @@ async fn it_checks_input_maturity() {
     unpack_enum!(ValidationError::TransactionError(TransactionError::InputMaturity) = err);
 }
 
+#[tokio::test]
+async fn it_checks_relative_height_locks() {
+    let (mut blockchain, validator) = setup(true).await;
+
+    let (_, coinbase_a) = blockchain.add_next_tip(block_spec!("A")).await.unwrap();
+    // The output mined in block B may only be spent 3 blocks later
+    let mut schema = txn_schema!(from: vec![coinbase_a.clone()], to: vec![50 * T]);
+    schema.script = script!(CheckRelativeHeightVerify(3)).unwrap();
+    let (txs, outputs) = schema_to_transaction(&[schema], &blockchain.km).await;
+    let txs = txs.into_iter().map(|t| Arc::try_unwrap(t).unwrap()).collect::<Vec<_>>();
+    blockchain
+        .add_next_tip(block_spec!("B", parent: "A", transactions: txs))
+        .await
+        .unwrap();
+
+    let schema = txn_schema!(from: vec![outputs[0].clone()], to: vec![10 * T]);
+    let (txs, _) = schema_to_transaction(&[schema], &blockchain.km).await;
+    let txs = txs.into_iter().map(|t| Arc::try_unwrap(t).unwrap()).collect::<Vec<_>>();
+    let (block, _) = blockchain
+        .create_next_tip(block_spec!("C", parent: "B", transactions: txs))
+        .await;
+
+    let txn = blockchain.db().db_read_access().unwrap();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
+    assert!(matches!(
+        err,
+        ValidationError::TransactionError(TransactionError::ScriptError(ScriptError::VerifyFailed))
+    ));
+}
+
 #[tokio::test]
 async fn it_checks_txo_sort_order() {
     let (mut blockchain, validator) = setup(true).await;
@@ -390,8 +411,7 @@ async fn it_checks_txo_sort_order() {
     let block = blockchain.mine_block("A", block, Difficulty::min());
 
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(err, ValidationError::UnsortedOrDuplicateOutput));
 }
 
@@ -417,8 +437,7 @@ async fn it_limits_the_script_byte_size() {
     let (block, _) = blockchain.create_next_tip(block_spec!("B", transactions: txs)).await;
 
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(err, ValidationError::TariScriptExceedsMaxSize { .. }));
 }
 
@@ -446,8 +465,7 @@ async fn it_limits_the_encrypted_data_byte_size() {
     let (block, _) = blockchain.create_next_tip(block_spec!("B", transactions: txs)).await;
 
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(err, ValidationError::EncryptedDataExceedsMaxSize { .. }));
 }
 
@@ -472,8 +490,7 @@ async fn it_rejects_invalid_input_metadata() {
     let (block, _) = blockchain.create_next_tip(block_spec!("B", transactions: txs)).await;
 
     let txn = blockchain.db().db_read_access().unwrap();
-    let smt = blockchain.db().smt();
-    let err = validator.validate_body(&*txn, block.block(), smt).unwrap_err();
+    let err = validator.validate_body(&*txn, block.block()).unwrap_err();
     assert!(matches!(err, ValidationError::UnknownInputs(_)));
 }
 
@@ -501,9 +518,8 @@ async fn it_rejects_zero_conf_double_spends() {
     let (unmined, _) = blockchain
         .create_unmined_block(block_spec!("2", parent: "1", transactions: transactions))
         .await;
-    let smt = blockchain.db().smt();
     let txn = blockchain.db().db_read_access().unwrap();
-    let err = validator.validate_body(&*txn, &unmined, smt).unwrap_err();
+    let err = validator.validate_body(&*txn, &unmined).unwrap_err();
     assert!(matches!(err, ValidationError::UnsortedOrDuplicateInput));
 }
 
@@ -536,10 +552,7 @@ mod body_only {
         let metadata = blockchain.db().get_chain_metadata().unwrap();
 
         let db = blockchain.db().db_read_access().unwrap();
-        let smt = blockchain.db().smt();
-        let err = validator
-            .validate(&*db, block.block(), Some(&metadata), smt)
-            .unwrap_err();
+        let err = validator.validate(&*db, block.block(), Some(&metadata)).unwrap_err();
         assert!(matches!(err, ValidationError::UnknownInputs(_)));
     }
 }
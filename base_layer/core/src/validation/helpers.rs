@@ -486,6 +486,20 @@ pub fn validate_output_version(
         }
     }
 
+    for filter in output.covenant.filters() {
+        if !consensus_constants
+            .output_version_range()
+            .covenant
+            .contains(&filter.get_version())
+        {
+            let msg = format!(
+                "Transaction output covenant filter is not allowed by consensus ({:?})",
+                filter
+            );
+            return Err(ValidationError::ConsensusError(msg));
+        }
+    }
+
     Ok(())
 }
 
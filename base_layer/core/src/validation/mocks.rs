@@ -23,7 +23,6 @@
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
-    RwLock,
 };
 
 use tari_common_types::{chain_metadata::ChainMetadata, types::Commitment};
@@ -43,7 +42,6 @@ use crate::{
     test_helpers::create_consensus_rules,
     transactions::transaction_components::Transaction,
     validation::{error::ValidationError, DifficultyCalculator, FinalHorizonStateValidation},
-    OutputSmt,
 };
 
 #[derive(Clone)]
@@ -72,7 +70,7 @@ impl MockValidator {
 }
 
 impl<B: BlockchainBackend> BlockBodyValidator<B> for MockValidator {
-    fn validate_body(&self, _: &B, block: &Block, _: Arc<RwLock<OutputSmt>>) -> Result<Block, ValidationError> {
+    fn validate_body(&self, _: &B, block: &Block) -> Result<Block, ValidationError> {
         if self.is_valid.load(Ordering::SeqCst) {
             Ok(block.clone())
         } else {
@@ -84,13 +82,7 @@ impl<B: BlockchainBackend> BlockBodyValidator<B> for MockValidator {
 }
 
 impl<B: BlockchainBackend> CandidateBlockValidator<B> for MockValidator {
-    fn validate_body_with_metadata(
-        &self,
-        _: &B,
-        _: &ChainBlock,
-        _: &ChainMetadata,
-        _: Arc<RwLock<OutputSmt>>,
-    ) -> Result<(), ValidationError> {
+    fn validate_body_with_metadata(&self, _: &B, _: &ChainBlock, _: &ChainMetadata) -> Result<(), ValidationError> {
         if self.is_valid.load(Ordering::SeqCst) {
             Ok(())
         } else {
@@ -1,12 +1,9 @@
-use std::sync::{Arc, RwLock};
-
 // Copyright 2019. The Tari Project
 //
 // Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
 // following conditions are met:
 //
-// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the
-//    following
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
 // disclaimer.
 //
 // 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
@@ -30,15 +27,25 @@ use crate::{
     blocks::{Block, BlockHeader, ChainBlock},
     chain_storage::BlockchainBackend,
     proof_of_work::{AchievedTargetDifficulty, Difficulty},
-    transactions::transaction_components::Transaction,
+    transactions::{aggregated_body::AggregateBody, transaction_components::Transaction},
     validation::error::ValidationError,
-    OutputSmt,
 };
 
 /// A validator that determines if a block body is valid, assuming that the header has already been
 /// validated
 pub trait BlockBodyValidator<B>: Send + Sync {
-    fn validate_body(&self, backend: &B, block: &Block, smt: Arc<RwLock<OutputSmt>>) -> Result<Block, ValidationError>;
+    fn validate_body(&self, backend: &B, block: &Block) -> Result<Block, ValidationError>;
+
+    /// Verifies the signatures and range proofs of a block body. These checks do not depend on the chain, so block
+    /// sync runs them for several downloaded blocks concurrently before calling `validate_body_with_verified_proofs`.
+    fn verify_body_proofs(&self, _body: &AggregateBody) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Validates the block body like `validate_body`, except for the checks done by `verify_body_proofs`
+    fn validate_body_with_verified_proofs(&self, backend: &B, block: &Block) -> Result<Block, ValidationError> {
+        self.validate_body(backend, block)
+    }
 }
 
 /// A validator that validates a body after it has been determined to be a valid orphan
@@ -48,7 +55,6 @@ pub trait CandidateBlockValidator<B>: Send + Sync {
         backend: &B,
         block: &ChainBlock,
         metadata: &ChainMetadata,
-        smt: Arc<RwLock<OutputSmt>>,
     ) -> Result<(), ValidationError>;
 }
 
@@ -64,8 +64,15 @@ impl<B: BlockchainBackend> TransactionValidator for TransactionChainLinkedValida
 
         {
             let db = self.db.db_read_access()?;
-            let tip_height = db.fetch_chain_metadata()?.best_block_height();
-            self.aggregate_body_validator.validate(&tx.body, tip_height, &*db)?;
+            let tip_metadata = db.fetch_chain_metadata()?;
+            let tip_height = tip_metadata.best_block_height();
+            self.aggregate_body_validator.validate(
+                &tx.body,
+                &tx.script_offset,
+                Some(*tip_metadata.best_block_hash()),
+                tip_height,
+                &*db,
+            )?;
         };
 
         Ok(())
@@ -20,10 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    sync::{Arc, RwLock},
-    time::Duration,
-};
+use std::{sync::Arc, time::Duration};
 
 use tari_common::configuration::Network;
 use tari_common_types::types::HashOutput;
@@ -42,6 +39,7 @@ use tari_core::{
         sync::SyncPeer,
         BaseNodeStateMachine,
         BaseNodeStateMachineConfig,
+        BlockchainSyncConfig,
         SyncValidators,
     },
     blocks::ChainBlock,
@@ -58,7 +56,6 @@ use tari_core::{
     },
     txn_schema,
     validation::mocks::MockValidator,
-    OutputSmt,
 };
 use tari_p2p::{services::liveness::LivenessConfig, P2pConfig};
 use tari_shutdown::Shutdown;
@@ -103,6 +100,22 @@ pub fn initialize_sync_blocks(peer_node_interfaces: &NodeInterfaces) -> BlockSyn
     ))])
 }
 
+/// Helper function to initialize block sync with multiple peers
+pub fn initialize_sync_blocks_with_multiple_peers(peer_node_interfaces: &[&NodeInterfaces]) -> BlockSync {
+    BlockSync::from(
+        peer_node_interfaces
+            .iter()
+            .map(|peer| {
+                SyncPeer::from(PeerChainMetadata::new(
+                    peer.node_identity.node_id().clone(),
+                    peer.blockchain_db.get_chain_metadata().unwrap(),
+                    None,
+                ))
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
 /// Helper function to initialize block sync with a single peer
 pub async fn sync_blocks_execute(
     state_machine: &mut BaseNodeStateMachine<TempDatabase>,
@@ -147,6 +160,21 @@ pub async fn create_network_with_multiple_nodes(
     ConsensusManager,
     MemoryDbKeyManager,
     WalletOutput,
+) {
+    create_network_with_multiple_nodes_and_sync_config(blockchain_db_configs, BlockchainSyncConfig::default()).await
+}
+
+/// Helper function to create a network with multiple nodes using the given blockchain sync config
+pub async fn create_network_with_multiple_nodes_and_sync_config(
+    blockchain_db_configs: Vec<BlockchainDatabaseConfig>,
+    blockchain_sync_config: BlockchainSyncConfig,
+) -> (
+    Vec<BaseNodeStateMachine<TempDatabase>>,
+    Vec<NodeInterfaces>,
+    ChainBlock,
+    ConsensusManager,
+    MemoryDbKeyManager,
+    WalletOutput,
 ) {
     let num_nodes = blockchain_db_configs.len();
     if num_nodes < 2 {
@@ -190,7 +218,10 @@ pub async fn create_network_with_multiple_nodes(
             node_interface.comms.connectivity(),
             node_interface.comms.peer_manager(),
             node_interface.chain_metadata_handle.get_event_stream(),
-            BaseNodeStateMachineConfig::default(),
+            BaseNodeStateMachineConfig {
+                blockchain_sync_config: blockchain_sync_config.clone(),
+                ..Default::default()
+            },
             SyncValidators::new(MockValidator::new(true), MockValidator::new(true)),
             status_event_sender,
             state_change_event_publisher,
@@ -221,14 +252,8 @@ pub enum WhatToDelete {
 
 // Private helper function to setup a delete a block transaction.
 // Note: This private function will panic if the index is out of bounds - caller function's responsibility.
-fn delete_block(
-    txn: &mut DbTransaction,
-    node: &NodeInterfaces,
-    blocks: &[ChainBlock],
-    index: usize,
-    smt: Arc<RwLock<OutputSmt>>,
-) {
-    txn.delete_tip_block(*blocks[index].hash(), smt);
+fn delete_block(txn: &mut DbTransaction, node: &NodeInterfaces, blocks: &[ChainBlock], index: usize) {
+    txn.delete_tip_block(*blocks[index].hash());
     txn.delete_orphan(*blocks[index].hash());
     txn.set_best_block(
         blocks[index + 1].height(),
@@ -245,7 +270,6 @@ pub fn delete_some_blocks_and_headers(
     instruction: WhatToDelete,
     node: &NodeInterfaces,
 ) {
-    let smt = node.blockchain_db.smt().clone();
     if blocks_with_anchor.is_empty() || blocks_with_anchor.len() < 2 {
         panic!("blocks must have at least 2 elements");
     }
@@ -255,11 +279,11 @@ pub fn delete_some_blocks_and_headers(
         let mut txn = DbTransaction::new();
         match instruction {
             WhatToDelete::BlocksAndHeaders => {
-                delete_block(&mut txn, node, &blocks, i, smt.clone());
+                delete_block(&mut txn, node, &blocks, i);
                 txn.delete_header(blocks[i].height());
             },
             WhatToDelete::Blocks => {
-                delete_block(&mut txn, node, &blocks, i, smt.clone());
+                delete_block(&mut txn, node, &blocks, i);
             },
             WhatToDelete::Headers => {
                 txn.delete_header(blocks[i].height());
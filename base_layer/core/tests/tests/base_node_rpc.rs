@@ -25,7 +25,7 @@ use std::{convert::TryFrom, sync::Arc, time::Duration};
 use futures::StreamExt;
 use randomx_rs::RandomXFlag;
 use tari_common::configuration::Network;
-use tari_comms::protocol::rpc::mock::RpcRequestMock;
+use tari_comms::protocol::rpc::{mock::RpcRequestMock, RpcStatusCode};
 use tari_core::{
     base_node::{
         comms_interface::LocalNodeCommsInterface,
@@ -40,11 +40,16 @@ use tari_core::{
         state_machine_service::states::{ListeningInfo, StateInfo, StatusInfo},
         sync::rpc::BaseNodeSyncRpcService,
     },
-    blocks::ChainBlock,
+    blocks::{BlockFilter, BlockFilterElement, ChainBlock},
     chain_storage::BlockchainDatabaseConfig,
     consensus::{ConsensusConstantsBuilder, ConsensusManager, ConsensusManagerBuilder, NetworkConsensus},
     proto::{
-        base_node::{FetchMatchingUtxos, Signatures as SignaturesProto, SyncUtxosByBlockRequest},
+        base_node::{
+            FetchMatchingUtxos,
+            GetBlockFiltersRequest,
+            Signatures as SignaturesProto,
+            SyncUtxosByBlockRequest,
+        },
         types::{Signature as SignatureProto, Transaction as TransactionProto},
     },
     test_helpers::blockchain::TempDatabase,
@@ -461,3 +466,62 @@ async fn test_sync_utxos_by_block() {
             .collect::<Vec<(u64, Vec<u8>, usize)>>()
     );
 }
+
+#[tokio::test]
+async fn test_get_block_filters() {
+    let (service, _, mut base_node, request_mock, consensus_manager, block0, utxo0, _temp_dir, key_manager) =
+        setup().await;
+
+    let (txs1, _utxos1) = schema_to_transaction(
+        &[txn_schema!(from: vec![utxo0.clone()], to: vec![10 * T, 10 * T])],
+        &key_manager,
+    )
+    .await;
+    let block1 = base_node
+        .blockchain_db
+        .prepare_new_block(
+            chain_block(
+                block0.block(),
+                vec![(*txs1[0]).clone()],
+                &consensus_manager,
+                &key_manager,
+            )
+            .await,
+        )
+        .unwrap();
+    base_node.local_nci.submit_block(block1.clone()).await.unwrap();
+
+    // The end height is capped at the tip
+    let msg = GetBlockFiltersRequest {
+        start_height: 0,
+        end_height: 10,
+    };
+    let req = request_mock.request_with_context(Default::default(), msg);
+    let filters = service.get_block_filters(req).await.unwrap().into_message().filters;
+    assert_eq!(filters.len(), 2);
+    for (filter, block) in filters.into_iter().zip([block0.block(), &block1]) {
+        let header_hash = block.header.hash();
+        assert_eq!(filter.height, block.header.height);
+        assert_eq!(filter.header_hash, header_hash.to_vec());
+        let filter = BlockFilter::from_parts(filter.num_elements, filter.data);
+        for output in block.body.outputs() {
+            assert!(filter.matches_any(&header_hash, &[BlockFilterElement::commitment(&output.commitment)]));
+        }
+    }
+
+    let msg = GetBlockFiltersRequest {
+        start_height: 2,
+        end_height: 1,
+    };
+    let req = request_mock.request_with_context(Default::default(), msg);
+    let err = service.get_block_filters(req).await.unwrap_err();
+    assert_eq!(err.as_status_code(), RpcStatusCode::BadRequest);
+
+    let msg = GetBlockFiltersRequest {
+        start_height: 0,
+        end_height: 1000,
+    };
+    let req = request_mock.request_with_context(Default::default(), msg);
+    let err = service.get_block_filters(req).await.unwrap_err();
+    assert_eq!(err.as_status_code(), RpcStatusCode::BadRequest);
+}
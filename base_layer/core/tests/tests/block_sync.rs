@@ -20,7 +20,10 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_core::{base_node::state_machine_service::states::StateEvent, chain_storage::BlockchainDatabaseConfig};
+use tari_core::{
+    base_node::{state_machine_service::states::StateEvent, BlockchainSyncConfig},
+    chain_storage::BlockchainDatabaseConfig,
+};
 
 use crate::helpers::{
     sync,
@@ -92,6 +95,79 @@ async fn test_block_sync_happy_path() {
     assert_eq!(alice_node.blockchain_db.get_height().unwrap(), 5);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_block_sync_from_multiple_peers_happy_path() {
+    // env_logger::init(); // Set `$env:RUST_LOG = "trace"`
+
+    // Create the network with Alice node, Bob node and Carol node, downloading small chunks of blocks so that the
+    // blocks are spread over both sync peers
+    let (mut state_machines, mut peer_nodes, initial_block, consensus_manager, key_manager, initial_coinbase) =
+        sync::create_network_with_multiple_nodes_and_sync_config(
+            vec![
+                BlockchainDatabaseConfig::default(),
+                BlockchainDatabaseConfig::default(),
+                BlockchainDatabaseConfig::default(),
+            ],
+            BlockchainSyncConfig {
+                block_sync_chunk_size: 2,
+                validation_concurrency: 2,
+                ..Default::default()
+            },
+        )
+        .await;
+    let mut alice_state_machine = state_machines.remove(0);
+    let alice_node = peer_nodes.remove(0);
+    let bob_node = peer_nodes.remove(0);
+    let carol_node = peer_nodes.remove(0);
+
+    // Add some blocks to Bob's and Carol's chains
+    let (blocks, _coinbases) = sync::create_and_add_some_blocks(
+        &bob_node,
+        &initial_block,
+        &initial_coinbase,
+        11,
+        &consensus_manager,
+        &key_manager,
+        &[3; 11],
+        &None,
+    )
+    .await;
+    sync::add_some_existing_blocks(&blocks[1..=11], &carol_node);
+    assert_eq!(bob_node.blockchain_db.get_height().unwrap(), 11);
+    assert_eq!(carol_node.blockchain_db.get_height().unwrap(), 11);
+
+    // Alice attempts header sync
+    let mut header_sync = sync::initialize_sync_headers_with_ping_pong_data(&alice_node, &bob_node);
+    let event = sync::sync_headers_execute(&mut alice_state_machine, &mut header_sync).await;
+    match event.clone() {
+        StateEvent::HeadersSynchronized(..) => {
+            // Good, headers are synced
+        },
+        _ => panic!("Expected HeadersSynchronized event"),
+    }
+
+    // Alice attempts block sync from Bob and Carol concurrently
+    println!();
+    assert_eq!(alice_node.blockchain_db.get_height().unwrap(), 0);
+    let mut block_sync = sync::initialize_sync_blocks_with_multiple_peers(&[&bob_node, &carol_node]);
+    let event = sync::sync_blocks_execute(&mut alice_state_machine, &mut block_sync).await;
+    match event {
+        StateEvent::BlocksSynchronized => {
+            // Good, blocks are synced
+        },
+        _ => panic!("Expected BlocksSynchronized event"),
+    }
+    assert_eq!(alice_node.blockchain_db.get_height().unwrap(), 11);
+    assert_eq!(
+        alice_node.blockchain_db.fetch_tip_header().unwrap().hash(),
+        bob_node.blockchain_db.fetch_tip_header().unwrap().hash()
+    );
+
+    // Neither sync peer misbehaved
+    assert!(!sync::wait_for_is_peer_banned(&alice_node, bob_node.node_identity.node_id(), 1).await);
+    assert!(!sync::wait_for_is_peer_banned(&alice_node, carol_node.node_identity.node_id(), 1).await);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_block_sync_peer_supplies_no_blocks_with_ban() {
     // env_logger::init(); // Set `$env:RUST_LOG = "trace"`
@@ -23,9 +23,13 @@
 use std::cmp::min;
 
 use tari_core::{
-    base_node::state_machine_service::states::{HorizonStateSync, StateEvent},
-    chain_storage::BlockchainDatabaseConfig,
+    base_node::{
+        state_machine_service::states::{HorizonStateSync, StateEvent},
+        BlockchainSyncConfig,
+    },
+    chain_storage::{create_utxo_snapshot, BlockchainDatabaseConfig},
 };
+use tempfile::tempdir;
 
 use crate::helpers::{
     sync,
@@ -839,3 +843,99 @@ async fn test_initial_horizon_sync_from_prune_node_happy_path() {
     // Carol will not be banned
     assert!(!sync::wait_for_is_peer_banned(&alice_node, carol_node.node_identity.node_id(), 1).await);
 }
+
+#[allow(clippy::too_many_lines)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_initial_horizon_sync_from_utxo_snapshot() {
+    // Create the network with Alice (pruning node, configured with a UTXO snapshot) and Bob (archival node)
+    let temp_dir = tempdir().unwrap();
+    let snapshot_path = temp_dir.path().join("utxo_snapshot.bin");
+    let pruning_horizon = 5;
+    let (mut state_machines, mut peer_nodes, initial_block, consensus_manager, key_manager, initial_coinbase) =
+        sync::create_network_with_multiple_nodes_and_sync_config(
+            vec![
+                BlockchainDatabaseConfig {
+                    orphan_storage_capacity: 5,
+                    pruning_horizon,
+                    pruning_interval: 5,
+                    track_reorgs: false,
+                    cleanup_orphans_at_startup: false,
+                },
+                BlockchainDatabaseConfig::default(),
+            ],
+            BlockchainSyncConfig {
+                utxo_snapshot_file: Some(snapshot_path.clone()),
+                ..Default::default()
+            },
+        )
+        .await;
+    let mut alice_state_machine = state_machines.remove(0);
+    let alice_node = peer_nodes.remove(0);
+    let bob_node = peer_nodes.remove(0);
+
+    // Create a blockchain that spends the genesys coinbase early on and then later spends some more coinbase outputs
+    let (_blocks, _coinbases) = sync::create_block_chain_with_transactions(
+        &bob_node,
+        &initial_block,
+        &initial_coinbase,
+        &consensus_manager,
+        &key_manager,
+        pruning_horizon,
+        30, // > follow_up_transaction_in_block + pruning_horizon + 1
+        3,  // < pruning_horizon
+        16, // > pruning_horizon
+        15, // > spend_genesis_coinbase_in_block - 1, < follow_up_transaction_in_block
+    )
+    .await;
+
+    // 1. Bob creates a UTXO snapshot at the height Alice will horizon sync to
+    println!("\n1. Bob creates a UTXO snapshot at height 25\n");
+
+    create_utxo_snapshot(&bob_node.blockchain_db, 25, &snapshot_path).unwrap();
+
+    // 2. Alice does header sync (to height 30)
+    println!("\n2. Alice does header sync (to height 30)\n");
+
+    let mut header_sync = sync::initialize_sync_headers_with_ping_pong_data(&alice_node, &bob_node);
+    let _event = sync::sync_headers_execute(&mut alice_state_machine, &mut header_sync).await;
+    assert_eq!(alice_node.blockchain_db.fetch_last_header().unwrap().height, 30);
+
+    // 3. Alice loads the horizon state (to height 25) from the snapshot, without any sync peers
+    println!("\n3. Alice loads the horizon state from the UTXO snapshot (to height 25)\n");
+
+    let output_hash = initial_coinbase.hash(&key_manager).await.unwrap();
+    assert!(alice_node.blockchain_db.fetch_output(output_hash).unwrap().is_some());
+    let mut horizon_sync = HorizonStateSync::from(vec![]);
+    let event = sync::horizon_sync_execute(&mut alice_state_machine, &mut horizon_sync).await;
+
+    println!(
+        "Event: {} to block {}",
+        state_event(&event),
+        alice_node.blockchain_db.get_height().unwrap()
+    );
+    assert_eq!(event, StateEvent::HorizonStateSynchronized);
+    assert_eq!(alice_node.blockchain_db.get_height().unwrap(), 25);
+    let alice_metadata = alice_node.blockchain_db.get_chain_metadata().unwrap();
+    assert_eq!(alice_metadata.pruned_height(), 25);
+    assert_eq!(
+        alice_metadata.best_block_hash(),
+        bob_node.blockchain_db.fetch_chain_header(25).unwrap().hash()
+    );
+    // The genesis coinbase was spent before the snapshot height
+    assert!(alice_node.blockchain_db.fetch_output(output_hash).unwrap().is_none());
+
+    // 4. Alice attempts block sync to the tip (to height 30)
+    println!("\n4. Alice attempts block sync to the tip (to height 30)\n");
+
+    let mut block_sync = sync::initialize_sync_blocks(&bob_node);
+    let event = sync::sync_blocks_execute(&mut alice_state_machine, &mut block_sync).await;
+    println!(
+        "Event: {} to block {}",
+        state_event(&event),
+        alice_node.blockchain_db.get_height().unwrap()
+    );
+    assert_eq!(event, StateEvent::BlocksSynchronized);
+    assert_eq!(alice_node.blockchain_db.get_height().unwrap(), 30);
+    // Bob will not be banned
+    assert!(!sync::wait_for_is_peer_banned(&alice_node, bob_node.node_identity.node_id(), 1).await);
+}
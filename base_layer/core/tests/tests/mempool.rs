@@ -33,7 +33,7 @@ use tari_core::{
     base_node::state_machine_service::states::{ListeningInfo, StateInfo, StatusInfo},
     chain_storage::BlockchainDatabaseConfig,
     consensus::{ConsensusConstantsBuilder, ConsensusManager},
-    mempool::{Mempool, MempoolConfig, MempoolServiceConfig, TxStorageResponse},
+    mempool::{Mempool, MempoolConfig, MempoolServiceConfig, SnapshotRestoreStats, TxStorageResponse},
     proof_of_work::Difficulty,
     proto,
     transactions::{
@@ -73,7 +73,7 @@ use tari_core::{
 };
 use tari_key_manager::key_manager_service::KeyManagerInterface;
 use tari_p2p::{services::liveness::LivenessConfig, tari_message::TariMessageType, P2pConfig};
-use tari_script::script;
+use tari_script::{script, OpcodeVersion};
 use tari_test_utils::async_assert_eventually;
 use tempfile::tempdir;
 
@@ -549,6 +549,181 @@ async fn test_zero_conf_no_piggyback() {
     assert!(!retrieved_txs.contains(&Arc::new(tx_a)));
 }
 
+#[tokio::test]
+async fn test_insert_package() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) = create_new_blockchain(network).await;
+    let txs = vec![txn_schema!(
+        from: vec![outputs[0][0].clone()],
+        to: vec![21 * T, 11 * T]
+    )];
+    // "Mine" Block 1
+    generate_new_block(
+        &mut store,
+        &mut blocks,
+        &mut outputs,
+        txs,
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap();
+
+    let (tx_parent, tx_parent_out) = spend_utxos(
+        txn_schema!(
+            from: vec![outputs[1][0].clone()],
+            to: vec![15 * T, 5 * T],
+            fee: 1*uT,
+            lock: 0,
+            features: OutputFeatures::default()
+        ),
+        &key_manager,
+    )
+    .await;
+    let (tx_child, _tx_child_out) = spend_utxos(
+        txn_schema!(
+            from: vec![tx_parent_out[0].clone()],
+            to: vec![7 * T, 4 * T],
+            fee: 50*uT,
+            lock: 0,
+            features: OutputFeatures::default()
+        ),
+        &key_manager,
+    )
+    .await;
+    let tx_parent = Arc::new(tx_parent);
+    let tx_child = Arc::new(tx_child);
+
+    // The parent does not pay the minimum fee on its own, but the package does on average
+    let parent_fee = tx_parent.body.get_total_fee().unwrap().as_u64();
+    let child_fee = tx_child.body.get_total_fee().unwrap().as_u64();
+    let mut mempool_config = MempoolConfig::default();
+    mempool_config.unconfirmed_pool.min_fee = (parent_fee + child_fee) / 2;
+    let mempool_validator = TransactionChainLinkedValidator::new(store.clone(), consensus_manager.clone());
+    let mempool = Mempool::new(mempool_config, consensus_manager.clone(), Box::new(mempool_validator));
+    mempool.process_published_block(blocks[1].to_arc_block()).await.unwrap();
+
+    assert_eq!(
+        mempool.insert(tx_parent.clone()).await.unwrap(),
+        TxStorageResponse::NotStoredFeeTooLow
+    );
+    assert_eq!(
+        mempool.insert(tx_child.clone()).await.unwrap(),
+        TxStorageResponse::NotStoredOrphan
+    );
+    // Children must follow their parents in the package
+    assert_eq!(
+        mempool
+            .insert_package(vec![tx_child.clone(), tx_parent.clone()])
+            .await
+            .unwrap(),
+        TxStorageResponse::NotStoredOrphan
+    );
+    assert_eq!(
+        mempool
+            .insert_package(vec![tx_parent.clone(), tx_child.clone()])
+            .await
+            .unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    assert_eq!(mempool.stats().await.unwrap().unconfirmed_txs, 2);
+
+    let weight = mempool.stats().await.unwrap().unconfirmed_weight;
+    let retrieved_txs = mempool.retrieve(weight).await.unwrap();
+    assert_eq!(retrieved_txs.len(), 2);
+    assert!(retrieved_txs.contains(&tx_parent));
+    assert!(retrieved_txs.contains(&tx_child));
+}
+
+#[tokio::test]
+#[allow(clippy::identity_op)]
+async fn test_restore_snapshot() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) = create_new_blockchain(network).await;
+    let txs = vec![txn_schema!(
+        from: vec![outputs[0][0].clone()],
+        to: vec![21 * T, 11 * T, 11 * T]
+    )];
+    // "Mine" Block 1
+    generate_new_block(
+        &mut store,
+        &mut blocks,
+        &mut outputs,
+        txs,
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap();
+
+    let (tx_parent, tx_parent_out) = spend_utxos(
+        txn_schema!(from: vec![outputs[1][0].clone()], to: vec![15 * T, 5 * T], fee: 20*uT, lock: 0, features: OutputFeatures::default()),
+        &key_manager,
+    )
+    .await;
+    let (tx_child, _) = spend_utxos(
+        txn_schema!(from: vec![tx_parent_out[0].clone()], to: vec![7 * T, 4 * T], fee: 20*uT, lock: 0, features: OutputFeatures::default()),
+        &key_manager,
+    )
+    .await;
+    // Spends an output that was already spent in block 1, so it can no longer be accepted
+    let (tx_spent, _) = spend_utxos(
+        txn_schema!(from: vec![outputs[0][0].clone()], to: vec![5 * T], fee: 20*uT, lock: 0, features: OutputFeatures::default()),
+        &key_manager,
+    )
+    .await;
+    let (tx_published_1, _) = spend_utxos(
+        txn_schema!(from: vec![outputs[1][1].clone()], to: vec![5 * T], fee: 20*uT, lock: 0, features: OutputFeatures::default()),
+        &key_manager,
+    )
+    .await;
+    let (tx_published_2, _) = spend_utxos(
+        txn_schema!(from: vec![outputs[1][2].clone()], to: vec![5 * T], fee: 20*uT, lock: 0, features: OutputFeatures::default()),
+        &key_manager,
+    )
+    .await;
+
+    let mempool_validator = TransactionChainLinkedValidator::new(store.clone(), consensus_manager.clone());
+    let mempool = Mempool::new(
+        MempoolConfig::default(),
+        consensus_manager.clone(),
+        Box::new(mempool_validator),
+    );
+    mempool.process_published_block(blocks[1].to_arc_block()).await.unwrap();
+    mempool.insert(Arc::new(tx_parent.clone())).await.unwrap();
+    mempool.insert(Arc::new(tx_child.clone())).await.unwrap();
+    let mut snapshot = mempool.create_snapshot().await.unwrap();
+    assert_eq!(snapshot.unconfirmed_pool.len(), 2);
+
+    // Children may be restored before their parents, and transactions that became invalid are dropped
+    snapshot.unconfirmed_pool = vec![tx_child.clone(), tx_spent, tx_parent.clone()];
+    let expiry_height = MempoolConfig::default().reorg_pool.expiry_height;
+    snapshot.reorg_pool = vec![(1, vec![tx_published_1]), (expiry_height + 1, vec![tx_published_2])];
+
+    let mempool_validator = TransactionChainLinkedValidator::new(store.clone(), consensus_manager.clone());
+    let restored_mempool = Mempool::new(
+        MempoolConfig::default(),
+        consensus_manager.clone(),
+        Box::new(mempool_validator),
+    );
+    let stats = restored_mempool
+        .restore_snapshot(snapshot, expiry_height + 1)
+        .await
+        .unwrap();
+    assert_eq!(stats, SnapshotRestoreStats {
+        unconfirmed_restored: 2,
+        unconfirmed_rejected: 1,
+        reorg_restored: 1,
+        reorg_expired: 1,
+    });
+    let mempool_stats = restored_mempool.stats().await.unwrap();
+    assert_eq!(mempool_stats.unconfirmed_txs, 2);
+    assert_eq!(mempool_stats.reorg_txs, 1);
+    let restored_txs = restored_mempool.snapshot().await.unwrap();
+    assert!(restored_txs.contains(&Arc::new(tx_parent)));
+    assert!(restored_txs.contains(&Arc::new(tx_child)));
+}
+
 #[tokio::test]
 #[allow(clippy::identity_op)]
 #[allow(clippy::too_many_lines)]
@@ -934,6 +1109,135 @@ async fn test_zero_conf() {
     assert!(retrieved_txs.contains(&Arc::new(tx34)));
 }
 
+#[tokio::test]
+#[allow(clippy::identity_op)]
+async fn test_replace_by_fee() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) = create_new_blockchain(network).await;
+    let mempool_validator = TransactionChainLinkedValidator::new(store.clone(), consensus_manager.clone());
+    let mut mempool_config = MempoolConfig::default();
+    mempool_config.unconfirmed_pool.replace_by_fee = true;
+    let mempool = Mempool::new(mempool_config, consensus_manager.clone(), Box::new(mempool_validator));
+    let txs = vec![txn_schema!(
+        from: vec![outputs[0][0].clone()],
+        to: vec![21 * T, 11 * T]
+    )];
+    // "Mine" Block 1
+    generate_new_block(
+        &mut store,
+        &mut blocks,
+        &mut outputs,
+        txs,
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    mempool.process_published_block(blocks[1].to_arc_block()).await.unwrap();
+
+    let (tx_a, tx_a_out) = spend_utxos(
+        txn_schema!(
+            from: vec![outputs[1][0].clone()],
+            to: vec![15 * T, 5 * T],
+            fee: 5*uT,
+            lock: 0,
+            features: OutputFeatures::default()
+        ),
+        &key_manager,
+    )
+    .await;
+    assert_eq!(
+        mempool.insert(Arc::new(tx_a.clone())).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    // tx_b is a zero-conf descendant of tx_a
+    let (tx_b, _tx_b_out) = spend_utxos(
+        txn_schema!(
+            from: vec![tx_a_out[0].clone()],
+            to: vec![7 * T, 4 * T],
+            fee: 5*uT,
+            lock: 0,
+            features: OutputFeatures::default()
+        ),
+        &key_manager,
+    )
+    .await;
+    assert_eq!(
+        mempool.insert(Arc::new(tx_b.clone())).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    let tx_d = Arc::new(
+        spend_utxos(
+            txn_schema!(
+                from: vec![outputs[1][1].clone()],
+                to: vec![5 * T],
+                fee: 5*uT,
+                lock: 0,
+                features: OutputFeatures::default()
+            ),
+            &key_manager,
+        )
+        .await
+        .0,
+    );
+    assert_eq!(
+        mempool.insert(tx_d.clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+
+    // A conflicting transaction that does not pay a higher fee is rejected
+    let (tx_a_same_fee, _) = spend_utxos(
+        txn_schema!(
+            from: vec![outputs[1][0].clone()],
+            to: vec![15 * T, 5 * T],
+            fee: 5*uT,
+            lock: 0,
+            features: OutputFeatures::default()
+        ),
+        &key_manager,
+    )
+    .await;
+    assert_eq!(
+        mempool.insert(Arc::new(tx_a_same_fee.clone())).await.unwrap(),
+        TxStorageResponse::NotStoredReplacementFeeTooLow
+    );
+
+    // A conflicting transaction paying a higher fee replaces tx_a and its descendant tx_b
+    let (tx_a_bumped, _) = spend_utxos(
+        txn_schema!(
+            from: vec![outputs[1][0].clone()],
+            to: vec![15 * T, 5 * T],
+            fee: 50*uT,
+            lock: 0,
+            features: OutputFeatures::default()
+        ),
+        &key_manager,
+    )
+    .await;
+    assert_eq!(
+        mempool.insert(Arc::new(tx_a_bumped.clone())).await.unwrap(),
+        TxStorageResponse::UnconfirmedPoolReplacedByFee
+    );
+
+    for (tx, expected) in [
+        (&tx_a, TxStorageResponse::NotStored),
+        (&tx_b, TxStorageResponse::NotStored),
+        (&tx_a_same_fee, TxStorageResponse::NotStored),
+        (&tx_a_bumped, TxStorageResponse::UnconfirmedPool),
+        (&*tx_d, TxStorageResponse::UnconfirmedPool),
+    ] {
+        assert_eq!(
+            mempool
+                .has_tx_with_excess_sig(tx.body.kernels()[0].excess_sig.clone())
+                .await
+                .unwrap(),
+            expected
+        );
+    }
+    let stats = mempool.stats().await.unwrap();
+    assert_eq!(stats.unconfirmed_txs, 2);
+}
+
 #[tokio::test]
 #[allow(clippy::identity_op)]
 async fn test_reorg() {
@@ -1650,6 +1954,92 @@ async fn consensus_validation_versions() {
     validator.validate(&tx, Some(25.into()), None, u64::MAX).unwrap_err();
 }
 
+#[tokio::test]
+async fn consensus_validation_relative_height_locks() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager, key_manager) = create_new_blockchain(network).await;
+
+    let mempool_validator = TransactionFullValidator::new(
+        CryptoFactories::default(),
+        true,
+        store.clone(),
+        consensus_manager.clone(),
+    );
+    let mempool = Mempool::new(
+        MempoolConfig::default(),
+        consensus_manager.clone(),
+        Box::new(mempool_validator),
+    );
+
+    // Create outputs that may only be spent 3 blocks after they were mined
+    let mut schema = txn_schema!(from: vec![outputs[0][0].clone()], to: vec![2 * T, 2 * T]);
+    schema.script = script!(CheckRelativeHeightVerify(3)).unwrap();
+    let (tx, _) = spend_utxos(schema.clone(), &key_manager).await;
+
+    // The relative height opcodes are only permitted once the consensus constants allow opcode version 1
+    let v0_opcodes_only = ConsensusManager::builder(network)
+        .add_consensus_constants(
+            ConsensusConstantsBuilder::new(network)
+                .with_opcode_version_range(OpcodeVersion::V0..=OpcodeVersion::V0)
+                .build(),
+        )
+        .build()
+        .unwrap();
+    let validator = TransactionInternalConsistencyValidator::new(true, v0_opcodes_only, CryptoFactories::default());
+    let err = validator
+        .validate_with_current_tip(&tx, store.get_chain_metadata().unwrap())
+        .unwrap_err();
+    assert!(matches!(err, ValidationError::ConsensusError(_)));
+
+    generate_new_block(
+        &mut store,
+        &mut blocks,
+        &mut outputs,
+        vec![schema],
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let mined_height = store.get_height().unwrap();
+
+    let schema = txn_schema!(from: vec![outputs[1][0].clone()], to: vec![1 * T]);
+    let (tx, _) = spend_utxos(schema, &key_manager).await;
+    let tx = Arc::new(tx);
+
+    // Not yet spendable
+    for _ in 0..2 {
+        let response = mempool.insert(tx.clone()).await.unwrap();
+        assert!(matches!(response, TxStorageResponse::NotStored));
+        generate_new_block(
+            &mut store,
+            &mut blocks,
+            &mut outputs,
+            vec![],
+            &consensus_manager,
+            &key_manager,
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(store.get_height().unwrap(), mined_height + 2);
+    let response = mempool.insert(tx.clone()).await.unwrap();
+    assert!(matches!(response, TxStorageResponse::NotStored));
+
+    generate_new_block(
+        &mut store,
+        &mut blocks,
+        &mut outputs,
+        vec![],
+        &consensus_manager,
+        &key_manager,
+    )
+    .await
+    .unwrap();
+    let response = mempool.insert(tx).await.unwrap();
+    assert!(matches!(response, TxStorageResponse::UnconfirmedPool));
+}
+
 #[tokio::test]
 async fn consensus_validation_unique_excess_sig() {
     let network = Network::LocalNet;
@@ -0,0 +1,224 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Optional support for standard [BIP-39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki) mnemonic
+//! phrases, for interoperability with generic custody tools. Only the English word list is supported, since it is the
+//! only BIP-39 list whose sorted order in [mnemonic_wordlists](crate::mnemonic_wordlists) matches the standard order.
+//!
+//! A BIP-39 phrase carries no birthday and no MAC, so [CipherSeed](crate::cipher_seed::CipherSeed) words remain the
+//! default wallet backup format.
+
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use tari_utilities::Hidden;
+use zeroize::Zeroizing;
+
+use crate::{error::MnemonicError, mnemonic_wordlists::MNEMONIC_ENGLISH_WORDS, SeedWords};
+
+const BITS_PER_WORD: usize = 11;
+
+/// The supported BIP-39 phrase lengths
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bip39WordCount {
+    /// 128 bits of entropy
+    Twelve,
+    /// 256 bits of entropy
+    TwentyFour,
+}
+
+impl Bip39WordCount {
+    /// The number of words in the phrase
+    pub fn words(self) -> usize {
+        match self {
+            Bip39WordCount::Twelve => 12,
+            Bip39WordCount::TwentyFour => 24,
+        }
+    }
+
+    /// The number of entropy bytes encoded by the phrase
+    pub fn entropy_bytes(self) -> usize {
+        match self {
+            Bip39WordCount::Twelve => 16,
+            Bip39WordCount::TwentyFour => 32,
+        }
+    }
+
+    /// The number of checksum bits appended to the entropy, one for every 32 bits of entropy
+    fn checksum_bits(self) -> usize {
+        self.entropy_bytes() * 8 / 32
+    }
+}
+
+impl TryFrom<usize> for Bip39WordCount {
+    type Error = MnemonicError;
+
+    fn try_from(words: usize) -> Result<Self, Self::Error> {
+        match words {
+            12 => Ok(Bip39WordCount::Twelve),
+            24 => Ok(Bip39WordCount::TwentyFour),
+            _ => Err(MnemonicError::Bip39InvalidLength),
+        }
+    }
+}
+
+/// Generates a new BIP-39 phrase from random entropy
+pub fn generate_bip39_mnemonic(word_count: Bip39WordCount) -> SeedWords {
+    let mut entropy = Zeroizing::new(vec![0u8; word_count.entropy_bytes()]);
+    OsRng.fill_bytes(&mut entropy);
+    entropy_to_bip39_mnemonic(&entropy).expect("Entropy has a valid BIP-39 length")
+}
+
+/// Encodes 16 or 32 bytes of entropy as a 12 or 24 word BIP-39 phrase
+pub fn entropy_to_bip39_mnemonic(entropy: &[u8]) -> Result<SeedWords, MnemonicError> {
+    let word_count = match entropy.len() {
+        16 => Bip39WordCount::Twelve,
+        32 => Bip39WordCount::TwentyFour,
+        _ => return Err(MnemonicError::Bip39InvalidLength),
+    };
+
+    // The checksum fits in the first byte of the hash for both supported lengths
+    let mut bytes = Zeroizing::new(entropy.to_vec());
+    bytes.push(Sha256::digest(entropy)[0]);
+
+    let mut words = Vec::with_capacity(word_count.words());
+    for word in 0..word_count.words() {
+        let mut index = 0usize;
+        for bit in word * BITS_PER_WORD..(word + 1) * BITS_PER_WORD {
+            index = (index << 1) | usize::from(get_bit(&bytes, bit));
+        }
+        words.push(Hidden::hide(MNEMONIC_ENGLISH_WORDS[index].to_string()));
+    }
+
+    Ok(SeedWords::new(words))
+}
+
+/// Decodes a 12 or 24 word BIP-39 phrase to its entropy, validating the checksum
+pub fn bip39_mnemonic_to_entropy(mnemonic: &SeedWords) -> Result<Zeroizing<Vec<u8>>, MnemonicError> {
+    let word_count = Bip39WordCount::try_from(mnemonic.len())?;
+
+    let mut bytes = Zeroizing::new(vec![0u8; word_count.entropy_bytes() + 1]);
+    for word in 0..mnemonic.len() {
+        let index = find_bip39_index_from_word(mnemonic.get_word(word)?)?;
+        for bit in 0..BITS_PER_WORD {
+            if (index >> (BITS_PER_WORD - 1 - bit)) & 1 == 1 {
+                set_bit(&mut bytes, word * BITS_PER_WORD + bit);
+            }
+        }
+    }
+
+    let checksum = bytes.pop().unwrap_or_default();
+    let checksum_shift = 8 - word_count.checksum_bits();
+    let expected_checksum = Sha256::digest(bytes.as_slice())[0];
+    if checksum >> checksum_shift != expected_checksum >> checksum_shift {
+        return Err(MnemonicError::Bip39InvalidChecksum);
+    }
+
+    Ok(bytes)
+}
+
+/// Returns true if the words form a valid BIP-39 phrase, including its checksum
+pub fn is_bip39_mnemonic(mnemonic: &SeedWords) -> bool {
+    bip39_mnemonic_to_entropy(mnemonic).is_ok()
+}
+
+fn find_bip39_index_from_word(word: &str) -> Result<usize, MnemonicError> {
+    MNEMONIC_ENGLISH_WORDS
+        .binary_search(&word.trim().to_lowercase().as_str())
+        .map_err(|_| MnemonicError::WordNotFound(word.to_string()))
+}
+
+/// Reads the bit at `position`, counting from the most significant bit of the first byte
+fn get_bit(bytes: &[u8], position: usize) -> bool {
+    (bytes[position / 8] >> (7 - position % 8)) & 1 == 1
+}
+
+fn set_bit(bytes: &mut [u8], position: usize) {
+    bytes[position / 8] |= 1 << (7 - position % 8);
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use tari_utilities::hex::from_hex;
+
+    use super::*;
+
+    // Test vectors from https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+    const VECTORS: [(&str, &str); 6] = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+        ),
+        (
+            "8080808080808080808080808080808080808080808080808080808080808080",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic \
+             avoid letter advice cage absurd amount doctor acoustic bless",
+        ),
+        (
+            "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+            "hamster diagram private dutch cause delay private meat slide toddler razor book happy fancy gospel \
+             tennis maple dilemma loan word shrug inflict delay length",
+        ),
+    ];
+
+    #[test]
+    fn it_matches_the_reference_vectors() {
+        for (entropy, phrase) in VECTORS {
+            let entropy = from_hex(entropy).unwrap();
+            let words = SeedWords::from_str(phrase).unwrap();
+            assert_eq!(entropy_to_bip39_mnemonic(&entropy).unwrap(), words);
+            assert_eq!(*bip39_mnemonic_to_entropy(&words).unwrap(), entropy);
+        }
+    }
+
+    #[test]
+    fn it_rejects_bad_checksums_and_lengths() {
+        let words = SeedWords::from_str(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+        )
+        .unwrap();
+        assert_eq!(
+            bip39_mnemonic_to_entropy(&words).unwrap_err(),
+            MnemonicError::Bip39InvalidChecksum
+        );
+
+        let words = SeedWords::from_str("abandon abandon abandon").unwrap();
+        assert_eq!(
+            bip39_mnemonic_to_entropy(&words).unwrap_err(),
+            MnemonicError::Bip39InvalidLength
+        );
+
+        let words = SeedWords::from_str(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon tari",
+        )
+        .unwrap();
+        assert!(matches!(
+            bip39_mnemonic_to_entropy(&words).unwrap_err(),
+            MnemonicError::WordNotFound(_)
+        ));
+        assert!(entropy_to_bip39_mnemonic(&[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn it_generates_valid_phrases() {
+        for word_count in [Bip39WordCount::Twelve, Bip39WordCount::TwentyFour] {
+            let words = generate_bip39_mnemonic(word_count);
+            assert_eq!(words.len(), word_count.words());
+            assert!(is_bip39_mnemonic(&words));
+        }
+    }
+}
@@ -39,12 +39,15 @@ use tari_utilities::{hidden::Hidden, safe_array::SafeArray, SafePassword};
 use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 use crate::{
+    bip39::{bip39_mnemonic_to_entropy, entropy_to_bip39_mnemonic},
     error::KeyManagerError,
     mnemonic::{from_bytes, to_bytes, to_bytes_with_language, Mnemonic, MnemonicLanguage},
+    seed_share::{combine_shares, split_secret, SeedShare, SEED_SHARE_SECRET_BYTES},
     CipherSeedEncryptionKey,
     CipherSeedMacKey,
     KeyManagerDomain,
     SeedWords,
+    HASHER_LABEL_BIP39_ENTROPY,
     HASHER_LABEL_CIPHER_SEED_ENCRYPTION_NONCE,
     HASHER_LABEL_CIPHER_SEED_MAC,
     HASHER_LABEL_CIPHER_SEED_PBKDF_SALT,
@@ -170,6 +173,82 @@ impl CipherSeed {
         }
     }
 
+    /// Restore a seed from a 12 or 24 word BIP-39 phrase. The entropy of a 12 word phrase is used as is, so the phrase
+    /// can be exported again with [to_bip39_mnemonic](Self::to_bip39_mnemonic); the entropy of a 24 word phrase is
+    /// hashed down to the seed entropy length. A BIP-39 phrase carries no birthday, so the seed is given the genesis
+    /// birthday and a wallet recovered from it scans the whole chain.
+    pub fn from_bip39_mnemonic(mnemonic: &SeedWords) -> Result<Self, KeyManagerError> {
+        let bip39_entropy = bip39_mnemonic_to_entropy(mnemonic)?;
+        let mut entropy = Box::new([0u8; CIPHER_SEED_ENTROPY_BYTES]);
+        if bip39_entropy.len() == CIPHER_SEED_ENTROPY_BYTES {
+            entropy.copy_from_slice(&bip39_entropy);
+        } else {
+            let hash =
+                DomainSeparatedHasher::<Blake2b<U32>, KeyManagerDomain>::new_with_label(HASHER_LABEL_BIP39_ENTROPY)
+                    .chain(bip39_entropy.as_slice())
+                    .finalize();
+            entropy.copy_from_slice(&hash.as_ref()[..CIPHER_SEED_ENTROPY_BYTES]);
+        }
+        let mut salt = [0u8; CIPHER_SEED_MAIN_SALT_BYTES];
+        OsRng.fill_bytes(salt.as_mut());
+
+        Ok(Self {
+            version: CIPHER_SEED_VERSION,
+            birthday: 0,
+            entropy,
+            salt,
+        })
+    }
+
+    /// Export the seed entropy as a 12 word BIP-39 phrase. Restoring from the phrase yields the same wallet keys, but
+    /// not the birthday.
+    pub fn to_bip39_mnemonic(&self) -> Result<SeedWords, KeyManagerError> {
+        Ok(entropy_to_bip39_mnemonic(self.entropy.as_ref())?)
+    }
+
+    /// Restore a seed from its own mnemonic words, or from a BIP-39 phrase if the caller explicitly asked for one. The
+    /// passphrase only applies to cipher seed words, so it is rejected for a BIP-39 phrase rather than ignored.
+    pub fn from_mnemonic_words(
+        mnemonic: &SeedWords,
+        passphrase: Option<SafePassword>,
+        bip39: bool,
+    ) -> Result<Self, KeyManagerError> {
+        if !bip39 {
+            return Self::from_mnemonic(mnemonic, passphrase);
+        }
+        if passphrase.is_some() {
+            return Err(KeyManagerError::Bip39PassphraseNotSupported);
+        }
+        Self::from_bip39_mnemonic(mnemonic)
+    }
+
+    /// Split the seed birthday and entropy into `count` shares, any `threshold` of which restore the seed with
+    /// [from_shares](Self::from_shares)
+    pub fn to_shares(&self, threshold: u8, count: u8) -> Result<Vec<SeedShare>, KeyManagerError> {
+        let mut secret = Zeroizing::new(Vec::with_capacity(SEED_SHARE_SECRET_BYTES));
+        secret.extend(self.birthday.to_le_bytes());
+        secret.extend(self.entropy.iter());
+        split_secret(&secret, threshold, count)
+    }
+
+    /// Restore a seed from at least the threshold number of shares made by [to_shares](Self::to_shares)
+    pub fn from_shares(shares: &[SeedShare]) -> Result<Self, KeyManagerError> {
+        let secret = combine_shares(shares)?;
+        let mut birthday_bytes = [0u8; CIPHER_SEED_BIRTHDAY_BYTES];
+        birthday_bytes.copy_from_slice(&secret[..CIPHER_SEED_BIRTHDAY_BYTES]);
+        let mut entropy = Box::new([0u8; CIPHER_SEED_ENTROPY_BYTES]);
+        entropy.copy_from_slice(&secret[CIPHER_SEED_BIRTHDAY_BYTES..]);
+        let mut salt = [0u8; CIPHER_SEED_MAIN_SALT_BYTES];
+        OsRng.fill_bytes(salt.as_mut());
+
+        Ok(Self {
+            version: CIPHER_SEED_VERSION,
+            birthday: u16::from_le_bytes(birthday_bytes),
+            entropy,
+            salt,
+        })
+    }
+
     /// Generate an encrypted seed from a passphrase
     pub fn encipher(&self, passphrase: Option<SafePassword>) -> Result<Vec<u8>, KeyManagerError> {
         // Derive encryption and MAC keys from passphrase and main salt
@@ -452,6 +531,7 @@ mod test {
 
     use super::BIRTHDAY_GENESIS_FROM_UNIX_EPOCH;
     use crate::{
+        bip39::{generate_bip39_mnemonic, Bip39WordCount},
         cipher_seed::{
             CipherSeed,
             CIPHER_SEED_BIRTHDAY_BYTES,
@@ -678,4 +758,60 @@ mod test {
         // to 3th July 2022 00:00:00
         assert_eq!(birthday_from_unix_epoch, 1656806400);
     }
+
+    #[test]
+    fn cipher_seed_round_trips_through_bip39() {
+        let seed = CipherSeed::new();
+        let words = seed.to_bip39_mnemonic().unwrap();
+        assert_eq!(words.len(), 12);
+
+        let restored = CipherSeed::from_bip39_mnemonic(&words).unwrap();
+        assert_eq!(restored.entropy(), seed.entropy());
+        assert_eq!(restored.birthday(), 0);
+
+        // A 24 word phrase is compressed deterministically
+        let words = generate_bip39_mnemonic(Bip39WordCount::TwentyFour);
+        let first = CipherSeed::from_bip39_mnemonic(&words).unwrap();
+        let second = CipherSeed::from_bip39_mnemonic(&words).unwrap();
+        assert_eq!(first.entropy(), second.entropy());
+    }
+
+    #[test]
+    fn bip39_phrases_are_only_restored_when_requested() {
+        let seed = CipherSeed::new();
+        let cipher_words = seed.to_mnemonic(MnemonicLanguage::English, None).unwrap();
+        assert_eq!(
+            CipherSeed::from_mnemonic_words(&cipher_words, None, false).unwrap(),
+            seed
+        );
+        assert!(CipherSeed::from_mnemonic_words(&cipher_words, None, true).is_err());
+
+        let bip39_words = seed.to_bip39_mnemonic().unwrap();
+        assert_eq!(
+            CipherSeed::from_mnemonic_words(&bip39_words, None, true)
+                .unwrap()
+                .entropy(),
+            seed.entropy()
+        );
+        // A BIP-39 phrase is not reinterpreted when cipher seed words were expected
+        assert!(CipherSeed::from_mnemonic_words(&bip39_words, None, false).is_err());
+        assert!(matches!(
+            CipherSeed::from_mnemonic_words(&bip39_words, Some(SafePassword::from("passphrase")), true),
+            Err(KeyManagerError::Bip39PassphraseNotSupported)
+        ));
+    }
+
+    #[test]
+    fn cipher_seed_round_trips_through_shares() {
+        let seed = CipherSeed::new();
+        let shares = seed.to_shares(2, 3).unwrap();
+        let restored = CipherSeed::from_shares(&shares[1..]).unwrap();
+        assert_eq!(restored.entropy(), seed.entropy());
+        assert_eq!(restored.birthday(), seed.birthday());
+
+        assert!(matches!(
+            CipherSeed::from_shares(&shares[..1]),
+            Err(KeyManagerError::InsufficientShares(1, 2))
+        ));
+    }
 }
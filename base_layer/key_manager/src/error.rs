@@ -47,6 +47,12 @@ pub enum KeyManagerError {
     SliceError(String),
     #[error("Key ID not valid")]
     InvalidKeyID,
+    #[error("Invalid seed share: {0}")]
+    InvalidShare(String),
+    #[error("Not enough seed shares: {0} provided but {1} required")]
+    InsufficientShares(usize, u8),
+    #[error("A seed passphrase cannot be used with a BIP-39 phrase")]
+    Bip39PassphraseNotSupported,
 }
 
 impl From<ByteArrayError> for KeyManagerError {
@@ -80,6 +86,10 @@ pub enum MnemonicError {
     BitsToIntConversion,
     #[error("Integer to bits conversion error")]
     IntToBitsConversion,
+    #[error("A BIP-39 mnemonic sequence must have 12 or 24 words")]
+    Bip39InvalidLength,
+    #[error("The BIP-39 mnemonic sequence checksum is invalid")]
+    Bip39InvalidChecksum,
 }
 
 impl From<ByteArrayError> for MnemonicError {
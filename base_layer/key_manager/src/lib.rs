@@ -13,6 +13,7 @@ use crate::{
     error::MnemonicError,
 };
 
+pub mod bip39;
 pub mod cipher_seed;
 pub mod diacritics;
 pub mod error;
@@ -23,6 +24,7 @@ pub mod mnemonic;
 pub mod mnemonic_wordlists;
 #[cfg(feature = "key_manager_service")]
 pub mod schema;
+pub mod seed_share;
 
 hash_domain!(KeyManagerDomain, "com.tari.base_layer.key_manager", 1);
 
@@ -30,6 +32,8 @@ const HASHER_LABEL_CIPHER_SEED_PBKDF_SALT: &str = "cipher_seed_pbkdf_salt";
 const HASHER_LABEL_CIPHER_SEED_ENCRYPTION_NONCE: &str = "cipher_seed_encryption_nonce";
 const HASHER_LABEL_CIPHER_SEED_MAC: &str = "cipher_seed_mac";
 const HASHER_LABEL_DERIVE_KEY: &str = "derive_key";
+const HASHER_LABEL_BIP39_ENTROPY: &str = "bip39_entropy";
+const HASHER_LABEL_SEED_SHARE_CHECKSUM: &str = "seed_share_checksum";
 
 hidden_type!(CipherSeedEncryptionKey, SafeArray<u8, CIPHER_SEED_ENCRYPTION_KEY_BYTES>);
 hidden_type!(CipherSeedMacKey, SafeArray< u8, CIPHER_SEED_MAC_KEY_BYTES>);
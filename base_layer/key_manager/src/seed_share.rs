@@ -0,0 +1,326 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! A SLIP-39 style k-of-n Shamir secret sharing of a seed, so that a wallet backup does not depend on a single copy
+//! of its seed words. Each share is encoded as 20 words from the existing mnemonic word lists, which keeps shares
+//! distinguishable from 24 word cipher seeds.
+//!
+//! The secret is split byte-wise with polynomials over GF(256), using the same field as AES and SLIP-39. Every share
+//! records the identifier of its split and the threshold, and carries a checksum to catch mistyped words, but any
+//! `threshold - 1` shares reveal nothing about the secret.
+
+use std::collections::HashSet;
+
+use blake2::Blake2b;
+use digest::consts::U32;
+use rand::{rngs::OsRng, RngCore};
+use tari_crypto::hashing::DomainSeparatedHasher;
+use zeroize::Zeroizing;
+
+use crate::{
+    cipher_seed::{CIPHER_SEED_BIRTHDAY_BYTES, CIPHER_SEED_ENTROPY_BYTES},
+    error::KeyManagerError,
+    mnemonic::{from_bytes, to_bytes, MnemonicLanguage},
+    KeyManagerDomain,
+    SeedWords,
+    HASHER_LABEL_SEED_SHARE_CHECKSUM,
+};
+
+const SEED_SHARE_VERSION: u8 = 1u8;
+
+pub const SEED_SHARE_IDENTIFIER_BYTES: usize = 2;
+pub const SEED_SHARE_CHECKSUM_BYTES: usize = 4;
+/// The length of the secret being shared: the cipher seed birthday and entropy
+pub const SEED_SHARE_SECRET_BYTES: usize = CIPHER_SEED_BIRTHDAY_BYTES + CIPHER_SEED_ENTROPY_BYTES;
+const SEED_SHARE_BYTES: usize =
+    1 + SEED_SHARE_IDENTIFIER_BYTES + 1 + 1 + SEED_SHARE_SECRET_BYTES + SEED_SHARE_CHECKSUM_BYTES;
+
+/// One share of a seed split with [split_secret]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeedShare {
+    identifier: [u8; SEED_SHARE_IDENTIFIER_BYTES],
+    threshold: u8,
+    index: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl SeedShare {
+    /// The random identifier shared by all shares of one split
+    pub fn identifier(&self) -> u16 {
+        u16::from_le_bytes(self.identifier)
+    }
+
+    /// The number of shares needed to recover the secret
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// The 1-based index of this share
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Encode the share as mnemonic words in the given language
+    pub fn to_mnemonic(&self, language: MnemonicLanguage) -> Result<SeedWords, KeyManagerError> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(SEED_SHARE_BYTES));
+        bytes.push(SEED_SHARE_VERSION);
+        bytes.extend(self.identifier);
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.extend(self.value.iter());
+        let checksum = Self::checksum(&bytes);
+        bytes.extend(checksum);
+
+        Ok(from_bytes(&bytes, language)?)
+    }
+
+    /// Decode a share from mnemonic words, the language is autodetected
+    pub fn from_mnemonic(mnemonic: &SeedWords) -> Result<Self, KeyManagerError> {
+        let bytes = to_bytes(mnemonic)?;
+        let bytes = bytes.reveal();
+        if bytes.len() != SEED_SHARE_BYTES {
+            return Err(KeyManagerError::InvalidShare(
+                "The words do not have the length of a seed share".to_string(),
+            ));
+        }
+        let (data, checksum) = bytes.split_at(SEED_SHARE_BYTES - SEED_SHARE_CHECKSUM_BYTES);
+        if checksum != Self::checksum(data) {
+            return Err(KeyManagerError::InvalidShare("Checksum validation failed".to_string()));
+        }
+        if data[0] != SEED_SHARE_VERSION {
+            return Err(KeyManagerError::VersionMismatch);
+        }
+
+        let mut identifier = [0u8; SEED_SHARE_IDENTIFIER_BYTES];
+        identifier.copy_from_slice(&data[1..=SEED_SHARE_IDENTIFIER_BYTES]);
+        let threshold = data[1 + SEED_SHARE_IDENTIFIER_BYTES];
+        let index = data[2 + SEED_SHARE_IDENTIFIER_BYTES];
+        if threshold == 0 || index == 0 {
+            return Err(KeyManagerError::InvalidShare(
+                "The threshold and index must be at least one".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            identifier,
+            threshold,
+            index,
+            value: Zeroizing::new(data[3 + SEED_SHARE_IDENTIFIER_BYTES..].to_vec()),
+        })
+    }
+
+    fn checksum(data: &[u8]) -> [u8; SEED_SHARE_CHECKSUM_BYTES] {
+        let hash =
+            DomainSeparatedHasher::<Blake2b<U32>, KeyManagerDomain>::new_with_label(HASHER_LABEL_SEED_SHARE_CHECKSUM)
+                .chain(data)
+                .finalize();
+        let mut checksum = [0u8; SEED_SHARE_CHECKSUM_BYTES];
+        checksum.copy_from_slice(&hash.as_ref()[..SEED_SHARE_CHECKSUM_BYTES]);
+        checksum
+    }
+}
+
+/// Split a secret into `count` shares, any `threshold` of which recover it
+pub fn split_secret(secret: &[u8], threshold: u8, count: u8) -> Result<Vec<SeedShare>, KeyManagerError> {
+    if secret.len() != SEED_SHARE_SECRET_BYTES {
+        return Err(KeyManagerError::InvalidData);
+    }
+    if threshold == 0 || threshold > count {
+        return Err(KeyManagerError::InvalidShare(format!(
+            "A threshold of {} is not possible with {} shares",
+            threshold, count
+        )));
+    }
+
+    let mut identifier = [0u8; SEED_SHARE_IDENTIFIER_BYTES];
+    OsRng.fill_bytes(&mut identifier);
+
+    // One random polynomial per secret byte, with the secret byte as the constant term
+    let mut coefficients = Zeroizing::new(vec![0u8; SEED_SHARE_SECRET_BYTES * usize::from(threshold - 1)]);
+    OsRng.fill_bytes(&mut coefficients);
+
+    Ok((1..=count)
+        .map(|index| {
+            let value = secret
+                .iter()
+                .enumerate()
+                .map(|(byte, secret_byte)| {
+                    let polynomial = coefficients.chunks(SEED_SHARE_SECRET_BYTES).map(|c| c[byte]);
+                    evaluate(*secret_byte, polynomial, index)
+                })
+                .collect::<Vec<_>>();
+            SeedShare {
+                identifier,
+                threshold,
+                index,
+                value: Zeroizing::new(value),
+            }
+        })
+        .collect())
+}
+
+/// Recover a secret from at least the threshold number of shares of the same split
+pub fn combine_shares(shares: &[SeedShare]) -> Result<Zeroizing<Vec<u8>>, KeyManagerError> {
+    let first = shares.first().ok_or(KeyManagerError::InsufficientShares(0, 1))?;
+    if shares
+        .iter()
+        .any(|s| s.identifier != first.identifier || s.threshold != first.threshold)
+    {
+        return Err(KeyManagerError::InvalidShare(
+            "The shares do not all belong to the same backup".to_string(),
+        ));
+    }
+    let mut indexes = HashSet::new();
+    let shares = shares
+        .iter()
+        .filter(|s| indexes.insert(s.index))
+        .take(usize::from(first.threshold))
+        .collect::<Vec<_>>();
+    if shares.len() < usize::from(first.threshold) {
+        return Err(KeyManagerError::InsufficientShares(shares.len(), first.threshold));
+    }
+
+    // Lagrange interpolation at zero
+    let mut secret = Zeroizing::new(vec![0u8; SEED_SHARE_SECRET_BYTES]);
+    for share in &shares {
+        let mut numerator = 1u8;
+        let mut denominator = 1u8;
+        for other in &shares {
+            if other.index != share.index {
+                numerator = gf_mul(numerator, other.index);
+                denominator = gf_mul(denominator, other.index ^ share.index);
+            }
+        }
+        let basis = gf_mul(numerator, gf_inverse(denominator));
+        for (secret_byte, share_byte) in secret.iter_mut().zip(share.value.iter()) {
+            *secret_byte ^= gf_mul(basis, *share_byte);
+        }
+    }
+
+    Ok(secret)
+}
+
+/// Evaluate the polynomial with the given constant term and higher coefficients at `x`, using Horner's method
+fn evaluate<I: DoubleEndedIterator<Item = u8>>(constant: u8, coefficients: I, x: u8) -> u8 {
+    let higher = coefficients.rev().fold(0u8, |acc, c| gf_mul(acc, x) ^ c);
+    gf_mul(higher, x) ^ constant
+}
+
+/// Multiplication in GF(256) modulo the AES polynomial x^8 + x^4 + x^3 + x + 1, without secret dependent branches
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = a >> 7;
+        a = (a << 1) ^ (0x1b & 0u8.wrapping_sub(carry));
+        b >>= 1;
+    }
+    product
+}
+
+/// The multiplicative inverse in GF(256), computed as a^254
+fn gf_inverse(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exponent >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_secret() -> Vec<u8> {
+        let mut secret = vec![0u8; SEED_SHARE_SECRET_BYTES];
+        OsRng.fill_bytes(&mut secret);
+        secret
+    }
+
+    #[test]
+    fn field_arithmetic_is_consistent() {
+        // 0x53 * 0xca = 0x01 in the AES field
+        assert_eq!(gf_mul(0x53, 0xca), 0x01);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inverse(a)), 1);
+        }
+    }
+
+    #[test]
+    fn any_threshold_subset_recovers_the_secret() {
+        let secret = random_secret();
+        let shares = split_secret(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                    assert_eq!(*combine_shares(&subset).unwrap(), secret);
+                }
+            }
+        }
+        assert_eq!(*combine_shares(&shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn too_few_or_mixed_shares_are_rejected() {
+        let secret = random_secret();
+        let shares = split_secret(&secret, 3, 5).unwrap();
+        assert_eq!(
+            combine_shares(&shares[..2]).unwrap_err(),
+            KeyManagerError::InsufficientShares(2, 3)
+        );
+        // Duplicates do not count towards the threshold
+        let duplicated = [shares[0].clone(), shares[1].clone(), shares[1].clone()];
+        assert_eq!(
+            combine_shares(&duplicated).unwrap_err(),
+            KeyManagerError::InsufficientShares(2, 3)
+        );
+
+        let other = split_secret(&secret, 3, 5).unwrap();
+        let mut mixed = vec![shares[0].clone(), shares[1].clone(), other[2].clone()];
+        // The identifiers are random, so make sure they differ
+        mixed[2].identifier = [!shares[0].identifier[0], shares[0].identifier[1]];
+        assert!(matches!(combine_shares(&mixed), Err(KeyManagerError::InvalidShare(_))));
+
+        assert!(split_secret(&secret, 4, 3).is_err());
+        assert!(split_secret(&secret, 0, 3).is_err());
+    }
+
+    #[test]
+    fn a_threshold_of_one_copies_the_secret() {
+        let secret = random_secret();
+        let shares = split_secret(&secret, 1, 2).unwrap();
+        assert_eq!(*shares[0].value, secret);
+        assert_eq!(*combine_shares(&shares[1..]).unwrap(), secret);
+    }
+
+    #[test]
+    fn shares_round_trip_through_mnemonics() {
+        let secret = random_secret();
+        let shares = split_secret(&secret, 2, 3).unwrap();
+        for share in &shares {
+            let words = share.to_mnemonic(MnemonicLanguage::Spanish).unwrap();
+            assert_eq!(words.len(), 20);
+            assert_eq!(&SeedShare::from_mnemonic(&words).unwrap(), share);
+        }
+
+        let words = shares[0].to_mnemonic(MnemonicLanguage::English).unwrap();
+        let mut mistyped = SeedWords::new(vec![]);
+        mistyped.push(words.get_word(1).unwrap().clone());
+        mistyped.push(words.get_word(0).unwrap().clone());
+        for i in 2..words.len() {
+            mistyped.push(words.get_word(i).unwrap().clone());
+        }
+        if mistyped != words {
+            assert!(SeedShare::from_mnemonic(&mistyped).is_err());
+        }
+    }
+}
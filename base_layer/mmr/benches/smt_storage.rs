@@ -0,0 +1,256 @@
+// Copyright 2024. The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Compares keeping the output SMT in memory and persisting it wholesale, with keeping it node-by-node in LMDB.
+//!
+//! Each benchmark commits a block that spends and creates outputs, and then rewinds it again as a reorg would, so the
+//! tree is the same at the start of every iteration. The resident set size of each approach is printed before the
+//! benchmarks run (Linux only).
+
+use std::{fs, path::PathBuf};
+
+use blake2::Blake2b;
+use criterion::{criterion_group, Criterion};
+use digest::consts::U32;
+use tari_mmr::sparse_merkle_tree::{
+    DeleteResult,
+    NodeKey,
+    NodePosition,
+    PersistentSparseMerkleTree,
+    SMTError,
+    SmtNodeStore,
+    SparseMerkleTree,
+    StoredNode,
+    ValueHash,
+};
+use tari_storage::lmdb_store::{db, LMDBBuilder, LMDBConfig, LMDBDatabase, LMDBStore, LMDBWriteTransaction};
+
+type Hasher = Blake2b<U32>;
+type MemorySmt = SparseMerkleTree<Hasher>;
+
+const SIZES: [usize; 2] = [10_000, 100_000];
+// The number of outputs spent and created by each block
+const BLOCK_CHANGES: usize = 100;
+const DB_NAME: &str = "smt_nodes";
+
+struct LmdbNodeStore<'a, 'txn, 'db> {
+    txn: &'a mut LMDBWriteTransaction<'txn, 'db>,
+}
+
+impl SmtNodeStore for LmdbNodeStore<'_, '_, '_> {
+    fn get_node(&self, position: &NodePosition) -> Result<Option<StoredNode>, SMTError> {
+        self.txn
+            .get(&position.to_bytes()[..])
+            .map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+
+    fn put_node(&mut self, position: NodePosition, node: StoredNode) -> Result<(), SMTError> {
+        self.txn
+            .insert(&position.to_bytes()[..], &node)
+            .map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+
+    fn delete_node(&mut self, position: &NodePosition) -> Result<(), SMTError> {
+        self.txn
+            .delete(&position.to_bytes()[..])
+            .map_err(|e| SMTError::StoreError(e.to_string()))
+    }
+}
+
+struct LmdbSmt {
+    path: PathBuf,
+    // Keeps the environment open for as long as the database handle is in use
+    _store: LMDBStore,
+    db: LMDBDatabase,
+}
+
+impl LmdbSmt {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("tari_mmr_{}_{}", name, rand::random::<u64>()));
+        fs::create_dir_all(&path).unwrap();
+        let store = LMDBBuilder::new()
+            .set_path(&path)
+            .set_env_config(LMDBConfig::new_from_mb(1024, 128, 64))
+            .set_max_number_of_databases(1)
+            .add_database(DB_NAME, db::CREATE)
+            .build()
+            .unwrap();
+        let db = store.get_handle(DB_NAME).unwrap();
+        Self {
+            path,
+            _store: store,
+            db,
+        }
+    }
+
+    // Applies `f` to the tree in a single write transaction, i.e. one block commit
+    fn commit<F>(&self, f: F)
+    where F: FnOnce(&mut PersistentSparseMerkleTree<Hasher, LmdbNodeStore<'_, '_, '_>>) {
+        self.db
+            .with_write_transaction(|mut txn| {
+                let mut tree = PersistentSparseMerkleTree::new(LmdbNodeStore { txn: &mut txn });
+                f(&mut tree);
+                Ok(())
+            })
+            .unwrap();
+    }
+}
+
+impl Drop for LmdbSmt {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn random_key() -> NodeKey {
+    NodeKey::from(rand::random::<[u8; 32]>())
+}
+
+fn random_value() -> ValueHash {
+    ValueHash::from(rand::random::<[u8; 32]>())
+}
+
+fn build_memory_smt(keys: &[NodeKey]) -> MemorySmt {
+    let mut smt = MemorySmt::new();
+    for key in keys {
+        smt.insert(key.clone(), random_value()).unwrap();
+    }
+    let _ = smt.hash();
+    smt
+}
+
+fn build_lmdb_smt(name: &str, keys: &[NodeKey]) -> LmdbSmt {
+    let smt = LmdbSmt::new(name);
+    smt.commit(|tree| {
+        for key in keys {
+            tree.insert(key.clone(), random_value()).unwrap();
+        }
+    });
+    smt
+}
+
+// Spends the first `BLOCK_CHANGES` keys and creates as many new ones, returning what is needed to rewind the block
+fn block_changes(keys: &[NodeKey]) -> (Vec<NodeKey>, Vec<NodeKey>) {
+    let spent = keys[..BLOCK_CHANGES].to_vec();
+    let created = (0..BLOCK_CHANGES).map(|_| random_key()).collect();
+    (spent, created)
+}
+
+// Commit a block to an in-memory tree, then serialise the whole tree as a backend that stores it wholesale would
+pub fn memory_commit(c: &mut Criterion) {
+    for size in SIZES {
+        let keys = (0..size).map(|_| random_key()).collect::<Vec<_>>();
+        let mut smt = build_memory_smt(&keys);
+        c.bench_function(
+            &format!("SMT storage: In-memory commit and rewind on {size}-key tree"),
+            |b| {
+                b.iter(|| {
+                    let (spent, created) = block_changes(&keys);
+                    let mut spent_values = Vec::with_capacity(spent.len());
+                    for key in &spent {
+                        if let DeleteResult::Deleted(value) = smt.delete(key).unwrap() {
+                            spent_values.push(value);
+                        }
+                    }
+                    for key in &created {
+                        smt.insert(key.clone(), random_value()).unwrap();
+                    }
+                    let _ = smt.hash();
+                    bincode::serialize(&smt).unwrap();
+
+                    for key in &created {
+                        smt.delete(key).unwrap();
+                    }
+                    for (key, value) in spent.into_iter().zip(spent_values) {
+                        smt.insert(key, value).unwrap();
+                    }
+                    let _ = smt.hash();
+                    bincode::serialize(&smt).unwrap();
+                });
+            },
+        );
+    }
+}
+
+// Commit the same block to a tree stored node-by-node in LMDB, updating only the affected nodes
+pub fn lmdb_commit(c: &mut Criterion) {
+    for size in SIZES {
+        let keys = (0..size).map(|_| random_key()).collect::<Vec<_>>();
+        let smt = build_lmdb_smt("commit", &keys);
+        c.bench_function(
+            &format!("SMT storage: LMDB commit and rewind on {size}-key tree"),
+            |b| {
+                b.iter(|| {
+                    let (spent, created) = block_changes(&keys);
+                    let mut spent_values = Vec::with_capacity(spent.len());
+                    smt.commit(|tree| {
+                        for key in &spent {
+                            if let DeleteResult::Deleted(value) = tree.delete(key).unwrap() {
+                                spent_values.push(value);
+                            }
+                        }
+                        for key in &created {
+                            tree.insert(key.clone(), random_value()).unwrap();
+                        }
+                        tree.hash().unwrap();
+                    });
+
+                    smt.commit(|tree| {
+                        for key in &created {
+                            tree.delete(key).unwrap();
+                        }
+                        for (key, value) in spent.into_iter().zip(spent_values) {
+                            tree.insert(key, value).unwrap();
+                        }
+                        tree.hash().unwrap();
+                    });
+                });
+            },
+        );
+    }
+}
+
+// Reads the resident set size of this process from procfs, in KiB
+fn resident_set_size_kib() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmRSS:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kib| kib.parse().ok())
+}
+
+fn report_resident_memory() {
+    let size = SIZES[SIZES.len() - 1];
+    let keys = (0..size).map(|_| random_key()).collect::<Vec<_>>();
+    let baseline = match resident_set_size_kib() {
+        Some(rss) => rss,
+        None => {
+            println!("SMT storage: resident set size is not available on this platform");
+            return;
+        },
+    };
+
+    let smt = build_lmdb_smt("rss", &keys);
+    let lmdb_rss = resident_set_size_kib().unwrap_or(baseline);
+    drop(smt);
+
+    let baseline_after_lmdb = resident_set_size_kib().unwrap_or(baseline);
+    let smt = build_memory_smt(&keys);
+    let memory_rss = resident_set_size_kib().unwrap_or(baseline_after_lmdb);
+    drop(smt);
+
+    println!(
+        "SMT storage: resident set size growth for a {size}-key tree: in-memory {} KiB, LMDB {} KiB",
+        memory_rss.saturating_sub(baseline_after_lmdb),
+        lmdb_rss.saturating_sub(baseline)
+    );
+}
+
+criterion_group!(smt_storage, memory_commit, lmdb_commit);
+
+fn main() {
+    report_resident_memory();
+    smt_storage();
+    Criterion::default().configure_from_args().final_summary();
+}
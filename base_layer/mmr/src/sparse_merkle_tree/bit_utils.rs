@@ -14,6 +14,12 @@ pub(crate) fn get_bit(data: &[u8], position: usize) -> usize {
     0
 }
 
+/// Sets the bit at an offset from the most significant bit. Does NOT perform range checking
+#[inline]
+pub(crate) fn set_bit(data: &mut [u8], position: usize) {
+    data[position / 8] |= 1 << (8 - 1 - (position % 8));
+}
+
 /// Given two node keys, this function returns the number of bits that are common to both keys, starting from the most
 /// significant bit. This function is used to tell you the height at which two node keys would diverge in the sparse
 /// Merkle& tree. For example, key 0110 and 0101 would diverge at height 2, because the first two bits are the same.
@@ -35,4 +35,6 @@ pub enum SMTError {
     NonViableProof,
     #[error("A duplicate key was found when trying to inserting")]
     KeyExists,
+    #[error("The node store failed: {0}")]
+    StoreError(String),
 }
@@ -79,10 +79,19 @@
 mod bit_utils;
 mod error;
 mod node;
+mod persistent;
 mod proofs;
 mod tree;
 
 pub use error::SMTError;
 pub use node::{BranchNode, EmptyNode, LeafNode, Node, NodeHash, NodeKey, ValueHash, EMPTY_NODE_HASH};
+pub use persistent::{
+    NodePosition,
+    OverlayNodeStore,
+    PersistentSparseMerkleTree,
+    SmtNodeStore,
+    StoredNode,
+    NODE_POSITION_LENGTH,
+};
 pub use proofs::{ExclusionProof, InclusionProof};
 pub use tree::{DeleteResult, SparseMerkleTree, UpdateResult};
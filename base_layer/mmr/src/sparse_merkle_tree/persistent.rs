@@ -0,0 +1,646 @@
+// Copyright 2024. The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! A sparse Merkle tree that keeps its nodes in a [`SmtNodeStore`] instead of in memory.
+//!
+//! Every non-empty node is stored individually under its [`NodePosition`], i.e. its height and the first `height` bits
+//! of the keys below it. Updates only touch the nodes on the path from the root to the affected leaf, so a backend
+//! such as LMDB can apply them incrementally in the same transaction as the rest of a block. The tree has exactly the
+//! same shape, and therefore the same root hash, as [`SparseMerkleTree`](crate::sparse_merkle_tree::SparseMerkleTree)
+//! for the same set of keys, irrespective of the order in which they were added or removed. Rewinding a set of changes
+//! is thus just a matter of applying the inverse operations.
+
+use std::{collections::HashMap, convert::TryFrom, marker::PhantomData};
+
+use digest::{consts::U32, Digest};
+use serde::{Deserialize, Serialize};
+
+use crate::sparse_merkle_tree::{
+    bit_utils::{count_common_prefix, get_bit, height_key, set_bit, TraverseDirection},
+    node::KEY_LENGTH,
+    BranchNode,
+    DeleteResult,
+    ExclusionProof,
+    InclusionProof,
+    LeafNode,
+    NodeHash,
+    NodeKey,
+    SMTError,
+    UpdateResult,
+    ValueHash,
+    EMPTY_NODE_HASH,
+};
+
+/// The maximum height of a node. Leaves of two keys that only differ in the last bit sit at this height.
+const MAX_HEIGHT: usize = KEY_LENGTH * 8;
+
+/// The length of the byte representation of a [`NodePosition`]
+pub const NODE_POSITION_LENGTH: usize = KEY_LENGTH + 2;
+
+/// The location of a node in the tree
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodePosition {
+    height: usize,
+    // Only the first `height` bits are set
+    key: NodeKey,
+}
+
+impl NodePosition {
+    /// The position of the root node
+    pub fn root() -> Self {
+        Self {
+            height: 0,
+            key: NodeKey::default(),
+        }
+    }
+
+    /// The position at `height` on the path to `key`
+    pub fn new(key: &NodeKey, height: usize) -> Result<Self, SMTError> {
+        let key = match height {
+            h if h < MAX_HEIGHT => height_key(key, h),
+            MAX_HEIGHT => key.clone(),
+            _ => {
+                return Err(SMTError::IllegalKey(format!(
+                    "Height {height} is beyond the bottom of the tree"
+                )))
+            },
+        };
+        Ok(Self { height, key })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn key(&self) -> &NodeKey {
+        &self.key
+    }
+
+    /// Returns the position of the left or right child of this position
+    pub(crate) fn child(&self, direction: TraverseDirection) -> Result<Self, SMTError> {
+        if self.height >= MAX_HEIGHT {
+            return Err(SMTError::IllegalKey("A leaf position has no children".into()));
+        }
+        let mut key = self.key.clone();
+        if direction == TraverseDirection::Right {
+            set_bit(key.as_slice_mut(), self.height);
+        }
+        Ok(Self {
+            height: self.height + 1,
+            key,
+        })
+    }
+
+    /// The big-endian height followed by the key, which keeps the nodes of a subtree close together in ordered stores
+    pub fn to_bytes(&self) -> [u8; NODE_POSITION_LENGTH] {
+        let mut bytes = [0u8; NODE_POSITION_LENGTH];
+        // MAX_HEIGHT fits comfortably in two bytes
+        let height = u16::try_from(self.height).unwrap_or(u16::MAX);
+        bytes[..2].copy_from_slice(&height.to_be_bytes());
+        bytes[2..].copy_from_slice(self.key.as_slice());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SMTError> {
+        if bytes.len() != NODE_POSITION_LENGTH {
+            return Err(SMTError::ArrayTooShort(bytes.len()));
+        }
+        let height = usize::from(u16::from_be_bytes([bytes[0], bytes[1]]));
+        let key = NodeKey::try_from(&bytes[2..])?;
+        Self::new(&key, height)
+    }
+}
+
+/// A non-empty node as it is kept in a [`SmtNodeStore`]. Branches record the number of leaves below them, so the size
+/// of the tree is available from the root without a separate counter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StoredNode {
+    Leaf {
+        key: NodeKey,
+        value: ValueHash,
+        hash: NodeHash,
+    },
+    Branch {
+        hash: NodeHash,
+        size: u64,
+    },
+}
+
+impl StoredNode {
+    pub fn leaf<H: Digest<OutputSize = U32>>(key: NodeKey, value: ValueHash) -> Self {
+        let hash = LeafNode::<H>::hash_value(&key, &value);
+        StoredNode::Leaf { key, value, hash }
+    }
+
+    pub fn hash(&self) -> &NodeHash {
+        match self {
+            StoredNode::Leaf { hash, .. } | StoredNode::Branch { hash, .. } => hash,
+        }
+    }
+
+    /// The number of leaves at or below this node
+    pub fn size(&self) -> u64 {
+        match self {
+            StoredNode::Leaf { .. } => 1,
+            StoredNode::Branch { size, .. } => *size,
+        }
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        matches!(self, StoredNode::Leaf { .. })
+    }
+}
+
+/// Storage for the nodes of a [`PersistentSparseMerkleTree`]. Empty nodes are never stored; a missing position is an
+/// empty node.
+pub trait SmtNodeStore {
+    /// Returns the node at `position`, if there is one
+    fn get_node(&self, position: &NodePosition) -> Result<Option<StoredNode>, SMTError>;
+
+    /// Stores `node` at `position`, replacing any node that is already there
+    fn put_node(&mut self, position: NodePosition, node: StoredNode) -> Result<(), SMTError>;
+
+    /// Removes the node at `position`, if there is one
+    fn delete_node(&mut self, position: &NodePosition) -> Result<(), SMTError>;
+}
+
+impl SmtNodeStore for HashMap<NodePosition, StoredNode> {
+    fn get_node(&self, position: &NodePosition) -> Result<Option<StoredNode>, SMTError> {
+        Ok(self.get(position).cloned())
+    }
+
+    fn put_node(&mut self, position: NodePosition, node: StoredNode) -> Result<(), SMTError> {
+        self.insert(position, node);
+        Ok(())
+    }
+
+    fn delete_node(&mut self, position: &NodePosition) -> Result<(), SMTError> {
+        self.remove(position);
+        Ok(())
+    }
+}
+
+/// Keeps the changes made to a tree in memory on top of a store that is only read from, e.g. to find the root that a
+/// set of changes would give without storing them.
+pub struct OverlayNodeStore<'a, S> {
+    base: &'a S,
+    changes: HashMap<NodePosition, Option<StoredNode>>,
+}
+
+impl<'a, S> OverlayNodeStore<'a, S> {
+    pub fn new(base: &'a S) -> Self {
+        Self {
+            base,
+            changes: HashMap::new(),
+        }
+    }
+}
+
+impl<S: SmtNodeStore> SmtNodeStore for OverlayNodeStore<'_, S> {
+    fn get_node(&self, position: &NodePosition) -> Result<Option<StoredNode>, SMTError> {
+        match self.changes.get(position) {
+            Some(node) => Ok(node.clone()),
+            None => self.base.get_node(position),
+        }
+    }
+
+    fn put_node(&mut self, position: NodePosition, node: StoredNode) -> Result<(), SMTError> {
+        self.changes.insert(position, Some(node));
+        Ok(())
+    }
+
+    fn delete_node(&mut self, position: &NodePosition) -> Result<(), SMTError> {
+        self.changes.insert(position.clone(), None);
+        Ok(())
+    }
+}
+
+/// A sparse Merkle tree whose nodes live in a [`SmtNodeStore`]. Node hashes are updated eagerly, so the root hash is
+/// always available without a mutable reference.
+pub struct PersistentSparseMerkleTree<H, S> {
+    store: S,
+    hash_type: PhantomData<H>,
+}
+
+impl<H, S> PersistentSparseMerkleTree<H, S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            hash_type: PhantomData,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+}
+
+impl<H: Digest<OutputSize = U32>, S: SmtNodeStore> PersistentSparseMerkleTree<H, S> {
+    /// Returns the root hash of the tree
+    pub fn hash(&self) -> Result<NodeHash, SMTError> {
+        let root = self.store.get_node(&NodePosition::root())?;
+        Ok(root.map(|n| n.hash().clone()).unwrap_or(EMPTY_NODE_HASH))
+    }
+
+    /// Returns the number of leaves in the tree
+    pub fn size(&self) -> Result<u64, SMTError> {
+        let root = self.store.get_node(&NodePosition::root())?;
+        Ok(root.map(|n| n.size()).unwrap_or(0))
+    }
+
+    pub fn is_empty(&self) -> Result<bool, SMTError> {
+        Ok(self.store.get_node(&NodePosition::root())?.is_none())
+    }
+
+    /// Returns the value at location `key` if it exists, or `None` otherwise.
+    pub fn get(&self, key: &NodeKey) -> Result<Option<ValueHash>, SMTError> {
+        let (_, _, terminal) = self.find_terminal(key)?;
+        match terminal {
+            Some(StoredNode::Leaf {
+                key: leaf_key, value, ..
+            }) if leaf_key == *key => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn contains(&self, key: &NodeKey) -> Result<bool, SMTError> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Generates an inclusion proof for the given key and value hash. See [`InclusionProof::from_tree`].
+    pub fn inclusion_proof(&self, key: &NodeKey, value_hash: &ValueHash) -> Result<InclusionProof<H>, SMTError> {
+        let proof = self.build_proof_candidate(key)?;
+        match proof.leaf() {
+            Some(leaf) if leaf.hash() == &LeafNode::<H>::hash_value(key, value_hash) => {
+                Ok(InclusionProof::new(proof.siblings().to_vec()))
+            },
+            _ => Err(SMTError::NonViableProof),
+        }
+    }
+
+    /// Generates an exclusion proof for the given key. See [`ExclusionProof::from_tree`].
+    pub fn exclusion_proof(&self, key: &NodeKey) -> Result<ExclusionProof<H>, SMTError> {
+        let proof = self.build_proof_candidate(key)?;
+        match proof.leaf() {
+            Some(leaf) if leaf.key() == key => Err(SMTError::NonViableProof),
+            _ => Ok(proof),
+        }
+    }
+
+    /// Update an existing node at location `key` in the tree, or, if the key does not exist, insert a new node at
+    /// location `key` instead. See [`SparseMerkleTree::upsert`](crate::sparse_merkle_tree::SparseMerkleTree::upsert).
+    pub fn upsert(&mut self, key: NodeKey, value: ValueHash) -> Result<UpdateResult, SMTError> {
+        let (mut path, position, terminal) = self.find_terminal(&key)?;
+        let result = match terminal {
+            None => {
+                self.store.put_node(position, StoredNode::leaf::<H>(key, value))?;
+                UpdateResult::Inserted
+            },
+            Some(StoredNode::Leaf {
+                key: leaf_key,
+                value: old_value,
+                ..
+            }) if leaf_key == key => {
+                self.store.put_node(position, StoredNode::leaf::<H>(key, value))?;
+                UpdateResult::Updated(old_value)
+            },
+            Some(StoredNode::Leaf {
+                key: old_key,
+                value: old_value,
+                hash: old_hash,
+            }) => {
+                // Branch at every height from here down to where the keys diverge, with the two leaves below that
+                let diverge_height = count_common_prefix(&key, &old_key);
+                self.store
+                    .put_node(NodePosition::new(&old_key, diverge_height + 1)?, StoredNode::Leaf {
+                        key: old_key,
+                        value: old_value,
+                        hash: old_hash,
+                    })?;
+                self.store.put_node(
+                    NodePosition::new(&key, diverge_height + 1)?,
+                    StoredNode::leaf::<H>(key.clone(), value),
+                )?;
+                for height in position.height()..=diverge_height {
+                    path.push(NodePosition::new(&key, height)?);
+                }
+                UpdateResult::Inserted
+            },
+            Some(StoredNode::Branch { .. }) => return Err(SMTError::InvalidTerminalNode),
+        };
+        self.rehash_branches(&path)?;
+        Ok(result)
+    }
+
+    /// This will only add new node when it does not exist
+    pub fn insert(&mut self, key: NodeKey, value: ValueHash) -> Result<UpdateResult, SMTError> {
+        if self.contains(&key)? {
+            return Err(SMTError::KeyExists);
+        }
+        self.upsert(key, value)
+    }
+
+    /// Attempts to delete the value at the location `key`. If the tree contains the key, the deleted value hash is
+    /// returned. Otherwise, `KeyNotFound` is returned.
+    pub fn delete(&mut self, key: &NodeKey) -> Result<DeleteResult, SMTError> {
+        let (mut path, position, terminal) = self.find_terminal(key)?;
+        let deleted = match terminal {
+            Some(StoredNode::Leaf {
+                key: leaf_key, value, ..
+            }) if leaf_key == *key => value,
+            _ => return Ok(DeleteResult::KeyNotFound),
+        };
+        self.store.delete_node(&position)?;
+
+        // A branch only exists while it has at least two leaves below it. If the deepest branch is now left with a
+        // single leaf, that leaf replaces it, and so on up the tree.
+        while let Some(parent) = path.last().cloned() {
+            let left_position = parent.child(TraverseDirection::Left)?;
+            let right_position = parent.child(TraverseDirection::Right)?;
+            let left = self.store.get_node(&left_position)?;
+            let right = self.store.get_node(&right_position)?;
+            let (orphan_position, orphan) = match (left, right) {
+                (Some(leaf), None) if leaf.is_leaf() => (left_position, leaf),
+                (None, Some(leaf)) if leaf.is_leaf() => (right_position, leaf),
+                _ => break,
+            };
+            self.store.delete_node(&orphan_position)?;
+            self.store.put_node(parent, orphan)?;
+            path.pop();
+        }
+        self.rehash_branches(&path)?;
+        Ok(DeleteResult::Deleted(deleted))
+    }
+
+    // The siblings of the branches on the path to `key`, from the root down, and the leaf at the end of the path
+    fn build_proof_candidate(&self, key: &NodeKey) -> Result<ExclusionProof<H>, SMTError> {
+        let (path, _, terminal) = self.find_terminal(key)?;
+        let mut siblings = Vec::with_capacity(path.len());
+        for position in &path {
+            let sibling = if get_bit(key.as_slice(), position.height()) == 0 {
+                position.child(TraverseDirection::Right)?
+            } else {
+                position.child(TraverseDirection::Left)?
+            };
+            let hash = self.store.get_node(&sibling)?.map(|n| n.hash().clone());
+            siblings.push(hash.unwrap_or(EMPTY_NODE_HASH));
+        }
+        let leaf = match terminal {
+            Some(StoredNode::Leaf { key, value, .. }) => Some(LeafNode::new(key, value)),
+            Some(StoredNode::Branch { .. }) => return Err(SMTError::InvalidTerminalNode),
+            None => None,
+        };
+        Ok(ExclusionProof::new(siblings, leaf))
+    }
+
+    // Walks down from the root towards `key`, returning the branch positions on the way, the first position that is not
+    // a branch, and the node at that position.
+    fn find_terminal(&self, key: &NodeKey) -> Result<(Vec<NodePosition>, NodePosition, Option<StoredNode>), SMTError> {
+        let mut path = Vec::new();
+        let mut position = NodePosition::root();
+        loop {
+            match self.store.get_node(&position)? {
+                Some(StoredNode::Branch { .. }) => {
+                    let direction = if get_bit(key.as_slice(), position.height()) == 0 {
+                        TraverseDirection::Left
+                    } else {
+                        TraverseDirection::Right
+                    };
+                    let child = position.child(direction)?;
+                    path.push(position);
+                    position = child;
+                },
+                terminal => return Ok((path, position, terminal)),
+            }
+        }
+    }
+
+    // Recalculates the branches in `path`, which must be ordered from the root downwards, from the bottom up
+    fn rehash_branches(&mut self, path: &[NodePosition]) -> Result<(), SMTError> {
+        for position in path.iter().rev() {
+            let left = self.store.get_node(&position.child(TraverseDirection::Left)?)?;
+            let right = self.store.get_node(&position.child(TraverseDirection::Right)?)?;
+            let (left_hash, left_size) = left
+                .map(|n| (n.hash().clone(), n.size()))
+                .unwrap_or((EMPTY_NODE_HASH, 0));
+            let (right_hash, right_size) = right
+                .map(|n| (n.hash().clone(), n.size()))
+                .unwrap_or((EMPTY_NODE_HASH, 0));
+            let hash = BranchNode::<H>::branch_hash(position.height(), position.key(), &left_hash, &right_hash);
+            self.store.put_node(position.clone(), StoredNode::Branch {
+                hash,
+                size: left_size + right_size,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use blake2::Blake2b;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::sparse_merkle_tree::SparseMerkleTree;
+
+    type MemoryTree = PersistentSparseMerkleTree<Blake2b<U32>, HashMap<NodePosition, StoredNode>>;
+
+    fn short_key(v: u8) -> NodeKey {
+        let mut key = [0u8; 32];
+        key[0] = v;
+        NodeKey::from(key)
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree = MemoryTree::new(HashMap::new());
+        assert!(tree.is_empty().unwrap());
+        assert_eq!(tree.size().unwrap(), 0);
+        assert_eq!(tree.hash().unwrap(), EMPTY_NODE_HASH);
+    }
+
+    #[test]
+    fn it_matches_the_documented_root() {
+        let mut tree = MemoryTree::new(HashMap::new());
+        for (key, value) in [(79, 1), (95, 2), (240, 3), (224, 4)] {
+            tree.upsert(short_key(key), ValueHash::from([value; 32])).unwrap();
+        }
+        assert_eq!(
+            tree.hash().unwrap().to_string(),
+            "e88862dc2d50248e7830924c1c415e9789069ae451f9eb5e437fdd2d6dffd4dd"
+        );
+        assert_eq!(tree.size().unwrap(), 4);
+        for key in [224, 95, 240, 79] {
+            assert!(matches!(
+                tree.delete(&short_key(key)).unwrap(),
+                DeleteResult::Deleted(_)
+            ));
+        }
+        assert!(tree.is_empty().unwrap());
+        assert!(tree.into_store().is_empty());
+    }
+
+    #[test]
+    fn it_matches_the_in_memory_tree() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut tree = MemoryTree::new(HashMap::new());
+        let mut reference = SparseMerkleTree::<Blake2b<U32>>::new();
+        let mut keys = Vec::new();
+        for i in 0..2_000 {
+            let value = ValueHash::from(rng.gen::<[u8; 32]>());
+            if i % 3 == 2 && !keys.is_empty() {
+                let key = keys.swap_remove(rng.gen_range(0..keys.len()));
+                assert_eq!(tree.delete(&key).unwrap(), reference.delete(&key).unwrap());
+            } else if i % 7 == 6 && !keys.is_empty() {
+                let key = keys[rng.gen_range(0..keys.len())].clone();
+                assert_eq!(
+                    tree.upsert(key.clone(), value.clone()).unwrap(),
+                    reference.upsert(key, value).unwrap()
+                );
+            } else {
+                // Share a prefix with an existing key now and then to get deep branches
+                let mut key = rng.gen::<[u8; 32]>();
+                if let Some(existing) = keys.last() {
+                    if i % 5 == 0 {
+                        key[..30].copy_from_slice(&existing.as_slice()[..30]);
+                    }
+                }
+                let key = NodeKey::from(key);
+                tree.insert(key.clone(), value.clone()).unwrap();
+                reference.insert(key.clone(), value).unwrap();
+                keys.push(key);
+            }
+            assert_eq!(&tree.hash().unwrap(), reference.hash());
+            assert_eq!(tree.size().unwrap(), reference.size());
+        }
+        for key in &keys {
+            assert_eq!(tree.get(key).unwrap().as_ref(), reference.get(key).unwrap());
+        }
+        assert_eq!(tree.delete(&short_key(1)).unwrap(), DeleteResult::KeyNotFound);
+        assert_eq!(
+            tree.insert(keys[0].clone(), ValueHash::default()),
+            Err(SMTError::KeyExists)
+        );
+    }
+
+    #[test]
+    fn it_rewinds_to_the_same_nodes() {
+        let mut rng = StdRng::seed_from_u64(61);
+        let mut tree = MemoryTree::new(HashMap::new());
+        let mut keys = Vec::new();
+        for _ in 0..500 {
+            let key = NodeKey::from(rng.gen::<[u8; 32]>());
+            tree.insert(key.clone(), ValueHash::from(rng.gen::<[u8; 32]>()))
+                .unwrap();
+            keys.push(key);
+        }
+        let before = tree.store().clone();
+        let root = tree.hash().unwrap();
+
+        // Apply a "block" that spends some outputs and creates new ones, then undo it in the order a reorg would
+        let spent = keys[..50].to_vec();
+        let mut spent_values = Vec::new();
+        for key in &spent {
+            match tree.delete(key).unwrap() {
+                DeleteResult::Deleted(value) => spent_values.push(value),
+                DeleteResult::KeyNotFound => panic!("Key should exist"),
+            }
+        }
+        let created = (0..50)
+            .map(|_| NodeKey::from(rng.gen::<[u8; 32]>()))
+            .collect::<Vec<_>>();
+        for key in &created {
+            tree.insert(key.clone(), ValueHash::default()).unwrap();
+        }
+        assert_ne!(tree.hash().unwrap(), root);
+
+        for key in &created {
+            tree.delete(key).unwrap();
+        }
+        for (key, value) in spent.into_iter().zip(spent_values) {
+            tree.insert(key, value).unwrap();
+        }
+        assert_eq!(tree.hash().unwrap(), root);
+        assert_eq!(tree.store(), &before);
+    }
+
+    #[test]
+    fn it_builds_the_same_proofs_as_the_in_memory_tree() {
+        let mut rng = StdRng::seed_from_u64(33);
+        let mut tree = MemoryTree::new(HashMap::new());
+        let mut reference = SparseMerkleTree::<Blake2b<U32>>::new();
+        let mut keys = Vec::new();
+        for _ in 0..200 {
+            let key = NodeKey::from(rng.gen::<[u8; 32]>());
+            let value = ValueHash::from(rng.gen::<[u8; 32]>());
+            tree.insert(key.clone(), value.clone()).unwrap();
+            reference.insert(key.clone(), value.clone()).unwrap();
+            keys.push((key, value));
+        }
+        let root = reference.hash().clone();
+        for (key, value) in &keys {
+            let proof = tree.inclusion_proof(key, value).unwrap();
+            let expected = InclusionProof::from_tree(&reference, key, value).unwrap();
+            assert_eq!(proof.siblings(), expected.siblings());
+            assert!(proof.validate(key, value, &root));
+            assert!(matches!(tree.exclusion_proof(key), Err(SMTError::NonViableProof)));
+        }
+        let missing = NodeKey::from(rng.gen::<[u8; 32]>());
+        let proof = tree.exclusion_proof(&missing).unwrap();
+        let expected = ExclusionProof::from_tree(&reference, &missing).unwrap();
+        assert_eq!(proof.siblings(), expected.siblings());
+        assert_eq!(proof.leaf().map(|l| l.hash()), expected.leaf().map(|l| l.hash()));
+        assert!(proof.validate(&missing, &root));
+        assert!(matches!(
+            tree.inclusion_proof(&missing, &ValueHash::default()),
+            Err(SMTError::NonViableProof)
+        ));
+    }
+
+    #[test]
+    fn overlay_changes_leave_the_store_untouched() {
+        let mut tree = MemoryTree::new(HashMap::new());
+        for key in [79, 95, 240] {
+            tree.insert(short_key(key), ValueHash::from([key; 32])).unwrap();
+        }
+        let store = tree.into_store();
+
+        let mut overlay = PersistentSparseMerkleTree::<Blake2b<U32>, _>::new(OverlayNodeStore::new(&store));
+        overlay.insert(short_key(224), ValueHash::from([224; 32])).unwrap();
+        overlay.delete(&short_key(95)).unwrap();
+
+        let mut expected = MemoryTree::new(store.clone());
+        expected.insert(short_key(224), ValueHash::from([224; 32])).unwrap();
+        expected.delete(&short_key(95)).unwrap();
+        assert_eq!(overlay.hash().unwrap(), expected.hash().unwrap());
+        assert_eq!(overlay.size().unwrap(), 3);
+        assert_eq!(MemoryTree::new(store).size().unwrap(), 3);
+        assert!(!MemoryTree::new(expected.into_store()).contains(&short_key(95)).unwrap());
+    }
+
+    #[test]
+    fn node_positions() {
+        let key = NodeKey::from([0xffu8; 32]);
+        let root = NodePosition::root();
+        assert_eq!(root, NodePosition::new(&key, 0).unwrap());
+        let right = root.child(TraverseDirection::Right).unwrap();
+        assert_eq!(right, NodePosition::new(&key, 1).unwrap());
+        assert_eq!(right.key().as_slice()[0], 0x80);
+        assert_eq!(root.child(TraverseDirection::Left).unwrap().key(), &NodeKey::default());
+
+        let bottom = NodePosition::new(&key, MAX_HEIGHT).unwrap();
+        assert_eq!(bottom.key(), &key);
+        assert!(bottom.child(TraverseDirection::Left).is_err());
+        assert!(NodePosition::new(&key, MAX_HEIGHT + 1).is_err());
+
+        for position in [root, right, bottom] {
+            assert_eq!(NodePosition::from_bytes(&position.to_bytes()).unwrap(), position);
+        }
+        assert!(NodePosition::from_bytes(&[0u8; 3]).is_err());
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright 2023. The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
-use std::marker::PhantomData;
+use std::{
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+};
 
 use digest::{consts::U32, Digest};
 
@@ -103,6 +106,42 @@ pub struct ExclusionProof<H> {
     phantom: std::marker::PhantomData<H>,
 }
 
+impl<H> Clone for InclusionProof<H> {
+    fn clone(&self) -> Self {
+        Self {
+            siblings: self.siblings.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H> Debug for InclusionProof<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InclusionProof")
+            .field("siblings", &self.siblings)
+            .finish()
+    }
+}
+
+impl<H> Clone for ExclusionProof<H> {
+    fn clone(&self) -> Self {
+        Self {
+            siblings: self.siblings.clone(),
+            leaf: self.leaf.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H> Debug for ExclusionProof<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExclusionProof")
+            .field("siblings", &self.siblings)
+            .field("leaf", &self.leaf.as_ref().map(|leaf| (leaf.key(), leaf.value())))
+            .finish()
+    }
+}
+
 trait MerkleProofDigest<H: Digest<OutputSize = U32>> {
     /// Returns an array to the vector of sibling hashes along the path to the key's leaf node for this proof.
     fn siblings(&self) -> &[NodeHash];
@@ -167,6 +206,11 @@ impl<H: Digest<OutputSize = U32>> InclusionProof<H> {
         let calculated_root = self.calculate_root_hash(expected_key, leaf_hash);
         calculated_root == *expected_root
     }
+
+    /// Returns the sibling hashes along the path from the root to the key's leaf node.
+    pub fn siblings(&self) -> &[NodeHash] {
+        &self.siblings
+    }
 }
 
 impl<H: Digest<OutputSize = U32>> MerkleProofDigest<H> for InclusionProof<H> {
@@ -216,6 +260,16 @@ impl<H: Digest<OutputSize = U32>> ExclusionProof<H> {
                 None => true,
             }
     }
+
+    /// Returns the sibling hashes along the path from the root to the terminal node of the proof.
+    pub fn siblings(&self) -> &[NodeHash] {
+        &self.siblings
+    }
+
+    /// Returns the leaf node at the terminal position of the proof, or `None` if the terminal node is empty.
+    pub fn leaf(&self) -> Option<&LeafNode<H>> {
+        self.leaf.as_ref()
+    }
 }
 
 impl<H: Digest<OutputSize = U32>> MerkleProofDigest<H> for ExclusionProof<H> {
@@ -93,6 +93,9 @@ pub struct WalletConfig {
     /// transaction events are received by the console wallet .
     /// (see example at 'applications/minotari_console_wallet/src/notifier/notify_example.sh')
     pub notify_file: Option<PathBuf>,
+    /// HTTP webhook settings for a notifier service - allows a wallet to POST signed JSON notifications to HTTP
+    /// endpoints when certain transaction events are received by the console wallet.
+    pub webhooks: WebhookConfig,
     /// If true, a GRPC server will bind to the configured address and listen for incoming GRPC requests.
     pub grpc_enabled: bool,
     /// GRPC bind address of the wallet
@@ -121,6 +124,9 @@ pub struct WalletConfig {
     /// responsiveness of the wallet with slightly delayed balance updates
     #[serde(with = "serializers::seconds")]
     pub balance_enquiry_cooldown_period: Duration,
+    /// Use compact block filters to skip blocks during UTXO scanning. Filters are not committed to the block header,
+    /// so they are cross-checked against a second base node and the scanner falls back to a full scan otherwise.
+    pub use_block_filters: bool,
 }
 
 impl Default for WalletConfig {
@@ -148,6 +154,7 @@ impl Default for WalletConfig {
             command_send_wait_stage: TransactionStage::Broadcast,
             command_send_wait_timeout: Duration::from_secs(300),
             notify_file: None,
+            webhooks: WebhookConfig::default(),
             grpc_enabled: false,
             grpc_address: None,
             grpc_authentication: GrpcAuthentication::default(),
@@ -160,6 +167,7 @@ impl Default for WalletConfig {
             use_libtor: true,
             identity_file: None,
             balance_enquiry_cooldown_period: Duration::from_secs(5),
+            use_block_filters: false,
         }
     }
 }
@@ -181,10 +189,54 @@ impl WalletConfig {
         if !self.db_file.is_absolute() {
             self.db_file = self.data_dir.join(self.db_file.as_path());
         }
+        if !self.webhooks.outbox_file.is_absolute() {
+            self.webhooks.outbox_file = self.data_dir.join(self.webhooks.outbox_file.as_path());
+        }
         self.p2p.set_base_path(base_path);
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// The URLs that transaction event notifications are POSTed to. Webhooks are disabled when this is empty.
+    pub urls: StringList,
+    /// The shared secret used to sign notifications with HMAC-SHA256. Notifications are not signed if no secret is
+    /// set.
+    #[serde(deserialize_with = "deserialize_safe_password_option")]
+    pub secret: Option<SafePassword>,
+    /// The file that notifications are kept in until they have been delivered, relative to the wallet data directory
+    pub outbox_file: PathBuf,
+    /// The delay before the first retry of a failed delivery. The delay doubles with each failed attempt.
+    #[serde(with = "serializers::seconds")]
+    pub initial_retry_delay: Duration,
+    /// The maximum delay between retries of a failed delivery
+    #[serde(with = "serializers::seconds")]
+    pub max_retry_delay: Duration,
+    /// How long to wait for an endpoint to respond before the delivery attempt is considered failed
+    #[serde(with = "serializers::seconds")]
+    pub request_timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: StringList::default(),
+            secret: None,
+            outbox_file: PathBuf::from("webhook_outbox.json"),
+            initial_retry_delay: Duration::from_secs(5),
+            max_retry_delay: Duration::from_secs(60 * 60),
+            request_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty()
+    }
+}
+
 #[derive(Debug, EnumString, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum TransactionStage {
     Initiated,
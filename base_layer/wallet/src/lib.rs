@@ -32,7 +32,7 @@ extern crate diesel_migrations;
 mod config;
 pub mod schema;
 pub mod utxo_scanner_service;
-pub use config::{TransactionStage, WalletConfig};
+pub use config::{TransactionStage, WalletConfig, WebhookConfig};
 use tari_contacts::contacts_service::storage::sqlite_db::ContactsServiceSqliteDatabase;
 use tari_core::transactions::key_manager::TransactionKeyManagerWrapper;
 use tari_key_manager::key_manager_service::storage::sqlite_db::KeyManagerSqliteDatabase;
@@ -45,6 +45,10 @@ pub struct OutputManagerServiceConfig {
     pub autoignore_onesided_utxos: bool,
     /// The number of seconds that have to pass for the wallet to run revalidation of invalid UTXOs on startup.
     pub num_of_seconds_to_revalidate_invalid_utxos: u64,
+    /// If set to `true`, the spent status of mined outputs reported by the base node is only accepted once it is
+    /// confirmed by an output SMT proof against the tip header's output Merkle root. The base node must support the
+    /// `get_output_smt_proofs` RPC method.
+    pub verify_output_smt_proofs: bool,
 }
 
 impl Default for OutputManagerServiceConfig {
@@ -57,6 +61,7 @@ impl Default for OutputManagerServiceConfig {
             tx_validator_batch_size: 100,
             autoignore_onesided_utxos: false,
             num_of_seconds_to_revalidate_invalid_utxos: 60 * 60 * 24 * 3,
+            verify_output_smt_proofs: false,
         }
     }
 }
@@ -152,6 +152,8 @@ pub enum OutputManagerError {
     TooManyInputsToFulfillTransaction(String),
     #[error("Std I/O error: {0}")]
     StdIoError(#[from] std::io::Error),
+    #[error("Offline signing error: {0}")]
+    OfflineSigningError(String),
 }
 
 impl From<RangeProofError> for OutputManagerError {
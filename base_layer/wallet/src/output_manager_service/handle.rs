@@ -30,6 +30,7 @@ use tari_common_types::{
 use tari_core::{
     covenants::Covenant,
     transactions::{
+        key_manager::TariKeyId,
         tari_amount::MicroMinotari,
         transaction_components::{
             encrypted_data::PaymentId,
@@ -39,7 +40,11 @@ use tari_core::{
             WalletOutput,
             WalletOutputBuilder,
         },
-        transaction_protocol::{sender::TransactionSenderMessage, TransactionMetadata},
+        transaction_protocol::{
+            sender::TransactionSenderMessage,
+            unsigned_transaction::{UnsignedRecipient, UnsignedTransactionBundle},
+            TransactionMetadata,
+        },
         ReceiverTransactionProtocol,
         SenderTransactionProtocol,
     },
@@ -116,7 +121,41 @@ pub enum OutputManagerRequest {
         selection_criteria: UtxoSelectionCriteria,
         payment_id: PaymentId,
     },
+    CreateTransactionWithRecipientOutputs {
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        fee_per_gram: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        payment_id: PaymentId,
+    },
+    PrepareUnsignedTransaction {
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        recipients: Vec<UnsignedRecipient>,
+        fee_per_gram: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        payment_id: PaymentId,
+    },
+    SignUnsignedTransaction {
+        bundle: Box<UnsignedTransactionBundle>,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+    },
+    ConfirmSignedTransaction {
+        tx_id: TxId,
+        transaction: Box<Transaction>,
+        change: MicroMinotari,
+    },
     CancelTransaction(TxId),
+    ReplacePendingTransaction {
+        original_tx_id: TxId,
+        replacement_tx_id: TxId,
+        spent_inputs: Vec<Commitment>,
+    },
+    ResolveReplacedTransaction {
+        confirmed_tx_id: TxId,
+        superseded_tx_id: TxId,
+        confirmed_spent_inputs: Vec<Commitment>,
+    },
     GetSpentOutputs,
     GetUnspentOutputs,
     GetInvalidOutputs,
@@ -146,6 +185,7 @@ pub enum OutputManagerRequest {
     ScanForRecoverableOutputs(Vec<(TransactionOutput, Option<TxId>)>),
     ScanOutputs(Vec<(TransactionOutput, Option<TxId>)>),
     AddKnownOneSidedPaymentScript(KnownOneSidedPaymentScript),
+    GetKnownOneSidedPaymentScripts,
     CreateOutputWithFeatures {
         value: MicroMinotari,
         features: Box<OutputFeatures>,
@@ -226,6 +266,24 @@ impl fmt::Display for OutputManagerRequest {
             PrepareToSendTransaction { payment_id, .. } => write!(f, "PrepareToSendTransaction ({})", payment_id),
             CreatePayToSelfTransaction { .. } => write!(f, "CreatePayToSelfTransaction",),
             CancelTransaction(v) => write!(f, "CancelTransaction ({})", v),
+            ReplacePendingTransaction {
+                original_tx_id,
+                replacement_tx_id,
+                ..
+            } => write!(
+                f,
+                "ReplacePendingTransaction ({} -> {})",
+                original_tx_id, replacement_tx_id
+            ),
+            ResolveReplacedTransaction {
+                confirmed_tx_id,
+                superseded_tx_id,
+                ..
+            } => write!(
+                f,
+                "ResolveReplacedTransaction ({} superseded by {})",
+                superseded_tx_id, confirmed_tx_id
+            ),
             GetSpentOutputs => write!(f, "GetSpentOutputs"),
             GetUnspentOutputs => write!(f, "GetUnspentOutputs"),
             GetInvalidOutputs => write!(f, "GetInvalidOutputs"),
@@ -265,10 +323,25 @@ impl fmt::Display for OutputManagerRequest {
             ScanForRecoverableOutputs(_) => write!(f, "ScanForRecoverableOutputs"),
             ScanOutputs(_) => write!(f, "ScanOutputs"),
             AddKnownOneSidedPaymentScript(_) => write!(f, "AddKnownOneSidedPaymentScript"),
+            GetKnownOneSidedPaymentScripts => write!(f, "GetKnownOneSidedPaymentScripts"),
             CreateOutputWithFeatures { value, features } => {
                 write!(f, "CreateOutputWithFeatures({}, {})", value, features,)
             },
             CreatePayToSelfWithOutputs { .. } => write!(f, "CreatePayToSelfWithOutputs"),
+            CreateTransactionWithRecipientOutputs { tx_id, outputs, .. } => write!(
+                f,
+                "CreateTransactionWithRecipientOutputs ({}, {} outputs)",
+                tx_id,
+                outputs.len()
+            ),
+            PrepareUnsignedTransaction { tx_id, recipients, .. } => write!(
+                f,
+                "PrepareUnsignedTransaction ({}, {} recipients)",
+                tx_id,
+                recipients.len()
+            ),
+            SignUnsignedTransaction { bundle, .. } => write!(f, "SignUnsignedTransaction ({})", bundle.tx_id),
+            ConfirmSignedTransaction { tx_id, .. } => write!(f, "ConfirmSignedTransaction ({})", tx_id),
             ReinstateCancelledInboundTx(_) => write!(f, "ReinstateCancelledInboundTx"),
             CreateClaimShaAtomicSwapTransaction(output, pre_image, fee_per_gram) => write!(
                 f,
@@ -309,8 +382,13 @@ pub enum OutputManagerResponse {
     OutputConfirmed,
     PendingTransactionConfirmed,
     PayToSelfTransaction((MicroMinotari, Transaction)),
+    TransactionWithRecipientOutputs((MicroMinotari, Transaction)),
+    UnsignedTransaction(Box<(UnsignedTransactionBundle, SenderTransactionProtocol)>),
+    SignedTransactionConfirmed,
     TransactionToSend(SenderTransactionProtocol),
     TransactionCancelled,
+    PendingTransactionReplaced,
+    ReplacedTransactionResolved,
     SpentOutputs(Vec<DbWalletOutput>),
     UnspentOutputs(Vec<DbWalletOutput>),
     Outputs(Vec<WalletOutput>),
@@ -324,6 +402,7 @@ pub enum OutputManagerResponse {
     RewoundOutputs(Vec<RecoveredOutput>),
     ScanOutputs(Vec<RecoveredOutput>),
     AddKnownOneSidedPaymentScript,
+    KnownOneSidedPaymentScripts(Vec<KnownOneSidedPaymentScript>),
     CreateOutputWithFeatures {
         output: Box<WalletOutputBuilder>,
     },
@@ -620,6 +699,50 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Hand the inputs of a pending transaction over to the transaction that replaces it and cancel the original's
+    /// pending outputs. Inputs the replacement does not spend stay locked to the original.
+    pub async fn replace_pending_transaction(
+        &mut self,
+        original_tx_id: TxId,
+        replacement_tx_id: TxId,
+        spent_inputs: Vec<Commitment>,
+    ) -> Result<(), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ReplacePendingTransaction {
+                original_tx_id,
+                replacement_tx_id,
+                spent_inputs,
+            })
+            .await??
+        {
+            OutputManagerResponse::PendingTransactionReplaced => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Settle the outputs of a replaced transaction and its replacement once one of the two has been confirmed,
+    /// cancelling the pending outputs of the other one and releasing the inputs only it spent.
+    pub async fn resolve_replaced_transaction(
+        &mut self,
+        confirmed_tx_id: TxId,
+        superseded_tx_id: TxId,
+        confirmed_spent_inputs: Vec<Commitment>,
+    ) -> Result<(), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ResolveReplacedTransaction {
+                confirmed_tx_id,
+                superseded_tx_id,
+                confirmed_spent_inputs,
+            })
+            .await??
+        {
+            OutputManagerResponse::ReplacedTransactionResolved => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_spent_outputs(&mut self) -> Result<Vec<DbWalletOutput>, OutputManagerError> {
         match self.handle.call(OutputManagerRequest::GetSpentOutputs).await?? {
             OutputManagerResponse::SpentOutputs(s) => Ok(s),
@@ -816,6 +939,19 @@ impl OutputManagerHandle {
         }
     }
 
+    pub async fn get_known_one_sided_payment_scripts(
+        &mut self,
+    ) -> Result<Vec<KnownOneSidedPaymentScript>, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::GetKnownOneSidedPaymentScripts)
+            .await??
+        {
+            OutputManagerResponse::KnownOneSidedPaymentScripts(scripts) => Ok(scripts),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn create_send_to_self_with_output(
         &mut self,
         outputs: Vec<WalletOutputBuilder>,
@@ -954,6 +1090,104 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Creates a transaction that pays the given pre-built recipient outputs, each paired with the sender offset key
+    /// used to sign it, with a single change output back to this wallet. Returns the fee and the finalized transaction.
+    pub async fn create_transaction_with_recipient_outputs(
+        &mut self,
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        fee_per_gram: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        payment_id: PaymentId,
+    ) -> Result<(MicroMinotari, Transaction), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateTransactionWithRecipientOutputs {
+                tx_id,
+                outputs,
+                fee_per_gram,
+                selection_criteria,
+                payment_id,
+            })
+            .await??
+        {
+            OutputManagerResponse::TransactionWithRecipientOutputs(result) => Ok(result),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Selects and encumbers the inputs to pay `recipients`, and captures the transaction for offline signing. The
+    /// `outputs` paying the recipients are only used to size the transaction; the signer recreates them. The returned
+    /// protocol is what the pending transaction is stored with, so that it can be cancelled.
+    pub async fn prepare_unsigned_transaction(
+        &mut self,
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        recipients: Vec<UnsignedRecipient>,
+        fee_per_gram: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        payment_id: PaymentId,
+    ) -> Result<(UnsignedTransactionBundle, SenderTransactionProtocol), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareUnsignedTransaction {
+                tx_id,
+                outputs,
+                recipients,
+                fee_per_gram,
+                selection_criteria,
+                payment_id,
+            })
+            .await??
+        {
+            OutputManagerResponse::UnsignedTransaction(prepared) => Ok(*prepared),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Builds and signs the transaction described by `bundle`, paying the recipients with `outputs`. Nothing is
+    /// written to the wallet database.
+    pub async fn sign_unsigned_transaction(
+        &mut self,
+        bundle: UnsignedTransactionBundle,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+    ) -> Result<(MicroMinotari, Transaction), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::SignUnsignedTransaction {
+                bundle: Box::new(bundle),
+                outputs,
+            })
+            .await??
+        {
+            OutputManagerResponse::TransactionWithRecipientOutputs(result) => Ok(result),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Checks that `transaction` spends exactly the outputs encumbered for `tx_id` by
+    /// [prepare_unsigned_transaction](Self::prepare_unsigned_transaction), and adds the `change` output the signer
+    /// created as an output to be received
+    pub async fn confirm_signed_transaction(
+        &mut self,
+        tx_id: TxId,
+        transaction: Transaction,
+        change: MicroMinotari,
+    ) -> Result<(), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ConfirmSignedTransaction {
+                tx_id,
+                transaction: Box::new(transaction),
+                change,
+            })
+            .await??
+        {
+            OutputManagerResponse::SignedTransactionConfirmed => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn reinstate_cancelled_inbound_transaction_outputs(
         &mut self,
         tx_id: TxId,
@@ -23,9 +23,16 @@
 use std::{
     fmt,
     fmt::{Display, Formatter},
+    str::FromStr,
 };
 
-use tari_common_types::types::Commitment;
+use rand::{seq::SliceRandom, Rng};
+use tari_common_types::{transaction::TxId, types::Commitment};
+
+/// The maximum number of branches explored by the branch-and-bound search before it gives up
+pub const BRANCH_AND_BOUND_MAX_TRIES: usize = 100_000;
+/// The number of random draws made by the random selector, of which the least wasteful one is used
+pub const RANDOM_SELECTION_ROUNDS: usize = 16;
 
 #[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub enum UtxoSelectionMode {
@@ -63,6 +70,24 @@ impl UtxoSelectionCriteria {
         }
     }
 
+    pub fn branch_and_bound(min_dust: u64) -> Self {
+        Self {
+            filter: UtxoSelectionFilter::Standard,
+            ordering: UtxoSelectionOrdering::BranchAndBound,
+            min_dust,
+            ..Default::default()
+        }
+    }
+
+    pub fn random(min_dust: u64) -> Self {
+        Self {
+            filter: UtxoSelectionFilter::Standard,
+            ordering: UtxoSelectionOrdering::Random,
+            min_dust,
+            ..Default::default()
+        }
+    }
+
     pub fn specific(commitments: Vec<Commitment>) -> Self {
         Self {
             filter: UtxoSelectionFilter::SpecificOutputs { commitments },
@@ -71,6 +96,15 @@ impl UtxoSelectionCriteria {
             ..Default::default()
         }
     }
+
+    pub fn replacing(tx_id: TxId) -> Self {
+        Self {
+            filter: UtxoSelectionFilter::ReplacingTransaction { tx_id },
+            ordering: UtxoSelectionOrdering::Default,
+            min_dust: 0,
+            ..Default::default()
+        }
+    }
 }
 
 impl Display for UtxoSelectionCriteria {
@@ -92,6 +126,14 @@ pub enum UtxoSelectionOrdering {
     SmallestFirst,
     /// A strategy that selects the largest UTXOs first. Preferred when the amount is large
     LargestFirst,
+    /// Searches for a set of UTXOs that covers the amount and fee closely enough that no change output is needed,
+    /// preferring the set that wastes the least on input fees and excess. Falls back to `Default` if no such set
+    /// exists.
+    BranchAndBound,
+    /// Selects UTXOs in a random order, which avoids leaking information about the wallet's UTXO set through the
+    /// selected inputs. The least wasteful of several random draws is used. Falls back to `Default` if the draws
+    /// cannot cover the amount.
+    Random,
 }
 
 impl Display for UtxoSelectionOrdering {
@@ -100,8 +142,140 @@ impl Display for UtxoSelectionOrdering {
             UtxoSelectionOrdering::SmallestFirst => write!(f, "Smallest"),
             UtxoSelectionOrdering::LargestFirst => write!(f, "Largest"),
             UtxoSelectionOrdering::Default => write!(f, "Default"),
+            UtxoSelectionOrdering::BranchAndBound => write!(f, "BranchAndBound"),
+            UtxoSelectionOrdering::Random => write!(f, "Random"),
+        }
+    }
+}
+
+impl FromStr for UtxoSelectionOrdering {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "default" => Ok(UtxoSelectionOrdering::Default),
+            "smallest" | "smallestfirst" => Ok(UtxoSelectionOrdering::SmallestFirst),
+            "largest" | "largestfirst" => Ok(UtxoSelectionOrdering::LargestFirst),
+            "bnb" | "branchandbound" => Ok(UtxoSelectionOrdering::BranchAndBound),
+            "random" => Ok(UtxoSelectionOrdering::Random),
+            _ => Err(format!(
+                "Invalid UTXO selection ordering '{}', expected one of: default, smallest, largest, branch-and-bound, \
+                 random",
+                s
+            )),
+        }
+    }
+}
+
+/// Searches for a subset of `values` that can pay for `target` without a change output, i.e. the sum of the
+/// selected values less `cost_per_input` for each of them lies in `[target, target + cost_of_change]`. Of all the
+/// subsets found within `BRANCH_AND_BOUND_MAX_TRIES` steps, the one with the least waste (input fees plus the excess
+/// that is given up to the fee) is returned as indexes into `values`.
+pub fn select_branch_and_bound(
+    values: &[u64],
+    target: u64,
+    cost_per_input: u64,
+    cost_of_change: u64,
+) -> Option<Vec<usize>> {
+    // Only UTXOs that are worth more than it costs to spend them are considered, largest first
+    let mut candidates = values
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| **v > cost_per_input)
+        .map(|(i, v)| (i, *v - cost_per_input))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let upper_bound = target.saturating_add(cost_of_change);
+    let mut available = candidates.iter().map(|(_, v)| *v).sum::<u64>();
+    if available < target {
+        return None;
+    }
+
+    let mut selected = Vec::<usize>::new();
+    let mut index = 0;
+    let mut value = 0u64;
+    let mut waste = 0u64;
+    let mut best = None;
+    let mut best_waste = u64::MAX;
+    for _ in 0..BRANCH_AND_BOUND_MAX_TRIES {
+        let mut backtrack = false;
+        if value + available < target || value > upper_bound || waste >= best_waste {
+            backtrack = true;
+        } else if value >= target {
+            let total_waste = waste + (value - target);
+            if total_waste < best_waste {
+                best_waste = total_waste;
+                best = Some(selected.clone());
+            }
+            backtrack = true;
+        }
+
+        if backtrack {
+            // Undo the most recent inclusion and continue down the branch that omits it
+            let last = match selected.pop() {
+                Some(last) => last,
+                // Every branch has been explored
+                None => break,
+            };
+            available += candidates[last + 1..index].iter().map(|(_, v)| *v).sum::<u64>();
+            value -= candidates[last].1;
+            waste -= cost_per_input;
+            index = last + 1;
+        } else {
+            available -= candidates[index].1;
+            value += candidates[index].1;
+            waste += cost_per_input;
+            selected.push(index);
+            index += 1;
         }
     }
+
+    best.map(|selected| selected.into_iter().map(|i| candidates[i].0).collect())
+}
+
+/// Draws UTXOs from `values` in a random order until their value, less `cost_per_input` for each of them, exceeds
+/// `target`. `RANDOM_SELECTION_ROUNDS` draws are made and the one that needs the fewest inputs (and then leaves the
+/// smallest excess) is returned as indexes into `values`, in the order they were drawn.
+pub fn select_random<R: Rng + ?Sized>(
+    values: &[u64],
+    target: u64,
+    cost_per_input: u64,
+    rng: &mut R,
+) -> Option<Vec<usize>> {
+    let mut candidates = values
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| **v > cost_per_input)
+        .map(|(i, v)| (i, *v - cost_per_input))
+        .collect::<Vec<_>>();
+
+    let mut best: Option<(Vec<usize>, u64)> = None;
+    for _ in 0..RANDOM_SELECTION_ROUNDS {
+        candidates.shuffle(rng);
+        let mut selected = Vec::new();
+        let mut value = 0u64;
+        for (i, v) in &candidates {
+            selected.push(*i);
+            value += v;
+            if value > target {
+                break;
+            }
+        }
+        if value <= target {
+            // Not even all of the candidates together are enough
+            return None;
+        }
+        let excess = value - target;
+        let is_better = best.as_ref().map_or(true, |(best_selected, best_excess)| {
+            (selected.len(), excess) < (best_selected.len(), *best_excess)
+        });
+        if is_better {
+            best = Some((selected, excess));
+        }
+    }
+
+    best.map(|(selected, _)| selected)
 }
 
 #[derive(Default, Debug, Clone)]
@@ -111,11 +285,21 @@ pub enum UtxoSelectionFilter {
     Standard,
     /// Selects specific outputs. All outputs must be exist and be spendable.
     SpecificOutputs { commitments: Vec<Commitment> },
+    /// Selects the inputs of a pending outbound transaction so that a replacement can spend them. The inputs stay
+    /// encumbered to the original transaction until the replacement is confirmed.
+    ReplacingTransaction { tx_id: TxId },
 }
 impl UtxoSelectionFilter {
     pub fn is_standard(&self) -> bool {
         matches!(self, UtxoSelectionFilter::Standard)
     }
+
+    pub fn replaced_tx_id(&self) -> Option<TxId> {
+        match self {
+            UtxoSelectionFilter::ReplacingTransaction { tx_id } => Some(*tx_id),
+            _ => None,
+        }
+    }
 }
 
 impl Display for UtxoSelectionFilter {
@@ -127,6 +311,88 @@ impl Display for UtxoSelectionFilter {
             UtxoSelectionFilter::SpecificOutputs { commitments: outputs } => {
                 write!(f, "Specific({} output(s))", outputs.len())
             },
+            UtxoSelectionFilter::ReplacingTransaction { tx_id } => {
+                write!(f, "Replacing({})", tx_id)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn it_parses_orderings() {
+        assert_eq!(
+            "branch-and-bound".parse::<UtxoSelectionOrdering>().unwrap(),
+            UtxoSelectionOrdering::BranchAndBound
+        );
+        assert_eq!(
+            "BnB".parse::<UtxoSelectionOrdering>().unwrap(),
+            UtxoSelectionOrdering::BranchAndBound
+        );
+        assert_eq!(
+            "random".parse::<UtxoSelectionOrdering>().unwrap(),
+            UtxoSelectionOrdering::Random
+        );
+        assert_eq!(
+            "smallest_first".parse::<UtxoSelectionOrdering>().unwrap(),
+            UtxoSelectionOrdering::SmallestFirst
+        );
+        assert!("biggest".parse::<UtxoSelectionOrdering>().is_err());
+    }
+
+    #[test]
+    fn branch_and_bound_finds_exact_match() {
+        let values = [1000, 600, 510, 310, 210, 110];
+        // With a cost of 10 per input, 1000 on its own pays exactly 990 and 510 + 310 + 210 pays exactly 1000
+        let selected = select_branch_and_bound(&values, 990, 10, 0).unwrap();
+        assert_eq!(selected, vec![0]);
+        let selected = select_branch_and_bound(&values, 1000, 10, 0).unwrap();
+        let mut selected = selected.into_iter().map(|i| values[i]).collect::<Vec<_>>();
+        selected.sort_unstable();
+        assert_eq!(selected, vec![210, 310, 510]);
+    }
+
+    #[test]
+    fn branch_and_bound_stays_within_change_window() {
+        let values = [5000, 3000, 2000];
+        // No subset lands in [4000, 4100]
+        assert!(select_branch_and_bound(&values, 4000, 0, 100).is_none());
+        let values = [5000, 2600, 2420];
+        // 2600 + 2420 and 5000 both land in [4950, 5050] after input costs, but the single input wastes less
+        let selected = select_branch_and_bound(&values, 4950, 10, 100).unwrap();
+        assert_eq!(selected, vec![0]);
+    }
+
+    #[test]
+    fn branch_and_bound_ignores_uneconomical_inputs() {
+        let values = [100, 5, 5, 5];
+        assert!(select_branch_and_bound(&values, 105, 5, 0).is_none());
+        assert_eq!(select_branch_and_bound(&values, 95, 5, 0).unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn branch_and_bound_handles_insufficient_funds() {
+        assert!(select_branch_and_bound(&[], 1, 0, 0).is_none());
+        assert!(select_branch_and_bound(&[10, 20], 31, 0, 100).is_none());
+    }
+
+    #[test]
+    fn random_selection_covers_target() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let values = (1..=50).map(|v| v * 100).collect::<Vec<_>>();
+        for _ in 0..10 {
+            let selected = select_random(&values, 7_000, 10, &mut rng).unwrap();
+            let total = selected.iter().map(|i| values[*i] - 10).sum::<u64>();
+            assert!(total > 7_000);
+            // Every input is needed, otherwise the draw would have stopped earlier
+            let without_last = total - (values[*selected.last().unwrap()] - 10);
+            assert!(without_last <= 7_000);
         }
+        assert!(select_random(&values, 200_000, 10, &mut rng).is_none());
     }
 }
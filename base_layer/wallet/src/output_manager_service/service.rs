@@ -60,7 +60,11 @@ use tari_core::{
             WalletOutput,
             WalletOutputBuilder,
         },
-        transaction_protocol::{sender::TransactionSenderMessage, TransactionMetadata},
+        transaction_protocol::{
+            sender::TransactionSenderMessage,
+            unsigned_transaction::{UnsignedRecipient, UnsignedTransactionBundle},
+            TransactionMetadata,
+        },
         CryptoFactories,
         ReceiverTransactionProtocol,
         SenderTransactionProtocol,
@@ -96,7 +100,7 @@ use crate::{
             OutputManagerResponse,
             RecoveredOutput,
         },
-        input_selection::UtxoSelectionCriteria,
+        input_selection::{select_branch_and_bound, select_random, UtxoSelectionCriteria, UtxoSelectionOrdering},
         recovery::StandardUtxoRecoverer,
         resources::OutputManagerResources,
         storage::{
@@ -379,6 +383,20 @@ where
             OutputManagerRequest::CancelTransaction(tx_id) => self
                 .cancel_transaction(tx_id)
                 .map(|_| OutputManagerResponse::TransactionCancelled),
+            OutputManagerRequest::ReplacePendingTransaction {
+                original_tx_id,
+                replacement_tx_id,
+                spent_inputs,
+            } => self
+                .replace_pending_transaction(original_tx_id, replacement_tx_id, &spent_inputs)
+                .map(|_| OutputManagerResponse::PendingTransactionReplaced),
+            OutputManagerRequest::ResolveReplacedTransaction {
+                confirmed_tx_id,
+                superseded_tx_id,
+                confirmed_spent_inputs,
+            } => self
+                .resolve_replaced_transaction(confirmed_tx_id, superseded_tx_id, &confirmed_spent_inputs)
+                .map(|_| OutputManagerResponse::ReplacedTransactionResolved),
             OutputManagerRequest::GetSpentOutputs => {
                 let outputs = self.fetch_spent_outputs()?;
                 Ok(OutputManagerResponse::SpentOutputs(outputs))
@@ -466,6 +484,11 @@ where
             OutputManagerRequest::AddKnownOneSidedPaymentScript(known_script) => self
                 .add_known_script(known_script)
                 .map(|_| OutputManagerResponse::AddKnownOneSidedPaymentScript),
+            OutputManagerRequest::GetKnownOneSidedPaymentScripts => {
+                Ok(OutputManagerResponse::KnownOneSidedPaymentScripts(
+                    self.resources.db.get_all_known_one_sided_payment_scripts()?,
+                ))
+            },
             OutputManagerRequest::ReinstateCancelledInboundTx(tx_id) => self
                 .reinstate_cancelled_inbound_transaction_outputs(tx_id)
                 .map(|_| OutputManagerResponse::ReinstatedCancelledInboundTx),
@@ -489,6 +512,39 @@ where
                     tx_id,
                 })
             },
+            OutputManagerRequest::CreateTransactionWithRecipientOutputs {
+                tx_id,
+                outputs,
+                fee_per_gram,
+                selection_criteria,
+                payment_id,
+            } => self
+                .create_transaction_with_recipient_outputs(tx_id, outputs, selection_criteria, fee_per_gram, payment_id)
+                .await
+                .map(OutputManagerResponse::TransactionWithRecipientOutputs),
+            OutputManagerRequest::PrepareUnsignedTransaction {
+                tx_id,
+                outputs,
+                recipients,
+                fee_per_gram,
+                selection_criteria,
+                payment_id,
+            } => self
+                .prepare_unsigned_transaction(tx_id, outputs, recipients, selection_criteria, fee_per_gram, payment_id)
+                .await
+                .map(|prepared| OutputManagerResponse::UnsignedTransaction(Box::new(prepared))),
+            OutputManagerRequest::SignUnsignedTransaction { bundle, outputs } => self
+                .sign_unsigned_transaction(*bundle, outputs)
+                .await
+                .map(OutputManagerResponse::TransactionWithRecipientOutputs),
+            OutputManagerRequest::ConfirmSignedTransaction {
+                tx_id,
+                transaction,
+                change,
+            } => self
+                .confirm_signed_transaction(tx_id, &transaction, change)
+                .await
+                .map(|_| OutputManagerResponse::SignedTransactionConfirmed),
             OutputManagerRequest::CreateClaimShaAtomicSwapTransaction(output_hash, pre_image, fee_per_gram) => {
                 self.claim_sha_atomic_swap_with_hash(output_hash, pre_image, fee_per_gram)
                     .await
@@ -998,6 +1054,7 @@ where
                         .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?,
             );
 
+        let replaces_tx = selection_criteria.filter.replaced_tx_id().is_some();
         let input_selection = self
             .select_utxos(
                 amount,
@@ -1081,10 +1138,14 @@ where
         }
 
         // The Transaction Protocol built successfully so we will pull the unspent outputs out of the unspent list and
-        // store them until the transaction times out OR is confirmed
-        self.resources
-            .db
-            .encumber_outputs(tx_id, input_selection.into_selected(), change_output)?;
+        // store them until the transaction times out OR is confirmed. The inputs of a transaction that is being
+        // replaced stay encumbered to it until the replacement is confirmed.
+        let inputs = if replaces_tx {
+            Vec::new()
+        } else {
+            input_selection.into_selected()
+        };
+        self.resources.db.encumber_outputs(tx_id, inputs, change_output)?;
 
         debug!(target: LOG_TARGET, "Prepared transaction (TxId: {}) to send", tx_id);
 
@@ -1221,6 +1282,342 @@ where
         Ok((tx_id, stp.into_transaction()?))
     }
 
+    /// Creates a transaction paying outputs that have already been built and signed for other parties, such as
+    /// one-sided payments to several recipients. Only the change output is added to this wallet's outputs.
+    async fn create_transaction_with_recipient_outputs(
+        &mut self,
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        payment_id: PaymentId,
+    ) -> Result<(MicroMinotari, Transaction), OutputManagerError> {
+        let input_selection = self
+            .select_utxos_for_recipient_outputs(&outputs, selection_criteria, fee_per_gram)
+            .await?;
+        let inputs = input_selection.iter().map(|uo| uo.wallet_output.clone()).collect();
+        let mut stp = self
+            .build_protocol_with_recipient_outputs(tx_id, inputs, outputs, fee_per_gram, 0, payment_id)
+            .await?;
+
+        let mut change_outputs = Vec::new();
+        if let Some(wallet_output) = stp.get_change_output()? {
+            change_outputs.push(
+                DbWalletOutput::from_wallet_output(
+                    wallet_output,
+                    &self.resources.key_manager,
+                    None,
+                    OutputSource::default(),
+                    Some(tx_id),
+                    None,
+                )
+                .await?,
+            );
+        }
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), change_outputs)?;
+        self.confirm_encumberance(tx_id)?;
+        let fee = stp.get_fee_amount()?;
+        stp.finalize(&self.resources.key_manager).await?;
+        let tx = stp.into_transaction()?;
+
+        Ok((fee, tx))
+    }
+
+    /// Selects and encumbers the inputs for an offline-signed transaction. No change output is encumbered, as the
+    /// change is only known once the transaction has been signed; it is added when the signed transaction is
+    /// confirmed.
+    async fn prepare_unsigned_transaction(
+        &mut self,
+        tx_id: TxId,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        recipients: Vec<UnsignedRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        payment_id: PaymentId,
+    ) -> Result<(UnsignedTransactionBundle, SenderTransactionProtocol), OutputManagerError> {
+        let input_selection = self
+            .select_utxos_for_recipient_outputs(&outputs, selection_criteria, fee_per_gram)
+            .await?;
+        let inputs = input_selection.iter().map(|uo| uo.wallet_output.clone()).collect();
+        let stp = self
+            .build_protocol_with_recipient_outputs(tx_id, inputs, outputs, fee_per_gram, 0, payment_id)
+            .await?;
+        let bundle = stp
+            .to_unsigned_bundle(recipients, fee_per_gram, &self.resources.key_manager)
+            .await?;
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), Vec::new())?;
+        self.confirm_encumberance(tx_id)?;
+        Ok((bundle, stp))
+    }
+
+    /// Signs an [UnsignedTransactionBundle] prepared by a wallet for the same spend key. The input keys are recovered
+    /// from the outputs being spent, so the inputs need not be known to this wallet.
+    async fn sign_unsigned_transaction(
+        &mut self,
+        bundle: UnsignedTransactionBundle,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+    ) -> Result<(MicroMinotari, Transaction), OutputManagerError> {
+        bundle.check_version()?;
+        let key_manager = &self.resources.key_manager;
+        let mut inputs = Vec::with_capacity(bundle.inputs.len());
+        for input in &bundle.inputs {
+            let commitment = input.output.commitment.to_hex();
+            let (spending_key_id, value, _) =
+                key_manager
+                    .try_output_key_recovery(&input.output, None)
+                    .await
+                    .map_err(|_| {
+                        OutputManagerError::OfflineSigningError(format!(
+                            "Input {} does not belong to this wallet",
+                            commitment
+                        ))
+                    })?;
+            if value != input.wallet_output.value {
+                return Err(OutputManagerError::OfflineSigningError(format!(
+                    "Input {} has value {}, not {}",
+                    commitment, value, input.wallet_output.value
+                )));
+            }
+            let script_key_id = key_manager
+                .find_script_key_id_from_commitment_mask_key_id(&spending_key_id, Some(&input.script_public_key))
+                .await?
+                .ok_or_else(|| {
+                    OutputManagerError::OfflineSigningError(format!(
+                        "The script key of input {} cannot be derived by this wallet",
+                        commitment
+                    ))
+                })?;
+            let mut wallet_output = input.wallet_output.clone();
+            wallet_output.spending_key_id = spending_key_id;
+            wallet_output.script_key_id = script_key_id;
+            inputs.push(wallet_output);
+        }
+
+        let mut stp = self
+            .build_protocol_with_recipient_outputs(
+                bundle.tx_id,
+                inputs,
+                outputs,
+                bundle.fee_per_gram,
+                bundle.lock_height,
+                bundle.payment_id.clone(),
+            )
+            .await?;
+        let fee = stp.get_fee_amount()?;
+        if fee != bundle.fee || stp.get_change_amount()? != bundle.change {
+            return Err(OutputManagerError::OfflineSigningError(format!(
+                "The signed transaction would pay fee {} and change {}, but {} and {} were prepared",
+                fee,
+                stp.get_change_amount()?,
+                bundle.fee,
+                bundle.change
+            )));
+        }
+        stp.finalize(&self.resources.key_manager).await?;
+        let tx = stp.into_transaction()?;
+
+        Ok((fee, tx))
+    }
+
+    async fn confirm_signed_transaction(
+        &mut self,
+        tx_id: TxId,
+        transaction: &Transaction,
+        change: MicroMinotari,
+    ) -> Result<(), OutputManagerError> {
+        let mut encumbered = self
+            .resources
+            .db
+            .fetch_outputs_by_tx_id(tx_id)?
+            .into_iter()
+            .filter(|o| o.status == OutputStatus::EncumberedToBeSpent && o.spent_in_tx_id == Some(tx_id))
+            .map(|o| o.commitment)
+            .collect::<Vec<_>>();
+        let mut spent = transaction
+            .body
+            .inputs()
+            .iter()
+            .map(|i| i.commitment().cloned())
+            .collect::<Result<Vec<_>, _>>()?;
+        encumbered.sort();
+        spent.sort();
+        if encumbered.is_empty() || encumbered != spent {
+            return Err(OutputManagerError::OfflineSigningError(format!(
+                "The signed transaction does not spend the {} inputs prepared for transaction {}",
+                encumbered.len(),
+                tx_id
+            )));
+        }
+        if change > MicroMinotari::zero() {
+            self.add_signed_transaction_change_output(tx_id, transaction, change)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// The signer derives its own change key, so the change output is found by recovering the outputs of the signed
+    /// transaction with this wallet's view key.
+    async fn add_signed_transaction_change_output(
+        &mut self,
+        tx_id: TxId,
+        transaction: &Transaction,
+        change: MicroMinotari,
+    ) -> Result<(), OutputManagerError> {
+        let key_manager = &self.resources.key_manager;
+        for output in transaction.body.outputs() {
+            let (spending_key_id, value, payment_id) = match key_manager.try_output_key_recovery(output, None).await {
+                Ok(recovered) => recovered,
+                Err(TransactionError::KeyManagerError(e)) => {
+                    return Err(TransactionError::KeyManagerError(e).into());
+                },
+                Err(_) => continue,
+            };
+            if value != change {
+                continue;
+            }
+            let script_public_key = match output.script.opcode(0) {
+                Some(Opcode::PushPubKey(public_key)) => public_key,
+                _ => continue,
+            };
+            let script_key_id = match key_manager
+                .find_script_key_id_from_commitment_mask_key_id(&spending_key_id, Some(script_public_key))
+                .await?
+            {
+                Some(script_key_id) => script_key_id,
+                None => continue,
+            };
+            let wallet_output = WalletOutput::new_with_rangeproof(
+                output.version,
+                value,
+                spending_key_id,
+                output.features.clone(),
+                output.script.clone(),
+                ExecutionStack::default(),
+                script_key_id,
+                output.sender_offset_public_key.clone(),
+                output.metadata_signature.clone(),
+                0,
+                output.covenant.clone(),
+                output.encrypted_data.clone(),
+                output.minimum_value_promise,
+                output.proof.clone(),
+                payment_id,
+            );
+            let db_output = DbWalletOutput::from_wallet_output(
+                wallet_output,
+                key_manager,
+                None,
+                OutputSource::default(),
+                Some(tx_id),
+                None,
+            )
+            .await?;
+            return match self.resources.db.add_output_to_be_received(tx_id, db_output) {
+                // The transaction is being broadcast again
+                Ok(()) | Err(OutputManagerStorageError::DuplicateOutput) => Ok(()),
+                Err(e) => Err(e.into()),
+            };
+        }
+        Err(OutputManagerError::OfflineSigningError(format!(
+            "The signed transaction has no change output of {} for this wallet",
+            change
+        )))
+    }
+
+    async fn select_utxos_for_recipient_outputs(
+        &mut self,
+        outputs: &[(WalletOutput, TariKeyId)],
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+    ) -> Result<UtxoSelection, OutputManagerError> {
+        if outputs.is_empty() {
+            return Err(OutputManagerError::BuildError(
+                "A transaction needs at least one recipient output".to_string(),
+            ));
+        }
+        let total_value = outputs
+            .iter()
+            .map(|(o, _)| o.value)
+            .try_fold(MicroMinotari::zero(), |acc, v| acc.checked_add(v))
+            .ok_or_else(|| OutputManagerError::BuildError("Total output amount overflow".to_string()))?;
+        let weighting = self.resources.consensus_constants.transaction_weight_params();
+        let mut features_and_scripts_byte_size = 0;
+        for (output, _) in outputs {
+            features_and_scripts_byte_size += weighting.round_up_features_and_scripts_size(
+                output
+                    .features_and_scripts_byte_size()
+                    .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?,
+            );
+        }
+
+        self.select_utxos(
+            total_value,
+            selection_criteria,
+            fee_per_gram,
+            outputs.len(),
+            features_and_scripts_byte_size,
+        )
+        .await
+    }
+
+    async fn build_protocol_with_recipient_outputs(
+        &self,
+        tx_id: TxId,
+        inputs: Vec<WalletOutput>,
+        outputs: Vec<(WalletOutput, TariKeyId)>,
+        fee_per_gram: MicroMinotari,
+        lock_height: u64,
+        payment_id: PaymentId,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        let mut builder = SenderTransactionProtocol::builder(
+            self.resources.consensus_constants.clone(),
+            self.resources.key_manager.clone(),
+        );
+        builder
+            .with_lock_height(lock_height)
+            .with_fee_per_gram(fee_per_gram)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_kernel_features(KernelFeatures::empty())
+            .with_sender_address(self.resources.one_sided_tari_address.clone())
+            .with_payment_id(payment_id)
+            .with_tx_id(tx_id);
+
+        for input in inputs {
+            builder.with_input(input).await?;
+        }
+        for (output, sender_offset_key_id) in outputs {
+            builder
+                .with_output(output, sender_offset_key_id)
+                .await
+                .map_err(|e| OutputManagerError::BuildError(e.to_string()))?;
+        }
+
+        let (change_commitment_mask_key_id, change_script_public_key) = self
+            .resources
+            .key_manager
+            .get_next_commitment_mask_and_script_key()
+            .await?;
+        builder.with_change_data(
+            script!(PushPubKey(Box::new(change_script_public_key.pub_key.clone())))?,
+            ExecutionStack::default(),
+            change_script_public_key.key_id.clone(),
+            change_commitment_mask_key_id.key_id,
+            Covenant::default(),
+            self.resources.interactive_tari_address.clone(),
+        );
+
+        builder
+            .build()
+            .await
+            .map_err(|e| OutputManagerError::BuildError(e.message))
+    }
+
     async fn pre_mine_script_key_from_payment_id(
         &self,
         payment_id: PaymentId,
@@ -2034,6 +2431,43 @@ where
         Ok(self.resources.db.cancel_pending_transaction_outputs(tx_id)?)
     }
 
+    /// Replace a pending transaction with one that spends (some of) its inputs
+    pub fn replace_pending_transaction(
+        &mut self,
+        original_tx_id: TxId,
+        replacement_tx_id: TxId,
+        spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerError> {
+        debug!(
+            target: LOG_TARGET,
+            "Replacing pending transaction outputs for TxId: {} with TxId: {}", original_tx_id, replacement_tx_id
+        );
+        Ok(self
+            .resources
+            .db
+            .replace_pending_transaction_outputs(original_tx_id, replacement_tx_id, spent_inputs)?)
+    }
+
+    /// Settle the outputs of a replaced transaction and its replacement once one of them is confirmed
+    pub fn resolve_replaced_transaction(
+        &mut self,
+        confirmed_tx_id: TxId,
+        superseded_tx_id: TxId,
+        confirmed_spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerError> {
+        debug!(
+            target: LOG_TARGET,
+            "Resolving replaced transaction outputs, TxId: {} confirmed and TxId: {} superseded",
+            confirmed_tx_id,
+            superseded_tx_id
+        );
+        Ok(self.resources.db.resolve_replaced_transaction_outputs(
+            confirmed_tx_id,
+            superseded_tx_id,
+            confirmed_spent_inputs,
+        )?)
+    }
+
     /// Restore the pending transaction encumberance and output for an inbound transaction that was previously
     /// cancelled.
     fn reinstate_cancelled_inbound_transaction_outputs(&mut self, tx_id: TxId) -> Result<(), OutputManagerError> {
@@ -2082,7 +2516,7 @@ where
         );
         let tip_height = chain_metadata.as_ref().map(|m| m.best_block_height());
         let start_new = Instant::now();
-        let uo = self
+        let mut uo = self
             .resources
             .db
             .fetch_unspent_outputs_for_spending(&selection_criteria, amount, tip_height)?;
@@ -2119,6 +2553,105 @@ where
 
         trace!(target: LOG_TARGET, "We found {} UTXOs to select from", uo_len);
 
+        if matches!(
+            selection_criteria.ordering,
+            UtxoSelectionOrdering::BranchAndBound | UtxoSelectionOrdering::Random
+        ) {
+            let values = uo.iter().map(|o| o.wallet_output.value.as_u64()).collect::<Vec<_>>();
+            let base_fee = fee_calc.calculate(
+                fee_per_gram,
+                1,
+                0,
+                num_outputs,
+                total_output_features_and_scripts_byte_size,
+            );
+            let cost_per_input = fee_calc.calculate(fee_per_gram, 0, 1, 0, 0);
+            // The builder adds a change output paying to a single public key, so that is what a change output costs
+            let change_features_and_scripts_size = fee_calc.weighting().round_up_features_and_scripts_size(
+                script!(PushPubKey(Box::default()))?
+                    .get_serialized_size()
+                    .map_err(|e| OutputManagerError::ConversionError(e.to_string()))? +
+                    output_features_estimate
+                        .get_serialized_size()
+                        .map_err(|e| OutputManagerError::ConversionError(e.to_string()))?,
+            );
+            let cost_of_change = fee_calc.calculate(fee_per_gram, 0, 0, 1, change_features_and_scripts_size);
+            let target = amount + base_fee;
+
+            if selection_criteria.ordering == UtxoSelectionOrdering::BranchAndBound {
+                if let Some(selected) = select_branch_and_bound(
+                    &values,
+                    target.as_u64(),
+                    cost_per_input.as_u64(),
+                    cost_of_change.as_u64(),
+                ) {
+                    let mut selected_flags = vec![false; uo.len()];
+                    for i in selected {
+                        selected_flags[i] = true;
+                    }
+                    let mut flags = selected_flags.into_iter();
+                    uo.retain(|_| flags.next().unwrap_or(false));
+                    let utxos_total_value = uo.iter().map(|o| o.wallet_output.value).sum::<MicroMinotari>();
+                    let fee_with_change = fee_calc.calculate(
+                        fee_per_gram,
+                        1,
+                        uo.len(),
+                        num_outputs + 1,
+                        total_output_features_and_scripts_byte_size + change_features_and_scripts_size,
+                    );
+                    debug!(
+                        target: LOG_TARGET,
+                        "select_utxos: branch-and-bound selected {} of {} UTXOs without change (excess {} given to \
+                         fee)",
+                        uo.len(),
+                        uo_len,
+                        utxos_total_value - amount - base_fee - cost_per_input * uo.len() as u64
+                    );
+                    return Ok(UtxoSelection {
+                        requires_change_output: false,
+                        total_value: utxos_total_value,
+                        // Whatever is not paid to the recipient goes to the fee, as there is no change output
+                        fee_without_change: utxos_total_value - amount,
+                        fee_with_change,
+                        utxos: uo,
+                    });
+                }
+                debug!(
+                    target: LOG_TARGET,
+                    "select_utxos: branch-and-bound found no changeless selection, falling back to default ordering"
+                );
+                order_utxos_by_default(&mut uo, amount);
+            } else {
+                let target_with_change = target + cost_of_change;
+                match select_random(
+                    &values,
+                    target_with_change.as_u64(),
+                    cost_per_input.as_u64(),
+                    &mut OsRng,
+                ) {
+                    Some(selected) => {
+                        // Move the randomly drawn UTXOs to the front in the order they were drawn, so that the
+                        // accumulation below picks exactly those
+                        let mut rank = vec![usize::MAX; uo.len()];
+                        for (pos, i) in selected.into_iter().enumerate() {
+                            rank[i] = pos;
+                        }
+                        let mut ranked = uo.into_iter().zip(rank).collect::<Vec<_>>();
+                        ranked.sort_by_key(|(_, rank)| *rank);
+                        uo = ranked.into_iter().map(|(o, _)| o).collect();
+                    },
+                    None => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "select_utxos: random selection could not cover the amount, falling back to default \
+                             ordering"
+                        );
+                        order_utxos_by_default(&mut uo, amount);
+                    },
+                }
+            }
+        }
+
         let mut requires_change_output = false;
         let mut utxos_total_value = MicroMinotari::from(0);
         let mut fee_without_change = MicroMinotari::from(0);
@@ -3438,6 +3971,16 @@ impl fmt::Display for Balance {
     }
 }
 
+/// Orders UTXOs the way the `Default` selection ordering does: largest first if the amount is larger than any single
+/// UTXO, to reduce the number of inputs, otherwise smallest first.
+fn order_utxos_by_default(utxos: &mut [DbWalletOutput], amount: MicroMinotari) {
+    let max = utxos.iter().map(|o| o.wallet_output.value).max();
+    match max {
+        Some(max) if amount > max => utxos.sort_by(|a, b| b.wallet_output.value.cmp(&a.wallet_output.value)),
+        _ => utxos.sort_by(|a, b| a.wallet_output.value.cmp(&b.wallet_output.value)),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct UtxoSelection {
     utxos: Vec<DbWalletOutput>,
@@ -71,6 +71,25 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
     /// `UnspentOutputs` pool. The `outputs_to_be_received`'` will be marked as cancelled inbound outputs in case they
     /// need to be recovered.
     fn cancel_pending_transaction(&self, tx_id: TxId) -> Result<(), OutputManagerStorageError>;
+    /// This method atomically hands the `spent_inputs` of the original transaction over to the replacement, cancels
+    /// the outputs the original would have received and confirms the encumberances of the replacement. Any other inputs
+    /// of the original stay encumbered to it, as the original can still be mined until the replacement is confirmed.
+    fn replace_pending_transaction(
+        &self,
+        original_tx_id: TxId,
+        replacement_tx_id: TxId,
+        spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError>;
+    /// Once either a replaced transaction or its replacement is confirmed, this method cancels the outputs the
+    /// `superseded_tx_id` would have received, restores the cancelled outputs of the `confirmed_tx_id`, hands the
+    /// `confirmed_spent_inputs` over to the confirmed transaction and moves any other inputs of the superseded
+    /// transaction back into the `UnspentOutputs` pool.
+    fn resolve_replaced_transaction(
+        &self,
+        confirmed_tx_id: TxId,
+        superseded_tx_id: TxId,
+        confirmed_spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError>;
     /// This method will update an output's metadata signature, akin to 'finalize output'
     fn update_output_metadata_signature(&self, output: &TransactionOutput) -> Result<(), OutputManagerStorageError>;
     /// If an invalid output is found to be valid this function will turn it back into an unspent output
@@ -214,6 +214,30 @@ where T: OutputManagerBackend + 'static
         self.db.cancel_pending_transaction(tx_id)
     }
 
+    /// When a pending transaction is replaced, the inputs spent by the replacement are handed over to it and the
+    /// original transaction's outputs are cancelled in a single step.
+    pub fn replace_pending_transaction_outputs(
+        &self,
+        original_tx_id: TxId,
+        replacement_tx_id: TxId,
+        spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError> {
+        self.db
+            .replace_pending_transaction(original_tx_id, replacement_tx_id, spent_inputs)
+    }
+
+    /// When either a replaced transaction or its replacement is confirmed, the outputs of the other one are cancelled
+    /// and the inputs that only it spent are released.
+    pub fn resolve_replaced_transaction_outputs(
+        &self,
+        confirmed_tx_id: TxId,
+        superseded_tx_id: TxId,
+        confirmed_spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError> {
+        self.db
+            .resolve_replaced_transaction(confirmed_tx_id, superseded_tx_id, confirmed_spent_inputs)
+    }
+
     pub fn fetch_all_unspent_outputs(&self) -> Result<Vec<DbWalletOutput>, OutputManagerStorageError> {
         let result = match self.db.fetch(&DbKey::UnspentOutputs)? {
             Some(DbValue::UnspentOutputs(outputs)) => outputs,
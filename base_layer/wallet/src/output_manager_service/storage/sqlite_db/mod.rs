@@ -1032,6 +1032,147 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
         Ok(())
     }
 
+    fn replace_pending_transaction(
+        &self,
+        original_tx_id: TxId,
+        replacement_tx_id: TxId,
+        spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError> {
+        let start = Instant::now();
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let acquire_lock = start.elapsed();
+
+        conn.transaction::<_, _, _>(|conn| {
+            let outputs = OutputSql::find_by_tx_id_and_encumbered(original_tx_id, conn)?;
+            let mut handed_over = 0;
+            for output in &outputs {
+                if output.received_in_tx_id == Some(original_tx_id.as_i64_wrapped()) {
+                    output.update(
+                        UpdateOutput {
+                            status: Some(OutputStatus::CancelledInbound),
+                            last_validation_timestamp: Some(Some(
+                                DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap().naive_utc(),
+                            )),
+                            ..Default::default()
+                        },
+                        conn,
+                    )?;
+                } else if spent_inputs
+                    .iter()
+                    .any(|c| c.as_bytes() == output.commitment.as_slice())
+                {
+                    output.update(
+                        UpdateOutput {
+                            status: Some(OutputStatus::EncumberedToBeSpent),
+                            spent_in_tx_id: Some(Some(replacement_tx_id)),
+                            ..Default::default()
+                        },
+                        conn,
+                    )?;
+                    handed_over += 1;
+                }
+                // Any other inputs stay encumbered to the original, which can still be mined, until either it or the
+                // replacement is confirmed
+            }
+            // Every input of the replacement must have been encumbered to the original transaction
+            if handed_over != spent_inputs.len() {
+                return Err(OutputManagerStorageError::OutputAlreadySpent);
+            }
+
+            update_outputs_with_tx_id_and_status_to_new_status(
+                conn,
+                replacement_tx_id,
+                OutputStatus::ShortTermEncumberedToBeReceived,
+                OutputStatus::EncumberedToBeReceived,
+            )
+        })?;
+
+        if start.elapsed().as_millis() > 0 {
+            trace!(
+                target: LOG_TARGET,
+                "sqlite profile - replace_pending_transaction (TxId: {} -> {}): lock {} + db_op {} = {} ms",
+                original_tx_id,
+                replacement_tx_id,
+                acquire_lock.as_millis(),
+                (start.elapsed() - acquire_lock).as_millis(),
+                start.elapsed().as_millis()
+            );
+        }
+
+        Ok(())
+    }
+
+    fn resolve_replaced_transaction(
+        &self,
+        confirmed_tx_id: TxId,
+        superseded_tx_id: TxId,
+        confirmed_spent_inputs: &[Commitment],
+    ) -> Result<(), OutputManagerStorageError> {
+        let start = Instant::now();
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let acquire_lock = start.elapsed();
+
+        conn.transaction::<_, _, _>(|conn| {
+            let outputs = OutputSql::find_by_tx_id_and_encumbered(superseded_tx_id, conn)?;
+            for output in &outputs {
+                if output.received_in_tx_id == Some(superseded_tx_id.as_i64_wrapped()) {
+                    output.update(
+                        UpdateOutput {
+                            status: Some(OutputStatus::CancelledInbound),
+                            last_validation_timestamp: Some(Some(
+                                DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap().naive_utc(),
+                            )),
+                            ..Default::default()
+                        },
+                        conn,
+                    )?;
+                } else if confirmed_spent_inputs
+                    .iter()
+                    .any(|c| c.as_bytes() == output.commitment.as_slice())
+                {
+                    output.update(
+                        UpdateOutput {
+                            spent_in_tx_id: Some(Some(confirmed_tx_id)),
+                            ..Default::default()
+                        },
+                        conn,
+                    )?;
+                } else {
+                    output.update(
+                        UpdateOutput {
+                            status: Some(OutputStatus::Unspent),
+                            spent_in_tx_id: Some(None),
+                            ..Default::default()
+                        },
+                        conn,
+                    )?;
+                }
+            }
+
+            // The outputs of a replaced transaction were cancelled when it was replaced
+            update_outputs_with_tx_id_and_status_to_new_status(
+                conn,
+                confirmed_tx_id,
+                OutputStatus::CancelledInbound,
+                OutputStatus::EncumberedToBeReceived,
+            )
+        })?;
+
+        if start.elapsed().as_millis() > 0 {
+            trace!(
+                target: LOG_TARGET,
+                "sqlite profile - resolve_replaced_transaction (TxId: {} over {}): lock {} + db_op {} = {} ms",
+                confirmed_tx_id,
+                superseded_tx_id,
+                acquire_lock.as_millis(),
+                (start.elapsed() - acquire_lock).as_millis(),
+                start.elapsed().as_millis()
+            );
+        }
+
+        Ok(())
+    }
+
     // This is typically used by a receiver after the finalized transaction has been broadcast/returned by the sender
     // as the sender has to finalize the signature that was partially constructed by the receiver
     fn update_output_metadata_signature(&self, output: &TransactionOutput) -> Result<(), OutputManagerStorageError> {
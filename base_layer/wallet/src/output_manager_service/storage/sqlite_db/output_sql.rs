@@ -209,10 +209,20 @@ impl OutputSql {
 
         let mut query = outputs::table
             .into_boxed()
-            .filter(outputs::status.eq(OutputStatus::Unspent as i32))
             .filter(outputs::value.gt(i64_value))
             .order_by(outputs::spending_priority.desc());
 
+        // The inputs of a transaction that is being replaced are still encumbered to it
+        query = match selection_criteria.filter.replaced_tx_id() {
+            Some(tx_id) => query.filter(outputs::spent_in_tx_id.eq(tx_id.as_i64_wrapped())).filter(
+                outputs::status.eq_any::<Vec<i32>>(vec![
+                    OutputStatus::EncumberedToBeSpent as i32,
+                    OutputStatus::ShortTermEncumberedToBeSpent as i32,
+                ]),
+            ),
+            None => query.filter(outputs::status.eq(OutputStatus::Unspent as i32)),
+        };
+
         // NOTE: Safe mode presets `script_lock_height` and `maturity` filters for all queries
         if selection_criteria.mode == UtxoSelectionMode::Safe {
             query = query
@@ -242,6 +252,8 @@ impl OutputSql {
                     ),
                 };
             },
+
+            UtxoSelectionFilter::ReplacingTransaction { .. } => {},
         }
 
         for exclude in &selection_criteria.excluding {
@@ -251,6 +263,11 @@ impl OutputSql {
         query = match selection_criteria.ordering {
             UtxoSelectionOrdering::SmallestFirst => query.then_order_by(outputs::value.asc()),
             UtxoSelectionOrdering::LargestFirst => query.then_order_by(outputs::value.desc()),
+            // The final selection for these is made in memory; when more candidates exist than the input limit
+            // allows, the largest ones are the most likely to be useful
+            UtxoSelectionOrdering::BranchAndBound | UtxoSelectionOrdering::Random => {
+                query.then_order_by(outputs::value.desc())
+            },
             UtxoSelectionOrdering::Default => {
                 // NOTE: keeping filtering by `script_lock_height` and `maturity` for all modes
                 // lets get the max value for all utxos
@@ -32,7 +32,15 @@ use tari_comms::protocol::rpc::RpcError::RequestFailed;
 use tari_core::{
     base_node::rpc::BaseNodeWalletRpcClient,
     blocks::BlockHeader,
-    proto::base_node::{QueryDeletedRequest, UtxoQueryRequest},
+    proto::base_node::{
+        GetOutputSmtProofsRequest,
+        QueryDeletedData,
+        QueryDeletedRequest,
+        QueryDeletedResponse,
+        UtxoQueryRequest,
+    },
+    transactions::transaction_components::TransactionOutput,
+    OutputSmtProof,
 };
 use tari_utilities::hex::Hex;
 use tokio::sync::watch;
@@ -230,6 +238,11 @@ where
                     ),
                 ));
             }
+            if self.config.verify_output_smt_proofs {
+                self.verify_spent_statuses(wallet_client, batch, &response)
+                    .await
+                    .for_protocol(self.operation_id)?;
+            }
 
             let mut unmined_and_invalid = Vec::with_capacity(batch.len());
             let mut unspent = Vec::with_capacity(batch.len());
@@ -297,6 +310,112 @@ where
         Ok(())
     }
 
+    /// Confirms the spent status that the base node reported for each mined output in the batch with output SMT
+    /// proofs against the output Merkle root of the tip the statuses were reported at. The wallet hashes the header
+    /// that comes with the proofs itself and only accepts it if it is that tip. A proof that is refused or missing
+    /// fails the validation, so no reported status is accepted without one.
+    #[allow(clippy::too_many_lines)]
+    async fn verify_spent_statuses(
+        &self,
+        wallet_client: &mut BaseNodeWalletRpcClient,
+        batch: &[DbWalletOutput],
+        response: &QueryDeletedResponse,
+    ) -> Result<(), OutputManagerError> {
+        // Outputs the base node does not know of are revalidated from scratch, so there is nothing to prove for them
+        let mined: Vec<(&DbWalletOutput, &QueryDeletedData)> = batch
+            .iter()
+            .zip(response.data.iter())
+            .filter(|(_, data)| !data.block_mined_in.is_empty())
+            .collect();
+        if mined.is_empty() {
+            return Ok(());
+        }
+
+        let tip_hash = BlockHash::try_from(response.best_block_hash.clone()).map_err(|_| {
+            OutputManagerError::InconsistentBaseNodeDataError("Base node sent a malformed best block hash")
+        })?;
+        let proofs_response = wallet_client
+            .get_output_smt_proofs(GetOutputSmtProofsRequest {
+                output_hashes: mined.iter().map(|(output, _)| output.hash.to_vec()).collect(),
+                header_hash: tip_hash.to_vec(),
+            })
+            .await
+            .map_err(|e| {
+                warn!(
+                    target: LOG_TARGET,
+                    "Base node did not generate output SMT proofs at its tip {}: {} (Operation ID: {})",
+                    tip_hash.to_hex(),
+                    e,
+                    self.operation_id
+                );
+                e
+            })?;
+        let header: BlockHeader = proofs_response
+            .header
+            .ok_or(OutputManagerError::InconsistentBaseNodeDataError(
+                "Base node did not send the header of the output SMT proofs",
+            ))?
+            .try_into()
+            .map_err(|s| OutputManagerError::InvalidMessageError(format!("Could not convert block header: {}", s)))?;
+        if header.hash() != tip_hash || header.height != response.best_block_height {
+            return Err(OutputManagerError::InconsistentBaseNodeDataError(
+                "Base node sent output SMT proofs against a header that is not its tip",
+            ));
+        }
+
+        let mut proofs = HashMap::new();
+        for proof in &proofs_response.proofs {
+            if let Ok(hash) = FixedHash::try_from(proof.output_hash.clone()) {
+                proofs.insert(hash, proof);
+            }
+        }
+        for (output, data) in mined {
+            if data.mined_at_height > header.height || data.height_deleted_at > header.height {
+                return Err(OutputManagerError::InconsistentBaseNodeDataError(
+                    "Base node reported an output as mined or spent above its tip",
+                ));
+            }
+            let proof = proofs
+                .get(&output.hash)
+                .ok_or(OutputManagerError::InconsistentBaseNodeDataError(
+                    "Base node did not send an output SMT proof for every mined output",
+                ))
+                .and_then(|proof| OutputSmtProof::try_from(*proof).map_err(OutputManagerError::InvalidMessageError))?;
+            let verified = if data.height_deleted_at == 0 {
+                let smt_hash = TransactionOutput::smt_hash_from_parts(
+                    output.wallet_output.version,
+                    &output.hash,
+                    data.mined_at_height,
+                );
+                proof.proves_unspent(&output.commitment, &smt_hash, &header.output_mr)
+            } else {
+                proof.proves_not_unspent(&output.commitment, &header.output_mr)
+            };
+            if !verified {
+                warn!(
+                    target: LOG_TARGET,
+                    "Output SMT proof for output comm:{}: hash {} does not match the spent status reported by the \
+                     base node at height {} (Operation ID: {})",
+                    output.commitment.to_hex(),
+                    output.hash.to_hex(),
+                    header.height,
+                    self.operation_id
+                );
+                return Err(OutputManagerError::InconsistentBaseNodeDataError(
+                    "Base node sent an output SMT proof that does not match the reported spent status",
+                ));
+            }
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Verified the spent status of {} outputs with output SMT proofs at height {} (Operation ID: {})",
+            proofs_response.proofs.len(),
+            header.height,
+            self.operation_id
+        );
+        Ok(())
+    }
+
     async fn update_unconfirmed_outputs(
         &self,
         wallet_client: &mut BaseNodeWalletRpcClient,
@@ -49,6 +49,7 @@ use tari_core::{
             Transaction,
             TransactionOutput,
         },
+        transaction_protocol::unsigned_transaction::{SignedTransactionBundle, UnsignedTransactionBundle},
     },
 };
 use tari_crypto::ristretto::pedersen::PedersenCommitment;
@@ -74,6 +75,32 @@ use crate::{
     OperationId,
 };
 
+/// Determines the fee per gram paid by an outgoing transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeRate {
+    /// Pay the given fee per gram
+    FeePerGram(MicroMinotari),
+    /// Pay the fee per gram that the base node estimates is required to be mined within the given number of blocks
+    ConfirmationTarget(u64),
+}
+
+impl Display for FeeRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeRate::FeePerGram(fee_per_gram) => write!(f, "{} per gram", fee_per_gram),
+            FeeRate::ConfirmationTarget(blocks) => write!(f, "confirmation within {} block(s)", blocks),
+        }
+    }
+}
+
+/// A single payment in a batch one-sided transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRecipient {
+    pub destination: TariAddress,
+    pub amount: MicroMinotari,
+    pub payment_id: PaymentId,
+}
+
 /// API Request enum
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
@@ -92,7 +119,7 @@ pub enum TransactionServiceRequest {
         amount: MicroMinotari,
         selection_criteria: UtxoSelectionCriteria,
         output_features: Box<OutputFeatures>,
-        fee_per_gram: MicroMinotari,
+        fee_rate: FeeRate,
         payment_id: PaymentId,
     },
     BurnTari {
@@ -166,6 +193,22 @@ pub enum TransactionServiceRequest {
         fee_per_gram: MicroMinotari,
         payment_id: PaymentId,
     },
+    /// Pays all recipients one-sided, to their stealth addresses, in a single transaction with one change output
+    SendBatchOneSidedTransaction {
+        recipients: Vec<BatchRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+    },
+    /// Selects inputs to pay the recipients one-sided and prepares the transaction for signing by a wallet holding
+    /// the spend key
+    PrepareOfflineTransaction {
+        recipients: Vec<BatchRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        payment_id: PaymentId,
+    },
+    SignOfflineTransaction(Box<UnsignedTransactionBundle>),
+    BroadcastSignedTransaction(Box<SignedTransactionBundle>),
     ScrapeWallet {
         destination: TariAddress,
         fee_per_gram: MicroMinotari,
@@ -178,6 +221,10 @@ pub enum TransactionServiceRequest {
         PaymentId,
     ),
     CancelTransaction(TxId),
+    BumpFee {
+        tx_id: TxId,
+        new_fee_per_gram: MicroMinotari,
+    },
     ImportUtxoWithStatus {
         amount: MicroMinotari,
         source_address: TariAddress,
@@ -354,10 +401,28 @@ impl fmt::Display for TransactionServiceRequest {
                 "SendOneSidedToStealthAddressTransaction (to {}, {}, {})",
                 destination, amount, payment_id
             ),
+            Self::SendBatchOneSidedTransaction { recipients, .. } => write!(
+                f,
+                "SendBatchOneSidedTransaction ({} recipients, total {})",
+                recipients.len(),
+                recipients.iter().map(|r| r.amount).sum::<MicroMinotari>()
+            ),
+            Self::PrepareOfflineTransaction { recipients, .. } => write!(
+                f,
+                "PrepareOfflineTransaction ({} recipients, total {})",
+                recipients.len(),
+                recipients.iter().map(|r| r.amount).sum::<MicroMinotari>()
+            ),
+            Self::SignOfflineTransaction(bundle) => write!(f, "SignOfflineTransaction ({})", bundle.tx_id),
+            Self::BroadcastSignedTransaction(bundle) => write!(f, "BroadcastSignedTransaction ({})", bundle.tx_id),
             Self::SendShaAtomicSwapTransaction(k, _, v, _, id) => {
                 write!(f, "SendShaAtomicSwapTransaction (to {}, {}, {})", k, v, id)
             },
             Self::CancelTransaction(t) => write!(f, "CancelTransaction ({})", t),
+            Self::BumpFee {
+                tx_id,
+                new_fee_per_gram,
+            } => write!(f, "BumpFee ({}, {} per gram)", tx_id, new_fee_per_gram),
             Self::ImportUtxoWithStatus {
                 amount,
                 source_address,
@@ -434,6 +499,8 @@ pub enum TransactionServiceResponse {
     CompletedTransactionValidityChanged,
     ShaAtomicSwapTransactionSent(Box<(TxId, PublicKey, TransactionOutput)>),
     FeePerGramStatsPerBlock(FeePerGramStatsResponse),
+    UnsignedTransaction(Box<UnsignedTransactionBundle>),
+    SignedTransaction(Box<SignedTransactionBundle>),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
@@ -618,6 +685,28 @@ impl TransactionServiceHandle {
         output_features: OutputFeatures,
         fee_per_gram: MicroMinotari,
         payment_id: PaymentId,
+    ) -> Result<TxId, TransactionServiceError> {
+        self.send_transaction_with_fee_rate(
+            destination,
+            amount,
+            selection_criteria,
+            output_features,
+            FeeRate::FeePerGram(fee_per_gram),
+            payment_id,
+        )
+        .await
+    }
+
+    /// Sends a transaction, either at a fixed fee per gram or at the fee per gram the base node estimates is needed to
+    /// meet a confirmation target.
+    pub async fn send_transaction_with_fee_rate(
+        &mut self,
+        destination: TariAddress,
+        amount: MicroMinotari,
+        selection_criteria: UtxoSelectionCriteria,
+        output_features: OutputFeatures,
+        fee_rate: FeeRate,
+        payment_id: PaymentId,
     ) -> Result<TxId, TransactionServiceError> {
         match self
             .handle
@@ -626,7 +715,7 @@ impl TransactionServiceHandle {
                 amount,
                 selection_criteria,
                 output_features: Box::new(output_features),
-                fee_per_gram,
+                fee_rate,
                 payment_id,
             })
             .await??
@@ -738,6 +827,84 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Pays several recipients one-sided in a single transaction. Each recipient output carries its own payment id,
+    /// and any change is returned in a single output.
+    pub async fn send_batch_one_sided_transaction(
+        &mut self,
+        recipients: Vec<BatchRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendBatchOneSidedTransaction {
+                recipients,
+                selection_criteria,
+                fee_per_gram,
+            })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepares a one-sided payment to the recipients for signing by a wallet holding the spend key. The selected
+    /// inputs are encumbered until the signed transaction is broadcast, or the returned transaction id is cancelled.
+    pub async fn prepare_offline_transaction(
+        &mut self,
+        recipients: Vec<BatchRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        payment_id: PaymentId,
+    ) -> Result<UnsignedTransactionBundle, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::PrepareOfflineTransaction {
+                recipients,
+                selection_criteria,
+                fee_per_gram,
+                payment_id,
+            })
+            .await??
+        {
+            TransactionServiceResponse::UnsignedTransaction(bundle) => Ok(*bundle),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Signs a transaction prepared by [prepare_offline_transaction](Self::prepare_offline_transaction). This does not
+    /// need a connection to the network.
+    pub async fn sign_offline_transaction(
+        &mut self,
+        bundle: UnsignedTransactionBundle,
+    ) -> Result<SignedTransactionBundle, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SignOfflineTransaction(Box::new(bundle)))
+            .await??
+        {
+            TransactionServiceResponse::SignedTransaction(bundle) => Ok(*bundle),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Broadcasts a transaction signed by [sign_offline_transaction](Self::sign_offline_transaction) from the wallet
+    /// that prepared it
+    pub async fn broadcast_signed_transaction(
+        &mut self,
+        bundle: SignedTransactionBundle,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::BroadcastSignedTransaction(Box::new(bundle)))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     /// Burns the given amount of Tari from the wallet
     pub async fn burn_tari(
         &mut self,
@@ -911,6 +1078,26 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Replace an unconfirmed outbound transaction with one paying `new_fee_per_gram`. Returns the TxId of the
+    /// replacement transaction; the original is marked as replaced.
+    pub async fn bump_fee(
+        &mut self,
+        tx_id: TxId,
+        new_fee_per_gram: MicroMinotari,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::BumpFee {
+                tx_id,
+                new_fee_per_gram,
+            })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_pending_inbound_transactions(
         &mut self,
     ) -> Result<HashMap<TxId, InboundTransaction>, TransactionServiceError> {
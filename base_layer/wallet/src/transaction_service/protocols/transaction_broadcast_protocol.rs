@@ -105,6 +105,15 @@ where
             let completed_tx = match self.resources.db.get_completed_transaction(self.tx_id) {
                 Ok(tx) => tx,
                 Err(e) => {
+                    if let Ok(tx) = self.resources.db.get_completed_transaction_cancelled_or_not(self.tx_id) {
+                        if tx.cancelled == Some(TxCancellationReason::Replaced) {
+                            debug!(
+                                target: LOG_TARGET,
+                                "Transaction (TxId: {}) has been replaced and will stop being broadcast", self.tx_id
+                            );
+                            return Ok(self.tx_id);
+                        }
+                    }
                     error!(
                         target: LOG_TARGET,
                         "Cannot find Completed Transaction (TxId: {}) referred to by this Broadcast protocol: {:?}",
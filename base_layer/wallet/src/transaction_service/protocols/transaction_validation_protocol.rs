@@ -44,12 +44,14 @@ use tari_utilities::hex::Hex;
 
 use crate::{
     connectivity_service::WalletConnectivityInterface,
+    output_manager_service::handle::OutputManagerHandle,
     transaction_service::{
         config::TransactionServiceConfig,
         error::{TransactionServiceError, TransactionServiceProtocolError, TransactionServiceProtocolErrorExt},
         handle::{TransactionEvent, TransactionEventSender},
         storage::{
             database::{TransactionBackend, TransactionDatabase},
+            models::TxCancellationReason,
             sqlite_db::UnconfirmedTransactionInfo,
         },
     },
@@ -64,6 +66,7 @@ pub struct TransactionValidationProtocol<TTransactionBackend, TWalletConnectivit
     connectivity: TWalletConnectivity,
     config: TransactionServiceConfig,
     event_publisher: TransactionEventSender,
+    output_manager_handle: OutputManagerHandle,
 }
 
 #[allow(unused_variables)]
@@ -78,6 +81,7 @@ where
         connectivity: TWalletConnectivity,
         config: TransactionServiceConfig,
         event_publisher: TransactionEventSender,
+        output_manager_handle: OutputManagerHandle,
     ) -> Self {
         Self {
             operation_id,
@@ -85,6 +89,7 @@ where
             connectivity,
             config,
             event_publisher,
+            output_manager_handle,
         }
     }
 
@@ -138,10 +143,14 @@ where
                     *mined_timestamp,
                 )
                 .await?;
+                if *num_confirmations >= self.config.num_confirmations_required {
+                    self.resolve_replaced_transactions(mined_tx).await?;
+                }
                 state_changed = true;
             }
             if let Some((tip_height, tip_block, tip_mined_timestamp)) = tip_info {
-                for unmined_tx in &unmined {
+                // A replaced transaction that is not mined stays cancelled
+                for unmined_tx in unmined.iter().filter(|tx| tx.cancelled.is_none()) {
                     debug!(
                         target: LOG_TARGET,
                         "Updated transaction {} as unmined (Operation ID: {})", unmined_tx.tx_id, self.operation_id
@@ -158,6 +167,93 @@ where
         Ok(self.operation_id)
     }
 
+    /// A fee bumped transaction and its replacement spend the same inputs, so only one of them can be mined. Once
+    /// either of them is confirmed, the other one is cancelled as a double spend, the confirmed one is no longer
+    /// considered cancelled and the outputs of both are settled.
+    async fn resolve_replaced_transactions(
+        &mut self,
+        confirmed: &UnconfirmedTransactionInfo,
+    ) -> Result<(), TransactionServiceProtocolError<OperationId>> {
+        let op_id = self.operation_id;
+        let replaced_txs = self
+            .db
+            .get_cancelled_completed_transactions()
+            .for_protocol(op_id)?
+            .into_values()
+            .filter(|tx| tx.cancelled == Some(TxCancellationReason::Replaced) && tx.mined_height.is_none());
+        let mut candidates = replaced_txs.collect::<Vec<_>>();
+        if candidates.is_empty() && confirmed.cancelled.is_none() {
+            return Ok(());
+        }
+        if confirmed.cancelled.is_some() {
+            // The replacement of a transaction that was mined after all is not cancelled yet
+            candidates.extend(
+                self.db
+                    .get_completed_transactions()
+                    .for_protocol(op_id)?
+                    .into_values()
+                    .filter(|tx| tx.mined_height.is_none()),
+            );
+        }
+
+        let confirmed_tx = self
+            .db
+            .get_completed_transaction_cancelled_or_not(confirmed.tx_id)
+            .for_protocol(op_id)?;
+        let confirmed_inputs = confirmed_tx
+            .transaction
+            .body
+            .inputs()
+            .iter()
+            .filter_map(|input| input.commitment().ok().cloned())
+            .collect::<Vec<_>>();
+        for superseded in candidates.into_iter().filter(|tx| {
+            tx.tx_id != confirmed.tx_id &&
+                tx.transaction
+                    .body
+                    .inputs()
+                    .iter()
+                    .any(|input| input.commitment().map_or(false, |c| confirmed_inputs.contains(c)))
+        }) {
+            info!(
+                target: LOG_TARGET,
+                "Transaction {} is confirmed and spends inputs of transaction {}, cancelling it as a double \
+                 spend (Operation ID: {})",
+                confirmed.tx_id,
+                superseded.tx_id,
+                op_id
+            );
+            if superseded.cancelled.is_none() {
+                self.db
+                    .reject_completed_transaction(superseded.tx_id, TxCancellationReason::DoubleSpend)
+                    .for_protocol(op_id)?;
+                self.publish_event(TransactionEvent::TransactionCancelled(
+                    superseded.tx_id,
+                    TxCancellationReason::DoubleSpend,
+                ));
+            } else {
+                self.db
+                    .set_completed_transaction_cancellation_reason(
+                        superseded.tx_id,
+                        Some(TxCancellationReason::DoubleSpend),
+                    )
+                    .for_protocol(op_id)?;
+            }
+            self.output_manager_handle
+                .resolve_replaced_transaction(confirmed.tx_id, superseded.tx_id, confirmed_inputs.clone())
+                .await
+                .map_err(TransactionServiceError::from)
+                .for_protocol(op_id)?;
+        }
+
+        if confirmed.cancelled.is_some() {
+            self.db
+                .set_completed_transaction_cancellation_reason(confirmed.tx_id, None)
+                .for_protocol(op_id)?;
+        }
+        Ok(())
+    }
+
     fn publish_event(&self, event: TransactionEvent) {
         if let Err(e) = self.event_publisher.send(Arc::new(event)) {
             debug!(
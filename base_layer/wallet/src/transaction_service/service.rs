@@ -51,7 +51,7 @@ use tari_core::{
     one_sided::{shared_secret_to_output_encryption_key, shared_secret_to_output_spending_key},
     proto::{base_node as base_node_proto, base_node::FetchMatchingUtxos},
     transactions::{
-        key_manager::TransactionKeyManagerInterface,
+        key_manager::{TariKeyId, TransactionKeyManagerInterface},
         tari_amount::MicroMinotari,
         transaction_components::{
             encrypted_data::PaymentId,
@@ -60,12 +60,14 @@ use tari_core::{
             OutputFeatures,
             Transaction,
             TransactionOutput,
+            WalletOutput,
             WalletOutputBuilder,
         },
         transaction_protocol::{
             proto::protocol as proto,
             recipient::RecipientSignedMessage,
             sender::TransactionSenderMessage,
+            unsigned_transaction::{SignedTransactionBundle, UnsignedRecipient, UnsignedTransactionBundle},
             TransactionMetadata,
         },
         CryptoFactories,
@@ -99,9 +101,11 @@ use crate::{
     storage::database::{WalletBackend, WalletDatabase},
     transaction_service::{
         config::TransactionServiceConfig,
-        error::{TransactionServiceError, TransactionServiceProtocolError},
+        error::{TransactionServiceError, TransactionServiceProtocolError, TransactionStorageError},
         handle::{
+            BatchRecipient,
             FeePerGramStatsResponse,
+            FeeRate,
             TransactionEvent,
             TransactionEventSender,
             TransactionServiceRequest,
@@ -118,6 +122,7 @@ use crate::{
             database::{TransactionBackend, TransactionDatabase},
             models::{
                 CompletedTransaction,
+                OutboundTransaction,
                 TxCancellationReason,
                 WalletTransaction::{Completed, PendingInbound, PendingOutbound},
             },
@@ -372,6 +377,9 @@ where
             JoinHandle<Result<OperationId, TransactionServiceProtocolError<OperationId>>>,
         > = FuturesUnordered::new();
 
+        let mut fee_estimate_handles: FuturesUnordered<JoinHandle<Option<(PendingSendTransaction, MicroMinotari)>>> =
+            FuturesUnordered::new();
+
         let mut base_node_service_event_stream = self.base_node_service.get_event_stream();
         let mut output_manager_event_stream = self.resources.output_manager_service.get_event_stream();
 
@@ -402,6 +410,7 @@ where
                         &mut receive_transaction_protocol_handles,
                         &mut transaction_broadcast_protocol_handles,
                         &mut transaction_validation_protocol_handles,
+                        &mut fee_estimate_handles,
                         reply_tx,
                     ).await.map_err(|e| {
                         warn!(target: LOG_TARGET, "Error handling request: {:?}", e);
@@ -564,6 +573,29 @@ where
                         Err(e) => error!(target: LOG_TARGET, "Error resolving Broadcast Protocol: {:?}", e),
                     };
                 }
+                Some(join_result) = fee_estimate_handles.next() => {
+                    match join_result {
+                        Ok(Some((pending, fee_per_gram))) => {
+                            let _result = self.send_transaction(
+                                pending.destination,
+                                pending.amount,
+                                pending.selection_criteria,
+                                pending.output_features,
+                                fee_per_gram,
+                                pending.payment_id,
+                                TransactionMetadata::default(),
+                                &mut send_transaction_protocol_handles,
+                                &mut transaction_broadcast_protocol_handles,
+                                pending.reply_channel,
+                            ).await.map_err(|e| {
+                                warn!(target: LOG_TARGET, "Error sending transaction at estimated fee: {:?}", e);
+                                e
+                            });
+                        },
+                        Ok(None) => (),
+                        Err(e) => error!(target: LOG_TARGET, "Error resolving fee estimate: {:?}", e),
+                    };
+                }
                 Some(join_result) = transaction_validation_protocol_handles.next() => {
                     trace!(target: LOG_TARGET, "Transaction Validation protocol has ended with result {:?}", join_result);
                     match join_result {
@@ -601,6 +633,7 @@ where
         transaction_validation_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<OperationId, TransactionServiceProtocolError<OperationId>>>,
         >,
+        fee_estimate_join_handles: &mut FuturesUnordered<JoinHandle<Option<(PendingSendTransaction, MicroMinotari)>>>,
         reply_channel: oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
     ) -> Result<(), TransactionServiceError> {
         let mut reply_channel = Some(reply_channel);
@@ -612,23 +645,38 @@ where
                 amount,
                 selection_criteria,
                 output_features,
-                fee_per_gram,
+                fee_rate,
                 payment_id,
             } => {
                 let rp = reply_channel.take().expect("Cannot be missing");
-                self.send_transaction(
-                    destination,
-                    amount,
-                    selection_criteria,
-                    *output_features,
-                    fee_per_gram,
-                    payment_id,
-                    TransactionMetadata::default(),
-                    send_transaction_join_handles,
-                    transaction_broadcast_join_handles,
-                    rp,
-                )
-                .await?;
+                match fee_rate {
+                    FeeRate::FeePerGram(fee_per_gram) => {
+                        self.send_transaction(
+                            destination,
+                            amount,
+                            selection_criteria,
+                            *output_features,
+                            fee_per_gram,
+                            payment_id,
+                            TransactionMetadata::default(),
+                            send_transaction_join_handles,
+                            transaction_broadcast_join_handles,
+                            rp,
+                        )
+                        .await?;
+                    },
+                    FeeRate::ConfirmationTarget(confirmation_target) => {
+                        let pending = PendingSendTransaction {
+                            destination,
+                            amount,
+                            selection_criteria,
+                            output_features: *output_features,
+                            payment_id,
+                            reply_channel: rp,
+                        };
+                        self.handle_fee_estimate_request(confirmation_target, pending, fee_estimate_join_handles);
+                    },
+                }
                 return Ok(());
             },
             TransactionServiceRequest::SendOneSidedTransaction {
@@ -651,6 +699,36 @@ where
                 .await
                 .map(TransactionServiceResponse::TransactionSent),
 
+            TransactionServiceRequest::SendBatchOneSidedTransaction {
+                recipients,
+                selection_criteria,
+                fee_per_gram,
+            } => self
+                .send_batch_one_sided_transaction(
+                    recipients,
+                    selection_criteria,
+                    fee_per_gram,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::PrepareOfflineTransaction {
+                recipients,
+                selection_criteria,
+                fee_per_gram,
+                payment_id,
+            } => self
+                .prepare_offline_transaction(recipients, selection_criteria, fee_per_gram, payment_id)
+                .await
+                .map(|bundle| TransactionServiceResponse::UnsignedTransaction(Box::new(bundle))),
+            TransactionServiceRequest::SignOfflineTransaction(bundle) => self
+                .sign_offline_transaction(*bundle)
+                .await
+                .map(|bundle| TransactionServiceResponse::SignedTransaction(Box::new(bundle))),
+            TransactionServiceRequest::BroadcastSignedTransaction(bundle) => self
+                .broadcast_signed_transaction(*bundle, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
             TransactionServiceRequest::ScrapeWallet {
                 destination,
                 fee_per_gram,
@@ -855,6 +933,13 @@ where
                 .cancel_pending_transaction(tx_id)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionCancelled),
+            TransactionServiceRequest::BumpFee {
+                tx_id,
+                new_fee_per_gram,
+            } => self
+                .bump_fee(tx_id, new_fee_per_gram, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
             TransactionServiceRequest::GetPendingInboundTransactions => Ok(
                 TransactionServiceResponse::PendingInboundTransactions(self.db.get_pending_inbound_transactions()?),
             ),
@@ -981,6 +1066,53 @@ where
         Ok(())
     }
 
+    /// Asks the base node for the fee per gram that is required to be mined within `confirmation_target` blocks. If
+    /// the estimate fails the pending send is answered from the spawned task, otherwise it is handed back to the
+    /// service to be sent at the estimated fee per gram.
+    fn handle_fee_estimate_request(
+        &self,
+        confirmation_target: u64,
+        pending: PendingSendTransaction,
+        fee_estimate_join_handles: &mut FuturesUnordered<JoinHandle<Option<(PendingSendTransaction, MicroMinotari)>>>,
+    ) {
+        let mut connectivity = self.resources.connectivity.clone();
+
+        let query_base_node_fut = async move {
+            let mut client = connectivity
+                .obtain_base_node_wallet_rpc_client()
+                .await
+                .ok_or(TransactionServiceError::Shutdown)?;
+            let estimate = client
+                .estimate_fee(base_node_proto::EstimateFeeRequest {
+                    confirmation_target,
+                    // Use the base node's default confidence
+                    confidence_percent: 0,
+                })
+                .await?;
+            debug!(
+                target: LOG_TARGET,
+                "Base node estimated {} µT/g for confirmation within {} block(s) (historical: {})",
+                estimate.fee_per_gram,
+                confirmation_target,
+                estimate.is_historical
+            );
+            Ok::<_, TransactionServiceError>(MicroMinotari::from(estimate.fee_per_gram.max(1)))
+        };
+
+        let join_handle = tokio::spawn(async move {
+            match query_base_node_fut.await {
+                Ok(fee_per_gram) => Some((pending, fee_per_gram)),
+                Err(e) => {
+                    if pending.reply_channel.send(Err(e)).is_err() {
+                        warn!(target: LOG_TARGET, "handle_fee_estimate_request: service reply cancelled");
+                    }
+                    None
+                },
+            }
+        });
+        fee_estimate_join_handles.push(join_handle);
+    }
+
     fn handle_get_fee_per_gram_stats_per_block_request(
         &self,
         count: usize,
@@ -1693,6 +1825,7 @@ where
             Some(s) => (s, false),
             None => (push_pubkey_script(&Default::default()), true),
         };
+        let replaced_tx_id = selection_criteria.filter.replaced_tx_id();
         // Prepare sender part of the transaction
         let mut stp = self
             .resources
@@ -1869,11 +2002,28 @@ where
             .get_fee_amount()
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
 
-        self.resources
-            .output_manager_service
-            .confirm_pending_transaction(tx_id)
-            .await
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        match replaced_tx_id {
+            Some(original_tx_id) => {
+                let spent_inputs = tx
+                    .body
+                    .inputs()
+                    .iter()
+                    .map(|input| input.commitment().cloned())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+                self.resources
+                    .output_manager_service
+                    .replace_pending_transaction(original_tx_id, tx_id, spent_inputs)
+                    .await
+            },
+            None => {
+                self.resources
+                    .output_manager_service
+                    .confirm_pending_transaction(tx_id)
+                    .await
+            },
+        }
+        .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
         self.submit_transaction(
             transaction_broadcast_join_handles,
             CompletedTransaction::new(
@@ -2465,6 +2615,334 @@ where
         .await
     }
 
+    /// Sends one-sided payments to several recipients in a single transaction. Every recipient output is paid to the
+    /// recipient's stealth address and carries its own encrypted payment id; there is one kernel and at most one
+    /// change output.
+    pub async fn send_batch_one_sided_transaction(
+        &mut self,
+        recipients: Vec<BatchRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let first_recipient = recipients
+            .first()
+            .ok_or_else(|| {
+                TransactionServiceError::InvalidMessageError(
+                    "A batch transaction needs at least one recipient".to_string(),
+                )
+            })?
+            .destination
+            .clone();
+        for recipient in &recipients {
+            self.verify_send(&recipient.destination, TariAddressFeatures::create_one_sided_only())?;
+        }
+        let total_amount = recipients
+            .iter()
+            .map(|r| r.amount)
+            .try_fold(MicroMinotari::zero(), |acc, v| acc.checked_add(v))
+            .ok_or_else(|| TransactionServiceError::InvalidMessageError("Total amount overflow".to_string()))?;
+
+        let tx_id = TxId::new_random();
+        let mut outputs = Vec::with_capacity(recipients.len());
+        for recipient in &recipients {
+            outputs.push(self.create_one_sided_stealth_output(recipient).await?);
+        }
+
+        let payment_id = PaymentId::open_from_str(&format!("Batch payment to {} recipients", recipients.len()));
+        let (fee, tx) = self
+            .resources
+            .output_manager_service
+            .create_transaction_with_recipient_outputs(
+                tx_id,
+                outputs,
+                fee_per_gram,
+                selection_criteria,
+                payment_id.clone(),
+            )
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Finalized batch one-sided transaction TxId: {} ({} recipients, total {})",
+            tx_id,
+            recipients.len(),
+            total_amount
+        );
+
+        // This event being sent is important, but not critical to the protocol being successful. Send only fails if
+        // there are no subscribers.
+        let _result = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.one_sided_tari_address.clone(),
+                first_recipient,
+                total_amount,
+                fee,
+                tx.clone(),
+                TransactionStatus::Completed,
+                Utc::now(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+                payment_id,
+            )?,
+        )
+        .await?;
+
+        let mut notified = HashSet::new();
+        for recipient in recipients {
+            let comms_public_key = recipient.destination.comms_public_key().clone();
+            if notified.insert(comms_public_key.clone()) {
+                tokio::spawn(send_finalized_transaction_message(
+                    tx_id,
+                    tx.clone(),
+                    comms_public_key,
+                    self.resources.outbound_message_service.clone(),
+                    self.resources.config.direct_send_timeout,
+                    self.resources.config.transaction_routing_mechanism,
+                ));
+            }
+        }
+
+        Ok(tx_id)
+    }
+
+    /// Prepares a one-sided payment to several recipients for signing by a wallet holding the spend key. The recipient
+    /// outputs built here only size the transaction, the signer builds its own. The transaction is stored as pending
+    /// until it is broadcast, and can be cancelled to release its inputs.
+    pub async fn prepare_offline_transaction(
+        &mut self,
+        recipients: Vec<BatchRecipient>,
+        selection_criteria: UtxoSelectionCriteria,
+        fee_per_gram: MicroMinotari,
+        payment_id: PaymentId,
+    ) -> Result<UnsignedTransactionBundle, TransactionServiceError> {
+        if recipients.is_empty() {
+            return Err(TransactionServiceError::InvalidMessageError(
+                "An offline transaction needs at least one recipient".to_string(),
+            ));
+        }
+        for recipient in &recipients {
+            self.verify_send(&recipient.destination, TariAddressFeatures::create_one_sided_only())?;
+        }
+
+        let tx_id = TxId::new_random();
+        let destination_address = recipients[0].destination.clone();
+        let total_amount = recipients
+            .iter()
+            .map(|r| r.amount)
+            .try_fold(MicroMinotari::zero(), |acc, v| acc.checked_add(v))
+            .ok_or_else(|| TransactionServiceError::InvalidMessageError("Total amount overflow".to_string()))?;
+        let mut outputs = Vec::with_capacity(recipients.len());
+        for recipient in &recipients {
+            outputs.push(self.create_one_sided_stealth_output(recipient).await?);
+        }
+        let recipients = recipients
+            .into_iter()
+            .map(|r| UnsignedRecipient {
+                destination: r.destination,
+                amount: r.amount,
+                payment_id: r.payment_id,
+            })
+            .collect();
+        let (bundle, stp) = self
+            .resources
+            .output_manager_service
+            .prepare_unsigned_transaction(
+                tx_id,
+                outputs,
+                recipients,
+                fee_per_gram,
+                selection_criteria,
+                payment_id.clone(),
+            )
+            .await?;
+        let outbound_tx = OutboundTransaction::new(
+            tx_id,
+            destination_address,
+            total_amount,
+            bundle.fee,
+            stp,
+            TransactionStatus::Pending,
+            payment_id,
+            Utc::now(),
+            false,
+        );
+        if let Err(e) = self.db.add_pending_outbound_transaction(tx_id, outbound_tx) {
+            let _ = self.resources.output_manager_service.cancel_transaction(tx_id).await;
+            return Err(e.into());
+        }
+        info!(
+            target: LOG_TARGET,
+            "Prepared offline transaction TxId: {} spending {} inputs", tx_id, bundle.inputs.len()
+        );
+        Ok(bundle)
+    }
+
+    /// Signs a transaction prepared by a wallet for the same spend key. Nothing is stored and nothing is sent.
+    pub async fn sign_offline_transaction(
+        &mut self,
+        bundle: UnsignedTransactionBundle,
+    ) -> Result<SignedTransactionBundle, TransactionServiceError> {
+        bundle.check_version()?;
+        let mut outputs = Vec::with_capacity(bundle.recipients.len());
+        for recipient in &bundle.recipients {
+            let recipient = BatchRecipient {
+                destination: recipient.destination.clone(),
+                amount: recipient.amount,
+                payment_id: recipient.payment_id.clone(),
+            };
+            outputs.push(self.create_one_sided_stealth_output(&recipient).await?);
+        }
+        let (_, transaction) = self
+            .resources
+            .output_manager_service
+            .sign_unsigned_transaction(bundle.clone(), outputs)
+            .await?;
+        info!(target: LOG_TARGET, "Signed offline transaction TxId: {}", bundle.tx_id);
+        Ok(SignedTransactionBundle::new(&bundle, transaction))
+    }
+
+    /// Broadcasts a transaction signed offline, once it has been checked to spend the inputs that were prepared for
+    /// it. The pending transaction is completed and its change output is added to the wallet.
+    pub async fn broadcast_signed_transaction(
+        &mut self,
+        bundle: SignedTransactionBundle,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        bundle.check_version()?;
+        let tx_id = bundle.tx_id;
+        let pending = match self.db.get_pending_outbound_transaction(tx_id) {
+            Ok(pending) => pending,
+            Err(TransactionStorageError::ValueNotFound(_)) => {
+                return Err(TransactionServiceError::InvalidMessageError(format!(
+                    "Transaction {} is not pending, it was cancelled or already broadcast",
+                    tx_id
+                )));
+            },
+            Err(e) => return Err(e.into()),
+        };
+        check_transaction_size(&bundle.transaction, tx_id)?;
+        let change = pending.sender_protocol.get_change_amount()?;
+        self.resources
+            .output_manager_service
+            .confirm_signed_transaction(tx_id, bundle.transaction.clone(), change)
+            .await?;
+        let tx = bundle.transaction;
+        let fee = tx.body.get_total_fee()?;
+
+        self.db.complete_outbound_transaction(
+            tx_id,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.one_sided_tari_address.clone(),
+                pending.destination_address,
+                pending.amount,
+                fee,
+                tx.clone(),
+                TransactionStatus::Completed,
+                Utc::now(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+                bundle.payment_id,
+            )?,
+        )?;
+        self.complete_send_transaction_protocol(
+            Ok(TransactionSendResult {
+                tx_id,
+                transaction_status: TransactionStatus::Completed,
+            }),
+            transaction_broadcast_join_handles,
+        );
+        info!(target: LOG_TARGET, "Broadcasting offline signed transaction TxId: {}", tx_id);
+
+        let mut notified = HashSet::new();
+        for recipient in bundle.recipients {
+            let comms_public_key = recipient.destination.comms_public_key().clone();
+            if notified.insert(comms_public_key.clone()) {
+                tokio::spawn(send_finalized_transaction_message(
+                    tx_id,
+                    tx.clone(),
+                    comms_public_key,
+                    self.resources.outbound_message_service.clone(),
+                    self.resources.config.direct_send_timeout,
+                    self.resources.config.transaction_routing_mechanism,
+                ));
+            }
+        }
+
+        Ok(tx_id)
+    }
+
+    /// Builds a one-sided output paying `recipient` at their stealth address, returned with the sender offset key that
+    /// signed it.
+    async fn create_one_sided_stealth_output(
+        &self,
+        recipient: &BatchRecipient,
+    ) -> Result<(WalletOutput, TariKeyId), TransactionServiceError> {
+        let key_manager = &self.resources.transaction_key_manager_service;
+        let payment_id = match recipient.payment_id.clone() {
+            PaymentId::Open(v) => PaymentId::AddressAndData {
+                sender_address: self.resources.interactive_tari_address.clone(),
+                user_data: v,
+            },
+            PaymentId::Empty => PaymentId::AddressAndData {
+                sender_address: self.resources.interactive_tari_address.clone(),
+                user_data: vec![],
+            },
+            payment_id => payment_id,
+        };
+
+        let sender_offset = key_manager
+            .get_next_key(TransactionKeyManagerBranch::OneSidedSenderOffset.get_branch_key())
+            .await?;
+        // Diffie-Hellman shared secret `k_Ob * K_Sb = K_Ob * k_Sb` results in a public key, which is fed into
+        // KDFs to produce the spending, rewind, and encryption keys
+        let shared_secret = key_manager
+            .get_diffie_hellman_shared_secret(
+                &sender_offset.key_id,
+                recipient.destination.public_view_key().ok_or_else(|| {
+                    TransactionServiceError::OneSidedTransactionError("Missing public view key".to_string())
+                })?,
+            )
+            .await?;
+        let commitment_mask_key_id = key_manager
+            .import_key(shared_secret_to_output_spending_key(&shared_secret)?)
+            .await?;
+        let encryption_key = key_manager
+            .import_key(shared_secret_to_output_encryption_key(&shared_secret)?)
+            .await?;
+        let script_spending_key = key_manager
+            .stealth_address_script_spending_key(&commitment_mask_key_id, recipient.destination.public_spend_key())
+            .await?;
+
+        let output = WalletOutputBuilder::new(recipient.amount, commitment_mask_key_id)
+            .with_features(OutputFeatures::default())
+            .with_script(push_pubkey_script(&script_spending_key))
+            .encrypt_data_for_recovery(key_manager, Some(&encryption_key), payment_id)
+            .await?
+            .with_input_data(Default::default())
+            .with_sender_offset_public_key(sender_offset.pub_key)
+            .with_script_key(KeyId::Zero)
+            .with_minimum_value_promise(MicroMinotari::zero())
+            .sign_as_sender_and_receiver_verified(key_manager, &sender_offset.key_id, &recipient.destination)
+            .await?
+            .try_build(key_manager)
+            .await?;
+
+        Ok((output, sender_offset.key_id))
+    }
+
     /// Accept the public reply from a recipient and apply the reply to the relevant transaction protocol
     /// # Arguments
     /// 'recipient_reply' - The public response from a recipient with data required to complete the transaction
@@ -2707,6 +3185,126 @@ where
         Ok(())
     }
 
+    /// Replace an unconfirmed outbound one-sided transaction with a transaction of the same kind (one-sided or stealth)
+    /// to the same destination, for the same amount and payment id, that spends the original inputs at
+    /// `new_fee_per_gram`. The original inputs are only handed over to the replacement once it has been built, so the
+    /// original is left untouched if that fails. The original transaction is then marked as replaced and the
+    /// replacement is broadcast.
+    ///
+    /// Only base nodes with `unconfirmed_pool.replace_by_fee` enabled (it is disabled by default) evict the original
+    /// from their mempools; other nodes keep both and only one of them can be mined. Until either of the two is
+    /// confirmed, all of the original inputs stay locked and the original is still monitored, see
+    /// `TransactionValidationProtocol`.
+    ///
+    /// Interactive transactions cannot be fee bumped: their kernel and outputs are signed together with the recipient,
+    /// so the wallet cannot rebuild them on its own.
+    pub async fn bump_fee(
+        &mut self,
+        tx_id: TxId,
+        new_fee_per_gram: MicroMinotari,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let completed_tx = self.db.get_completed_transaction(tx_id)?;
+        if completed_tx.direction != TransactionDirection::Outbound {
+            return Err(TransactionServiceError::NotSupported(format!(
+                "Only outbound transactions can be fee bumped (TxId: {})",
+                tx_id
+            )));
+        }
+        if !matches!(
+            completed_tx.status,
+            TransactionStatus::Completed | TransactionStatus::Broadcast
+        ) {
+            return Err(TransactionServiceError::NotSupported(format!(
+                "Transaction (TxId: {}) has status {} and can no longer be fee bumped",
+                tx_id, completed_tx.status
+            )));
+        }
+
+        let tip_height = self.last_seen_tip_height.unwrap_or(0);
+        let weight = completed_tx.transaction.calculate_weight(
+            self.resources
+                .consensus_manager
+                .consensus_constants(tip_height)
+                .transaction_weight_params(),
+        )?;
+        let current_fee_per_gram = completed_tx.fee.as_u64() / weight.max(1);
+        if new_fee_per_gram.as_u64() <= current_fee_per_gram {
+            return Err(TransactionServiceError::NotSupported(format!(
+                "New fee per gram ({}) must be higher than the current fee per gram ({}) of TxId: {}",
+                new_fee_per_gram, current_fee_per_gram, tx_id
+            )));
+        }
+
+        if completed_tx.source_address != self.resources.one_sided_tari_address {
+            return Err(TransactionServiceError::NotSupported(format!(
+                "Only one-sided transactions can be fee bumped, interactive transactions would have to be signed by \
+                 the recipient again (TxId: {})",
+                tx_id
+            )));
+        }
+
+        // A plain one-sided payment pays to the destination's spend key, anything else was sent to a stealth address
+        let one_sided_script = push_pubkey_script(completed_tx.destination_address.public_spend_key());
+        let (output_features, recipient_script) = match completed_tx
+            .transaction
+            .body
+            .outputs()
+            .iter()
+            .find(|output| output.script == one_sided_script)
+        {
+            Some(output) => (output.features.clone(), Some(one_sided_script)),
+            None => (OutputFeatures::default(), None),
+        };
+
+        let new_tx_id = self
+            .send_one_sided_or_stealth(
+                completed_tx.destination_address.clone(),
+                completed_tx.amount,
+                UtxoSelectionCriteria::replacing(tx_id),
+                output_features,
+                new_fee_per_gram,
+                transaction_broadcast_join_handles,
+                recipient_script,
+                completed_tx.payment_id.clone(),
+            )
+            .await
+            .map_err(|e| {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not build fee bump replacement for TxId: {}: {:?}", tx_id, e
+                );
+                e
+            })?;
+
+        self.db
+            .reject_completed_transaction(tx_id, TxCancellationReason::Replaced)?;
+
+        let _size = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCancelled(
+                tx_id,
+                TxCancellationReason::Replaced,
+            )))
+            .map_err(|e| {
+                trace!(
+                    target: LOG_TARGET,
+                    "Error sending event because there are no subscribers: {:?}",
+                    e
+                );
+                e
+            });
+
+        info!(
+            target: LOG_TARGET,
+            "Transaction (TxId: {}) replaced by TxId: {} at {} per gram", tx_id, new_tx_id, new_fee_per_gram
+        );
+
+        Ok(new_tx_id)
+    }
+
     /// Handle a Transaction Cancelled message received from the Comms layer
     pub async fn handle_transaction_cancelled_message(
         &mut self,
@@ -2751,6 +3349,10 @@ where
     ) -> Result<(), TransactionServiceError> {
         let outbound_txs = self.db.get_pending_outbound_transactions()?;
         for (tx_id, tx) in outbound_txs {
+            // Transactions prepared for offline signing have no recipient to negotiate with
+            if tx.sender_protocol.is_finalizing() {
+                continue;
+            }
             let (sender_protocol, stage) = if tx.send_count > 0 {
                 (None, TransactionSendProtocolStage::WaitForReply)
             } else {
@@ -3283,6 +3885,7 @@ where
             self.resources.connectivity.clone(),
             self.resources.config.clone(),
             self.event_publisher.clone(),
+            self.resources.output_manager_service.clone(),
         );
 
         let mut base_node_watch = self.connectivity().get_current_base_node_watcher();
@@ -3746,3 +4349,13 @@ pub struct TransactionSendResult {
     pub tx_id: TxId,
     pub transaction_status: TransactionStatus,
 }
+
+/// A `SendTransaction` request that is waiting for the base node to estimate its fee per gram
+struct PendingSendTransaction {
+    destination: TariAddress,
+    amount: MicroMinotari,
+    selection_criteria: UtxoSelectionCriteria,
+    output_features: OutputFeatures,
+    payment_id: PaymentId,
+    reply_channel: oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
+}
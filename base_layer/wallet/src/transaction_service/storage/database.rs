@@ -110,6 +110,13 @@ pub trait TransactionBackend: Send + Sync + Clone {
         tx_id: TxId,
         reason: TxCancellationReason,
     ) -> Result<(), TransactionStorageError>;
+    /// Overwrite the cancellation reason of a completed transaction, or clear it with `None` if the transaction turns
+    /// out not to be cancelled after all
+    fn set_completed_transaction_cancellation_reason(
+        &self,
+        tx_id: TxId,
+        reason: Option<TxCancellationReason>,
+    ) -> Result<(), TransactionStorageError>;
     /// Set cancellation on Pending transaction, this will update the transaction status
     fn set_pending_transaction_cancellation_status(
         &self,
@@ -665,6 +672,14 @@ where T: TransactionBackend + 'static
         self.db.reject_completed_transaction(tx_id, reason)
     }
 
+    pub fn set_completed_transaction_cancellation_reason(
+        &self,
+        tx_id: TxId,
+        reason: Option<TxCancellationReason>,
+    ) -> Result<(), TransactionStorageError> {
+        self.db.set_completed_transaction_cancellation_reason(tx_id, reason)
+    }
+
     pub fn cancel_pending_transaction(&self, tx_id: TxId) -> Result<(), TransactionStorageError> {
         self.db.set_pending_transaction_cancellation_status(tx_id, true)
     }
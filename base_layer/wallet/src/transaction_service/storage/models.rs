@@ -331,6 +331,7 @@ pub enum TxCancellationReason {
     TimeLocked,         // 5
     InvalidTransaction, // 6
     Oversized,          // 7
+    Replaced,           // 8
 }
 
 impl TryFrom<u32> for TxCancellationReason {
@@ -346,6 +347,7 @@ impl TryFrom<u32> for TxCancellationReason {
             5 => Ok(TxCancellationReason::TimeLocked),
             6 => Ok(TxCancellationReason::InvalidTransaction),
             7 => Ok(TxCancellationReason::Oversized),
+            8 => Ok(TxCancellationReason::Replaced),
             code => Err(TransactionConversionError { code: code as i32 }),
         }
     }
@@ -364,6 +366,7 @@ impl Display for TxCancellationReason {
             TimeLocked => "TimeLocked",
             InvalidTransaction => "Invalid Transaction",
             Oversized => "Oversized",
+            Replaced => "Replaced",
         };
         fmt.write_str(response)
     }
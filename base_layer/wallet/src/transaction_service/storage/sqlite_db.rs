@@ -717,6 +717,35 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
         Ok(())
     }
 
+    fn set_completed_transaction_cancellation_reason(
+        &self,
+        tx_id: TxId,
+        reason: Option<TxCancellationReason>,
+    ) -> Result<(), TransactionStorageError> {
+        let start = Instant::now();
+        let mut conn = self.database_connection.get_pooled_connection()?;
+        let acquire_lock = start.elapsed();
+        match CompletedTransactionSql::set_cancellation_reason(tx_id, reason, &mut conn) {
+            Ok(_) => {},
+            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(TransactionStorageError::ValueNotFound(DbKey::CompletedTransaction(
+                    tx_id,
+                )));
+            },
+            Err(e) => return Err(e),
+        }
+        if start.elapsed().as_millis() > 0 {
+            trace!(
+                target: LOG_TARGET,
+                "sqlite profile - set_completed_transaction_cancellation_reason: lock {} + db_op {} = {} ms",
+                acquire_lock.as_millis(),
+                (start.elapsed() - acquire_lock).as_millis(),
+                start.elapsed().as_millis()
+            );
+        }
+        Ok(())
+    }
+
     fn set_pending_transaction_cancellation_status(
         &self,
         tx_id: TxId,
@@ -1809,6 +1838,22 @@ impl CompletedTransactionSql {
         Ok(())
     }
 
+    pub fn set_cancellation_reason(
+        tx_id: TxId,
+        reason: Option<TxCancellationReason>,
+        conn: &mut SqliteConnection,
+    ) -> Result<(), TransactionStorageError> {
+        diesel::update(completed_transactions::table.filter(completed_transactions::tx_id.eq(tx_id.as_u64() as i64)))
+            .set(UpdateCompletedTransactionSql {
+                cancelled: Some(reason.map(|r| r as i32)),
+                ..Default::default()
+            })
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+
+        Ok(())
+    }
+
     pub fn increment_send_count(tx_id: TxId, conn: &mut SqliteConnection) -> Result<(), TransactionStorageError> {
         // This query uses a sub-query to retrieve an existing value in the table
         diesel::update(completed_transactions::table.filter(completed_transactions::tx_id.eq(tx_id.as_u64() as i64)))
@@ -2113,6 +2158,7 @@ pub struct UnconfirmedTransactionInfo {
     pub signature: Signature,
     pub status: TransactionStatus,
     pub payment_id: PaymentId,
+    pub cancelled: Option<TxCancellationReason>,
 }
 
 impl TryFrom<UnconfirmedTransactionInfoSql> for UnconfirmedTransactionInfo {
@@ -2127,6 +2173,10 @@ impl TryFrom<UnconfirmedTransactionInfoSql> for UnconfirmedTransactionInfo {
             ),
             status: TransactionStatus::try_from(i.status)?,
             payment_id: PaymentId::from_bytes(&i.payment_id.unwrap_or_default()),
+            cancelled: i
+                .cancelled
+                .map(|c| TxCancellationReason::try_from(c as u32))
+                .transpose()?,
         })
     }
 }
@@ -2138,10 +2188,12 @@ pub struct UnconfirmedTransactionInfoSql {
     pub transaction_signature_nonce: Vec<u8>,
     pub transaction_signature_key: Vec<u8>,
     pub payment_id: Option<Vec<u8>>,
+    pub cancelled: Option<i32>,
 }
 
 impl UnconfirmedTransactionInfoSql {
-    /// This method returns completed but unconfirmed transactions that were not imported or scanned
+    /// This method returns completed but unconfirmed transactions that were not imported or scanned. Transactions
+    /// that were replaced by a fee bump are included, as they can still be mined until their replacement is.
     pub fn fetch_unconfirmed_transactions_info(
         conn: &mut SqliteConnection,
     ) -> Result<Vec<UnconfirmedTransactionInfoSql>, TransactionStorageError> {
@@ -2152,6 +2204,7 @@ impl UnconfirmedTransactionInfoSql {
                 completed_transactions::transaction_signature_nonce,
                 completed_transactions::transaction_signature_key,
                 completed_transactions::payment_id,
+                completed_transactions::cancelled,
             ))
             .filter(
                 completed_transactions::status
@@ -2172,7 +2225,11 @@ impl UnconfirmedTransactionInfoSql {
                             .or(completed_transactions::status.eq(TransactionStatus::MinedUnconfirmed as i32)),
                     ),
             )
-            .filter(completed_transactions::cancelled.is_null())
+            .filter(
+                completed_transactions::cancelled
+                    .is_null()
+                    .or(completed_transactions::cancelled.eq(TxCancellationReason::Replaced as i32)),
+            )
             .order_by(completed_transactions::tx_id)
             .load::<UnconfirmedTransactionInfoSql>(conn)?;
         Ok(query_result)
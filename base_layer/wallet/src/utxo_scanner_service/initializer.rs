@@ -51,17 +51,24 @@ pub struct UtxoScannerServiceInitializer<T, TKeyManagerInterface> {
     backend: Option<WalletDatabase<T>>,
     factories: CryptoFactories,
     network: Network,
+    use_block_filters: bool,
     phantom: PhantomData<TKeyManagerInterface>,
 }
 
 impl<T, TKeyManagerInterface> UtxoScannerServiceInitializer<T, TKeyManagerInterface>
 where T: WalletBackend + 'static
 {
-    pub fn new(backend: WalletDatabase<T>, factories: CryptoFactories, network: Network) -> Self {
+    pub fn new(
+        backend: WalletDatabase<T>,
+        factories: CryptoFactories,
+        network: Network,
+        use_block_filters: bool,
+    ) -> Self {
         Self {
             backend: Some(backend),
             factories,
             network,
+            use_block_filters,
             phantom: PhantomData,
         }
     }
@@ -95,6 +102,7 @@ where
             .expect("Cannot start Utxo scanner service without setting a storage backend");
         let factories = self.factories.clone();
         let network = self.network;
+        let use_block_filters = self.use_block_filters;
 
         context.spawn_when_ready(move |handles| async move {
             let transaction_service = handles.expect_handle::<TransactionServiceHandle>();
@@ -123,6 +131,7 @@ where
                 .with_peers(vec![])
                 .with_retry_limit(2)
                 .with_mode(UtxoScannerMode::Scanning)
+                .with_block_filters(use_block_filters)
                 .build_with_resources::<T, WalletConnectivityHandle, TKeyManagerInterface>(
                     backend,
                     comms_connectivity,
@@ -56,6 +56,7 @@ pub struct UtxoScannerService<TBackend, TWalletConnectivity> {
     pub(crate) retry_limit: usize,
     pub(crate) peer_seeds: Vec<CommsPublicKey>,
     pub(crate) mode: UtxoScannerMode,
+    pub(crate) use_block_filters: bool,
     pub(crate) shutdown_signal: ShutdownSignal,
     pub(crate) event_sender: broadcast::Sender<UtxoScannerEvent>,
     pub(crate) base_node_service: BaseNodeServiceHandle,
@@ -72,6 +73,7 @@ where
         peer_seeds: Vec<CommsPublicKey>,
         retry_limit: usize,
         mode: UtxoScannerMode,
+        use_block_filters: bool,
         resources: UtxoScannerResources<TBackend, TWalletConnectivity>,
         shutdown_signal: ShutdownSignal,
         event_sender: broadcast::Sender<UtxoScannerEvent>,
@@ -84,6 +86,7 @@ where
             peer_seeds,
             retry_limit,
             mode,
+            use_block_filters,
             shutdown_signal,
             event_sender,
             base_node_service,
@@ -101,6 +104,7 @@ where
             peer_index: 0,
             num_retries: 1,
             mode: self.mode.clone(),
+            use_block_filters: self.use_block_filters,
             shutdown_signal,
         }
     }
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    cmp,
     convert::{TryFrom, TryInto},
     time::{Duration, Instant},
 };
@@ -44,11 +45,11 @@ use tari_comms::{
 };
 use tari_core::{
     base_node::rpc::BaseNodeWalletRpcClient,
-    blocks::BlockHeader,
-    proto::base_node::SyncUtxosByBlockRequest,
+    blocks::{BlockFilter, BlockFilterElement, BlockHeader},
+    proto::base_node::{GetBlockFiltersRequest, SyncUtxosByBlockRequest},
     transactions::{
         tari_amount::MicroMinotari,
-        transaction_components::{encrypted_data::PaymentId, TransactionOutput, WalletOutput},
+        transaction_components::{encrypted_data::PaymentId, OutputType, TransactionOutput, WalletOutput},
     },
 };
 use tari_key_manager::get_birthday_from_unix_epoch_in_seconds;
@@ -71,6 +72,8 @@ use crate::{
 };
 
 pub const LOG_TARGET: &str = "wallet::utxo_scanning";
+// The number of block filters to request at a time, this must not exceed the base node's limit
+const BLOCK_FILTER_BATCH_SIZE: u64 = 1000;
 
 pub struct UtxoScannerTask<TBackend, TWalletConnectivity> {
     pub(crate) resources: UtxoScannerResources<TBackend, TWalletConnectivity>,
@@ -80,6 +83,7 @@ pub struct UtxoScannerTask<TBackend, TWalletConnectivity> {
     pub(crate) peer_seeds: Vec<CommsPublicKey>,
     pub(crate) peer_index: usize,
     pub(crate) mode: UtxoScannerMode,
+    pub(crate) use_block_filters: bool,
     pub(crate) shutdown_signal: ShutdownSignal,
 }
 impl<TBackend, TWalletConnectivity> UtxoScannerTask<TBackend, TWalletConnectivity>
@@ -307,16 +311,15 @@ where
             let (num_recovered, num_scanned, amount) = self
                 .scan_utxos(
                     &mut client,
-                    next_block_to_scan.header_hash,
+                    &peer,
+                    HeightHash {
+                        height: next_block_to_scan.height,
+                        header_hash: next_block_to_scan.header_hash,
+                    },
                     tip_header_hash,
                     tip_header.height,
                 )
                 .await?;
-            if num_scanned == 0 {
-                return Err(UtxoScannerError::UtxoScanningError(
-                    "Peer returned 0 UTXOs to scan".to_string(),
-                ));
-            }
             debug!(
                 target: LOG_TARGET,
                 "Scanning round completed up to height {} in {:.2?} ({} outputs scanned, {} recovered with value {})",
@@ -452,7 +455,8 @@ where
     async fn scan_utxos(
         &mut self,
         client: &mut BaseNodeWalletRpcClient,
-        start_header_hash: HashOutput,
+        peer: &NodeId,
+        start_block: HeightHash,
         end_header_hash: HashOutput,
         tip_height: u64,
     ) -> Result<(u64, u64, MicroMinotari), UtxoScannerError> {
@@ -463,86 +467,108 @@ where
         let mut total_amount = MicroMinotari::from(0);
         let mut total_scanned = 0;
 
-        let request = SyncUtxosByBlockRequest {
-            start_header_hash: start_header_hash.to_vec(),
-            end_header_hash: end_header_hash.to_vec(),
+        let block_ranges = match self
+            .fetch_candidate_block_ranges(client, peer, &start_block, end_header_hash, tip_height)
+            .await?
+        {
+            Some(ranges) => ranges,
+            None => vec![BlockRange {
+                start_header_hash: start_block.header_hash,
+                end_height: tip_height,
+                end_header_hash,
+            }],
         };
-
-        let start = Instant::now();
-        let mut utxo_stream = client.sync_utxos_by_block(request).await?;
-        trace!(
-            target: LOG_TARGET,
-            "bulletproof rewind profile - UTXO stream request time {} ms",
-            start.elapsed().as_millis(),
-        );
+        let scans_to_tip = block_ranges.last().map(|r| r.end_height) == Some(tip_height);
+        let num_ranges = block_ranges.len();
 
         let mut utxo_next_await_profiling = Vec::new();
         let mut scan_for_outputs_profiling = Vec::new();
         let mut prev_scanned_block: Option<ScannedBlock> = None;
-        while let Some(response) = {
+        for block_range in block_ranges {
+            let request = SyncUtxosByBlockRequest {
+                start_header_hash: block_range.start_header_hash.to_vec(),
+                end_header_hash: block_range.end_header_hash.to_vec(),
+            };
+
             let start = Instant::now();
-            let utxo_stream_next = utxo_stream.next().await;
-            utxo_next_await_profiling.push(start.elapsed());
-            utxo_stream_next
-        } {
-            if self.shutdown_signal.is_triggered() {
-                // if running is set to false, we know its been canceled upstream so lets exit the loop
-                return Ok((num_recovered, total_scanned as u64, total_amount));
-            }
+            let mut utxo_stream = client.sync_utxos_by_block(request).await?;
+            trace!(
+                target: LOG_TARGET,
+                "bulletproof rewind profile - UTXO stream request time {} ms",
+                start.elapsed().as_millis(),
+            );
 
-            let response = response.map_err(|e| UtxoScannerError::RpcStatus(e.to_string()))?;
-            let current_height = response.height;
-            let current_header_hash = response.header_hash;
-            let mined_timestamp =
-                DateTime::<Utc>::from_timestamp(response.mined_timestamp as i64, 0).unwrap_or(DateTime::<Utc>::MIN_UTC);
-            let outputs = response
-                .outputs
-                .into_iter()
-                .map(|utxo| TransactionOutput::try_from(utxo).map_err(UtxoScannerError::ConversionError))
-                .collect::<Result<Vec<_>, _>>()?;
-            total_scanned += outputs.len();
+            while let Some(response) = {
+                let start = Instant::now();
+                let utxo_stream_next = utxo_stream.next().await;
+                utxo_next_await_profiling.push(start.elapsed());
+                utxo_stream_next
+            } {
+                if self.shutdown_signal.is_triggered() {
+                    // if running is set to false, we know its been canceled upstream so lets exit the loop
+                    return Ok((num_recovered, total_scanned as u64, total_amount));
+                }
 
-            let start = Instant::now();
-            let found_outputs = self.scan_for_outputs(outputs).await?;
-            scan_for_outputs_profiling.push(start.elapsed());
+                let response = response.map_err(|e| UtxoScannerError::RpcStatus(e.to_string()))?;
+                let current_height = response.height;
+                let current_header_hash = response.header_hash;
+                let mined_timestamp = DateTime::<Utc>::from_timestamp(response.mined_timestamp as i64, 0)
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC);
+                let outputs = response
+                    .outputs
+                    .into_iter()
+                    .map(|utxo| TransactionOutput::try_from(utxo).map_err(UtxoScannerError::ConversionError))
+                    .collect::<Result<Vec<_>, _>>()?;
+                total_scanned += outputs.len();
+
+                let start = Instant::now();
+                let found_outputs = self.scan_for_outputs(outputs).await?;
+                scan_for_outputs_profiling.push(start.elapsed());
+
+                let (mut count, mut amount) = self
+                    .import_utxos_to_transaction_service(found_outputs, current_height, mined_timestamp)
+                    .await?;
+                let block_hash = current_header_hash.try_into()?;
+                if let Some(scanned_block) = prev_scanned_block {
+                    if block_hash == scanned_block.header_hash {
+                        count += scanned_block.num_outputs.unwrap_or(0);
+                        amount += scanned_block.amount.unwrap_or_else(|| 0.into())
+                    } else {
+                        self.resources.db.save_scanned_block(scanned_block)?;
+                        self.resources.db.clear_scanned_blocks_before_height(
+                            current_height.saturating_sub(SCANNED_BLOCK_CACHE_SIZE),
+                            true,
+                        )?;
+
+                        if current_height % PROGRESS_REPORT_INTERVAL == 0 {
+                            debug!(
+                                target: LOG_TARGET,
+                                "Scanned up to block {} with a current tip_height of {}", current_height, tip_height
+                            );
+                            self.publish_event(UtxoScannerEvent::Progress {
+                                current_height,
+                                tip_height,
+                            });
+                        }
 
-            let (mut count, mut amount) = self
-                .import_utxos_to_transaction_service(found_outputs, current_height, mined_timestamp)
-                .await?;
-            let block_hash = current_header_hash.try_into()?;
-            if let Some(scanned_block) = prev_scanned_block {
-                if block_hash == scanned_block.header_hash {
-                    count += scanned_block.num_outputs.unwrap_or(0);
-                    amount += scanned_block.amount.unwrap_or_else(|| 0.into())
-                } else {
-                    self.resources.db.save_scanned_block(scanned_block)?;
-                    self.resources.db.clear_scanned_blocks_before_height(
-                        current_height.saturating_sub(SCANNED_BLOCK_CACHE_SIZE),
-                        true,
-                    )?;
-
-                    if current_height % PROGRESS_REPORT_INTERVAL == 0 {
-                        debug!(
-                            target: LOG_TARGET,
-                            "Scanned up to block {} with a current tip_height of {}", current_height, tip_height
-                        );
-                        self.publish_event(UtxoScannerEvent::Progress {
-                            current_height,
-                            tip_height,
-                        });
+                        num_recovered = num_recovered.saturating_add(count);
+                        total_amount += amount;
                     }
-
-                    num_recovered = num_recovered.saturating_add(count);
-                    total_amount += amount;
                 }
+                prev_scanned_block = Some(ScannedBlock {
+                    header_hash: block_hash,
+                    height: current_height,
+                    num_outputs: Some(count),
+                    amount: Some(amount),
+                    timestamp: Utc::now().naive_utc(),
+                });
             }
-            prev_scanned_block = Some(ScannedBlock {
-                header_hash: block_hash,
-                height: current_height,
-                num_outputs: Some(count),
-                amount: Some(amount),
-                timestamp: Utc::now().naive_utc(),
-            });
+        }
+        // Every block in a range has at least one output, so the peer should have returned some
+        if num_ranges > 0 && total_scanned == 0 {
+            return Err(UtxoScannerError::UtxoScanningError(
+                "Peer returned 0 UTXOs to scan".to_string(),
+            ));
         }
         // We need to update the last one
         if let Some(scanned_block) = prev_scanned_block {
@@ -552,6 +578,19 @@ where
             )?;
             self.resources.db.save_scanned_block(scanned_block)?;
         }
+        // If the blocks up to the tip were skipped, record the tip as scanned so that the next round starts after it
+        if !scans_to_tip {
+            self.resources
+                .db
+                .clear_scanned_blocks_before_height(tip_height.saturating_sub(SCANNED_BLOCK_CACHE_SIZE), true)?;
+            self.resources.db.save_scanned_block(ScannedBlock {
+                header_hash: end_header_hash,
+                height: tip_height,
+                num_outputs: Some(0),
+                amount: Some(MicroMinotari::from(0)),
+                timestamp: Utc::now().naive_utc(),
+            })?;
+        }
         trace!(
             target: LOG_TARGET,
             "bulletproof rewind profile - streamed {} outputs in {} ms",
@@ -568,6 +607,195 @@ where
         Ok((num_recovered, total_scanned as u64, total_amount))
     }
 
+    /// Uses the base node's compact block filters to find the blocks from `start_block` to the tip that may contain
+    /// outputs for this wallet, grouped into contiguous ranges. Returns `None` if block filters are not enabled or the
+    /// base node does not serve them, in which case every block has to be scanned.
+    ///
+    /// Block filters are not committed to in the block header, so a peer could omit the wallet's outputs from them.
+    /// Every batch is therefore compared with the filters of a second base node, and any disagreement (or the lack of
+    /// a second base node) also results in a full scan.
+    async fn fetch_candidate_block_ranges(
+        &mut self,
+        client: &mut BaseNodeWalletRpcClient,
+        peer: &NodeId,
+        start_block: &HeightHash,
+        end_header_hash: HashOutput,
+        tip_height: u64,
+    ) -> Result<Option<Vec<BlockRange>>, UtxoScannerError> {
+        if !self.use_block_filters {
+            return Ok(None);
+        }
+        let mut verification_client = match self.connect_to_filter_verification_peer(peer).await {
+            Some(client) => client,
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "No second base node available to verify block filters, scanning every block"
+                );
+                return Ok(None);
+            },
+        };
+        let elements = self.block_filter_query_elements().await?;
+        let mut ranges: Vec<BlockRange> = Vec::new();
+        let mut num_candidates = 0u64;
+        let mut height = start_block.height;
+        while height <= tip_height {
+            let end_height = cmp::min(height.saturating_add(BLOCK_FILTER_BATCH_SIZE - 1), tip_height);
+            let response = match client
+                .get_block_filters(GetBlockFiltersRequest {
+                    start_height: height,
+                    end_height,
+                })
+                .await
+            {
+                Ok(response) => response,
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Base node did not provide block filters ({}), scanning every block", e
+                    );
+                    return Ok(None);
+                },
+            };
+            match verification_client
+                .get_block_filters(GetBlockFiltersRequest {
+                    start_height: height,
+                    end_height,
+                })
+                .await
+            {
+                Ok(verification) if verification.filters == response.filters => {},
+                Ok(_) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Block filters for heights {} to {} differ between base nodes, scanning every block",
+                        height,
+                        end_height
+                    );
+                    return Ok(None);
+                },
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Could not verify block filters with a second base node ({}), scanning every block", e
+                    );
+                    return Ok(None);
+                },
+            }
+
+            let mut filters = response.filters.into_iter();
+            for expected_height in height..=end_height {
+                let filter = filters.next().ok_or_else(|| {
+                    UtxoScannerError::BaseNodeResponseError(format!(
+                        "Base node did not return a block filter for height {}",
+                        expected_height
+                    ))
+                })?;
+                if filter.height != expected_height {
+                    return Err(UtxoScannerError::BaseNodeResponseError(format!(
+                        "Base node returned the block filter for height {} instead of {}",
+                        filter.height, expected_height
+                    )));
+                }
+                let header_hash = HashOutput::try_from(filter.header_hash)?;
+                if (expected_height == start_block.height && header_hash != start_block.header_hash) ||
+                    (expected_height == tip_height && header_hash != end_header_hash)
+                {
+                    return Err(UtxoScannerError::UtxoScanningError(format!(
+                        "Block at height {} changed while fetching block filters",
+                        expected_height
+                    )));
+                }
+
+                let filter = BlockFilter::from_parts(filter.num_elements, filter.data);
+                if !filter.matches_any(&header_hash, &elements) {
+                    continue;
+                }
+                num_candidates += 1;
+                match ranges.last_mut() {
+                    Some(range) if range.end_height + 1 == expected_height => {
+                        range.end_height = expected_height;
+                        range.end_header_hash = header_hash;
+                    },
+                    _ => ranges.push(BlockRange {
+                        start_header_hash: header_hash,
+                        end_height: expected_height,
+                        end_header_hash: header_hash,
+                    }),
+                }
+            }
+            height = end_height + 1;
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Block filters matched {} of {} blocks from height {}",
+            num_candidates,
+            tip_height.saturating_sub(start_block.height) + 1,
+            start_block.height
+        );
+        Ok(Some(ranges))
+    }
+
+    /// Connects to a base node other than `sync_peer` to cross-check its block filters, trying the scanner's peer
+    /// seeds before the wallet's configured base node peers.
+    async fn connect_to_filter_verification_peer(
+        &mut self,
+        sync_peer: &NodeId,
+    ) -> Option<RpcClientLease<BaseNodeWalletRpcClient>> {
+        let mut candidates = self.peer_seeds.iter().map(NodeId::from_public_key).collect::<Vec<_>>();
+        if let Some((_, peers)) = self.resources.wallet_connectivity.get_base_node_peer_manager_state() {
+            for peer in peers {
+                if !candidates.contains(&peer.node_id) {
+                    candidates.push(peer.node_id);
+                }
+            }
+        }
+        candidates.retain(|p| p != sync_peer);
+
+        for candidate in candidates {
+            let mut connection = match self.resources.comms_connectivity.dial_peer(candidate.clone()).await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Could not connect to {} to verify block filters: {}", candidate, e
+                    );
+                    continue;
+                },
+            };
+            match connection
+                .connect_rpc_using_builder(BaseNodeWalletRpcClient::builder().with_deadline(Duration::from_secs(60)))
+                .await
+            {
+                Ok(client) => return Some(RpcClientLease::new(client)),
+                Err(e) => debug!(
+                    target: LOG_TARGET,
+                    "Could not open an RPC session with {} to verify block filters: {}", candidate, e
+                ),
+            }
+        }
+        None
+    }
+
+    /// The block filter elements relevant to this wallet: one for each of its known one-sided payment scripts, and the
+    /// stealth payment markers. Stealth scripts contain a one-time key the wallet cannot predict, so blocks with any
+    /// stealth payment are treated as candidates.
+    async fn block_filter_query_elements(&mut self) -> Result<Vec<BlockFilterElement>, UtxoScannerError> {
+        let known_scripts = self
+            .resources
+            .output_manager_service
+            .get_known_one_sided_payment_scripts()
+            .await?;
+        let mut elements = known_scripts
+            .iter()
+            .map(|s| BlockFilterElement::script(&s.script))
+            .collect::<Vec<_>>();
+        elements.push(BlockFilterElement::stealth_marker(OutputType::Standard));
+        elements.push(BlockFilterElement::stealth_marker(OutputType::Coinbase));
+        Ok(elements)
+    }
+
     async fn scan_for_outputs(
         &mut self,
         outputs: Vec<TransactionOutput>,
@@ -782,6 +1010,13 @@ where
     }
 }
 
+/// A contiguous run of blocks to fetch outputs for
+struct BlockRange {
+    start_header_hash: HashOutput,
+    end_height: u64,
+    end_header_hash: HashOutput,
+}
+
 struct HeightHash {
     height: u64,
     header_hash: HashOutput,
@@ -55,6 +55,7 @@ pub struct UtxoScannerServiceBuilder {
     retry_limit: usize,
     peers: Vec<CommsPublicKey>,
     mode: Option<UtxoScannerMode>,
+    use_block_filters: bool,
     one_sided_message: String,
     recovery_message: String,
 }
@@ -65,6 +66,7 @@ impl Default for UtxoScannerServiceBuilder {
             retry_limit: 0,
             peers: vec![],
             mode: None,
+            use_block_filters: false,
             one_sided_message: "Detected one-sided payment on blockchain".to_string(),
             recovery_message: "Output found on blockchain during Wallet Recovery".to_string(),
         }
@@ -89,6 +91,14 @@ impl UtxoScannerServiceBuilder {
         self
     }
 
+    /// Only scan the blocks whose compact block filter matches one of the wallet's known one-sided payment scripts.
+    /// Outputs the wallet can only identify by trial decryption, such as stealth and interactive payments, are missed
+    /// in the blocks that are skipped.
+    pub fn with_block_filters(&mut self, use_block_filters: bool) -> &mut Self {
+        self.use_block_filters = use_block_filters;
+        self
+    }
+
     pub fn with_one_sided_message(&mut self, message: String) -> &mut Self {
         self.one_sided_message = message;
         self
@@ -124,6 +134,7 @@ impl UtxoScannerServiceBuilder {
             self.peers.drain(..).collect(),
             self.retry_limit,
             self.mode.clone().unwrap_or_default(),
+            self.use_block_filters,
             resources,
             shutdown_signal,
             event_sender,
@@ -169,6 +180,7 @@ impl UtxoScannerServiceBuilder {
             self.peers.drain(..).collect(),
             self.retry_limit,
             self.mode.clone().unwrap_or_default(),
+            self.use_block_filters,
             resources,
             shutdown_signal,
             event_sender,
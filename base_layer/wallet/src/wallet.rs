@@ -242,6 +242,7 @@ where
                 wallet_database.clone(),
                 factories.clone(),
                 config.network,
+                config.use_block_filters,
             ));
 
         // Check if we have update config. FFI wallets don't do this, the update on mobile is done differently.
@@ -797,6 +798,38 @@ where
         let seed_words = master_seed.to_mnemonic(*language, None)?;
         Ok(seed_words)
     }
+
+    /// Exports the wallet's master seed as a 12 word BIP-39 phrase, for use with generic custody tools. The phrase does
+    /// not record the wallet birthday.
+    pub fn get_bip39_seed_words(&self) -> Result<SeedWords, WalletError> {
+        let master_seed = self.db.get_master_seed()?.ok_or_else(|| {
+            WalletError::WalletStorageError(WalletStorageError::RecoverySeedError(
+                "Cipher Seed not found".to_string(),
+            ))
+        })?;
+
+        Ok(master_seed.to_bip39_mnemonic()?)
+    }
+
+    /// Splits the wallet's master seed into `count` seed share mnemonics, any `threshold` of which recover the wallet
+    pub fn get_seed_shares(
+        &self,
+        threshold: u8,
+        count: u8,
+        language: &MnemonicLanguage,
+    ) -> Result<Vec<SeedWords>, WalletError> {
+        let master_seed = self.db.get_master_seed()?.ok_or_else(|| {
+            WalletError::WalletStorageError(WalletStorageError::RecoverySeedError(
+                "Cipher Seed not found".to_string(),
+            ))
+        })?;
+
+        master_seed
+            .to_shares(threshold, count)?
+            .iter()
+            .map(|share| share.to_mnemonic(*language).map_err(WalletError::from))
+            .collect()
+    }
 }
 
 pub fn read_or_create_master_seed<T: WalletBackend + 'static>(
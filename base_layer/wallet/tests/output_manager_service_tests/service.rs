@@ -107,11 +107,19 @@ struct TestOmsService {
     pub key_manager_handle: MemoryDbKeyManager,
 }
 
+async fn setup_output_manager_service<T: OutputManagerBackend + 'static>(
+    backend: T,
+    with_connection: bool,
+) -> TestOmsService {
+    setup_output_manager_service_with_config(backend, with_connection, OutputManagerServiceConfig::default()).await
+}
+
 #[allow(clippy::type_complexity)]
 #[allow(clippy::too_many_lines)]
-async fn setup_output_manager_service<T: OutputManagerBackend + 'static>(
+async fn setup_output_manager_service_with_config<T: OutputManagerBackend + 'static>(
     backend: T,
     with_connection: bool,
+    config: OutputManagerServiceConfig,
 ) -> TestOmsService {
     let shutdown = Shutdown::new();
     let factories = CryptoFactories::default();
@@ -159,7 +167,7 @@ async fn setup_output_manager_service<T: OutputManagerBackend + 'static>(
     let key_manager = create_memory_db_key_manager().unwrap();
 
     let output_manager_service = OutputManagerService::new(
-        OutputManagerServiceConfig { ..Default::default() },
+        config,
         oms_request_receiver,
         OutputManagerDatabase::new(backend),
         oms_event_publisher.clone(),
@@ -876,6 +884,192 @@ async fn send_no_change() {
     );
 }
 
+#[tokio::test]
+async fn send_with_branch_and_bound_ordering() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let mut oms = setup_output_manager_service(backend.clone(), true).await;
+
+    let fee_per_gram = MicroMinotari::from(4);
+    let constants = create_consensus_constants(0);
+    let fee_without_change = Fee::new(*constants.transaction_weight_params()).calculate(
+        fee_per_gram,
+        1,
+        2,
+        1,
+        default_features_and_scripts_size_byte_size()
+            .expect("Failed to get default features and scripts size byte size"),
+    );
+    for value in [2000, 5000, 8000, 20000] {
+        let uo = create_wallet_output_with_data(
+            script!(Nop).unwrap(),
+            OutputFeatures::default(),
+            &TestParams::new(&oms.key_manager_handle).await,
+            MicroMinotari::from(value),
+            &oms.key_manager_handle,
+        )
+        .await
+        .unwrap();
+        oms.output_manager_handle.add_output(uo.clone(), None).await.unwrap();
+        backend
+            .mark_outputs_as_unspent(vec![(uo.hash(&oms.key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+    }
+
+    // Only the 5000 and 8000 outputs pay for the amount and fee exactly, so no change output is needed
+    let stp = oms
+        .output_manager_handle
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            MicroMinotari::from(5000 + 8000) - fee_without_change,
+            UtxoSelectionCriteria::branch_and_bound(0),
+            OutputFeatures::default(),
+            fee_per_gram,
+            TransactionMetadata::default(),
+            TariScript::default(),
+            Covenant::default(),
+            MicroMinotari::zero(),
+            TariAddress::default(),
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(stp.get_amount_to_self().unwrap(), MicroMinotari::from(0));
+    assert_eq!(stp.get_fee_amount().unwrap(), fee_without_change);
+    let balance = oms.output_manager_handle.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, MicroMinotari::from(2000 + 20000));
+    assert_eq!(balance.pending_incoming_balance, MicroMinotari::from(0));
+}
+
+#[tokio::test]
+async fn send_with_branch_and_bound_ordering_leaves_excess_below_change_cost_to_fee() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let mut oms = setup_output_manager_service(backend.clone(), true).await;
+
+    let fee_per_gram = MicroMinotari::from(4);
+    let constants = create_consensus_constants(0);
+    let fee_calc = Fee::new(*constants.transaction_weight_params());
+    let fee_without_change = fee_calc.calculate(
+        fee_per_gram,
+        1,
+        2,
+        1,
+        default_features_and_scripts_size_byte_size()
+            .expect("Failed to get default features and scripts size byte size"),
+    );
+    // The change output the builder would add pays to a single public key
+    let cost_of_change = fee_calc.calculate(
+        fee_per_gram,
+        0,
+        0,
+        1,
+        TransactionWeight::latest().round_up_features_and_scripts_size(
+            OutputFeatures::default().get_serialized_size().unwrap() +
+                script!(PushPubKey(Box::default()))
+                    .unwrap()
+                    .get_serialized_size()
+                    .unwrap(),
+        ),
+    );
+    for value in [2000, 5000, 8000, 20000] {
+        let uo = create_wallet_output_with_data(
+            script!(Nop).unwrap(),
+            OutputFeatures::default(),
+            &TestParams::new(&oms.key_manager_handle).await,
+            MicroMinotari::from(value),
+            &oms.key_manager_handle,
+        )
+        .await
+        .unwrap();
+        oms.output_manager_handle.add_output(uo.clone(), None).await.unwrap();
+        backend
+            .mark_outputs_as_unspent(vec![(uo.hash(&oms.key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+    }
+
+    // The 5000 and 8000 outputs leave an excess that is just too small to pay for a change output
+    let excess = cost_of_change - MicroMinotari::from(1);
+    let stp = oms
+        .output_manager_handle
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            MicroMinotari::from(5000 + 8000) - fee_without_change - excess,
+            UtxoSelectionCriteria::branch_and_bound(0),
+            OutputFeatures::default(),
+            fee_per_gram,
+            TransactionMetadata::default(),
+            TariScript::default(),
+            Covenant::default(),
+            MicroMinotari::zero(),
+            TariAddress::default(),
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(stp.get_amount_to_self().unwrap(), MicroMinotari::from(0));
+    assert_eq!(stp.get_fee_amount().unwrap(), fee_without_change + excess);
+    let balance = oms.output_manager_handle.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, MicroMinotari::from(2000 + 20000));
+    assert_eq!(balance.pending_incoming_balance, MicroMinotari::from(0));
+}
+
+#[tokio::test]
+async fn send_with_random_ordering() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let mut oms = setup_output_manager_service(backend.clone(), true).await;
+
+    let fee_per_gram = MicroMinotari::from(4);
+    let values = [2000, 5000, 8000, 20000];
+    for value in values {
+        let uo = create_wallet_output_with_data(
+            script!(Nop).unwrap(),
+            OutputFeatures::default(),
+            &TestParams::new(&oms.key_manager_handle).await,
+            MicroMinotari::from(value),
+            &oms.key_manager_handle,
+        )
+        .await
+        .unwrap();
+        oms.output_manager_handle.add_output(uo.clone(), None).await.unwrap();
+        backend
+            .mark_outputs_as_unspent(vec![(uo.hash(&oms.key_manager_handle).await.unwrap(), true)])
+            .unwrap();
+    }
+
+    let amount = MicroMinotari::from(30000);
+    let stp = oms
+        .output_manager_handle
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            amount,
+            UtxoSelectionCriteria::random(0),
+            OutputFeatures::default(),
+            fee_per_gram,
+            TransactionMetadata::default(),
+            TariScript::default(),
+            Covenant::default(),
+            MicroMinotari::zero(),
+            TariAddress::default(),
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap();
+
+    // Every selection that covers the amount spends the 5000, 8000 and 20000 outputs; the 2000 one is optional
+    let balance = oms.output_manager_handle.get_balance().await.unwrap();
+    assert!(
+        balance.available_balance == MicroMinotari::from(0) || balance.available_balance == MicroMinotari::from(2000)
+    );
+    let selected_value = MicroMinotari::from(values.iter().sum::<u64>()) - balance.available_balance;
+    let change = selected_value - amount - stp.get_fee_amount().unwrap();
+    assert_eq!(stp.get_amount_to_self().unwrap(), change);
+    assert_eq!(balance.pending_incoming_balance, change);
+}
+
 #[tokio::test]
 async fn send_not_enough_for_change() {
     let (connection, _tempdir) = get_temp_sqlite_database_connection();
@@ -2139,6 +2333,95 @@ async fn test_txo_revalidation() {
     assert_eq!(unspent_txos.len(), 0);
 }
 
+#[tokio::test]
+async fn test_txo_validation_rejects_spent_status_without_proof() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone());
+    let config = OutputManagerServiceConfig {
+        verify_output_smt_proofs: true,
+        ..Default::default()
+    };
+    let mut oms = setup_output_manager_service_with_config(backend, true, config).await;
+
+    let output1_value = 1_000_000;
+    let output1 = create_wallet_output_with_data(
+        script!(Nop).unwrap(),
+        OutputFeatures::default(),
+        &TestParams::new(&oms.key_manager_handle).await,
+        MicroMinotari::from(output1_value),
+        &oms.key_manager_handle,
+    )
+    .await
+    .unwrap();
+    let output1_tx_output = output1.to_transaction_output(&oms.key_manager_handle).await.unwrap();
+    oms.output_manager_handle
+        .add_output_with_tx_id(TxId::from(1u64), output1, None)
+        .await
+        .unwrap();
+
+    let mut block1_header = BlockHeader::new(1);
+    block1_header.height = 1;
+    let mut block4_header = BlockHeader::new(1);
+    block4_header.height = 4;
+    let mut block_headers = HashMap::new();
+    block_headers.insert(1, block1_header.clone());
+    block_headers.insert(4, block4_header.clone());
+    oms.base_node_wallet_rpc_mock_state.set_blocks(block_headers);
+    oms.base_node_wallet_rpc_mock_state
+        .set_utxo_query_response(UtxoQueryResponses {
+            best_block_hash: block4_header.hash().to_vec(),
+            best_block_height: 4,
+            responses: vec![UtxoQueryResponse {
+                output: Some(output1_tx_output.clone().try_into().unwrap()),
+                mined_at_height: 1,
+                mined_in_block: block1_header.hash().to_vec(),
+                output_hash: output1_tx_output.hash().to_vec(),
+                mined_timestamp: 0,
+            }],
+        });
+    // The base node claims the output was spent, but sends no proof of it
+    oms.base_node_wallet_rpc_mock_state
+        .set_query_deleted_response(QueryDeletedResponse {
+            best_block_hash: block4_header.hash().to_vec(),
+            best_block_height: 4,
+            data: vec![QueryDeletedData {
+                mined_at_height: 1,
+                block_mined_in: block1_header.hash().to_vec(),
+                height_deleted_at: 4,
+                block_deleted_in: block4_header.hash().to_vec(),
+            }],
+        });
+
+    let mut event_stream = oms.output_manager_handle.get_event_stream();
+    oms.output_manager_handle.validate_txos().await.unwrap();
+    let delay = sleep(Duration::from_secs(60));
+    tokio::pin!(delay);
+    let mut validation_failed = false;
+    loop {
+        tokio::select! {
+            event = event_stream.recv() => {
+                match &*event.unwrap() {
+                    OutputManagerEvent::TxoValidationCommunicationFailure(_) => {
+                        validation_failed = true;
+                        break;
+                    },
+                    OutputManagerEvent::TxoValidationSuccess(_) => break,
+                    _ => {},
+                }
+            },
+            () = &mut delay => break,
+        }
+    }
+    assert!(validation_failed);
+
+    // The proofs were asked for at the tip the spent status was reported at
+    let proof_calls = oms.base_node_wallet_rpc_mock_state.take_output_smt_proofs_calls();
+    assert_eq!(proof_calls.len(), 1);
+    assert_eq!(proof_calls[0].header_hash, block4_header.hash().to_vec());
+    let unspent_txos = oms.output_manager_handle.get_unspent_outputs().await.unwrap();
+    assert_eq!(unspent_txos.len(), 1);
+}
+
 #[tokio::test]
 async fn test_get_status_by_tx_id() {
     let (connection, _tempdir) = get_temp_sqlite_database_connection();
@@ -626,6 +626,94 @@ pub async fn test_short_term_encumberance() {
     );
 }
 
+#[tokio::test]
+pub async fn test_replaced_transaction_keeps_inputs_until_resolved() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection);
+    let db = OutputManagerDatabase::new(backend);
+
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let mut outputs = Vec::new();
+    for value in [1000u64, 2000, 3000, 400, 500] {
+        let uo = make_input(&mut OsRng, value.into(), &OutputFeatures::default(), &key_manager).await;
+        let kmo = DbWalletOutput::from_wallet_output(uo, &key_manager, None, OutputSource::Standard, None, None)
+            .await
+            .unwrap();
+        outputs.push(kmo);
+    }
+    let (change_original, change_replacement) = (outputs.pop().unwrap(), outputs.pop().unwrap());
+    for kmo in &outputs {
+        db.add_unspent_output(kmo.clone()).unwrap();
+        db.mark_outputs_as_unspent(vec![(kmo.hash, true)]).unwrap();
+    }
+
+    // The original spends the first two outputs, the replacement only the first one
+    db.encumber_outputs(1u64.into(), outputs[0..2].to_vec(), vec![change_original.clone()])
+        .unwrap();
+    db.confirm_encumbered_outputs(1u64.into()).unwrap();
+    db.encumber_outputs(2u64.into(), vec![], vec![change_replacement.clone()])
+        .unwrap();
+    db.replace_pending_transaction_outputs(1u64.into(), 2u64.into(), &[outputs[0].commitment.clone()])
+        .unwrap();
+
+    // The original can still be mined, so its other input is not available yet
+    let balance = db.get_balance(None).unwrap();
+    assert_eq!(balance.available_balance, outputs[2].wallet_output.value);
+    assert_eq!(balance.pending_incoming_balance, change_replacement.wallet_output.value);
+
+    // The replacement is confirmed, so the input only the original spent is released
+    db.resolve_replaced_transaction_outputs(2u64.into(), 1u64.into(), &[outputs[0].commitment.clone()])
+        .unwrap();
+    let balance = db.get_balance(None).unwrap();
+    assert_eq!(
+        balance.available_balance,
+        outputs[1].wallet_output.value + outputs[2].wallet_output.value
+    );
+    assert_eq!(balance.pending_incoming_balance, change_replacement.wallet_output.value);
+}
+
+#[tokio::test]
+pub async fn test_replaced_transaction_confirmed_after_all() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection);
+    let db = OutputManagerDatabase::new(backend);
+
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let mut outputs = Vec::new();
+    for value in [1000u64, 2000, 3000, 400, 500] {
+        let uo = make_input(&mut OsRng, value.into(), &OutputFeatures::default(), &key_manager).await;
+        let kmo = DbWalletOutput::from_wallet_output(uo, &key_manager, None, OutputSource::Standard, None, None)
+            .await
+            .unwrap();
+        outputs.push(kmo);
+    }
+    let (change_original, change_replacement) = (outputs.pop().unwrap(), outputs.pop().unwrap());
+    for kmo in &outputs {
+        db.add_unspent_output(kmo.clone()).unwrap();
+        db.mark_outputs_as_unspent(vec![(kmo.hash, true)]).unwrap();
+    }
+
+    db.encumber_outputs(1u64.into(), outputs[0..2].to_vec(), vec![change_original.clone()])
+        .unwrap();
+    db.confirm_encumbered_outputs(1u64.into()).unwrap();
+    db.encumber_outputs(2u64.into(), vec![], vec![change_replacement.clone()])
+        .unwrap();
+    db.replace_pending_transaction_outputs(1u64.into(), 2u64.into(), &[outputs[0].commitment.clone()])
+        .unwrap();
+
+    // The original is confirmed instead, so both inputs stay spent and its change is pending again
+    let spent_inputs = vec![outputs[0].commitment.clone(), outputs[1].commitment.clone()];
+    db.resolve_replaced_transaction_outputs(1u64.into(), 2u64.into(), &spent_inputs)
+        .unwrap();
+    let balance = db.get_balance(None).unwrap();
+    assert_eq!(balance.available_balance, outputs[2].wallet_output.value);
+    assert_eq!(balance.pending_incoming_balance, change_original.wallet_output.value);
+    assert_eq!(
+        balance.pending_outgoing_balance,
+        outputs[0].wallet_output.value + outputs[1].wallet_output.value
+    );
+}
+
 #[tokio::test]
 pub async fn test_no_duplicate_outputs() {
     let (connection, _tempdir) = get_temp_sqlite_database_connection();
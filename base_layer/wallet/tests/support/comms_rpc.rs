@@ -41,15 +41,22 @@ use tari_core::{
         proto::wallet_rpc::{TxLocation, TxQueryResponse, TxSubmissionRejectionReason, TxSubmissionResponse},
         rpc::BaseNodeWalletService,
     },
-    blocks::BlockHeader,
+    blocks::{BlockFilter, BlockHeader},
     proto,
     proto::{
         base_node::{
+            BlockFilter as BlockFilterProto,
             ChainMetadata as ChainMetadataProto,
+            EstimateFeeRequest,
+            EstimateFeeResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
+            GetBlockFiltersRequest,
+            GetBlockFiltersResponse,
             GetMempoolFeePerGramStatsRequest,
             GetMempoolFeePerGramStatsResponse,
+            GetOutputSmtProofsRequest,
+            GetOutputSmtProofsResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
             Signatures as SignaturesProto,
@@ -111,6 +118,12 @@ pub struct BaseNodeWalletRpcMockState {
     utxos: Arc<Mutex<Vec<TransactionOutput>>>,
     blocks: Arc<Mutex<HashMap<u64, BlockHeader>>>,
     get_mempool_fee_per_gram_stats: Arc<Mutex<GetMempoolFeePerGramStatsResponse>>,
+    estimate_fee_calls: Arc<Mutex<Vec<EstimateFeeRequest>>>,
+    estimate_fee_response: Arc<Mutex<EstimateFeeResponse>>,
+    output_smt_proofs_calls: Arc<Mutex<Vec<GetOutputSmtProofsRequest>>>,
+    output_smt_proofs_response: Arc<Mutex<GetOutputSmtProofsResponse>>,
+    block_filters_calls: Arc<Mutex<Vec<GetBlockFiltersRequest>>>,
+    serve_block_filters: Arc<Mutex<bool>>,
     utxos_by_block: Arc<Mutex<Vec<UtxosByBlock>>>,
     sync_utxos_by_block_trigger_channel: Arc<Mutex<Option<mpsc::Receiver<usize>>>>,
 }
@@ -175,6 +188,12 @@ impl BaseNodeWalletRpcMockState {
             utxos: Arc::new(Mutex::new(Vec::new())),
             blocks: Arc::new(Mutex::new(Default::default())),
             get_mempool_fee_per_gram_stats: Default::default(),
+            estimate_fee_calls: Arc::new(Mutex::new(vec![])),
+            estimate_fee_response: Default::default(),
+            output_smt_proofs_calls: Arc::new(Mutex::new(vec![])),
+            output_smt_proofs_response: Default::default(),
+            block_filters_calls: Arc::new(Mutex::new(vec![])),
+            serve_block_filters: Arc::new(Mutex::new(false)),
 
             utxos_by_block: Arc::new(Mutex::new(vec![])),
             sync_utxos_by_block_trigger_channel: Arc::new(Mutex::new(None)),
@@ -243,6 +262,35 @@ impl BaseNodeWalletRpcMockState {
         *lock = resp;
     }
 
+    pub fn set_estimate_fee_response(&self, resp: EstimateFeeResponse) {
+        let mut lock = acquire_lock!(self.estimate_fee_response);
+        *lock = resp;
+    }
+
+    pub fn take_estimate_fee_calls(&self) -> Vec<EstimateFeeRequest> {
+        acquire_lock!(self.estimate_fee_calls).drain(..).collect()
+    }
+
+    pub fn set_output_smt_proofs_response(&self, resp: GetOutputSmtProofsResponse) {
+        let mut lock = acquire_lock!(self.output_smt_proofs_response);
+        *lock = resp;
+    }
+
+    pub fn take_output_smt_proofs_calls(&self) -> Vec<GetOutputSmtProofsRequest> {
+        acquire_lock!(self.output_smt_proofs_calls).drain(..).collect()
+    }
+
+    /// Serve block filters built from the blocks set with `set_utxos_by_block`, by default the mock does not support
+    /// block filters
+    pub fn set_serve_block_filters(&self, serve: bool) {
+        let mut lock = acquire_lock!(self.serve_block_filters);
+        *lock = serve;
+    }
+
+    pub fn take_block_filters_calls(&self) -> Vec<GetBlockFiltersRequest> {
+        acquire_lock!(self.block_filters_calls).drain(..).collect()
+    }
+
     pub fn set_utxos_by_block(&self, utxos_by_block: Vec<UtxosByBlock>) {
         let mut lock = acquire_lock!(self.utxos_by_block);
         *lock = utxos_by_block;
@@ -527,6 +575,7 @@ impl Default for BaseNodeWalletRpcMockState {
     }
 }
 
+#[derive(Clone)]
 pub struct BaseNodeWalletRpcMockService {
     state: BaseNodeWalletRpcMockState,
 }
@@ -850,6 +899,53 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
             acquire_lock!(self.state.get_mempool_fee_per_gram_stats).clone(),
         ))
     }
+
+    async fn estimate_fee(
+        &self,
+        request: Request<EstimateFeeRequest>,
+    ) -> Result<Response<EstimateFeeResponse>, RpcStatus> {
+        acquire_lock!(self.state.estimate_fee_calls).push(request.into_message());
+        Ok(Response::new(acquire_lock!(self.state.estimate_fee_response).clone()))
+    }
+
+    async fn get_output_smt_proofs(
+        &self,
+        request: Request<GetOutputSmtProofsRequest>,
+    ) -> Result<Response<GetOutputSmtProofsResponse>, RpcStatus> {
+        acquire_lock!(self.state.output_smt_proofs_calls).push(request.into_message());
+        Ok(Response::new(
+            acquire_lock!(self.state.output_smt_proofs_response).clone(),
+        ))
+    }
+
+    async fn get_block_filters(
+        &self,
+        request: Request<GetBlockFiltersRequest>,
+    ) -> Result<Response<GetBlockFiltersResponse>, RpcStatus> {
+        let request = request.into_message();
+        acquire_lock!(self.state.block_filters_calls).push(request.clone());
+        if !*acquire_lock!(self.state.serve_block_filters) {
+            return Err(RpcStatus::not_implemented("Block filters are not enabled in the mock"));
+        }
+
+        let mut blocks = acquire_lock!(self.state.utxos_by_block).clone();
+        blocks.sort_by(|a, b| a.height.cmp(&b.height));
+        let filters = blocks
+            .into_iter()
+            .filter(|b| b.height >= request.start_height && b.height <= request.end_height)
+            .map(|b| {
+                let header_hash = FixedHash::try_from(b.header_hash.as_slice()).unwrap();
+                let (num_elements, data) = BlockFilter::from_outputs(&header_hash, &b.utxos).into_parts();
+                BlockFilterProto {
+                    header_hash: b.header_hash,
+                    height: b.height,
+                    num_elements,
+                    data,
+                }
+            })
+            .collect();
+        Ok(Response::new(GetBlockFiltersResponse { filters }))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -27,7 +27,7 @@ use log::*;
 use minotari_wallet::output_manager_service::{
     error::OutputManagerError,
     handle::{OutputManagerEvent, OutputManagerHandle, OutputManagerRequest, OutputManagerResponse, RecoveredOutput},
-    storage::models::DbWalletOutput,
+    storage::models::{DbWalletOutput, KnownOneSidedPaymentScript},
 };
 use tari_common_types::transaction::TxId;
 use tari_service_framework::{reply_channel, reply_channel::Receiver};
@@ -144,6 +144,15 @@ impl OutputManagerServiceMock {
                         warn!(target: LOG_TARGET, "Failed to send reply");
                     });
             },
+            OutputManagerRequest::GetKnownOneSidedPaymentScripts => {
+                let _result = reply_tx
+                    .send(Ok(OutputManagerResponse::KnownOneSidedPaymentScripts(
+                        acquire_lock!(self.state.known_scripts).clone(),
+                    )))
+                    .inspect_err(|_| {
+                        warn!(target: LOG_TARGET, "Failed to send reply");
+                    });
+            },
             OutputManagerRequest::ValidateUtxos => {},
             _ => panic!("Output Manager Service Mock does not support this call"),
         }
@@ -154,6 +163,7 @@ impl OutputManagerServiceMock {
 pub struct OutputManagerMockState {
     pub recoverable_outputs: Arc<Mutex<Vec<DbWalletOutput>>>,
     pub one_sided_payments: Arc<Mutex<Vec<DbWalletOutput>>>,
+    pub known_scripts: Arc<Mutex<Vec<KnownOneSidedPaymentScript>>>,
 }
 
 impl OutputManagerMockState {
@@ -161,6 +171,7 @@ impl OutputManagerMockState {
         Self {
             recoverable_outputs: Arc::new(Mutex::new(Vec::new())),
             one_sided_payments: Arc::new(Mutex::new(Vec::new())),
+            known_scripts: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -173,6 +184,11 @@ impl OutputManagerMockState {
         let mut lock = acquire_lock!(self.one_sided_payments);
         *lock = outputs;
     }
+
+    pub fn set_known_scripts(&self, scripts: Vec<KnownOneSidedPaymentScript>) {
+        let mut lock = acquire_lock!(self.known_scripts);
+        *lock = scripts;
+    }
 }
 
 impl Default for OutputManagerMockState {
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     mem::size_of,
     path::Path,
@@ -72,11 +73,17 @@ use minotari_wallet::{
     },
     transaction_service::{
         config::TransactionServiceConfig,
-        handle::{TransactionEvent, TransactionSendStatus, TransactionServiceHandle},
+        handle::{BatchRecipient, FeeRate, TransactionEvent, TransactionSendStatus, TransactionServiceHandle},
         service::TransactionService,
         storage::{
             database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
-            models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
+            models::{
+                CompletedTransaction,
+                InboundTransaction,
+                OutboundTransaction,
+                TxCancellationReason,
+                WalletTransaction,
+            },
             sqlite_db::TransactionServiceSqliteDatabase,
         },
         TransactionServiceInitializer,
@@ -194,6 +201,44 @@ async fn setup_transaction_service<P: AsRef<Path>>(
     WalletConnectivityHandle,
     MemoryDbKeyManager,
     OutputManagerSqliteDatabase,
+) {
+    let wallet_type = WalletType::ProvidedKeys(ProvidedKeysWallet {
+        public_spend_key: PublicKey::from_secret_key(node_identity.secret_key()),
+        private_spend_key: Some(node_identity.secret_key().clone()),
+        view_key: SK::random(&mut OsRng),
+        private_comms_key: Some(node_identity.secret_key().clone()),
+    });
+    setup_transaction_service_with_wallet_type(
+        node_identity,
+        peers,
+        consensus_manager,
+        factories,
+        db_connection,
+        database_path,
+        discovery_request_timeout,
+        shutdown_signal,
+        wallet_type,
+    )
+    .await
+}
+
+async fn setup_transaction_service_with_wallet_type<P: AsRef<Path>>(
+    node_identity: Arc<NodeIdentity>,
+    peers: Vec<Arc<NodeIdentity>>,
+    consensus_manager: ConsensusManager,
+    factories: CryptoFactories,
+    db_connection: WalletDbConnection,
+    database_path: P,
+    discovery_request_timeout: Duration,
+    shutdown_signal: ShutdownSignal,
+    wallet_type: WalletType,
+) -> (
+    TransactionServiceHandle,
+    OutputManagerHandle,
+    CommsNode,
+    WalletConnectivityHandle,
+    MemoryDbKeyManager,
+    OutputManagerSqliteDatabase,
 ) {
     let (publisher, subscription_factory) = pubsub_connector(100);
     let subscription_factory = Arc::new(subscription_factory);
@@ -228,12 +273,7 @@ async fn setup_transaction_service<P: AsRef<Path>>(
     let key_ga = Key::from_slice(&key);
     let db_cipher = XChaCha20Poly1305::new(key_ga);
     let kms_backend = KeyManagerSqliteDatabase::init(connection, db_cipher);
-    let wallet_type = Arc::new(WalletType::ProvidedKeys(ProvidedKeysWallet {
-        public_spend_key: PublicKey::from_secret_key(node_identity.secret_key()),
-        private_spend_key: Some(node_identity.secret_key().clone()),
-        view_key: SK::random(&mut OsRng),
-        private_comms_key: Some(node_identity.secret_key().clone()),
-    }));
+    let wallet_type = Arc::new(wallet_type);
     let handles = StackBuilder::new(shutdown_signal)
         .add_initializer(RegisterHandle::new(dht))
         .add_initializer(RegisterHandle::new(comms.connectivity()))
@@ -1669,86 +1709,820 @@ async fn send_one_sided_transaction_to_other() {
 }
 
 #[tokio::test]
-async fn recover_one_sided_transaction() {
+async fn bump_fee_of_one_sided_transaction() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+
+    let db_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            db_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let initial_wallet_value = 25000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let value = 10000.into();
+    let random_pvt_key = PrivateKey::random(&mut OsRng);
+    let bob_view_key = PublicKey::from_secret_key(&random_pvt_key);
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        bob_view_key,
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let tx_id = alice_ts
+        .send_one_sided_transaction(
+            bob_address.clone(),
+            value,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            PaymentId::open_from_str("Fee bump"),
+        )
+        .await
+        .expect("Alice sending one-sided tx to Bob");
+    let original_tx = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+
+    // The new fee per gram must be higher than the original
+    assert!(alice_ts.bump_fee(tx_id, 20.into()).await.is_err());
+
+    let new_tx_id = alice_ts.bump_fee(tx_id, 40.into()).await.unwrap();
+    assert_ne!(new_tx_id, tx_id);
+
+    let replacement_tx = alice_ts.get_completed_transaction(new_tx_id).await.unwrap();
+    assert!(replacement_tx.fee > original_tx.fee);
+    assert_eq!(replacement_tx.amount, value);
+    assert_eq!(replacement_tx.destination_address, bob_address);
+    assert_eq!(
+        replacement_tx.transaction.body.inputs()[0].commitment().unwrap(),
+        original_tx.transaction.body.inputs()[0].commitment().unwrap()
+    );
+    // The replacement is a plain one-sided payment, like the original
+    let one_sided_script = push_pubkey_script(bob_address.public_spend_key());
+    assert!(replacement_tx
+        .transaction
+        .body
+        .outputs()
+        .iter()
+        .any(|output| output.script == one_sided_script));
+
+    let cancelled_txs = alice_ts.get_cancelled_completed_transactions().await.unwrap();
+    let replaced_tx = cancelled_txs
+        .get(&tx_id)
+        .expect("Original transaction should be marked as replaced");
+    assert_eq!(replaced_tx.cancelled, Some(TxCancellationReason::Replaced));
+
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().pending_incoming_balance,
+        initial_wallet_value - value - replacement_tx.fee
+    );
+
+    // A replaced transaction cannot be bumped again
+    assert!(alice_ts.bump_fee(tx_id, 80.into()).await.is_err());
+}
+
+#[tokio::test]
+async fn failed_fee_bump_leaves_original_transaction_intact() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+
+    let db_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            db_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let uo1 = make_input(
+        &mut OsRng,
+        25000.into(),
+        &OutputFeatures::default(),
+        &key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let random_pvt_key = PrivateKey::random(&mut OsRng);
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&random_pvt_key),
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let tx_id = alice_ts
+        .send_one_sided_transaction(
+            bob_address,
+            10000.into(),
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            PaymentId::open_from_str("Fee bump"),
+        )
+        .await
+        .expect("Alice sending one-sided tx to Bob");
+    let original_tx = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+    let balance = alice_oms.get_balance().await.unwrap();
+
+    // The original inputs cannot cover this fee, so the replacement cannot be built
+    assert!(alice_ts.bump_fee(tx_id, 1_000_000.into()).await.is_err());
+
+    let unchanged_tx = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+    assert_eq!(unchanged_tx.status, original_tx.status);
+    assert_eq!(unchanged_tx.cancelled, None);
+    assert!(!alice_ts
+        .get_cancelled_completed_transactions()
+        .await
+        .unwrap()
+        .contains_key(&tx_id));
+    // The inputs are still encumbered to the original and its change is still pending
+    assert_eq!(alice_oms.get_balance().await.unwrap(), balance);
+
+    // The original can still be replaced afterwards
+    let new_tx_id = alice_ts.bump_fee(tx_id, 40.into()).await.unwrap();
+    assert_ne!(new_tx_id, tx_id);
+}
+
+#[tokio::test]
+async fn recover_one_sided_transaction() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    // Alice's parameters
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    // Bob's parameters
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let base_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    log::info!(
+        "manage_single_transaction: Alice: '{}', Bob: '{}', Base: '{}'",
+        alice_node_identity.node_id().short_str(),
+        bob_node_identity.node_id().short_str(),
+        base_node_identity.node_id().short_str()
+    );
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
+
+    let alice_connection = make_wallet_database_memory_connection();
+    let bob_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            alice_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
+        setup_transaction_service(
+            bob_node_identity.clone(),
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            bob_connection,
+            database_path2,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+    let script = push_pubkey_script(bob_node_identity.public_key());
+    let known_script = KnownOneSidedPaymentScript {
+        script_hash: script.as_hash::<Blake2b<U32>>().unwrap().to_vec(),
+        script_key_id: bob_key_manager_handle
+            .import_key(bob_node_identity.secret_key().clone())
+            .await
+            .unwrap(),
+        script,
+        input: ExecutionStack::default(),
+        script_lock_height: 0,
+    };
+    let mut cloned_bob_oms = bob_oms.clone();
+    cloned_bob_oms.add_known_script(known_script).await.unwrap();
+
+    let initial_wallet_value = 25000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &alice_key_manager_handle,
+    )
+    .await;
+    let mut alice_oms_clone = alice_oms;
+    alice_oms_clone.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let value = 10000.into();
+    let mut alice_ts_clone = alice_ts.clone();
+    let bob_view_key = bob_key_manager_handle.get_view_key().await.unwrap();
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        bob_view_key.pub_key,
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let tx_id = alice_ts_clone
+        .send_one_sided_transaction(
+            bob_address,
+            value,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            PaymentId::Empty,
+        )
+        .await
+        .expect("Alice sending one-sided tx to Bob");
+
+    let completed_tx = alice_ts
+        .get_completed_transaction(tx_id)
+        .await
+        .expect("Could not find completed one-sided tx");
+    let outputs = completed_tx.transaction.body.outputs().clone();
+
+    let recovered_outputs_1 = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.iter().map(|o| (o.clone(), None)).collect())
+        .await
+        .unwrap();
+    // Bob should be able to claim 1 output.
+    assert_eq!(1, recovered_outputs_1.len());
+    assert_eq!(value, recovered_outputs_1[0].output.value);
+
+    // Should ignore already existing outputs
+    let recovered_outputs_2 = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
+        .await
+        .unwrap();
+    assert!(recovered_outputs_2.is_empty());
+}
+
+#[tokio::test]
+async fn recover_stealth_one_sided_transaction() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    // Alice's parameters
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    // Bob's parameters
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let base_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    log::info!(
+        "manage_single_transaction: Alice: '{}', Bob: '{}', Base: '{}'",
+        alice_node_identity.node_id().short_str(),
+        bob_node_identity.node_id().short_str(),
+        base_node_identity.node_id().short_str()
+    );
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
+
+    let alice_connection = make_wallet_database_memory_connection();
+    let bob_connection = make_wallet_database_memory_connection();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            alice_connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
+        setup_transaction_service(
+            bob_node_identity.clone(),
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            bob_connection,
+            database_path2,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let bob_view_key = bob_key_manager_handle.get_view_key().await.unwrap();
+
+    let initial_wallet_value = 25000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &alice_key_manager_handle,
+    )
+    .await;
+    let mut alice_oms_clone = alice_oms;
+    alice_oms_clone.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let value = 10000.into();
+    let mut alice_ts_clone = alice_ts.clone();
+
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        bob_view_key.pub_key,
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let tx_id = alice_ts_clone
+        .send_one_sided_to_stealth_address_transaction(
+            bob_address,
+            value,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            20.into(),
+            PaymentId::Empty,
+        )
+        .await
+        .expect("Alice sending one-sided tx to Bob");
+
+    let completed_tx = alice_ts
+        .get_completed_transaction(tx_id)
+        .await
+        .expect("Could not find completed one-sided tx");
+    let outputs = completed_tx.transaction.body.outputs().clone();
+
+    let recovered_outputs_1 = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.iter().map(|o| (o.clone(), None)).collect())
+        .await
+        .unwrap();
+    // Bob should be able to claim 1 output.
+    assert_eq!(1, recovered_outputs_1.len());
+    assert_eq!(value, recovered_outputs_1[0].output.value);
+
+    // Should ignore already existing outputs
+    let recovered_outputs_2 = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
+        .await
+        .unwrap();
+    assert!(recovered_outputs_2.is_empty());
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn send_batch_one_sided_transaction() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let carol_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let temp_dir3 = tempdir().unwrap();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            temp_dir.path().to_str().unwrap().to_string(),
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
+        setup_transaction_service(
+            bob_node_identity.clone(),
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            temp_dir2.path().to_str().unwrap().to_string(),
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+    let (_carol_ts, mut carol_oms, _carol_comms, _carol_connectivity, carol_key_manager_handle, _carol_db) =
+        setup_transaction_service(
+            carol_node_identity.clone(),
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            temp_dir3.path().to_str().unwrap().to_string(),
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let initial_wallet_value = 100000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &alice_key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        bob_key_manager_handle.get_view_key().await.unwrap().pub_key,
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let carol_address = TariAddress::new_dual_address_with_default_features(
+        carol_key_manager_handle.get_view_key().await.unwrap().pub_key,
+        carol_node_identity.public_key().clone(),
+        network,
+    );
+    let recipients = vec![
+        BatchRecipient {
+            destination: bob_address.clone(),
+            amount: 10000.into(),
+            payment_id: PaymentId::open_from_str("payout 1"),
+        },
+        BatchRecipient {
+            destination: carol_address,
+            amount: 20000.into(),
+            payment_id: PaymentId::open_from_str("payout 2"),
+        },
+        BatchRecipient {
+            destination: bob_address,
+            amount: 30000.into(),
+            payment_id: PaymentId::open_from_str("payout 3"),
+        },
+    ];
+    let total_sent = MicroMinotari::from(60000);
+
+    let tx_id = alice_ts
+        .send_batch_one_sided_transaction(recipients, UtxoSelectionCriteria::default(), 20.into())
+        .await
+        .expect("Alice sending batch one-sided tx");
+
+    let completed_tx = alice_ts
+        .get_completed_transaction(tx_id)
+        .await
+        .expect("Could not find completed batch tx");
+    assert_eq!(completed_tx.amount, total_sent);
+    let body = &completed_tx.transaction.body;
+    assert_eq!(body.kernels().len(), 1);
+    // Three recipient outputs and one change output
+    assert_eq!(body.outputs().len(), 4);
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().pending_incoming_balance,
+        initial_wallet_value - total_sent - completed_tx.fee
+    );
+
+    let outputs = body.outputs().clone();
+    let mut bob_recovered = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.iter().map(|o| (o.clone(), None)).collect())
+        .await
+        .unwrap();
+    bob_recovered.sort_by_key(|o| o.output.value);
+    assert_eq!(bob_recovered.len(), 2);
+    assert_eq!(bob_recovered[0].output.value, 10000.into());
+    assert_eq!(bob_recovered[0].output.payment_id.user_data_as_string(), "payout 1");
+    assert_eq!(bob_recovered[1].output.value, 30000.into());
+    assert_eq!(bob_recovered[1].output.payment_id.user_data_as_string(), "payout 3");
+
+    let carol_recovered = carol_oms
+        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
+        .await
+        .unwrap();
+    assert_eq!(carol_recovered.len(), 1);
+    assert_eq!(carol_recovered[0].output.value, 20000.into());
+    assert_eq!(carol_recovered[0].output.payment_id.user_data_as_string(), "payout 2");
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn prepare_sign_and_broadcast_offline_transaction() {
+    let network = Network::LocalNet;
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+        setup_transaction_service(
+            alice_node_identity,
+            vec![],
+            consensus_manager.clone(),
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            temp_dir.path().to_str().unwrap().to_string(),
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
+        setup_transaction_service(
+            bob_node_identity.clone(),
+            vec![],
+            consensus_manager,
+            factories.clone(),
+            make_wallet_database_memory_connection(),
+            temp_dir2.path().to_str().unwrap().to_string(),
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        )
+        .await;
+
+    let initial_wallet_value = 100000.into();
+    let uo1 = make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &OutputFeatures::default(),
+        &alice_key_manager_handle,
+    )
+    .await;
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
+    alice_db
+        .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
+        .unwrap();
+
+    let bob_address = TariAddress::new_dual_address_with_default_features(
+        bob_key_manager_handle.get_view_key().await.unwrap().pub_key,
+        bob_node_identity.public_key().clone(),
+        network,
+    );
+    let recipients = vec![BatchRecipient {
+        destination: bob_address,
+        amount: 25000.into(),
+        payment_id: PaymentId::open_from_str("offline"),
+    }];
+
+    let unsigned = alice_ts
+        .prepare_offline_transaction(
+            recipients,
+            UtxoSelectionCriteria::default(),
+            20.into(),
+            PaymentId::Empty,
+        )
+        .await
+        .expect("Alice preparing offline tx");
+    assert_eq!(unsigned.inputs.len(), 1);
+    assert_eq!(unsigned.total_input_value().unwrap(), initial_wallet_value);
+    // The selected input stays encumbered until the signed transaction comes back
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().available_balance,
+        MicroMinotari::zero()
+    );
+    let pending_outbound = alice_ts.get_pending_outbound_transactions().await.unwrap();
+    assert_eq!(pending_outbound.len(), 1);
+    assert_eq!(pending_outbound[&unsigned.tx_id].amount, MicroMinotari::from(25000));
+    let change = unsigned.change;
+
+    // The bundle must survive being carried to the signer as a file
+    let unsigned = serde_json::from_str(&serde_json::to_string(&unsigned).unwrap()).unwrap();
+    let signed = alice_ts
+        .sign_offline_transaction(unsigned)
+        .await
+        .expect("Alice signing offline tx");
+    let tx_id = signed.tx_id;
+    let outputs = signed.transaction.body.outputs().clone();
+    // One recipient output and one change output
+    assert_eq!(outputs.len(), 2);
+
+    // A transaction spending different inputs is rejected
+    let mut tampered = signed.clone();
+    tampered.tx_id = TxId::new_random();
+    assert!(alice_ts.broadcast_signed_transaction(tampered).await.is_err());
+
+    assert_eq!(
+        alice_ts
+            .broadcast_signed_transaction(signed)
+            .await
+            .expect("Alice broadcasting signed tx"),
+        tx_id
+    );
+    let completed_tx = alice_ts
+        .get_completed_transaction(tx_id)
+        .await
+        .expect("Could not find completed offline tx");
+    assert_eq!(completed_tx.amount, MicroMinotari::from(25000));
+    assert!(alice_ts.get_pending_outbound_transactions().await.unwrap().is_empty());
+    // The change output the signer created is expected by the preparing wallet
+    assert_eq!(alice_oms.get_balance().await.unwrap().pending_incoming_balance, change);
+
+    let bob_recovered = bob_oms
+        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
+        .await
+        .unwrap();
+    assert_eq!(bob_recovered.len(), 1);
+    assert_eq!(bob_recovered[0].output.value, 25000.into());
+    assert_eq!(bob_recovered[0].output.payment_id.user_data_as_string(), "offline");
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn view_only_wallet_prepares_offline_transaction_for_seed_wallet() {
     let network = Network::LocalNet;
     let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let factories = CryptoFactories::default();
-    // Alice's parameters
     let alice_node_identity = Arc::new(NodeIdentity::random(
         &mut OsRng,
         get_next_memory_address(),
         PeerFeatures::COMMUNICATION_NODE,
     ));
-
-    // Bob's parameters
-    let bob_node_identity = Arc::new(NodeIdentity::random(
+    let signer_node_identity = Arc::new(NodeIdentity::random(
         &mut OsRng,
         get_next_memory_address(),
         PeerFeatures::COMMUNICATION_NODE,
     ));
-
-    let base_node_identity = Arc::new(NodeIdentity::random(
+    let watcher_node_identity = Arc::new(NodeIdentity::random(
         &mut OsRng,
         get_next_memory_address(),
         PeerFeatures::COMMUNICATION_NODE,
     ));
 
-    log::info!(
-        "manage_single_transaction: Alice: '{}', Bob: '{}', Base: '{}'",
-        alice_node_identity.node_id().short_str(),
-        bob_node_identity.node_id().short_str(),
-        base_node_identity.node_id().short_str()
-    );
-
     let temp_dir = tempdir().unwrap();
     let temp_dir2 = tempdir().unwrap();
-    let database_path = temp_dir.path().to_str().unwrap().to_string();
-    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
-
-    let alice_connection = make_wallet_database_memory_connection();
-    let bob_connection = make_wallet_database_memory_connection();
+    let temp_dir3 = tempdir().unwrap();
 
     let shutdown = Shutdown::new();
-    let (mut alice_ts, alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
         setup_transaction_service(
-            alice_node_identity,
+            alice_node_identity.clone(),
             vec![],
             consensus_manager.clone(),
             factories.clone(),
-            alice_connection,
-            database_path,
+            make_wallet_database_memory_connection(),
+            temp_dir.path().to_str().unwrap().to_string(),
             Duration::from_secs(0),
             shutdown.to_signal(),
         )
         .await;
-
-    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
-        setup_transaction_service(
-            bob_node_identity.clone(),
+    // The seed wallet holds the spend key and never sees the chain
+    let (mut signer_ts, _signer_oms, _signer_comms, _signer_connectivity, signer_key_manager_handle, _signer_db) =
+        setup_transaction_service_with_wallet_type(
+            signer_node_identity,
             vec![],
-            consensus_manager,
+            consensus_manager.clone(),
             factories.clone(),
-            bob_connection,
-            database_path2,
+            make_wallet_database_memory_connection(),
+            temp_dir2.path().to_str().unwrap().to_string(),
             Duration::from_secs(0),
             shutdown.to_signal(),
+            WalletType::DerivedKeys,
         )
         .await;
-    let script = push_pubkey_script(bob_node_identity.public_key());
-    let known_script = KnownOneSidedPaymentScript {
-        script_hash: script.as_hash::<Blake2b<U32>>().unwrap().to_vec(),
-        script_key_id: bob_key_manager_handle
-            .import_key(bob_node_identity.secret_key().clone())
-            .await
-            .unwrap(),
-        script,
-        input: ExecutionStack::default(),
-        script_lock_height: 0,
-    };
-    let mut cloned_bob_oms = bob_oms.clone();
-    cloned_bob_oms.add_known_script(known_script).await.unwrap();
+    // The watching wallet only knows the keys exported from the seed wallet
+    let spend_public_key = signer_key_manager_handle.get_spend_key().await.unwrap().pub_key;
+    let view_key = signer_key_manager_handle.get_private_view_key().await.unwrap();
+    let watcher_wallet_type = WalletType::ProvidedKeys(ProvidedKeysWallet {
+        public_spend_key: spend_public_key.clone(),
+        private_spend_key: None,
+        view_key: view_key.clone(),
+        private_comms_key: Some(watcher_node_identity.secret_key().clone()),
+    });
+    let (
+        mut watcher_ts,
+        mut watcher_oms,
+        _watcher_comms,
+        _watcher_connectivity,
+        watcher_key_manager_handle,
+        watcher_db,
+    ) = setup_transaction_service_with_wallet_type(
+        watcher_node_identity,
+        vec![],
+        consensus_manager,
+        factories.clone(),
+        make_wallet_database_memory_connection(),
+        temp_dir3.path().to_str().unwrap().to_string(),
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+        watcher_wallet_type,
+    )
+    .await;
 
-    let initial_wallet_value = 25000.into();
+    let initial_wallet_value = 100000.into();
     let uo1 = make_input(
         &mut OsRng,
         initial_wallet_value,
@@ -1756,124 +2530,152 @@ async fn recover_one_sided_transaction() {
         &alice_key_manager_handle,
     )
     .await;
-    let mut alice_oms_clone = alice_oms;
-    alice_oms_clone.add_output(uo1.clone(), None).await.unwrap();
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
     alice_db
         .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
         .unwrap();
 
-    let value = 10000.into();
-    let mut alice_ts_clone = alice_ts.clone();
-    let bob_view_key = bob_key_manager_handle.get_view_key().await.unwrap();
-    let bob_address = TariAddress::new_dual_address_with_default_features(
-        bob_view_key.pub_key,
-        bob_node_identity.public_key().clone(),
+    // Alice funds the seed wallet, and the watching wallet finds the payment
+    let signer_address = TariAddress::new_dual_address_with_default_features(
+        PublicKey::from_secret_key(&view_key),
+        spend_public_key,
         network,
     );
-    let tx_id = alice_ts_clone
-        .send_one_sided_transaction(
-            bob_address,
-            value,
+    let funding_tx_id = alice_ts
+        .send_batch_one_sided_transaction(
+            vec![BatchRecipient {
+                destination: signer_address,
+                amount: 50000.into(),
+                payment_id: PaymentId::open_from_str("funding"),
+            }],
+            UtxoSelectionCriteria::default(),
+            20.into(),
+        )
+        .await
+        .expect("Alice funding the seed wallet");
+    let funding_tx = alice_ts.get_completed_transaction(funding_tx_id).await.unwrap();
+    let watcher_recovered = watcher_oms
+        .scan_outputs_for_one_sided_payments(
+            funding_tx
+                .transaction
+                .body
+                .outputs()
+                .iter()
+                .map(|o| (o.clone(), None))
+                .collect(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(watcher_recovered.len(), 1);
+    assert_eq!(watcher_recovered[0].output.value, 50000.into());
+    watcher_db
+        .mark_outputs_as_unspent(vec![(
+            watcher_recovered[0]
+                .output
+                .hash(&watcher_key_manager_handle)
+                .await
+                .unwrap(),
+            true,
+        )])
+        .unwrap();
+
+    let alice_address = TariAddress::new_dual_address_with_default_features(
+        alice_key_manager_handle.get_view_key().await.unwrap().pub_key,
+        alice_node_identity.public_key().clone(),
+        network,
+    );
+    let recipients = vec![BatchRecipient {
+        destination: alice_address,
+        amount: 20000.into(),
+        payment_id: PaymentId::open_from_str("from cold storage"),
+    }];
+    let unsigned = watcher_ts
+        .prepare_offline_transaction(
+            recipients,
             UtxoSelectionCriteria::default(),
-            OutputFeatures::default(),
             20.into(),
             PaymentId::Empty,
         )
         .await
-        .expect("Alice sending one-sided tx to Bob");
+        .expect("Watching wallet preparing offline tx");
+    assert_eq!(unsigned.inputs.len(), 1);
+    assert_eq!(unsigned.total_input_value().unwrap(), 50000.into());
+    let change = unsigned.change;
 
-    let completed_tx = alice_ts
-        .get_completed_transaction(tx_id)
+    // The watching wallet cannot sign for the spend key it was given
+    assert!(watcher_ts.sign_offline_transaction(unsigned.clone()).await.is_err());
+
+    let unsigned = serde_json::from_str(&serde_json::to_string(&unsigned).unwrap()).unwrap();
+    let signed = signer_ts
+        .sign_offline_transaction(unsigned)
         .await
-        .expect("Could not find completed one-sided tx");
-    let outputs = completed_tx.transaction.body.outputs().clone();
+        .expect("Seed wallet signing offline tx");
+    let tx_id = signed.tx_id;
+    let outputs = signed.transaction.body.outputs().clone();
+    assert_eq!(outputs.len(), 2);
 
-    let recovered_outputs_1 = bob_oms
-        .scan_outputs_for_one_sided_payments(outputs.iter().map(|o| (o.clone(), None)).collect())
+    let signed = serde_json::from_str(&serde_json::to_string(&signed).unwrap()).unwrap();
+    assert_eq!(
+        watcher_ts
+            .broadcast_signed_transaction(signed)
+            .await
+            .expect("Watching wallet broadcasting signed tx"),
+        tx_id
+    );
+    let completed_tx = watcher_ts
+        .get_completed_transaction(tx_id)
         .await
-        .unwrap();
-    // Bob should be able to claim 1 output.
-    assert_eq!(1, recovered_outputs_1.len());
-    assert_eq!(value, recovered_outputs_1[0].output.value);
+        .expect("Could not find completed offline tx");
+    assert_eq!(completed_tx.amount, MicroMinotari::from(20000));
+    assert_eq!(
+        watcher_oms.get_balance().await.unwrap().pending_incoming_balance,
+        change
+    );
 
-    // Should ignore already existing outputs
-    let recovered_outputs_2 = bob_oms
+    let alice_recovered = alice_oms
         .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
         .await
         .unwrap();
-    assert!(recovered_outputs_2.is_empty());
+    assert_eq!(alice_recovered.len(), 1);
+    assert_eq!(alice_recovered[0].output.value, 20000.into());
+    assert_eq!(
+        alice_recovered[0].output.payment_id.user_data_as_string(),
+        "from cold storage"
+    );
 }
 
 #[tokio::test]
-async fn recover_stealth_one_sided_transaction() {
+async fn cancelled_offline_transaction_releases_its_inputs() {
     let network = Network::LocalNet;
     let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let factories = CryptoFactories::default();
-    // Alice's parameters
     let alice_node_identity = Arc::new(NodeIdentity::random(
         &mut OsRng,
         get_next_memory_address(),
         PeerFeatures::COMMUNICATION_NODE,
     ));
-
-    // Bob's parameters
     let bob_node_identity = Arc::new(NodeIdentity::random(
         &mut OsRng,
         get_next_memory_address(),
         PeerFeatures::COMMUNICATION_NODE,
     ));
 
-    let base_node_identity = Arc::new(NodeIdentity::random(
-        &mut OsRng,
-        get_next_memory_address(),
-        PeerFeatures::COMMUNICATION_NODE,
-    ));
-
-    log::info!(
-        "manage_single_transaction: Alice: '{}', Bob: '{}', Base: '{}'",
-        alice_node_identity.node_id().short_str(),
-        bob_node_identity.node_id().short_str(),
-        base_node_identity.node_id().short_str()
-    );
-
     let temp_dir = tempdir().unwrap();
-    let temp_dir2 = tempdir().unwrap();
-    let database_path = temp_dir.path().to_str().unwrap().to_string();
-    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
-
-    let alice_connection = make_wallet_database_memory_connection();
-    let bob_connection = make_wallet_database_memory_connection();
-
     let shutdown = Shutdown::new();
-    let (mut alice_ts, alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
+    let (mut alice_ts, mut alice_oms, _alice_comms, _alice_connectivity, alice_key_manager_handle, alice_db) =
         setup_transaction_service(
             alice_node_identity,
             vec![],
-            consensus_manager.clone(),
-            factories.clone(),
-            alice_connection,
-            database_path,
-            Duration::from_secs(0),
-            shutdown.to_signal(),
-        )
-        .await;
-
-    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity, bob_key_manager_handle, _bob_db) =
-        setup_transaction_service(
-            bob_node_identity.clone(),
-            vec![],
             consensus_manager,
-            factories.clone(),
-            bob_connection,
-            database_path2,
+            factories,
+            make_wallet_database_memory_connection(),
+            temp_dir.path().to_str().unwrap().to_string(),
             Duration::from_secs(0),
             shutdown.to_signal(),
         )
         .await;
 
-    let bob_view_key = bob_key_manager_handle.get_view_key().await.unwrap();
-
-    let initial_wallet_value = 25000.into();
+    let initial_wallet_value = 100000.into();
     let uo1 = make_input(
         &mut OsRng,
         initial_wallet_value,
@@ -1881,52 +2683,47 @@ async fn recover_stealth_one_sided_transaction() {
         &alice_key_manager_handle,
     )
     .await;
-    let mut alice_oms_clone = alice_oms;
-    alice_oms_clone.add_output(uo1.clone(), None).await.unwrap();
+    alice_oms.add_output(uo1.clone(), None).await.unwrap();
     alice_db
         .mark_outputs_as_unspent(vec![(uo1.hash(&alice_key_manager_handle).await.unwrap(), true)])
         .unwrap();
 
-    let value = 10000.into();
-    let mut alice_ts_clone = alice_ts.clone();
-
     let bob_address = TariAddress::new_dual_address_with_default_features(
-        bob_view_key.pub_key,
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
         bob_node_identity.public_key().clone(),
         network,
     );
-    let tx_id = alice_ts_clone
-        .send_one_sided_to_stealth_address_transaction(
-            bob_address,
-            value,
+    let unsigned = alice_ts
+        .prepare_offline_transaction(
+            vec![BatchRecipient {
+                destination: bob_address,
+                amount: 25000.into(),
+                payment_id: PaymentId::Empty,
+            }],
             UtxoSelectionCriteria::default(),
-            OutputFeatures::default(),
             20.into(),
             PaymentId::Empty,
         )
         .await
-        .expect("Alice sending one-sided tx to Bob");
-
-    let completed_tx = alice_ts
-        .get_completed_transaction(tx_id)
+        .expect("Alice preparing offline tx");
+    let signed = alice_ts
+        .sign_offline_transaction(unsigned.clone())
         .await
-        .expect("Could not find completed one-sided tx");
-    let outputs = completed_tx.transaction.body.outputs().clone();
+        .expect("Alice signing offline tx");
 
-    let recovered_outputs_1 = bob_oms
-        .scan_outputs_for_one_sided_payments(outputs.iter().map(|o| (o.clone(), None)).collect())
+    alice_ts
+        .cancel_transaction(unsigned.tx_id)
         .await
-        .unwrap();
-    // Bob should be able to claim 1 output.
-    assert_eq!(1, recovered_outputs_1.len());
-    assert_eq!(value, recovered_outputs_1[0].output.value);
+        .expect("Alice cancelling offline tx");
+    assert_eq!(
+        alice_oms.get_balance().await.unwrap().available_balance,
+        initial_wallet_value
+    );
+    assert!(alice_ts.get_pending_outbound_transactions().await.unwrap().is_empty());
 
-    // Should ignore already existing outputs
-    let recovered_outputs_2 = bob_oms
-        .scan_outputs_for_one_sided_payments(outputs.into_iter().map(|o| (o, None)).collect())
-        .await
-        .unwrap();
-    assert!(recovered_outputs_2.is_empty());
+    // A transaction signed before it was cancelled can no longer be broadcast
+    assert!(alice_ts.broadcast_signed_transaction(signed).await.is_err());
+    assert!(alice_ts.get_completed_transaction(unsigned.tx_id).await.is_err());
 }
 
 #[tokio::test]
@@ -6202,3 +6999,99 @@ async fn test_get_fee_per_gram_per_block_basic() {
     assert_eq!(estimates.stats, stats.into_iter().map(Into::into).collect::<Vec<_>>());
     assert_eq!(estimates.stats.len(), 1)
 }
+
+#[tokio::test]
+async fn send_transaction_with_confirmation_target() {
+    let factories = CryptoFactories::default();
+    let connection = make_wallet_database_memory_connection();
+    let mut alice_ts_interface = setup_transaction_service_no_comms(factories, connection, None).await;
+
+    for _ in 0..2 {
+        let uo = make_input(
+            &mut OsRng,
+            1_000_000 * uT,
+            &OutputFeatures::default(),
+            &alice_ts_interface.key_manager_handle,
+        )
+        .await;
+        alice_ts_interface
+            .output_manager_service_handle
+            .add_output(uo.clone(), None)
+            .await
+            .unwrap();
+        alice_ts_interface
+            .oms_db
+            .mark_outputs_as_unspent(vec![(
+                uo.hash(&alice_ts_interface.key_manager_handle).await.unwrap(),
+                true,
+            )])
+            .unwrap();
+    }
+    alice_ts_interface
+        .base_node_rpc_mock_state
+        .set_estimate_fee_response(base_node_proto::EstimateFeeResponse {
+            fee_per_gram: 25,
+            confirmation_target: 3,
+            is_historical: true,
+        });
+
+    let bob_node_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE);
+    let bob_address = TariAddress::new_single_address_with_interactive_only(
+        bob_node_identity.public_key().clone(),
+        Network::LocalNet,
+    );
+    let estimated_tx_id = alice_ts_interface
+        .transaction_service_handle
+        .send_transaction_with_fee_rate(
+            bob_address.clone(),
+            100_000 * uT,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            FeeRate::ConfirmationTarget(3),
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap();
+    let calls = alice_ts_interface.base_node_rpc_mock_state.take_estimate_fee_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].confirmation_target, 3);
+
+    // The same transaction sent at the estimated fee per gram pays the same fee
+    let explicit_tx_id = alice_ts_interface
+        .transaction_service_handle
+        .send_transaction(
+            bob_address,
+            100_000 * uT,
+            UtxoSelectionCriteria::default(),
+            OutputFeatures::default(),
+            25 * uT,
+            PaymentId::Empty,
+        )
+        .await
+        .unwrap();
+    assert!(alice_ts_interface
+        .base_node_rpc_mock_state
+        .take_estimate_fee_calls()
+        .is_empty());
+
+    let mut pending = HashMap::new();
+    for _ in 0..20 {
+        pending = alice_ts_interface
+            .transaction_service_handle
+            .get_pending_outbound_transactions()
+            .await
+            .unwrap();
+        if pending.contains_key(&estimated_tx_id) && pending.contains_key(&explicit_tx_id) {
+            break;
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+    let estimated_tx = pending
+        .get(&estimated_tx_id)
+        .expect("estimated fee transaction is pending");
+    let explicit_tx = pending
+        .get(&explicit_tx_id)
+        .expect("explicit fee transaction is pending");
+    assert_eq!(estimated_tx.fee, explicit_tx.fee);
+}
@@ -43,7 +43,7 @@ use minotari_wallet::{
         service::TransactionServiceResources,
         storage::{
             database::TransactionDatabase,
-            models::CompletedTransaction,
+            models::{CompletedTransaction, TxCancellationReason},
             sqlite_db::TransactionServiceSqliteDatabase,
         },
     },
@@ -241,8 +241,8 @@ pub async fn add_transaction_to_database(
     db.insert_completed_transaction(tx_id, completed_tx1).unwrap();
 }
 
-/// Simple task that responds with a OutputManagerResponse::TransactionCancelled response to any request made on this
-/// channel
+/// Simple task that responds with a OutputManagerResponse::TransactionCancelled or
+/// OutputManagerResponse::ReplacedTransactionResolved response to any request made on this channel
 pub async fn oms_reply_channel_task(
     mut receiver: Receiver<OutputManagerRequest, Result<OutputManagerResponse, OutputManagerError>>,
 ) {
@@ -250,6 +250,9 @@ pub async fn oms_reply_channel_task(
         let (request, reply_tx) = request_context.split();
         let response = match request {
             OutputManagerRequest::CancelTransaction(_) => Ok(OutputManagerResponse::TransactionCancelled),
+            OutputManagerRequest::ResolveReplacedTransaction { .. } => {
+                Ok(OutputManagerResponse::ReplacedTransactionResolved)
+            },
             _ => Err(OutputManagerError::InvalidResponseError(
                 "Unhandled request type".to_string(),
             )),
@@ -835,6 +838,7 @@ async fn tx_validation_protocol_tx_becomes_mined_unconfirmed_then_confirmed() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
@@ -862,6 +866,7 @@ async fn tx_validation_protocol_tx_becomes_mined_unconfirmed_then_confirmed() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
@@ -907,6 +912,7 @@ async fn tx_validation_protocol_tx_becomes_mined_unconfirmed_then_confirmed() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
@@ -922,6 +928,106 @@ async fn tx_validation_protocol_tx_becomes_mined_unconfirmed_then_confirmed() {
     assert_eq!(completed_txs.get(&2u64.into()).unwrap().confirmations.unwrap(), 4);
 }
 
+/// Test that a fee bumped transaction that is mined after all is restored and its replacement is cancelled
+#[tokio::test]
+async fn tx_validation_protocol_replaced_tx_becomes_confirmed() {
+    let (
+        resources,
+        _outbound_mock_state,
+        mock_rpc_server,
+        server_node_identity,
+        rpc_service_state,
+        _shutdown,
+        _temp_dir,
+        _transaction_event_receiver,
+        wallet_connectivity,
+    ) = setup().await;
+    let mut connection = mock_rpc_server
+        .create_connection(server_node_identity.to_peer(), "t/bnwallet/1".into())
+        .await;
+    wallet_connectivity.set_base_node_wallet_rpc_client(connect_rpc_client(&mut connection).await);
+
+    // The original and its replacement spend the same input
+    let key_manager_handle = create_memory_db_key_manager().unwrap();
+    let uo0 = make_input(&mut OsRng, 10 * T, &OutputFeatures::default(), &key_manager_handle).await;
+    for (tx_id, fee_per_gram) in [(1u64, 5), (2u64, 10)] {
+        let (txs, _uou) = schema_to_transaction(
+            &[txn_schema!(from: vec![uo0.clone()], to: vec![T], fee: fee_per_gram.into())],
+            &key_manager_handle,
+        )
+        .await;
+        let completed_tx = CompletedTransaction::new(
+            tx_id.into(),
+            TariAddress::default(),
+            TariAddress::default(),
+            T,
+            txs[0].body.get_total_fee().unwrap(),
+            (*txs[0]).clone(),
+            TransactionStatus::Broadcast,
+            Utc::now(),
+            TransactionDirection::Outbound,
+            None,
+            None,
+            PaymentId::open_from_str("Test"),
+        )
+        .unwrap();
+        resources
+            .db
+            .insert_completed_transaction(tx_id.into(), completed_tx)
+            .unwrap();
+    }
+    resources
+        .db
+        .reject_completed_transaction(1u64.into(), TxCancellationReason::Replaced)
+        .unwrap();
+
+    // The original is still queried, and the base node reports it as confirmed
+    let original_tx = resources
+        .db
+        .get_completed_transaction_cancelled_or_not(1u64.into())
+        .unwrap();
+    let timestamp = EpochTime::now().as_u64();
+    rpc_service_state.set_transaction_query_batch_responses(TxQueryBatchResponsesProto {
+        responses: vec![TxQueryBatchResponseProto {
+            signature: Some(SignatureProto::from(
+                original_tx.transaction.first_kernel_excess_sig().unwrap().clone(),
+            )),
+            location: TxLocationProto::from(TxLocation::Mined) as i32,
+            best_block_hash: [5u8; 32].to_vec(),
+            confirmations: 4,
+            best_block_height: 5,
+            mined_timestamp: timestamp,
+        }],
+        is_synced: true,
+        best_block_hash: [5u8; 32].to_vec(),
+        best_block_height: 5,
+        tip_mined_timestamp: timestamp,
+    });
+
+    let protocol = TransactionValidationProtocol::new(
+        1.into(),
+        resources.db.clone(),
+        wallet_connectivity.clone(),
+        resources.config.clone(),
+        resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
+    );
+    let result = task::spawn(protocol.execute()).await.unwrap();
+    assert!(result.is_ok());
+
+    let completed_txs = resources.db.get_completed_transactions().unwrap();
+    let original_tx = completed_txs.get(&1u64.into()).unwrap();
+    assert_eq!(original_tx.status, TransactionStatus::MinedConfirmed);
+    assert_eq!(original_tx.cancelled, None);
+    assert!(!completed_txs.contains_key(&2u64.into()));
+
+    let cancelled_txs = resources.db.get_cancelled_completed_transactions().unwrap();
+    assert_eq!(
+        cancelled_txs.get(&2u64.into()).unwrap().cancelled,
+        Some(TxCancellationReason::DoubleSpend)
+    );
+}
+
 /// Test that revalidation clears the correct db fields and calls for validation of is said transactions
 #[tokio::test]
 #[allow(clippy::identity_op)]
@@ -988,6 +1094,7 @@ async fn tx_revalidation() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
@@ -1041,6 +1148,7 @@ async fn tx_revalidation() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
@@ -1210,6 +1318,7 @@ async fn tx_validation_protocol_reorg() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
@@ -1320,6 +1429,7 @@ async fn tx_validation_protocol_reorg() {
         wallet_connectivity.clone(),
         resources.config.clone(),
         resources.event_publisher.clone(),
+        resources.output_manager_service.clone(),
     );
 
     let join_handle = task::spawn(protocol.execute());
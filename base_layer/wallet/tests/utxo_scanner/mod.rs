@@ -27,11 +27,16 @@ use std::{
     time::Duration,
 };
 
+use blake2::Blake2b;
 use chrono::{Duration as ChronoDuration, Utc};
+use digest::consts::U32;
 use minotari_wallet::{
     base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
     connectivity_service::{create_wallet_connectivity_mock, WalletConnectivityMock},
-    output_manager_service::storage::{models::DbWalletOutput, OutputSource},
+    output_manager_service::storage::{
+        models::{DbWalletOutput, KnownOneSidedPaymentScript},
+        OutputSource,
+    },
     storage::{
         database::WalletDatabase,
         sqlite_db::wallet::WalletSqliteDatabase,
@@ -47,7 +52,7 @@ use minotari_wallet::{
 };
 use rand::{rngs::OsRng, RngCore};
 use tari_common::configuration::Network;
-use tari_common_types::tari_address::TariAddress;
+use tari_common_types::{tari_address::TariAddress, types::FixedHash};
 use tari_comms::{
     peer_manager::PeerFeatures,
     protocol::rpc::{mock::MockRpcServer, NamedProtocolService},
@@ -68,6 +73,7 @@ use tari_core::{
     },
 };
 use tari_key_manager::{cipher_seed::CipherSeed, get_birthday_from_unix_epoch_in_seconds};
+use tari_script::{script, ExecutionStack};
 use tari_service_framework::reply_channel;
 use tari_shutdown::Shutdown;
 use tari_test_utils::random;
@@ -94,6 +100,7 @@ pub struct UtxoScannerTestInterface {
     base_node_service_event_publisher: broadcast::Sender<Arc<BaseNodeEvent>>,
     rpc_service_state: BaseNodeWalletRpcMockState,
     _rpc_mock_server: MockRpcServer<BaseNodeWalletRpcServer<BaseNodeWalletRpcMockService>>,
+    _verification_rpc_mock_server: Option<MockRpcServer<BaseNodeWalletRpcServer<BaseNodeWalletRpcMockService>>>,
     _comms_connectivity_mock_state: ConnectivityManagerMockState,
     transaction_service_mock_state: TransactionServiceMockState,
     oms_mock_state: OutputManagerMockState,
@@ -107,6 +114,25 @@ async fn setup(
     previous_db: Option<WalletDatabase<WalletSqliteDatabase>>,
     recovery_message: Option<String>,
     one_sided_message: Option<String>,
+) -> UtxoScannerTestInterface {
+    setup_with_block_filters(
+        key_manager,
+        mode,
+        previous_db,
+        recovery_message,
+        one_sided_message,
+        false,
+    )
+    .await
+}
+
+async fn setup_with_block_filters(
+    key_manager: MemoryDbKeyManager,
+    mode: UtxoScannerMode,
+    previous_db: Option<WalletDatabase<WalletSqliteDatabase>>,
+    recovery_message: Option<String>,
+    one_sided_message: Option<String>,
+    use_block_filters: bool,
 ) -> UtxoScannerTestInterface {
     let shutdown = Shutdown::new();
     let factories = CryptoFactories::default();
@@ -122,7 +148,7 @@ async fn setup(
     // BaseNodeRpcService Mock
     let service = BaseNodeWalletRpcMockService::new();
     let rpc_service_state = service.get_state();
-    let server = BaseNodeWalletRpcServer::new(service);
+    let server = BaseNodeWalletRpcServer::new(service.clone());
     let protocol_name = server.as_protocol_name();
     let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
     let mut mock_server = MockRpcServer::new(server, server_node_identity.clone());
@@ -137,6 +163,23 @@ async fn setup(
     comms_connectivity_mock_state
         .add_active_connection(rpc_server_connection)
         .await;
+    let mut peers = vec![server_node_identity.public_key().clone()];
+
+    // A second base node sharing the same state, used to cross-check the block filters of the first
+    let verification_mock_server = if use_block_filters {
+        let server = BaseNodeWalletRpcServer::new(service);
+        let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        let mut mock_server = MockRpcServer::new(server, server_node_identity.clone());
+        mock_server.serve();
+        let connection = mock_server
+            .create_connection(server_node_identity.to_peer(), protocol_name.into())
+            .await;
+        comms_connectivity_mock_state.add_active_connection(connection).await;
+        peers.push(server_node_identity.public_key().clone());
+        Some(mock_server)
+    } else {
+        None
+    };
     task::spawn(connectivity_mock.run());
 
     let wallet_connectivity_mock = create_wallet_connectivity_mock();
@@ -181,9 +224,10 @@ async fn setup(
     let mut scanner_service_builder = UtxoScannerService::<WalletSqliteDatabase, WalletConnectivityMock>::builder();
 
     scanner_service_builder
-        .with_peers(vec![server_node_identity.public_key().clone()])
+        .with_peers(peers)
         .with_retry_limit(1)
-        .with_mode(mode);
+        .with_mode(mode)
+        .with_block_filters(use_block_filters);
 
     if let Some(message) = one_sided_message {
         scanner_service_builder.with_one_sided_message(message);
@@ -223,6 +267,7 @@ async fn setup(
         base_node_service_event_publisher: event_publisher_bns,
         rpc_service_state,
         _rpc_mock_server: mock_server,
+        _verification_rpc_mock_server: verification_mock_server,
         _comms_connectivity_mock_state: comms_connectivity_mock_state,
         transaction_service_mock_state,
         oms_mock_state,
@@ -386,6 +431,128 @@ async fn test_utxo_scanner_recovery() {
         }
     }
 }
+#[tokio::test]
+async fn test_utxo_scanner_skips_blocks_without_wallet_outputs() {
+    let key_manager = create_memory_db_key_manager().unwrap();
+    let mut test_interface =
+        setup_with_block_filters(key_manager.clone(), UtxoScannerMode::Recovery, None, None, None, true).await;
+
+    let cipher_seed = CipherSeed::new();
+    let birthday_epoch_time = get_birthday_from_unix_epoch_in_seconds(cipher_seed.birthday(), 14u16);
+    test_interface.wallet_db.set_master_seed(cipher_seed).unwrap();
+
+    const NUM_BLOCKS: u64 = 11;
+    const BIRTHDAY_OFFSET: u64 = 5;
+
+    let TestBlockData {
+        block_headers,
+        mut wallet_outputs,
+        mut utxos_by_block,
+    } = generate_block_headers_and_utxos(0, NUM_BLOCKS, birthday_epoch_time, BIRTHDAY_OFFSET, false, &key_manager)
+        .await;
+
+    // Only the outputs in the even blocks pay to the wallet's one-sided script, so the odd blocks can be skipped
+    let spend_key = key_manager.get_spend_key().await.unwrap();
+    let script = script!(PushPubKey(Box::new(spend_key.pub_key))).unwrap();
+    test_interface
+        .oms_mock_state
+        .set_known_scripts(vec![KnownOneSidedPaymentScript {
+            script_hash: script.as_hash::<Blake2b<U32>>().unwrap().to_vec(),
+            script_key_id: spend_key.key_id,
+            script: script.clone(),
+            input: ExecutionStack::default(),
+            script_lock_height: 0,
+        }]);
+    for block in utxos_by_block.iter_mut().filter(|b| b.height % 2 == 0) {
+        let outputs = wallet_outputs.get_mut(&block.height).unwrap();
+        block.utxos.clear();
+        for output in outputs {
+            output.script = script.clone();
+            block
+                .utxos
+                .push(output.to_transaction_output(&key_manager).await.unwrap());
+        }
+    }
+
+    test_interface.rpc_service_state.set_utxos_by_block(utxos_by_block);
+    test_interface.rpc_service_state.set_blocks(block_headers.clone());
+    test_interface.rpc_service_state.set_serve_block_filters(true);
+
+    let chain_metadata = ChainMetadata {
+        best_block_height: NUM_BLOCKS - 1,
+        best_block_hash: block_headers.get(&(NUM_BLOCKS - 1)).unwrap().clone().hash().to_vec(),
+        accumulated_difficulty: Vec::new(),
+        pruned_height: 0,
+        timestamp: 0,
+    };
+    test_interface.rpc_service_state.set_tip_info_response(TipInfoResponse {
+        metadata: Some(chain_metadata),
+        is_synced: true,
+    });
+
+    let mut db_wallet_outputs = Vec::new();
+    let mut total_outputs_to_recover = 0;
+    for (h, outputs) in wallet_outputs.iter().filter(|(h, _)| *h % 2 == 0) {
+        for output in outputs {
+            let dbo = DbWalletOutput::from_wallet_output(
+                output.clone(),
+                &key_manager,
+                None,
+                OutputSource::Standard,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            if *h >= NUM_BLOCKS.saturating_sub(BIRTHDAY_OFFSET).saturating_sub(2) {
+                total_outputs_to_recover += 1;
+            }
+            db_wallet_outputs.push(dbo);
+        }
+    }
+    test_interface.oms_mock_state.set_recoverable_outputs(db_wallet_outputs);
+
+    let mut scanner_event_stream = test_interface.scanner_handle.get_event_receiver();
+
+    tokio::spawn(test_interface.scanner_service.take().unwrap().run());
+
+    let delay = time::sleep(Duration::from_secs(60));
+    tokio::pin!(delay);
+    loop {
+        tokio::select! {
+            _ = &mut delay => {
+                panic!("Completed event should have arrived by now.");
+            }
+            event = scanner_event_stream.recv() => {
+                if let UtxoScannerEvent::Completed {
+                    final_height,
+                    num_recovered,
+                    ..
+                } = event.unwrap() {
+                    assert_eq!(final_height, NUM_BLOCKS - 1);
+                    assert_eq!(num_recovered, total_outputs_to_recover);
+                    break;
+                }
+            }
+        }
+    }
+
+    assert!(!test_interface.rpc_service_state.take_block_filters_calls().is_empty());
+    let height_of = |hash: &FixedHash| {
+        block_headers
+            .values()
+            .find(|h| h.hash() == *hash)
+            .map(|h| h.height)
+            .unwrap()
+    };
+    let sync_calls = test_interface.rpc_service_state.take_sync_utxos_by_block_calls();
+    assert!(!sync_calls.is_empty());
+    for (start, end) in sync_calls {
+        assert_eq!(start, end);
+        assert_eq!(height_of(&start) % 2, 0);
+    }
+}
+
 #[tokio::test]
 #[allow(clippy::too_many_lines)]
 async fn test_utxo_scanner_recovery_with_restart() {
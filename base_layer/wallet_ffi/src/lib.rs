@@ -100,6 +100,7 @@ use minotari_wallet::{
     transaction_service::{
         config::TransactionServiceConfig,
         error::TransactionServiceError,
+        handle::BatchRecipient,
         storage::{
             database::TransactionDatabase,
             models::{CompletedTransaction, InboundTransaction, OutboundTransaction},
@@ -161,6 +162,7 @@ use tari_crypto::{
     tari_utilities::{ByteArray, Hidden},
 };
 use tari_key_manager::{
+    bip39::{generate_bip39_mnemonic, is_bip39_mnemonic, Bip39WordCount},
     cipher_seed::CipherSeed,
     mnemonic::{Mnemonic, MnemonicLanguage},
     SeedWords,
@@ -229,6 +231,8 @@ pub struct TariUnblindedOutputs(Vec<UnblindedOutput>);
 
 pub struct TariContacts(Vec<TariContact>);
 
+pub struct TariBatchRecipients(Vec<BatchRecipient>);
+
 pub type TariContact = Contact;
 pub type TariCompletedTransaction = CompletedTransaction;
 pub type TariTransactionSendStatus = minotari_wallet::transaction_service::handle::TransactionSendStatus;
@@ -2869,6 +2873,60 @@ pub unsafe extern "C" fn seed_words_create_from_cipher(
     Box::into_raw(Box::new(TariSeedWords(seed_words)))
 }
 
+/// Create a TariSeedWords instance containing a new random BIP-39 phrase. A wallet can be created from the phrase,
+/// but `seed_words_create` with the wallet's own seed words remains the default.
+///
+/// ## Arguments
+/// `word_count` - The number of words in the phrase, either 12 or 24
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `TariSeedWords` - Returns the TariSeedWords instance, or null if the word count is not supported
+///
+/// # Safety
+/// The `seed_words_destroy` method must be called when finished with a TariSeedWords instance from rust to prevent a
+/// memory leak
+#[no_mangle]
+pub unsafe extern "C" fn seed_words_create_bip39(word_count: c_uint, error_out: *mut c_int) -> *mut TariSeedWords {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+
+    match Bip39WordCount::try_from(word_count as usize) {
+        Ok(word_count) => Box::into_raw(Box::new(TariSeedWords(generate_bip39_mnemonic(word_count)))),
+        Err(e) => {
+            error = LibWalletError::from(InterfaceError::InvalidArgument(e.to_string())).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Checks whether a TariSeedWords instance holds a valid BIP-39 phrase, including its checksum
+///
+/// ## Arguments
+/// `seed_words` - The pointer to a TariSeedWords
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `bool` - Returns true if the words form a valid 12 or 24 word BIP-39 phrase, false otherwise
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn seed_words_is_bip39(seed_words: *const TariSeedWords, error_out: *mut c_int) -> bool {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if seed_words.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("seed words".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+
+    is_bip39_mnemonic(&(*seed_words).0)
+}
+
 /// Create a TariSeedWords instance containing the entire mnemonic wordlist for the requested language
 ///
 /// ## Arguments
@@ -3431,6 +3489,131 @@ pub unsafe extern "C" fn contacts_destroy(contacts: *mut TariContacts) {
 
 /// -------------------------------------------------------------------------------------------- ///
 
+/// ----------------------------------- Batch Recipients -----------------------------------------///
+
+/// Creates an empty TariBatchRecipients
+///
+/// ## Arguments
+/// None
+///
+/// ## Returns
+/// `*mut TariBatchRecipients` - Returns a pointer to an empty TariBatchRecipients
+///
+/// # Safety
+/// The ```batch_recipients_destroy``` method must be called when finished with a TariBatchRecipients to prevent a
+/// memory leak
+#[no_mangle]
+pub unsafe extern "C" fn batch_recipients_create() -> *mut TariBatchRecipients {
+    Box::into_raw(Box::new(TariBatchRecipients(Vec::new())))
+}
+
+/// Appends a recipient to a TariBatchRecipients
+///
+/// ## Arguments
+/// `recipients` - The pointer to a TariBatchRecipients
+/// `destination` - The TariWalletAddress pointer of the recipient
+/// `amount` - The amount to send to the recipient
+/// `payment_id_string` - The pointer to a char array containing the memo for this recipient, may be null
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `bool` - Returns true if the recipient was added, false otherwise
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn batch_recipients_add(
+    recipients: *mut TariBatchRecipients,
+    destination: *mut TariWalletAddress,
+    amount: c_ulonglong,
+    payment_id_string: *const c_char,
+    error_out: *mut c_int,
+) -> bool {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if recipients.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("recipients".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    if destination.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("destination".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+
+    let payment_id = if payment_id_string.is_null() {
+        PaymentId::Empty
+    } else {
+        match CStr::from_ptr(payment_id_string).to_str() {
+            Ok(v) => PaymentId::open_from_str(v),
+            _ => {
+                error = LibWalletError::from(InterfaceError::NullError("payment_id".to_string())).code;
+                ptr::swap(error_out, &mut error as *mut c_int);
+                return false;
+            },
+        }
+    };
+
+    (*recipients).0.push(BatchRecipient {
+        destination: (*destination).clone(),
+        amount: MicroMinotari::from(amount),
+        payment_id,
+    });
+    true
+}
+
+/// Gets the length of TariBatchRecipients
+///
+/// ## Arguments
+/// `recipients` - The pointer to a TariBatchRecipients
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `c_uint` - Returns number of elements in recipients, zero if recipients is null
+///
+/// # Safety
+/// None
+// casting here is okay as we dont have more than u32 recipients
+#[allow(clippy::cast_possible_truncation)]
+#[no_mangle]
+pub unsafe extern "C" fn batch_recipients_get_length(
+    recipients: *mut TariBatchRecipients,
+    error_out: *mut c_int,
+) -> c_uint {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    let mut len = 0;
+    if recipients.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("recipients".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+    } else {
+        len = (*recipients).0.len();
+    }
+    len as c_uint
+}
+
+/// Frees memory for a TariBatchRecipients
+///
+/// ## Arguments
+/// `recipients` - The pointer to a TariBatchRecipients
+///
+/// ## Returns
+/// `()` - Does not return a value, equivalent to void in C
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn batch_recipients_destroy(recipients: *mut TariBatchRecipients) {
+    if !recipients.is_null() {
+        drop(Box::from_raw(recipients))
+    }
+}
+
+/// -------------------------------------------------------------------------------------------- ///
+
 /// ----------------------------------- Contacts Liveness Data ----------------------------------///
 
 /// Gets the public_key from a TariContactsLivenessData
@@ -5710,6 +5893,8 @@ unsafe fn init_logging(
 /// `seed_passphrase` - an optional string, if present this will derypt the seed words
 /// `seed_words` - An optional instance of TariSeedWords, used to create a wallet for recovery purposes.
 /// If this is null, then a new master key is created for the wallet.
+/// `seed_words_bip39` - Restore from `seed_words` as a 12 or 24 word BIP-39 phrase rather than cipher seed words. A
+/// `seed_passphrase` cannot be used with a BIP-39 phrase.
 /// `dns_seed_name_servers_str` - An optional list of DNS servers to query to get hold of the seed peer list.
 /// `use_dns_sec` - Use DNSSEC when querying the DNS servers.
 /// `callback_received_transaction` - The callback function pointer matching the function signature. This will be
@@ -5749,6 +5934,8 @@ unsafe fn init_logging(
 ///     Orphan,                 // 4
 ///     TimeLocked,             // 5
 ///     InvalidTransaction,     // 6
+///     Oversized,              // 7
+///     Replaced,               // 8
 /// }
 /// `callback_txo_validation_complete` - The callback function pointer matching the function signature. This is called
 /// when a TXO validation process is completed. The request_key is used to identify which request this
@@ -5802,6 +5989,7 @@ pub unsafe extern "C" fn wallet_create(
     passphrase: *const c_char,
     seed_passphrase: *const c_char,
     seed_words: *const TariSeedWords,
+    seed_words_bip39: bool,
     network_str: *const c_char,
     dns_seeds_str: *const c_char,
     dns_seed_name_servers_str: *const c_char,
@@ -5840,8 +6028,6 @@ pub unsafe extern "C" fn wallet_create(
     recovery_in_progress: *mut bool,
     error_out: *mut c_int,
 ) -> *mut TariWallet {
-    use tari_key_manager::mnemonic::Mnemonic;
-
     let mut error = 0;
     ptr::swap(error_out, &mut error as *mut c_int);
     if config.is_null() {
@@ -5922,7 +6108,7 @@ pub unsafe extern "C" fn wallet_create(
     let recovery_seed = if seed_words.is_null() {
         None
     } else {
-        match CipherSeed::from_mnemonic(&(*seed_words).0, seed_passphrase) {
+        match CipherSeed::from_mnemonic_words(&(*seed_words).0, seed_passphrase, seed_words_bip39) {
             Ok(seed) => Some(seed),
             Err(e) => {
                 error!(target: LOG_TARGET, "Mnemonic Error for given seed words: {:?}", e);
@@ -7373,6 +7559,71 @@ pub unsafe extern "C" fn wallet_send_transaction(
     }
 }
 
+/// Sends a single one-sided transaction paying every recipient in a TariBatchRecipients
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `recipients` - The TariBatchRecipients pointer, must contain at least one recipient
+/// `commitments` - A `TariVector` of "strings", tagged as `TariTypeTag::String`, containing commitment's hex values
+///   (see `Commitment::to_hex()`)
+/// `fee_per_gram` - The transaction fee
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `unsigned long long` - Returns 0 if unsuccessful or the TxId of the sent transaction if successful
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn wallet_send_batch_transaction(
+    wallet: *mut TariWallet,
+    recipients: *mut TariBatchRecipients,
+    commitments: *mut TariVector,
+    fee_per_gram: c_ulonglong,
+    error_out: *mut c_int,
+) -> c_ulonglong {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return 0;
+    }
+    if recipients.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("recipients".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return 0;
+    }
+
+    let selection_criteria = match commitments.as_ref() {
+        None => UtxoSelectionCriteria::default(),
+        Some(cs) => match cs.to_commitment_vec() {
+            Ok(cs) => UtxoSelectionCriteria::specific(cs),
+            Err(e) => {
+                error!(target: LOG_TARGET, "failed to convert from tari vector: {:?}", e);
+                ptr::replace(error_out, LibWalletError::from(e).code as c_int);
+                return 0;
+            },
+        },
+    };
+
+    match (*wallet)
+        .runtime
+        .block_on((*wallet).wallet.transaction_service.send_batch_one_sided_transaction(
+            (*recipients).0.clone(),
+            selection_criteria,
+            MicroMinotari::from(fee_per_gram),
+        )) {
+        Ok(tx_id) => tx_id.as_u64(),
+        Err(e) => {
+            error = LibWalletError::from(WalletError::TransactionServiceError(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            0
+        },
+    }
+}
+
 /// Sends a TariPendingOutboundTransaction
 ///
 /// ## Arguments
@@ -8528,6 +8779,44 @@ pub unsafe extern "C" fn wallet_get_seed_words(wallet: *mut TariWallet, error_ou
     }
 }
 
+/// Gets the wallet's master seed as a 12 word BIP-39 phrase, for use with generic custody tools. Unlike the words
+/// from `wallet_get_seed_words`, the phrase does not record the wallet birthday.
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `*mut TariSeedWords` - A collection of the seed words
+///
+/// # Safety
+/// The ```tari_seed_words_destroy``` method must be called when finished with a
+/// TariSeedWords to prevent a memory leak
+#[no_mangle]
+pub unsafe extern "C" fn wallet_get_bip39_seed_words(
+    wallet: *mut TariWallet,
+    error_out: *mut c_int,
+) -> *mut TariSeedWords {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return ptr::null_mut();
+    }
+
+    match (*wallet).wallet.get_bip39_seed_words() {
+        Ok(seed_words) => Box::into_raw(Box::new(TariSeedWords(seed_words))),
+        Err(e) => {
+            error = LibWalletError::from(e).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            ptr::null_mut()
+        },
+    }
+}
+
 /// Set the power mode of the wallet to Low Power mode which will reduce the amount of network operations the wallet
 /// performs to conserve power
 ///
@@ -9892,6 +10181,32 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_seed_words_create_bip39() {
+        unsafe {
+            let mut error = 0;
+            let error_ptr = &mut error as *mut c_int;
+
+            for word_count in [12, 24] {
+                let seed_words = seed_words_create_bip39(word_count, error_ptr);
+                assert_eq!(*error_ptr, 0, "No error expected");
+                assert_eq!(seed_words_get_length(seed_words, error_ptr), word_count);
+                assert!(seed_words_is_bip39(seed_words, error_ptr));
+                assert!(CipherSeed::from_bip39_mnemonic(&(*seed_words).0).is_ok());
+                seed_words_destroy(seed_words);
+            }
+
+            let seed_words = seed_words_create_bip39(13, error_ptr);
+            assert!(seed_words.is_null());
+            assert_ne!(*error_ptr, 0, "Error expected");
+
+            let seed_words = seed_words_create();
+            assert!(!seed_words_is_bip39(seed_words, error_ptr));
+            assert_eq!(*error_ptr, 0, "No error expected");
+            seed_words_destroy(seed_words);
+        }
+    }
+
     #[test]
     #[allow(clippy::cast_possible_truncation)]
     fn test_seed_words_create() {
@@ -10372,6 +10687,61 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_batch_recipients() {
+        unsafe {
+            let mut error = 0;
+            let error_ptr = &mut error as *mut c_int;
+            let private_key = private_key_generate();
+            let key = PublicKey::from_secret_key(&(*private_key));
+            let test_address = Box::into_raw(Box::new(TariWalletAddress::new_single_address_with_interactive_only(
+                key,
+                Network::default(),
+            )));
+            let memo_str = CString::new("payout 1").unwrap();
+            let memo: *const c_char = CString::into_raw(memo_str) as *const c_char;
+
+            let recipients = batch_recipients_create();
+            assert_eq!(batch_recipients_get_length(recipients, error_ptr), 0);
+            assert!(batch_recipients_add(recipients, test_address, 1000, memo, error_ptr));
+            assert_eq!(error, 0);
+            assert!(batch_recipients_add(
+                recipients,
+                test_address,
+                2000,
+                ptr::null(),
+                error_ptr
+            ));
+            assert_eq!(batch_recipients_get_length(recipients, error_ptr), 2);
+            assert_eq!((*recipients).0[0].amount, MicroMinotari::from(1000));
+            assert_eq!((*recipients).0[0].payment_id, PaymentId::open_from_str("payout 1"));
+            assert_eq!((*recipients).0[1].payment_id, PaymentId::Empty);
+
+            assert!(!batch_recipients_add(
+                recipients,
+                ptr::null_mut(),
+                3000,
+                memo,
+                error_ptr
+            ));
+            assert_eq!(
+                error,
+                LibWalletError::from(InterfaceError::NullError("destination".to_string())).code
+            );
+            assert_eq!(batch_recipients_get_length(recipients, error_ptr), 2);
+            assert_eq!(batch_recipients_get_length(ptr::null_mut(), error_ptr), 0);
+            assert_eq!(
+                error,
+                LibWalletError::from(InterfaceError::NullError("recipients".to_string())).code
+            );
+
+            batch_recipients_destroy(recipients);
+            tari_address_destroy(test_address);
+            private_key_destroy(private_key);
+            string_destroy(memo as *mut c_char);
+        }
+    }
+
     #[test]
     fn test_contact_dont_panic() {
         unsafe {
@@ -10485,6 +10855,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 alice_network_str,
                 dns_string,
                 ptr::null(),
@@ -10536,6 +10907,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 alice_network_str,
                 dns_string,
                 ptr::null(),
@@ -10657,6 +11029,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -10889,6 +11262,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -10962,6 +11336,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 seed_words,
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -11046,6 +11421,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -11228,6 +11604,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -11371,6 +11748,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -11595,6 +11973,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -11826,6 +12205,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -12091,6 +12471,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 network_str,
                 dns_string,
                 ptr::null(),
@@ -12476,6 +12857,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 alice_network_str,
                 dns_string,
                 ptr::null(),
@@ -12545,6 +12927,7 @@ mod test {
                 passphrase,
                 ptr::null(),
                 ptr::null(),
+                false,
                 bob_network_str,
                 dns_string,
                 ptr::null(),
@@ -162,6 +162,8 @@ struct TariAddress;
 
 struct TariBaseNodeState;
 
+struct TariBatchRecipients;
+
 struct TariCompletedTransactions;
 
 struct TariContacts;
@@ -1515,6 +1517,42 @@ struct TariSeedWords *seed_words_create_from_cipher(const char *cipher_bytes,
                                                     const char *passphrase,
                                                     int *error_out);
 
+/**
+ * Create a TariSeedWords instance containing a new random BIP-39 phrase. A wallet can be created from the phrase,
+ * but `seed_words_create` with the wallet's own seed words remains the default.
+ *
+ * ## Arguments
+ * `word_count` - The number of words in the phrase, either 12 or 24
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `TariSeedWords` - Returns the TariSeedWords instance, or null if the word count is not supported
+ *
+ * # Safety
+ * The `seed_words_destroy` method must be called when finished with a TariSeedWords instance from rust to prevent a
+ * memory leak
+ */
+struct TariSeedWords *seed_words_create_bip39(unsigned int word_count,
+                                              int *error_out);
+
+/**
+ * Checks whether a TariSeedWords instance holds a valid BIP-39 phrase, including its checksum
+ *
+ * ## Arguments
+ * `seed_words` - The pointer to a TariSeedWords
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `bool` - Returns true if the words form a valid 12 or 24 word BIP-39 phrase, false otherwise
+ *
+ * # Safety
+ * None
+ */
+bool seed_words_is_bip39(const struct TariSeedWords *seed_words,
+                         int *error_out);
+
 /**
  * Create a TariSeedWords instance containing the entire mnemonic wordlist for the requested language
  *
@@ -1756,6 +1794,77 @@ TariContact *contacts_get_at(struct TariContacts *contacts,
  */
 void contacts_destroy(struct TariContacts *contacts);
 
+/**
+ * -------------------------------------------------------------------------------------------- ///
+ * ----------------------------------- Batch Recipients -----------------------------------------///
+ * Creates an empty TariBatchRecipients
+ *
+ * ## Arguments
+ * None
+ *
+ * ## Returns
+ * `*mut TariBatchRecipients` - Returns a pointer to an empty TariBatchRecipients
+ *
+ * # Safety
+ * The ```batch_recipients_destroy``` method must be called when finished with a TariBatchRecipients to prevent a
+ * memory leak
+ */
+struct TariBatchRecipients *batch_recipients_create(void);
+
+/**
+ * Appends a recipient to a TariBatchRecipients
+ *
+ * ## Arguments
+ * `recipients` - The pointer to a TariBatchRecipients
+ * `destination` - The TariWalletAddress pointer of the recipient
+ * `amount` - The amount to send to the recipient
+ * `payment_id_string` - The pointer to a char array containing the memo for this recipient, may be null
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `bool` - Returns true if the recipient was added, false otherwise
+ *
+ * # Safety
+ * None
+ */
+bool batch_recipients_add(struct TariBatchRecipients *recipients,
+                          TariWalletAddress *destination,
+                          unsigned long long amount,
+                          const char *payment_id_string,
+                          int *error_out);
+
+/**
+ * Gets the length of TariBatchRecipients
+ *
+ * ## Arguments
+ * `recipients` - The pointer to a TariBatchRecipients
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `c_uint` - Returns number of elements in recipients, zero if recipients is null
+ *
+ * # Safety
+ * None
+ */
+unsigned int batch_recipients_get_length(struct TariBatchRecipients *recipients,
+                                         int *error_out);
+
+/**
+ * Frees memory for a TariBatchRecipients
+ *
+ * ## Arguments
+ * `recipients` - The pointer to a TariBatchRecipients
+ *
+ * ## Returns
+ * `()` - Does not return a value, equivalent to void in C
+ *
+ * # Safety
+ * None
+ */
+void batch_recipients_destroy(struct TariBatchRecipients *recipients);
+
 /**
  * -------------------------------------------------------------------------------------------- ///
  * ----------------------------------- Contacts Liveness Data ----------------------------------///
@@ -2871,6 +2980,8 @@ TariPublicKey *public_keys_get_at(const struct TariPublicKeys *public_keys,
  * `seed_passphrase` - an optional string, if present this will derypt the seed words
  * `seed_words` - An optional instance of TariSeedWords, used to create a wallet for recovery purposes.
  * If this is null, then a new master key is created for the wallet.
+ * `seed_words_bip39` - Restore from `seed_words` as a 12 or 24 word BIP-39 phrase rather than cipher seed words. A
+ * `seed_passphrase` cannot be used with a BIP-39 phrase.
  * `dns_seed_name_servers_str` - An optional list of DNS servers to query to get hold of the seed peer list.
  * `use_dns_sec` - Use DNSSEC when querying the DNS servers.
  * `callback_received_transaction` - The callback function pointer matching the function signature. This will be
@@ -2910,6 +3021,8 @@ TariPublicKey *public_keys_get_at(const struct TariPublicKeys *public_keys,
  *     Orphan,                 // 4
  *     TimeLocked,             // 5
  *     InvalidTransaction,     // 6
+ *     Oversized,              // 7
+ *     Replaced,               // 8
  * }
  * `callback_txo_validation_complete` - The callback function pointer matching the function signature. This is called
  * when a TXO validation process is completed. The request_key is used to identify which request this
@@ -2960,6 +3073,7 @@ struct TariWallet *wallet_create(void *context,
                                  const char *passphrase,
                                  const char *seed_passphrase,
                                  const struct TariSeedWords *seed_words,
+                                 bool seed_words_bip39,
                                  const char *network_str,
                                  const char *dns_seeds_str,
                                  const char *dns_seed_name_servers_str,
@@ -3440,6 +3554,30 @@ unsigned long long wallet_send_transaction(struct TariWallet *wallet,
                                            const char *payment_id_string,
                                            int *error_out);
 
+/**
+ * Sends a single one-sided transaction paying every recipient in a TariBatchRecipients
+ *
+ * ## Arguments
+ * `wallet` - The TariWallet pointer
+ * `recipients` - The TariBatchRecipients pointer, must contain at least one recipient
+ * `commitments` - A `TariVector` of "strings", tagged as `TariTypeTag::String`, containing commitment's hex values
+ *   (see `Commitment::to_hex()`)
+ * `fee_per_gram` - The transaction fee
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `unsigned long long` - Returns 0 if unsuccessful or the TxId of the sent transaction if successful
+ *
+ * # Safety
+ * None
+ */
+unsigned long long wallet_send_batch_transaction(struct TariWallet *wallet,
+                                                 struct TariBatchRecipients *recipients,
+                                                 struct TariVector *commitments,
+                                                 unsigned long long fee_per_gram,
+                                                 int *error_out);
+
 /**
  * Sends a TariPendingOutboundTransaction
  *
@@ -3837,6 +3975,25 @@ bool wallet_restart_transaction_broadcast(struct TariWallet *wallet,
 struct TariSeedWords *wallet_get_seed_words(struct TariWallet *wallet,
                                             int *error_out);
 
+/**
+ * Gets the wallet's master seed as a 12 word BIP-39 phrase, for use with generic custody tools. Unlike the words
+ * from `wallet_get_seed_words`, the phrase does not record the wallet birthday.
+ *
+ * ## Arguments
+ * `wallet` - The TariWallet pointer
+ * `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+ * as an out parameter.
+ *
+ * ## Returns
+ * `*mut TariSeedWords` - A collection of the seed words
+ *
+ * # Safety
+ * The ```tari_seed_words_destroy``` method must be called when finished with a
+ * TariSeedWords to prevent a memory leak
+ */
+struct TariSeedWords *wallet_get_bip39_seed_words(struct TariWallet *wallet,
+                                                  int *error_out);
+
 /**
  * Set the power mode of the wallet to Low Power mode which will reduce the amount of network operations the wallet
  * performs to conserve power
@@ -28,4 +28,13 @@ pub use lmdb_zero::{
     db,
     traits::{AsLmdbBytes, FromLmdbBytes},
 };
-pub use store::{DatabaseRef, LMDBBuilder, LMDBConfig, LMDBDatabase, LMDBStore, BYTES_PER_MB};
+pub use store::{
+    DatabaseRef,
+    LMDBBuilder,
+    LMDBConfig,
+    LMDBDatabase,
+    LMDBReadTransaction,
+    LMDBStore,
+    LMDBWriteTransaction,
+    BYTES_PER_MB,
+};
@@ -93,7 +93,7 @@ impl LMDBConfig {
 
 impl Default for LMDBConfig {
     fn default() -> Self {
-        // Do not choose these values too small, as the entire SMT is replaced for every new block
+        // Do not choose these values too small, as a new block can touch a large number of SMT nodes
         Self::new_from_mb(128, 128, 64)
     }
 }
@@ -770,6 +770,16 @@ impl<'txn, 'db: 'txn> LMDBWriteTransaction<'txn, 'db> {
         Ok(())
     }
 
+    /// Get and deserialise a value from the database, including any changes made earlier in this transaction.
+    pub fn get<K, V>(&self, key: &K) -> Result<Option<V>, LMDBError>
+    where
+        K: AsLmdbBytes + ?Sized,
+        for<'t> V: serde::de::DeserializeOwned, // read this as, for *any* lifetime, t, we can convert a [u8] to V
+    {
+        let val = self.access.get(self.db, key).to_opt();
+        LMDBReadTransaction::convert_value(val)
+    }
+
     /// Checks whether a key exists in this database
     pub fn exists<K>(&self, key: &K) -> Result<bool, LMDBError>
     where K: AsLmdbBytes + ?Sized {
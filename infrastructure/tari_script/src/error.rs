@@ -52,6 +52,8 @@ pub enum ScriptError {
     InvalidDigest,
     #[error("A compare opcode failed, aborting the script immediately with reason: `{0}`")]
     CompareFailed(String),
+    #[error("The script requires the mined height of the UTXO being spent, but it was not provided")]
+    MinedHeightUnavailable,
     #[error("Max sized vector error: {0}")]
     MaxSizeVecError(#[from] MaxSizeVecError),
 }
@@ -83,6 +83,8 @@ const OP_CHECK_HEIGHT_VERIFY: u8 = 0x66;
 const OP_CHECK_HEIGHT: u8 = 0x67;
 const OP_COMPARE_HEIGHT_VERIFY: u8 = 0x68;
 const OP_COMPARE_HEIGHT: u8 = 0x69;
+const OP_CHECK_RELATIVE_HEIGHT_VERIFY: u8 = 0x6a;
+const OP_CHECK_RELATIVE_HEIGHT: u8 = 0x6b;
 
 // Opcode constants: Stack Manipulation
 const OP_DROP: u8 = 0x70;
@@ -148,6 +150,16 @@ pub enum Opcode {
     /// current height. Fails with `InvalidInput` if there is not a valid integer value on top of the stack. Fails
     /// with `StackUnderflow` if the stack is empty.
     CompareHeight,
+    /// Compare the current block height to the height at which the UTXO being spent was mined plus `height`. Fails
+    /// with `MinedHeightUnavailable` if the mined height is not known. Fails with `ValueExceedsBounds` if the mined
+    /// height plus `height` overflows. Fails with `VerifyFailed` if the block height < the mined height + `height`.
+    CheckRelativeHeightVerify(u64),
+    /// Pushes the value of (the current block height - (the mined height of the UTXO being spent + `height`)) to the
+    /// stack. In other words, the top of the stack will be positive once `height` blocks have passed since the UTXO
+    /// was mined, and negative before that. Fails with `MinedHeightUnavailable` if the mined height is not known.
+    /// Fails with `ValueExceedsBounds` if the mined height plus `height` overflows. Fails with `StackOverflow` if the
+    /// stack would exceed the max stack height.
+    CheckRelativeHeight(u64),
 
     // Stack Manipulation
     /// No op. Does nothing. Never fails.
@@ -324,9 +336,19 @@ impl Opcode {
             Opcode::IfThen |
             Opcode::Else |
             Opcode::EndIf => OpcodeVersion::V0,
+            Opcode::CheckRelativeHeightVerify(..) | Opcode::CheckRelativeHeight(..) => OpcodeVersion::V1,
         }
     }
 
+    /// Returns true if executing this opcode requires the mined height of the UTXO being spent to be provided in the
+    /// script context.
+    pub fn requires_mined_height(&self) -> bool {
+        matches!(
+            self,
+            Opcode::CheckRelativeHeightVerify(..) | Opcode::CheckRelativeHeight(..)
+        )
+    }
+
     pub fn parse(bytes: &[u8]) -> Result<Vec<Opcode>, ScriptError> {
         let mut script = Vec::new();
         let mut bytes_copy = bytes;
@@ -357,6 +379,14 @@ impl Opcode {
             },
             OP_COMPARE_HEIGHT_VERIFY => Ok((CompareHeightVerify, &bytes[1..])),
             OP_COMPARE_HEIGHT => Ok((CompareHeight, &bytes[1..])),
+            OP_CHECK_RELATIVE_HEIGHT_VERIFY => {
+                let (height, size) = u64::decode_var(&bytes[1..]).ok_or(ScriptError::InvalidData)?;
+                Ok((CheckRelativeHeightVerify(height), &bytes[size + 1..]))
+            },
+            OP_CHECK_RELATIVE_HEIGHT => {
+                let (height, size) = u64::decode_var(&bytes[1..]).ok_or(ScriptError::InvalidData)?;
+                Ok((CheckRelativeHeight(height), &bytes[size + 1..]))
+            },
             OP_NOP => Ok((Nop, &bytes[1..])),
             OP_PUSH_ZERO => Ok((PushZero, &bytes[1..])),
             OP_PUSH_ONE => Ok((PushOne, &bytes[1..])),
@@ -480,6 +510,18 @@ impl Opcode {
             },
             CompareHeightVerify => array.push(OP_COMPARE_HEIGHT_VERIFY),
             CompareHeight => array.push(OP_COMPARE_HEIGHT),
+            CheckRelativeHeightVerify(height) => {
+                array.push(OP_CHECK_RELATIVE_HEIGHT_VERIFY);
+                let mut buf = [0u8; 10];
+                let used = height.encode_var(&mut buf[..]);
+                array.extend_from_slice(&buf[0..used]);
+            },
+            CheckRelativeHeight(height) => {
+                array.push(OP_CHECK_RELATIVE_HEIGHT);
+                let mut buf = [0u8; 10];
+                let used = height.encode_var(&mut buf[..]);
+                array.extend_from_slice(&buf[0..used]);
+            },
             Nop => array.push(OP_NOP),
             PushZero => array.push(OP_PUSH_ZERO),
             PushOne => array.push(OP_PUSH_ONE),
@@ -568,6 +610,8 @@ impl fmt::Display for Opcode {
             CheckHeight(height) => write!(fmt, "CheckHeight({})", *height),
             CompareHeightVerify => write!(fmt, "CompareHeightVerify"),
             CompareHeight => write!(fmt, "CompareHeight"),
+            CheckRelativeHeightVerify(height) => write!(fmt, "CheckRelativeHeightVerify({})", *height),
+            CheckRelativeHeight(height) => write!(fmt, "CheckRelativeHeight({})", *height),
             Nop => write!(fmt, "Nop"),
             PushZero => write!(fmt, "PushZero"),
             PushOne => write!(fmt, "PushOne"),
@@ -638,6 +682,7 @@ impl fmt::Display for Opcode {
 #[repr(u8)]
 pub enum OpcodeVersion {
     V0 = 0,
+    V1 = 1,
 }
 
 #[cfg(test)]
@@ -691,6 +736,12 @@ mod test {
         }
         test_check_height(&Opcode::CheckHeight(63), 0x67, "CheckHeight(63)");
         test_check_height(&Opcode::CheckHeightVerify(63), 0x66, "CheckHeightVerify(63)");
+        test_check_height(
+            &Opcode::CheckRelativeHeightVerify(63),
+            0x6a,
+            "CheckRelativeHeightVerify(63)",
+        );
+        test_check_height(&Opcode::CheckRelativeHeight(63), 0x6b, "CheckRelativeHeight(63)");
     }
 
     #[test]
@@ -258,6 +258,14 @@ impl TariScript {
             CheckHeight(height) => TariScript::handle_check_height(stack, *height, ctx.block_height()),
             CompareHeightVerify => TariScript::handle_compare_height_verify(stack, ctx.block_height()),
             CompareHeight => TariScript::handle_compare_height(stack, ctx.block_height()),
+            CheckRelativeHeightVerify(height) => {
+                let target_height = TariScript::relative_target_height(*height, ctx)?;
+                TariScript::handle_check_height_verify(target_height, ctx.block_height())
+            },
+            CheckRelativeHeight(height) => {
+                let target_height = TariScript::relative_target_height(*height, ctx)?;
+                TariScript::handle_check_height(stack, target_height, ctx.block_height())
+            },
             Nop => Ok(()),
             PushZero => stack.push(Number(0)),
             PushOne => stack.push(Number(1)),
@@ -362,6 +370,11 @@ impl TariScript {
         stack.push(item)
     }
 
+    fn relative_target_height(height: u64, ctx: &ScriptContext) -> Result<u64, ScriptError> {
+        let mined_height = ctx.mined_height().ok_or(ScriptError::MinedHeightUnavailable)?;
+        mined_height.checked_add(height).ok_or(ScriptError::ValueExceedsBounds)
+    }
+
     fn handle_compare_height_verify(stack: &mut ExecutionStack, block_height: u64) -> Result<(), ScriptError> {
         let target_height = stack.pop_into_number::<u64>()?;
 
@@ -940,6 +953,48 @@ mod test {
         }
     }
 
+    #[test]
+    fn op_check_relative_height() {
+        let inputs = ExecutionStack::default();
+        let script = script!(CheckRelativeHeight(5)).unwrap();
+
+        for block_height in 11..=20 {
+            let ctx = context_with_height(u64::try_from(block_height).unwrap()).with_mined_height(10);
+            assert_eq!(
+                script.execute_with_context(&inputs, &ctx).unwrap(),
+                Number(block_height - 15)
+            );
+        }
+
+        let ctx = context_with_height(20);
+        let err = script.execute_with_context(&inputs, &ctx).unwrap_err();
+        assert!(matches!(err, ScriptError::MinedHeightUnavailable));
+
+        let script = script!(CheckRelativeHeight(u64::MAX)).unwrap();
+        let ctx = context_with_height(20).with_mined_height(10);
+        let err = script.execute_with_context(&inputs, &ctx).unwrap_err();
+        assert!(matches!(err, ScriptError::ValueExceedsBounds));
+
+        let script = script!(CheckRelativeHeightVerify(5)).unwrap();
+        let inputs = inputs!(1);
+
+        for block_height in 10..15 {
+            let ctx = context_with_height(block_height).with_mined_height(10);
+            let err = script.execute_with_context(&inputs, &ctx).unwrap_err();
+            assert!(matches!(err, ScriptError::VerifyFailed));
+        }
+
+        for block_height in 15..=20 {
+            let ctx = context_with_height(block_height).with_mined_height(10);
+            let result = script.execute_with_context(&inputs, &ctx).unwrap();
+            assert_eq!(result, Number(1));
+        }
+
+        let ctx = context_with_height(20);
+        let err = script.execute_with_context(&inputs, &ctx).unwrap_err();
+        assert!(matches!(err, ScriptError::MinedHeightUnavailable));
+    }
+
     #[test]
     fn op_compare_height() {
         let script = script!(CompareHeight).unwrap();
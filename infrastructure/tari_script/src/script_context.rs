@@ -29,6 +29,8 @@ pub struct ScriptContext {
     prev_block_hash: HashValue,
     /// The commitment of the UTXO that is attached to this script
     commitment: PedersenCommitment,
+    /// The height at which the UTXO being spent was mined, if known
+    mined_height: Option<u64>,
 }
 
 impl ScriptContext {
@@ -37,9 +39,16 @@ impl ScriptContext {
             block_height: height,
             prev_block_hash: *prev_hash,
             commitment: com.clone(),
+            mined_height: None,
         }
     }
 
+    /// Sets the height at which the UTXO being spent was mined, which is required by relative height opcodes
+    pub fn with_mined_height(mut self, mined_height: u64) -> Self {
+        self.mined_height = Some(mined_height);
+        self
+    }
+
     pub fn block_height(&self) -> u64 {
         self.block_height
     }
@@ -51,4 +60,8 @@ impl ScriptContext {
     pub fn commitment(&self) -> &PedersenCommitment {
         &self.commitment
     }
+
+    pub fn mined_height(&self) -> Option<u64> {
+        self.mined_height
+    }
 }
@@ -388,6 +388,7 @@ extern "C" {
         passphrase: *const c_char,
         seed_passphrase: *const c_char,
         seed_words: *const TariSeedWords,
+        seed_words_bip39: bool,
         network_str: *const c_char,
         dns_seeds_str: *const c_char,
         dns_seed_name_servers_str: *const c_char,
@@ -210,6 +210,7 @@ impl Wallet {
                 CString::new("kensentme").unwrap().into_raw(),
                 ptr::null(),
                 seed_words_ptr,
+                false,
                 CString::new("localnet").unwrap().into_raw(),
                 CString::new("").unwrap().into_raw(),
                 ptr::null(),
@@ -223,6 +223,7 @@ pub fn get_default_cli() -> Cli {
         change_password: false,
         recovery: false,
         seed_words: None,
+        bip39: false,
         seed_words_file_name: None,
         non_interactive_mode: true,
         input_file: None,
@@ -235,6 +236,7 @@ pub fn get_default_cli() -> Cli {
         profile_with_tokio_console: false,
         view_private_key: None,
         spend_key: None,
+        ledger_account: None,
     }
 }
 
@@ -90,3 +90,16 @@ Feature: Wallet Transfer
     When I claim an HTLC refund transaction with wallet WALLET_A at fee 20
     When mining node MINER_2 mines 6 blocks
     Then I wait for wallet WALLET_A to have at least 9000000000 uT
+
+  Scenario: As a wallet on a ledger device I want to receive a transfer
+    Given I have a seed node NODE
+    # Add a 2nd node otherwise initial sync will not succeed
+    When I have 1 base nodes connected to all seed nodes
+    When I have wallet WALLET_A with 10T connected to base node NODE
+    When I have wallet LEDGER_WALLET on emulated ledger account 1 connected to base node NODE
+    When I wait 5 seconds
+    When I transfer 5T from WALLET_A to LEDGER_WALLET
+    When I mine 4 blocks on NODE
+    Then wallet WALLET_A has 5T
+    When I wait 5 seconds
+    When wallet LEDGER_WALLET has 5T
@@ -36,6 +36,7 @@ use minotari_console_wallet::{
     SetBaseNodeArgs,
     WhoisArgs,
 };
+use minotari_wallet::output_manager_service::UtxoSelectionOrdering;
 use tari_common_types::tari_address::TariAddress;
 use tari_comms::multiaddr::Multiaddr;
 use tari_core::transactions::tari_amount::MicroMinotari;
@@ -154,6 +155,7 @@ async fn send_from_cli(world: &mut TariWorld, amount: u64, wallet_a: String, wal
         amount: MicroMinotari(amount),
         destination: wallet_b_address,
         payment_id: format!("Send amount {} from {} to {}", amount, wallet_a, wallet_b),
+        selection: UtxoSelectionOrdering::Default,
     };
     cli.command2 = Some(CliCommands::SendMinotari(args));
 
@@ -41,6 +41,7 @@ use grpc::{
 };
 use minotari_app_grpc::tari_rpc::{self as grpc, TransactionStatus};
 use minotari_console_wallet::{CliCommands, ExportUtxosArgs};
+use minotari_ledger_wallet_comms::emulator::use_emulated_ledger_device;
 use minotari_wallet::transaction_service::config::TransactionRoutingMechanism;
 use tari_common_types::types::{ComAndPubSignature, PrivateKey, PublicKey, RangeProof};
 use tari_core::{
@@ -145,6 +146,27 @@ async fn wallet_connected_to_base_node(world: &mut TariWorld, wallet: String, ba
     spawn_wallet(world, wallet, Some(base_node), peer_seeds, None, Some(cli)).await;
 }
 
+#[when(expr = "I have wallet {word} on emulated ledger account {int} connected to base node {word}")]
+async fn emulated_ledger_wallet_connected_to_base_node(
+    world: &mut TariWorld,
+    wallet: String,
+    account: u64,
+    base_node: String,
+) {
+    // Wallets run in-process, so every ledger request of this wallet is answered by the emulated device
+    use_emulated_ledger_device();
+    let bn = world.base_nodes.get(&base_node).unwrap();
+    let peer_seeds = bn.seed_nodes.clone();
+    world
+        .wallet_connected_to_base_node
+        .insert(wallet.clone(), base_node.clone());
+
+    let mut cli = get_default_cli();
+    cli.seed_words_file_name = Some(PathBuf::new().join("seed_words.txt"));
+    cli.ledger_account = Some(account);
+    spawn_wallet(world, wallet, Some(base_node), peer_seeds, None, Some(cli)).await;
+}
+
 #[when(expr = "I have wallet {word} connected to seed node {word}")]
 async fn have_wallet_connect_to_seed_node(world: &mut TariWorld, wallet: String, seed_node: String) {
     world